@@ -1,8 +1,8 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use dsq_shared::value::Value;
+use indexmap::IndexMap;
 use polars::datatypes::PlSmallStr;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 fn benchmark_json_conversion(c: &mut Criterion) {
     let mut group = c.benchmark_group("json_conversion");
@@ -10,7 +10,7 @@ fn benchmark_json_conversion(c: &mut Criterion) {
     // Small array (100 objects)
     let small_array: Vec<Value> = (0..100)
         .map(|i| {
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("id".to_string(), Value::int(i)),
                 ("name".to_string(), Value::string(format!("Item {}", i))),
                 ("value".to_string(), Value::float(i as f64 * 1.5)),
@@ -21,7 +21,7 @@ fn benchmark_json_conversion(c: &mut Criterion) {
     // Medium array (10,000 objects)
     let medium_array: Vec<Value> = (0..10_000)
         .map(|i| {
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("id".to_string(), Value::int(i)),
                 ("name".to_string(), Value::string(format!("Item {}", i))),
                 ("value".to_string(), Value::float(i as f64 * 1.5)),
@@ -57,7 +57,7 @@ fn benchmark_dataframe_conversion(c: &mut Criterion) {
     for size in sizes.iter() {
         let array: Vec<Value> = (0..*size)
             .map(|i| {
-                Value::object(HashMap::from([
+                Value::object(IndexMap::from([
                     ("id".to_string(), Value::int(i)),
                     ("name".to_string(), Value::string(format!("Item {}", i))),
                     ("value".to_string(), Value::float(i as f64 * 1.5)),
@@ -120,13 +120,13 @@ fn benchmark_field_access(c: &mut Criterion) {
     let mut group = c.benchmark_group("field_access");
 
     // Create test object
-    let obj = Value::object(HashMap::from([
+    let obj = Value::object(IndexMap::from([
         ("name".to_string(), Value::string("Test")),
         ("age".to_string(), Value::int(30)),
         ("active".to_string(), Value::bool(true)),
         (
             "nested".to_string(),
-            Value::object(HashMap::from([(
+            Value::object(IndexMap::from([(
                 "inner".to_string(),
                 Value::string("value"),
             )])),
@@ -144,7 +144,7 @@ fn benchmark_field_access(c: &mut Criterion) {
     // Create array of objects for field access
     let array: Vec<Value> = (0..1000)
         .map(|i| {
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("id".to_string(), Value::int(i)),
                 ("name".to_string(), Value::string(format!("Item {}", i))),
             ]))
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 use clap::{Parser, Subcommand, ValueEnum};
 
 use dsq_core::DataFormat;
+use dsq_formats::Compression;
 
 /// dsq - A data processing tool for structured data formats with jq-like syntax
 ///
@@ -65,6 +66,11 @@ pub struct Cli {
     #[arg(long, value_enum)]
     pub output_format: Option<DataFormat>,
 
+    /// Compress the output file (gzip/zstd/bzip2/xz); detected from the
+    /// output file extension if not specified
+    #[arg(long, value_name = "CODEC")]
+    pub compress: Option<Compression>,
+
     /// Use compact output (no pretty-printing)
     #[arg(short, long)]
     pub compact_output: bool,
@@ -145,6 +151,30 @@ pub struct Cli {
     #[arg(long = "csv-null", value_name = "VALUE", action = clap::ArgAction::Append)]
     pub csv_null_values: Vec<String>,
 
+    /// Excel worksheet to read, by name or zero-based index
+    #[arg(long, value_name = "NAME|INDEX")]
+    pub sheet: Option<String>,
+
+    /// Excel cell range to read (e.g. A1:D100, or B2 to start at a cell)
+    #[arg(long = "cell-range", value_name = "RANGE")]
+    pub cell_range: Option<String>,
+
+    /// Excel worksheets have a header row
+    #[arg(long = "excel-headers")]
+    pub excel_headers: Option<bool>,
+
+    /// Compress Arrow output with lz4 or zstd (default: uncompressed)
+    #[arg(long = "ipc-compression", value_name = "CODEC")]
+    pub ipc_compression: Option<String>,
+
+    /// Avro schema (JSON) to resolve Avro input against, for schema evolution
+    #[arg(long = "avro-reader-schema", value_name = "FILE")]
+    pub avro_reader_schema: Option<PathBuf>,
+
+    /// Avro schema (JSON) to write Avro output with
+    #[arg(long = "avro-schema", value_name = "FILE")]
+    pub avro_schema: Option<PathBuf>,
+
     /// Skip first N rows when reading
     #[arg(long, value_name = "N")]
     pub skip_rows: Option<usize>,
@@ -173,6 +203,11 @@ pub struct Cli {
     #[arg(long, value_name = "SIZE")]
     pub batch_size: Option<usize>,
 
+    /// Read JSON Lines, CSV, TSV or ADT input in batches of --batch-size
+    /// rows and write the output as each batch is filtered
+    #[arg(long)]
+    pub streaming: bool,
+
     /// Memory limit (e.g., 1GB, 500MB)
     #[arg(long, value_name = "LIMIT")]
     pub memory_limit: Option<String>,
@@ -442,6 +477,7 @@ pub struct CliConfig {
     pub output: Option<PathBuf>,
     pub input_format: Option<DataFormat>,
     pub output_format: Option<DataFormat>,
+    pub compress: Option<Compression>,
 
     // Output options
     pub compact_output: bool,
@@ -468,6 +504,12 @@ pub struct CliConfig {
     pub csv_headers: Option<bool>,
     pub csv_quote: Option<String>,
     pub csv_null_values: Vec<String>,
+    pub sheet: Option<String>,
+    pub cell_range: Option<String>,
+    pub excel_headers: Option<bool>,
+    pub ipc_compression: Option<String>,
+    pub avro_reader_schema: Option<PathBuf>,
+    pub avro_schema: Option<PathBuf>,
 
     // Processing options
     pub skip_rows: Option<usize>,
@@ -479,6 +521,7 @@ pub struct CliConfig {
 
     // Performance options
     pub batch_size: Option<usize>,
+    pub streaming: bool,
     pub memory_limit: Option<String>,
     pub threads: Option<usize>,
     pub parallel: bool,
@@ -521,6 +564,7 @@ impl From<&Cli> for CliConfig {
             output: cli.output.clone(),
             input_format: cli.input_format,
             output_format: cli.output_format,
+            compress: cli.compress,
             compact_output: cli.compact_output,
             raw_output: cli.raw_output,
             sort_keys: cli.sort_keys,
@@ -543,6 +587,12 @@ impl From<&Cli> for CliConfig {
             csv_headers: cli.csv_headers,
             csv_quote: cli.csv_quote.clone(),
             csv_null_values: cli.csv_null_values.clone(),
+            sheet: cli.sheet.clone(),
+            cell_range: cli.cell_range.clone(),
+            excel_headers: cli.excel_headers,
+            ipc_compression: cli.ipc_compression.clone(),
+            avro_reader_schema: cli.avro_reader_schema.clone(),
+            avro_schema: cli.avro_schema.clone(),
             skip_rows: cli.skip_rows,
             limit: cli.limit,
             infer_schema_length: cli.infer_schema_length,
@@ -550,6 +600,7 @@ impl From<&Cli> for CliConfig {
             lazy: cli.lazy,
             dataframe_optimizations: cli.dataframe_optimizations,
             batch_size: cli.batch_size,
+            streaming: cli.streaming,
             memory_limit: cli.memory_limit.clone(),
             threads: cli.threads,
             parallel: cli.parallel,
@@ -763,6 +814,12 @@ mod tests {
         let cli = parse_args_from(args).unwrap();
         assert_eq!(cli.input_format, Some(DataFormat::Csv));
         assert_eq!(cli.output_format, Some(DataFormat::Parquet));
+        assert_eq!(cli.compress, None);
+
+        let args = vec!["dsq", "--compress", "zst", ".", "in.csv", "-o", "out.csv"];
+        let cli = parse_args_from(args).unwrap();
+        assert_eq!(cli.compress, Some(Compression::Zstd));
+        assert_eq!(CliConfig::from(&cli).compress, Some(Compression::Zstd));
     }
 
     #[test]
@@ -784,6 +841,62 @@ mod tests {
         assert_eq!(cli.csv_quote, Some("'".to_string()));
     }
 
+    #[test]
+    fn test_excel_options() {
+        let args = vec![
+            "dsq",
+            "--sheet",
+            "Orders",
+            "--cell-range",
+            "B2:F100",
+            "--excel-headers",
+            "false",
+            ".",
+            "book.xlsx",
+        ];
+        let cli = parse_args_from(args).unwrap();
+        let config = CliConfig::from(&cli);
+        assert_eq!(config.sheet, Some("Orders".to_string()));
+        assert_eq!(config.cell_range, Some("B2:F100".to_string()));
+        assert_eq!(config.excel_headers, Some(false));
+    }
+
+    #[test]
+    fn test_ipc_compression_option() {
+        let args = vec![
+            "dsq",
+            "--output-format",
+            "arrow",
+            "--ipc-compression",
+            "zstd",
+            ".",
+            "data.csv",
+        ];
+        let cli = parse_args_from(args).unwrap();
+        let config = CliConfig::from(&cli);
+        assert_eq!(config.output_format, Some(DataFormat::Arrow));
+        assert_eq!(config.ipc_compression, Some("zstd".to_string()));
+    }
+
+    #[test]
+    fn test_avro_schema_options() {
+        let args = vec![
+            "dsq",
+            "--avro-reader-schema",
+            "v2.avsc",
+            "--avro-schema",
+            "out.avsc",
+            "-o",
+            "out.avro",
+            ".",
+            "data.avro",
+        ];
+        let cli = parse_args_from(args).unwrap();
+        let config = CliConfig::from(&cli);
+        assert_eq!(config.avro_reader_schema, Some(PathBuf::from("v2.avsc")));
+        assert_eq!(config.avro_schema, Some(PathBuf::from("out.avsc")));
+    }
+
     #[test]
     fn test_variables() {
         let args = vec![
@@ -827,6 +940,7 @@ mod tests {
             "dsq",
             "--batch-size",
             "5000",
+            "--streaming",
             "--memory-limit",
             "2GB",
             "--threads",
@@ -835,6 +949,7 @@ mod tests {
         ];
         let cli = parse_args_from(args).unwrap();
         assert_eq!(cli.batch_size, Some(5000));
+        assert!(cli.streaming);
         assert_eq!(cli.memory_limit, Some("2GB".to_string()));
         assert_eq!(cli.threads, Some(4));
     }
@@ -14,10 +14,11 @@ use serde::{Deserialize, Serialize};
 use crate::cli::CliConfig;
 use dsq_core::{
     error::{Error, Result},
-    filter::{ErrorMode, ExecutorConfig},
+    filter::{ErrorMode, ExecutionMode, ExecutorConfig},
     io::{ReadOptions, WriteOptions},
     DataFormat,
 };
+use dsq_formats::Compression;
 
 /// Main configuration structure for dsq runtime
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +60,16 @@ pub struct IoConfig {
     pub max_memory_file_size: usize,
     /// Maximum number of rows to output
     pub limit: Option<usize>,
+    /// Number of input rows to skip
+    #[serde(default)]
+    pub skip_rows: usize,
+    /// Columns to read from tabular input; all when empty
+    #[serde(default)]
+    pub select_columns: Vec<String>,
+    /// Compression of output files; detected from the output file
+    /// extension when not set
+    #[serde(default)]
+    pub compress: Option<Compression>,
 }
 
 /// Filter execution configuration
@@ -92,6 +103,12 @@ pub struct FormatConfigs {
     pub json: JsonConfig,
     /// Parquet configuration
     pub parquet: ParquetConfig,
+    /// Excel configuration
+    pub excel: ExcelConfig,
+    /// Arrow IPC configuration
+    pub arrow: ArrowConfig,
+    /// Avro configuration
+    pub avro: AvroConfig,
 }
 
 /// CSV format configuration
@@ -142,6 +159,38 @@ pub struct ParquetConfig {
     pub data_page_size: usize,
 }
 
+/// Excel format configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExcelConfig {
+    /// Worksheet to read, by name or zero-based index (the first if None)
+    pub sheet: Option<String>,
+    /// Whether worksheets have a header row
+    pub has_header: bool,
+    /// Cell range to read, such as `A1:D100` (the used cells if None)
+    pub range: Option<String>,
+}
+
+/// Arrow IPC format configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArrowConfig {
+    /// Compression of written record batches: lz4, zstd or uncompressed
+    pub compression: String,
+}
+
+/// Avro format configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AvroConfig {
+    /// Schema file to resolve Avro input against (the schema of the file if
+    /// None)
+    pub reader_schema: Option<PathBuf>,
+    /// Schema file to write Avro output with (derived from the columns if
+    /// None)
+    pub schema: Option<PathBuf>,
+}
+
 /// Display and output configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DisplayConfig {
@@ -202,6 +251,8 @@ pub struct DateTimeFormatConfig {
 pub struct PerformanceConfig {
     /// Default batch size for processing
     pub batch_size: usize,
+    /// Whether to read record-oriented input in batches of `batch_size`
+    pub streaming: bool,
     /// Memory limit in bytes
     pub memory_limit: Option<usize>,
     /// Number of threads to use (0 = auto)
@@ -219,6 +270,9 @@ pub struct ModuleConfig {
     pub library_paths: Vec<PathBuf>,
     /// Auto-load modules
     pub auto_load: Vec<String>,
+    /// Module files whose definitions are included into every filter
+    #[serde(default)]
+    pub includes: Vec<PathBuf>,
     /// Module cache directory
     pub cache_dir: Option<PathBuf>,
 }
@@ -250,6 +304,9 @@ impl Default for IoConfig {
             overwrite_by_default: false,
             max_memory_file_size: 100 * 1024 * 1024, // 100MB
             limit: None,
+            skip_rows: 0,
+            select_columns: Vec::new(),
+            compress: None,
         }
     }
 }
@@ -305,6 +362,24 @@ impl Default for ParquetConfig {
     }
 }
 
+impl Default for ExcelConfig {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            has_header: true,
+            range: None,
+        }
+    }
+}
+
+impl Default for ArrowConfig {
+    fn default() -> Self {
+        Self {
+            compression: "uncompressed".to_string(),
+        }
+    }
+}
+
 impl Default for ColorConfig {
     fn default() -> Self {
         Self {
@@ -340,6 +415,7 @@ impl Default for PerformanceConfig {
     fn default() -> Self {
         Self {
             batch_size: 10000,
+            streaming: false,
             memory_limit: None,
             threads: 0, // Auto-detect
             parallel: true,
@@ -556,6 +632,9 @@ impl Config {
         if other.io.max_memory_file_size != IoConfig::default().max_memory_file_size {
             self.io.max_memory_file_size = other.io.max_memory_file_size;
         }
+        if other.io.compress.is_some() {
+            self.io.compress = other.io.compress;
+        }
 
         // Merge filter config
         if !other.filter.lazy_evaluation {
@@ -584,6 +663,9 @@ impl Config {
         self.merge_csv_config(other.formats.csv);
         self.merge_json_config(other.formats.json);
         self.merge_parquet_config(other.formats.parquet);
+        self.merge_excel_config(other.formats.excel);
+        self.merge_arrow_config(other.formats.arrow);
+        self.merge_avro_config(other.formats.avro);
 
         // Merge display config
         self.merge_display_config(other.display);
@@ -592,6 +674,9 @@ impl Config {
         if other.performance.batch_size != PerformanceConfig::default().batch_size {
             self.performance.batch_size = other.performance.batch_size;
         }
+        if other.performance.streaming {
+            self.performance.streaming = true;
+        }
         if other.performance.memory_limit.is_some() {
             self.performance.memory_limit = other.performance.memory_limit;
         }
@@ -614,6 +699,9 @@ impl Config {
         if !other.modules.auto_load.is_empty() {
             self.modules.auto_load.extend(other.modules.auto_load);
         }
+        if !other.modules.includes.is_empty() {
+            self.modules.includes.extend(other.modules.includes);
+        }
         if other.modules.cache_dir.is_some() {
             self.modules.cache_dir = other.modules.cache_dir;
         }
@@ -701,6 +789,36 @@ impl Config {
         }
     }
 
+    /// Merge Excel format config
+    fn merge_excel_config(&mut self, other: ExcelConfig) {
+        if other.sheet.is_some() {
+            self.formats.excel.sheet = other.sheet;
+        }
+        if !other.has_header {
+            self.formats.excel.has_header = other.has_header;
+        }
+        if other.range.is_some() {
+            self.formats.excel.range = other.range;
+        }
+    }
+
+    /// Merge Arrow format config
+    fn merge_arrow_config(&mut self, other: ArrowConfig) {
+        if other.compression != ArrowConfig::default().compression {
+            self.formats.arrow.compression = other.compression;
+        }
+    }
+
+    /// Merge Avro format config
+    fn merge_avro_config(&mut self, other: AvroConfig) {
+        if other.reader_schema.is_some() {
+            self.formats.avro.reader_schema = other.reader_schema;
+        }
+        if other.schema.is_some() {
+            self.formats.avro.schema = other.schema;
+        }
+    }
+
     /// Merge display config
     fn merge_display_config(&mut self, other: DisplayConfig) {
         // Merge color config
@@ -770,7 +888,21 @@ impl Config {
         if let Some(format) = &cli_config.output_format {
             self.io.default_output_format = Some(*format);
         }
+        if cli_config.compress.is_some() {
+            self.io.compress = cli_config.compress;
+        }
         self.io.limit = cli_config.limit;
+        if let Some(skip_rows) = cli_config.skip_rows {
+            self.io.skip_rows = skip_rows;
+        }
+        // `--select a,b` and `--select a --select b` are the same
+        self.io.select_columns = cli_config
+            .select_columns
+            .iter()
+            .flat_map(|cols| cols.split(','))
+            .map(|col| col.trim().to_string())
+            .filter(|col| !col.is_empty())
+            .collect();
 
         // Filter settings
         self.filter.lazy_evaluation = cli_config.lazy;
@@ -792,14 +924,47 @@ impl Config {
         if let Some(has_header) = cli_config.csv_headers {
             self.formats.csv.has_header = has_header;
         }
+        if let Some(quote) = &cli_config.csv_quote {
+            self.formats.csv.quote_char = quote.clone();
+        }
+        if !cli_config.csv_null_values.is_empty() {
+            self.formats.csv.null_values = cli_config.csv_null_values.clone();
+        }
         if let Some(infer_len) = cli_config.infer_schema_length {
             self.formats.csv.infer_schema_length = Some(infer_len);
         }
 
+        // Excel settings
+        if let Some(sheet) = &cli_config.sheet {
+            self.formats.excel.sheet = Some(sheet.clone());
+        }
+        if let Some(has_header) = cli_config.excel_headers {
+            self.formats.excel.has_header = has_header;
+        }
+        if let Some(range) = &cli_config.cell_range {
+            self.formats.excel.range = Some(range.clone());
+        }
+
+        // Arrow settings
+        if let Some(compression) = &cli_config.ipc_compression {
+            self.formats.arrow.compression = compression.clone();
+        }
+
+        // Avro settings
+        if let Some(path) = &cli_config.avro_reader_schema {
+            self.formats.avro.reader_schema = Some(path.clone());
+        }
+        if let Some(path) = &cli_config.avro_schema {
+            self.formats.avro.schema = Some(path.clone());
+        }
+
         // Performance settings
         if let Some(batch_size) = cli_config.batch_size {
             self.performance.batch_size = batch_size;
         }
+        if cli_config.streaming {
+            self.performance.streaming = true;
+        }
         if let Some(limit) = &cli_config.memory_limit {
             self.performance.memory_limit = Some(parse_memory_limit(limit)?);
         }
@@ -808,9 +973,19 @@ impl Config {
         if !cli_config.library_path.is_empty() {
             self.modules.library_paths = cli_config.library_path.clone();
         }
+        self.modules
+            .auto_load
+            .extend(cli_config.imports.iter().cloned());
+        self.modules
+            .includes
+            .extend(cli_config.includes.iter().cloned());
 
         // Debug settings
         self.debug.verbosity = cli_config.verbose;
+        if cli_config.verbose > 0 {
+            // Verbose output reports the execution stats
+            self.filter.collect_stats = true;
+        }
         self.debug.show_plans = cli_config.explain;
 
         // Variables
@@ -825,17 +1000,105 @@ impl Config {
             infer_schema: true,
             infer_schema_length: self.formats.csv.infer_schema_length,
             n_rows: None,
-            skip_rows: 0,
+            skip_rows: self.io.skip_rows,
             chunk_size: None,
             use_mmap: false,
         }
     }
 
+    /// Options for reading CSV and TSV input, from the CSV settings and the
+    /// `--select` and `--skip-rows` options; lazy unless lazy evaluation is
+    /// turned off
+    pub fn to_csv_read_options(
+        &self,
+    ) -> (dsq_formats::ReadOptions, dsq_formats::FormatReadOptions) {
+        let csv = &self.formats.csv;
+        let options = dsq_formats::ReadOptions {
+            lazy: self.filter.lazy_evaluation,
+            infer_schema_length: csv.infer_schema_length,
+            skip_rows: self.io.skip_rows,
+            columns: (!self.io.select_columns.is_empty()).then(|| self.io.select_columns.clone()),
+            ..Default::default()
+        };
+        let format_options = dsq_formats::FormatReadOptions::Csv {
+            separator: match csv.separator.as_str() {
+                "\\t" | "tab" => b'\t',
+                sep => sep.bytes().next().unwrap_or(b','),
+            },
+            has_header: csv.has_header,
+            quote_char: csv.quote_char.bytes().next(),
+            comment_char: csv.comment_char.as_ref().and_then(|c| c.bytes().next()),
+            null_values: (!csv.null_values.is_empty()).then(|| csv.null_values.clone()),
+            encoding: dsq_formats::reader::CsvEncoding::Utf8,
+        };
+        (options, format_options)
+    }
+
+    /// Options for reading Excel workbooks, from the Excel settings and the
+    /// `--select` and `--skip-rows` options
+    pub fn to_excel_read_options(
+        &self,
+    ) -> (dsq_formats::ReadOptions, dsq_formats::FormatReadOptions) {
+        let excel = &self.formats.excel;
+        let options = dsq_formats::ReadOptions {
+            lazy: self.filter.lazy_evaluation,
+            skip_rows: self.io.skip_rows,
+            columns: (!self.io.select_columns.is_empty()).then(|| self.io.select_columns.clone()),
+            ..Default::default()
+        };
+        let format_options = dsq_formats::FormatReadOptions::Excel {
+            sheet: excel.sheet.as_deref().and_then(|sheet| sheet.parse().ok()),
+            has_header: excel.has_header,
+            range: excel.range.clone(),
+        };
+        (options, format_options)
+    }
+
+    /// Options for reading Avro files, from the Avro reader schema and the
+    /// `--select` and `--skip-rows` options
+    pub fn to_avro_read_options(
+        &self,
+    ) -> Result<(dsq_formats::ReadOptions, dsq_formats::FormatReadOptions)> {
+        let options = dsq_formats::ReadOptions {
+            lazy: self.filter.lazy_evaluation,
+            skip_rows: self.io.skip_rows,
+            columns: (!self.io.select_columns.is_empty()).then(|| self.io.select_columns.clone()),
+            ..Default::default()
+        };
+        let format_options = dsq_formats::FormatReadOptions::Avro {
+            columns: None,
+            reader_schema: self
+                .formats
+                .avro
+                .reader_schema
+                .as_deref()
+                .map(read_avro_schema)
+                .transpose()?,
+        };
+        Ok((options, format_options))
+    }
+
+    /// Options for writing Avro files, with the Avro schema if one is set
+    pub fn to_avro_write_options(&self) -> Result<WriteOptions> {
+        Ok(WriteOptions {
+            schema: self
+                .formats
+                .avro
+                .schema
+                .as_deref()
+                .map(read_avro_schema)
+                .transpose()?,
+            ..self.to_write_options()
+        })
+    }
+
     /// Convert to WriteOptions for dsq-core
     pub fn to_write_options(&self) -> WriteOptions {
         WriteOptions {
             include_header: true,
             compression: None,
+            compress: self.io.compress,
+            schema: None,
         }
     }
 
@@ -856,13 +1119,22 @@ impl Config {
 
         ExecutorConfig {
             timeout_ms: self.filter.max_execution_time.map(|s| s * 1000), // convert seconds to ms
+            memory_limit: self.performance.memory_limit,
             error_mode,
             collect_stats: self.filter.collect_stats,
             max_recursion_depth: self.filter.max_recursion_depth,
             debug_mode: self.debug.debug_mode,
             batch_size: self.performance.batch_size,
+            execution_mode: if self.performance.streaming {
+                ExecutionMode::Streaming
+            } else {
+                ExecutionMode::Standard
+            },
             variables,
             filter_cache_size: 100,
+            library_paths: self.modules.library_paths.clone(),
+            imports: self.modules.auto_load.clone(),
+            includes: self.modules.includes.clone(),
         }
     }
 
@@ -905,6 +1177,8 @@ impl Config {
         let mut options = WriteOptions {
             include_header: true,
             compression: None,
+            compress: self.io.compress,
+            schema: None,
         };
 
         match format {
@@ -920,6 +1194,10 @@ impl Config {
                 options.compression = Some(self.formats.parquet.compression.clone());
                 options.include_header = false; // Parquet doesn't have headers in the same way
             }
+            DataFormat::Arrow => {
+                options.compression = Some(self.formats.arrow.compression.clone());
+                options.include_header = false;
+            }
             _ => {
                 // Use defaults for other formats
             }
@@ -1020,6 +1298,17 @@ fn parse_memory_limit(limit: &str) -> Result<usize> {
     }
 }
 
+/// Read an Avro schema file
+fn read_avro_schema(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| {
+        Error::config(format!(
+            "Failed to read Avro schema {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
 /// Create a default config file template
 #[allow(dead_code)]
 pub fn create_default_config_file(path: &Path) -> Result<()> {
@@ -1056,6 +1345,28 @@ pub fn validate_config(config: &Config) -> Result<()> {
         ));
     }
 
+    if config.formats.excel.sheet.as_deref() == Some("") {
+        return Err(Error::config("Excel sheet name cannot be empty"));
+    }
+
+    if let Some(range) = &config.formats.excel.range {
+        dsq_formats::excel::parse_cell_range(range)
+            .map_err(|e| Error::config(format!("Invalid Excel cell range: {e}")))?;
+    }
+
+    config
+        .formats
+        .arrow
+        .compression
+        .parse::<dsq_formats::ArrowCompression>()
+        .map_err(Error::config)?;
+
+    let avro = &config.formats.avro;
+    for path in avro.reader_schema.iter().chain(&avro.schema) {
+        dsq_formats::avro::parse_schema(&read_avro_schema(path)?)
+            .map_err(|e| Error::config(format!("{}: {e}", path.display())))?;
+    }
+
     // Validate paths
     for path in &config.modules.library_paths {
         if !path.exists() {
@@ -1116,6 +1427,9 @@ mod tests {
         assert!(formats.csv.has_header);
         assert!(formats.json.pretty_print);
         assert_eq!(formats.parquet.compression, "snappy");
+        assert!(formats.excel.has_header);
+        assert_eq!(formats.excel.sheet, None);
+        assert_eq!(formats.arrow.compression, "uncompressed");
 
         // Test CsvConfig default
         let csv = CsvConfig::default();
@@ -1456,6 +1770,8 @@ performance:
             batch_size: Some(5000),
             memory_limit: Some("2GB".to_string()),
             library_path: vec![std::path::PathBuf::from("/lib")],
+            imports: vec!["util".to_string()],
+            includes: vec![std::path::PathBuf::from("common.dsq")],
             verbose: 2,
             explain: true,
             ..Default::default()
@@ -1483,6 +1799,11 @@ performance:
             config.modules.library_paths,
             vec![std::path::PathBuf::from("/lib")]
         );
+        assert_eq!(config.modules.auto_load, vec!["util".to_string()]);
+        assert_eq!(
+            config.modules.includes,
+            vec![std::path::PathBuf::from("common.dsq")]
+        );
         assert_eq!(config.debug.verbosity, 2);
         assert!(config.debug.show_plans);
         assert_eq!(config.variables["test"], serde_json::json!("value"));
@@ -1705,6 +2026,53 @@ verbosity = 3
 
         assert!(opts.include_header);
         assert!(opts.compression.is_none());
+        assert!(opts.compress.is_none());
+
+        let mut config = Config::default();
+        config.io.compress = Some(Compression::Zstd);
+        assert_eq!(config.to_write_options().compress, Some(Compression::Zstd));
+    }
+
+    #[test]
+    fn test_avro_read_and_write_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema_path = temp_dir.path().join("order.avsc");
+        let schema =
+            r#"{"type": "record", "name": "Order", "fields": [{"name": "id", "type": "long"}]}"#;
+        fs::write(&schema_path, schema).unwrap();
+
+        let mut config = Config::default();
+        let (_, format_options) = config.to_avro_read_options().unwrap();
+        assert!(matches!(
+            format_options,
+            dsq_formats::FormatReadOptions::Avro {
+                reader_schema: None,
+                ..
+            }
+        ));
+        assert_eq!(config.to_avro_write_options().unwrap().schema, None);
+
+        config
+            .apply_cli(&CliConfig {
+                avro_reader_schema: Some(schema_path.clone()),
+                avro_schema: Some(schema_path.clone()),
+                ..Default::default()
+            })
+            .unwrap();
+        match config.to_avro_read_options().unwrap().1 {
+            dsq_formats::FormatReadOptions::Avro { reader_schema, .. } => {
+                assert_eq!(reader_schema.as_deref(), Some(schema))
+            }
+            other => panic!("expected Avro options, got {other:?}"),
+        }
+        let write_options = config.to_avro_write_options().unwrap();
+        assert_eq!(write_options.schema.as_deref(), Some(schema));
+        assert!(validate_config(&config).is_ok());
+
+        fs::write(&schema_path, "{\"type\": \"record\"}").unwrap();
+        assert!(validate_config(&config).is_err());
+        config.formats.avro.schema = Some(temp_dir.path().join("missing.avsc"));
+        assert!(config.to_avro_write_options().is_err());
     }
 
     #[test]
@@ -1724,6 +2092,7 @@ verbosity = 3
         assert!(!exec_config.debug_mode);
         assert_eq!(exec_config.batch_size, 10000);
         assert!(exec_config.variables.contains_key("test"));
+        assert!(exec_config.imports.is_empty());
     }
 
     #[test]
@@ -1814,6 +2183,7 @@ verbosity = 3
             },
             performance: PerformanceConfig {
                 batch_size: 2000,
+                streaming: false,
                 memory_limit: Some(1024 * 1024 * 1024),
                 threads: 8,
                 parallel: false,
@@ -1839,6 +2209,7 @@ verbosity = 3
             modules: ModuleConfig {
                 library_paths: vec![std::path::PathBuf::from("/test")],
                 auto_load: vec!["test".to_string()],
+                includes: vec![std::path::PathBuf::from("/test/common.dsq")],
                 cache_dir: Some(std::path::PathBuf::from("/cache")),
             },
             debug: DebugConfig {
@@ -1983,6 +2354,23 @@ verbosity = 3
         config.formats.csv.quote_char = "\"\"".to_string();
         assert!(validate_config(&config).is_err());
 
+        // Test Excel sheet and cell range
+        config = Config::default();
+        config.formats.excel.sheet = Some("".to_string());
+        assert!(validate_config(&config).is_err());
+        config = Config::default();
+        config.formats.excel.range = Some("B2:D10".to_string());
+        assert!(validate_config(&config).is_ok());
+        config.formats.excel.range = Some("2B".to_string());
+        assert!(validate_config(&config).is_err());
+
+        // Test Arrow compression
+        config = Config::default();
+        config.formats.arrow.compression = "zstd".to_string();
+        assert!(validate_config(&config).is_ok());
+        config.formats.arrow.compression = "snappy".to_string();
+        assert!(validate_config(&config).is_err());
+
         // Test zero batch size
         config = Config::default();
         config.performance.batch_size = 0;
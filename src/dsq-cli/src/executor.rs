@@ -6,15 +6,100 @@
 use std::borrow::Cow;
 use std::fmt::Write;
 use std::io::{self, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use polars::prelude::SerWriter;
 
 use crate::config::Config;
+use crate::stream::StreamWriter;
 use dsq_core::error::{Error, Result};
-use dsq_core::filter::{FilterCompiler, FilterExecutor as CoreFilterExecutor};
-use dsq_core::io::{read_file, write_file};
+use dsq_core::filter::{ExecutionMode, FilterExecutor as CoreFilterExecutor, InputCursor};
+use dsq_core::io::{read_file, read_file_lazy, write_file};
+use dsq_core::DataFormat;
 use dsq_core::Value;
+use dsq_filter::batch_output;
+use dsq_formats::{
+    BatchReader, CompressedWriter, Compression, DataReader, FileReader, FormatWriteOptions,
+    IpcFraming,
+};
+use dsq_shared::ops::collapse;
+use dsq_shared::value::df_row_to_value;
+
+/// The documents of an input file for `input` and `inputs`: each row of
+/// tabular data, or the whole value otherwise
+fn documents(value: Value) -> Box<dyn Iterator<Item = dsq_shared::Result<Value>> + Send> {
+    match value {
+        Value::DataFrame(df) => {
+            Box::new((0..df.height()).map(move |row| df_row_to_value(&df, row)))
+        }
+        Value::LazyFrame(lf) => match lf.collect() {
+            Ok(df) => documents(Value::DataFrame(df)),
+            Err(e) => Box::new(std::iter::once(Err(dsq_shared::error::operation_error(
+                format!("Failed to collect input: {}", e),
+            )))),
+        },
+        other => Box::new(std::iter::once(Ok(other))),
+    }
+}
+
+/// The JSON documents of `reader`, each parsed when it is read
+fn json_documents(
+    reader: impl Read + Send + 'static,
+) -> Box<dyn Iterator<Item = dsq_shared::Result<Value>> + Send> {
+    Box::new(
+        serde_json::Deserializer::from_reader(io::BufReader::new(reader))
+            .into_iter::<serde_json::Value>()
+            .map(|document| {
+                document.map(Value::from_json).map_err(|e| {
+                    dsq_shared::error::operation_error(format!("Invalid JSON input: {}", e))
+                })
+            }),
+    )
+}
+
+/// `value` with the keys of every object sorted, as `--sort-keys` prints it
+pub(crate) fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut sorted: indexmap::IndexMap<String, Value> =
+                obj.into_iter().map(|(k, v)| (k, sort_keys(v))).collect();
+            sorted.sort_keys();
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Run `f` on a thread outside the async runtime
+///
+/// Polars blocks on its own runtime while scanning files, which panics on a
+/// thread that drives another runtime, so filters that may collect a lazily
+/// scanned input run here.
+pub(crate) fn off_runtime<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    if tokio::runtime::Handle::try_current().is_err() {
+        return f();
+    }
+    std::thread::scope(|scope| {
+        let handle = std::thread::Builder::new()
+            // The stack of the main thread, for deeply recursive filters
+            .stack_size(8 * 1024 * 1024)
+            .spawn_scoped(scope, f)
+            .expect("failed to spawn filter thread");
+        match handle.join() {
+            Ok(value) => value,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    })
+}
+
+/// `value` with a lazy frame collected
+pub(crate) fn collect_lazy(value: Value) -> Result<Value> {
+    match value {
+        Value::LazyFrame(lf) => Ok(Value::DataFrame(lf.collect()?)),
+        other => Ok(other),
+    }
+}
 
 /// Main executor for dsq operations
 pub struct Executor {
@@ -40,6 +125,13 @@ impl Executor {
         input_path: Option<&Path>,
         output_path: Option<&Path>,
     ) -> Result<()> {
+        if self.filter_executor.get_config().execution_mode == ExecutionMode::Streaming {
+            let path = input_path.ok_or_else(|| {
+                Error::operation("Streaming mode reads a file; stdin is read as a whole")
+            })?;
+            return self.execute_streaming(filter, path, output_path);
+        }
+
         // Read input data
         let input_value = if let Some(path) = input_path {
             self.read_input(path).await?
@@ -52,6 +144,82 @@ impl Executor {
             .await
     }
 
+    /// Execute a filter on `null`, with the documents of the input files (or
+    /// of stdin, when there are none) read by `input` and `inputs`
+    pub async fn execute_filter_with_inputs(
+        &mut self,
+        filter: &str,
+        input_paths: &[PathBuf],
+        output_path: Option<&Path>,
+    ) -> Result<()> {
+        let inputs = if input_paths.is_empty() {
+            InputCursor::new(json_documents(io::stdin()))
+        } else {
+            let mut sources = Vec::with_capacity(input_paths.len());
+            for path in input_paths {
+                let json_lines = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| matches!(ext, "jsonl" | "ndjson"));
+                // JSON Lines files are read one record at a time
+                if json_lines {
+                    sources.push(json_documents(std::fs::File::open(path)?));
+                } else {
+                    sources.push(documents(self.read_input(path).await?));
+                }
+            }
+            InputCursor::new(sources.into_iter().flatten())
+        };
+        self.filter_executor.set_inputs(inputs);
+        self.execute_filter_on_value(filter, Value::Null, output_path)
+            .await
+    }
+
+    /// Execute a filter on the file at `input_path` a batch of
+    /// `--batch-size` rows at a time, writing the outputs of each batch
+    /// before reading the next one
+    pub fn execute_streaming(
+        &mut self,
+        filter: &str,
+        input_path: &Path,
+        output_path: Option<&Path>,
+    ) -> Result<()> {
+        let shape = batch_output(filter)?;
+        let batches = BatchReader::from_path(input_path, self.config.performance.batch_size)?
+            .with_options(dsq_formats::ReadOptions {
+                infer_schema_length: self.config.formats.csv.infer_schema_length,
+                ..Default::default()
+            });
+
+        let (out, format, compression): (Box<dyn io::Write>, _, _) = match output_path {
+            Some(path) => (
+                Box::new(io::BufWriter::new(std::fs::File::create(path)?)),
+                DataFormat::from_path(path)?,
+                self.config
+                    .io
+                    .compress
+                    .or_else(|| Compression::from_path(path)),
+            ),
+            None => (
+                Box::new(io::BufWriter::new(io::stdout().lock())),
+                self.config
+                    .io
+                    .default_output_format
+                    .unwrap_or(DataFormat::Json),
+                None,
+            ),
+        };
+        let out = CompressedWriter::new(out, compression)?;
+        let mut writer = StreamWriter::new(out, format, shape, &self.config)?;
+        self.filter_executor.execute_batches(
+            filter,
+            batches.map(|batch| Ok(batch?)),
+            |output| Ok(writer.write(output)?),
+        )?;
+        writer.finish()?.finish()?;
+        Ok(())
+    }
+
     /// Execute a filter on a value directly
     pub async fn execute_filter_on_value(
         &mut self,
@@ -63,23 +231,54 @@ impl Executor {
         #[cfg(feature = "profiling")]
         coz::progress!("filter_execution");
 
-        let result = self.filter_executor.execute_str(filter, input_value)?;
-        let mut result_value = result.value;
+        // Limits are applied before lazy outputs are collected, so they are
+        // pushed down into the scan of the input
+        let limit = self.config.io.limit;
+        let filter_executor = &mut self.filter_executor;
+        let (result, mut outputs) = off_runtime(move || -> Result<_> {
+            let mut result = filter_executor.execute_str(filter, input_value)?;
+            let mut outputs = std::mem::take(&mut result.outputs);
 
-        #[cfg(feature = "profiling")]
-        coz::progress!("filter_complete");
+            #[cfg(feature = "profiling")]
+            coz::progress!("filter_complete");
+
+            // Apply limit if specified: to the rows of a single output, or to
+            // the number of outputs of a stream
+            if let Some(limit) = limit {
+                outputs = match outputs.len() {
+                    1 => vec![Self::apply_limit(outputs.remove(0), limit)?],
+                    _ => outputs.into_iter().take(limit).collect(),
+                };
+            }
+            let outputs = outputs
+                .into_iter()
+                .map(collect_lazy)
+                .collect::<Result<Vec<_>>>()?;
+            Ok((result, outputs))
+        })?;
 
-        // Apply limit if specified
-        if let Some(limit) = self.config.io.limit {
-            result_value = self.apply_limit(result_value, limit)?;
+        // Objects keep the key order of the input unless asked otherwise
+        if self.config.display.sort_keys {
+            outputs = outputs.into_iter().map(sort_keys).collect();
         }
 
+        // The exit status is set by the last output
+        let exit_code = match outputs.last() {
+            None | Some(Value::Null) => 1,
+            Some(Value::Bool(false)) => 1,
+            Some(Value::Array(arr)) if arr.is_empty() => 1,
+            Some(Value::String(s)) if s.is_empty() => 1,
+            _ => 0,
+        };
+
         // Write output
         if let Some(path) = output_path {
-            self.write_output(&result_value, path).await?;
+            self.write_output(&collapse(outputs), path).await?;
         } else {
-            // Write to stdout
-            self.write_to_stdout(&result_value)?;
+            // Write each output to stdout
+            for output in &outputs {
+                self.write_to_stdout(output)?;
+            }
         }
 
         #[cfg(feature = "profiling")]
@@ -87,13 +286,6 @@ impl Executor {
 
         // Handle exit status
         if self.config.display.exit_status {
-            let exit_code = match &result_value {
-                Value::Null => 1,
-                Value::Bool(false) => 1,
-                Value::Array(arr) if arr.is_empty() => 1,
-                Value::String(s) if s.is_empty() => 1,
-                _ => 0,
-            };
             std::process::exit(exit_code);
         }
 
@@ -115,19 +307,31 @@ impl Executor {
                     .map(|s| s.operations_executed)
                     .unwrap_or(0)
             );
+            eprintln!(
+                "Peak memory: {} bytes",
+                result
+                    .stats
+                    .as_ref()
+                    .map(|s| s.peak_memory_bytes)
+                    .unwrap_or(0)
+            );
         }
 
         Ok(())
     }
 
     /// Apply limit to a value
-    fn apply_limit(&self, value: Value, limit: usize) -> Result<Value> {
+    fn apply_limit(value: Value, limit: usize) -> Result<Value> {
         match value {
             Value::Array(arr) => {
                 let limited = arr.into_iter().take(limit).collect();
                 Ok(Value::Array(limited))
             }
             Value::DataFrame(df) => Ok(Value::DataFrame(df.head(Some(limit)))),
+            // Pushed down into the scan of lazily read input
+            Value::LazyFrame(lf) => Ok(Value::LazyFrame(Box::new(
+                lf.limit(limit as polars::prelude::IdxSize),
+            ))),
             // For other types, return as-is (limit doesn't apply)
             other => Ok(other),
         }
@@ -135,19 +339,57 @@ impl Executor {
 
     /// Validate that a filter is syntactically correct
     pub fn validate_filter(&self, filter: &str) -> Result<()> {
-        // For now, just try to compile it
-        let compiler = FilterCompiler::new();
-        let _compiled = compiler.compile_str(filter)?;
+        // Compile with the configured modules so imports resolve
+        self.filter_executor.validate_filter(filter)?;
         Ok(())
     }
 
     /// Explain what a filter does
     pub fn explain_filter(&self, filter: &str) -> Result<String> {
-        Ok(dsq_core::filter::explain_filter(filter)?)
+        Ok(self.filter_executor.explain(filter, None)?)
+    }
+
+    /// Explain how a filter runs on the input at `input_path`; local files
+    /// are scanned lazily so the plan shows what is pushed down into the scan
+    pub async fn explain(&self, filter: &str, input_path: Option<&Path>) -> Result<String> {
+        let read_options = self.config.to_read_options();
+        let input = match input_path {
+            Some(path) if path.exists() => Some(read_file_lazy(path, &read_options)?),
+            Some(path) => Some(read_file(path, &read_options).await?),
+            None => None,
+        };
+        Ok(self.filter_executor.explain(filter, input.as_ref())?)
     }
 
     /// Read input from a file path
+    ///
+    /// Local CSV and TSV files are read with the CSV settings; with lazy
+    /// evaluation they are scanned rather than read, so the columns,
+    /// predicates and row limits of the filter are pushed down into the scan.
+    /// Excel workbooks are read with the Excel settings, and Avro files
+    /// resolved against the Avro reader schema when one is set.
     pub async fn read_input(&self, path: &Path) -> Result<Value> {
+        if path.is_file() {
+            if let Ok(format @ (DataFormat::Csv | DataFormat::Tsv)) = DataFormat::from_path(path) {
+                let (options, format_options) = self.config.to_csv_read_options();
+                let mut reader =
+                    FileReader::with_format(path, format).with_format_options(format_options);
+                return Ok(reader.read(&options)?);
+            }
+            if let Ok(DataFormat::Excel) = DataFormat::from_path(path) {
+                let (options, format_options) = self.config.to_excel_read_options();
+                let mut reader = FileReader::with_format(path, DataFormat::Excel)
+                    .with_format_options(format_options);
+                return Ok(reader.read(&options)?);
+            }
+            if let Ok(DataFormat::Avro) = DataFormat::from_path(path) {
+                let (options, format_options) = self.config.to_avro_read_options()?;
+                let mut reader = FileReader::with_format(path, DataFormat::Avro)
+                    .with_format_options(format_options);
+                return Ok(reader.read(&options)?);
+            }
+        }
+
         let read_options = self.config.to_read_options();
 
         // Always use async read_file since we're in an async context
@@ -157,15 +399,51 @@ impl Executor {
         #[cfg(feature = "profiling")]
         coz::progress!("input_read");
 
-        Ok(result)
+        self.select_columns(result)
+    }
+
+    /// Keep the `--select` columns of tabular input
+    fn select_columns(&self, value: Value) -> Result<Value> {
+        let columns = &self.config.io.select_columns;
+        if columns.is_empty() {
+            return Ok(value);
+        }
+        match value {
+            Value::DataFrame(df) => Ok(Value::DataFrame(
+                df.select(columns.iter().map(String::as_str))?,
+            )),
+            Value::LazyFrame(lf) => Ok(Value::LazyFrame(Box::new(
+                lf.select(
+                    columns
+                        .iter()
+                        .map(|c| polars::prelude::col(c.as_str()))
+                        .collect::<Vec<_>>(),
+                ),
+            ))),
+            other => Ok(other),
+        }
     }
 
     /// Read input from stdin
+    ///
+    /// Arrow IPC streams and files are recognized by their first bytes;
+    /// other input is read as JSON or CSV.
     async fn read_from_stdin(&self) -> Result<Value> {
         use std::io::BufRead;
         let stdin = io::stdin();
         let mut reader = io::BufReader::new(stdin);
 
+        if self.config.io.default_input_format == Some(DataFormat::Arrow)
+            || IpcFraming::detect(reader.fill_buf()?).is_some()
+        {
+            let options = dsq_formats::ReadOptions {
+                skip_rows: self.config.io.skip_rows,
+                ..Default::default()
+            };
+            let value = dsq_formats::deserialize_arrow(reader, &options)?;
+            return self.select_columns(value);
+        }
+
         // Read first line to detect format
         let mut first_line = String::new();
         reader.read_line(&mut first_line)?;
@@ -193,14 +471,16 @@ impl Executor {
 
     /// Write output to a file path
     async fn write_output(&self, value: &Value, path: &Path) -> Result<()> {
-        let write_options = self.config.to_write_options();
+        let write_options = match DataFormat::from_path(path) {
+            Ok(DataFormat::Arrow) => self.config.get_format_write_options(DataFormat::Arrow),
+            Ok(DataFormat::Avro) => self.config.to_avro_write_options()?,
+            _ => self.config.to_write_options(),
+        };
         write_file(value, path, &write_options).await
     }
 
     /// Write output to stdout
     pub fn write_to_stdout(&self, value: &Value) -> Result<()> {
-        use dsq_core::DataFormat;
-
         // Handle raw output
         if self.config.display.raw_output {
             match value {
@@ -377,6 +657,30 @@ impl Executor {
                     }
                 }
             }
+            DataFormat::Arrow => {
+                // Arrow is written as an IPC stream, which a reader on the
+                // other end of a pipe can consume without seeking
+                use std::io::{BufWriter, Write};
+                let compression = self
+                    .config
+                    .formats
+                    .arrow
+                    .compression
+                    .parse()
+                    .map_err(Error::config)?;
+                let format_options = FormatWriteOptions::Arrow {
+                    compression,
+                    framing: IpcFraming::Stream,
+                };
+                let mut writer = BufWriter::with_capacity(65536, io::stdout().lock());
+                dsq_formats::serialize_arrow(
+                    &mut writer,
+                    value,
+                    &dsq_formats::WriteOptions::default(),
+                    &format_options,
+                )?;
+                writer.flush()?;
+            }
             _ => {
                 // For other formats, fall back to string representation
                 println!("{}", value);
@@ -408,6 +712,43 @@ mod tests {
         // For now, just check it doesn't error
     }
 
+    #[test]
+    fn test_input_documents() {
+        let text = "{\"n\": 1}\n[2] 3";
+        let docs = json_documents(io::Cursor::new(text))
+            .collect::<dsq_shared::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            docs,
+            vec![
+                Value::from_json(serde_json::json!({"n": 1})),
+                Value::from_json(serde_json::json!([2])),
+                Value::int(3),
+            ]
+        );
+
+        let df = polars::df! { "n" => [1i64, 2] }.unwrap();
+        let rows = documents(Value::DataFrame(df))
+            .collect::<dsq_shared::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].field("n").unwrap(), Value::int(2));
+        assert_eq!(documents(Value::int(7)).count(), 1);
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let value = Value::from_json(serde_json::json!({"b": [{"d": 1, "c": 2}], "a": 3}));
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"b":[{"d":1,"c":2}],"a":3}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&sort_keys(value)).unwrap(),
+            r#"{"a":3,"b":[{"c":2,"d":1}]}"#
+        );
+    }
+
     #[test]
     fn test_validate_filter() {
         let config = Config::default();
@@ -433,6 +774,22 @@ mod tests {
         assert!(!explanation.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_explain_with_input_file() {
+        let config = Config::default();
+        let executor = Executor::new(config);
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("people.csv");
+        std::fs::write(&path, "name,age\nAlice,30\nBob,25\n").unwrap();
+
+        let explanation = executor
+            .explain("map(select(.age > 26) | {name})", Some(&path))
+            .await
+            .unwrap();
+        assert!(explanation.contains("function call: map [vectorized]"));
+        assert!(explanation.contains("1 of 1 stage(s) run in the query"));
+    }
+
     #[tokio::test]
     async fn test_execute_filter_on_value_with_filter_duplicate() {
         let config = Config::default();
@@ -518,6 +875,93 @@ mod tests {
         assert!(content.contains("Bob"));
     }
 
+    #[tokio::test]
+    async fn test_write_output_arrow_stream_with_compression() {
+        use polars::prelude::*;
+
+        let mut config = Config::default();
+        config.formats.arrow.compression = "zstd".to_string();
+        let executor = Executor::new(config);
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("people.arrows");
+
+        let df = df! {
+            "name" => &["Alice", "Bob"],
+            "age" => &[30i64, 25],
+        }
+        .unwrap();
+        executor
+            .write_output(&Value::DataFrame(df.clone()), &path)
+            .await
+            .unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(IpcFraming::detect(&bytes), Some(IpcFraming::Stream));
+        let options = dsq_formats::ReadOptions::default();
+        match dsq_formats::deserialize_arrow(io::Cursor::new(bytes), &options).unwrap() {
+            Value::DataFrame(read) => assert!(read.equals(&df)),
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_avro_output_schema_and_reader_schema() {
+        use polars::prelude::*;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let writer_schema = temp_dir.path().join("v1.avsc");
+        std::fs::write(
+            &writer_schema,
+            r#"{"type": "record", "name": "Person", "fields": [
+                {"name": "name", "type": "string"},
+                {"name": "age", "type": "int"}
+            ]}"#,
+        )
+        .unwrap();
+        let reader_schema = temp_dir.path().join("v2.avsc");
+        std::fs::write(
+            &reader_schema,
+            r#"{"type": "record", "name": "Person", "fields": [
+                {"name": "name", "type": "string"},
+                {"name": "age", "type": "long"},
+                {"name": "team", "type": "string", "default": "core"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.filter.lazy_evaluation = false;
+        config.formats.avro.schema = Some(writer_schema);
+        config.formats.avro.reader_schema = Some(reader_schema);
+        let executor = Executor::new(config);
+        let path = temp_dir.path().join("people.avro");
+        let df = df! {
+            "name" => &["Alice", "Bob"],
+            "age" => &[30i64, 25],
+        }
+        .unwrap();
+        executor
+            .write_output(&Value::DataFrame(df), &path)
+            .await
+            .unwrap();
+
+        match executor.read_input(&path).await.unwrap() {
+            Value::DataFrame(read) => {
+                assert_eq!(read.get_column_names(), ["name", "age", "team"]);
+                assert_eq!(read.column("age").unwrap().dtype(), &DataType::Int64);
+                let teams: Vec<_> = read
+                    .column("team")
+                    .unwrap()
+                    .str()
+                    .unwrap()
+                    .into_iter()
+                    .collect();
+                assert_eq!(teams, [Some("core"), Some("core")]);
+            }
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn test_execute_filter_with_file() {
         let config = Config::default();
@@ -532,6 +976,159 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_execute_filter_on_scanned_csv() {
+        let mut config = Config::default();
+        config.io.select_columns = vec!["name".to_string(), "age".to_string()];
+        config.io.limit = Some(1);
+        let mut executor = Executor::new(config);
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("people.csv");
+        let output = dir.path().join("adults.json");
+        std::fs::write(
+            &input,
+            "name,age,city\nTim,9,NA\nAda,36,London\nGrace,45,NA\n",
+        )
+        .unwrap();
+
+        let Value::LazyFrame(lf) = executor.read_input(&input).await.unwrap() else {
+            panic!("expected CSV input to be scanned lazily");
+        };
+        let plan = lf.describe_optimized_plan().unwrap();
+        assert!(plan.contains("PROJECT 2/3 COLUMNS"), "{plan}");
+
+        // The scan is collected off the runtime driving this test
+        executor
+            .execute_filter("map(select(.age > 18))", Some(&input), Some(&output))
+            .await
+            .unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(written, serde_json::json!([{"name": "Ada", "age": 36}]));
+    }
+
+    #[tokio::test]
+    async fn test_read_input_excel_range() {
+        use polars::prelude::*;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("people.xlsx");
+        let df = df! {
+            "name" => &["Ada", "Tim", "Grace"],
+            "age" => &[36, 9, 45],
+            "city" => &["London", "Leeds", "New York"],
+        }
+        .unwrap();
+        Executor::new(Config::default())
+            .write_output(&Value::DataFrame(df), &path)
+            .await
+            .unwrap();
+
+        let mut config = Config::default();
+        config.filter.lazy_evaluation = false;
+        config.formats.excel.sheet = Some("0".to_string());
+        config.formats.excel.range = Some("A1:B3".to_string());
+        let executor = Executor::new(config);
+        let Value::DataFrame(df) = executor.read_input(&path).await.unwrap() else {
+            panic!("expected a DataFrame");
+        };
+        assert_eq!(df.get_column_names(), ["name", "age"]);
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.column("age").unwrap().i64().unwrap().get(1), Some(9));
+    }
+
+    #[tokio::test]
+    async fn test_execute_filter_streaming() {
+        let mut config = Config::default();
+        config.performance.streaming = true;
+        config.performance.batch_size = 2;
+        let mut executor = Executor::new(config);
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("people.csv");
+        let output = dir.path().join("adults.jsonl");
+        std::fs::write(
+            &input,
+            "name,age\nAda,36\nTim,9\nGrace,45\nAlan,41\nEve,12\n",
+        )
+        .unwrap();
+
+        executor
+            .execute_filter("select(.age >= 18)", Some(&input), Some(&output))
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "{\"name\":\"Ada\",\"age\":36}\n\
+             {\"name\":\"Grace\",\"age\":45}\n\
+             {\"name\":\"Alan\",\"age\":41}\n"
+        );
+
+        // JSON Lines batches are frames too, so `select` tests each row
+        let jsonl = dir.path().join("people.jsonl");
+        std::fs::write(
+            &jsonl,
+            "{\"name\":\"Ada\",\"age\":36}\n{\"name\":\"Tim\",\"age\":9}\n\
+             {\"name\":\"Grace\",\"age\":45}\n{\"name\":\"Eve\",\"age\":12}\n",
+        )
+        .unwrap();
+        executor
+            .execute_filter("select(.age >= 18)", Some(&jsonl), Some(&output))
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "{\"name\":\"Ada\",\"age\":36}\n\
+             {\"name\":\"Grace\",\"age\":45}\n"
+        );
+
+        let error = executor
+            .execute_filter("group_by(.age)", Some(&input), Some(&output))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("cannot run in streaming mode"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_filter_compressed() {
+        use dsq_formats::compression::{compress, decompress};
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("events.jsonl.gz");
+        std::fs::write(
+            &input,
+            compress(
+                b"{\"id\":1,\"ok\":true}\n{\"id\":2,\"ok\":false}\n{\"id\":3,\"ok\":true}\n",
+                Some(Compression::Gzip),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        for streaming in [false, true] {
+            let mut config = Config::default();
+            config.performance.streaming = streaming;
+            config.io.compress = Some(Compression::Zstd);
+            let mut executor = Executor::new(config);
+            let output = dir.path().join(format!("ok-{streaming}.csv"));
+            executor
+                .execute_filter(
+                    "map(select(.ok)) | map({id: .id})",
+                    Some(&input),
+                    Some(&output),
+                )
+                .await
+                .unwrap();
+
+            let bytes = std::fs::read(&output).unwrap();
+            assert_eq!(Compression::detect(&bytes), Some(Compression::Zstd));
+            assert_eq!(
+                String::from_utf8(decompress(bytes).unwrap()).unwrap(),
+                "id\n1\n3\n",
+                "streaming: {streaming}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_execute_filter_on_value_with_stats() {
         let mut config = Config::default();
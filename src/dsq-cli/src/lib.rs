@@ -6,6 +6,7 @@
 mod cli;
 mod config;
 mod executor;
+mod stream;
 
 pub use config::Config;
 pub use executor::Executor;
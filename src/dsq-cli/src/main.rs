@@ -6,6 +6,7 @@ mod executor;
 mod output;
 #[cfg(feature = "cli")]
 mod repl;
+mod stream;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs;
@@ -37,6 +38,11 @@ use crate::repl::Repl;
 #[cfg(not(target_arch = "wasm32"))]
 use dsq_core::io::{read_file, write_file};
 
+/// Counts allocations so `--memory-limit` can be enforced and the peak
+/// memory of an execution reported
+#[global_allocator]
+static ALLOC: dsq_shared::limits::TrackingAllocator = dsq_shared::limits::TrackingAllocator;
+
 #[cfg(all(not(target_arch = "wasm32"), feature = "cli"))]
 #[tokio::main]
 async fn main() {
@@ -208,21 +214,47 @@ async fn run() -> Result<()> {
         (filter, vec![])
     };
 
+    // Show how the filter would run instead of running it
+    if cli_config.explain {
+        let executor = Executor::new(config);
+        let input_path = input_paths.first().filter(|_| !cli_config.null_input);
+        print!(
+            "{}",
+            executor
+                .explain(&filter, input_path.map(PathBuf::as_path))
+                .await?
+        );
+        return Ok(());
+    }
+
     let output_path = cli_config.output.as_deref();
 
-    if input_paths.is_empty() {
+    // Output to stdout can be piped through a compressor instead
+    if cli_config.compress.is_some() && output_path.is_none() {
+        return Err(anyhow::anyhow!("--compress needs an --output file"));
+    }
+
+    // Streaming reads one file in batches; `input`, `inputs` and several
+    // input files need the whole input
+    if config.performance.streaming && (cli_config.null_input || input_paths.len() != 1) {
+        return Err(anyhow::anyhow!(
+            "--streaming needs exactly one JSON Lines, CSV, TSV or ADT input file"
+        ));
+    }
+
+    if cli_config.null_input {
+        // The input is only read through `input` and `inputs`
         let mut executor = Executor::new(config);
-        if cli_config.null_input {
-            executor
-                .execute_filter_on_value(&filter, Value::Null, output_path)
-                .await
-                .map_err(|e| e.into())
-        } else {
-            executor
-                .execute_filter(&filter, None, output_path)
-                .await
-                .map_err(|e| e.into())
-        }
+        executor
+            .execute_filter_with_inputs(&filter, &input_paths, output_path)
+            .await
+            .map_err(|e| e.into())
+    } else if input_paths.is_empty() {
+        let mut executor = Executor::new(config);
+        executor
+            .execute_filter(&filter, None, output_path)
+            .await
+            .map_err(|e| e.into())
     } else if input_paths.len() == 1 {
         // Single input file
         let mut executor = Executor::new(config);
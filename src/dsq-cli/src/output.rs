@@ -270,8 +270,8 @@ impl OutputWriter {
 mod tests {
     use super::*;
     use dsq_core::Value;
+    use indexmap::IndexMap;
     use num_bigint::BigInt;
-    use std::collections::HashMap;
     use std::fs;
     use tempfile::NamedTempFile;
 
@@ -332,7 +332,7 @@ mod tests {
     fn test_write_to_stdout_object() {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
-        let obj = Value::Object(HashMap::from([
+        let obj = Value::Object(IndexMap::from([
             ("name".to_string(), Value::String("Alice".to_string())),
             ("age".to_string(), Value::Int(30)),
         ]));
@@ -437,7 +437,7 @@ mod tests {
     async fn test_write_with_options_json_pretty() {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
-        let obj = Value::Object(HashMap::from([
+        let obj = Value::Object(IndexMap::from([
             ("name".to_string(), Value::String("Alice".to_string())),
             ("age".to_string(), Value::Int(30)),
         ]));
@@ -459,7 +459,7 @@ mod tests {
     async fn test_write_with_options_json_compact() {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
-        let obj = Value::Object(HashMap::from([
+        let obj = Value::Object(IndexMap::from([
             ("name".to_string(), Value::String("Alice".to_string())),
             ("age".to_string(), Value::Int(30)),
         ]));
@@ -481,11 +481,11 @@ mod tests {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
         let arr = Value::Array(vec![
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "name".to_string(),
                 Value::String("Alice".to_string()),
             )])),
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "name".to_string(),
                 Value::String("Bob".to_string()),
             )])),
@@ -510,7 +510,7 @@ mod tests {
     async fn test_write_with_options_jsonlines_single() {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
-        let obj = Value::Object(HashMap::from([(
+        let obj = Value::Object(IndexMap::from([(
             "name".to_string(),
             Value::String("Alice".to_string()),
         )]));
@@ -584,9 +584,9 @@ mod tests {
 
         // Create a value that might cause JSON serialization issues
         // Most values should work, but let's test with a complex nested structure
-        let nested = Value::Object(HashMap::from([(
+        let nested = Value::Object(IndexMap::from([(
             "data".to_string(),
-            Value::Array(vec![Value::Object(HashMap::from([(
+            Value::Array(vec![Value::Object(IndexMap::from([(
                 "nested".to_string(),
                 Value::Array(vec![Value::Int(1), Value::Int(2)]),
             )]))]),
@@ -674,7 +674,7 @@ mod tests {
     async fn test_write_with_options_stdout_json_pretty() {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
-        let obj = Value::Object(HashMap::from([
+        let obj = Value::Object(IndexMap::from([
             ("name".to_string(), Value::String("Alice".to_string())),
             ("age".to_string(), Value::Int(30)),
         ]));
@@ -690,7 +690,7 @@ mod tests {
     async fn test_write_with_options_stdout_json_compact() {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
-        let obj = Value::Object(HashMap::from([
+        let obj = Value::Object(IndexMap::from([
             ("name".to_string(), Value::String("Alice".to_string())),
             ("age".to_string(), Value::Int(30)),
         ]));
@@ -707,11 +707,11 @@ mod tests {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
         let arr = Value::Array(vec![
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "name".to_string(),
                 Value::String("Alice".to_string()),
             )])),
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "name".to_string(),
                 Value::String("Bob".to_string()),
             )])),
@@ -728,7 +728,7 @@ mod tests {
     async fn test_write_with_options_stdout_jsonlines_single() {
         let config = create_test_config();
         let writer = OutputWriter::new(config);
-        let obj = Value::Object(HashMap::from([(
+        let obj = Value::Object(IndexMap::from([(
             "name".to_string(),
             Value::String("Alice".to_string()),
         )]));
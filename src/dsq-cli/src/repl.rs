@@ -10,7 +10,7 @@ use dsq_core::error::{Error, Result};
 use dsq_core::Value;
 
 use crate::config::Config;
-use crate::executor::Executor;
+use crate::executor::{collect_lazy, off_runtime, Executor};
 use crate::output::OutputWriter;
 
 /// Interactive REPL for dsq
@@ -147,7 +147,9 @@ impl Repl {
             )));
         }
 
-        self.current_data = Some(self.executor.read_input(path).await?);
+        // Every filter runs on the loaded data, so it is read once
+        let data = self.executor.read_input(path).await?;
+        self.current_data = Some(off_runtime(|| collect_lazy(data))?);
         println!("Loaded data from: {}", path.display());
         Ok(())
     }
@@ -183,7 +185,9 @@ impl Repl {
             .execute_str(filter, data.clone())?;
 
         println!("Result:");
-        self.output_writer.write_to_stdout(&result.value)?;
+        for output in &result.outputs {
+            self.output_writer.write_to_stdout(output)?;
+        }
 
         // Add to history
         self.history.push(filter.to_string());
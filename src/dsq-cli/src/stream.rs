@@ -0,0 +1,384 @@
+//! Writing the outputs of a filter run in streaming mode
+//!
+//! The outputs of each batch are written before the next batch is read, so
+//! the output is never held in memory as a whole. A filter whose outputs
+//! are collections of rows writes one JSON array, CSV table or JSON Lines
+//! file holding the rows of every batch, the same as a filter run on the
+//! whole input; a filter that iterates the rows with `.[]` writes separate
+//! documents.
+
+use std::io::Write;
+
+use dsq_core::error::{Error, Result};
+use dsq_core::DataFormat;
+use dsq_core::Value;
+use dsq_filter::BatchOutput;
+use dsq_shared::value::df_row_to_value;
+use polars::prelude::{CsvWriter, DataFrame, SerWriter};
+
+use crate::config::Config;
+use crate::executor::sort_keys;
+
+/// Number of rows converted to a DataFrame at a time for CSV output
+const CSV_CHUNK_ROWS: usize = 1024;
+
+/// Incremental writer of the outputs of a streamed filter
+pub(crate) struct StreamWriter<W: Write> {
+    out: W,
+    format: DataFormat,
+    shape: BatchOutput,
+    compact: bool,
+    raw: bool,
+    sort_keys: bool,
+    limit: Option<usize>,
+    /// Rows or documents written so far
+    written: usize,
+    /// Column names of the CSV header, once it is written
+    columns: Option<Vec<String>>,
+    /// Rows waiting to be written as one CSV chunk
+    pending: Vec<Value>,
+}
+
+impl<W: Write> StreamWriter<W> {
+    /// Write outputs of `shape` to `out` in `format`, with the display
+    /// options and `--limit` of `config`
+    pub(crate) fn new(
+        out: W,
+        format: DataFormat,
+        shape: BatchOutput,
+        config: &Config,
+    ) -> Result<Self> {
+        let raw = config.display.raw_output;
+        if !raw
+            && !matches!(
+                format,
+                DataFormat::Json
+                    | DataFormat::JsonCompact
+                    | DataFormat::JsonLines
+                    | DataFormat::Csv
+                    | DataFormat::Tsv
+            )
+        {
+            return Err(Error::operation(format!(
+                "{} output cannot be written in streaming mode; use JSON, JSON Lines, CSV or TSV",
+                format.display_name()
+            )));
+        }
+        Ok(Self {
+            out,
+            format,
+            shape,
+            compact: config.display.compact || format == DataFormat::JsonCompact,
+            raw,
+            sort_keys: config.display.sort_keys,
+            limit: config.io.limit,
+            written: 0,
+            columns: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Write one output of the filter; breaks once `--limit` rows or
+    /// documents are written
+    pub(crate) fn write(&mut self, output: Value) -> Result<std::ops::ControlFlow<()>> {
+        use std::ops::ControlFlow;
+
+        match (self.shape, output) {
+            (BatchOutput::Rows, Value::LazyFrame(lf)) => {
+                return self.write(Value::DataFrame(lf.collect()?));
+            }
+            // Tables go to CSV as they are
+            (BatchOutput::Rows, Value::DataFrame(df)) if self.is_csv() => {
+                let df = match self.remaining() {
+                    Some(remaining) => df.head(Some(remaining)),
+                    None => df,
+                };
+                self.flush_pending()?;
+                self.written += df.height();
+                self.write_frame(df)?;
+            }
+            (BatchOutput::Rows, Value::DataFrame(df)) => {
+                for row in 0..df.height() {
+                    if self.write_item(df_row_to_value(&df, row)?)?.is_break() {
+                        return Ok(ControlFlow::Break(()));
+                    }
+                }
+            }
+            (BatchOutput::Rows, Value::Array(rows)) => {
+                for row in rows {
+                    if self.write_item(row)?.is_break() {
+                        return Ok(ControlFlow::Break(()));
+                    }
+                }
+            }
+            (_, output) => return self.write_item(output),
+        }
+        Ok(if self.remaining() == Some(0) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Finish the output once every batch is written, returning the
+    /// underlying writer
+    pub(crate) fn finish(mut self) -> Result<W> {
+        self.flush_pending()?;
+        if self.shape == BatchOutput::Rows && self.is_json_array() {
+            match (self.written, self.compact) {
+                (0, _) => writeln!(self.out, "[]")?,
+                (_, true) => writeln!(self.out, "]")?,
+                (_, false) => writeln!(self.out, "\n]")?,
+            }
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    /// Rows or documents left to write under `--limit`
+    fn remaining(&self) -> Option<usize> {
+        self.limit.map(|limit| limit.saturating_sub(self.written))
+    }
+
+    fn is_csv(&self) -> bool {
+        !self.raw && matches!(self.format, DataFormat::Csv | DataFormat::Tsv)
+    }
+
+    /// Whether rows are written as the elements of one JSON array
+    fn is_json_array(&self) -> bool {
+        !self.raw && matches!(self.format, DataFormat::Json | DataFormat::JsonCompact)
+    }
+
+    /// Write one row or document
+    fn write_item(&mut self, item: Value) -> Result<std::ops::ControlFlow<()>> {
+        use std::ops::ControlFlow;
+
+        if self.remaining() == Some(0) {
+            return Ok(ControlFlow::Break(()));
+        }
+        let item = if self.sort_keys {
+            sort_keys(item)
+        } else {
+            item
+        };
+
+        if self.is_csv() {
+            self.pending.push(item);
+            if self.pending.len() >= CSV_CHUNK_ROWS {
+                self.flush_pending()?;
+            }
+        } else if self.raw {
+            match item {
+                Value::String(s) => writeln!(self.out, "{s}")?,
+                other => writeln!(self.out, "{}", to_json_string(&other, true)?)?,
+            }
+        } else if self.shape == BatchOutput::Rows && self.is_json_array() {
+            let json = to_json_string(&item, self.compact)?;
+            match (self.written, self.compact) {
+                (0, true) => write!(self.out, "[{json}")?,
+                (_, true) => write!(self.out, ",{json}")?,
+                (0, false) => write!(self.out, "[\n{}", indent(&json))?,
+                (_, false) => write!(self.out, ",\n{}", indent(&json))?,
+            }
+        } else {
+            let compact = self.compact || self.format == DataFormat::JsonLines;
+            writeln!(self.out, "{}", to_json_string(&item, compact)?)?;
+        }
+
+        self.written += 1;
+        Ok(if self.remaining() == Some(0) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Write the rows waiting for CSV output
+    fn flush_pending(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let rows = Value::Array(std::mem::take(&mut self.pending));
+        self.write_frame(rows.to_dataframe()?)
+    }
+
+    /// Write a table as CSV, with the header before the first one; later
+    /// tables are written in the columns of the header
+    fn write_frame(&mut self, df: DataFrame) -> Result<()> {
+        let mut df = match &self.columns {
+            Some(columns) => df.select(columns.iter().map(String::as_str)).map_err(|e| {
+                Error::operation(format!(
+                    "Streamed rows do not all have the columns {}: {e}",
+                    columns.join(", ")
+                ))
+            })?,
+            None => df,
+        };
+        let include_header = self.columns.is_none();
+        if include_header {
+            self.columns = Some(
+                df.get_column_names()
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            );
+        }
+        let separator = if self.format == DataFormat::Tsv {
+            b'\t'
+        } else {
+            b','
+        };
+        CsvWriter::new(&mut self.out)
+            .include_header(include_header)
+            .with_separator(separator)
+            .finish(&mut df)
+            .map_err(|e| Error::operation(format!("CSV write error: {e}")))
+    }
+}
+
+/// `value` as JSON text
+fn to_json_string(value: &Value, compact: bool) -> Result<String> {
+    let json = value.to_json()?;
+    if compact {
+        serde_json::to_string(&json)
+    } else {
+        serde_json::to_string_pretty(&json)
+    }
+    .map_err(|e| Error::operation(format!("JSON serialization error: {e}")))
+}
+
+/// Pretty JSON indented one level, as an element of a pretty array
+fn indent(json: &str) -> String {
+    json.lines()
+        .map(|line| format!("  {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    fn config(format: DataFormat) -> Config {
+        let mut config = Config::default();
+        config.io.default_output_format = Some(format);
+        config
+    }
+
+    fn people(names: &[&str]) -> Value {
+        let ages: Vec<i64> = (0..names.len() as i64).collect();
+        Value::DataFrame(df! { "name" => names, "age" => ages }.unwrap())
+    }
+
+    fn write_all(
+        format: DataFormat,
+        shape: BatchOutput,
+        config: &Config,
+        outputs: Vec<Value>,
+    ) -> String {
+        let mut out = Vec::new();
+        let mut writer = StreamWriter::new(&mut out, format, shape, config).unwrap();
+        for output in outputs {
+            if writer.write(output).unwrap().is_break() {
+                break;
+            }
+        }
+        writer.finish().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_rows_form_one_json_array() {
+        let outputs = vec![people(&["Ada"]), people(&["Grace", "Alan"])];
+        let streamed = write_all(
+            DataFormat::Json,
+            BatchOutput::Rows,
+            &Config::default(),
+            outputs,
+        );
+        let expected = serde_json::json!([
+            {"name": "Ada", "age": 0},
+            {"name": "Grace", "age": 0},
+            {"name": "Alan", "age": 1}
+        ]);
+        assert_eq!(
+            streamed,
+            serde_json::to_string_pretty(&expected).unwrap() + "\n"
+        );
+
+        let mut compact = Config::default();
+        compact.display.compact = true;
+        let streamed = write_all(
+            DataFormat::Json,
+            BatchOutput::Rows,
+            &compact,
+            vec![
+                Value::Array(vec![]),
+                Value::Array(vec![Value::int(1), Value::int(2)]),
+            ],
+        );
+        assert_eq!(streamed, "[1,2]\n");
+
+        let empty = write_all(
+            DataFormat::Json,
+            BatchOutput::Rows,
+            &Config::default(),
+            vec![],
+        );
+        assert_eq!(empty, "[]\n");
+    }
+
+    #[test]
+    fn test_csv_header_is_written_once() {
+        let outputs = vec![
+            people(&["Ada"]),
+            Value::Array(vec![Value::from_json(
+                serde_json::json!({"age": 7, "name": "Alan"}),
+            )]),
+        ];
+        let streamed = write_all(
+            DataFormat::Csv,
+            BatchOutput::Rows,
+            &config(DataFormat::Csv),
+            outputs,
+        );
+        assert_eq!(streamed, "name,age\nAda,0\nAlan,7\n");
+    }
+
+    #[test]
+    fn test_documents_and_limit() {
+        let mut config = config(DataFormat::JsonLines);
+        config.io.limit = Some(3);
+        let outputs = (1..10).map(Value::int).collect();
+        let streamed = write_all(
+            DataFormat::JsonLines,
+            BatchOutput::Documents,
+            &config,
+            outputs,
+        );
+        assert_eq!(streamed, "1\n2\n3\n");
+
+        config.io.limit = Some(2);
+        let streamed = write_all(
+            DataFormat::Csv,
+            BatchOutput::Rows,
+            &config,
+            vec![people(&["Ada", "Grace", "Alan"]), people(&["Edsger"])],
+        );
+        assert_eq!(streamed, "name,age\nAda,0\nGrace,1\n");
+    }
+
+    #[test]
+    fn test_unstreamable_output_format() {
+        let error = StreamWriter::new(
+            Vec::new(),
+            DataFormat::Parquet,
+            BatchOutput::Rows,
+            &Config::default(),
+        )
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("streaming mode"));
+    }
+}
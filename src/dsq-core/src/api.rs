@@ -259,6 +259,7 @@ impl Dsq {
     pub fn apply_filter(mut self, filter: &str) -> Result<Self> {
         let config = ExecutorConfig {
             collect_stats: self.options.collect_stats,
+            memory_limit: self.options.memory_limit,
             error_mode: match self.options.error_mode {
                 ErrorMode::Strict => FilterErrorMode::Strict,
                 ErrorMode::Collect => FilterErrorMode::Collect,
@@ -270,7 +271,7 @@ impl Dsq {
         let mut executor = FilterExecutor::new();
         executor.set_config(config);
         let result = executor.execute_str(filter, self.data)?;
-        self.data = result.value;
+        self.data = result.into_value();
         self.operations.push(format!("filter: {}", filter));
         Ok(self)
     }
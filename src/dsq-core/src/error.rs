@@ -6,6 +6,7 @@ use std::io;
 use dsq_formats;
 #[cfg(feature = "io")]
 use dsq_io;
+pub use dsq_shared::error::LimitExceeded;
 
 /// Result type alias for dsq operations
 pub type Result<T> = std::result::Result<T, Error>;
@@ -37,6 +38,9 @@ pub enum Error {
     /// Configuration errors
     Config(String),
 
+    /// An execution ran past its timeout or memory budget
+    LimitExceeded(LimitExceeded),
+
     /// Multiple errors collected during processing
     Multiple(Vec<Error>),
 }
@@ -142,6 +146,7 @@ impl fmt::Display for Error {
             Error::Type(e) => write!(f, "Type error: {e}"),
             Error::Operation(msg) => write!(f, "Operation error: {msg}"),
             Error::Config(msg) => write!(f, "Configuration error: {msg}"),
+            Error::LimitExceeded(e) => write!(f, "Limit exceeded: {e}"),
             Error::Multiple(errors) => {
                 write!(f, "Multiple errors occurred:")?;
                 for (i, e) in errors.iter().enumerate() {
@@ -218,6 +223,7 @@ impl StdError for Error {
             Error::Io(e) => Some(e),
             Error::Polars(e) => Some(e),
             Error::Json(e) => Some(e),
+            Error::LimitExceeded(e) => Some(e),
             _ => None,
         }
     }
@@ -264,9 +270,18 @@ impl From<TypeError> for Error {
     }
 }
 
+impl From<LimitExceeded> for Error {
+    fn from(e: LimitExceeded) -> Self {
+        Error::LimitExceeded(e)
+    }
+}
+
 impl From<anyhow::Error> for Error {
     fn from(e: anyhow::Error) -> Self {
-        Error::Operation(Cow::Owned(e.to_string()))
+        match e.downcast::<LimitExceeded>() {
+            Ok(limit) => Error::LimitExceeded(limit),
+            Err(e) => Error::Operation(Cow::Owned(e.to_string())),
+        }
     }
 }
 
@@ -391,6 +406,14 @@ mod tests {
         let format_err = FormatError::unknown("test");
         let err: Error = format_err.into();
         assert!(matches!(err, Error::Format(_)));
+
+        let limit = LimitExceeded::Timeout { timeout_ms: 5 };
+        let err: Error = anyhow::Error::from(limit.clone()).into();
+        assert!(matches!(err, Error::LimitExceeded(ref e) if *e == limit));
+        assert_eq!(
+            err.to_string(),
+            "Limit exceeded: execution timed out after 5 ms"
+        );
     }
 
     #[test]
@@ -5,7 +5,7 @@
 
 pub use dsq_filter::{
     compile_filter, ErrorMode, ExecutionMode, ExecutionResult, ExecutionStats, ExecutorConfig,
-    FilterCompiler, FilterExecutor, OptimizationLevel,
+    FilterCompiler, FilterExecutor, InputCursor, OptimizationLevel,
 };
 
 /// Convenience function to execute a filter string on a value
@@ -33,18 +33,21 @@ pub fn execute_filter_with_config(
     }
 }
 
-/// Explain a filter (placeholder for future implementation)
+/// Explain how a filter runs: the AST it parses to and the operations it
+/// compiles to, with those lowered to Polars expressions marked
 pub fn explain_filter(filter: &str) -> dsq_shared::Result<String> {
-    // Basic implementation - just return a simple description
-    match filter.trim() {
-        "." => Ok("Identity filter - returns the input unchanged".to_string()),
-        _ => Ok(format!("Filter: {filter}")),
-    }
+    dsq_filter::explain::explain(&FilterCompiler::new(), filter, None)
+}
+
+/// Explain how a filter runs on `input`, including the optimized Polars
+/// logical plan when the input is a DataFrame or LazyFrame
+pub fn explain_filter_with_input(filter: &str, input: &crate::Value) -> dsq_shared::Result<String> {
+    dsq_filter::explain::explain(&FilterCompiler::new(), filter, Some(input))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     use crate::Value;
 
@@ -71,7 +74,7 @@ mod tests {
     #[test]
     fn test_execute_filter_object_access() {
         // Test object field access
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("name".to_string(), Value::String("Alice".to_string()));
         obj.insert("age".to_string(), Value::Int(30));
         let input = Value::Object(obj);
@@ -120,7 +123,7 @@ mod tests {
         let result = execute_filter_with_config(".", &input, &config);
         assert!(result.is_ok());
         let execution_result = result.unwrap();
-        assert_eq!(execution_result.value, Value::Int(42));
+        assert_eq!(execution_result.into_value(), Value::Int(42));
     }
 
     #[test]
@@ -134,7 +137,7 @@ mod tests {
         let result = execute_filter_with_config(".", &input, &config);
         assert!(result.is_ok());
         let execution_result = result.unwrap();
-        assert_eq!(execution_result.value, Value::Int(42));
+        assert_eq!(execution_result.into_value(), Value::Int(42));
     }
 
     #[test]
@@ -163,6 +166,17 @@ mod tests {
         assert!(explanation.contains("Identity"));
     }
 
+    #[test]
+    fn test_explain_filter_with_input() {
+        use polars::prelude::IntoLazy;
+
+        let df = polars::df! { "x" => [1i64, 2, 3] }.unwrap();
+        let input = Value::LazyFrame(Box::new(df.lazy()));
+        let explanation = explain_filter_with_input("select(.x > 1)", &input).unwrap();
+        assert!(explanation.contains("[vectorized]"));
+        assert!(explanation.contains("FILTER"));
+    }
+
     #[test]
     fn test_explain_filter_with_complex_filter() {
         // Test explain_filter with a more complex filter string
@@ -206,7 +220,7 @@ mod tests {
     #[test]
     fn test_execute_filter_object_transformation() {
         // Test object transformation
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("first".to_string(), Value::String("John".to_string()));
         obj.insert("last".to_string(), Value::String("Doe".to_string()));
         obj.insert("age".to_string(), Value::Int(30));
@@ -12,16 +12,24 @@ use polars::prelude::*;
 
 use crate::error::{Error, Result};
 use crate::Value;
+use dsq_formats::compression::{compress, decompress};
 use dsq_formats::format::detect_format_from_content;
 use dsq_formats::{
     deserialize_adt, deserialize_csv, deserialize_json, deserialize_parquet, serialize_adt,
-    serialize_csv, serialize_json, serialize_parquet, DataFormat, FormatReadOptions,
+    serialize_csv, serialize_json, serialize_parquet, Compression, DataFormat, FormatReadOptions,
     FormatWriteOptions, ReadOptions as DsFormatReadOptions,
 };
 
-#[cfg(feature = "avro")]
+#[cfg(all(feature = "avro", not(target_arch = "wasm32")))]
 use dsq_formats::serialize_avro;
 
+#[cfg(not(target_arch = "wasm32"))]
+use dsq_formats::{
+    deserialize_arrow, deserialize_avro, deserialize_excel, deserialize_orc, ipc::read_arrow_file,
+    orc::read_orc_file, serialize_arrow, serialize_excel, serialize_orc, ArrowCompression,
+    IpcFraming,
+};
+
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::runtime::Runtime;
 
@@ -67,6 +75,12 @@ pub struct WriteOptions {
     pub include_header: bool,
     /// Compression to use (if supported by format)
     pub compression: Option<String>,
+    /// Compression of the whole file; by default the extension of the path
+    /// decides, as in `out.csv.gz`
+    pub compress: Option<Compression>,
+    /// Avro schema of the records, as JSON; by default one is derived from
+    /// the columns
+    pub schema: Option<String>,
 }
 
 impl Default for WriteOptions {
@@ -74,6 +88,8 @@ impl Default for WriteOptions {
         Self {
             include_header: true,
             compression: None,
+            compress: None,
+            schema: None,
         }
     }
 }
@@ -106,12 +122,15 @@ pub async fn read_file<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result
         // Extract extension from URL path
         extract_extension_from_url(&path_str).unwrap_or("")
     } else {
-        path.extension().and_then(|ext| ext.to_str()).unwrap_or("")
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(format_extension)
+            .unwrap_or("")
     };
 
     let format = if extension.is_empty() {
         // No extension, try content detection
-        let bytes = dsq_io::read_file(path).await?;
+        let bytes = decompress(dsq_io::read_file(path).await?)?;
         detect_format_from_content(&bytes).ok_or_else(|| {
             Error::operation("Could not detect file format from content".to_string())
         })?
@@ -123,6 +142,10 @@ pub async fn read_file<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result
             "json" => DataFormat::Json,
             "jsonl" | "ndjson" => DataFormat::JsonLines,
             "parquet" => DataFormat::Parquet,
+            "avro" => DataFormat::Avro,
+            "arrow" | "arrows" | "feather" | "ipc" => DataFormat::Arrow,
+            "xlsx" => DataFormat::Excel,
+            "orc" => DataFormat::Orc,
             _ => {
                 return Err(Error::operation(format!(
                     "Unsupported file format: {extension}"
@@ -131,17 +154,29 @@ pub async fn read_file<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result
         }
     };
 
-    // Read the file bytes (dsq_io handles URLs)
-    let bytes = dsq_io::read_file(path).await?;
-    let cursor = Cursor::new(bytes);
-
-    // Deserialize based on format
     let format_read_options = DsFormatReadOptions {
         max_rows: options.n_rows,
         skip_rows: options.skip_rows,
         infer_schema_length: options.infer_schema_length,
         ..Default::default()
     };
+
+    // Local Arrow IPC files are memory-mapped rather than read into memory
+    if format == DataFormat::Arrow && !is_url(&path_str) {
+        return Ok(read_arrow_file(path, &format_read_options)?);
+    }
+
+    // Local ORC files are read stripe by stripe rather than into memory
+    if format == DataFormat::Orc && !is_url(&path_str) {
+        return Ok(read_orc_file(path, &format_read_options)?);
+    }
+
+    // Read the file bytes (dsq_io handles URLs), decompressing them if
+    // they are compressed
+    let bytes = decompress(dsq_io::read_file(path).await?)?;
+    let cursor = Cursor::new(bytes);
+
+    // Deserialize based on format
     let format_options = FormatReadOptions::default();
     match format {
         DataFormat::Csv => Ok(deserialize_csv(
@@ -149,6 +184,18 @@ pub async fn read_file<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result
             &format_read_options,
             &format_options,
         )?),
+        DataFormat::Tsv => Ok(deserialize_csv(
+            cursor,
+            &format_read_options,
+            &FormatReadOptions::Csv {
+                separator: b'\t',
+                has_header: true,
+                quote_char: Some(b'"'),
+                comment_char: None,
+                null_values: None,
+                encoding: dsq_formats::CsvEncoding::Utf8,
+            },
+        )?),
         DataFormat::Adt => Ok(deserialize_adt(
             cursor,
             &format_read_options,
@@ -159,11 +206,43 @@ pub async fn read_file<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result
             &format_read_options,
             &format_options,
         )?),
+        DataFormat::JsonLines => Ok(deserialize_json(
+            cursor,
+            &format_read_options,
+            &FormatReadOptions::Json {
+                lines: true,
+                ignore_errors: false,
+            },
+        )?),
         DataFormat::Parquet => Ok(deserialize_parquet(
             cursor,
             &format_read_options,
             &format_options,
         )?),
+        DataFormat::Excel => Ok(deserialize_excel(
+            cursor,
+            &format_read_options,
+            &FormatReadOptions::Excel {
+                sheet: None,
+                has_header: true,
+                range: None,
+            },
+        )?),
+        DataFormat::Avro => Ok(deserialize_avro(
+            cursor,
+            &format_read_options,
+            &format_options,
+        )?),
+        DataFormat::Arrow => Ok(deserialize_arrow(
+            cursor,
+            &format_read_options,
+            &format_options,
+        )?),
+        DataFormat::Orc => Ok(deserialize_orc(
+            cursor,
+            &format_read_options,
+            &format_options,
+        )?),
         _ => Err(Error::operation(format!("Unsupported format: {format:?}"))),
     }
 }
@@ -181,11 +260,17 @@ fn extract_extension_from_url(url: &str) -> Option<&str> {
     // Get the last path segment
     let last_segment = path.split('/').next_back()?;
 
-    // Extract extension
-    if let Some(dot_pos) = last_segment.rfind('.') {
-        Some(&last_segment[dot_pos + 1..])
+    format_extension(last_segment)
+}
+
+/// The extension naming the format of a file, skipping the extension of a
+/// compression as in `events.jsonl.gz`
+fn format_extension(file_name: &str) -> Option<&str> {
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if Compression::from_extension(extension).is_some() {
+        stem.rsplit_once('.').map(|(_, extension)| extension)
     } else {
-        None
+        Some(extension)
     }
 }
 
@@ -212,6 +297,11 @@ pub fn read_file_lazy<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<
     let path = path.as_ref();
     let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
 
+    // Compressed files cannot be scanned
+    if dsq_formats::compression::is_compressed(path)? {
+        return read_file_sync(path, options);
+    }
+
     let format = if extension.is_empty() {
         // No extension, try content detection
         let content =
@@ -244,7 +334,14 @@ pub async fn write_file<P: AsRef<Path>>(
     options: &WriteOptions,
 ) -> Result<()> {
     let path = path.as_ref();
-    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let extension = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(format_extension)
+        .unwrap_or("");
+
+    // Compress as asked, or as the extension of the path says
+    let compression = options.compress.or_else(|| Compression::from_path(path));
 
     let format = if extension.is_empty() {
         // Default to CSV when no extension
@@ -258,6 +355,9 @@ pub async fn write_file<P: AsRef<Path>>(
             "jsonl" | "ndjson" => DataFormat::JsonLines,
             "parquet" => DataFormat::Parquet,
             "avro" => DataFormat::Avro,
+            "arrow" | "arrows" | "feather" | "ipc" => DataFormat::Arrow,
+            "xlsx" => DataFormat::Excel,
+            "orc" => DataFormat::Orc,
             _ => {
                 return Err(Error::operation(format!(
                     "Unsupported output format: {extension}"
@@ -280,26 +380,71 @@ pub async fn write_file<P: AsRef<Path>>(
         DataFormat::Adt => {
             serialize_adt(&mut buffer, value, &format_write_options, &format_options)?;
         }
-        DataFormat::Json | DataFormat::JsonLines => {
+        DataFormat::Tsv => {
+            dsq_formats::serialize(
+                &mut buffer,
+                value,
+                format,
+                &format_write_options,
+                &format_options,
+            )?;
+        }
+        DataFormat::Json => {
+            serialize_json(&mut buffer, value, &format_write_options, &format_options)?;
+        }
+        DataFormat::JsonLines => {
+            let format_options = FormatWriteOptions::Json {
+                lines: true,
+                pretty: false,
+            };
             serialize_json(&mut buffer, value, &format_write_options, &format_options)?;
         }
         DataFormat::Parquet => {
             serialize_parquet(&mut buffer, value, &format_write_options, &format_options)?;
         }
-        #[cfg(feature = "avro")]
+        #[cfg(all(feature = "avro", not(target_arch = "wasm32")))]
         DataFormat::Avro => {
+            let format_options = FormatWriteOptions::Avro {
+                compression: dsq_formats::AvroCompression::Null,
+                schema: options.schema.clone(),
+            };
             serialize_avro(&mut buffer, value, &format_write_options, &format_options)?;
         }
-        #[cfg(not(feature = "avro"))]
+        #[cfg(not(all(feature = "avro", not(target_arch = "wasm32"))))]
         DataFormat::Avro => {
             return Err(Error::operation("Avro support not enabled in this build"));
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Excel => {
+            let format_options = FormatWriteOptions::excel();
+            serialize_excel(&mut buffer, value, &format_write_options, &format_options)?;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Arrow => {
+            let compression = match &options.compression {
+                Some(compression) => compression
+                    .parse::<ArrowCompression>()
+                    .map_err(Error::operation)?,
+                None => ArrowCompression::Uncompressed,
+            };
+            let format_options = FormatWriteOptions::Arrow {
+                compression,
+                framing: IpcFraming::from_path(path),
+            };
+            serialize_arrow(&mut buffer, value, &format_write_options, &format_options)?;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Orc => {
+            let format_options = FormatWriteOptions::orc();
+            serialize_orc(&mut buffer, value, &format_write_options, &format_options)?;
+        }
         _ => {
             return Err(Error::operation(format!("Unsupported format: {format:?}")));
         }
     }
 
     // Write the buffer to file
+    let buffer = compress(&buffer, compression)?;
     dsq_io::write_file(path, &buffer).await?;
     Ok(())
 }
@@ -474,39 +619,26 @@ fn read_adt<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<Value> {
 }
 
 fn read_csv_lazy<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<Value> {
-    use polars::prelude::PlPath;
-    use std::sync::Arc;
-
-    // Use LazyCsvReader for truly lazy reading - doesn't read file until executed
-    let pl_path = PlPath::Local(Arc::from(path.as_ref()));
-    let lf = LazyCsvReader::new(pl_path)
-        .with_has_header(true)
-        .with_infer_schema_length(options.infer_schema_length)
-        .with_skip_rows(options.skip_rows)
-        .finish()?;
-
-    // Apply n_rows limit if specified
-    let lf = if let Some(n_rows) = options.n_rows {
-        lf.limit(n_rows as u32)
-    } else {
-        lf
-    };
-
-    Ok(Value::LazyFrame(Box::new(lf)))
+    read_delimited_lazy(path, b',', options)
 }
 
 fn read_tsv_lazy<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<Value> {
-    use polars::prelude::PlPath;
-    use std::sync::Arc;
-
-    // Use LazyCsvReader for truly lazy reading - doesn't read file until executed
-    let pl_path = PlPath::Local(Arc::from(path.as_ref()));
-    let lf = LazyCsvReader::new(pl_path)
-        .with_has_header(true)
-        .with_separator(b'\t')
-        .with_infer_schema_length(options.infer_schema_length)
-        .with_skip_rows(options.skip_rows)
-        .finish()?;
+    read_delimited_lazy(path, b'\t', options)
+}
+
+/// Scan a CSV or TSV file; nothing is read until the frame is collected
+fn read_delimited_lazy<P: AsRef<Path>>(
+    path: P,
+    separator: u8,
+    options: &ReadOptions,
+) -> Result<Value> {
+    let csv_options = dsq_formats::CsvReadOptions {
+        separator,
+        infer_schema_length: options.infer_schema_length,
+        skip_rows: options.skip_rows,
+        ..Default::default()
+    };
+    let lf = dsq_formats::scan_csv_file_with_options(path, csv_options)?;
 
     // Apply n_rows limit if specified
     let lf = if let Some(n_rows) = options.n_rows {
@@ -1305,6 +1437,8 @@ mod tests {
         let write_options = WriteOptions {
             include_header: false,
             compression: None,
+            compress: None,
+            schema: None,
         };
         let write_result = write_file_sync(&df_value, &csv_path, &write_options);
         assert!(
@@ -1319,6 +1453,172 @@ mod tests {
         assert!(!lines[0].contains("name"));
     }
 
+    #[test]
+    fn test_compressed_round_trip() {
+        use tempfile::TempDir;
+        let df = DataFrame::new(vec![
+            Series::new("name".into(), vec!["Alice", "Bob"]).into(),
+            Series::new("age".into(), vec![30i64, 25i64]).into(),
+        ])
+        .unwrap();
+        let df_value = Value::DataFrame(df.clone());
+
+        let temp_dir = TempDir::new().unwrap();
+        for name in [
+            "events.jsonl.gz",
+            "people.tsv.zst",
+            "people.csv.bz2",
+            "people.adt.xz",
+        ] {
+            let path = temp_dir.path().join(name);
+            write_file_sync(&df_value, &path, &WriteOptions::default()).unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            assert_eq!(
+                Compression::detect(&bytes),
+                Compression::from_path(&path),
+                "{name}"
+            );
+
+            match read_file_sync(&path, &ReadOptions::default()).unwrap() {
+                Value::DataFrame(read) => {
+                    assert_eq!(read.get_column_names(), df.get_column_names(), "{name}");
+                    assert_eq!(read.height(), 2, "{name}");
+                }
+                other => panic!("{name}: expected DataFrame, got {other:?}"),
+            }
+        }
+
+        // An explicit compression applies whatever the extension
+        let path = temp_dir.path().join("people.json");
+        let write_options = WriteOptions {
+            compress: Some(Compression::Xz),
+            ..Default::default()
+        };
+        write_file_sync(&df_value, &path, &write_options).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(Compression::detect(&bytes), Some(Compression::Xz));
+        let read = read_file_sync(&path, &ReadOptions::default()).unwrap();
+        assert_eq!(read.len(), Some(2));
+    }
+
+    #[test]
+    fn test_excel_round_trip() {
+        use tempfile::TempDir;
+        let df = DataFrame::new(vec![
+            Series::new("name".into(), vec!["Alice", "Bob"]).into(),
+            Series::new("age".into(), vec![30i64, 25i64]).into(),
+        ])
+        .unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("people.xlsx");
+        write_file_sync(
+            &Value::DataFrame(df.clone()),
+            &path,
+            &WriteOptions::default(),
+        )
+        .unwrap();
+
+        match read_file_sync(&path, &ReadOptions::default()).unwrap() {
+            Value::DataFrame(read) => assert!(read.equals(&df)),
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_arrow_round_trip() {
+        use tempfile::TempDir;
+        let df = DataFrame::new(vec![
+            Series::new("name".into(), vec!["Alice", "Bob"]).into(),
+            Series::new("age".into(), vec![30i64, 25i64]).into(),
+        ])
+        .unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["people.feather", "people.arrows"] {
+            let path = temp_dir.path().join(name);
+            let options = WriteOptions {
+                compression: Some("zstd".to_string()),
+                ..Default::default()
+            };
+            write_file_sync(&Value::DataFrame(df.clone()), &path, &options).unwrap();
+
+            match read_file_sync(&path, &ReadOptions::default()).unwrap() {
+                Value::DataFrame(read) => assert!(read.equals(&df)),
+                other => panic!("expected DataFrame, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_avro_round_trip() {
+        use tempfile::TempDir;
+        let df = DataFrame::new(vec![
+            Series::new("name".into(), vec![Some("Alice"), None, Some("Carol")]).into(),
+            Series::new("age".into(), vec![30i64, 25i64, 41i64]).into(),
+        ])
+        .unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("people.avro");
+        write_file_sync(
+            &Value::DataFrame(df.clone()),
+            &path,
+            &WriteOptions::default(),
+        )
+        .unwrap();
+        match read_file_sync(&path, &ReadOptions::default()).unwrap() {
+            Value::DataFrame(read) => assert!(read.equals_missing(&df)),
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+
+        let options = WriteOptions {
+            schema: Some(
+                r#"{"type": "record", "name": "Person", "fields": [
+                    {"name": "name", "type": ["null", "string"]},
+                    {"name": "age", "type": "int"}
+                ]}"#
+                .to_string(),
+            ),
+            ..Default::default()
+        };
+        write_file_sync(&Value::DataFrame(df), &path, &options).unwrap();
+        match read_file_sync(&path, &ReadOptions::default()).unwrap() {
+            Value::DataFrame(read) => {
+                assert_eq!(read.column("age").unwrap().dtype(), &DataType::Int32)
+            }
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_orc_round_trip() {
+        use tempfile::TempDir;
+        let df = DataFrame::new(vec![
+            Series::new("name".into(), vec![Some("Alice"), None, Some("Carol")]).into(),
+            Series::new("age".into(), vec![30i64, 25i64, 41i64]).into(),
+        ])
+        .unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("people.orc");
+        write_file_sync(
+            &Value::DataFrame(df.clone()),
+            &path,
+            &WriteOptions::default(),
+        )
+        .unwrap();
+
+        let options = ReadOptions {
+            n_rows: Some(2),
+            ..Default::default()
+        };
+        match read_file_sync(&path, &options).unwrap() {
+            Value::DataFrame(read) => assert!(read.equals_missing(&df.head(Some(2)))),
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_inspect_file() {
         use tempfile::TempDir;
@@ -242,8 +242,8 @@ pub use io::{
 // Re-export filter system
 #[cfg(feature = "filter")]
 pub use filter::{
-    execute_filter, execute_filter_with_config, explain_filter, ExecutionResult, ExecutorConfig,
-    FilterCompiler, FilterExecutor,
+    execute_filter, execute_filter_with_config, explain_filter, explain_filter_with_input,
+    ExecutionResult, ExecutorConfig, FilterCompiler, FilterExecutor,
 };
 
 /// Prelude module for convenient imports
@@ -307,7 +307,7 @@ pub const BUILD_INFO: BuildInfo = BuildInfo {
 
 /// Utility functions for working with dsq
 pub mod utils {
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     use crate::{Error, Result, Value};
 
@@ -329,7 +329,7 @@ pub mod utils {
         I: IntoIterator<Item = (K, Value)>,
         K: Into<String>,
     {
-        let map: HashMap<String, Value> = pairs.into_iter().map(|(k, v)| (k.into(), v)).collect();
+        let map: IndexMap<String, Value> = pairs.into_iter().map(|(k, v)| (k.into(), v)).collect();
         Value::Object(map)
     }
 
@@ -510,7 +510,7 @@ pub mod utils {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
     #[allow(unused_imports)]
     use std::path::Path;
 
@@ -619,7 +619,7 @@ mod tests {
         assert_eq!(Value::float(3.14).type_name(), "float");
         assert_eq!(Value::string("hello").type_name(), "string");
         assert_eq!(Value::array(vec![]).type_name(), "array");
-        assert_eq!(Value::object(HashMap::new()).type_name(), "object");
+        assert_eq!(Value::object(IndexMap::new()).type_name(), "object");
     }
 
     #[test]
@@ -654,7 +654,7 @@ mod tests {
 
     #[test]
     fn test_value_field() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("name".to_string(), Value::string("Alice"));
         obj.insert("age".to_string(), Value::int(30));
         let obj_val = Value::object(obj);
@@ -699,7 +699,7 @@ mod tests {
         assert_eq!(arr.to_json().unwrap(), expected);
 
         // Test object
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("name".to_string(), Value::string("Alice"));
         obj.insert("age".to_string(), Value::int(30));
         let obj_val = Value::object(obj);
@@ -180,7 +180,7 @@ impl Operation for IterateOperation {
                 // Convert DataFrame to array of objects
                 let mut rows = Vec::new();
                 for i in 0..df.height() {
-                    let mut row_obj = std::collections::HashMap::new();
+                    let mut row_obj = indexmap::IndexMap::new();
                     for col_name in df.get_column_names() {
                         if let Ok(series) = df.column(col_name) {
                             if let Ok(val) = series.get(i) {
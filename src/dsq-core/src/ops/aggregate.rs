@@ -59,7 +59,7 @@ pub(super) fn df_to_array(df: &DataFrame) -> Result<Vec<Value>> {
     let mut result = Vec::with_capacity(df.height());
 
     for row_idx in 0..df.height() {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         for col_name in &columns {
             let series = df.column(col_name).map_err(Error::from)?;
             let any_val = series.get(row_idx).map_err(Error::from)?;
@@ -1,7 +1,7 @@
 use crate::{Error, Result, TypeError, Value};
+use indexmap::IndexMap;
 use polars::prelude::*;
 use smallvec::SmallVec;
-use std::collections::HashMap;
 
 use super::{compare_values_for_ordering, df_to_array, AggregationFunction};
 
@@ -182,7 +182,7 @@ fn group_by_agg_array(
     let mut result_rows = Vec::new();
 
     for (group_key, group_items) in groups {
-        let mut result_row = HashMap::new();
+        let mut result_row = IndexMap::new();
 
         // Add group key columns
         let key_parts: Vec<&str> = group_key.split('|').collect();
@@ -9,7 +9,7 @@ use crate::ops::{
     ObjectConstructOperation, Operation, OperationType, OrOperation, SelectConditionOperation,
     SliceOperation, SubOperation, VariableOperation,
 };
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 fn create_test_dataframe() -> DataFrame {
     df! {
@@ -265,7 +265,7 @@ fn test_slice_operation_array() {
 #[test]
 fn test_assign_update_operation_object_field() {
     // Create test object
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
     obj.insert("name".to_string(), Value::String("Alice".to_string()));
     obj.insert("age".to_string(), Value::Int(30));
     obj.insert("salary".to_string(), Value::Int(75000));
@@ -294,7 +294,7 @@ fn test_assign_update_operation_object_field() {
 #[test]
 fn test_assign_update_operation_string_field() {
     // Create test object
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
     obj.insert("name".to_string(), Value::String("Alice".to_string()));
     obj.insert(
         "department".to_string(),
@@ -331,7 +331,7 @@ fn test_assign_update_operation_string_field() {
 #[test]
 fn test_assign_update_operation_with_expression() {
     // Create test object
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
     obj.insert("salary".to_string(), Value::Int(75000));
     obj.insert("bonus".to_string(), Value::Int(5000));
     let input = Value::Object(obj);
@@ -380,11 +380,11 @@ fn test_assign_update_operation_non_object() {
 #[test]
 fn test_assign_update_operation_nested_field() {
     // Create nested object
-    let mut address = HashMap::new();
+    let mut address = IndexMap::new();
     address.insert("city".to_string(), Value::String("New York".to_string()));
     address.insert("zip".to_string(), Value::String("10001".to_string()));
 
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
     obj.insert("name".to_string(), Value::String("Alice".to_string()));
     obj.insert("address".to_string(), Value::Object(address));
     let input = Value::Object(obj);
@@ -715,7 +715,7 @@ fn test_iterate_operation() {
     assert_eq!(result, arr);
 
     // Test iterating over object
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
     obj.insert("a".to_string(), Value::Int(1));
     obj.insert("b".to_string(), Value::Int(2));
     let obj_value = Value::Object(obj);
@@ -763,7 +763,7 @@ fn test_variable_operation() {
 
 #[test]
 fn test_field_access_operation() {
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
     obj.insert("name".to_string(), Value::String("Alice".to_string()));
     obj.insert("age".to_string(), Value::Int(30));
     let obj_value = Value::Object(obj);
@@ -778,7 +778,7 @@ fn test_field_access_operation() {
 
     // Test nested field access
     let nested_op = FieldAccessOperation::with_fields(vec!["user".to_string(), "name".to_string()]);
-    let mut outer_obj = HashMap::new();
+    let mut outer_obj = IndexMap::new();
     outer_obj.insert("user".to_string(), obj_value);
     let outer_value = Value::Object(outer_obj);
 
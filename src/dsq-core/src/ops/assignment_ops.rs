@@ -120,7 +120,7 @@ impl AssignUpdateOperation {
 
     /// Helper method to update nested fields in an object
     fn update_nested_field(
-        obj: &mut std::collections::HashMap<String, Value>,
+        obj: &mut indexmap::IndexMap<String, Value>,
         fields: &[&str],
         value: Value,
     ) -> Result<()> {
@@ -370,7 +370,7 @@ pub fn select_columns(value: &Value, columns: &[String]) -> Result<Value> {
                 .iter()
                 .map(|v| match v {
                     Value::Object(obj) => {
-                        let mut selected_obj = std::collections::HashMap::new();
+                        let mut selected_obj = indexmap::IndexMap::new();
                         for column in columns {
                             if let Some(val) = obj.get(column) {
                                 selected_obj.insert(column.clone(), val.clone());
@@ -384,7 +384,7 @@ pub fn select_columns(value: &Value, columns: &[String]) -> Result<Value> {
             Ok(Value::Array(selected_objects?))
         }
         Value::Object(obj) => {
-            let mut selected_obj = std::collections::HashMap::new();
+            let mut selected_obj = indexmap::IndexMap::new();
             for column in columns {
                 if let Some(val) = obj.get(column) {
                     selected_obj.insert(column.clone(), val.clone());
@@ -668,7 +668,7 @@ pub fn count(value: &Value) -> Result<Value> {
 // Helper functions
 
 fn df_row_to_value(df: &DataFrame, row_idx: usize) -> Result<Value> {
-    let mut obj = std::collections::HashMap::new();
+    let mut obj = indexmap::IndexMap::new();
 
     for col_name in df.get_column_names() {
         let series = df
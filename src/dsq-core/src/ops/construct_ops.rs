@@ -75,7 +75,7 @@ impl ObjectConstructOperation {
 
 impl Operation for ObjectConstructOperation {
     fn apply(&self, value: &Value) -> Result<Value> {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
 
         for (key_op, value_op) in &self.field_ops {
             let key_value = key_op.apply(value)?;
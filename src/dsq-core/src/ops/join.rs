@@ -12,7 +12,7 @@
 //! These operations correspond to SQL JOIN operations and allow combining
 //! data from multiple `DataFrames` based on common keys.
 
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 use polars::prelude::*;
 
@@ -390,7 +390,7 @@ fn left_join_arrays(
 
             if !found_match {
                 // Add left row with nulls for right columns
-                let joined = merge_objects(left_obj, &HashMap::new(), suffix, true, &right_keys)?;
+                let joined = merge_objects(left_obj, &IndexMap::new(), suffix, true, &right_keys)?;
                 result.push(Value::Object(joined));
             }
         }
@@ -442,7 +442,7 @@ fn right_join_arrays(
 
             if !found_match {
                 // Add right row with nulls for left columns
-                let joined = merge_objects(&HashMap::new(), right_obj, suffix, true, &left_keys)?;
+                let joined = merge_objects(&IndexMap::new(), right_obj, suffix, true, &left_keys)?;
                 result.push(Value::Object(joined));
             }
         }
@@ -508,7 +508,7 @@ fn outer_join_arrays(
 
             if !found_match {
                 // Add left row with nulls for right columns
-                let joined = merge_objects(left_obj, &HashMap::new(), suffix, true, &right_keys)?;
+                let joined = merge_objects(left_obj, &IndexMap::new(), suffix, true, &right_keys)?;
                 result.push(Value::Object(joined));
             }
         }
@@ -518,7 +518,7 @@ fn outer_join_arrays(
     for (right_idx, right_item) in right_arr.iter().enumerate() {
         if !right_matched[right_idx] {
             if let Value::Object(right_obj) = right_item {
-                let joined = merge_objects(&HashMap::new(), right_obj, suffix, true, &left_keys)?;
+                let joined = merge_objects(&IndexMap::new(), right_obj, suffix, true, &left_keys)?;
                 result.push(Value::Object(joined));
             }
         }
@@ -607,8 +607,8 @@ fn anti_join_arrays(
 
 /// Check if two objects match on the specified join keys
 fn objects_match_on_keys(
-    left_obj: &HashMap<String, Value>,
-    right_obj: &HashMap<String, Value>,
+    left_obj: &IndexMap<String, Value>,
+    right_obj: &IndexMap<String, Value>,
     keys: &JoinKeys,
 ) -> Result<bool> {
     let left_keys = keys.left_columns();
@@ -652,12 +652,12 @@ fn values_equal_for_join(left: &Value, right: &Value) -> bool {
 /// Merge two objects, handling column name conflicts
 #[allow(clippy::unnecessary_wraps)]
 fn merge_objects(
-    left_obj: &HashMap<String, Value>,
-    right_obj: &HashMap<String, Value>,
+    left_obj: &IndexMap<String, Value>,
+    right_obj: &IndexMap<String, Value>,
     suffix: &str,
     fill_nulls: bool,
     null_keys: &std::collections::HashSet<String>,
-) -> Result<HashMap<String, Value>> {
+) -> Result<IndexMap<String, Value>> {
     let mut result = left_obj.clone();
 
     for (right_key, right_val) in right_obj {
@@ -864,7 +864,7 @@ pub fn join_with_condition(
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     use super::*;
 
@@ -958,22 +958,22 @@ mod tests {
     #[test]
     fn test_array_join() {
         let left_array = Value::Array(vec![
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(1)),
                 ("name".to_string(), Value::String("Alice".to_string())),
             ])),
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(2)),
                 ("name".to_string(), Value::String("Bob".to_string())),
             ])),
         ]);
 
         let right_array = Value::Array(vec![
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(1)),
                 ("age".to_string(), Value::Int(30)),
             ])),
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(3)),
                 ("age".to_string(), Value::Int(25)),
             ])),
@@ -1018,23 +1018,23 @@ mod tests {
     #[test]
     fn test_semi_join() {
         let left_array = Value::Array(vec![
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(1)),
                 ("name".to_string(), Value::String("Alice".to_string())),
             ])),
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(2)),
                 ("name".to_string(), Value::String("Bob".to_string())),
             ])),
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(3)),
                 ("name".to_string(), Value::String("Charlie".to_string())),
             ])),
         ]);
 
         let right_array = Value::Array(vec![
-            Value::Object(HashMap::from([("id".to_string(), Value::Int(1))])),
-            Value::Object(HashMap::from([("id".to_string(), Value::Int(3))])),
+            Value::Object(IndexMap::from([("id".to_string(), Value::Int(1))])),
+            Value::Object(IndexMap::from([("id".to_string(), Value::Int(3))])),
         ]);
 
         let keys = JoinKeys::on(vec!["id".to_string()]);
@@ -1061,17 +1061,17 @@ mod tests {
     #[test]
     fn test_anti_join() {
         let left_array = Value::Array(vec![
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(1)),
                 ("name".to_string(), Value::String("Alice".to_string())),
             ])),
-            Value::Object(HashMap::from([
+            Value::Object(IndexMap::from([
                 ("id".to_string(), Value::Int(2)),
                 ("name".to_string(), Value::String("Bob".to_string())),
             ])),
         ]);
 
-        let right_array = Value::Array(vec![Value::Object(HashMap::from([(
+        let right_array = Value::Array(vec![Value::Object(IndexMap::from([(
             "id".to_string(),
             Value::Int(1),
         )]))]);
@@ -1098,22 +1098,22 @@ mod tests {
     #[test]
     fn test_cross_join() {
         let left_array = Value::Array(vec![
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "name".to_string(),
                 Value::String("Alice".to_string()),
             )])),
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "name".to_string(),
                 Value::String("Bob".to_string()),
             )])),
         ]);
 
         let right_array = Value::Array(vec![
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "color".to_string(),
                 Value::String("Red".to_string()),
             )])),
-            Value::Object(HashMap::from([(
+            Value::Object(IndexMap::from([(
                 "color".to_string(),
                 Value::String("Blue".to_string()),
             )])),
@@ -1281,12 +1281,12 @@ mod tests {
 
     #[test]
     fn test_join_with_suffix() {
-        let left_array = Value::Array(vec![Value::Object(HashMap::from([
+        let left_array = Value::Array(vec![Value::Object(IndexMap::from([
             ("id".to_string(), Value::Int(1)),
             ("name".to_string(), Value::String("Alice".to_string())),
         ]))]);
 
-        let right_array = Value::Array(vec![Value::Object(HashMap::from([
+        let right_array = Value::Array(vec![Value::Object(IndexMap::from([
             ("id".to_string(), Value::Int(1)),
             ("name".to_string(), Value::String("Bob".to_string())), // Conflicting column
         ]))]);
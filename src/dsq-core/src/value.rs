@@ -1,7 +1,7 @@
 use crate::error::{Error, Result, TypeError};
 use polars::prelude::*;
 use serde_json::{Number as JsonNumber, Value as JsonValue};
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 impl Value {
     /// Check if value is null
@@ -359,7 +359,7 @@ impl Value {
                 };
 
                 let columns: Vec<String> = first_obj.keys().cloned().collect();
-                let mut series_map: HashMap<String, Vec<AnyValue>> = HashMap::new();
+                let mut series_map: IndexMap<String, Vec<AnyValue>> = IndexMap::new();
 
                 // Initialize series vectors
                 for col in &columns {
@@ -389,7 +389,7 @@ impl Value {
                 // Create Series from vectors
                 let mut series_vec = Vec::new();
                 for col in columns {
-                    let values = series_map.remove(&col).unwrap();
+                    let values = series_map.shift_remove(&col).unwrap();
                     let series = Series::new(col.as_str().into(), values);
                     series_vec.push(series);
                 }
@@ -472,7 +472,7 @@ impl Value {
 
                 if index >= 0 && index < len {
                     // Return a row as an object
-                    let mut row_obj = HashMap::new();
+                    let mut row_obj = IndexMap::new();
                     for col_name in df.get_column_names() {
                         let series = df.column(col_name).map_err(Error::from)?;
                         let value = self.series_value_to_json(series, index as usize)?;
@@ -491,7 +491,7 @@ impl Value {
 
                 if index >= 0 && index < len {
                     // Return a row as an object
-                    let mut row_obj = HashMap::new();
+                    let mut row_obj = IndexMap::new();
                     for col_name in df.get_column_names() {
                         let series = df.column(col_name).map_err(Error::from)?;
                         let value = self.series_value_to_json(series, index as usize)?;
@@ -637,7 +637,7 @@ mod tests {
         let arr = vec![Value::int(1), Value::int(2)];
         assert_eq!(Value::array(arr.clone()), Value::Array(arr));
 
-        let obj = HashMap::from([("key".to_string(), Value::string("value"))]);
+        let obj = IndexMap::from([("key".to_string(), Value::string("value"))]);
         assert_eq!(Value::object(obj.clone()), Value::Object(obj));
     }
 
@@ -690,7 +690,7 @@ mod tests {
         assert_eq!(Value::Float(3.14).type_name(), "float");
         assert_eq!(Value::String("test".to_string()).type_name(), "string");
         assert_eq!(Value::Array(vec![]).type_name(), "array");
-        assert_eq!(Value::Object(HashMap::new()).type_name(), "object");
+        assert_eq!(Value::Object(IndexMap::new()).type_name(), "object");
         assert_eq!(
             Value::DataFrame(DataFrame::empty()).type_name(),
             "dataframe"
@@ -749,7 +749,7 @@ mod tests {
         assert_eq!(Value::from_json(json), arr);
 
         // Object
-        let obj = Value::object(HashMap::from([
+        let obj = Value::object(IndexMap::from([
             ("name".to_string(), Value::string("Alice")),
             ("age".to_string(), Value::int(30)),
             ("active".to_string(), Value::bool(true)),
@@ -764,14 +764,14 @@ mod tests {
         assert_eq!(Value::from_json(json), obj);
 
         // Nested structures
-        let nested = Value::object(HashMap::from([
+        let nested = Value::object(IndexMap::from([
             (
                 "data".to_string(),
                 Value::array(vec![Value::int(1), Value::int(2)]),
             ),
             (
                 "meta".to_string(),
-                Value::object(HashMap::from([("count".to_string(), Value::int(2))])),
+                Value::object(IndexMap::from([("count".to_string(), Value::int(2))])),
             ),
         ]));
         let json = nested.to_json().unwrap();
@@ -832,7 +832,7 @@ mod tests {
 
     #[test]
     fn test_indexing_invalid() {
-        let obj = Value::object(HashMap::new());
+        let obj = Value::object(IndexMap::new());
         assert!(obj.index(0).is_err());
 
         let null_val = Value::Null;
@@ -841,12 +841,12 @@ mod tests {
 
     #[test]
     fn test_field_access_object() {
-        let obj = Value::object(HashMap::from([
+        let obj = Value::object(IndexMap::from([
             ("name".to_string(), Value::string("Bob")),
             ("age".to_string(), Value::int(25)),
             (
                 "nested".to_string(),
-                Value::object(HashMap::from([("inner".to_string(), Value::bool(true))])),
+                Value::object(IndexMap::from([("inner".to_string(), Value::bool(true))])),
             ),
         ]));
 
@@ -866,11 +866,11 @@ mod tests {
     #[test]
     fn test_field_access_array() {
         let arr = Value::array(vec![
-            Value::object(HashMap::from([(
+            Value::object(IndexMap::from([(
                 "name".to_string(),
                 Value::string("Alice"),
             )])),
-            Value::object(HashMap::from([("name".to_string(), Value::string("Bob"))])),
+            Value::object(IndexMap::from([("name".to_string(), Value::string("Bob"))])),
         ]);
 
         let result = arr.field("name").unwrap();
@@ -914,8 +914,8 @@ mod tests {
         let arr2 = Value::array(vec![Value::int(1), Value::int(2)]);
         assert_eq!(arr1, arr2);
 
-        let obj1 = Value::object(HashMap::from([("a".to_string(), Value::int(1))]));
-        let obj2 = Value::object(HashMap::from([("a".to_string(), Value::int(1))]));
+        let obj1 = Value::object(IndexMap::from([("a".to_string(), Value::int(1))]));
+        let obj2 = Value::object(IndexMap::from([("a".to_string(), Value::int(1))]));
         assert_eq!(obj1, obj2);
 
         // Inequalities
@@ -943,7 +943,7 @@ mod tests {
         let arr = Value::array(vec![Value::int(1), Value::string("two")]);
         assert_eq!(format!("{}", arr), "[1, \"two\"]");
 
-        let obj = Value::object(HashMap::from([
+        let obj = Value::object(IndexMap::from([
             ("a".to_string(), Value::int(1)),
             ("b".to_string(), Value::string("x")),
         ]));
@@ -956,11 +956,11 @@ mod tests {
     fn test_to_dataframe() {
         // Array of objects
         let data = Value::array(vec![
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("name".to_string(), Value::string("Alice")),
                 ("age".to_string(), Value::int(30)),
             ])),
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("name".to_string(), Value::string("Bob")),
                 ("age".to_string(), Value::int(25)),
             ])),
@@ -1168,13 +1168,13 @@ mod tests {
 
     #[test]
     fn test_nested_field_path() {
-        let nested = Value::object(HashMap::from([(
+        let nested = Value::object(IndexMap::from([(
             "user".to_string(),
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("name".to_string(), Value::string("Alice")),
                 (
                     "profile".to_string(),
-                    Value::object(HashMap::from([("age".to_string(), Value::int(30))])),
+                    Value::object(IndexMap::from([("age".to_string(), Value::int(30))])),
                 ),
             ])),
         )]));
@@ -1200,14 +1200,14 @@ mod tests {
     #[test]
     fn test_array_field_access_complex() {
         let arr = Value::array(vec![
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("name".to_string(), Value::string("Alice")),
                 (
                     "scores".to_string(),
                     Value::array(vec![Value::int(85), Value::int(90)]),
                 ),
             ])),
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("name".to_string(), Value::string("Bob")),
                 (
                     "scores".to_string(),
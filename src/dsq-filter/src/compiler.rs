@@ -10,102 +10,48 @@
 //! - Optimizing filter expressions for DataFrame operations
 //! - Type checking and error reporting
 
+use crate::modules::ModuleLoader;
+use crate::paths::{DeletePathsOperation, PathExpr, PathKey, PathOperation};
+use crate::rowwise::{Mapped, RowExpr};
+use crate::strings::{FormatOperation, FormatPart, FormatStringOperation};
+use dsq_functions::builtin::format::FORMATS;
+use dsq_functions::builtin::leaf_paths::is_leaf;
+use dsq_functions::builtin::paths::for_each_path;
+use dsq_functions::builtin::range::{range_bounds, range_values};
+use dsq_functions::builtin::recurse::{child_entries, for_each_descendant};
+use dsq_functions::builtin::regex_match::regex_args;
+use dsq_functions::builtin::sub::substitute;
 use dsq_functions::BuiltinRegistry;
-use dsq_parser::{BinaryOperator, Expr, FilterParser, Literal, ObjectEntry, UnaryOperator};
+use dsq_parser::{
+    BinaryOperator, Expr, FilterParser, FunctionDefinition, FunctionParam, Literal,
+    ModuleDirective, ObjectEntry, Pattern, PatternKey, StringPart, UnaryOperator,
+};
+use dsq_shared::error::{escapes_try, Break};
+use dsq_shared::limits;
+use dsq_shared::ops::stream::{CONTINUE, STOP};
 use dsq_shared::ops::*;
 use dsq_shared::value::{is_truthy, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use polars::prelude::*;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
 
 /// Convert Polars AnyValue to dsq Value
 fn value_from_any_value(any_val: AnyValue) -> Result<Value> {
-    match any_val {
-        AnyValue::Null => Ok(Value::Null),
-        AnyValue::Boolean(b) => Ok(Value::Bool(b)),
-        AnyValue::Int8(i) => Ok(Value::Int(i as i64)),
-        AnyValue::Int16(i) => Ok(Value::Int(i as i64)),
-        AnyValue::Int32(i) => Ok(Value::Int(i as i64)),
-        AnyValue::Int64(i) => Ok(Value::Int(i)),
-        AnyValue::UInt8(i) => Ok(Value::Int(i as i64)),
-        AnyValue::UInt16(i) => Ok(Value::Int(i as i64)),
-        AnyValue::UInt32(i) => Ok(Value::Int(i as i64)),
-        AnyValue::UInt64(i) => Ok(Value::Int(i as i64)),
-        AnyValue::Float32(f) => Ok(Value::Float(f as f64)),
-        AnyValue::Float64(f) => Ok(Value::Float(f)),
-        AnyValue::String(s) => Ok(Value::String(s.to_string())),
-        _ => Ok(Value::String(any_val.to_string())),
+    match dsq_shared::value::value_from_any_value(any_val.clone()) {
+        Some(value) => Ok(value),
+        None => Ok(Value::String(any_val.to_string())),
     }
 }
 
 /// Convert Values to Series
 fn values_to_series(name: &str, values: &[Value]) -> Result<Series> {
-    if values.is_empty() {
-        return Ok(Series::new_empty(name.into(), &DataType::Null));
-    }
-
-    // Determine the data type from the first non-null value
-    let dtype = values
-        .iter()
-        .find(|v| !matches!(v, Value::Null))
-        .map(|v| match v {
-            Value::Bool(_) => DataType::Boolean,
-            Value::Int(_) => DataType::Int64,
-            Value::Float(_) => DataType::Float64,
-            Value::String(_) => DataType::String,
-            _ => DataType::Null,
-        })
-        .unwrap_or(DataType::Null);
-
-    match dtype {
-        DataType::Boolean => {
-            let vec: Vec<Option<bool>> = values
-                .iter()
-                .map(|v| match v {
-                    Value::Bool(b) => Some(*b),
-                    Value::Null => None,
-                    _ => None,
-                })
-                .collect();
-            Ok(Series::new(name.into(), vec))
-        }
-        DataType::Int64 => {
-            let vec: Vec<Option<i64>> = values
-                .iter()
-                .map(|v| match v {
-                    Value::Int(i) => Some(*i),
-                    Value::Null => None,
-                    _ => None,
-                })
-                .collect();
-            Ok(Series::new(name.into(), vec))
-        }
-        DataType::Float64 => {
-            let vec: Vec<Option<f64>> = values
-                .iter()
-                .map(|v| match v {
-                    Value::Float(f) => Some(*f),
-                    Value::Int(i) => Some(*i as f64),
-                    Value::Null => None,
-                    _ => None,
-                })
-                .collect();
-            Ok(Series::new(name.into(), vec))
-        }
-        DataType::String => {
-            let vec: Vec<Option<&str>> = values
-                .iter()
-                .map(|v| match v {
-                    Value::String(s) => Some(s.as_str()),
-                    Value::Null => None,
-                    _ => None,
-                })
-                .collect();
-            Ok(Series::new(name.into(), vec))
-        }
-        _ => Ok(Series::new_null(name.into(), values.len())),
-    }
+    let values: Vec<&Value> = values.iter().collect();
+    dsq_shared::value::schema::values_to_series(name, &values)
 }
 
 /// Compare two values for sorting purposes
@@ -206,6 +152,10 @@ pub struct FilterCompiler {
     dataframe_optimizations: bool,
     /// Maximum recursion depth for compilation
     max_recursion_depth: usize,
+    /// Library search path for `import` and `include`
+    modules: ModuleLoader,
+    /// Directives applied before those of every compiled filter
+    directives: Vec<ModuleDirective>,
 }
 
 /// Optimization levels for filter compilation
@@ -233,6 +183,19 @@ pub struct CompiledFilter {
     pub complexity: usize,
 }
 
+impl CompiledFilter {
+    /// A filter with no operations, to be filled in by the caller
+    fn empty() -> Self {
+        Self {
+            operations: Vec::new(),
+            variables: HashMap::new(),
+            functions: HashMap::new(),
+            requires_lazy: false,
+            complexity: 1,
+        }
+    }
+}
+
 /// Compilation context for building filter operations
 ///
 /// The compilation context tracks the current compilation state including:
@@ -249,6 +212,9 @@ pub struct CompilationContext {
     pub variables: HashMap<String, Value>,
     /// Functions available during compilation
     pub functions: HashMap<String, FunctionDef>,
+    /// Lexically scoped user-defined functions and filter parameters,
+    /// innermost last
+    scope: Vec<ScopeEntry>,
 }
 
 impl Default for CompilationContext {
@@ -265,6 +231,7 @@ impl CompilationContext {
             max_depth: 1000,
             variables: HashMap::new(),
             functions: HashMap::new(),
+            scope: Vec::new(),
         }
     }
 
@@ -275,6 +242,7 @@ impl CompilationContext {
             max_depth,
             variables: HashMap::new(),
             functions: HashMap::new(),
+            scope: Vec::new(),
         }
     }
 }
@@ -330,6 +298,33 @@ impl std::fmt::Debug for FunctionBody {
 /// Built-in function implementation
 pub type BuiltinFunction = dsq_functions::BuiltinFunction;
 
+/// Cursor over the documents read by `input` and `inputs`
+///
+/// Clones share the cursor, so a document read once is not read again.
+#[derive(Clone, Default)]
+pub struct InputCursor(Option<Arc<Mutex<Documents>>>);
+
+/// The documents behind an [`InputCursor`]
+type Documents = Box<dyn Iterator<Item = Result<Value>> + Send>;
+
+impl InputCursor {
+    /// Create a cursor over `documents`
+    pub fn new(documents: impl Iterator<Item = Result<Value>> + Send + 'static) -> Self {
+        Self(Some(Arc::new(Mutex::new(Box::new(documents)))))
+    }
+
+    /// The next document, or `None` once every document has been read
+    pub fn next_document(&self) -> Option<Result<Value>> {
+        self.0.as_ref()?.lock().ok()?.next()
+    }
+}
+
+impl std::fmt::Debug for InputCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("InputCursor")
+    }
+}
+
 /// Execution context for filter operations
 #[derive(Debug, Clone)]
 pub struct FilterContext {
@@ -356,6 +351,9 @@ pub struct FilterContext {
 
     /// Error handling mode
     error_mode: ErrorMode,
+
+    /// Documents read by `input` and `inputs`
+    inputs: InputCursor,
 }
 
 /// Stack frame for debugging and recursion tracking
@@ -415,6 +413,7 @@ impl FilterContext {
             debug_mode: false,
             current_input: None,
             error_mode: ErrorMode::Strict,
+            inputs: InputCursor::default(),
         }
     }
 
@@ -553,6 +552,16 @@ impl FilterContext {
     pub fn error_mode(&self) -> ErrorMode {
         self.error_mode
     }
+
+    /// Set the documents read by `input` and `inputs`
+    pub fn set_inputs(&mut self, inputs: InputCursor) {
+        self.inputs = inputs;
+    }
+
+    /// Read the next document for `input` and `inputs`
+    pub fn next_input(&self) -> Option<Result<Value>> {
+        self.inputs.next_document()
+    }
 }
 
 impl dsq_shared::ops::Context for FilterContext {
@@ -564,6 +573,10 @@ impl dsq_shared::ops::Context for FilterContext {
         self.variables.insert(name.to_string(), value);
     }
 
+    fn remove_variable(&mut self, name: &str) {
+        self.variables.remove(name);
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -587,6 +600,8 @@ impl FilterCompiler {
             optimization_level: OptimizationLevel::Basic,
             dataframe_optimizations: true,
             max_recursion_depth: 1000,
+            modules: ModuleLoader::new(&[]),
+            directives: Vec::new(),
         }
     }
 
@@ -608,6 +623,49 @@ impl FilterCompiler {
         self
     }
 
+    /// Search `paths` for modules before the default library locations
+    pub fn with_library_paths(mut self, paths: &[PathBuf]) -> Self {
+        self.modules = ModuleLoader::new(paths);
+        self
+    }
+
+    /// Import the module `module` into every filter, as if by
+    /// `import "module" as name;` where `name` is its last path component
+    pub fn with_import(mut self, module: &str) -> Self {
+        let alias = module
+            .rsplit('/')
+            .next()
+            .unwrap_or(module)
+            .split('.')
+            .next()
+            .unwrap_or(module)
+            .to_string();
+        self.directives.push(ModuleDirective::Import {
+            path: module.to_string(),
+            alias,
+            metadata: None,
+        });
+        self
+    }
+
+    /// Include the definitions of `path` into every filter, as if by
+    /// `include "path";`; an existing relative file is taken from the
+    /// working directory
+    pub fn with_include(mut self, path: &Path) -> Self {
+        let path = if path.is_relative() && path.is_file() {
+            std::env::current_dir()
+                .map(|dir| dir.join(path))
+                .unwrap_or_else(|_| path.to_path_buf())
+        } else {
+            path.to_path_buf()
+        };
+        self.directives.push(ModuleDirective::Include {
+            path: path.to_string_lossy().into_owned(),
+            metadata: None,
+        });
+        self
+    }
+
     /// Compile a filter expression from a string
     pub fn compile_str(&self, filter: &str) -> Result<CompiledFilter> {
         // Parse the filter string using dsq-parser
@@ -632,7 +690,145 @@ impl FilterCompiler {
             }
         }
 
-        self.compile_expr(&parsed.expr, &mut ctx)
+        // Bring imported functions into scope and collect data modules
+        let mut variables = HashMap::new();
+        let mut loading = Vec::new();
+        for directives in [&self.directives, &parsed.directives] {
+            self.load_directives(directives, None, &mut ctx, &mut variables, &mut loading)?;
+        }
+
+        let mut compiled = self.compile_expr(&parsed.expr, &mut ctx)?;
+        compiled.variables.extend(variables);
+        Ok(compiled)
+    }
+
+    /// Apply `import`/`include` directives found in `origin` (`None` for the
+    /// main filter), pushing the loaded functions onto the scope of `ctx`
+    fn load_directives(
+        &self,
+        directives: &[ModuleDirective],
+        origin: Option<&Path>,
+        ctx: &mut CompilationContext,
+        variables: &mut HashMap<String, Value>,
+        loading: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        for directive in directives {
+            match directive {
+                ModuleDirective::Import {
+                    path,
+                    alias,
+                    metadata,
+                } => {
+                    let file = self
+                        .modules
+                        .resolve_module(path, origin, metadata.as_ref())?;
+                    for function in self.load_module(&file, ctx, variables, loading)? {
+                        // The body still refers to its siblings and itself unqualified
+                        let mut scope = function.scope.clone();
+                        scope.push(ScopeEntry::Function(function.clone()));
+                        ctx.scope.push(ScopeEntry::Function(Arc::new(UserFunction {
+                            name: format!("{}::{}", alias, function.name),
+                            params: function.params.clone(),
+                            body: function.body.clone(),
+                            scope,
+                            specializations: Mutex::new(Vec::new()),
+                        })));
+                    }
+                }
+                ModuleDirective::Include { path, metadata } => {
+                    let file = self
+                        .modules
+                        .resolve_module(path, origin, metadata.as_ref())?;
+                    for function in self.load_module(&file, ctx, variables, loading)? {
+                        ctx.scope.push(ScopeEntry::Function(function));
+                    }
+                }
+                ModuleDirective::ImportData {
+                    path,
+                    alias,
+                    metadata,
+                } => {
+                    let file = self.modules.resolve_data(path, origin, metadata.as_ref())?;
+                    let data = ModuleLoader::load_data(&file)?;
+                    variables.insert(format!("{}::{}", alias, alias), data.clone());
+                    variables.insert(alias.clone(), data);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the module in `file` and return the functions it defines,
+    /// including those it includes
+    fn load_module(
+        &self,
+        file: &Path,
+        ctx: &mut CompilationContext,
+        variables: &mut HashMap<String, Value>,
+        loading: &mut Vec<PathBuf>,
+    ) -> Result<Vec<Arc<UserFunction>>> {
+        let canonical = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        if loading.contains(&canonical) {
+            let cycle: Vec<_> = loading
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err(dsq_shared::error::operation_error(format!(
+                "Circular module import: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        let source = std::fs::read_to_string(file).map_err(|e| {
+            dsq_shared::error::operation_error(format!(
+                "Failed to read module {}: {}",
+                file.display(),
+                e
+            ))
+        })?;
+        let module = FilterParser::new().parse_module(&source).map_err(|e| {
+            dsq_shared::error::operation_error(format!(
+                "Failed to parse module {}: {}",
+                file.display(),
+                e
+            ))
+        })?;
+
+        // Modules only see their own imports and definitions
+        let saved = std::mem::take(&mut ctx.scope);
+        loading.push(canonical.clone());
+        let loaded = self.load_directives(
+            &module.directives,
+            Some(&canonical),
+            ctx,
+            variables,
+            loading,
+        );
+        loading.pop();
+        if let Err(e) = loaded {
+            ctx.scope = saved;
+            return Err(e);
+        }
+        for FunctionDefinition { name, params, body } in module.functions {
+            ctx.scope.push(ScopeEntry::Function(Arc::new(UserFunction {
+                name,
+                params,
+                body,
+                scope: ctx.scope.clone(),
+                specializations: Mutex::new(Vec::new()),
+            })));
+        }
+
+        // Imported functions stay private to the module
+        let scope = std::mem::replace(&mut ctx.scope, saved);
+        Ok(scope
+            .into_iter()
+            .filter_map(|entry| match entry {
+                ScopeEntry::Function(function) if !function.name.contains("::") => Some(function),
+                _ => None,
+            })
+            .collect())
     }
 
     /// Compile a dsq-parser Expr into dsq operations
@@ -722,7 +918,7 @@ impl FilterCompiler {
             }
             Expr::Identifier(name) => {
                 // Identifier (variable or function)
-                self.compile_identifier(name)
+                self.compile_identifier(name, ctx)
             }
             Expr::Paren(inner) => {
                 // Parenthesized expression
@@ -755,6 +951,99 @@ impl FilterCompiler {
                 // If-then-else expression
                 self.compile_if(condition, then_branch, else_branch, ctx)
             }
+            Expr::Try { body, catch } => {
+                // Error handling (expr? or try-catch)
+                self.compile_try(body, catch.as_deref(), ctx)
+            }
+            Expr::FormatString { format, parts } => {
+                // String interpolation, optionally through a format
+                self.compile_format_string(format.as_deref(), parts, ctx)
+            }
+            Expr::Format(format) => {
+                // Format applied to the input (@csv, @base64, ...)
+                Ok(CompiledFilter {
+                    operations: vec![Box::new(FormatOperation {
+                        format: check_format(format)?.to_string(),
+                    })],
+                    variables: HashMap::new(),
+                    functions: HashMap::new(),
+                    requires_lazy: false,
+                    complexity: 1,
+                })
+            }
+            Expr::Reduce {
+                source,
+                variable,
+                init,
+                update,
+            } => {
+                // Reduce expression
+                self.compile_reduce(source, variable, init, update, ctx)
+            }
+            Expr::Foreach {
+                source,
+                variable,
+                init,
+                update,
+                extract,
+            } => {
+                // Foreach expression
+                self.compile_foreach(source, variable, init, update, extract.as_deref(), ctx)
+            }
+            Expr::Bind {
+                source,
+                patterns,
+                body,
+            } => {
+                // Variable binding
+                self.compile_bind(source, patterns, body, ctx)
+            }
+            Expr::Def {
+                name,
+                params,
+                body,
+                rest,
+            } => {
+                // Function definition, visible in `rest` and in its own body
+                ctx.scope.push(ScopeEntry::Function(Arc::new(UserFunction {
+                    name: name.clone(),
+                    params: params.clone(),
+                    body: (**body).clone(),
+                    scope: ctx.scope.clone(),
+                    specializations: Mutex::new(Vec::new()),
+                })));
+                let result = self.compile_expr(rest, ctx);
+                ctx.scope.pop();
+                result
+            }
+            Expr::Label { name, body } => {
+                // Label that `break $name` inside `body` exits to
+                self.compile_label(name, body, ctx)
+            }
+            Expr::Break(name) => {
+                // Early exit to the innermost label of that name
+                let label = ctx
+                    .scope
+                    .iter()
+                    .rev()
+                    .find_map(|entry| match entry {
+                        ScopeEntry::Label { name: label, id } if label == name => Some(*id),
+                        _ => None,
+                    })
+                    .ok_or_else(|| {
+                        dsq_shared::error::operation_error(format!(
+                            "$*label-{} is not defined",
+                            name
+                        ))
+                    })?;
+                Ok(CompiledFilter {
+                    operations: vec![Box::new(BreakOperation { label })],
+                    variables: HashMap::new(),
+                    functions: HashMap::new(),
+                    requires_lazy: false,
+                    complexity: 1,
+                })
+            }
         }
     }
 
@@ -964,6 +1253,10 @@ impl FilterCompiler {
                 left_filter.operations,
                 right_filter.operations,
             )),
+            BinaryOperator::Alternative => Box::new(AlternativeOperation::new(
+                left_filter.operations,
+                right_filter.operations,
+            )),
         };
 
         Ok(CompiledFilter {
@@ -982,11 +1275,19 @@ impl FilterCompiler {
         expr: &Expr,
         ctx: &mut CompilationContext,
     ) -> Result<CompiledFilter> {
+        if let UnaryOperator::Del = op {
+            let mut parts = CompiledFilter::empty();
+            let path = self.compile_path(expr, ctx, &mut parts)?;
+            parts.operations = vec![Box::new(DeletePathsOperation { path })];
+            parts.complexity += 1;
+            return Ok(parts);
+        }
+
         let expr_filter = self.compile_expr(expr, ctx)?;
 
         let operation: Box<dyn Operation + Send + Sync> = match op {
             UnaryOperator::Not => Box::new(NegationOperation::new(expr_filter.operations)),
-            UnaryOperator::Del => Box::new(DelOperation::new(expr_filter.operations)),
+            UnaryOperator::Del => unreachable!("del is compiled as a path expression"),
         };
 
         Ok(CompiledFilter {
@@ -1038,6 +1339,108 @@ impl FilterCompiler {
         })
     }
 
+    /// Compile `label $name | body`
+    fn compile_label(
+        &self,
+        name: &str,
+        body: &Expr,
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        static NEXT_LABEL: AtomicUsize = AtomicUsize::new(0);
+        let label = NEXT_LABEL.fetch_add(1, Ordering::Relaxed);
+        ctx.scope.push(ScopeEntry::Label {
+            name: name.to_string(),
+            id: label,
+        });
+        let body_filter = self.compile_expr(body, ctx);
+        ctx.scope.pop();
+        let body_filter = body_filter?;
+
+        Ok(CompiledFilter {
+            operations: vec![Box::new(LabelOperation {
+                label,
+                body_ops: body_filter.operations,
+            })],
+            variables: body_filter.variables,
+            functions: body_filter.functions,
+            requires_lazy: body_filter.requires_lazy,
+            complexity: body_filter.complexity + 1,
+        })
+    }
+
+    /// Compile error handling expressions
+    fn compile_try(
+        &self,
+        body: &Expr,
+        catch: Option<&Expr>,
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        let body_filter = self.compile_expr(body, ctx)?;
+        let catch_filter = catch.map(|c| self.compile_expr(c, ctx)).transpose()?;
+
+        let mut variables = body_filter.variables;
+        let mut functions = body_filter.functions;
+        let mut requires_lazy = body_filter.requires_lazy;
+        let mut complexity = body_filter.complexity + 1;
+        let catch_ops = catch_filter.map(|filter| {
+            variables.extend(filter.variables);
+            functions.extend(filter.functions);
+            requires_lazy |= filter.requires_lazy;
+            complexity += filter.complexity;
+            filter.operations
+        });
+
+        Ok(CompiledFilter {
+            operations: vec![Box::new(TryOperation::new(
+                body_filter.operations,
+                catch_ops,
+            ))],
+            variables,
+            functions,
+            requires_lazy,
+            complexity,
+        })
+    }
+
+    /// Compile `"text \(f)"` and `@fmt "text \(f)"`
+    fn compile_format_string(
+        &self,
+        format: Option<&str>,
+        parts: &[StringPart],
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        let format = format.map(check_format).transpose()?;
+        let mut compiled = Vec::with_capacity(parts.len());
+        let mut variables = HashMap::new();
+        let mut functions = HashMap::new();
+        let mut requires_lazy = false;
+        let mut complexity = 1;
+        for part in parts {
+            compiled.push(match part {
+                StringPart::Literal(text) => FormatPart::Literal(text.clone()),
+                StringPart::Interpolation(expr) => {
+                    let filter = self.compile_expr(expr, ctx)?;
+                    variables.extend(filter.variables);
+                    functions.extend(filter.functions);
+                    requires_lazy |= filter.requires_lazy;
+                    complexity += filter.complexity;
+                    FormatPart::Interpolation(filter.operations)
+                }
+            });
+        }
+
+        Ok(CompiledFilter {
+            operations: vec![Box::new(FormatStringOperation {
+                format: format.map(str::to_string),
+                parts: compiled,
+            })],
+            variables,
+            functions,
+            requires_lazy,
+            complexity,
+        })
+    }
+
     /// Compile function call expressions
     fn compile_function_call(
         &self,
@@ -1045,6 +1448,41 @@ impl FilterCompiler {
         args: &[Expr],
         ctx: &mut CompilationContext,
     ) -> Result<CompiledFilter> {
+        // User-defined functions shadow builtins of the same arity
+        if let Some(entry) = ctx.lookup(name, args.len()) {
+            return self.compile_user_call(entry, args, ctx);
+        }
+        if name.contains("::") {
+            return Err(dsq_shared::error::operation_error(format!(
+                "{}/{} is not defined",
+                name,
+                args.len()
+            )));
+        }
+
+        // `path(f)` and `del(f)`; as with other calls, `path(.a, .b)` passes
+        // the comma-separated paths as separate arguments
+        if (name == "path" || name == "del") && !args.is_empty() {
+            let target = match args {
+                [arg] => arg.clone(),
+                _ => Expr::Sequence(args.to_vec()),
+            };
+            let mut parts = CompiledFilter::empty();
+            match self.compile_path(&target, ctx, &mut parts) {
+                Ok(path) if name == "path" => {
+                    parts.operations = vec![Box::new(PathOperation { path })];
+                    return Ok(parts);
+                }
+                Ok(path) => {
+                    parts.operations = vec![Box::new(DeletePathsOperation { path })];
+                    return Ok(parts);
+                }
+                // `del(object; key, ...)` deletes keys by value
+                Err(_) if name == "del" && args.len() > 1 => {}
+                Err(e) => return Err(e),
+            }
+        }
+
         // Special handling for join function
         if name == "join" && args.len() == 2 {
             if let (
@@ -1108,11 +1546,15 @@ impl FilterCompiler {
             arg_filters.push(arg_filter.operations);
         }
 
-        let operation = Box::new(FunctionCallOperation::new(
-            name.to_string(),
-            arg_filters,
-            self.builtins.clone(),
-        ));
+        let mut operation =
+            FunctionCallOperation::new(name.to_string(), arg_filters, self.builtins.clone());
+        if self.dataframe_optimizations
+            && matches!((name, args), ("map" | "select" | "filter", [_]))
+        {
+            operation.row_filter =
+                RowExpr::lower(&args[0], &|name, arity| ctx.lookup(name, arity).is_none());
+        }
+        let operation = Box::new(operation);
 
         Ok(CompiledFilter {
             operations: vec![operation],
@@ -1123,6 +1565,124 @@ impl FilterCompiler {
         })
     }
 
+    /// Compile `expr` as a path expression, merging the variables and
+    /// functions of any sub-filters into `parts`
+    fn compile_path(
+        &self,
+        expr: &Expr,
+        ctx: &mut CompilationContext,
+        parts: &mut CompiledFilter,
+    ) -> Result<PathExpr> {
+        let access = |base: PathExpr, key: PathKey| PathExpr::Access {
+            base: Box::new(base),
+            key,
+        };
+        Ok(match expr {
+            Expr::Identity => PathExpr::Identity,
+            Expr::Paren(inner) => self.compile_path(inner, ctx, parts)?,
+            Expr::FieldAccess { base, fields } => {
+                let mut path = self.compile_path(base, ctx, parts)?;
+                for field in fields {
+                    path = access(path, PathKey::Key(Value::String(field.clone())));
+                }
+                path
+            }
+            Expr::ArrayAccess { array, index } => {
+                let base = self.compile_path(array, ctx, parts)?;
+                let key = match index.as_ref() {
+                    Expr::Literal(Literal::String(s)) => PathKey::Key(Value::String(s.clone())),
+                    Expr::Literal(Literal::Int(i)) => PathKey::Key(Value::Int(*i)),
+                    other => PathKey::Index(self.compile_path_ops(other, ctx, parts)?),
+                };
+                access(base, key)
+            }
+            Expr::ArraySlice { array, start, end } => {
+                let base = self.compile_path(array, ctx, parts)?;
+                let start = start
+                    .as_ref()
+                    .map(|e| self.compile_path_ops(e, ctx, parts))
+                    .transpose()?;
+                let end = end
+                    .as_ref()
+                    .map(|e| self.compile_path_ops(e, ctx, parts))
+                    .transpose()?;
+                access(base, PathKey::Slice(start, end))
+            }
+            Expr::ArrayIteration(base) => {
+                access(self.compile_path(base, ctx, parts)?, PathKey::Iterate)
+            }
+            Expr::Pipeline(exprs) => {
+                let mut path = PathExpr::Identity;
+                for expr in exprs {
+                    let next = self.compile_path(expr, ctx, parts)?;
+                    path = match path {
+                        PathExpr::Identity => next,
+                        path => PathExpr::Pipe(Box::new(path), Box::new(next)),
+                    };
+                }
+                path
+            }
+            Expr::Sequence(exprs) => PathExpr::Comma(
+                exprs
+                    .iter()
+                    .map(|e| self.compile_path(e, ctx, parts))
+                    .collect::<Result<_>>()?,
+            ),
+            Expr::Try { body, catch: None } => {
+                PathExpr::Try(Box::new(self.compile_path(body, ctx, parts)?))
+            }
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => PathExpr::If {
+                condition: self.compile_path_ops(condition, ctx, parts)?,
+                then_branch: Box::new(self.compile_path(then_branch, ctx, parts)?),
+                else_branch: Box::new(self.compile_path(else_branch, ctx, parts)?),
+            },
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Alternative,
+                right,
+            } => PathExpr::Alternative(
+                Box::new(self.compile_path(left, ctx, parts)?),
+                Box::new(self.compile_path(right, ctx, parts)?),
+            ),
+            Expr::Identifier(name) | Expr::FunctionCall { name, .. }
+                if ctx.lookup(name, path_call_args(expr).len()).is_none() =>
+            {
+                match (name.as_str(), path_call_args(expr)) {
+                    ("recurse", []) => PathExpr::Recurse,
+                    ("first", []) => access(PathExpr::Identity, PathKey::Key(Value::Int(0))),
+                    ("last", []) => access(PathExpr::Identity, PathKey::Key(Value::Int(-1))),
+                    ("select", [condition]) => {
+                        PathExpr::Select(self.compile_path_ops(condition, ctx, parts)?)
+                    }
+                    ("getpath", [path]) => {
+                        PathExpr::GetPath(self.compile_path_ops(path, ctx, parts)?)
+                    }
+                    _ => return Err(invalid_path_expression(expr)),
+                }
+            }
+            _ => return Err(invalid_path_expression(expr)),
+        })
+    }
+
+    /// Compile a sub-filter of a path expression to plain operations
+    fn compile_path_ops(
+        &self,
+        expr: &Expr,
+        ctx: &mut CompilationContext,
+        parts: &mut CompiledFilter,
+    ) -> Result<Vec<Box<dyn Operation + Send + Sync>>> {
+        let filter = self.compile_expr(expr, ctx)?;
+        parts.variables.extend(filter.variables);
+        parts.functions.extend(filter.functions);
+        parts.requires_lazy |= filter.requires_lazy;
+        parts.complexity += filter.complexity;
+        Ok(filter.operations)
+    }
+
     /// Compile assignment operations
     fn compile_assignment(
         &self,
@@ -1284,9 +1844,15 @@ impl FilterCompiler {
             element_filters.push(element_filter.operations);
         }
 
-        let mut all_element_ops = Vec::new();
-        for element_ops in element_filters {
-            all_element_ops.extend(element_ops);
+        // ArrayConstructOperation takes one operation per element, so chain
+        // multi-step elements like `$x.name` into a single operation
+        let mut all_element_ops: Vec<Box<dyn Operation + Send + Sync>> = Vec::new();
+        for mut element_ops in element_filters {
+            if element_ops.len() == 1 {
+                all_element_ops.extend(element_ops.pop());
+            } else {
+                all_element_ops.push(Box::new(ChainOperation { ops: element_ops }));
+            }
         }
         let operations: Vec<Box<dyn Operation + Send + Sync>> =
             vec![Box::new(ArrayConstructOperation::new(all_element_ops))];
@@ -1321,7 +1887,22 @@ impl FilterCompiler {
     }
 
     /// Compile identifiers
-    fn compile_identifier(&self, name: &str) -> Result<CompiledFilter> {
+    fn compile_identifier(
+        &self,
+        name: &str,
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        // User-defined functions and filter parameters shadow builtins
+        if let Some(entry) = ctx.lookup(name, 0) {
+            return self.compile_user_call(entry, &[], ctx);
+        }
+        if name.contains("::") {
+            return Err(dsq_shared::error::operation_error(format!(
+                "{}/0 is not defined",
+                name
+            )));
+        }
+
         // Check if this is a builtin function
         if self.builtins.has_function(name) {
             Ok(CompiledFilter {
@@ -1469,36 +2050,2015 @@ impl FilterCompiler {
     }
 }
 
-struct FunctionCallOperation {
-    name: String,
-    arg_ops: Vec<Vec<Box<dyn Operation + Send + Sync>>>,
-    builtins: Arc<BuiltinRegistry>,
-}
-
-impl FunctionCallOperation {
-    fn new(
-        name: String,
-        arg_ops: Vec<Vec<Box<dyn Operation + Send + Sync>>>,
-        builtins: Arc<BuiltinRegistry>,
-    ) -> Self {
-        Self {
-            name,
-            arg_ops,
-            builtins,
+impl FilterCompiler {
+    /// Compile the source of a reduce/foreach into operations plus a flag
+    /// telling whether the result should be iterated (`.[]`, `a, b`) or bound
+    /// as a single value.
+    fn compile_fold_source(
+        &self,
+        source: &Expr,
+        ctx: &mut CompilationContext,
+    ) -> Result<(CompiledFilter, bool)> {
+        match source {
+            Expr::Paren(inner) => self.compile_fold_source(inner, ctx),
+            // Compile the base only so DataFrame rows can be folded one at a time
+            Expr::ArrayIteration(base) => Ok((self.compile_expr(base, ctx)?, true)),
+            _ => Ok((self.compile_expr(source, ctx)?, false)),
         }
     }
-}
-
-impl Operation for FunctionCallOperation {
-    fn apply(&self, value: &Value) -> Result<Value> {
-        let mut context = None;
-        self.apply_with_context(value, &mut context)
-    }
+
+    /// Compile reduce expressions
+    fn compile_reduce(
+        &self,
+        source: &Expr,
+        variable: &str,
+        init: &Expr,
+        update: &Expr,
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        let (source_filter, iterate_source) = self.compile_fold_source(source, ctx)?;
+        let init_filter = self.compile_expr(init, ctx)?;
+        let update_filter = self.compile_expr(update, ctx)?;
+
+        let mut fields = Vec::new();
+        let row_columns = variable_fields(update, variable, &mut fields).then_some(fields);
+        let column_sum = column_sum_field(update, variable);
+
+        let mut variables = source_filter.variables;
+        variables.extend(init_filter.variables);
+        variables.extend(update_filter.variables);
+        let mut functions = source_filter.functions;
+        functions.extend(init_filter.functions);
+        functions.extend(update_filter.functions);
+
+        Ok(CompiledFilter {
+            operations: vec![Box::new(ReduceOperation {
+                source_ops: source_filter.operations,
+                iterate_source,
+                variable: variable.to_string(),
+                init_ops: init_filter.operations,
+                update_ops: update_filter.operations,
+                row_columns,
+                column_sum,
+            })],
+            variables,
+            functions,
+            requires_lazy: source_filter.requires_lazy,
+            complexity: source_filter.complexity
+                + init_filter.complexity
+                + update_filter.complexity * 2,
+        })
+    }
+
+    /// Compile foreach expressions
+    fn compile_foreach(
+        &self,
+        source: &Expr,
+        variable: &str,
+        init: &Expr,
+        update: &Expr,
+        extract: Option<&Expr>,
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        let (source_filter, iterate_source) = self.compile_fold_source(source, ctx)?;
+        let init_filter = self.compile_expr(init, ctx)?;
+        let update_filter = self.compile_expr(update, ctx)?;
+        let extract_filter = extract.map(|e| self.compile_expr(e, ctx)).transpose()?;
+
+        let mut fields = Vec::new();
+        let whole_row_needed = !variable_fields(update, variable, &mut fields)
+            || extract.is_some_and(|e| !variable_fields(e, variable, &mut fields));
+        let row_columns = (!whole_row_needed).then_some(fields);
+
+        let mut variables = source_filter.variables;
+        variables.extend(init_filter.variables);
+        variables.extend(update_filter.variables);
+        let mut functions = source_filter.functions;
+        functions.extend(init_filter.functions);
+        functions.extend(update_filter.functions);
+        let mut complexity =
+            source_filter.complexity + init_filter.complexity + update_filter.complexity * 2;
+        let extract_ops = extract_filter.map(|filter| {
+            variables.extend(filter.variables);
+            functions.extend(filter.functions);
+            complexity += filter.complexity;
+            filter.operations
+        });
+
+        Ok(CompiledFilter {
+            operations: vec![Box::new(ForeachOperation {
+                source_ops: source_filter.operations,
+                iterate_source,
+                variable: variable.to_string(),
+                init_ops: init_filter.operations,
+                update_ops: update_filter.operations,
+                extract_ops,
+                row_columns,
+            })],
+            variables,
+            functions,
+            requires_lazy: source_filter.requires_lazy,
+            complexity,
+        })
+    }
+
+    /// Compile variable bindings (`source as $x | body`)
+    fn compile_bind(
+        &self,
+        source: &Expr,
+        patterns: &[Pattern],
+        body: &Expr,
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        let (source_filter, iterate_source) = self.compile_fold_source(source, ctx)?;
+        let compiled_patterns = patterns
+            .iter()
+            .map(|pattern| self.compile_pattern(pattern, ctx))
+            .collect::<Result<Vec<_>>>()?;
+        let body_filter = self.compile_expr(body, ctx)?;
+
+        let mut bound = Vec::new();
+        for pattern in patterns {
+            pattern_variables(pattern, &mut bound);
+        }
+
+        let mut variables = source_filter.variables;
+        variables.extend(body_filter.variables);
+        let mut functions = source_filter.functions;
+        functions.extend(body_filter.functions);
+
+        Ok(CompiledFilter {
+            operations: vec![Box::new(BindOperation {
+                source_ops: source_filter.operations,
+                iterate_source,
+                patterns: compiled_patterns,
+                variables: bound,
+                body_ops: body_filter.operations,
+            })],
+            variables,
+            functions,
+            requires_lazy: source_filter.requires_lazy || body_filter.requires_lazy,
+            complexity: source_filter.complexity + body_filter.complexity + patterns.len(),
+        })
+    }
+
+    /// Compile a destructuring pattern, compiling any computed keys
+    fn compile_pattern(
+        &self,
+        pattern: &Pattern,
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledPattern> {
+        Ok(match pattern {
+            Pattern::Variable(name) => CompiledPattern::Variable(name.clone()),
+            Pattern::Array(elements) => CompiledPattern::Array(
+                elements
+                    .iter()
+                    .map(|p| self.compile_pattern(p, ctx))
+                    .collect::<Result<_>>()?,
+            ),
+            Pattern::Object(entries) => {
+                let mut compiled = Vec::with_capacity(entries.len());
+                for entry in entries {
+                    let key = match &entry.key {
+                        PatternKey::Variable(name) => CompiledPatternKey::Variable(name.clone()),
+                        PatternKey::Literal(key) => CompiledPatternKey::Literal(key.clone()),
+                        PatternKey::Expr(expr) => {
+                            CompiledPatternKey::Expr(self.compile_expr(expr, ctx)?.operations)
+                        }
+                    };
+                    let value = entry
+                        .pattern
+                        .as_ref()
+                        .map(|p| self.compile_pattern(p, ctx))
+                        .transpose()?;
+                    compiled.push((key, value));
+                }
+                CompiledPattern::Object(compiled)
+            }
+        })
+    }
+}
+
+impl FilterCompiler {
+    /// Compile a call to a user-defined function or filter parameter.
+    ///
+    /// Arguments are compiled as closures in the caller's scope. The function
+    /// body is compiled in the scope of the definition once per distinct set
+    /// of filter closures, so a recursive call that passes its own filter
+    /// parameters through reuses the body being compiled.
+    fn compile_user_call(
+        &self,
+        entry: ScopeEntry,
+        args: &[Expr],
+        ctx: &mut CompilationContext,
+    ) -> Result<CompiledFilter> {
+        let function = match entry {
+            ScopeEntry::Param { closure, .. } => {
+                return Ok(CompiledFilter {
+                    operations: vec![Box::new(ClosureOperation { closure })],
+                    variables: HashMap::new(),
+                    functions: HashMap::new(),
+                    requires_lazy: false,
+                    complexity: 1,
+                });
+            }
+            ScopeEntry::Function(function) => function,
+            ScopeEntry::Label { name, .. } => {
+                return Err(dsq_shared::error::operation_error(format!(
+                    "${} is a label, not a function",
+                    name
+                )))
+            }
+        };
+
+        let mut closures = Vec::with_capacity(args.len());
+        let mut captures = Vec::new();
+        let mut complexity = 1;
+        for (param, arg) in function.params.iter().zip(args) {
+            let passthrough = match arg {
+                Expr::Identifier(name) => ctx.lookup(name, 0),
+                Expr::FunctionCall { name, args } if args.is_empty() => ctx.lookup(name, 0),
+                _ => None,
+            };
+            let closure = match passthrough {
+                // Already bound to its caller's variables further up the stack
+                Some(ScopeEntry::Param { closure, .. }) => closure,
+                _ => {
+                    let filter = self.compile_expr(arg, ctx)?;
+                    complexity += filter.complexity;
+                    let mut variables = Vec::new();
+                    closure_variables(arg, &ctx.scope, &mut variables, &mut Vec::new());
+                    let closure = Arc::new(Closure::new(filter.operations, variables));
+                    if matches!(param, FunctionParam::Filter(_)) {
+                        captures.push(closure.clone());
+                    }
+                    closure
+                }
+            };
+            closures.push(closure);
+        }
+
+        // Value arguments are bound at run time, so only filter arguments
+        // need a separately compiled body
+        let filter_closures: Vec<Arc<Closure>> = function
+            .params
+            .iter()
+            .zip(&closures)
+            .filter(|(param, _)| matches!(param, FunctionParam::Filter(_)))
+            .map(|(_, closure)| closure.clone())
+            .collect();
+        let (body, fresh) = function.specialization(&filter_closures);
+        if fresh {
+            let saved = std::mem::replace(&mut ctx.scope, function.scope.clone());
+            ctx.scope.push(ScopeEntry::Function(function.clone()));
+            for (param, closure) in function.params.iter().zip(&closures) {
+                let closure = match param {
+                    FunctionParam::Filter(_) => closure.clone(),
+                    // `x` for a `$x` parameter yields the bound value
+                    FunctionParam::Value(name) => Arc::new(Closure::new(
+                        vec![Box::new(VariableOperation::new(name.clone()))],
+                        Vec::new(),
+                    )),
+                };
+                ctx.scope.push(ScopeEntry::Param {
+                    name: param.name().to_string(),
+                    closure,
+                });
+            }
+            let compiled = self.compile_expr(&function.body, ctx);
+            ctx.scope = saved;
+            // Ignore the result: only this call sets the body
+            let _ = body.set(compiled?.operations);
+        }
+
+        // Calls compiled while the body is still being compiled are recursive
+        // and must not keep the body alive themselves
+        let body = if body.get().is_some() {
+            FunctionBodyRef::Strong(body)
+        } else {
+            FunctionBodyRef::Weak(Arc::downgrade(&body))
+        };
+        let value_params = function
+            .params
+            .iter()
+            .zip(closures)
+            .filter_map(|(param, closure)| match param {
+                FunctionParam::Value(name) => Some((name.clone(), closure)),
+                FunctionParam::Filter(_) => None,
+            })
+            .collect();
+
+        Ok(CompiledFilter {
+            operations: vec![Box::new(UserFunctionCallOperation {
+                name: function.name.clone(),
+                body,
+                value_params,
+                captures,
+                max_depth: ctx.max_depth,
+            })],
+            variables: HashMap::new(),
+            functions: HashMap::new(),
+            requires_lazy: false,
+            complexity,
+        })
+    }
+}
+
+impl CompilationContext {
+    /// Find the innermost function or filter parameter named `name` taking
+    /// `arity` arguments
+    fn lookup(&self, name: &str, arity: usize) -> Option<ScopeEntry> {
+        lookup_in(&self.scope, name, arity)
+    }
+}
+
+/// Find the innermost function or filter parameter named `name` taking
+/// `arity` arguments in `scope`
+fn lookup_in(scope: &[ScopeEntry], name: &str, arity: usize) -> Option<ScopeEntry> {
+    scope
+        .iter()
+        .rev()
+        .find(|entry| match entry {
+            ScopeEntry::Function(function) => {
+                function.name == name && function.params.len() == arity
+            }
+            ScopeEntry::Param { name: param, .. } => param == name && arity == 0,
+            ScopeEntry::Label { .. } => false,
+        })
+        .cloned()
+}
+
+/// Collect the variables a filter argument may read: those named in `expr`
+/// and in the bodies of the user-defined functions it calls
+fn closure_variables(
+    expr: &Expr,
+    scope: &[ScopeEntry],
+    variables: &mut Vec<String>,
+    seen: &mut Vec<*const UserFunction>,
+) {
+    let mut walk = |expr: &Expr| closure_variables(expr, scope, variables, seen);
+    match expr {
+        Expr::Variable(name) => {
+            if !variables.contains(name) {
+                variables.push(name.clone());
+            }
+        }
+        Expr::Identifier(name) | Expr::FunctionCall { name, .. } => {
+            let args = path_call_args(expr);
+            for arg in args {
+                walk(arg);
+            }
+            if let Some(ScopeEntry::Function(function)) = lookup_in(scope, name, args.len()) {
+                if !seen.contains(&Arc::as_ptr(&function)) {
+                    seen.push(Arc::as_ptr(&function));
+                    closure_variables(&function.body, &function.scope, variables, seen);
+                }
+            }
+        }
+        Expr::Identity | Expr::Literal(_) | Expr::Format(_) | Expr::Break(_) => {}
+        Expr::FieldAccess { base: expr, .. }
+        | Expr::ArrayIteration(expr)
+        | Expr::UnaryOp { expr, .. }
+        | Expr::Paren(expr)
+        | Expr::Label { body: expr, .. } => walk(expr),
+        Expr::ArrayAccess { array, index } => {
+            walk(array);
+            walk(index);
+        }
+        Expr::ArraySlice { array, start, end } => {
+            walk(array);
+            for bound in [start, end].into_iter().flatten() {
+                walk(bound);
+            }
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            walk(left);
+            walk(right);
+        }
+        Expr::Assignment { target, value, .. } => {
+            walk(target);
+            walk(value);
+        }
+        Expr::Object { pairs } => {
+            for pair in pairs {
+                if let ObjectEntry::KeyValue { value, .. } = pair {
+                    walk(value);
+                }
+            }
+        }
+        Expr::Array(items) | Expr::Pipeline(items) | Expr::Sequence(items) => {
+            for item in items {
+                walk(item);
+            }
+        }
+        Expr::FormatString { parts, .. } => {
+            for part in parts {
+                if let StringPart::Interpolation(expr) = part {
+                    walk(expr);
+                }
+            }
+        }
+        Expr::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            walk(condition);
+            walk(then_branch);
+            walk(else_branch);
+        }
+        Expr::Try { body, catch } => {
+            walk(body);
+            if let Some(catch) = catch {
+                walk(catch);
+            }
+        }
+        Expr::Reduce {
+            source,
+            init,
+            update,
+            ..
+        } => {
+            walk(source);
+            walk(init);
+            walk(update);
+        }
+        Expr::Foreach {
+            source,
+            init,
+            update,
+            extract,
+            ..
+        } => {
+            walk(source);
+            walk(init);
+            walk(update);
+            if let Some(extract) = extract {
+                walk(extract);
+            }
+        }
+        Expr::Bind {
+            source,
+            patterns,
+            body,
+        } => {
+            walk(source);
+            let mut keys = Vec::new();
+            for pattern in patterns {
+                pattern_key_exprs(pattern, &mut keys);
+            }
+            for key in keys {
+                walk(key);
+            }
+            walk(body);
+        }
+        Expr::Def { body, rest, .. } => {
+            walk(body);
+            walk(rest);
+        }
+    }
+}
+
+/// Collect the computed keys of the object patterns in `pattern`
+fn pattern_key_exprs<'a>(pattern: &'a Pattern, keys: &mut Vec<&'a Expr>) {
+    match pattern {
+        Pattern::Variable(_) => {}
+        Pattern::Array(elements) => {
+            for element in elements {
+                pattern_key_exprs(element, keys);
+            }
+        }
+        Pattern::Object(entries) => {
+            for entry in entries {
+                if let PatternKey::Expr(key) = &entry.key {
+                    keys.push(key);
+                }
+                if let Some(pattern) = &entry.pattern {
+                    pattern_key_exprs(pattern, keys);
+                }
+            }
+        }
+    }
+}
+
+/// Entry of the lexical scope used to resolve user-defined functions
+#[derive(Clone)]
+enum ScopeEntry {
+    /// A `def` visible at this point
+    Function(Arc<UserFunction>),
+    /// A filter parameter of the enclosing function, bound to its argument
+    Param { name: String, closure: Arc<Closure> },
+    /// An enclosing `label $name`, identified for `break $name`
+    Label { name: String, id: usize },
+}
+
+impl std::fmt::Debug for ScopeEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScopeEntry::Function(function) => {
+                write!(f, "Function({}/{})", function.name, function.params.len())
+            }
+            ScopeEntry::Param { name, .. } => write!(f, "Param({name})"),
+            ScopeEntry::Label { name, id } => write!(f, "Label({name}#{id})"),
+        }
+    }
+}
+
+/// A user-defined function (`def name(params): body;`)
+struct UserFunction {
+    name: String,
+    params: Vec<FunctionParam>,
+    body: Expr,
+    /// Scope at the point of definition
+    scope: Vec<ScopeEntry>,
+    /// Compiled bodies, one per distinct set of filter argument closures
+    specializations: Mutex<Vec<(Vec<Arc<Closure>>, CompiledBody)>>,
+}
+
+type CompiledBody = Arc<OnceLock<Vec<Box<dyn Operation + Send + Sync>>>>;
+
+impl UserFunction {
+    /// Get the body compiled for `closures`, and whether it still has to be
+    /// compiled by the caller
+    fn specialization(&self, closures: &[Arc<Closure>]) -> (CompiledBody, bool) {
+        let mut specializations = self
+            .specializations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some((_, body)) = specializations.iter().find(|(key, _)| {
+            key.len() == closures.len() && key.iter().zip(closures).all(|(a, b)| Arc::ptr_eq(a, b))
+        }) {
+            return (body.clone(), false);
+        }
+        let body = CompiledBody::default();
+        specializations.push((closures.to_vec(), body.clone()));
+        (body, true)
+    }
+}
+
+/// Filter argument compiled in the caller's scope
+pub struct Closure {
+    ops: Vec<Box<dyn Operation + Send + Sync>>,
+    /// Caller variables the argument may read, bound to their values at the
+    /// call when it runs
+    variables: Vec<String>,
+    /// Variable holding an object of the caller's bindings of `variables`
+    /// while the call that passed the argument runs; filters cannot name it
+    frame: String,
+}
+
+impl Closure {
+    fn new(ops: Vec<Box<dyn Operation + Send + Sync>>, variables: Vec<String>) -> Self {
+        static NEXT_CLOSURE: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_CLOSURE.fetch_add(1, Ordering::Relaxed);
+        Self {
+            ops,
+            variables,
+            frame: format!("@closure{id}"),
+        }
+    }
+
+    /// The caller's bindings of the closure's variables, unbound ones left out
+    fn capture(&self, context: &Option<&mut dyn dsq_shared::ops::Context>) -> Value {
+        let mut captured = IndexMap::new();
+        if let Some(ctx) = context.as_deref() {
+            for (name, value) in self.variables.iter().zip(bindings(ctx, &self.variables)) {
+                if let Some(value) = value {
+                    captured.insert(name.clone(), value);
+                }
+            }
+        }
+        Value::Object(captured)
+    }
+}
+
+/// Operation evaluating a filter parameter against the current input
+pub struct ClosureOperation {
+    closure: Arc<Closure>,
+}
+
+impl Operation for ClosureOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    /// Runs the argument with the variables of the call that passed it, so
+    /// bindings made inside the function do not shadow them
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let captured = context
+            .as_deref()
+            .and_then(|ctx| ctx.get_variable(&self.closure.frame));
+        let Some(Value::Object(captured)) = captured else {
+            return generate_ops(&self.closure.ops, value, context, emit);
+        };
+        let variables = &self.closure.variables;
+        let captured: Vec<_> = variables
+            .iter()
+            .map(|name| captured.get(name).cloned())
+            .collect();
+        generate_in_scope(context, variables, emit, |context, emit| {
+            if let Some(ctx) = context.as_deref_mut() {
+                restore_bindings(ctx, variables, captured);
+            }
+            generate_ops(&self.closure.ops, value, context, emit)
+        })
+    }
+
+    fn description(&self) -> String {
+        "filter parameter".to_string()
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("closure", &self.closure.ops)]
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+enum FunctionBodyRef {
+    Strong(CompiledBody),
+    Weak(Weak<OnceLock<Vec<Box<dyn Operation + Send + Sync>>>>),
+}
+
+std::thread_local! {
+    /// Nesting depth of user-defined function calls on this thread
+    static CALL_DEPTH: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Decrements `CALL_DEPTH` when a call returns, including on error
+struct CallDepthGuard;
+
+impl Drop for CallDepthGuard {
+    fn drop(&mut self) {
+        CALL_DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+/// Operation calling a user-defined function
+pub struct UserFunctionCallOperation {
+    name: String,
+    body: FunctionBodyRef,
+    /// `$name` parameters and the closures producing their values
+    value_params: Vec<(String, Arc<Closure>)>,
+    /// Filter arguments compiled at this call, which see its variables
+    captures: Vec<Arc<Closure>>,
+    /// Maximum nesting of user function calls
+    max_depth: usize,
+}
+
+impl UserFunctionCallOperation {
+    fn run_body(
+        &self,
+        ops: &[Box<dyn Operation + Send + Sync>],
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        if self.value_params.is_empty() {
+            return generate_ops(ops, value, context, emit);
+        }
+
+        let names: Vec<String> = self.value_params.iter().map(|(n, _)| n.clone()).collect();
+        let mut args = Vec::with_capacity(self.value_params.len());
+        for (_, closure) in &self.value_params {
+            args.push(collapse(collect_ops(&closure.ops, value, context)?));
+        }
+        generate_in_scope(context, &names, emit, |context, emit| {
+            if let Some(ctx) = context.as_deref_mut() {
+                for (name, arg) in names.iter().zip(args) {
+                    ctx.set_variable(name, arg);
+                }
+            }
+            generate_ops(ops, value, context, emit)
+        })
+    }
+}
+
+impl Operation for UserFunctionCallOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        limits::check()?;
+        let depth = CALL_DEPTH.with(|depth| {
+            depth.set(depth.get() + 1);
+            depth.get()
+        });
+        let _guard = CallDepthGuard;
+        if depth > self.max_depth {
+            return Err(dsq_shared::error::operation_error(format!(
+                "Maximum recursion depth of {} exceeded in function '{}'",
+                self.max_depth, self.name
+            )));
+        }
+
+        let body = match &self.body {
+            FunctionBodyRef::Strong(body) => body.clone(),
+            FunctionBodyRef::Weak(body) => body.upgrade().ok_or_else(|| {
+                dsq_shared::error::operation_error(format!(
+                    "Function '{}' is no longer available",
+                    self.name
+                ))
+            })?,
+        };
+        let ops = body.get().map(Vec::as_slice).unwrap_or_default();
+        if self.captures.is_empty() {
+            return self.run_body(ops, value, context, emit);
+        }
+
+        // The caller's bindings of the filter arguments are in scope for the
+        // call, and out of it again while its outputs continue in the caller
+        let frames: Vec<String> = self.captures.iter().map(|c| c.frame.clone()).collect();
+        let captured: Vec<Value> = self.captures.iter().map(|c| c.capture(&*context)).collect();
+        generate_in_scope(context, &frames, emit, |context, emit| {
+            if let Some(ctx) = context.as_deref_mut() {
+                for (frame, captured) in frames.iter().zip(captured) {
+                    ctx.set_variable(frame, captured);
+                }
+            }
+            self.run_body(ops, value, context, emit)
+        })
+    }
+
+    fn description(&self) -> String {
+        format!("call {}", self.name)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Collect the names bound by a pattern, in order of appearance
+/// The arguments of a call appearing in a path expression
+fn path_call_args(expr: &Expr) -> &[Expr] {
+    match expr {
+        Expr::FunctionCall { args, .. } => args,
+        _ => &[],
+    }
+}
+
+fn invalid_path_expression(expr: &Expr) -> anyhow::Error {
+    dsq_shared::error::operation_error(format!("Invalid path expression: {}", expr))
+}
+
+/// Check that `format` names a known `@format`
+fn check_format(format: &str) -> Result<&str> {
+    if FORMATS.contains(&format) {
+        Ok(format)
+    } else {
+        Err(dsq_shared::error::operation_error(format!(
+            "{} is not a valid format",
+            format
+        )))
+    }
+}
+
+fn pattern_variables(pattern: &Pattern, names: &mut Vec<String>) {
+    fn add(names: &mut Vec<String>, name: &String) {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    match pattern {
+        Pattern::Variable(name) => add(names, name),
+        Pattern::Array(elements) => {
+            for element in elements {
+                pattern_variables(element, names);
+            }
+        }
+        Pattern::Object(entries) => {
+            for entry in entries {
+                if let PatternKey::Variable(name) = &entry.key {
+                    add(names, name);
+                }
+                if let Some(pattern) = &entry.pattern {
+                    pattern_variables(pattern, names);
+                }
+            }
+        }
+    }
+}
+
+/// Collect the fields read from `$variable` in `expr`.
+///
+/// Returns `false` when the variable is used as a whole (or in a way we can't
+/// see through), meaning every column of a DataFrame row has to be bound.
+fn variable_fields(expr: &Expr, variable: &str, fields: &mut Vec<String>) -> bool {
+    match expr {
+        Expr::Variable(name) => name != variable,
+        Expr::FieldAccess { base, fields: path } => match base.as_ref() {
+            Expr::Variable(name) if name == variable => {
+                if let Some(first) = path.first() {
+                    if !fields.contains(first) {
+                        fields.push(first.clone());
+                    }
+                }
+                true
+            }
+            _ => variable_fields(base, variable, fields),
+        },
+        Expr::ArrayAccess { array, index } => match (array.as_ref(), index.as_ref()) {
+            (Expr::Variable(name), Expr::Literal(Literal::String(key))) if name == variable => {
+                if !fields.contains(key) {
+                    fields.push(key.clone());
+                }
+                true
+            }
+            _ => {
+                variable_fields(array, variable, fields) && variable_fields(index, variable, fields)
+            }
+        },
+        Expr::ArraySlice { array, start, end } => {
+            variable_fields(array, variable, fields)
+                && start
+                    .as_deref()
+                    .is_none_or(|e| variable_fields(e, variable, fields))
+                && end
+                    .as_deref()
+                    .is_none_or(|e| variable_fields(e, variable, fields))
+        }
+        Expr::ArrayIteration(inner) | Expr::Paren(inner) | Expr::UnaryOp { expr: inner, .. } => {
+            variable_fields(inner, variable, fields)
+        }
+        Expr::FormatString { parts, .. } => parts.iter().all(|part| match part {
+            StringPart::Interpolation(expr) => variable_fields(expr, variable, fields),
+            StringPart::Literal(_) => true,
+        }),
+        Expr::Try { body, catch } => {
+            variable_fields(body, variable, fields)
+                && catch
+                    .as_deref()
+                    .is_none_or(|e| variable_fields(e, variable, fields))
+        }
+        Expr::FunctionCall { args, .. } => {
+            args.iter().all(|e| variable_fields(e, variable, fields))
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            variable_fields(left, variable, fields) && variable_fields(right, variable, fields)
+        }
+        Expr::Assignment { target, value, .. } => {
+            variable_fields(target, variable, fields) && variable_fields(value, variable, fields)
+        }
+        Expr::Object { pairs } => pairs.iter().all(|entry| match entry {
+            ObjectEntry::KeyValue { value, .. } => variable_fields(value, variable, fields),
+            ObjectEntry::Shorthand(_) => true,
+        }),
+        Expr::Array(items) | Expr::Pipeline(items) | Expr::Sequence(items) => {
+            items.iter().all(|e| variable_fields(e, variable, fields))
+        }
+        Expr::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            variable_fields(condition, variable, fields)
+                && variable_fields(then_branch, variable, fields)
+                && variable_fields(else_branch, variable, fields)
+        }
+        Expr::Reduce {
+            source,
+            init,
+            update,
+            ..
+        } => {
+            variable_fields(source, variable, fields)
+                && variable_fields(init, variable, fields)
+                && variable_fields(update, variable, fields)
+        }
+        Expr::Foreach {
+            source,
+            init,
+            update,
+            extract,
+            ..
+        } => {
+            variable_fields(source, variable, fields)
+                && variable_fields(init, variable, fields)
+                && variable_fields(update, variable, fields)
+                && extract
+                    .as_deref()
+                    .is_none_or(|e| variable_fields(e, variable, fields))
+        }
+        Expr::Bind {
+            source,
+            patterns,
+            body,
+        } => {
+            variable_fields(source, variable, fields)
+                && variable_fields(body, variable, fields)
+                && patterns.iter().all(|p| pattern_fields(p, variable, fields))
+        }
+        Expr::Def { body, rest, .. } => {
+            variable_fields(body, variable, fields) && variable_fields(rest, variable, fields)
+        }
+        Expr::Label { body, .. } => variable_fields(body, variable, fields),
+        Expr::Identity
+        | Expr::Literal(_)
+        | Expr::Identifier(_)
+        | Expr::Format(_)
+        | Expr::Break(_) => true,
+    }
+}
+
+/// `variable_fields` for the computed keys of a destructuring pattern
+fn pattern_fields(pattern: &Pattern, variable: &str, fields: &mut Vec<String>) -> bool {
+    match pattern {
+        Pattern::Variable(_) => true,
+        Pattern::Array(elements) => elements.iter().all(|p| pattern_fields(p, variable, fields)),
+        Pattern::Object(entries) => entries.iter().all(|entry| {
+            let key_ok = match &entry.key {
+                PatternKey::Expr(expr) => variable_fields(expr, variable, fields),
+                _ => true,
+            };
+            key_ok
+                && entry
+                    .pattern
+                    .as_ref()
+                    .is_none_or(|p| pattern_fields(p, variable, fields))
+        }),
+    }
+}
+
+/// Detect `. + $variable.field` (or `$variable.field + .`), which over a
+/// DataFrame is just the column sum added to the initial value.
+fn column_sum_field(update: &Expr, variable: &str) -> Option<String> {
+    let Expr::BinaryOp {
+        left,
+        op: BinaryOperator::Add,
+        right,
+    } = update
+    else {
+        return None;
+    };
+    let field = match (left.as_ref(), right.as_ref()) {
+        (Expr::Identity, other) | (other, Expr::Identity) => other,
+        _ => return None,
+    };
+    match field {
+        Expr::FieldAccess { base, fields }
+            if fields.len() == 1
+                && matches!(base.as_ref(), Expr::Variable(name) if name == variable) =>
+        {
+            Some(fields[0].clone())
+        }
+        _ => None,
+    }
+}
+
+/// Apply a chain of operations to a value within a context
+fn apply_ops_in(
+    ops: &[Box<dyn Operation + Send + Sync>],
+    value: &Value,
+    ctx: &mut dyn dsq_shared::ops::Context,
+) -> Result<Value> {
+    let mut current = value.clone();
+    for op in ops {
+        current = op.apply_with_context(&current, &mut Some(&mut *ctx))?;
+    }
+    Ok(current)
+}
+
+/// Feed every value produced by a reduce/foreach/binding source to `step`,
+/// until it asks to stop.
+///
+/// DataFrames are walked row by row, binding only `columns` (all columns
+/// when `None`) instead of converting the whole frame to objects up front.
+fn for_each_fold_item(
+    source: Value,
+    iterate: bool,
+    columns: Option<&[String]>,
+    mut step: impl FnMut(Value) -> Result<ControlFlow<()>>,
+) -> Result<ControlFlow<()>> {
+    if !iterate {
+        return step(source);
+    }
+    match source {
+        Value::Array(items) => {
+            for item in items {
+                limits::check()?;
+                if step(item)?.is_break() {
+                    return Ok(STOP);
+                }
+            }
+            Ok(CONTINUE)
+        }
+        Value::Object(obj) => {
+            for item in obj.into_values() {
+                limits::check()?;
+                if step(item)?.is_break() {
+                    return Ok(STOP);
+                }
+            }
+            Ok(CONTINUE)
+        }
+        Value::LazyFrame(lf) => {
+            let df = lf.collect().map_err(|e| {
+                dsq_shared::error::operation_error(format!("Failed to collect: {e}"))
+            })?;
+            for_each_fold_item(Value::DataFrame(df), iterate, columns, step)
+        }
+        Value::DataFrame(df) => {
+            let selected: Vec<&Column> = match columns {
+                Some(names) => df
+                    .get_columns()
+                    .iter()
+                    .filter(|c| names.iter().any(|n| n.as_str() == c.name().as_str()))
+                    .collect(),
+                None => df.get_columns().iter().collect(),
+            };
+            for i in 0..df.height() {
+                let mut row = IndexMap::with_capacity(selected.len());
+                for column in &selected {
+                    let value = value_from_any_value(column.get(i).map_err(|e| {
+                        dsq_shared::error::operation_error(format!("Failed to read row {i}: {e}"))
+                    })?)?;
+                    row.insert(column.name().to_string(), value);
+                }
+                limits::check()?;
+                if step(Value::Object(row))?.is_break() {
+                    return Ok(STOP);
+                }
+            }
+            Ok(CONTINUE)
+        }
+        Value::Series(series) => {
+            for i in 0..series.len() {
+                let value = value_from_any_value(series.get(i).map_err(|e| {
+                    dsq_shared::error::operation_error(format!("Failed to read row {i}: {e}"))
+                })?)?;
+                limits::check()?;
+                if step(value)?.is_break() {
+                    return Ok(STOP);
+                }
+            }
+            Ok(CONTINUE)
+        }
+        other => Err(dsq_shared::error::operation_error(format!(
+            "Cannot iterate over {}",
+            other.type_name()
+        ))),
+    }
+}
+
+/// The current values of `variables`
+fn bindings(ctx: &dyn dsq_shared::ops::Context, variables: &[String]) -> Vec<Option<Value>> {
+    variables
+        .iter()
+        .map(|name| ctx.get_variable(name).cloned())
+        .collect()
+}
+
+/// Bind `variables` to `values`, removing those without a value
+fn restore_bindings(
+    ctx: &mut dyn dsq_shared::ops::Context,
+    variables: &[String],
+    values: Vec<Option<Value>>,
+) {
+    for (name, value) in variables.iter().zip(values) {
+        match value {
+            Some(value) => ctx.set_variable(name, value),
+            None => ctx.remove_variable(name),
+        }
+    }
+}
+
+/// Run `body` with `variables` in scope, restoring any outer bindings
+/// afterwards. Falls back to a scratch context when none was supplied.
+fn with_variable_scope<T>(
+    context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    variables: &[String],
+    body: impl FnOnce(&mut dyn dsq_shared::ops::Context) -> Result<T>,
+) -> Result<T> {
+    let mut scratch;
+    let ctx: &mut dyn dsq_shared::ops::Context = match context {
+        Some(ctx) => &mut **ctx,
+        None => {
+            scratch = FilterContext::new();
+            &mut scratch
+        }
+    };
+    let outer = bindings(ctx, variables);
+    let result = body(&mut *ctx);
+    restore_bindings(ctx, variables, outer);
+    result
+}
+
+/// Run the generator `body` with `variables` in scope
+///
+/// The outer bindings are restored around each call to `emit`, so the
+/// variables do not leak into the rest of the pipeline.
+fn generate_in_scope(
+    context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    variables: &[String],
+    emit: &mut Emit<'_>,
+    body: impl FnOnce(
+        &mut Option<&mut dyn dsq_shared::ops::Context>,
+        &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>>,
+) -> Result<ControlFlow<()>> {
+    let outer = match context.as_deref() {
+        Some(ctx) => bindings(ctx, variables),
+        None => vec![None; variables.len()],
+    };
+    with_variable_scope(context, variables, |ctx| {
+        body(&mut Some(ctx), &mut |output, context| {
+            let inner = context.as_deref_mut().map(|ctx| {
+                let inner = bindings(ctx, variables);
+                restore_bindings(ctx, variables, outer.clone());
+                inner
+            });
+            let flow = emit(output, context);
+            if let (Some(ctx), Some(inner)) = (context.as_deref_mut(), inner) {
+                restore_bindings(ctx, variables, inner);
+            }
+            flow
+        })
+    })
+}
+
+/// Applies a chain of operations as a single operation
+pub struct ChainOperation {
+    pub ops: Vec<Box<dyn Operation + Send + Sync>>,
+}
+
+impl Operation for ChainOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        collect_ops(&self.ops, value, context).map(collapse)
+    }
+
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_ops(&self.ops, value, context, emit)
+    }
+
+    fn description(&self) -> String {
+        let steps: Vec<String> = self.ops.iter().map(|op| op.description()).collect();
+        steps.join(" | ")
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("pipeline", &self.ops)]
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Operation for `reduce SOURCE as $var (INIT; UPDATE)`
+pub struct ReduceOperation {
+    pub source_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    pub iterate_source: bool,
+    pub variable: String,
+    pub init_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    pub update_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    /// Row columns referenced by UPDATE; `None` binds whole rows
+    pub row_columns: Option<Vec<String>>,
+    /// Column summed by an `. + $var.field` update
+    pub column_sum: Option<String>,
+}
+
+impl ReduceOperation {
+    /// Vectorized `reduce .[] as $row (n; . + $row.col)` over a DataFrame
+    fn try_column_sum(&self, source: &Value, init: &Value) -> Option<Value> {
+        let (Value::DataFrame(df), Some(field)) = (source, &self.column_sum) else {
+            return None;
+        };
+        let series = df.column(field).ok()?.as_materialized_series();
+        match (init, series.dtype()) {
+            // A sum that overflows i64, or a UInt64 column beyond it, is
+            // summed as floats instead
+            (Value::Int(n), dtype) if dtype.is_integer() => {
+                let sum = series.strict_cast(&DataType::Int64).ok().and_then(|ints| {
+                    ints.i64()
+                        .ok()?
+                        .into_iter()
+                        .flatten()
+                        .try_fold(*n, i64::checked_add)
+                });
+                Some(match sum {
+                    Some(sum) => Value::Int(sum),
+                    None => Value::Float(*n as f64 + series.sum::<f64>().ok()?),
+                })
+            }
+            (Value::Int(_) | Value::Float(_), dtype) if dtype.is_primitive_numeric() => {
+                let start = match init {
+                    Value::Int(n) => *n as f64,
+                    Value::Float(f) => *f,
+                    _ => return None,
+                };
+                Some(Value::Float(start + series.sum::<f64>().ok()?))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Operation for ReduceOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        with_variable_scope(context, std::slice::from_ref(&self.variable), |ctx| {
+            let sources = collect_ops(&self.source_ops, value, &mut Some(&mut *ctx))?;
+            let mut acc = apply_ops_in(&self.init_ops, value, ctx)?;
+            if let [source] = sources.as_slice() {
+                if self.iterate_source {
+                    if let Some(result) = self.try_column_sum(source, &acc) {
+                        return Ok(result);
+                    }
+                }
+            }
+            for source in sources {
+                let _ = for_each_fold_item(
+                    source,
+                    self.iterate_source,
+                    self.row_columns.as_deref(),
+                    |item| {
+                        ctx.set_variable(&self.variable, item);
+                        // An update producing several states keeps the last
+                        acc = collect_ops(&self.update_ops, &acc, &mut Some(&mut *ctx))?
+                            .pop()
+                            .unwrap_or(Value::Null);
+                        Ok(CONTINUE)
+                    },
+                )?;
+            }
+            Ok(acc)
+        })
+    }
+
+    fn description(&self) -> String {
+        format!("reduce as ${}", self.variable)
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![
+            ("source", &self.source_ops),
+            ("init", &self.init_ops),
+            ("update", &self.update_ops),
+        ]
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Operation for `foreach SOURCE as $var (INIT; UPDATE; EXTRACT)`
+///
+/// Produces every intermediate state, passed through EXTRACT when given.
+pub struct ForeachOperation {
+    pub source_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    pub iterate_source: bool,
+    pub variable: String,
+    pub init_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    pub update_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    pub extract_ops: Option<Vec<Box<dyn Operation + Send + Sync>>>,
+    /// Row columns referenced by UPDATE/EXTRACT; `None` binds whole rows
+    pub row_columns: Option<Vec<String>>,
+}
+
+impl Operation for ForeachOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let variables = std::slice::from_ref(&self.variable);
+        generate_in_scope(context, variables, emit, |context, emit| {
+            let mut state = collapse(collect_ops(&self.init_ops, value, context)?);
+            generate_ops(&self.source_ops, value, context, &mut |source, context| {
+                for_each_fold_item(
+                    source,
+                    self.iterate_source,
+                    self.row_columns.as_deref(),
+                    |item| {
+                        if let Some(ctx) = context.as_deref_mut() {
+                            ctx.set_variable(&self.variable, item);
+                        }
+                        for update in collect_ops(&self.update_ops, &state, context)? {
+                            state = update;
+                            let flow = match &self.extract_ops {
+                                Some(ops) => generate_ops(ops, &state, context, emit)?,
+                                None => emit(state.clone(), context)?,
+                            };
+                            if flow.is_break() {
+                                return Ok(STOP);
+                            }
+                        }
+                        Ok(CONTINUE)
+                    },
+                )
+            })
+        })
+    }
+
+    fn description(&self) -> String {
+        format!("foreach as ${}", self.variable)
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        let mut branches: Vec<Branch<'_>> = vec![
+            ("source", &self.source_ops),
+            ("init", &self.init_ops),
+            ("update", &self.update_ops),
+        ];
+        if let Some(extract_ops) = &self.extract_ops {
+            branches.push(("extract", extract_ops));
+        }
+        branches
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Compiled form of a destructuring pattern
+pub enum CompiledPattern {
+    Variable(String),
+    Array(Vec<CompiledPattern>),
+    Object(Vec<(CompiledPatternKey, Option<CompiledPattern>)>),
+}
+
+/// Compiled key of an object destructuring entry
+pub enum CompiledPatternKey {
+    Variable(String),
+    Literal(String),
+    Expr(Vec<Box<dyn Operation + Send + Sync>>),
+}
+
+impl CompiledPattern {
+    /// Bind the variables of this pattern against `value`. Computed keys are
+    /// evaluated against `input`, the input of the whole binding.
+    fn destructure(
+        &self,
+        value: &Value,
+        input: &Value,
+        ctx: &mut dyn dsq_shared::ops::Context,
+    ) -> Result<()> {
+        match self {
+            CompiledPattern::Variable(name) => {
+                ctx.set_variable(name, value.clone());
+                Ok(())
+            }
+            CompiledPattern::Array(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    let item = match value {
+                        Value::Array(items) => items.get(i).cloned().unwrap_or(Value::Null),
+                        Value::Null => Value::Null,
+                        other => {
+                            return Err(dsq_shared::error::operation_error(format!(
+                                "Cannot index {} with number",
+                                other.type_name()
+                            )))
+                        }
+                    };
+                    element.destructure(&item, input, ctx)?;
+                }
+                Ok(())
+            }
+            CompiledPattern::Object(entries) => {
+                for (key, pattern) in entries {
+                    let name = match key {
+                        CompiledPatternKey::Variable(name) | CompiledPatternKey::Literal(name) => {
+                            name.clone()
+                        }
+                        CompiledPatternKey::Expr(ops) => match apply_ops_in(ops, input, ctx)? {
+                            Value::String(name) => name,
+                            other => {
+                                return Err(dsq_shared::error::operation_error(format!(
+                                    "Cannot index object with {}",
+                                    other.type_name()
+                                )))
+                            }
+                        },
+                    };
+                    let field = match value {
+                        Value::Object(obj) => obj.get(&name).cloned().unwrap_or(Value::Null),
+                        Value::Null => Value::Null,
+                        other => {
+                            return Err(dsq_shared::error::operation_error(format!(
+                                "Cannot index {} with \"{}\"",
+                                other.type_name(),
+                                name
+                            )))
+                        }
+                    };
+                    if let Some(pattern) = pattern {
+                        pattern.destructure(&field, input, ctx)?;
+                    }
+                    if let CompiledPatternKey::Variable(variable) = key {
+                        ctx.set_variable(variable, field);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Operation for `SOURCE as PATTERN ?// PATTERN ... | BODY`
+///
+/// The body runs with the binding's own input, once per value of the source.
+pub struct BindOperation {
+    pub source_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    pub iterate_source: bool,
+    pub patterns: Vec<CompiledPattern>,
+    /// Every variable bound by any of the patterns
+    pub variables: Vec<String>,
+    pub body_ops: Vec<Box<dyn Operation + Send + Sync>>,
+}
+
+impl BindOperation {
+    /// Bind one source value and run the body
+    ///
+    /// With alternative patterns (`?//`), the body's outputs are only
+    /// emitted once a pattern binds and the body completes without error;
+    /// otherwise the next pattern is tried.
+    fn bind_value(
+        &self,
+        item: &Value,
+        input: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let Some(ctx) = context.as_deref_mut() else {
+            return Err(dsq_shared::error::operation_error(
+                "Binding needs a context",
+            ));
+        };
+        if let [pattern] = self.patterns.as_slice() {
+            pattern.destructure(item, input, ctx)?;
+            return generate_ops(&self.body_ops, input, context, emit);
+        }
+        let mut last_error = None;
+        for pattern in &self.patterns {
+            // Variables not mentioned by the matching alternative are null
+            for name in &self.variables {
+                ctx.set_variable(name, Value::Null);
+            }
+            match pattern
+                .destructure(item, input, ctx)
+                .and_then(|()| collect_ops(&self.body_ops, input, &mut Some(&mut *ctx)))
+            {
+                Ok(outputs) => {
+                    for output in outputs {
+                        if emit(output, &mut Some(&mut *ctx))?.is_break() {
+                            return Ok(STOP);
+                        }
+                    }
+                    return Ok(CONTINUE);
+                }
+                Err(e) if escapes_try(&e) => return Err(e),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| dsq_shared::error::operation_error("Binding has no patterns")))
+    }
+}
+
+impl Operation for BindOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_in_scope(context, &self.variables, emit, |context, emit| {
+            generate_ops(&self.source_ops, value, context, &mut |source, context| {
+                for_each_fold_item(source, self.iterate_source, None, |item| {
+                    self.bind_value(&item, value, context, emit)
+                })
+            })
+        })
+    }
+
+    fn description(&self) -> String {
+        format!("bind {}", self.variables.join(", "))
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("source", &self.source_ops), ("body", &self.body_ops)]
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Operation for `label $name | BODY`
+///
+/// Produces the outputs of the body until a `break` aimed at this label,
+/// which ends the stream without an error.
+pub struct LabelOperation {
+    pub label: usize,
+    pub body_ops: Vec<Box<dyn Operation + Send + Sync>>,
+}
+
+impl Operation for LabelOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
 
     fn apply_with_context(
         &self,
         value: &Value,
         context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        match generate_ops(&self.body_ops, value, context, emit) {
+            Err(e) => match e.downcast::<Break>() {
+                Ok(brk) if brk.label == self.label => Ok(CONTINUE),
+                Ok(brk) => Err(brk.into()),
+                Err(e) => Err(e),
+            },
+            flow => flow,
+        }
+    }
+
+    fn description(&self) -> String {
+        "label".to_string()
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("body", &self.body_ops)]
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Operation for `break $name`: unwinds to the label with id `label`
+pub struct BreakOperation {
+    pub label: usize,
+}
+
+impl Operation for BreakOperation {
+    fn apply(&self, _value: &Value) -> Result<Value> {
+        Err(Break { label: self.label }.into())
+    }
+
+    fn description(&self) -> String {
+        "break".to_string()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Maximum nesting followed by `recurse(f)` before giving up
+const MAX_RECURSE_DEPTH: usize = 1000;
+
+/// The count argument of `limit` and `nth`, which must not be negative
+fn count_arg(name: &str, value: &Value) -> Result<usize> {
+    match value {
+        Value::Int(n) if *n >= 0 => Ok(*n as usize),
+        Value::Float(f) if *f >= 0.0 => Ok(f.ceil() as usize),
+        Value::Int(_) | Value::Float(_) => Err(dsq_shared::error::operation_error(format!(
+            "{}() count must not be negative",
+            name
+        ))),
+        other => Err(dsq_shared::error::operation_error(format!(
+            "{}() count must be a number, not {}",
+            name,
+            other.type_name()
+        ))),
+    }
+}
+
+/// The next document of the input cursor of `context`
+fn next_input(context: &mut Option<&mut dyn dsq_shared::ops::Context>) -> Option<Result<Value>> {
+    context
+        .as_mut()?
+        .as_any_mut()
+        .downcast_mut::<FilterContext>()?
+        .next_input()
+}
+
+struct FunctionCallOperation {
+    name: String,
+    arg_ops: Vec<Vec<Box<dyn Operation + Send + Sync>>>,
+    builtins: Arc<BuiltinRegistry>,
+    /// The argument of `map`, `select` or `filter`, lowered to run on
+    /// DataFrames as a Polars expression
+    row_filter: Option<RowExpr>,
+}
+
+impl FunctionCallOperation {
+    fn new(
+        name: String,
+        arg_ops: Vec<Vec<Box<dyn Operation + Send + Sync>>>,
+        builtins: Arc<BuiltinRegistry>,
+    ) -> Self {
+        Self {
+            name,
+            arg_ops,
+            builtins,
+            row_filter: None,
+        }
+    }
+}
+
+impl FunctionCallOperation {
+    /// The Polars query of a lowered `map`, `select` or `filter` on `frame`,
+    /// or `None` when it has to run row by row
+    fn query(&self, frame: &LazyFrame) -> Option<Mapped> {
+        let row_filter = self.row_filter.as_ref()?;
+        match self.name.as_str() {
+            "map" => row_filter.map(frame),
+            _ => row_filter.select(frame).map(Mapped::Frame),
+        }
+    }
+
+    /// Run a lowered `map`, `select` or `filter` on a frame as one Polars
+    /// query, or `None` to run it row by row instead
+    fn vectorized(&self, value: &Value) -> Option<Value> {
+        let (frame, lazy) = match value {
+            Value::DataFrame(df) => (df.clone().lazy(), false),
+            Value::LazyFrame(lf) => (lf.as_ref().clone(), true),
+            _ => return None,
+        };
+        if self.row_filter.as_ref()?.runs_row_by_row(&frame) {
+            return None;
+        }
+        let frame = match self.query(&frame)? {
+            Mapped::Frame(frame) => frame,
+            // `map` gives an array of its outputs, as it does row by row
+            Mapped::Objects(frame) => {
+                let df = frame.collect().ok()?;
+                return (0..df.height())
+                    .map(|i| frame_row(&df, i))
+                    .collect::<Result<Vec<_>>>()
+                    .ok()
+                    .map(Value::Array);
+            }
+            Mapped::Values(frame) => {
+                let df = frame.collect().ok()?;
+                let values = df.get_columns().first()?.as_materialized_series().iter();
+                return values
+                    .map(value_from_any_value)
+                    .collect::<Result<Vec<_>>>()
+                    .ok()
+                    .map(Value::Array);
+            }
+        };
+        if lazy {
+            Some(Value::LazyFrame(Box::new(frame)))
+        } else {
+            frame.collect().ok().map(Value::DataFrame)
+        }
+    }
+
+    /// Run a `map`, `select` or `filter` row by row on the collected rows of
+    /// `lf`, keeping a frame result lazy like the lowered query does
+    fn call_collected(
+        &self,
+        lf: &LazyFrame,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        let df = lf.clone().collect().map_err(|e| {
+            dsq_shared::error::operation_error(format!("Failed to collect LazyFrame: {}", e))
+        })?;
+        Ok(match self.call(&Value::DataFrame(df), context)? {
+            Value::DataFrame(df) => Value::LazyFrame(Box::new(df.lazy())),
+            other => other,
+        })
+    }
+}
+
+/// Row `i` of `df` as an object
+fn frame_row(df: &DataFrame, i: usize) -> Result<Value> {
+    let mut row = IndexMap::new();
+    for column in df.get_columns() {
+        let value = column.get(i).map_err(|e| {
+            dsq_shared::error::operation_error(format!("Failed to get value: {}", e))
+        })?;
+        row.insert(
+            column.name().to_string(),
+            value_from_any_value(value).unwrap_or(Value::Null),
+        );
+    }
+    Ok(Value::Object(row))
+}
+
+/// The Polars query `op` runs on `frame`, when it is a `map`, `select` or
+/// `filter` lowered to Polars expressions
+pub(crate) fn lowered_query(op: &dyn Operation, frame: &LazyFrame) -> Option<Mapped> {
+    op.as_any()
+        .downcast_ref::<FunctionCallOperation>()?
+        .query(frame)
+}
+
+impl FunctionCallOperation {
+    /// Evaluate argument `index` against `value`
+    fn eval_arg(
+        &self,
+        index: usize,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        let mut result = value.clone();
+        for op in &self.arg_ops[index] {
+            result = op.apply_with_context(&result, context)?;
+        }
+        Ok(result)
+    }
+
+    /// `recurse(f)` and `recurse(f; cond)`: emit `value`, then recurse into
+    /// every output of `f` that satisfies `cond` (is not null for `recurse(f)`)
+    fn generate_recurse(
+        &self,
+        value: &Value,
+        depth: usize,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        if depth > MAX_RECURSE_DEPTH {
+            return Err(dsq_shared::error::operation_error(format!(
+                "recurse() exceeded maximum depth of {}",
+                MAX_RECURSE_DEPTH
+            )));
+        }
+        limits::check()?;
+        if emit(value.clone(), context)?.is_break() {
+            return Ok(STOP);
+        }
+        generate_ops(&self.arg_ops[0], value, context, &mut |item, context| {
+            let descend = match self.arg_ops.get(1) {
+                Some(cond) => is_truthy(&collapse(collect_ops(cond, &item, context)?)),
+                None => !matches!(item, Value::Null),
+            };
+            if !descend {
+                return Ok(CONTINUE);
+            }
+            self.generate_recurse(&item, depth + 1, context, emit)
+        })
+    }
+
+    /// `limit(n; f)`: the first `n` outputs of `f`, for each output `n`
+    fn generate_limit(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_ops(&self.arg_ops[0], value, context, &mut |n, context| {
+            let mut remaining = count_arg("limit", &n)?;
+            if remaining == 0 {
+                return Ok(CONTINUE);
+            }
+            // Reaching the limit ends `f` only; a stop from downstream ends
+            // the whole stream
+            let mut downstream = CONTINUE;
+            let _ = generate_ops(&self.arg_ops[1], value, context, &mut |item, context| {
+                remaining -= 1;
+                downstream = emit(item, context)?;
+                Ok(if remaining == 0 { STOP } else { downstream })
+            })?;
+            Ok(downstream)
+        })
+    }
+
+    /// `nth(n; f)`: output `n` of `f`, counting from 0, for each output `n`
+    fn generate_nth(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_ops(&self.arg_ops[0], value, context, &mut |n, context| {
+            let mut skip = count_arg("nth", &n)?;
+            let mut downstream = CONTINUE;
+            let _ = generate_ops(&self.arg_ops[1], value, context, &mut |item, context| {
+                if skip > 0 {
+                    skip -= 1;
+                    return Ok(CONTINUE);
+                }
+                downstream = emit(item, context)?;
+                Ok(STOP)
+            })?;
+            Ok(downstream)
+        })
+    }
+
+    /// `repeat(f)`, `while(cond; update)` and `until(cond; update)`
+    ///
+    /// The recursion of their jq definitions is unrolled onto an explicit
+    /// stack, so long iterations do not grow the native stack; outputs come
+    /// in the same order as the recursive definitions produce them.
+    fn generate_iterate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        enum Step {
+            Emit(Value),
+            Test(Value),
+            Update(Value),
+        }
+        let update = self.arg_ops.last().expect("iteration has an update");
+        let mut pending = vec![Step::Test(value.clone())];
+        while let Some(step) = pending.pop() {
+            limits::check()?;
+            let mut next = Vec::new();
+            match step {
+                Step::Emit(item) => {
+                    if emit(item, context)?.is_break() {
+                        return Ok(STOP);
+                    }
+                }
+                Step::Update(item) => {
+                    for output in collect_ops(update, &item, context)? {
+                        next.push(Step::Test(output));
+                    }
+                }
+                Step::Test(item) if self.name == "repeat" => {
+                    next.push(Step::Emit(item.clone()));
+                    next.push(Step::Update(item));
+                }
+                Step::Test(item) => {
+                    for condition in collect_ops(&self.arg_ops[0], &item, context)? {
+                        match (self.name.as_str(), is_truthy(&condition)) {
+                            ("while", true) => {
+                                next.push(Step::Emit(item.clone()));
+                                next.push(Step::Update(item.clone()));
+                            }
+                            ("until", true) => next.push(Step::Emit(item.clone())),
+                            ("until", false) => next.push(Step::Update(item.clone())),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            pending.extend(next.into_iter().rev());
+        }
+        Ok(CONTINUE)
+    }
+
+    /// `range(upto)`, `range(from; upto)` and `range(from; upto; by)`, for
+    /// every combination of the outputs of the arguments
+    fn generate_range(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let mut combinations = vec![Vec::new()];
+        for ops in &self.arg_ops {
+            let outputs = collect_ops(ops, value, context)?;
+            combinations = combinations
+                .into_iter()
+                .flat_map(|args: Vec<Value>| {
+                    outputs.iter().map(move |output| {
+                        let mut args = args.clone();
+                        args.push(output.clone());
+                        args
+                    })
+                })
+                .collect();
+        }
+        for args in combinations {
+            let (start, end, step) = range_bounds(&args)?;
+            if emit_all(range_values(&start, &end, &step)?, context, emit)?.is_break() {
+                return Ok(STOP);
+            }
+        }
+        Ok(CONTINUE)
+    }
+
+    /// `walk(f)`: rewrite children first, then apply `f` to the result
+    ///
+    /// DataFrames are walked as arrays of row objects and converted back to a
+    /// DataFrame when the rewritten rows still form a table.
+    fn walk_value(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        limits::check()?;
+        let rebuilt = match value {
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.walk_value(item, context))
+                    .collect::<Result<_>>()?,
+            ),
+            Value::Object(obj) => Value::Object(
+                obj.iter()
+                    .map(|(key, item)| Ok((key.clone(), self.walk_value(item, context)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            Value::DataFrame(_) | Value::LazyFrame(_) => {
+                let rows = child_entries(value)?
+                    .into_iter()
+                    .map(|(_, row)| row)
+                    .collect();
+                return match self.walk_value(&Value::Array(rows), context)? {
+                    Value::Array(rows) => {
+                        let rows = Value::Array(rows);
+                        Ok(rows.to_dataframe().map(Value::DataFrame).unwrap_or(rows))
+                    }
+                    other => Ok(other),
+                };
+            }
+            _ => value.clone(),
+        };
+        self.eval_arg(0, &rebuilt, context)
+    }
+}
+
+impl FunctionCallOperation {
+    /// Call the function once, producing a single value
+    fn call(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
     ) -> Result<Value> {
         match self.name.as_str() {
             "add" | "head" | "tail" | "limit" | "url_set_protocol" | "replace"
@@ -1525,6 +4085,22 @@ impl Operation for FunctionCallOperation {
                         Value::String(s) => Ok(Value::Int(s.chars().count() as i64)),
                         Value::Object(obj) => Ok(Value::Int(obj.len() as i64)),
                         Value::DataFrame(df) => Ok(Value::Int(df.height() as i64)),
+                        // Counting the rows of a scan reads none of its columns
+                        Value::LazyFrame(lf) => lf
+                            .as_ref()
+                            .clone()
+                            .select([len()])
+                            .collect()
+                            .and_then(|counted| counted.get_columns()[0].cast(&DataType::Int64))
+                            .map(|count| {
+                                Value::Int(count.i64().ok().and_then(|c| c.get(0)).unwrap_or(0))
+                            })
+                            .map_err(|e| {
+                                dsq_shared::error::operation_error(format!(
+                                    "Failed to count LazyFrame rows: {}",
+                                    e
+                                ))
+                            }),
                         Value::Series(s) => Ok(Value::Int(s.len() as i64)),
                         Value::Null => Ok(Value::Int(0)),
                         _ => Ok(Value::Int(1)),
@@ -1552,8 +4128,11 @@ impl Operation for FunctionCallOperation {
 
             "keys" => match value {
                 Value::Object(obj) => {
-                    let keys: Vec<Value> = obj.keys().map(|k| Value::String(k.clone())).collect();
-                    Ok(Value::Array(keys))
+                    let mut keys: Vec<&String> = obj.keys().collect();
+                    keys.sort();
+                    Ok(Value::Array(
+                        keys.into_iter().map(|k| Value::String(k.clone())).collect(),
+                    ))
                 }
                 _ => Err(dsq_shared::error::operation_error(
                     "keys() requires an object",
@@ -1577,8 +4156,9 @@ impl Operation for FunctionCallOperation {
 
                 match value {
                     Value::Array(arr) => {
-                        let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+                        let mut groups: IndexMap<String, Vec<Value>> = IndexMap::new();
                         for item in arr {
+                            limits::check()?;
                             let mut key_value = item.clone();
                             for op in &self.arg_ops[0] {
                                 key_value = op.apply_with_context(&key_value, context)?;
@@ -1598,10 +4178,11 @@ impl Operation for FunctionCallOperation {
                     Value::DataFrame(df) => {
                         // For DataFrame, we need to extract the grouping key from each row
                         // This is more complex, but for now let's assume the argument is a field access
-                        let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+                        let mut groups: IndexMap<String, Vec<usize>> = IndexMap::new();
                         for i in 0..df.height() {
+                            limits::check()?;
                             // Convert row to object-like value for applying the grouping expression
-                            let mut row_obj = HashMap::new();
+                            let mut row_obj = IndexMap::new();
                             for col_name in df.get_column_names() {
                                 if let Ok(s) = df.column(col_name) {
                                     if let Ok(val) = s.get(i) {
@@ -1629,7 +4210,8 @@ impl Operation for FunctionCallOperation {
                         for (_key, indices) in groups {
                             let mut group = Vec::new();
                             for &i in &indices {
-                                let mut row_obj = HashMap::new();
+                                limits::check()?;
+                                let mut row_obj = IndexMap::new();
                                 for col_name in df.get_column_names() {
                                     if let Ok(s) = df.column(col_name) {
                                         if let Ok(val) = s.get(i) {
@@ -1671,6 +4253,7 @@ impl Operation for FunctionCallOperation {
                 match value {
                     Value::Array(arr) => {
                         for item in arr {
+                            limits::check()?;
                             let mut key_value = item.clone();
                             for op in &self.arg_ops[0] {
                                 key_value = op.apply_with_context(&key_value, context)?;
@@ -1683,7 +4266,8 @@ impl Operation for FunctionCallOperation {
                     Value::DataFrame(df) => {
                         // For DataFrame, evaluate the sort key for each row
                         for i in 0..df.height() {
-                            let mut row_obj = HashMap::new();
+                            limits::check()?;
+                            let mut row_obj = IndexMap::new();
                             for col_name in df.get_column_names() {
                                 if let Ok(s) = df.column(col_name) {
                                     if let Ok(val) = s.get(i) {
@@ -1732,7 +4316,7 @@ impl Operation for FunctionCallOperation {
                     Value::DataFrame(df) => {
                         // For DataFrame, evaluate the key for each row
                         for i in 0..df.height() {
-                            let mut row_obj = HashMap::new();
+                            let mut row_obj = IndexMap::new();
                             for col_name in df.get_column_names() {
                                 if let Ok(s) = df.column(col_name) {
                                     if let Ok(val) = s.get(i) {
@@ -1781,7 +4365,7 @@ impl Operation for FunctionCallOperation {
                     Value::DataFrame(df) => {
                         // For DataFrame, evaluate the key for each row
                         for i in 0..df.height() {
-                            let mut row_obj = HashMap::new();
+                            let mut row_obj = IndexMap::new();
                             for col_name in df.get_column_names() {
                                 if let Ok(s) = df.column(col_name) {
                                     if let Ok(val) = s.get(i) {
@@ -1812,24 +4396,16 @@ impl Operation for FunctionCallOperation {
                         "map() expects 1 argument",
                     ));
                 }
+                if let Some(result) = self.vectorized(value) {
+                    return Ok(result);
+                }
 
                 match value {
                     Value::Array(arr) => {
                         let mut result = Vec::new();
-                        // Check if this is an identity operation
-                        let is_identity = self.arg_ops[0].len() == 1
-                            && self.arg_ops[0][0]
-                                .as_any()
-                                .is::<dsq_shared::ops::IdentityOperation>();
                         for item in arr {
-                            let mut transformed = item.clone();
-                            for op in &self.arg_ops[0] {
-                                transformed = op.apply_with_context(&transformed, context)?;
-                            }
-                            // Filter out null values that result from select() operations, but preserve nulls from identity
-                            if !matches!(transformed, Value::Null) || is_identity {
-                                result.push(transformed);
-                            }
+                            limits::check()?;
+                            result.extend(collect_ops(&self.arg_ops[0], item, context)?);
                         }
                         Ok(Value::Array(result))
                     }
@@ -1839,44 +4415,26 @@ impl Operation for FunctionCallOperation {
                         // For now, assume it returns objects that can be collected into a new DataFrame
                         let mut results = Vec::new();
                         for i in 0..df.height() {
+                            limits::check()?;
                             // Convert row to object-like value
-                            let mut row_obj = HashMap::new();
-                            for col_name in df.get_column_names() {
-                                let series = df.column(col_name).map_err(|e| {
-                                    dsq_shared::error::operation_error(format!(
-                                        "Failed to get column: {}",
-                                        e
-                                    ))
-                                })?;
-                                let any_val = series.get(i).map_err(|e| {
-                                    dsq_shared::error::operation_error(format!(
-                                        "Failed to get value: {}",
-                                        e
-                                    ))
-                                })?;
-                                let val = value_from_any_value(any_val).unwrap_or(Value::Null);
-                                row_obj.insert(col_name.to_string(), val);
-                            }
-                            let row_value = Value::Object(row_obj);
+                            let row_value = frame_row(df, i)?;
 
                             let mut ctx = dsq_shared::ops::SimpleContext {
                                 value: row_value.clone(),
                             };
-                            let mut transformed = row_value;
-                            for op in &self.arg_ops[0] {
-                                transformed =
-                                    op.apply_with_context(&transformed, &mut Some(&mut ctx))?;
-                            }
-                            // Filter out null values that result from select() operations
-                            if !matches!(transformed, Value::Null) {
-                                results.push(transformed);
-                            }
+                            results.extend(collect_ops(
+                                &self.arg_ops[0],
+                                &row_value,
+                                &mut Some(&mut ctx),
+                            )?);
                         }
                         Ok(Value::Array(results))
                     }
+                    Value::LazyFrame(lf) => self.call_collected(lf, context),
                     Value::Series(series) => {
                         let mut results = Vec::new();
                         for i in 0..series.len() {
+                            limits::check()?;
                             let any_val = series.get(i).map_err(|e| {
                                 dsq_shared::error::operation_error(format!(
                                     "Failed to get value: {e}"
@@ -1884,14 +4442,11 @@ impl Operation for FunctionCallOperation {
                             })?;
                             let val = value_from_any_value(any_val)?;
                             let mut ctx = dsq_shared::ops::SimpleContext { value: val.clone() };
-                            let mut transformed = val;
-                            for op in &self.arg_ops[0] {
-                                transformed =
-                                    op.apply_with_context(&transformed, &mut Some(&mut ctx))?;
-                            }
-                            if !matches!(transformed, Value::Null) {
-                                results.push(transformed);
-                            }
+                            results.extend(collect_ops(
+                                &self.arg_ops[0],
+                                &val,
+                                &mut Some(&mut ctx),
+                            )?);
                         }
                         Ok(Value::Array(results))
                     }
@@ -1910,6 +4465,9 @@ impl Operation for FunctionCallOperation {
                         "filter() expects 1 argument",
                     ));
                 }
+                if let Some(result) = self.vectorized(value) {
+                    return Ok(result);
+                }
 
                 match value {
                     Value::Array(arr) => {
@@ -1931,7 +4489,7 @@ impl Operation for FunctionCallOperation {
                         let mut mask_values = Vec::new();
                         for i in 0..df.height() {
                             // Convert row to object-like value
-                            let mut row_obj = HashMap::new();
+                            let mut row_obj = IndexMap::new();
                             for col_name in df.get_column_names() {
                                 let series = df.column(col_name).map_err(|e| {
                                     dsq_shared::error::operation_error(format!(
@@ -1971,6 +4529,7 @@ impl Operation for FunctionCallOperation {
                         })?;
                         Ok(Value::DataFrame(filtered_df))
                     }
+                    Value::LazyFrame(lf) => self.call_collected(lf, context),
                     _ => Err(dsq_shared::error::operation_error(
                         "filter() requires an array or DataFrame",
                     )),
@@ -2018,7 +4577,7 @@ impl Operation for FunctionCallOperation {
 
                 match value {
                     Value::Object(obj) => {
-                        let mut result = HashMap::new();
+                        let mut result = IndexMap::new();
                         for (key, val) in obj {
                             let mut transformed = val.clone();
                             for op in &self.arg_ops[0] {
@@ -2098,26 +4657,16 @@ impl Operation for FunctionCallOperation {
                         "select() expects 1 argument",
                     ));
                 }
+                if let Some(result) = self.vectorized(value) {
+                    return Ok(result);
+                }
                 match &value {
-                    Value::Array(arr) => {
-                        let mut filtered = Vec::new();
-                        for item in arr {
-                            let mut condition_val = item.clone();
-                            for op in &self.arg_ops[0] {
-                                condition_val = op.apply_with_context(&condition_val, context)?;
-                            }
-                            if is_truthy(&condition_val) {
-                                filtered.push(item.clone());
-                            }
-                        }
-                        Ok(Value::Array(filtered))
-                    }
                     Value::DataFrame(df) => {
                         // For DataFrame, evaluate condition for each row and create boolean mask
                         let mut mask_values = Vec::new();
                         for i in 0..df.height() {
                             // Convert row to object-like value
-                            let mut row_obj = HashMap::new();
+                            let mut row_obj = IndexMap::new();
                             for col_name in df.get_column_names() {
                                 let series = df.column(col_name).map_err(|e| {
                                     dsq_shared::error::operation_error(format!(
@@ -2158,19 +4707,110 @@ impl Operation for FunctionCallOperation {
                         })?;
                         Ok(Value::DataFrame(filtered_df))
                     }
-                    _ => {
-                        let mut condition_val = value.clone();
-                        for op in &self.arg_ops[0] {
-                            condition_val = op.apply_with_context(&condition_val, context)?;
-                        }
-                        if is_truthy(&condition_val) {
-                            Ok(value.clone())
-                        } else {
-                            Ok(Value::Null)
-                        }
-                    }
+                    Value::LazyFrame(lf) => self.call_collected(lf, context),
+                    other => unreachable!("select() streams {} inputs", other.type_name()),
+                }
+            }
+            "getpath" | "setpath" | "delpaths" | "format" => {
+                let expected = if self.name == "setpath" { 2 } else { 1 };
+                if self.arg_ops.len() != expected {
+                    return Err(dsq_shared::error::operation_error(format!(
+                        "{}() expects {} argument{}",
+                        self.name,
+                        expected,
+                        if expected == 1 { "" } else { "s" }
+                    )));
+                }
+                let mut arg_values = vec![value.clone()];
+                for index in 0..expected {
+                    arg_values.push(self.eval_arg(index, value, context)?);
+                }
+                self.builtins.call_function(&self.name, &arg_values)
+            }
+            "test" | "match" | "capture" | "scan" | "splits" => {
+                if !(1..=2).contains(&self.arg_ops.len()) {
+                    return Err(dsq_shared::error::operation_error(format!(
+                        "{}() expects 1 or 2 arguments",
+                        self.name
+                    )));
+                }
+                let mut arg_values = vec![value.clone()];
+                for index in 0..self.arg_ops.len() {
+                    arg_values.push(self.eval_arg(index, value, context)?);
+                }
+                self.builtins.call_function(&self.name, &arg_values)
+            }
+            "sub" | "gsub" => {
+                if !(2..=3).contains(&self.arg_ops.len()) {
+                    return Err(dsq_shared::error::operation_error(format!(
+                        "{}() expects 2 or 3 arguments",
+                        self.name
+                    )));
+                }
+                let mut regex = vec![self.eval_arg(0, value, context)?];
+                if self.arg_ops.len() == 3 {
+                    regex.push(self.eval_arg(2, value, context)?);
+                }
+                let (pattern, mut flags) = regex_args(&self.name, &regex)?;
+                flags.global |= self.name == "gsub";
+                // The replacement is a filter over each match's capture object
+                substitute(value, &pattern, &flags, &mut |captures| {
+                    self.eval_arg(1, captures, context)
+                })
+            }
+            "with_entries" => {
+                if self.arg_ops.len() != 1 {
+                    return Err(dsq_shared::error::operation_error(
+                        "with_entries() expects 1 argument",
+                    ));
+                }
+                let Value::Array(entries) = self
+                    .builtins
+                    .call_function("to_entries", std::slice::from_ref(value))?
+                else {
+                    unreachable!("to_entries() returns an array");
+                };
+                let mut mapped = Vec::with_capacity(entries.len());
+                for entry in &entries {
+                    mapped.extend(collect_ops(&self.arg_ops[0], entry, context)?);
+                }
+                self.builtins
+                    .call_function("from_entries", &[Value::Array(mapped)])
+            }
+            "recurse" => match self.arg_ops.len() {
+                0 => self
+                    .builtins
+                    .call_function("recurse", std::slice::from_ref(value)),
+                _ => Err(dsq_shared::error::operation_error(
+                    "recurse() expects 0, 1 or 2 arguments",
+                )),
+            },
+            "walk" => {
+                if self.arg_ops.len() != 1 {
+                    return Err(dsq_shared::error::operation_error(
+                        "walk() expects 1 argument",
+                    ));
                 }
+                self.walk_value(value, context)
             }
+            "paths" => match self.arg_ops.len() {
+                0 => self
+                    .builtins
+                    .call_function("paths", std::slice::from_ref(value)),
+                1 => {
+                    let mut paths = Vec::new();
+                    for_each_path(value, &mut |path, item| {
+                        if is_truthy(&self.eval_arg(0, item, context)?) {
+                            paths.push(Value::Array(path.to_vec()));
+                        }
+                        Ok(CONTINUE)
+                    })?;
+                    Ok(Value::Array(paths))
+                }
+                _ => Err(dsq_shared::error::operation_error(
+                    "paths() expects 0 or 1 argument",
+                )),
+            },
             "ceil" => {
                 if self.arg_ops.is_empty() {
                     self.builtins
@@ -2254,7 +4894,7 @@ impl Operation for FunctionCallOperation {
                             let mut mask = Vec::new();
                             for i in 0..df.height() {
                                 // Create a row object from the DataFrame row
-                                let mut row_obj = std::collections::HashMap::new();
+                                let mut row_obj = IndexMap::new();
                                 for col_name in df.get_column_names() {
                                     if let Ok(series) = df.column(col_name) {
                                         if let Ok(val) = series.get(i) {
@@ -2322,11 +4962,125 @@ impl Operation for FunctionCallOperation {
             }
         }
     }
+}
+
+impl Operation for FunctionCallOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        let mut context = None;
+        self.apply_with_context(value, &mut context)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    /// Generators produce their outputs one by one; other functions produce
+    /// the single value of [`FunctionCallOperation::call`]
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn dsq_shared::ops::Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        match (self.name.as_str(), self.arg_ops.len()) {
+            ("empty", 0) => Ok(CONTINUE),
+            // A DataFrame is filtered row by row into a new frame instead
+            ("select", 1) if !matches!(value, Value::DataFrame(_) | Value::LazyFrame(_)) => {
+                generate_ops(
+                    &self.arg_ops[0],
+                    value,
+                    context,
+                    &mut |condition, context| {
+                        if is_truthy(&condition) {
+                            emit(value.clone(), context)
+                        } else {
+                            Ok(CONTINUE)
+                        }
+                    },
+                )
+            }
+            ("recurse", 0) => for_each_descendant(value, &mut |item| {
+                limits::check()?;
+                emit(item.clone(), context)
+            }),
+            ("recurse", 1 | 2) => self.generate_recurse(value, 0, context, emit),
+            ("paths", 0 | 1) => for_each_path(value, &mut |path, item| {
+                limits::check()?;
+                if !self.arg_ops.is_empty() && !is_truthy(&self.eval_arg(0, item, context)?) {
+                    return Ok(CONTINUE);
+                }
+                emit(Value::Array(path.to_vec()), context)
+            }),
+            ("leaf_paths", 0) => for_each_path(value, &mut |path, item| {
+                limits::check()?;
+                if !is_leaf(item) {
+                    return Ok(CONTINUE);
+                }
+                emit(Value::Array(path.to_vec()), context)
+            }),
+            ("limit", 2) => self.generate_limit(value, context, emit),
+            ("first", 1) => match first_output(&self.arg_ops[0], value, context)? {
+                Some(item) => emit(item, context),
+                None => Ok(CONTINUE),
+            },
+            ("last", 1) => match collect_ops(&self.arg_ops[0], value, context)?.pop() {
+                Some(item) => emit(item, context),
+                None => Ok(CONTINUE),
+            },
+            ("nth", 1) => generate_ops(&self.arg_ops[0], value, context, &mut |n, context| {
+                let n = count_arg("nth", &n)?;
+                emit(value.index(n as i64)?, context)
+            }),
+            ("nth", 2) => self.generate_nth(value, context, emit),
+            ("repeat", 1) | ("while" | "until", 2) => self.generate_iterate(value, context, emit),
+            ("range", 1..=3) => self.generate_range(value, context, emit),
+            ("input", 0) => match next_input(context) {
+                Some(document) => emit(document?, context),
+                None => Err(dsq_shared::error::operation_error("No more inputs")),
+            },
+            ("inputs", 0) => {
+                while let Some(document) = next_input(context) {
+                    if emit(document?, context)?.is_break() {
+                        return Ok(STOP);
+                    }
+                }
+                Ok(CONTINUE)
+            }
+            ("recurse" | "paths" | "leaf_paths", _) => match self.call(value, context)? {
+                Value::Array(items) => emit_all(items, context, emit),
+                other => emit(other, context),
+            },
+            // On a Series these map each string to a column of results
+            ("match" | "capture" | "scan" | "splits", _) if !matches!(value, Value::Series(_)) => {
+                match self.call(value, context)? {
+                    Value::Array(items) => emit_all(items, context, emit),
+                    Value::Null => Ok(CONTINUE),
+                    other => emit(other, context),
+                }
+            }
+            _ => emit(self.call(value, context)?, context),
+        }
+    }
 
     fn description(&self) -> String {
         format!("function call: {}", self.name)
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        self.arg_ops
+            .iter()
+            .map(|ops| ("argument", ops.as_slice()))
+            .collect()
+    }
+
+    fn is_vectorized(&self) -> bool {
+        self.row_filter.is_some()
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -2433,6 +5187,10 @@ impl Operation for AssignAddOperation {
         "assign add".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("target", &self.target_ops), ("value", &self.value_ops)]
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -2496,6 +5254,10 @@ impl Operation for AssignUpdateOperation {
         "assign update".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("target", &self.target_ops), ("value", &self.value_ops)]
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -2573,6 +5335,17 @@ impl Operation for SliceOperation {
         "array slice".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        let mut branches: Vec<Branch<'_>> = Vec::new();
+        if let Some(start_ops) = &self.start_ops {
+            branches.push(("start", start_ops));
+        }
+        if let Some(end_ops) = &self.end_ops {
+            branches.push(("end", end_ops));
+        }
+        branches
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -2614,6 +5387,10 @@ impl Operation for AssignFieldAddOperation {
         format!("assign add to field {}", self.field)
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("value", &self.value_ops)]
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -2763,7 +5540,7 @@ mod tests {
         assert_eq!(ctx.get_input(), None);
 
         // Test setting input
-        let value = Value::Object(HashMap::from([("key".to_string(), Value::Int(123))]));
+        let value = Value::Object(IndexMap::from([("key".to_string(), Value::Int(123))]));
         ctx.set_input(value.clone());
         assert_eq!(ctx.get_input(), Some(&value));
     }
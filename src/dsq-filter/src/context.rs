@@ -9,6 +9,6 @@
 
 pub use crate::compiler::{
     BuiltinFunction, CompilationContext, ErrorMode, FilterContext, FunctionBody, FunctionDef,
-    Location, StackFrame,
+    InputCursor, Location, StackFrame,
 };
 pub use dsq_functions::BuiltinRegistry;
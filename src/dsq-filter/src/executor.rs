@@ -4,11 +4,18 @@
 //! data values, managing the execution lifecycle, error handling, and performance
 //! monitoring.
 
-use crate::compiler::{CompiledFilter, ErrorMode, FilterCompiler, FilterContext};
+use crate::compiler::{CompiledFilter, ErrorMode, FilterCompiler, FilterContext, InputCursor};
+use crate::streaming::{batch_output, BatchOutput};
+use dsq_shared::error::LimitExceeded;
+use dsq_shared::limits;
+use dsq_shared::ops::stream::CONTINUE;
+use dsq_shared::ops::{collapse, generate_ops};
 use dsq_shared::value::Value;
 use dsq_shared::Result;
 use lru::LruCache;
 use std::num::NonZeroUsize;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
 use std::sync::Arc;
 #[cfg(target_arch = "wasm32")]
 use std::time::Duration;
@@ -20,6 +27,11 @@ use std::time::{Duration, Instant};
 pub struct ExecutorConfig {
     /// Maximum execution time in milliseconds
     pub timeout_ms: Option<u64>,
+    /// Maximum memory in bytes an execution may allocate
+    ///
+    /// Only enforced when the process installs
+    /// [`dsq_shared::limits::TrackingAllocator`].
+    pub memory_limit: Option<usize>,
     /// Error handling mode
     pub error_mode: ErrorMode,
     /// Whether to collect execution statistics
@@ -28,25 +40,39 @@ pub struct ExecutorConfig {
     pub max_recursion_depth: usize,
     /// Whether to enable debug mode
     pub debug_mode: bool,
-    /// Batch size for DataFrame operations
+    /// Batch size for DataFrame operations, and the number of rows read
+    /// at a time in streaming mode
     pub batch_size: usize,
+    /// How inputs are read and filtered
+    pub execution_mode: ExecutionMode,
     /// Variables available during execution
     pub variables: std::collections::HashMap<String, Value>,
     /// Maximum number of compiled filters to cache
     pub filter_cache_size: usize,
+    /// Directories searched for modules before the default locations
+    pub library_paths: Vec<PathBuf>,
+    /// Modules imported into every filter (`import "m" as m;`)
+    pub imports: Vec<String>,
+    /// Module files included into every filter (`include "f";`)
+    pub includes: Vec<PathBuf>,
 }
 
 impl Default for ExecutorConfig {
     fn default() -> Self {
         Self {
             timeout_ms: None,
+            memory_limit: None,
             error_mode: ErrorMode::Strict,
             collect_stats: false,
             max_recursion_depth: 1000,
             debug_mode: false,
             batch_size: 10000,
+            execution_mode: ExecutionMode::Standard,
             variables: std::collections::HashMap::new(),
             filter_cache_size: 1000, // Cache up to 1000 compiled filters
+            library_paths: Vec::new(),
+            imports: Vec::new(),
+            includes: Vec::new(),
         }
     }
 }
@@ -65,14 +91,30 @@ pub enum ExecutionMode {
 /// Result of filter execution
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
-    /// The output value
-    pub value: Value,
+    /// Every output of the filter, in order
+    pub outputs: Vec<Value>,
     /// Execution statistics (if collected)
     pub stats: Option<ExecutionStats>,
     /// Any warnings generated during execution
     pub warnings: Vec<String>,
 }
 
+impl ExecutionResult {
+    /// The single output, `null` when there is none, or an array of all
+    /// outputs when there are several
+    pub fn into_value(self) -> Value {
+        collapse(self.outputs)
+    }
+
+    /// The outputs collapsed as by [`Self::into_value`], copying them
+    #[deprecated(
+        note = "the `value` field was replaced by `outputs`; use `into_value` or `outputs`"
+    )]
+    pub fn value(&self) -> Value {
+        collapse(self.outputs.clone())
+    }
+}
+
 /// Execution statistics
 #[derive(Debug, Clone)]
 pub struct ExecutionStats {
@@ -80,7 +122,8 @@ pub struct ExecutionStats {
     pub execution_time: Duration,
     /// Number of operations executed
     pub operations_executed: usize,
-    /// Peak memory usage in bytes
+    /// Peak memory allocated by an execution, in bytes (0 unless the
+    /// process installs [`dsq_shared::limits::TrackingAllocator`])
     pub peak_memory_bytes: usize,
     /// Number of function calls
     pub function_calls: usize,
@@ -104,6 +147,8 @@ pub struct FilterExecutor {
     cache_misses: usize,
     /// Statistics accumulator
     stats_accumulator: Option<ExecutionStats>,
+    /// Documents read by `input` and `inputs`
+    inputs: InputCursor,
 }
 
 impl FilterExecutor {
@@ -120,7 +165,7 @@ impl FilterExecutor {
         let cache_capacity =
             NonZeroUsize::new(cache_size).unwrap_or(NonZeroUsize::new(1000).unwrap());
         Self {
-            compiler: FilterCompiler::new(),
+            compiler: Self::compiler_for(&config),
             config,
             filter_cache: LruCache::new(cache_capacity),
             cache_hits: 0,
@@ -137,7 +182,20 @@ impl FilterExecutor {
             } else {
                 None
             },
+            inputs: InputCursor::default(),
+        }
+    }
+
+    /// Build a compiler with the module settings of `config`
+    fn compiler_for(config: &ExecutorConfig) -> FilterCompiler {
+        let mut compiler = FilterCompiler::new().with_library_paths(&config.library_paths);
+        for module in &config.imports {
+            compiler = compiler.with_import(module);
+        }
+        for path in &config.includes {
+            compiler = compiler.with_include(path);
         }
+        compiler
     }
 
     /// Execute a filter string against a value
@@ -196,6 +254,9 @@ impl FilterExecutor {
                     stats.execution_time += start_time.elapsed();
                 }
                 stats.operations_executed += operations_count;
+                if let Some(peak) = result.stats.as_ref().map(|s| s.peak_memory_bytes) {
+                    stats.peak_memory_bytes = stats.peak_memory_bytes.max(peak);
+                }
             }
             result.stats = self.stats_accumulator.clone();
         }
@@ -209,8 +270,7 @@ impl FilterExecutor {
         filter: &CompiledFilter,
         input: Value,
     ) -> Result<ExecutionResult> {
-        #[cfg(not(target_arch = "wasm32"))]
-        let start_time = Instant::now();
+        let limits = limits::enforce(self.config.timeout_ms, self.config.memory_limit);
 
         // Create execution context
         let mut context = FilterContext::new();
@@ -218,58 +278,52 @@ impl FilterExecutor {
         context.set_debug_mode(self.config.debug_mode);
         context.set_input(input);
         context.set_functions(filter.functions.clone());
+        context.set_inputs(self.inputs.clone());
 
-        // Set variables from config
+        // Set data modules, then variables from config
+        for (name, value) in &filter.variables {
+            context.set_variable(name, value.clone());
+        }
         for (name, value) in &self.config.variables {
             context.set_variable(name, value.clone());
         }
 
-        // Execute operations
-        let mut current_value = context.get_input().cloned().unwrap_or(Value::Null);
+        // Generate the outputs of the pipeline
+        let input = context.get_input().cloned().unwrap_or(Value::Null);
+        let mut outputs = Vec::new();
         let mut warnings = Vec::new();
 
-        for operation in &filter.operations {
+        let mut ctx = Some(&mut context as &mut dyn dsq_shared::ops::Context);
+        let generated = generate_ops(&filter.operations, &input, &mut ctx, &mut |output, _| {
             #[cfg(feature = "profiling")]
             coz::progress!("operation_exec");
 
-            let mut ctx = Some(&mut context as &mut dyn dsq_shared::ops::Context);
-            match operation.apply_with_context(&current_value, &mut ctx) {
-                Ok(new_value) => {
-                    current_value = new_value;
-                }
-                Err(e) => {
-                    match self.config.error_mode {
-                        ErrorMode::Strict => return Err(e),
-                        ErrorMode::Collect => {
-                            warnings.push(format!("Operation failed: {}", e));
-                            // Continue with null value
-                            current_value = Value::Null;
-                        }
-                        ErrorMode::Ignore => {
-                            // Continue with null value
-                            current_value = Value::Null;
-                        }
-                    }
-                }
-            }
-
-            // Check timeout
-            #[cfg(not(target_arch = "wasm32"))]
-            if let Some(timeout_ms) = self.config.timeout_ms {
-                if start_time.elapsed() > Duration::from_millis(timeout_ms) {
-                    return Err(dsq_shared::error::operation_error("Execution timeout"));
-                }
+            outputs.push(output);
+            limits::check()?;
+            Ok(CONTINUE)
+        });
+        if let Err(e) = generated {
+            // The outputs produced before the error are kept; an exceeded
+            // limit always ends the execution
+            match self.config.error_mode {
+                _ if e.is::<LimitExceeded>() => return Err(e),
+                ErrorMode::Strict => return Err(e),
+                ErrorMode::Collect => warnings.push(format!("Operation failed: {}", e)),
+                ErrorMode::Ignore => {}
             }
         }
 
         let stats = if self.config.collect_stats {
-            self.stats_accumulator.clone()
+            self.stats_accumulator.clone().map(|mut stats| {
+                stats.peak_memory_bytes = limits.peak_memory_bytes();
+                stats
+            })
         } else {
             None
         };
 
         Ok(ExecutionResult {
-            value: current_value,
+            outputs,
             stats,
             warnings,
         })
@@ -296,12 +350,43 @@ impl FilterExecutor {
         Ok(results)
     }
 
+    /// Run a filter on each batch of an input read in batches, passing
+    /// every output to `emit` as soon as its batch has run
+    ///
+    /// Fails before reading any batch when the filter needs the whole
+    /// input (see [`crate::streaming::batch_output`]). Stops reading when
+    /// `emit` returns [`ControlFlow::Break`].
+    pub fn execute_batches(
+        &mut self,
+        filter: &str,
+        batches: impl Iterator<Item = Result<Value>>,
+        mut emit: impl FnMut(Value) -> Result<ControlFlow<()>>,
+    ) -> Result<BatchOutput> {
+        let shape = batch_output(filter)?;
+        let compiled = self.compiler.compile_str(filter)?;
+        for batch in batches {
+            let result = self.execute_compiled(&compiled, batch?)?;
+            for output in result.outputs {
+                if emit(output)?.is_break() {
+                    return Ok(shape);
+                }
+            }
+        }
+        Ok(shape)
+    }
+
     /// Validate a filter string without executing it
     pub fn validate_filter(&self, filter: &str) -> Result<()> {
         self.compiler.compile_str(filter)?;
         Ok(())
     }
 
+    /// Explain how a filter string runs on `input`: its AST, its
+    /// operation tree and, for tabular input, the optimized Polars plan
+    pub fn explain(&self, filter: &str, input: Option<&Value>) -> Result<String> {
+        crate::explain::explain(&self.compiler, filter, input)
+    }
+
     /// Get execution statistics
     pub fn get_stats(&self) -> Option<&ExecutionStats> {
         self.stats_accumulator.as_ref()
@@ -328,6 +413,14 @@ impl FilterExecutor {
     /// Set execution configuration
     pub fn set_config(&mut self, config: ExecutorConfig) {
         let collect_stats = config.collect_stats;
+        if config.library_paths != self.config.library_paths
+            || config.imports != self.config.imports
+            || config.includes != self.config.includes
+        {
+            // Cached filters were compiled against the old modules
+            self.compiler = Self::compiler_for(&config);
+            self.filter_cache.clear();
+        }
         self.config = config;
         if collect_stats && self.stats_accumulator.is_none() {
             self.stats_accumulator = Some(ExecutionStats {
@@ -343,6 +436,12 @@ impl FilterExecutor {
         }
     }
 
+    /// Set the documents read by `input` and `inputs`, shared by every
+    /// execution of this executor
+    pub fn set_inputs(&mut self, inputs: InputCursor) {
+        self.inputs = inputs;
+    }
+
     /// Get current configuration
     pub fn get_config(&self) -> &ExecutorConfig {
         &self.config
@@ -366,20 +465,34 @@ mod tests {
         let input = Value::int(42);
         let result = executor.execute_str(".", input.clone()).unwrap();
 
-        assert_eq!(result.value, input);
         assert!(result.warnings.is_empty());
+        assert_eq!(result.into_value(), input);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_value_accessor() {
+        let mut executor = FilterExecutor::new();
+        let result = executor
+            .execute_str(".[]", Value::array(vec![Value::int(1), Value::int(2)]))
+            .unwrap();
+        assert_eq!(
+            result.value(),
+            Value::array(vec![Value::int(1), Value::int(2)])
+        );
+        assert_eq!(result.value(), result.into_value());
     }
 
     #[test]
     fn test_execute_field_access() {
         let mut executor = FilterExecutor::new();
-        let input = Value::object(std::collections::HashMap::from([
+        let input = Value::object(indexmap::IndexMap::from([
             ("name".to_string(), Value::string("Alice")),
             ("age".to_string(), Value::int(30)),
         ]));
 
         let result = executor.execute_str(".name", input).unwrap();
-        assert_eq!(result.value, Value::string("Alice"));
+        assert_eq!(result.into_value(), Value::string("Alice"));
     }
 
     #[test]
@@ -401,11 +514,11 @@ mod tests {
         // First execution should compile
         let input = Value::int(42);
         let result1 = executor.execute_str(". + 1", input.clone()).unwrap();
-        assert_eq!(result1.value, Value::int(43));
+        assert_eq!(result1.into_value(), Value::int(43));
 
         // Second execution should use cache
         let result2 = executor.execute_str(". + 1", input).unwrap();
-        assert_eq!(result2.value, Value::int(43));
+        assert_eq!(result2.into_value(), Value::int(43));
 
         assert_eq!(executor.cache_size(), 1);
     }
@@ -428,7 +541,7 @@ mod tests {
 
         let input = Value::int(42);
         let result = executor.execute_str(".invalid_field", input).unwrap();
-        assert_eq!(result.value, Value::Null);
+        assert_eq!(result.into_value(), Value::Null);
     }
 
     #[test]
@@ -436,14 +549,14 @@ mod tests {
         let mut executor = FilterExecutor::new();
 
         // Test field assignment on object
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("salary".to_string(), Value::int(75000));
         obj.insert("name".to_string(), Value::string("Alice"));
         let input = Value::object(obj);
 
         let result = executor.execute_str(".salary += 5000", input).unwrap();
 
-        if let Value::Object(result_obj) = result.value {
+        if let Value::Object(result_obj) = result.into_value() {
             assert_eq!(result_obj.get("salary"), Some(&Value::int(80000)));
             assert_eq!(result_obj.get("name"), Some(&Value::string("Alice")));
         } else {
@@ -456,7 +569,7 @@ mod tests {
         let mut executor = FilterExecutor::new();
 
         // Test the query from example_095: map(.salary += 5000) | map({name, new_salary: .salary, department})
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("id".to_string(), Value::int(1));
         obj.insert("name".to_string(), Value::string("Alice Johnson"));
         obj.insert("age".to_string(), Value::int(28));
@@ -472,7 +585,7 @@ mod tests {
             )
             .unwrap();
 
-        if let Value::Array(arr) = result.value {
+        if let Value::Array(arr) = result.into_value() {
             assert_eq!(arr.len(), 1);
             if let Value::Object(obj) = &arr[0] {
                 assert_eq!(obj.get("name"), Some(&Value::string("Alice Johnson")));
@@ -500,8 +613,9 @@ mod tests {
         let stats = result.stats.unwrap();
         assert!(stats.execution_time > Duration::ZERO);
         assert!(stats.operations_executed > 0);
-        // Other stats are initialized to 0 and not updated yet
+        // Memory is not measured without the tracking allocator
         assert_eq!(stats.peak_memory_bytes, 0);
+        // Other stats are initialized to 0 and not updated yet
         assert_eq!(stats.function_calls, 0);
         assert_eq!(stats.dataframe_operations, 0);
         assert_eq!(stats.cache_hit_rate, 0.0);
@@ -514,9 +628,39 @@ mod tests {
 
         let results = executor.execute_streaming(".", inputs.into_iter()).unwrap();
         assert_eq!(results.len(), 3);
-        assert_eq!(results[0].value, Value::int(1));
-        assert_eq!(results[1].value, Value::int(2));
-        assert_eq!(results[2].value, Value::int(3));
+        let values: Vec<Value> = results
+            .into_iter()
+            .map(ExecutionResult::into_value)
+            .collect();
+        assert_eq!(values, [Value::int(1), Value::int(2), Value::int(3)]);
+    }
+
+    #[test]
+    fn test_execute_batches() {
+        let mut executor = FilterExecutor::new();
+        let batches = vec![
+            Ok(Value::Array(vec![Value::int(1), Value::int(2)])),
+            Ok(Value::Array(vec![Value::int(3)])),
+        ];
+        let mut outputs = Vec::new();
+        let shape = executor
+            .execute_batches(".[] | . * 10", batches.into_iter(), |output| {
+                outputs.push(output);
+                Ok(ControlFlow::Continue(()))
+            })
+            .unwrap();
+        assert_eq!(shape, BatchOutput::Documents);
+        assert_eq!(
+            outputs,
+            vec![Value::int(10), Value::int(20), Value::int(30)]
+        );
+
+        // Nothing is read when the filter needs the whole input
+        let batches = std::iter::once_with(|| -> Result<Value> { panic!("read a batch") });
+        let error = executor
+            .execute_batches("sort", batches, |_| Ok(ControlFlow::Continue(())))
+            .unwrap_err();
+        assert!(error.to_string().contains("streaming mode"));
     }
 
     #[test]
@@ -527,7 +671,7 @@ mod tests {
 
         let input = Value::int(42);
         let result = executor.execute_str(". + 1", input).unwrap();
-        assert_eq!(result.value, Value::int(43));
+        assert_eq!(result.into_value(), Value::int(43));
     }
 
     #[test]
@@ -567,9 +711,9 @@ mod tests {
 
         let input = Value::int(42);
         let result = executor.execute_str(".invalid_field", input).unwrap();
-        assert_eq!(result.value, Value::Null);
         assert!(!result.warnings.is_empty());
         assert!(result.warnings[0].contains("Operation failed"));
+        assert_eq!(result.into_value(), Value::Null);
     }
 
     #[test]
@@ -584,6 +728,6 @@ mod tests {
         let input = Value::int(42);
         let result = executor.execute_str(".", input).unwrap();
         // Should succeed since operation is fast
-        assert_eq!(result.value, Value::int(42));
+        assert_eq!(result.into_value(), Value::int(42));
     }
 }
@@ -0,0 +1,188 @@
+//! Explaining how a filter runs
+//!
+//! An explanation has three parts: the AST the filter parses to, the tree
+//! of operations it compiles to, with the `map`, `select` and `filter`
+//! calls lowered to Polars expressions marked `[vectorized]`, and, given
+//! tabular input, the optimized Polars logical plan. The plan covers the
+//! leading stages of the pipeline that run as one lazy query, so it shows
+//! the projections and predicates pushed down into the scan; the stages
+//! after the first one that runs row by row are listed after the plan.
+
+use std::fmt::Write;
+
+use dsq_parser::FilterParser;
+use dsq_shared::ops::Operation;
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use polars::prelude::*;
+
+use crate::compiler::{lowered_query, FilterCompiler};
+use crate::rowwise::Mapped;
+
+/// Explain how `filter` runs on `input`, or on any input when `input` is
+/// `None`
+pub fn explain(compiler: &FilterCompiler, filter: &str, input: Option<&Value>) -> Result<String> {
+    let parsed = FilterParser::new()
+        .parse(filter)
+        .map_err(|e| dsq_shared::error::operation_error(format!("{e}")))?;
+    let compiled = compiler.compile_str(filter)?;
+
+    let mut out = String::new();
+    let _ = writeln!(out, "Filter: {}", filter.trim());
+
+    let _ = writeln!(out, "\nAST:");
+    for directive in &parsed.directives {
+        let _ = writeln!(out, "  {directive}");
+    }
+    for line in format!("{:#?}", parsed.expr).lines() {
+        let _ = writeln!(out, "  {line}");
+    }
+
+    let _ = writeln!(out, "\nOperations:");
+    write_operations(&mut out, &compiled.operations, 1);
+
+    let _ = writeln!(out, "\nQuery plan:");
+    match input {
+        Some(input) => write_query_plan(&mut out, &compiled.operations, input)?,
+        None => {
+            let _ = writeln!(out, "  (no input; the plan depends on the input schema)");
+        }
+    }
+    Ok(out)
+}
+
+/// Write `ops` as an indented tree, one operation per line
+fn write_operations(out: &mut String, ops: &[Box<dyn Operation + Send + Sync>], depth: usize) {
+    for op in ops {
+        let marker = if op.is_vectorized() {
+            " [vectorized]"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "{:indent$}{}{marker}",
+            "",
+            op.description(),
+            indent = depth * 2
+        );
+        for (label, branch) in op.branches() {
+            let _ = writeln!(out, "{:indent$}{label}:", "", indent = (depth + 1) * 2);
+            write_operations(out, branch, depth + 2);
+        }
+    }
+}
+
+/// Write the optimized logical plan of the leading stages of `ops` that
+/// run as one Polars query on `input`, followed by the stages that run row
+/// by row on its result
+fn write_query_plan(
+    out: &mut String,
+    ops: &[Box<dyn Operation + Send + Sync>],
+    input: &Value,
+) -> Result<()> {
+    let mut frame = match input {
+        Value::DataFrame(df) => df.clone().lazy(),
+        Value::LazyFrame(lf) => lf.as_ref().clone(),
+        _ => {
+            let _ = writeln!(
+                out,
+                "  (input is not tabular; every operation runs row by row)"
+            );
+            return Ok(());
+        }
+    };
+
+    let mut lazy_stages = 0;
+    for op in ops {
+        match lowered_query(op.as_ref(), &frame) {
+            Some(Mapped::Frame(next)) => frame = next,
+            // Later stages see an array of outputs rather than a frame
+            Some(Mapped::Objects(next) | Mapped::Values(next)) => {
+                frame = next;
+                lazy_stages += 1;
+                break;
+            }
+            None => break,
+        }
+        lazy_stages += 1;
+    }
+
+    let plan = frame.describe_optimized_plan().map_err(|e| {
+        dsq_shared::error::operation_error(format!("Failed to optimize query plan: {e}"))
+    })?;
+    for line in plan.lines() {
+        let _ = writeln!(out, "  {line}");
+    }
+
+    let _ = writeln!(
+        out,
+        "\n  {lazy_stages} of {} stage(s) run in the query",
+        ops.len()
+    );
+    if lazy_stages < ops.len() {
+        let rest: Vec<String> = ops[lazy_stages..]
+            .iter()
+            .map(|op| op.description())
+            .collect();
+        let _ = writeln!(out, "  Row by row on the result: {}", rest.join(" | "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn people() -> Value {
+        let df = df! {
+            "name" => ["Ada", "Grace", "Alan"],
+            "age" => [36i64, 45, 41],
+            "city" => ["London", "Arlington", "Wilmslow"],
+        }
+        .unwrap();
+        Value::LazyFrame(Box::new(df.lazy()))
+    }
+
+    #[test]
+    fn test_explain_without_input() {
+        let explanation = explain(&FilterCompiler::new(), "map(.age > 40)", None).unwrap();
+        assert!(explanation.contains("AST:"));
+        assert!(explanation.contains("FunctionCall"));
+        assert!(explanation.contains("function call: map [vectorized]"));
+        assert!(explanation.contains("no input"));
+    }
+
+    #[test]
+    fn test_explain_marks_row_by_row_operations() {
+        let explanation = explain(&FilterCompiler::new(), "map(tojson)", None).unwrap();
+        assert!(explanation.contains("function call: map\n"));
+        assert!(!explanation.contains("[vectorized]"));
+    }
+
+    #[test]
+    fn test_explain_shows_pushed_down_plan() {
+        let explanation = explain(
+            &FilterCompiler::new(),
+            "map(select(.age > 40) | {name})",
+            Some(&people()),
+        )
+        .unwrap();
+        let plan = &explanation[explanation.find("Query plan:").unwrap()..];
+        assert!(plan.contains("FILTER"), "{plan}");
+        assert!(plan.contains("2/3 COLUMNS"), "{plan}");
+        assert!(plan.contains("1 of 1 stage(s) run in the query"), "{plan}");
+    }
+
+    #[test]
+    fn test_explain_lists_row_by_row_stages() {
+        let explanation = explain(
+            &FilterCompiler::new(),
+            "select(.age > 40) | length",
+            Some(&people()),
+        )
+        .unwrap();
+        assert!(explanation.contains("1 of 2 stage(s) run in the query"));
+        assert!(explanation.contains("Row by row on the result: function call: length"));
+    }
+}
@@ -8,18 +8,29 @@
 //! - jq-compatible syntax support
 //! - DataFrame and JSON data processing
 //! - Built-in functions and operations
+//! - Explanations of compiled filters and their Polars query plans
 //! - Comprehensive testing against real examples
 
 pub mod compiler;
 pub mod context;
 pub mod executor;
+pub mod explain;
+pub mod modules;
+pub mod paths;
+pub mod rowwise;
+pub mod streaming;
+pub mod strings;
 
 pub use compiler::{CompiledFilter, FilterCompiler, OptimizationLevel};
-pub use context::{CompilationContext, ErrorMode, FilterContext, FunctionBody, FunctionDef};
+pub use context::{
+    CompilationContext, ErrorMode, FilterContext, FunctionBody, FunctionDef, InputCursor,
+};
 pub use dsq_functions::BuiltinRegistry;
 pub use executor::{
     ExecutionMode, ExecutionResult, ExecutionStats, ExecutorConfig, FilterExecutor,
 };
+pub use modules::ModuleLoader;
+pub use streaming::{batch_output, BatchOutput};
 
 /// Convenience function to execute a filter string on a value
 pub fn execute_filter(
@@ -28,7 +39,7 @@ pub fn execute_filter(
 ) -> anyhow::Result<dsq_shared::value::Value> {
     let mut executor = FilterExecutor::new();
     let result = executor.execute_str(filter, value.clone())?;
-    Ok(result.value)
+    Ok(result.into_value())
 }
 
 /// Convenience function to compile a filter string
@@ -91,7 +102,7 @@ mod tests {
             Value::Int(50),
             Value::Int(300),
         ]);
-        let result = execute_filter("select(. > 100)", &input);
+        let result = execute_filter(".[] | select(. > 100)", &input);
         assert!(
             result.is_ok(),
             "Failed to execute select on array: {:?}",
@@ -104,8 +115,8 @@ mod tests {
     #[test]
     fn test_select_on_object() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
-        let mut obj = HashMap::new();
+        use indexmap::IndexMap;
+        let mut obj = IndexMap::new();
         obj.insert("age".to_string(), Value::Int(35));
         obj.insert("name".to_string(), Value::String("John".to_string()));
         obj.insert(
@@ -146,12 +157,12 @@ mod tests {
     fn test_stress_009_execution() {
         // Test execution of the stress_009 query: map(select(.age > 30 and .department == "IT" or .salary < 60000)) | map({name, age, department, salary})
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Create test data similar to stress_009
         let employees = vec![
             {
-                let mut obj = HashMap::new();
+                let mut obj = IndexMap::new();
                 obj.insert("id".to_string(), Value::Int(1));
                 obj.insert(
                     "name".to_string(),
@@ -167,7 +178,7 @@ mod tests {
                 Value::Object(obj)
             },
             {
-                let mut obj = HashMap::new();
+                let mut obj = IndexMap::new();
                 obj.insert("id".to_string(), Value::Int(2));
                 obj.insert("name".to_string(), Value::String("Bob Smith".to_string()));
                 obj.insert("age".to_string(), Value::Int(34));
@@ -232,24 +243,24 @@ mod tests {
     #[test]
     fn test_max_by() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Create test data: array of objects with prices
         let data = vec![
             {
-                let mut obj = HashMap::new();
+                let mut obj = IndexMap::new();
                 obj.insert("name".to_string(), Value::String("Laptop".to_string()));
                 obj.insert("price".to_string(), Value::Int(1200));
                 Value::Object(obj)
             },
             {
-                let mut obj = HashMap::new();
+                let mut obj = IndexMap::new();
                 obj.insert("name".to_string(), Value::String("Phone".to_string()));
                 obj.insert("price".to_string(), Value::Int(800));
                 Value::Object(obj)
             },
             {
-                let mut obj = HashMap::new();
+                let mut obj = IndexMap::new();
                 obj.insert("name".to_string(), Value::String("Book".to_string()));
                 obj.insert("price".to_string(), Value::Int(20));
                 Value::Object(obj)
@@ -266,7 +277,7 @@ mod tests {
         );
 
         let expected = {
-            let mut obj = HashMap::new();
+            let mut obj = IndexMap::new();
             obj.insert("name".to_string(), Value::String("Laptop".to_string()));
             obj.insert("price".to_string(), Value::Int(1200));
             Value::Object(obj)
@@ -302,7 +313,7 @@ mod tests {
         );
 
         let expected = {
-            let mut obj = std::collections::HashMap::new();
+            let mut obj = indexmap::IndexMap::new();
             obj.insert("name".to_string(), Value::String("Laptop".to_string()));
             obj.insert("price".to_string(), Value::Int(1200));
             obj.insert(
@@ -402,6 +413,84 @@ mod tests {
         assert_eq!(result.unwrap(), expected_range);
     }
 
+    #[test]
+    fn test_length_of_lazyframe() {
+        use polars::prelude::*;
+
+        let df = df! { "id" => [1i64, 2, 3] }.unwrap();
+        let input = Value::LazyFrame(Box::new(df.lazy()));
+        assert_eq!(execute_filter("length", &input).unwrap(), Value::Int(3));
+    }
+
+    #[test]
+    fn test_lowered_map_matches_row_by_row() {
+        use polars::prelude::*;
+
+        let df = df! {
+            "name" => ["Ada", "Grace", "Linus"],
+            "age" => [36i64, 45, 27],
+        }
+        .unwrap();
+        let rows = Value::Array(
+            [("Ada", 36), ("Grace", 45), ("Linus", 27)]
+                .into_iter()
+                .map(|(name, age)| {
+                    let mut obj = indexmap::IndexMap::new();
+                    obj.insert("name".to_string(), Value::String(name.to_string()));
+                    obj.insert("age".to_string(), Value::Int(age));
+                    Value::Object(obj)
+                })
+                .collect(),
+        );
+
+        // `.age / 2` runs as a Polars query on the frame and row by row on
+        // the array
+        let lowered = execute_filter("map(.age / 2)", &Value::DataFrame(df.clone())).unwrap();
+        let expected = Value::Array(vec![
+            Value::Float(18.0),
+            Value::Float(22.5),
+            Value::Float(13.5),
+        ]);
+        assert_eq!(lowered, expected);
+        assert_eq!(execute_filter("map(.age / 2)", &rows).unwrap(), expected);
+        assert!(execute_filter("map(.age / 0)", &Value::DataFrame(df.clone())).is_err());
+
+        // A null operand is an error row by row, so the query is not used
+        let df = df! {
+            "name" => ["Ada", "Grace"],
+            "age" => [Some(36i64), None],
+        }
+        .unwrap();
+        assert!(execute_filter("map(.age + 1)", &Value::DataFrame(df.clone())).is_err());
+
+        // Object outputs give an array whether or not the filter is lowered
+        let expected = Value::Array(
+            ["Ada", "Grace"]
+                .into_iter()
+                .map(|name| {
+                    let mut obj = indexmap::IndexMap::new();
+                    obj.insert("name".to_string(), Value::String(name.to_string()));
+                    Value::Object(obj)
+                })
+                .collect(),
+        );
+        let lowered = execute_filter("map({name})", &Value::DataFrame(df.clone())).unwrap();
+        assert_eq!(lowered, expected);
+        let input = Value::LazyFrame(Box::new(df.clone().lazy()));
+        assert_eq!(execute_filter("map({name})", &input).unwrap(), expected);
+
+        let row_by_row =
+            execute_filter(r#"map({name, parts: (.name | split("a"))})"#, &input).unwrap();
+        let Value::Array(row_by_row) = row_by_row else {
+            panic!("expected an array");
+        };
+        assert_eq!(row_by_row.len(), 2);
+        assert_eq!(
+            row_by_row[1].field("parts").unwrap(),
+            Value::Array(vec![Value::String("Gr".into()), Value::String("ce".into())])
+        );
+    }
+
     #[test]
     fn test_filter_on_dataframe() {
         use dsq_shared::value::Value;
@@ -570,7 +659,7 @@ mod tests {
     #[test]
     fn test_assign_update_compilation() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Test compilation of |= assignment
         let result = compile_filter(".field |= 42");
@@ -581,7 +670,7 @@ mod tests {
         );
 
         // Test execution of |= assignment on object
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("field".to_string(), Value::Int(10));
         obj.insert("other".to_string(), Value::String("unchanged".to_string()));
         let input = Value::Object(obj);
@@ -607,7 +696,7 @@ mod tests {
     #[test]
     fn test_assign_update_compilation_with_expression() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Test compilation of |= assignment with expression
         let result = compile_filter(".total |= .price + .tax");
@@ -618,7 +707,7 @@ mod tests {
         );
 
         // Test execution
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("price".to_string(), Value::Int(100));
         obj.insert("tax".to_string(), Value::Int(10));
         let input = Value::Object(obj);
@@ -642,10 +731,10 @@ mod tests {
     #[test]
     fn test_assign_update_compilation_string_field() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Test |= assignment with string value
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("status".to_string(), Value::String("pending".to_string()));
         let input = Value::Object(obj);
 
@@ -669,10 +758,10 @@ mod tests {
     #[test]
     fn test_assign_update_compilation_array_field() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Test |= assignment with array value
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert(
             "tags".to_string(),
             Value::Array(vec![Value::String("old".to_string())]),
@@ -709,10 +798,10 @@ mod tests {
     #[test]
     fn test_assign_update_compilation_in_pipeline() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Test |= assignment in a pipeline
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("salary".to_string(), Value::Int(50000));
         obj.insert("name".to_string(), Value::String("Alice".to_string()));
         let input = Value::Object(obj);
@@ -731,13 +820,13 @@ mod tests {
     #[test]
     fn test_assign_update_compilation_nested_object() {
         use dsq_shared::value::Value;
-        use std::collections::HashMap;
+        use indexmap::IndexMap;
 
         // Test |= assignment on nested object field
-        let mut address = HashMap::new();
+        let mut address = IndexMap::new();
         address.insert("city".to_string(), Value::String("NYC".to_string()));
 
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("address".to_string(), Value::Object(address));
         let input = Value::Object(obj);
 
@@ -787,4 +876,1068 @@ mod tests {
         let expected = Value::Array(vec![Value::Int(1), Value::Null, Value::Int(2)]);
         assert_eq!(result.unwrap(), expected);
     }
+
+    #[test]
+    fn test_execute_reduce() {
+        let input = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+
+        let result = execute_filter("reduce .[] as $x (0; . + $x)", &input).unwrap();
+        assert_eq!(result, Value::Int(6));
+
+        // Init is evaluated against the input, update against the accumulator
+        let result = execute_filter("reduce .[] as $x (length; . * $x)", &input).unwrap();
+        assert_eq!(result, Value::Int(18));
+
+        let result = execute_filter("reduce .b as $x (.a; . - $x)", &{
+            let mut obj = indexmap::IndexMap::new();
+            obj.insert("a".to_string(), Value::Int(10));
+            obj.insert("b".to_string(), Value::Int(3));
+            Value::Object(obj)
+        })
+        .unwrap();
+        assert_eq!(result, Value::Int(7));
+
+        // The binding doesn't leak out of the reduce
+        let result = execute_filter("reduce .[] as $x (0; . + $x) | $x", &input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_execute_reduce_over_dataframe() {
+        use polars::prelude::*;
+
+        let df = df! {
+            "name" => ["a", "b", "c"],
+            "qty" => [2i64, 3, 5],
+            "price" => [1.5f64, 2.0, 4.0],
+        }
+        .unwrap();
+        let input = Value::DataFrame(df);
+
+        // Column-sum fast path
+        let result = execute_filter("reduce .[] as $row (0; . + $row.qty)", &input).unwrap();
+        assert_eq!(result, Value::Int(10));
+        let result = execute_filter("reduce .[] as $row (1; $row.price + .)", &input).unwrap();
+        assert_eq!(result, Value::Float(8.5));
+
+        // General row-by-row fold
+        let result =
+            execute_filter("reduce .[] as $row (0; . + $row.qty * $row.price)", &input).unwrap();
+        assert_eq!(result, Value::Float(29.0));
+
+        let result = execute_filter("reduce .[] as $row (\"\"; . + $row.name)", &input).unwrap();
+        assert_eq!(result, Value::String("abc".to_string()));
+
+        // Column sums that overflow i64 are summed as floats
+        let df = df! { "n" => [i64::MAX, 1] }.unwrap();
+        let result =
+            execute_filter("reduce .[] as $row (0; . + $row.n)", &Value::DataFrame(df)).unwrap();
+        assert_eq!(result, Value::Float(i64::MAX as f64 + 1.0));
+        let df = df! { "n" => [2u64, 3] }.unwrap();
+        let result =
+            execute_filter("reduce .[] as $row (0; . + $row.n)", &Value::DataFrame(df)).unwrap();
+        assert_eq!(result, Value::Int(5));
+    }
+
+    #[test]
+    fn test_execute_foreach() {
+        let input = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+
+        let result = execute_filter("foreach .[] as $x (0; . + $x)", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::Int(1), Value::Int(3), Value::Int(6)])
+        );
+
+        let result = execute_filter("foreach .[] as $x (0; . + $x; [$x, .])", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Array(vec![Value::Int(1), Value::Int(1)]),
+                Value::Array(vec![Value::Int(2), Value::Int(3)]),
+                Value::Array(vec![Value::Int(3), Value::Int(6)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_execute_variable_binding() {
+        use indexmap::IndexMap;
+
+        let mut obj = IndexMap::new();
+        obj.insert("a".to_string(), Value::Int(2));
+        obj.insert("b".to_string(), Value::Int(5));
+        let input = Value::Object(obj);
+
+        let result = execute_filter(".a as $x | .b as $y | $x * $y", &input).unwrap();
+        assert_eq!(result, Value::Int(10));
+
+        // The body sees the binding's input, not the bound value
+        let result = execute_filter(".a as $x | .b + $x", &input).unwrap();
+        assert_eq!(result, Value::Int(7));
+
+        // Iterating sources run the body once per value
+        let input = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let result = execute_filter(".[] as $x | $x * length", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::Int(3), Value::Int(6), Value::Int(9)])
+        );
+
+        // Bindings are scoped to their body
+        let result = execute_filter("(. as $x | $x) | $x", &input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_execute_destructuring() {
+        let input: Value = serde_json::from_str::<serde_json::Value>(
+            r#"{"name": "ada", "tags": ["math", "code"], "meta": {"id": 7}, "key": "name"}"#,
+        )
+        .map(Value::from_json)
+        .unwrap();
+
+        let result = execute_filter(
+            ". as {name: $n, tags: [$first, $second]} | [$n, $first, $second]",
+            &input,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::String("ada".to_string()),
+                Value::String("math".to_string()),
+                Value::String("code".to_string()),
+            ])
+        );
+
+        let result = execute_filter(
+            ". as {$meta: {id: $id}, (.key): $k, missing: [$m]} | [$meta.id, $id, $k, $m]",
+            &input,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Int(7),
+                Value::Int(7),
+                Value::String("ada".to_string()),
+                Value::Null,
+            ])
+        );
+
+        let result = execute_filter(". as [$a] | $a", &input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_execute_alternative_patterns() {
+        let input: Value = serde_json::from_str::<serde_json::Value>(r#"[{"a": 1}, [2], 3]"#)
+            .map(Value::from_json)
+            .unwrap();
+
+        let result = execute_filter(".[] as {a: $a} ?// [$a] ?// $a | $a", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+        );
+
+        // Variables from other alternatives are null
+        let result = execute_filter(".[1] as {a: $a} ?// [$b] | [$a, $b]", &input).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Null, Value::Int(2)]));
+
+        // The last alternative's error is reported
+        let result = execute_filter(".[2] as {a: $a} ?// [$a] | $a", &input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_execute_function_definitions() {
+        let input = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+
+        let result = execute_filter("def double: . * 2; map(double)", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::Int(2), Value::Int(4), Value::Int(6)])
+        );
+
+        // Filter parameters are evaluated against the input at each use
+        let result = execute_filter("def twice(f): f | f; .[2] | twice(. * 3)", &input).unwrap();
+        assert_eq!(result, Value::Int(27));
+
+        // Value parameters are bound once, as both $x and x
+        let result = execute_filter("def add_to($n): map(. + $n); add_to(.[0])", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::Int(2), Value::Int(3), Value::Int(4)])
+        );
+        let result = execute_filter("def f($n): n + $n; f(length)", &input).unwrap();
+        assert_eq!(result, Value::Int(6));
+
+        // User definitions shadow builtins
+        let result = execute_filter("def length: 42; length", &input).unwrap();
+        assert_eq!(result, Value::Int(42));
+    }
+
+    #[test]
+    fn test_execute_function_scoping() {
+        // Inner definitions shadow outer ones only inside their scope
+        let result = execute_filter("def f: 1; def g: f; def f: 2; [f, g]", &Value::Null).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(2), Value::Int(1)]));
+
+        let result = execute_filter("def f: def g: 3; g * 2; f", &Value::Null).unwrap();
+        assert_eq!(result, Value::Int(6));
+
+        // Definitions are not visible outside their scope
+        let result = execute_filter("(def g: 3; g) | g", &Value::Null);
+        assert!(result.is_err());
+
+        // Closures resolve in the caller's scope
+        let result = execute_filter(
+            "def apply(f): def x: 100; f; def x: 1; apply(x)",
+            &Value::Null,
+        )
+        .unwrap();
+        assert_eq!(result, Value::Int(1));
+
+        // ... and see the caller's variables, not the function's
+        let result =
+            execute_filter("def f(g): 1 as $x | g; 2 as $x | f($x)", &Value::Null).unwrap();
+        assert_eq!(result, Value::Int(2));
+        let result = execute_filter(
+            "def f(g): 1 as $x | g, $x; def h(g): 3 as $x | f(g); 2 as $x | [h($x)]",
+            &Value::Null,
+        )
+        .unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(2), Value::Int(1)]));
+    }
+
+    #[test]
+    fn test_execute_recursive_functions() {
+        let result = execute_filter(
+            "def fact: if . <= 1 then 1 else . * (. - 1 | fact) end; fact",
+            &Value::Int(10),
+        )
+        .unwrap();
+        assert_eq!(result, Value::Int(3628800));
+
+        // Recursion passing a filter parameter through
+        let result = execute_filter(
+            "def apply_n(f; $n): if $n <= 0 then . else f | apply_n(f; $n - 1) end; apply_n(. * 2; 5)",
+            &Value::Int(1),
+        )
+        .unwrap();
+        assert_eq!(result, Value::Int(32));
+    }
+
+    #[test]
+    fn test_execute_recursion_depth_limit() {
+        let compiler = FilterCompiler::new();
+        let parsed = dsq_parser::FilterParser::new()
+            .parse("def forever: . + 1 | forever; forever")
+            .unwrap();
+        let mut ctx = CompilationContext::with_max_depth(32);
+        let compiled = compiler.compile_expr(&parsed.expr, &mut ctx).unwrap();
+
+        let executor = FilterExecutor::new();
+        let result = executor.execute_compiled(&compiled, Value::Int(0));
+        let Err(err) = result else {
+            panic!("unbounded recursion should fail");
+        };
+        assert!(
+            err.to_string().contains("Maximum recursion depth of 32"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_execute_alternative_operator() {
+        let input = Value::object(indexmap::IndexMap::from([
+            ("price".to_string(), Value::Null),
+            ("count".to_string(), Value::Int(0)),
+            ("active".to_string(), Value::Bool(false)),
+        ]));
+
+        let result = execute_filter(".price // 10", &input).unwrap();
+        assert_eq!(result, Value::Int(10));
+        let result = execute_filter(".count // 10", &input).unwrap();
+        assert_eq!(result, Value::Int(0));
+        let result = execute_filter(".active // \"n/a\"", &input).unwrap();
+        assert_eq!(result, Value::string("n/a"));
+        let result = execute_filter(".missing // .price // .count", &input).unwrap();
+        assert_eq!(result, Value::Int(0));
+
+        // Errors on the left fall back to the right
+        let result = execute_filter(".count.x // \"bad\"", &input).unwrap();
+        assert_eq!(result, Value::string("bad"));
+    }
+
+    #[test]
+    fn test_execute_alternative_over_dataframe() {
+        let df = polars::df! {
+            "price" => [Some(10i64), None, Some(30)],
+            "name" => [Some("a"), Some("b"), None],
+        }
+        .unwrap();
+        let input = Value::DataFrame(df);
+
+        let result = execute_filter(".price // 0", &input).unwrap();
+        let Value::Series(series) = result else {
+            panic!("expected a series, got {result:?}");
+        };
+        assert_eq!(
+            series.i64().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![Some(10), Some(0), Some(30)]
+        );
+
+        let result = execute_filter(". // \"unknown\"", &input).unwrap();
+        let Value::DataFrame(df) = result else {
+            panic!("expected a dataframe, got {result:?}");
+        };
+        assert_eq!(df.column("name").unwrap().null_count(), 0);
+        assert_eq!(df.column("price").unwrap().null_count(), 1);
+    }
+
+    #[test]
+    fn test_execute_optional_postfix() {
+        let input = Value::object(indexmap::IndexMap::from([
+            ("n".to_string(), Value::Int(1)),
+            (
+                "tags".to_string(),
+                Value::Array(vec![Value::string("x"), Value::string("y")]),
+            ),
+        ]));
+
+        assert!(execute_filter(".n.a", &input).is_err());
+        let result = execute_filter(".n.a?", &input).unwrap();
+        assert_eq!(result, Value::Null);
+        let result = execute_filter(".n.a? // \"none\"", &input).unwrap();
+        assert_eq!(result, Value::string("none"));
+        let result = execute_filter(".tags[]?", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::string("x"), Value::string("y")])
+        );
+    }
+
+    #[test]
+    fn test_execute_recursive_descent() {
+        let input = Value::object(indexmap::IndexMap::from([
+            ("id".to_string(), Value::Int(1)),
+            (
+                "items".to_string(),
+                Value::Array(vec![
+                    Value::object(indexmap::IndexMap::from([(
+                        "id".to_string(),
+                        Value::Int(2),
+                    )])),
+                    Value::string("x"),
+                ]),
+            ),
+        ]));
+
+        // Every id, regardless of depth
+        let result = execute_filter("[.. | .id? | select(. != null)]", &input).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(1), Value::Int(2)]));
+
+        let result = execute_filter("[recurse(.items[]?)] | length", &input).unwrap();
+        assert_eq!(result, Value::Int(3));
+
+        let result = execute_filter("leaf_paths", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Array(vec![Value::string("id")]),
+                Value::Array(vec![
+                    Value::string("items"),
+                    Value::Int(0),
+                    Value::string("id")
+                ]),
+                Value::Array(vec![Value::string("items"), Value::Int(1)]),
+            ])
+        );
+
+        let result = execute_filter("paths(type == \"string\")", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::string("items"), Value::Int(1)])
+        );
+
+        // The walks stop as soon as enough outputs have been taken
+        let result = execute_filter("first(paths)", &input).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::string("id")]));
+        let result = execute_filter("[limit(2; ..)] | .[1]", &input).unwrap();
+        assert_eq!(result, Value::Int(1));
+        let result = execute_filter("first(leaf_paths | select(length > 1))", &input).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::string("items"),
+                Value::Int(0),
+                Value::string("id")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_execute_walk() {
+        let input = Value::Array(vec![
+            Value::Int(1),
+            Value::Array(vec![Value::Int(3), Value::Int(2)]),
+        ]);
+
+        let result = execute_filter(
+            "walk(if type == \"integer\" then . * 10 else . end)",
+            &input,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Int(10),
+                Value::Array(vec![Value::Int(30), Value::Int(20)]),
+            ])
+        );
+
+        // Children are rewritten before their parent
+        let result =
+            execute_filter("walk(if type == \"array\" then length else . end)", &input).unwrap();
+        assert_eq!(result, Value::Int(2));
+    }
+
+    #[test]
+    fn test_execute_recurse_over_dataframe() {
+        use polars::prelude::*;
+
+        let tags = Series::new(
+            "tags".into(),
+            [
+                Series::new("".into(), ["a"]),
+                Series::new("".into(), ["b", "c"]),
+            ],
+        );
+        let df = DataFrame::new(vec![
+            Series::new("id".into(), [1i64, 2]).into(),
+            tags.into(),
+        ])
+        .unwrap();
+
+        let result =
+            execute_filter("[leaf_paths] | length", &Value::DataFrame(df.clone())).unwrap();
+        assert_eq!(result, Value::Int(5));
+
+        // Flat rows are rebuilt into a DataFrame
+        let flat = df.select(["id"]).unwrap();
+        let result = execute_filter(
+            "walk(if type == \"integer\" then . + 1 else . end) | .id",
+            &Value::DataFrame(flat),
+        )
+        .unwrap();
+        let Value::Series(ids) = result else {
+            panic!("Expected a column, got {:?}", result);
+        };
+        assert_eq!(ids.i64().unwrap().get(1), Some(3));
+    }
+
+    /// `{"a": [{"b": 1, "c": 2}], "x": "s"}`
+    fn path_sample() -> Value {
+        let item = Value::object(indexmap::IndexMap::from([
+            ("b".to_string(), Value::Int(1)),
+            ("c".to_string(), Value::Int(2)),
+        ]));
+        Value::object(indexmap::IndexMap::from([
+            ("a".to_string(), Value::Array(vec![item])),
+            ("x".to_string(), Value::string("s")),
+        ]))
+    }
+
+    /// Parse a JSON literal for expected values
+    fn json(text: &str) -> Value {
+        Value::from_json(serde_json::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn test_execute_path_expressions() {
+        let input = path_sample();
+        let cases = [
+            ("path(.a[0].b)", r#"["a", 0, "b"]"#),
+            ("path(.a[].b)", r#"["a", 0, "b"]"#),
+            ("path(.a | first)", r#"["a", 0]"#),
+            ("path(.a[0:1])", r#"["a", {"start": 0, "end": 1}]"#),
+            ("path(.a, .x)", r#"[["a"], ["x"]]"#),
+            (
+                "path(..)",
+                r#"[[], ["a"], ["a", 0], ["a", 0, "b"], ["a", 0, "c"], ["x"]]"#,
+            ),
+            ("path(.. | select(. == 2))", r#"["a", 0, "c"]"#),
+            ("path(.missing.deeper)", r#"["missing", "deeper"]"#),
+            ("path(getpath([\"a\", 0]))", r#"["a", 0]"#),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(
+                execute_filter(filter, &input).unwrap(),
+                json(expected),
+                "{}",
+                filter
+            );
+        }
+
+        let err = execute_filter("path(1)", &input).unwrap_err();
+        assert!(err.to_string().contains("Invalid path expression"));
+        assert!(execute_filter("path(.x[])", &input).is_err());
+    }
+
+    #[test]
+    fn test_execute_getpath_setpath_delpaths() {
+        let input = path_sample();
+        let cases = [
+            (r#"getpath(["a", 0, "b"])"#, "1"),
+            (r#"getpath(["nope", 1])"#, "null"),
+            (
+                r#"setpath(["a", 0, "b"]; 5)"#,
+                r#"{"a": [{"b": 5, "c": 2}], "x": "s"}"#,
+            ),
+            (
+                r#"["x"] as $p | setpath($p; .a | length)"#,
+                r#"{"a": [{"b": 1, "c": 2}], "x": 1}"#,
+            ),
+            (
+                r#"delpaths([["a", 0, "b"], ["x"]])"#,
+                r#"{"a": [{"c": 2}]}"#,
+            ),
+            ("del(.a[0].c, .x)", r#"{"a": [{"b": 1}]}"#),
+            ("del(.a[])", r#"{"a": [], "x": "s"}"#),
+            ("del(.. | select(. == 2))", r#"{"a": [{"b": 1}], "x": "s"}"#),
+            ("del(.missing)", r#"{"a": [{"b": 1, "c": 2}], "x": "s"}"#),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(
+                execute_filter(filter, &input).unwrap(),
+                json(expected),
+                "{}",
+                filter
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_entries() {
+        let input = json(r#"{"a": 1, "b": 2}"#);
+        let cases = [
+            (
+                "to_entries",
+                r#"[{"key": "a", "value": 1}, {"key": "b", "value": 2}]"#,
+            ),
+            ("to_entries | from_entries", r#"{"a": 1, "b": 2}"#),
+            (
+                r#"with_entries(.key |= "col_" + .key)"#,
+                r#"{"col_a": 1, "col_b": 2}"#,
+            ),
+            ("with_entries(select(.value > 1))", r#"{"b": 2}"#),
+            (
+                r#"[{name: "k", value: true}] | from_entries"#,
+                r#"{"k": true}"#,
+            ),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(
+                execute_filter(filter, &input).unwrap(),
+                json(expected),
+                "{}",
+                filter
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_del_dataframe_columns() {
+        use polars::prelude::*;
+
+        let df = df! { "a" => [1i64, 2], "b" => ["x", "y"], "c" => [true, false] }.unwrap();
+        let result = execute_filter("del(.b, .c)", &Value::DataFrame(df.clone())).unwrap();
+        let Value::DataFrame(out) = result else {
+            panic!("Expected DataFrame, got {:?}", result);
+        };
+        assert_eq!(out.get_column_names(), vec!["a"]);
+
+        let result = execute_filter("del(.[0])", &Value::DataFrame(df)).unwrap();
+        let Value::DataFrame(out) = result else {
+            panic!("Expected DataFrame, got {:?}", result);
+        };
+        assert_eq!(out.height(), 1);
+    }
+
+    #[test]
+    fn test_execute_string_interpolation() {
+        let input = json(r#"{"first": "Ada", "last": "Lovelace", "n": 3, "tags": ["a", "b,c"]}"#);
+        let cases = [
+            (r#""\(.first) \(.last)""#, r#""Ada Lovelace""#),
+            (r#""n=\(.n + 1), \(.tags)""#, r#""n=4, [\"a\",\"b,c\"]""#),
+            (r#"@csv "\(.tags)!""#, r#""\"a\",\"b,c\"!""#),
+            (r#"@uri "q=\(.last) \(.first)""#, r#""q=Lovelace Ada""#),
+            (r#"@json "v: \(.first)""#, r#""v: \"Ada\"""#),
+            (".tags | @tsv", r#""a\tb,c""#),
+            (".first | @base64 | @base64d", r#""Ada""#),
+            (
+                r#"[.first, .last] | @sh "echo \(.)""#,
+                r#""echo 'Ada' 'Lovelace'""#,
+            ),
+            (".tags | format(\"csv\")", r#""\"a\",\"b,c\"""#),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(
+                execute_filter(filter, &input).unwrap(),
+                json(expected),
+                "{}",
+                filter
+            );
+        }
+        assert!(execute_filter("@nope", &input).is_err());
+    }
+
+    #[test]
+    fn test_execute_string_interpolation_dataframe() {
+        use polars::prelude::*;
+
+        let df = df! {
+            "first" => ["Ada", "Alan"],
+            "age" => [Some(36i64), None],
+        }
+        .unwrap();
+        let input = Value::DataFrame(df);
+        let strings = |result: Value| -> Vec<Option<String>> {
+            let Value::Series(series) = result else {
+                panic!("Expected Series, got {:?}", result);
+            };
+            series
+                .str()
+                .unwrap()
+                .into_iter()
+                .map(|s| s.map(str::to_string))
+                .collect()
+        };
+
+        let result = execute_filter(r#""\(.first) is \(.age)""#, &input).unwrap();
+        assert_eq!(
+            strings(result),
+            vec![
+                Some("Ada is 36".to_string()),
+                Some("Alan is null".to_string())
+            ]
+        );
+
+        let result = execute_filter(r#"@html "<b>\(.first)</b>""#, &input).unwrap();
+        assert_eq!(
+            strings(result),
+            vec![
+                Some("<b>Ada</b>".to_string()),
+                Some("<b>Alan</b>".to_string())
+            ]
+        );
+
+        let result = execute_filter("@csv", &input).unwrap();
+        assert_eq!(
+            strings(result),
+            vec![
+                Some("\"Ada\",36".to_string()),
+                Some("\"Alan\",".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_regex() {
+        let input = json(r#"{"email": "ada@math.org", "text": "a1 b22"}"#);
+        let cases = [
+            (r#".email | test("\\.ORG$"; "i")"#, "true"),
+            (r#".email | test(["^x", "g"])"#, "false"),
+            (
+                r#".email | capture("(?<user>[^@]+)@(?<host>.+)")"#,
+                r#"{"user": "ada", "host": "math.org"}"#,
+            ),
+            (r#".text | match("\\d+").offset"#, "1"),
+            (r#"[.text | match("\\d+"; "g")] | length"#, "2"),
+            (r#".text | scan("([a-z])\\d+")"#, r#"[["a"], ["b"]]"#),
+            (r#".text | splits(" ")"#, r#"["a1", "b22"]"#),
+            (
+                r#".email | sub("(?<user>[^@]+)@"; "\(.user | toupper) at ")"#,
+                r#""ADA at math.org""#,
+            ),
+            (r#".text | gsub("\\d"; "*")"#, r#""a* b**""#),
+            (
+                r#".text | gsub("(?<d>\\d)"; "<\(.d)>"; "g")"#,
+                r#""a<1> b<2><2>""#,
+            ),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(
+                execute_filter(filter, &input).unwrap(),
+                json(expected),
+                "{}",
+                filter
+            );
+        }
+        assert!(execute_filter(r#".email | test("a"; "z")"#, &input).is_err());
+    }
+
+    #[test]
+    fn test_execute_try_catch() {
+        let input = json(r#"{"a": {"b": 1}, "items": [1, 2, 3, 4]}"#);
+        let cases = [
+            (r#"try error("boom") catch ."#, r#""boom""#),
+            (
+                r#"try error({"code": 3, "reason": "bad"}) catch .reason"#,
+                r#""bad""#,
+            ),
+            (r#"try error("boom")"#, "null"),
+            (r#"try .a catch "unused""#, r#"{"b": 1}"#),
+            (r#"try (.items | error) catch length"#, "4"),
+            (r#"error("boom") // "fallback""#, r#""fallback""#),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(
+                execute_filter(filter, &input).unwrap(),
+                json(expected),
+                "{}",
+                filter
+            );
+        }
+        let err = execute_filter(r#"error({"code": 3})"#, &input).unwrap_err();
+        assert!(err.to_string().contains("(not a string)"));
+    }
+
+    #[test]
+    fn test_execute_label_break() {
+        let input = json("[1, 2, 3, 4]");
+        let cases = [
+            ("label $out | (1, 2, break $out, 3)", "[1, 2]"),
+            (
+                "label $out | foreach .[] as $x (0; . + $x; if . > 5 then break $out else . end)",
+                "[1, 3]",
+            ),
+            (
+                "label $out | .[] as $x | if $x > 2 then break $out else $x end",
+                "[1, 2]",
+            ),
+            // `try` does not stop a break
+            (r#"label $out | try (1, break $out) catch "caught""#, "1"),
+            ("label $out | label $in | (1, break $out)", "1"),
+            ("label $out | break $out", "null"),
+            ("label $out | length", "4"),
+        ];
+        for (filter, expected) in cases {
+            assert_eq!(
+                execute_filter(filter, &input).unwrap(),
+                json(expected),
+                "{}",
+                filter
+            );
+        }
+        let err = execute_filter("break $out", &input).unwrap_err();
+        assert!(err.to_string().contains("$*label-out is not defined"));
+    }
+
+    #[test]
+    fn test_execute_output_streams() {
+        let input = json(r#"[1, 2, 3, "x"]"#);
+        // Each case lists every output of the filter
+        let cases = [
+            (".[0:3][] | . * 2", "[2, 4, 6]"),
+            (".[0:3][] | select(. > 1)", "[2, 3]"),
+            ("[.[0:3][] | select(. > 1)]", "[[2, 3]]"),
+            ("empty", "[]"),
+            (
+                "[.[] | if . == 2 then empty else . end]",
+                r#"[[1, 3, "x"]]"#,
+            ),
+            ("label $f | .[] | (., break $f)", "[1]"),
+            // Later elements are never evaluated once the label is left
+            ("label $f | .[] | . + 1 | (., break $f)", "[2]"),
+            (
+                "{a: (1, 2), b: (3, 4)}",
+                r#"[{"a": 1, "b": 3}, {"a": 1, "b": 4}, {"a": 2, "b": 3}, {"a": 2, "b": 4}]"#,
+            ),
+            ("(1, 2) + (10, 20)", "[11, 12, 21, 22]"),
+            ("(1, 2) as $x | $x * 10", "[10, 20]"),
+            ("map(select(. != 2))", r#"[[1, 3, "x"]]"#),
+            ("try (.[] | . - 1)", "[0, 1, 2]"),
+            (".[] | (null, false, .) // 0", r#"[1, 2, 3, "x"]"#),
+        ];
+        let mut executor = FilterExecutor::new();
+        for (filter, expected) in cases {
+            let result = executor.execute_str(filter, input.clone()).unwrap();
+            assert_eq!(Value::Array(result.outputs), json(expected), "{}", filter);
+        }
+    }
+
+    #[test]
+    fn test_execute_control_generators() {
+        let input = json(r#"[1, 2, 3]"#);
+        // Each case lists every output of the filter
+        let cases = [
+            ("limit(2; .[])", "[1, 2]"),
+            ("limit(0; .[])", "[]"),
+            ("limit(5; .[])", "[1, 2, 3]"),
+            ("[limit(3; repeat(.))] | length", "[3]"),
+            ("1 | [limit(4; repeat(. * 2))]", "[[1, 2, 4, 8]]"),
+            ("first(.[] | select(. > 1))", "[2]"),
+            ("first(empty)", "[]"),
+            ("last(.[])", "[3]"),
+            ("nth(1; .[])", "[2]"),
+            ("nth(5; .[])", "[]"),
+            ("nth(2)", "[3]"),
+            ("1 | until(. > 100; . * 2)", "[128]"),
+            ("1 | [while(. < 20; . * 3)]", "[[1, 3, 9]]"),
+            ("range(3)", "[0, 1, 2]"),
+            ("range(1; 3)", "[1, 2]"),
+            ("range(0; 10; 4)", "[0, 4, 8]"),
+            ("range(5; 0; -2)", "[5, 3, 1]"),
+            ("range(0; 1; 0.5)", "[0.0, 0.5]"),
+            ("range((1, 2); 3)", "[1, 2, 2]"),
+            // Infinite generators stop as soon as enough outputs are read
+            ("first(range(1; 9223372036854775807))", "[1]"),
+            ("label $f | repeat(.) | (., break $f)", "[[1, 2, 3]]"),
+            ("0 | until(. == 100000; . + 1)", "[100000]"),
+        ];
+        let mut executor = FilterExecutor::new();
+        for (filter, expected) in cases {
+            let result = executor.execute_str(filter, input.clone()).unwrap();
+            assert_eq!(Value::Array(result.outputs), json(expected), "{}", filter);
+        }
+        assert!(executor
+            .execute_str("limit(-1; .[])", input.clone())
+            .is_err());
+        assert!(executor.execute_str("range(0; 1; 0)", input).is_err());
+    }
+
+    #[test]
+    fn test_execute_preserves_key_order() {
+        let input = json(r#"{"zeta": 1, "alpha": {"y": 2, "x": 3}}"#);
+        let cases = [
+            (".", r#"{"zeta":1,"alpha":{"y":2,"x":3}}"#),
+            ("{b: .zeta, a: .alpha.x}", r#"{"b":1,"a":3}"#),
+            ("keys_unsorted", r#"["zeta","alpha"]"#),
+            ("keys", r#"["alpha","zeta"]"#),
+            ("[.[]] | length", "2"),
+            ("[to_entries | .[] | .key]", r#"["zeta","alpha"]"#),
+            (
+                "del(.zeta) | .omega |= 4",
+                r#"{"alpha":{"y":2,"x":3},"omega":4}"#,
+            ),
+        ];
+        let mut executor = FilterExecutor::new();
+        for (filter, expected) in cases {
+            let result = executor.execute_str(filter, input.clone()).unwrap();
+            let text = serde_json::to_string(&result.into_value()).unwrap();
+            assert_eq!(text, expected, "{}", filter);
+        }
+    }
+
+    #[test]
+    fn test_execute_timeout_inside_loops() {
+        use dsq_shared::error::LimitExceeded;
+
+        let config = ExecutorConfig {
+            timeout_ms: Some(50),
+            error_mode: ErrorMode::Ignore,
+            ..Default::default()
+        };
+        let mut executor = FilterExecutor::with_config(config);
+        // Neither produces an output before the timeout, and neither `try`
+        // nor the error mode swallows it
+        for filter in [
+            "0 | until(. < 0; . + 1)",
+            "try reduce range(1; 9223372036854775807) as $i (0; . + 1)",
+        ] {
+            let err = executor.execute_str(filter, Value::Null).unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<LimitExceeded>(),
+                Some(&LimitExceeded::Timeout { timeout_ms: 50 }),
+                "{}",
+                filter
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_input_cursor() {
+        let documents = (1..=4).map(|i| Ok(json(&format!(r#"{{"n": {}}}"#, i))));
+        let mut executor = FilterExecutor::new();
+        executor.set_inputs(InputCursor::new(documents));
+
+        let result = executor.execute_str("input | .n", Value::Null).unwrap();
+        assert_eq!(result.outputs, vec![Value::Int(1)]);
+        // The cursor is shared by later executions
+        let result = executor
+            .execute_str("reduce inputs as $doc (0; . + $doc.n)", Value::Null)
+            .unwrap();
+        assert_eq!(result.outputs, vec![Value::Int(9)]);
+        let result = executor.execute_str("[inputs]", Value::Null).unwrap();
+        assert_eq!(result.outputs, vec![json("[]")]);
+        let err = executor.execute_str("input", Value::Null).unwrap_err();
+        assert!(err.to_string().contains("No more inputs"));
+    }
+
+    #[test]
+    fn test_execute_regex_dataframe_columns() {
+        use polars::prelude::*;
+
+        let df = df! {
+            "name" => ["alice", "Bob", "carol"],
+            "n" => [1i64, 2, 3],
+        }
+        .unwrap();
+        let input = Value::DataFrame(df);
+        let result = execute_filter(
+            r#"map(select(.name | test("^[ab]"; "i"))) | length"#,
+            &input,
+        )
+        .unwrap();
+        assert_eq!(result, Value::Int(2));
+
+        let result = execute_filter(r#".name | gsub("[aeiou]"; "_")"#, &input).unwrap();
+        let Value::Series(series) = result else {
+            panic!("Expected Series, got {:?}", result);
+        };
+        let names: Vec<_> = series.str().unwrap().into_iter().flatten().collect();
+        assert_eq!(names, vec!["_l_c_", "B_b", "c_r_l"]);
+    }
+
+    /// Write `files` into a fresh library directory
+    fn module_library(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (name, contents) in files {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    fn execute_with_library(dir: &tempfile::TempDir, filter: &str, input: Value) -> Result<Value> {
+        let config = ExecutorConfig {
+            library_paths: vec![dir.path().to_path_buf()],
+            ..Default::default()
+        };
+        FilterExecutor::with_config(config)
+            .execute_str(filter, input)
+            .map(ExecutionResult::into_value)
+    }
+
+    #[test]
+    fn test_execute_module_imports() {
+        let lib = module_library(&[
+            (
+                "util.dsq",
+                "# Shared helpers\ninclude \"helpers\";\ndef inc: . + 1;\ndef fact: if . <= 1 then 1 else . * (. - 1 | fact) end;\n",
+            ),
+            ("helpers.jq", "def double: . * 2;"),
+            ("stats/stats.jq", "import \"util\" as u; def double_it: . * 2; def bump: u::inc | double_it;"),
+        ]);
+
+        // Imported functions are namespaced, included ones are re-exported
+        let result = execute_with_library(
+            &lib,
+            "import \"util\" as util; [util::inc, util::fact, util::double]",
+            Value::Int(4),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::Int(5), Value::Int(24), Value::Int(8)])
+        );
+
+        // Include brings definitions in unqualified
+        let result =
+            execute_with_library(&lib, "include \"util\"; inc | double", Value::Int(1)).unwrap();
+        assert_eq!(result, Value::Int(4));
+
+        // Modules import other modules (lib/lib.jq layout)
+        let result =
+            execute_with_library(&lib, "import \"stats\" as s; s::bump", Value::Int(1)).unwrap();
+        assert_eq!(result, Value::Int(4));
+
+        // A module's own imports are not visible to the importer
+        let err = execute_with_library(&lib, "import \"stats\" as s; s::u::inc", Value::Int(1))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("s::u::inc/0 is not defined"),
+            "unexpected error: {err}"
+        );
+        let err = execute_with_library(&lib, "import \"util\" as util; util::nope", Value::Int(1))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("util::nope/0 is not defined"),
+            "unexpected error: {err}"
+        );
+
+        let err =
+            execute_with_library(&lib, "import \"missing\" as m; .", Value::Null).unwrap_err();
+        assert!(
+            err.to_string().contains("Module not found: \"missing\""),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_execute_data_modules() {
+        let lib = module_library(&[
+            ("codes.json", r#"{"US": "United States"}"#),
+            ("people.csv", "id,name\n1,Alice\n2,Bob\n"),
+        ]);
+
+        let result = execute_with_library(
+            &lib,
+            "import \"codes\" as $codes; [$codes.US, $codes::codes.US]",
+            Value::Null,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::string("United States"),
+                Value::string("United States")
+            ])
+        );
+
+        let result = execute_with_library(
+            &lib,
+            "import \"people\" as $people; $people | length",
+            Value::Null,
+        )
+        .unwrap();
+        assert_eq!(result, Value::Int(2));
+    }
+
+    #[test]
+    fn test_execute_configured_modules() {
+        let lib = module_library(&[
+            ("util.dsq", "def inc: . + 1;"),
+            ("common.dsq", "def dec: . - 1;"),
+        ]);
+        let config = ExecutorConfig {
+            library_paths: vec![lib.path().to_path_buf()],
+            imports: vec!["util".to_string()],
+            includes: vec![lib.path().join("common.dsq")],
+            ..Default::default()
+        };
+        let mut executor = FilterExecutor::with_config(config);
+        let result = executor
+            .execute_str("util::inc | util::inc | dec", Value::Int(1))
+            .unwrap();
+        assert_eq!(result.into_value(), Value::Int(2));
+    }
+
+    #[test]
+    fn test_execute_circular_module_import() {
+        let lib = module_library(&[
+            ("a.dsq", "import \"b\" as b; def f: 1;"),
+            ("b.dsq", "import \"a\" as a; def g: 2;"),
+        ]);
+        let err = execute_with_library(&lib, "import \"a\" as a; a::f", Value::Null).unwrap_err();
+        assert!(
+            err.to_string().contains("Circular module import"),
+            "unexpected error: {err}"
+        );
+    }
 }
@@ -0,0 +1,247 @@
+//! Module resolution for `import` and `include` directives
+//!
+//! This module locates library modules and data files on the library search
+//! path and loads data modules. Compiling the definitions of a loaded module
+//! is left to the [`FilterCompiler`](crate::FilterCompiler).
+
+use dsq_parser::{Expr, Literal, ObjectEntry};
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// File extensions tried for code modules, in order
+const MODULE_EXTENSIONS: &[&str] = &["dsq", "jq"];
+
+/// File extensions tried for data modules, in order
+const DATA_EXTENSIONS: &[&str] = &["json", "csv"];
+
+/// Resolves module paths against the library search path
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ModuleLoader {
+    /// Directories searched after any `search` metadata, in order
+    search_paths: Vec<PathBuf>,
+}
+
+impl ModuleLoader {
+    /// Create a loader searching `library_paths` followed by the default
+    /// locations (`~/.dsq` and `$ORIGIN/../lib/dsq`)
+    pub fn new(library_paths: &[PathBuf]) -> Self {
+        let mut search_paths: Vec<PathBuf> = library_paths
+            .iter()
+            .map(|p| expand_search_path(p))
+            .collect();
+        search_paths.extend(Self::default_search_paths());
+        Self { search_paths }
+    }
+
+    /// The default library locations: `~/.dsq` and `../lib/dsq` relative to
+    /// the directory of the running executable
+    pub fn default_search_paths() -> Vec<PathBuf> {
+        [Path::new("~/.dsq"), Path::new("$ORIGIN/../lib/dsq")]
+            .iter()
+            .map(|p| expand_search_path(p))
+            .collect()
+    }
+
+    /// Directories searched for modules, in order
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+
+    /// Find the file for the code module `path` (`lib` resolves to
+    /// `lib.dsq`, `lib.jq`, `lib/lib.dsq` or `lib/lib.jq`)
+    ///
+    /// `origin` is the file containing the directive, if any; its directory
+    /// is searched first and anchors relative `search` metadata.
+    pub fn resolve_module(
+        &self,
+        path: &str,
+        origin: Option<&Path>,
+        metadata: Option<&Expr>,
+    ) -> Result<PathBuf> {
+        let candidates = |dir: &Path| {
+            let base = dir.join(path);
+            let mut files = Vec::new();
+            if has_extension(&base, MODULE_EXTENSIONS) {
+                files.push(base.clone());
+            }
+            let stem = base.file_name().map(|s| s.to_os_string());
+            for ext in MODULE_EXTENSIONS {
+                files.push(base.with_extension(ext));
+            }
+            if let Some(stem) = stem {
+                for ext in MODULE_EXTENSIONS {
+                    files.push(base.join(&stem).with_extension(ext));
+                }
+            }
+            files
+        };
+        self.resolve(path, origin, metadata, candidates)
+            .ok_or_else(|| {
+                dsq_shared::error::operation_error(format!(
+                    "Module not found: \"{}\" (searched {})",
+                    path,
+                    self.describe_search(origin, metadata)
+                ))
+            })
+    }
+
+    /// Find the file for the data module `path` (`data` resolves to
+    /// `data.json` or `data.csv`)
+    pub fn resolve_data(
+        &self,
+        path: &str,
+        origin: Option<&Path>,
+        metadata: Option<&Expr>,
+    ) -> Result<PathBuf> {
+        let candidates = |dir: &Path| {
+            let base = dir.join(path);
+            if has_extension(&base, DATA_EXTENSIONS) {
+                vec![base]
+            } else {
+                DATA_EXTENSIONS
+                    .iter()
+                    .map(|ext| base.with_extension(ext))
+                    .collect()
+            }
+        };
+        self.resolve(path, origin, metadata, candidates)
+            .ok_or_else(|| {
+                dsq_shared::error::operation_error(format!(
+                    "Data module not found: \"{}\" (searched {})",
+                    path,
+                    self.describe_search(origin, metadata)
+                ))
+            })
+    }
+
+    /// Load a JSON or CSV data module; CSV files load as a DataFrame
+    pub fn load_data(path: &Path) -> Result<Value> {
+        let is_csv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        if is_csv {
+            let df = CsvReadOptions::default()
+                .with_has_header(true)
+                .try_into_reader_with_file_path(Some(path.to_path_buf()))
+                .and_then(|reader| reader.finish())
+                .map_err(|e| {
+                    dsq_shared::error::operation_error(format!(
+                        "Failed to load data module {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+            return Ok(Value::DataFrame(df));
+        }
+
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            dsq_shared::error::operation_error(format!(
+                "Failed to read data module {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| {
+            dsq_shared::error::operation_error(format!(
+                "Invalid JSON in data module {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Value::from_json(json))
+    }
+
+    /// Try `candidates` in each directory of the search order
+    fn resolve(
+        &self,
+        path: &str,
+        origin: Option<&Path>,
+        metadata: Option<&Expr>,
+        candidates: impl Fn(&Path) -> Vec<PathBuf>,
+    ) -> Option<PathBuf> {
+        if Path::new(path).is_absolute() {
+            return candidates(Path::new("/")).into_iter().find(|f| f.is_file());
+        }
+        self.search_order(origin, metadata)
+            .iter()
+            .flat_map(|dir| candidates(dir))
+            .find(|f| f.is_file())
+    }
+
+    /// Directories to search for a directive in `origin` with `metadata`
+    fn search_order(&self, origin: Option<&Path>, metadata: Option<&Expr>) -> Vec<PathBuf> {
+        let base = origin
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let mut dirs: Vec<PathBuf> = metadata_search_paths(metadata)
+            .into_iter()
+            .map(|p| base.join(expand_search_path(Path::new(&p))))
+            .collect();
+        if origin.is_some() {
+            dirs.push(base);
+        }
+        dirs.extend(self.search_paths.iter().cloned());
+        dirs
+    }
+
+    fn describe_search(&self, origin: Option<&Path>, metadata: Option<&Expr>) -> String {
+        self.search_order(origin, metadata)
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Whether `path` ends in one of `extensions`
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext))
+}
+
+/// Expand a leading `~` to the home directory and a leading `$ORIGIN` to the
+/// directory of the running executable
+pub fn expand_search_path(path: &Path) -> PathBuf {
+    let mut components = path.components();
+    let first = components.next().and_then(|c| c.as_os_str().to_str());
+    let prefix = match first {
+        Some("~") => std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(PathBuf::from),
+        Some("$ORIGIN") => std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf)),
+        _ => None,
+    };
+    match prefix {
+        Some(prefix) => prefix.join(components.as_path()),
+        None => path.to_path_buf(),
+    }
+}
+
+/// The `search` entry of a directive's metadata (a string or an array of
+/// strings)
+fn metadata_search_paths(metadata: Option<&Expr>) -> Vec<String> {
+    let Some(Expr::Object { pairs }) = metadata else {
+        return Vec::new();
+    };
+    let search = pairs.iter().find_map(|entry| match entry {
+        ObjectEntry::KeyValue { key, value } if key == "search" => Some(value),
+        _ => None,
+    });
+    match search {
+        Some(Expr::Literal(Literal::String(path))) => vec![path.clone()],
+        Some(Expr::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Expr::Literal(Literal::String(path)) => Some(path.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
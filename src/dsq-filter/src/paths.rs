@@ -0,0 +1,285 @@
+//! Path expressions
+//!
+//! `path(f)` and `del(f)` need the locations `f` selects rather than the
+//! values found there. The [`FilterCompiler`](crate::FilterCompiler) lowers
+//! such an `f` to a [`PathExpr`], which evaluates to `(path, value)` pairs
+//! relative to its input.
+
+use dsq_functions::builtin::delpaths::delete_paths;
+use dsq_functions::builtin::getpath::{get_key, get_path};
+use dsq_functions::builtin::paths::value_paths;
+use dsq_functions::builtin::recurse::child_entries;
+use dsq_shared::ops::{collapse, collect_outputs, emit_all, Context, Emit, Operation};
+use dsq_shared::value::{is_truthy, Value};
+use dsq_shared::Result;
+use indexmap::IndexMap;
+use std::ops::ControlFlow;
+
+type Ops = Vec<Box<dyn Operation + Send + Sync>>;
+
+/// A path and the value found at it
+type PathValue = (Vec<Value>, Value);
+
+/// How a path expression steps from a value to its children
+pub enum PathKey {
+    /// A fixed field name or array index
+    Key(Value),
+    /// `.[f]`, with `f` evaluated against the input of the whole term
+    Index(Ops),
+    /// `.[start:end]`
+    Slice(Option<Ops>, Option<Ops>),
+    /// `.[]`
+    Iterate,
+}
+
+/// A filter lowered to the paths it selects
+pub enum PathExpr {
+    /// `.`
+    Identity,
+    /// A step below the paths of `base`
+    Access { base: Box<PathExpr>, key: PathKey },
+    /// `a | b`
+    Pipe(Box<PathExpr>, Box<PathExpr>),
+    /// `a, b`
+    Comma(Vec<PathExpr>),
+    /// `..`
+    Recurse,
+    /// `select(f)`
+    Select(Ops),
+    /// `getpath(p)`
+    GetPath(Ops),
+    /// `f?`
+    Try(Box<PathExpr>),
+    /// `if c then a else b end`
+    If {
+        condition: Ops,
+        then_branch: Box<PathExpr>,
+        else_branch: Box<PathExpr>,
+    },
+    /// `a // b`
+    Alternative(Box<PathExpr>, Box<PathExpr>),
+}
+
+/// Evaluate `ops` against `value`
+fn eval(ops: &Ops, value: &Value, context: &mut Option<&mut dyn Context>) -> Result<Value> {
+    let mut current = value.clone();
+    for op in ops {
+        current = op.apply_with_context(&current, context)?;
+    }
+    Ok(current)
+}
+
+impl PathExpr {
+    /// The paths selected below `input`, with the value at each
+    pub fn paths(
+        &self,
+        input: &Value,
+        context: &mut Option<&mut dyn Context>,
+    ) -> Result<Vec<PathValue>> {
+        match self {
+            PathExpr::Identity => Ok(vec![(Vec::new(), input.clone())]),
+            PathExpr::Access { base, key } => {
+                let parents = base.paths(input, context)?;
+                let mut out = Vec::new();
+                match key {
+                    PathKey::Key(key) => {
+                        for (mut path, value) in parents {
+                            let child = get_key(&value, key)?;
+                            path.push(key.clone());
+                            out.push((path, child));
+                        }
+                    }
+                    PathKey::Index(ops) => {
+                        let key = eval(ops, input, context)?;
+                        for (mut path, value) in parents {
+                            let child = get_key(&value, &key)?;
+                            path.push(key.clone());
+                            out.push((path, child));
+                        }
+                    }
+                    PathKey::Slice(start, end) => {
+                        let mut bounds = IndexMap::new();
+                        for (name, ops) in [("start", start), ("end", end)] {
+                            let bound = match ops {
+                                Some(ops) => eval(ops, input, context)?,
+                                None => Value::Null,
+                            };
+                            bounds.insert(name.to_string(), bound);
+                        }
+                        let key = Value::Object(bounds);
+                        for (mut path, value) in parents {
+                            let child = get_key(&value, &key)?;
+                            path.push(key.clone());
+                            out.push((path, child));
+                        }
+                    }
+                    PathKey::Iterate => {
+                        for (path, value) in parents {
+                            if !matches!(
+                                value,
+                                Value::Null
+                                    | Value::Array(_)
+                                    | Value::Object(_)
+                                    | Value::DataFrame(_)
+                                    | Value::LazyFrame(_)
+                                    | Value::Series(_)
+                            ) {
+                                return Err(dsq_shared::error::operation_error(format!(
+                                    "Cannot iterate over {}",
+                                    value.type_name()
+                                )));
+                            }
+                            for (key, child) in child_entries(&value)? {
+                                let mut child_path = path.clone();
+                                child_path.push(key);
+                                out.push((child_path, child));
+                            }
+                        }
+                    }
+                }
+                Ok(out)
+            }
+            PathExpr::Pipe(a, b) => {
+                let mut out = Vec::new();
+                for (prefix, value) in a.paths(input, context)? {
+                    for (suffix, child) in b.paths(&value, context)? {
+                        let mut path = prefix.clone();
+                        path.extend(suffix);
+                        out.push((path, child));
+                    }
+                }
+                Ok(out)
+            }
+            PathExpr::Comma(exprs) => {
+                let mut out = Vec::new();
+                for expr in exprs {
+                    out.extend(expr.paths(input, context)?);
+                }
+                Ok(out)
+            }
+            PathExpr::Recurse => {
+                let mut out = vec![(Vec::new(), input.clone())];
+                out.extend(value_paths(input)?);
+                Ok(out)
+            }
+            PathExpr::Select(condition) => {
+                if is_truthy(&eval(condition, input, context)?) {
+                    Ok(vec![(Vec::new(), input.clone())])
+                } else {
+                    Ok(Vec::new())
+                }
+            }
+            PathExpr::GetPath(ops) => match eval(ops, input, context)? {
+                Value::Array(path) => {
+                    let value = get_path(input, &path)?;
+                    Ok(vec![(path, value)])
+                }
+                other => Err(dsq_shared::error::operation_error(format!(
+                    "Path must be specified as an array, got {}",
+                    other.type_name()
+                ))),
+            },
+            PathExpr::Try(inner) => Ok(inner.paths(input, context).unwrap_or_default()),
+            PathExpr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if is_truthy(&eval(condition, input, context)?) {
+                    then_branch.paths(input, context)
+                } else {
+                    else_branch.paths(input, context)
+                }
+            }
+            PathExpr::Alternative(a, b) => {
+                let found: Vec<PathValue> = a
+                    .paths(input, context)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(_, value)| is_truthy(value))
+                    .collect();
+                if found.is_empty() {
+                    b.paths(input, context)
+                } else {
+                    Ok(found)
+                }
+            }
+        }
+    }
+}
+
+/// Operation for `path(f)`
+///
+/// Yields the path of each value selected by `f`.
+pub struct PathOperation {
+    pub path: PathExpr,
+}
+
+impl Operation for PathOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let paths = self.path.paths(value, context)?;
+        emit_all(
+            paths.into_iter().map(|(path, _)| Value::Array(path)),
+            context,
+            emit,
+        )
+    }
+
+    fn description(&self) -> String {
+        "path".to_string()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Operation for `del(f)`: removes every path selected by `f`
+pub struct DeletePathsOperation {
+    pub path: PathExpr,
+}
+
+impl Operation for DeletePathsOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+    ) -> Result<Value> {
+        let paths: Vec<Vec<Value>> = self
+            .path
+            .paths(value, context)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        delete_paths(value, &paths)
+    }
+
+    fn description(&self) -> String {
+        "delete paths".to_string()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
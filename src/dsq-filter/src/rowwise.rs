@@ -0,0 +1,724 @@
+//! Row-wise filters lowered to Polars expressions
+//!
+//! `map(f)`, `select(f)` and `filter(f)` on a DataFrame run `f` once per
+//! row, converting every row to a [`Value`](dsq_shared::value::Value)
+//! object first. When `f` only reads fields and combines them with
+//! arithmetic, comparisons, `and`/`or`/`not`, `if`, `//` and a few string
+//! and math builtins, the [`FilterCompiler`](crate::FilterCompiler) lowers
+//! it to a [`RowExpr`], which becomes a single Polars expression over the
+//! whole frame once the frame's schema is known. Anything else, and any
+//! expression Polars rejects for the schema, runs row by row as before.
+
+use std::cell::RefCell;
+
+use dsq_parser::{BinaryOperator, Expr, Literal, ObjectEntry, UnaryOperator};
+use polars::prelude::{
+    as_struct, col, lit, when, DataType, Expr as PolarsExpr, LazyFrame, RoundMode, NULL,
+};
+
+/// A row-wise filter in the subset that can run as a Polars expression
+#[derive(Debug, Clone)]
+pub enum RowExpr {
+    /// `.`
+    Identity,
+    /// `.field` on the output of `base`
+    Field(Box<RowExpr>, String),
+    /// A literal scalar
+    Literal(Literal),
+    /// `{key: value, ...}`
+    Object(Vec<(String, RowExpr)>),
+    /// `left op right`
+    Binary(Box<RowExpr>, BinaryOperator, Box<RowExpr>),
+    /// `if condition then a else b end`
+    If(Box<RowExpr>, Box<RowExpr>, Box<RowExpr>),
+    /// `first | second`
+    Pipe(Box<RowExpr>, Box<RowExpr>),
+    /// `select(condition)`
+    Select(Box<RowExpr>),
+    /// A builtin applied to the input
+    Call(RowFunction),
+}
+
+/// Builtins with a Polars equivalent
+#[derive(Debug, Clone)]
+pub enum RowFunction {
+    /// `not`
+    Not,
+    /// `length` of a string, array or null
+    Length,
+    /// `ascii_downcase`
+    Downcase,
+    /// `ascii_upcase`
+    Upcase,
+    /// `tostring` of a string, integer or boolean
+    ToString,
+    /// `test("regex")`
+    Test(String),
+    /// `contains("text")` on a string
+    Contains(String),
+    /// `floor`
+    Floor,
+    /// `ceil`
+    Ceil,
+    /// `round`
+    Round,
+    /// `abs`
+    Abs,
+    /// `sqrt`
+    Sqrt,
+}
+
+impl RowExpr {
+    /// Lower `expr` if it is in the supported subset; `is_builtin` tells
+    /// whether a call of the given name and arity resolves to the builtin
+    /// rather than a user-defined function
+    pub fn lower(expr: &Expr, is_builtin: &dyn Fn(&str, usize) -> bool) -> Option<RowExpr> {
+        let lower = |expr: &Expr| RowExpr::lower(expr, is_builtin).map(Box::new);
+        Some(match expr {
+            Expr::Identity => RowExpr::Identity,
+            Expr::Paren(inner) => return RowExpr::lower(inner, is_builtin),
+            Expr::FieldAccess { base, fields } => {
+                fields.iter().fold(*lower(base)?, |base, field| {
+                    RowExpr::Field(Box::new(base), field.clone())
+                })
+            }
+            Expr::ArrayAccess { array, index } => match index.as_ref() {
+                Expr::Literal(Literal::String(key)) => RowExpr::Field(lower(array)?, key.clone()),
+                _ => return None,
+            },
+            Expr::Literal(Literal::BigInt(_)) => return None,
+            Expr::Literal(literal) => RowExpr::Literal(literal.clone()),
+            Expr::Object { pairs } => RowExpr::Object(
+                pairs
+                    .iter()
+                    .map(|entry| match entry {
+                        ObjectEntry::KeyValue { key, value } => {
+                            Some((key.clone(), RowExpr::lower(value, is_builtin)?))
+                        }
+                        ObjectEntry::Shorthand(key) => Some((
+                            key.clone(),
+                            RowExpr::Field(Box::new(RowExpr::Identity), key.clone()),
+                        )),
+                    })
+                    .collect::<Option<_>>()?,
+            ),
+            // Division by zero is an error row by row, so only a non-zero
+            // literal divisor is lowered
+            Expr::BinaryOp {
+                op: BinaryOperator::Div,
+                right,
+                ..
+            } if !is_nonzero_literal(right) => return None,
+            Expr::BinaryOp { left, op, right } => {
+                RowExpr::Binary(lower(left)?, op.clone(), lower(right)?)
+            }
+            Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => RowExpr::Pipe(lower(expr)?, Box::new(RowExpr::Call(RowFunction::Not))),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => RowExpr::If(lower(condition)?, lower(then_branch)?, lower(else_branch)?),
+            Expr::Pipeline(stages) => {
+                let (first, rest) = stages.split_first()?;
+                rest.iter().try_fold(*lower(first)?, |input, stage| {
+                    Some(RowExpr::Pipe(Box::new(input), lower(stage)?))
+                })?
+            }
+            Expr::Identifier(name) if is_builtin(name, 0) => RowExpr::call(name, &[], is_builtin)?,
+            Expr::FunctionCall { name, args } if is_builtin(name, args.len()) => {
+                RowExpr::call(name, args, is_builtin)?
+            }
+            _ => return None,
+        })
+    }
+
+    fn call(
+        name: &str,
+        args: &[Expr],
+        is_builtin: &dyn Fn(&str, usize) -> bool,
+    ) -> Option<RowExpr> {
+        let function = match (name, args) {
+            ("select", [condition]) => {
+                return Some(RowExpr::Select(Box::new(RowExpr::lower(
+                    condition, is_builtin,
+                )?)))
+            }
+            ("not", []) => RowFunction::Not,
+            ("length", []) => RowFunction::Length,
+            ("ascii_downcase", []) => RowFunction::Downcase,
+            ("ascii_upcase", []) => RowFunction::Upcase,
+            ("tostring", []) => RowFunction::ToString,
+            ("test", [Expr::Literal(Literal::String(pattern))]) => {
+                RowFunction::Test(pattern.clone())
+            }
+            ("contains", [Expr::Literal(Literal::String(text))]) => {
+                RowFunction::Contains(text.clone())
+            }
+            ("floor", []) => RowFunction::Floor,
+            ("ceil", []) => RowFunction::Ceil,
+            ("round", []) => RowFunction::Round,
+            ("abs", []) => RowFunction::Abs,
+            ("sqrt", []) => RowFunction::Sqrt,
+            _ => return None,
+        };
+        Some(RowExpr::Call(function))
+    }
+
+    /// `map(self)` over `frame`, or `None` when Polars cannot evaluate it
+    /// for the frame's schema
+    pub fn map(&self, frame: &LazyFrame) -> Option<Mapped> {
+        let planner = Planner::new(frame);
+        let mut predicate = None;
+        let output = planner.eval(self, &Lowered::Row, &mut predicate)?;
+        let frame = match predicate {
+            Some(predicate) => frame.clone().filter(predicate),
+            None => frame.clone(),
+        };
+        let mapped = match output {
+            Lowered::Row => Mapped::Objects(frame),
+            Lowered::Object(fields) => Mapped::Objects(frame.select(aliased(fields))),
+            Lowered::Scalar(expr) => Mapped::Values(frame.select([expr.alias("value")])),
+        };
+        mapped.checked()
+    }
+
+    /// `select(self)` over `frame`, or `None` when Polars cannot evaluate it
+    /// for the frame's schema
+    pub fn select(&self, frame: &LazyFrame) -> Option<LazyFrame> {
+        let planner = Planner::new(frame);
+        let mut predicate = None;
+        let condition = match planner.eval(self, &Lowered::Row, &mut predicate)? {
+            Lowered::Scalar(expr) => Some(planner.truthy(expr)?),
+            // Objects are always truthy
+            Lowered::Row | Lowered::Object(_) => None,
+        };
+        let frame = match both(predicate, condition) {
+            Some(mask) => frame.clone().filter(mask),
+            None => frame.clone(),
+        };
+        match Mapped::Frame(frame).checked()? {
+            Mapped::Frame(frame) => Some(frame),
+            Mapped::Objects(_) | Mapped::Values(_) => None,
+        }
+    }
+
+    /// Whether the data of `frame` makes the lowered expression differ from
+    /// row-by-row evaluation, which errors on null operands of arithmetic
+    /// and does not wrap integers around on overflow
+    pub fn runs_row_by_row(&self, frame: &LazyFrame) -> bool {
+        let planner = Planner::new(frame);
+        if planner.eval(self, &Lowered::Row, &mut None).is_none() {
+            return true;
+        }
+        let Some(guard) = planner.guard.into_inner() else {
+            return false;
+        };
+        let flagged = frame
+            .clone()
+            .select([guard.any(true).alias("guard")])
+            .collect();
+        flagged
+            .ok()
+            .and_then(|df| {
+                let column = df.column("guard").ok()?.as_materialized_series().clone();
+                column.bool().ok()?.get(0)
+            })
+            .unwrap_or(true)
+    }
+}
+
+/// Result of a lowered `map`, `select` or `filter`
+pub enum Mapped {
+    /// The rows a `select` or `filter` keeps
+    Frame(LazyFrame),
+    /// One output object per row, with a column per key
+    Objects(LazyFrame),
+    /// A single `value` column holding one output value per row
+    Values(LazyFrame),
+}
+
+impl Mapped {
+    /// Keep the plan only if Polars accepts it for the input schema
+    fn checked(self) -> Option<Self> {
+        let mut frame = match &self {
+            Mapped::Frame(frame) | Mapped::Objects(frame) | Mapped::Values(frame) => frame.clone(),
+        };
+        frame.collect_schema().ok()?;
+        Some(self)
+    }
+}
+
+/// A value produced by a lowered filter for every row
+#[derive(Clone)]
+enum Lowered {
+    /// The row itself
+    Row,
+    /// An object with one expression per key
+    Object(Vec<(String, PolarsExpr)>),
+    /// Any other value
+    Scalar(PolarsExpr),
+}
+
+/// Lowers [`RowExpr`]s against the schema of a frame
+struct Planner<'a> {
+    frame: &'a LazyFrame,
+    /// True for the rows where the lowered arithmetic would not match
+    /// row-by-row evaluation
+    guard: RefCell<Option<PolarsExpr>>,
+}
+
+impl<'a> Planner<'a> {
+    fn new(frame: &'a LazyFrame) -> Self {
+        Planner {
+            frame,
+            guard: RefCell::new(None),
+        }
+    }
+
+    /// Evaluate `expr` on `input`, adding the conditions of any `select` to
+    /// `predicate`
+    fn eval(
+        &self,
+        expr: &RowExpr,
+        input: &Lowered,
+        predicate: &mut Option<PolarsExpr>,
+    ) -> Option<Lowered> {
+        Some(match expr {
+            RowExpr::Identity => input.clone(),
+            RowExpr::Pipe(first, second) => {
+                let input = self.eval(first, input, predicate)?;
+                self.eval(second, &input, predicate)?
+            }
+            RowExpr::Select(condition) => {
+                let condition = self.scalar(condition, input)?;
+                let condition = self.truthy(condition)?;
+                *predicate = both(predicate.take(), Some(condition));
+                input.clone()
+            }
+            RowExpr::Object(entries) => {
+                let mut fields: Vec<(String, PolarsExpr)> = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let value = self.scalar(value, input)?;
+                    // A repeated key keeps its first position and last value
+                    match fields.iter_mut().find(|(existing, _)| existing == key) {
+                        Some(field) => field.1 = value,
+                        None => fields.push((key.clone(), value)),
+                    }
+                }
+                Lowered::Object(fields)
+            }
+            _ => Lowered::Scalar(self.scalar(expr, input)?),
+        })
+    }
+
+    /// Evaluate `expr` on `input` where no `select` may drop the row, as
+    /// inside an object or an operand
+    fn value(&self, expr: &RowExpr, input: &Lowered) -> Option<Lowered> {
+        let mut predicate = None;
+        let value = self.eval(expr, input, &mut predicate)?;
+        predicate.is_none().then_some(value)
+    }
+
+    /// Evaluate `expr` on `input` to a single expression
+    fn scalar(&self, expr: &RowExpr, input: &Lowered) -> Option<PolarsExpr> {
+        Some(match expr {
+            RowExpr::Field(base, field) => {
+                let base = self.value(base, input)?;
+                self.field(base, field)?
+            }
+            RowExpr::Literal(literal) => match literal {
+                Literal::Int(i) => lit(*i),
+                Literal::Float(f) => lit(*f),
+                Literal::String(s) => lit(s.clone()),
+                Literal::Bool(b) => lit(*b),
+                Literal::Null => lit(NULL),
+                Literal::BigInt(_) => return None,
+            },
+            RowExpr::Binary(left, op, right) => {
+                let left = self.scalar(left, input)?;
+                let right = self.scalar(right, input)?;
+                self.binary(left, op, right)?
+            }
+            RowExpr::If(condition, then_branch, else_branch) => {
+                let condition = self.truthy(self.scalar(condition, input)?)?;
+                when(condition)
+                    .then(self.scalar(then_branch, input)?)
+                    .otherwise(self.scalar(else_branch, input)?)
+            }
+            RowExpr::Call(function) => self.call(function, self.to_scalar(input.clone()))?,
+            RowExpr::Identity | RowExpr::Object(_) | RowExpr::Pipe(_, _) | RowExpr::Select(_) => {
+                self.to_scalar(self.value(expr, input)?)
+            }
+        })
+    }
+
+    /// A row or object as a struct expression
+    fn to_scalar(&self, value: Lowered) -> PolarsExpr {
+        match value {
+            Lowered::Row => {
+                let mut frame = self.frame.clone();
+                let columns = frame
+                    .collect_schema()
+                    .map(|schema| schema.iter_names().map(|name| col(name.clone())).collect())
+                    .unwrap_or_default();
+                as_struct(columns)
+            }
+            Lowered::Object(fields) => as_struct(aliased(fields)),
+            Lowered::Scalar(expr) => expr,
+        }
+    }
+
+    /// `.field` of `base`; missing fields are null
+    fn field(&self, base: Lowered, field: &str) -> Option<PolarsExpr> {
+        match base {
+            Lowered::Row => {
+                let mut frame = self.frame.clone();
+                let schema = frame.collect_schema().ok()?;
+                Some(if schema.contains(field) {
+                    col(field)
+                } else {
+                    lit(NULL)
+                })
+            }
+            Lowered::Object(fields) => Some(
+                fields
+                    .into_iter()
+                    .find(|(key, _)| key == field)
+                    .map_or(lit(NULL), |(_, expr)| expr),
+            ),
+            Lowered::Scalar(expr) => match self.dtype(&expr)? {
+                DataType::Struct(fields) if fields.iter().any(|f| f.name() == field) => {
+                    Some(expr.struct_().field_by_name(field))
+                }
+                DataType::Struct(_) | DataType::Null => Some(lit(NULL)),
+                _ => None,
+            },
+        }
+    }
+
+    fn binary(
+        &self,
+        left: PolarsExpr,
+        op: &BinaryOperator,
+        right: PolarsExpr,
+    ) -> Option<PolarsExpr> {
+        let (left_type, right_type) = (self.dtype(&left)?, self.dtype(&right)?);
+        let numeric = left_type.is_primitive_numeric() && right_type.is_primitive_numeric();
+        Some(match op {
+            BinaryOperator::Add if left_type.is_string() && right_type.is_string() => {
+                self.guard_nulls(&left, &right);
+                left + right
+            }
+            // `/` always gives a float, where Polars floors integers
+            BinaryOperator::Div if numeric => {
+                let (left, right) = (left.cast(DataType::Float64), right.cast(DataType::Float64));
+                self.guard_nulls(&left, &right);
+                left / right
+            }
+            BinaryOperator::Add | BinaryOperator::Sub | BinaryOperator::Mul if numeric => {
+                // Row by row every integer is an i64 and every float an f64
+                let widen = |expr: PolarsExpr, dtype: &DataType| {
+                    if dtype.is_integer() {
+                        expr.cast(DataType::Int64)
+                    } else {
+                        expr.cast(DataType::Float64)
+                    }
+                };
+                let (left, right) = (widen(left, &left_type), widen(right, &right_type));
+                self.guard_nulls(&left, &right);
+                if left_type.is_integer() && right_type.is_integer() {
+                    // Estimated in floats, with room for their rounding
+                    let estimate = arithmetic(
+                        left.clone().cast(DataType::Float64),
+                        op,
+                        right.clone().cast(DataType::Float64),
+                    );
+                    self.guard(estimate.abs().gt_eq(lit(2f64.powi(62))));
+                }
+                arithmetic(left, op, right)
+            }
+            BinaryOperator::Gt
+            | BinaryOperator::Lt
+            | BinaryOperator::Eq
+            | BinaryOperator::Ne
+            | BinaryOperator::Ge
+            | BinaryOperator::Le
+                if comparable(&left_type, &right_type) =>
+            {
+                // Null sorts before every other value
+                let both_present = left.clone().is_not_null().and(right.clone().is_not_null());
+                let by_rank = compare(left.clone().is_not_null(), op, right.clone().is_not_null());
+                when(both_present)
+                    .then(compare(left, op, right))
+                    .otherwise(by_rank)
+            }
+            BinaryOperator::And => self.truthy(left)?.and(self.truthy(right)?),
+            BinaryOperator::Or => self.truthy(left)?.or(self.truthy(right)?),
+            BinaryOperator::Alternative => {
+                when(self.truthy(left.clone())?).then(left).otherwise(right)
+            }
+            _ => return None,
+        })
+    }
+
+    fn call(&self, function: &RowFunction, input: PolarsExpr) -> Option<PolarsExpr> {
+        let dtype = self.dtype(&input)?;
+        let numeric = dtype.is_primitive_numeric();
+        Some(match function {
+            RowFunction::Not => self.truthy(input)?.not(),
+            RowFunction::Length => match dtype {
+                DataType::String => input.str().len_chars().cast(DataType::Int64),
+                DataType::List(_) => input.list().len().cast(DataType::Int64),
+                DataType::Null => lit(0i64),
+                _ => return None,
+            }
+            .fill_null(lit(0i64)),
+            RowFunction::Downcase if dtype.is_string() => input.str().to_lowercase(),
+            RowFunction::Upcase if dtype.is_string() => input.str().to_uppercase(),
+            RowFunction::ToString => {
+                let text = match dtype {
+                    DataType::String => input.clone(),
+                    DataType::Boolean | DataType::Int64 | DataType::Int32 => {
+                        input.clone().cast(DataType::String)
+                    }
+                    _ => return None,
+                };
+                when(input.is_null()).then(lit("null")).otherwise(text)
+            }
+            RowFunction::Test(pattern) if dtype.is_string() => {
+                input.str().contains(lit(pattern.clone()), true)
+            }
+            RowFunction::Contains(text) if dtype.is_string() => {
+                input.str().contains_literal(lit(text.clone()))
+            }
+            RowFunction::Floor if dtype.is_float() => input.floor(),
+            RowFunction::Ceil if dtype.is_float() => input.ceil(),
+            RowFunction::Round if dtype.is_float() => input
+                .round(0, RoundMode::HalfAwayFromZero)
+                .cast(DataType::Int64),
+            RowFunction::Floor | RowFunction::Ceil | RowFunction::Round if dtype.is_integer() => {
+                input
+            }
+            RowFunction::Abs if numeric => input.abs(),
+            RowFunction::Sqrt if numeric => input.sqrt(),
+            _ => return None,
+        })
+    }
+
+    /// Whether each value is truthy: neither null nor false
+    fn truthy(&self, expr: PolarsExpr) -> Option<PolarsExpr> {
+        Some(match self.dtype(&expr)? {
+            DataType::Boolean => expr.fill_null(lit(false)),
+            DataType::Null => lit(false),
+            _ => expr.is_not_null(),
+        })
+    }
+
+    /// Run the filter row by row on frames where `rows` holds for some row
+    fn guard(&self, rows: PolarsExpr) {
+        let mut guard = self.guard.borrow_mut();
+        *guard = Some(match guard.take() {
+            Some(guard) => guard.or(rows),
+            None => rows,
+        });
+    }
+
+    /// Run the filter row by row on frames where an operand is null, since
+    /// row-by-row arithmetic on null is an error
+    fn guard_nulls(&self, left: &PolarsExpr, right: &PolarsExpr) {
+        self.guard(left.clone().is_null().or(right.clone().is_null()));
+    }
+
+    /// The dtype `expr` evaluates to on the frame, or `None` if Polars
+    /// rejects it
+    fn dtype(&self, expr: &PolarsExpr) -> Option<DataType> {
+        let mut frame = self.frame.clone().select([expr.clone().alias("dtype")]);
+        let schema = frame.collect_schema().ok()?;
+        schema.get("dtype").cloned()
+    }
+}
+
+fn aliased(fields: Vec<(String, PolarsExpr)>) -> Vec<PolarsExpr> {
+    fields
+        .into_iter()
+        .map(|(key, expr)| expr.alias(key))
+        .collect()
+}
+
+fn both(a: Option<PolarsExpr>, b: Option<PolarsExpr>) -> Option<PolarsExpr> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.and(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Whether values of the two dtypes compare the same way in Polars and jq
+fn comparable(a: &DataType, b: &DataType) -> bool {
+    match (a, b) {
+        (DataType::Null, _) | (_, DataType::Null) => true,
+        (a, b) if a.is_primitive_numeric() && b.is_primitive_numeric() => true,
+        (DataType::String, DataType::String) | (DataType::Boolean, DataType::Boolean) => true,
+        (DataType::Date, DataType::Date) => true,
+        _ => false,
+    }
+}
+
+fn is_nonzero_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(Literal::Int(n)) => *n != 0,
+        Expr::Literal(Literal::Float(f)) => *f != 0.0,
+        _ => false,
+    }
+}
+
+fn arithmetic(left: PolarsExpr, op: &BinaryOperator, right: PolarsExpr) -> PolarsExpr {
+    match op {
+        BinaryOperator::Sub => left - right,
+        BinaryOperator::Mul => left * right,
+        BinaryOperator::Div => left / right,
+        _ => left + right,
+    }
+}
+
+fn compare(left: PolarsExpr, op: &BinaryOperator, right: PolarsExpr) -> PolarsExpr {
+    match op {
+        BinaryOperator::Gt => left.gt(right),
+        BinaryOperator::Lt => left.lt(right),
+        BinaryOperator::Ge => left.gt_eq(right),
+        BinaryOperator::Le => left.lt_eq(right),
+        BinaryOperator::Ne => left.neq(right),
+        _ => left.eq(right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsq_parser::FilterParser;
+    use polars::prelude::{df, IntoLazy};
+
+    fn lower(filter: &str) -> Option<RowExpr> {
+        let parsed = FilterParser::new().parse(filter).unwrap();
+        RowExpr::lower(&parsed.expr, &|_, _| true)
+    }
+
+    fn frame() -> LazyFrame {
+        df! {
+            "name" => ["Ada", "Grace", "Linus"],
+            "age" => [Some(36i64), None, Some(28)],
+            "salary" => [1000.0, 2000.0, 3000.0],
+        }
+        .unwrap()
+        .lazy()
+    }
+
+    #[test]
+    fn test_lower_supported_subset() {
+        assert!(lower("select(.age > 30) | {name, bonus: .salary * 0.1}").is_some());
+        assert!(lower("if .age then .age // 0 else -1 end").is_some());
+        assert!(lower(".name | ascii_downcase | length").is_some());
+        assert!(lower(".[0]").is_none());
+        assert!(lower("$x").is_none());
+        assert!(lower(".name | split(\"a\")").is_none());
+    }
+
+    #[test]
+    fn test_map_select_object() {
+        let expr = lower("select(.age > 30) | {name, bonus: .salary * 0.1}").unwrap();
+        let Some(Mapped::Objects(frame)) = expr.map(&frame()) else {
+            panic!("expected objects");
+        };
+        let df = frame.collect().unwrap();
+        assert_eq!(df.get_column_names(), vec!["name", "bonus"]);
+        assert_eq!(df.height(), 1);
+        assert_eq!(
+            df.column("name").unwrap().str().unwrap().get(0),
+            Some("Ada")
+        );
+    }
+
+    #[test]
+    fn test_null_ordering() {
+        // Null sorts before numbers, so a missing age is less than 30
+        let expr = lower(".age < 30").unwrap();
+        let frame = expr.select(&frame()).unwrap().collect().unwrap();
+        let names: Vec<_> = frame
+            .column("name")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(names, vec![Some("Grace"), Some("Linus")]);
+    }
+
+    #[test]
+    fn test_map_values() {
+        let expr = lower(".age // 0").unwrap();
+        let Some(Mapped::Values(frame)) = expr.map(&frame()) else {
+            panic!("expected values");
+        };
+        let df = frame.collect().unwrap();
+        let ages: Vec<_> = df
+            .column("value")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(ages, vec![Some(36), Some(0), Some(28)]);
+    }
+
+    #[test]
+    fn test_type_mismatch_is_not_lowered() {
+        let expr = lower(".name > 3").unwrap();
+        assert!(expr.select(&frame()).is_none());
+        let expr = lower(".name - .age").unwrap();
+        assert!(expr.map(&frame()).is_none());
+    }
+
+    #[test]
+    fn test_division() {
+        assert!(lower(".salary / 0").is_none());
+        assert!(lower(".salary / .age").is_none());
+        let expr = lower(".salary / 8").unwrap();
+        assert!(!expr.runs_row_by_row(&frame()));
+        let Some(Mapped::Values(frame)) = expr.map(&frame()) else {
+            panic!("expected values");
+        };
+        let df = frame.collect().unwrap();
+        let salaries: Vec<_> = df
+            .column("value")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(salaries, vec![Some(125.0), Some(250.0), Some(375.0)]);
+    }
+
+    #[test]
+    fn test_null_operand_runs_row_by_row() {
+        // Grace has no age, and `null + 1` is an error row by row
+        let expr = lower(".age + 1").unwrap();
+        assert!(expr.map(&frame()).is_some());
+        assert!(expr.runs_row_by_row(&frame()));
+        assert!(lower(".age / 8").unwrap().runs_row_by_row(&frame()));
+
+        let ages = df! { "age" => [36i64, 28] }.unwrap().lazy();
+        assert!(!expr.runs_row_by_row(&ages));
+        assert!(!lower(".age // 0").unwrap().runs_row_by_row(&frame()));
+    }
+
+    #[test]
+    fn test_overflow_runs_row_by_row() {
+        let frame = df! { "n" => [1i64, i64::MAX] }.unwrap().lazy();
+        assert!(lower(".n + 1").unwrap().runs_row_by_row(&frame));
+        assert!(lower(".n * 2").unwrap().runs_row_by_row(&frame));
+        assert!(!lower(".n + 1.5").unwrap().runs_row_by_row(&frame));
+        assert!(!lower(".n > 1").unwrap().runs_row_by_row(&frame));
+    }
+}
@@ -0,0 +1,109 @@
+//! Running filters over an input read in batches
+//!
+//! A filter can run on each batch of rows separately when its output for
+//! the whole input is the concatenation of its outputs for the batches.
+//! That holds for a pipeline of `.`, `map(f)`, `select(f)` and `filter(f)`
+//! stages, whose output is a collection of rows, and for a pipeline that
+//! iterates the rows with `.[]`, after which every stage sees one row at a
+//! time. Anything else, such as `sort_by`, `group_by` or `length`, needs
+//! the whole input.
+
+use dsq_parser::{Expr, FilterParser};
+use dsq_shared::Result;
+
+/// What a filter run on a batch of rows produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchOutput {
+    /// A collection of rows; the rows of all batches form one collection
+    Rows,
+    /// Separate output documents, as after `.[]`
+    Documents,
+}
+
+/// Check that `filter` can run on each batch of an input separately, and
+/// what it produces for a batch
+pub fn batch_output(filter: &str) -> Result<BatchOutput> {
+    let parsed = FilterParser::new()
+        .parse(filter)
+        .map_err(|e| dsq_shared::error::operation_error(format!("{e}")))?;
+    let mut stages = Vec::new();
+    flatten(&parsed.expr, &mut stages)?;
+    for stage in stages {
+        match stage {
+            Expr::Identity => {}
+            Expr::FunctionCall { name, args }
+                if args.len() == 1 && matches!(name.as_str(), "map" | "select" | "filter") => {}
+            Expr::ArrayIteration(base) if matches!(**base, Expr::Identity) => {
+                return Ok(BatchOutput::Documents);
+            }
+            Expr::FieldAccess { base, .. } if is_iteration(base) => {
+                return Ok(BatchOutput::Documents);
+            }
+            _ => {
+                return Err(dsq_shared::error::operation_error(format!(
+                    "`{stage}` needs the whole input, so it cannot run in streaming mode"
+                )))
+            }
+        }
+    }
+    Ok(BatchOutput::Rows)
+}
+
+/// Collect the stages of the top-level pipeline of `expr`
+fn flatten<'a>(expr: &'a Expr, stages: &mut Vec<&'a Expr>) -> Result<()> {
+    match expr {
+        Expr::Pipeline(exprs) => {
+            for expr in exprs {
+                flatten(expr, stages)?;
+            }
+        }
+        Expr::Paren(inner) => flatten(inner, stages)?,
+        // Definitions are row-local as long as the expression using them is
+        Expr::Def { rest, .. } => flatten(rest, stages)?,
+        _ => stages.push(expr),
+    }
+    Ok(())
+}
+
+/// Whether `expr` is `.[]`
+fn is_iteration(expr: &Expr) -> bool {
+    matches!(expr, Expr::ArrayIteration(base) if matches!(**base, Expr::Identity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_local_pipelines() {
+        assert_eq!(batch_output(".").unwrap(), BatchOutput::Rows);
+        assert_eq!(
+            batch_output("map(select(.age > 40)) | select(.city == \"London\")").unwrap(),
+            BatchOutput::Rows
+        );
+        assert_eq!(
+            batch_output("def adult: .age >= 18; filter(adult)").unwrap(),
+            BatchOutput::Rows
+        );
+    }
+
+    #[test]
+    fn test_iterated_pipelines() {
+        assert_eq!(batch_output(".[]").unwrap(), BatchOutput::Documents);
+        assert_eq!(
+            batch_output("map(.a) | .[] | . * 2 | sort").unwrap(),
+            BatchOutput::Documents
+        );
+        assert_eq!(batch_output(".[].name").unwrap(), BatchOutput::Documents);
+    }
+
+    #[test]
+    fn test_global_operations_are_rejected() {
+        for filter in ["sort_by(.age)", "map(.a) | group_by(.b)", "length", ".[0]"] {
+            let error = batch_output(filter).unwrap_err().to_string();
+            assert!(error.contains("cannot run in streaming mode"), "{error}");
+        }
+        let error = batch_output("map(.a) | sort_by(.age)").unwrap_err();
+        assert!(error.to_string().contains("`sort_by(.age)`"), "{error}");
+    }
+}
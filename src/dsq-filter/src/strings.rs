@@ -0,0 +1,217 @@
+//! String interpolation and `@format` strings
+//!
+//! `"\(.first) \(.last)"` evaluates each interpolation against the input and
+//! splices the results into the text, rendering them through a format when
+//! one is given (`@csv "\(.row)"`). On a DataFrame, interpolations that yield
+//! columns are combined with Polars `concat_str` instead of row by row.
+
+use dsq_functions::builtin::format::{format_any, format_value};
+use dsq_shared::ops::{Context, Operation};
+use dsq_shared::value::{df_row_to_value, Value};
+use dsq_shared::Result;
+use polars::prelude::*;
+
+type Ops = Vec<Box<dyn Operation + Send + Sync>>;
+
+/// A compiled piece of an interpolated string
+pub enum FormatPart {
+    /// Literal text
+    Literal(String),
+    /// `\(f)`
+    Interpolation(Ops),
+}
+
+/// Operation for `"text \(f)"` and `@fmt "text \(f)"`
+pub struct FormatStringOperation {
+    /// Format applied to each interpolated value; `None` renders as `tostring`
+    pub format: Option<String>,
+    pub parts: Vec<FormatPart>,
+}
+
+/// Evaluate `ops` against `value`
+fn eval(ops: &Ops, value: &Value, context: &mut Option<&mut dyn Context>) -> Result<Value> {
+    let mut current = value.clone();
+    for op in ops {
+        current = op.apply_with_context(&current, context)?;
+    }
+    Ok(current)
+}
+
+fn polars_error(e: PolarsError) -> anyhow::Error {
+    dsq_shared::error::operation_error(format!("String interpolation failed: {}", e))
+}
+
+impl FormatStringOperation {
+    fn format_name(&self) -> &str {
+        self.format.as_deref().unwrap_or("text")
+    }
+
+    /// Evaluate every interpolation against `value`
+    fn interpolations(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+    ) -> Result<Vec<Value>> {
+        self.parts
+            .iter()
+            .filter_map(|part| match part {
+                FormatPart::Interpolation(ops) => Some(eval(ops, value, context)),
+                FormatPart::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// Splice the rendered `values` between the literal parts
+    fn render(&self, values: &[Value]) -> Result<String> {
+        let mut values = values.iter();
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                FormatPart::Literal(text) => out.push_str(text),
+                FormatPart::Interpolation(_) => {
+                    if let Some(value) = values.next() {
+                        out.push_str(&format_value(self.format_name(), value)?);
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Build the strings for every row of `df` with `concat_str`, when each
+    /// interpolation yielded a column of `df` or a scalar
+    fn vectorized(&self, df: &DataFrame, values: Vec<Value>) -> Result<Option<Value>> {
+        let mut values = values.into_iter();
+        let mut columns = Vec::new();
+        let mut exprs = Vec::new();
+        let mut name = None;
+        for part in &self.parts {
+            match part {
+                FormatPart::Literal(text) => exprs.push(lit(text.clone())),
+                FormatPart::Interpolation(_) => match values.next() {
+                    Some(Value::Series(series)) if series.len() == df.height() => {
+                        name.get_or_insert_with(|| series.name().clone());
+                        let alias = format!("__part{}", columns.len());
+                        let plain = self.format_name() == "text"
+                            && (series.dtype().is_primitive_numeric()
+                                || matches!(series.dtype(), DataType::String | DataType::Boolean));
+                        if plain {
+                            exprs.push(
+                                col(alias.as_str())
+                                    .cast(DataType::String)
+                                    .fill_null(lit("null")),
+                            );
+                            columns.push(series.with_name(alias.into()).into_column());
+                        } else {
+                            let Value::Series(rendered) =
+                                format_any(self.format_name(), &Value::Series(series.clone()))?
+                            else {
+                                return Ok(None);
+                            };
+                            if rendered.null_count() > 0 {
+                                return Ok(None);
+                            }
+                            exprs.push(col(alias.as_str()));
+                            columns.push(rendered.with_name(alias.into()).into_column());
+                        }
+                    }
+                    Some(
+                        scalar @ (Value::Null
+                        | Value::Bool(_)
+                        | Value::Int(_)
+                        | Value::BigInt(_)
+                        | Value::Float(_)
+                        | Value::String(_)),
+                    ) => exprs.push(lit(format_value(self.format_name(), &scalar)?)),
+                    _ => return Ok(None),
+                },
+            }
+        }
+        let Some(name) = name else {
+            return Ok(None);
+        };
+        let result = DataFrame::new(columns)
+            .map_err(polars_error)?
+            .lazy()
+            .select([concat_str(exprs, "", false).alias(name)])
+            .collect()
+            .map_err(polars_error)?;
+        Ok(Some(Value::Series(
+            result.get_columns()[0].as_materialized_series().clone(),
+        )))
+    }
+
+    /// Evaluate the string for each row of `df`
+    fn row_wise(&self, df: &DataFrame, context: &mut Option<&mut dyn Context>) -> Result<Value> {
+        let mut strings = Vec::with_capacity(df.height());
+        for row in 0..df.height() {
+            let row = df_row_to_value(df, row)?;
+            let values = self.interpolations(&row, context)?;
+            strings.push(self.render(&values)?);
+        }
+        Ok(Value::Series(Series::new("string".into(), strings)))
+    }
+}
+
+impl Operation for FormatStringOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+    ) -> Result<Value> {
+        let df = match value {
+            Value::DataFrame(df) => df.clone(),
+            Value::LazyFrame(lf) => lf.clone().collect().map_err(polars_error)?,
+            _ => {
+                return Ok(Value::String(
+                    self.render(&self.interpolations(value, context)?)?,
+                ))
+            }
+        };
+        let frame = Value::DataFrame(df.clone());
+        let values = self.interpolations(&frame, context);
+        if let Ok(values) = values {
+            if values.iter().all(|v| !matches!(v, Value::Series(_))) {
+                return Ok(Value::String(self.render(&values)?));
+            }
+            if let Some(result) = self.vectorized(&df, values)? {
+                return Ok(result);
+            }
+        }
+        self.row_wise(&df, context)
+    }
+
+    fn description(&self) -> String {
+        match &self.format {
+            Some(format) => format!("@{} string", format),
+            None => "string interpolation".to_string(),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Operation for a bare `@fmt`, which formats the input itself
+pub struct FormatOperation {
+    pub format: String,
+}
+
+impl Operation for FormatOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        format_any(&self.format, value)
+    }
+
+    fn description(&self) -> String {
+        format!("@{}", self.format)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,54 @@
+//! Memory budgets, measured by the tracking allocator
+//!
+//! The allocator counts the whole process, so everything runs in a single
+//! test to keep other executions from adding to the measurement.
+
+use dsq_filter::{ExecutorConfig, FilterExecutor};
+use dsq_shared::error::LimitExceeded;
+use dsq_shared::limits::TrackingAllocator;
+use dsq_shared::value::Value;
+
+#[global_allocator]
+static ALLOC: TrackingAllocator = TrackingAllocator;
+
+#[test]
+fn test_memory_budget_and_peak() {
+    let config = ExecutorConfig {
+        collect_stats: true,
+        ..Default::default()
+    };
+    let mut executor = FilterExecutor::with_config(config);
+    let result = executor
+        .execute_str("[range(100000)] | length", Value::Null)
+        .unwrap();
+    let peak = result.stats.as_ref().unwrap().peak_memory_bytes;
+    assert_eq!(result.into_value(), Value::Int(100000));
+    assert!(peak > 100000 * std::mem::size_of::<Value>(), "{}", peak);
+
+    let config = ExecutorConfig {
+        memory_limit: Some(1024 * 1024),
+        ..Default::default()
+    };
+    let mut executor = FilterExecutor::with_config(config);
+    let err = executor
+        .execute_str("try [range(10000000)] catch 0", Value::Null)
+        .unwrap_err();
+    match err.downcast_ref::<LimitExceeded>() {
+        Some(LimitExceeded::Memory { limit, used }) => {
+            assert_eq!(*limit, 1024 * 1024);
+            assert!(used > limit);
+        }
+        other => panic!("expected the memory limit to be exceeded, got {:?}", other),
+    }
+
+    // Small executions stay within the budget
+    let result = executor
+        .execute_str("[range(10)] | add", Value::Null)
+        .unwrap();
+    assert_eq!(result.into_value(), Value::Int(45));
+
+    // An allocation freed before any check still counts towards the peak
+    let guard = dsq_shared::limits::enforce(None, None);
+    drop(std::hint::black_box(vec![0u8; 16 * 1024 * 1024]));
+    assert!(guard.peak_memory_bytes() >= 16 * 1024 * 1024);
+}
@@ -0,0 +1,1542 @@
+//! Apache Avro (.avro) format support
+//!
+//! Avro object container files are decoded with apache-avro and assembled
+//! into Arrow arrays column by column, which reach Polars through the Arrow
+//! IPC format. Every Avro type has a Polars counterpart:
+//!
+//! - records become structs, arrays become lists, and maps become lists of
+//!   `key`/`value` structs, the way Polars represents maps
+//! - enums, UUIDs and big decimals become strings; fixed becomes binary
+//! - `date`, `time-*`, `timestamp-*` and `local-timestamp-*` become Date,
+//!   Time and Datetime columns, timestamps in UTC and local timestamps
+//!   without a time zone
+//! - `decimal` becomes a Decimal column of the same precision and scale,
+//!   and `duration` a struct of `months`, `days` and `milliseconds`
+//! - a union of null and one type is that type, nullable; a union of several
+//!   types is a struct with a field per branch, of which only the branch
+//!   holding the value is set
+//! - a record nested in itself, such as the nodes of a linked list, nests
+//!   as deep as its values do, below which it is null
+//!
+//! A reader schema projects the file through Avro schema resolution: fields
+//! it adds take their defaults, fields it drops are skipped, and types are
+//! promoted as the specification allows.
+//!
+//! Files are written with a schema given as JSON, or one derived from the
+//! columns in which every field is nullable.
+
+use crate::error::{Error, FormatError, Result};
+use crate::ipc::{batches_to_dataframe, dataframe_to_batches};
+use crate::reader::{FormatReadOptions, ReadOptions};
+use crate::writer::{AvroCompression, FormatWriteOptions, WriteOptions};
+use apache_avro::schema::{
+    ArraySchema, DecimalSchema, EnumSchema, FixedSchema, MapSchema, Name, NamesRef, RecordSchema,
+    ResolvedSchema, SchemaKind, UnionSchema,
+};
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{Codec, Days, Decimal, Duration, Millis, Months, Reader, Schema, Writer};
+use arrow::array::{
+    Array, ArrayRef, AsArray, BinaryArray, BooleanArray, Date32Array, Decimal128Array,
+    Float32Array, Float64Array, Int32Array, Int64Array, LargeListArray, NullArray, StringArray,
+    StructArray, Time64NanosecondArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+    TimestampNanosecondArray, UInt32Array,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::datatypes::{
+    DataType, Date32Type, Date64Type, Decimal128Type, DurationMicrosecondType,
+    DurationMillisecondType, DurationNanosecondType, DurationSecondType, Field, Fields,
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, Schema as ArrowSchema,
+    Time32MillisecondType, Time32SecondType, Time64MicrosecondType, Time64NanosecondType, TimeUnit,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use dsq_shared::value::Value;
+use polars::prelude::DataFrame;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+/// Parse an Avro schema written as JSON
+pub fn parse_schema(text: &str) -> Result<Schema> {
+    Schema::parse_str(text).map_err(|e| {
+        Error::Format(FormatError::InvalidOption(format!(
+            "invalid Avro schema: {e}"
+        )))
+    })
+}
+
+/// Deserialize an Avro object container file from a reader
+///
+/// With a reader schema in `FormatReadOptions::Avro`, records are resolved
+/// against it and the columns are those of the reader schema.
+pub fn deserialize_avro<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+    format_options: &FormatReadOptions,
+) -> Result<Value> {
+    let (columns, reader_schema) = match format_options {
+        FormatReadOptions::Avro {
+            columns,
+            reader_schema,
+        } => (
+            options.columns.as_ref().or(columns.as_ref()),
+            reader_schema.as_deref().map(parse_schema).transpose()?,
+        ),
+        _ => (options.columns.as_ref(), None),
+    };
+
+    let reader = match &reader_schema {
+        Some(schema) => Reader::with_schema(schema, reader)?,
+        None => Reader::new(reader)?,
+    };
+    let schema = reader
+        .reader_schema()
+        .unwrap_or(reader.writer_schema())
+        .clone();
+    let records = reader
+        .skip(options.skip_rows)
+        .take(options.max_rows.unwrap_or(usize::MAX))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let resolved = ResolvedSchema::try_from(&schema)?;
+    let names = resolved.get_names();
+    let Schema::Record(RecordSchema { name, fields, .. }) = resolve(&schema, names)? else {
+        return Err(Error::Format(FormatError::UnsupportedFeature(
+            "Avro schema must be a record".to_string(),
+        )));
+    };
+    let selected = match columns {
+        Some(columns) => columns
+            .iter()
+            .map(|name| {
+                fields
+                    .iter()
+                    .position(|field| &field.name == name)
+                    .ok_or_else(|| {
+                        Error::Format(FormatError::InvalidOption(format!(
+                            "no column {name} in the Avro schema"
+                        )))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => (0..fields.len()).collect(),
+    };
+
+    let mut resolving = vec![name.clone()];
+    let mut arrow_fields = Vec::with_capacity(selected.len());
+    let mut arrays = Vec::with_capacity(selected.len());
+    for position in selected {
+        let field = &fields[position];
+        let values: Vec<_> = records
+            .iter()
+            .map(|record| record_field(record, position, &field.name))
+            .collect();
+        let array = column(&field.schema, &values, names, &mut resolving)?;
+        arrow_fields.push(Field::new(&field.name, array.data_type().clone(), true));
+        arrays.push(array);
+    }
+
+    let batch = RecordBatch::try_new_with_options(
+        Arc::new(ArrowSchema::new(arrow_fields)),
+        arrays,
+        &RecordBatchOptions::new().with_row_count(Some(records.len())),
+    )?;
+    let df = batches_to_dataframe(batch.schema(), &[batch])?;
+    Ok(Value::DataFrame(df))
+}
+
+/// The schema a reference names, or `schema` itself
+fn resolve<'s>(schema: &'s Schema, names: &NamesRef<'s>) -> Result<&'s Schema> {
+    match schema {
+        Schema::Ref { name } => names
+            .get(name)
+            .or_else(|| {
+                names
+                    .iter()
+                    .find(|(known, _)| known.name == name.name)
+                    .map(|(_, schema)| schema)
+            })
+            .copied()
+            .ok_or_else(|| {
+                Error::Format(FormatError::InvalidOption(format!(
+                    "undefined Avro type {}",
+                    name.name
+                )))
+            }),
+        schema => Ok(schema),
+    }
+}
+
+/// The value of a field of a record, looked up by position and then by name
+fn record_field<'v>(record: &'v AvroValue, position: usize, name: &str) -> Option<&'v AvroValue> {
+    let AvroValue::Record(fields) = record else {
+        return None;
+    };
+    fields
+        .get(position)
+        .filter(|(field, _)| field == name)
+        .or_else(|| fields.iter().find(|(field, _)| field == name))
+        .map(|(_, value)| value)
+}
+
+/// A value that is not null, seen through the union holding it
+fn present(value: &AvroValue) -> Option<&AvroValue> {
+    match value {
+        AvroValue::Null => None,
+        AvroValue::Union(_, value) => present(value),
+        value => Some(value),
+    }
+}
+
+/// Validity of values that are missing when None
+fn validity(values: &[Option<&AvroValue>]) -> Option<NullBuffer> {
+    if values.iter().all(Option::is_some) {
+        return None;
+    }
+    Some(NullBuffer::from(
+        values.iter().map(Option::is_some).collect::<Vec<_>>(),
+    ))
+}
+
+/// Build the Arrow array of Avro values of `schema`; None is a null
+///
+/// `resolving` holds the names of the records being built around these
+/// values. A record inside itself is only built while some of its values
+/// are present, and is a null array below that.
+fn column(
+    schema: &Schema,
+    values: &[Option<&AvroValue>],
+    names: &NamesRef<'_>,
+    resolving: &mut Vec<Name>,
+) -> Result<ArrayRef> {
+    let schema = resolve(schema, names)?;
+    if let Schema::Union(union) = schema {
+        return union_column(union, values, names, resolving);
+    }
+    let values: Vec<_> = values.iter().map(|v| v.and_then(present)).collect();
+    if let Schema::Record(RecordSchema { name, .. }) = schema {
+        if resolving.contains(name) && values.iter().all(Option::is_none) {
+            return Ok(Arc::new(NullArray::new(values.len())));
+        }
+    }
+
+    let array: ArrayRef = match schema {
+        Schema::Null => Arc::new(NullArray::new(values.len())),
+        Schema::Boolean => Arc::new(BooleanArray::from(scalars(&values, schema, |v| match v {
+            AvroValue::Boolean(b) => Some(*b),
+            _ => None,
+        })?)),
+        Schema::Int => Arc::new(Int32Array::from(scalars(&values, schema, |v| match v {
+            AvroValue::Int(i) => Some(*i),
+            _ => None,
+        })?)),
+        Schema::Long => Arc::new(Int64Array::from(scalars(&values, schema, |v| match v {
+            AvroValue::Long(l) => Some(*l),
+            AvroValue::Int(i) => Some(i64::from(*i)),
+            _ => None,
+        })?)),
+        Schema::Float => Arc::new(Float32Array::from(scalars(&values, schema, |v| match v {
+            AvroValue::Float(f) => Some(*f),
+            _ => None,
+        })?)),
+        Schema::Double => Arc::new(Float64Array::from(scalars(&values, schema, |v| match v {
+            AvroValue::Double(d) => Some(*d),
+            AvroValue::Float(f) => Some(f64::from(*f)),
+            _ => None,
+        })?)),
+        Schema::Bytes | Schema::Fixed(_) => {
+            Arc::new(BinaryArray::from(scalars(&values, schema, |v| match v {
+                AvroValue::Bytes(b) | AvroValue::Fixed(_, b) => Some(b.as_slice()),
+                _ => None,
+            })?))
+        }
+        Schema::String => Arc::new(StringArray::from(scalars(&values, schema, |v| match v {
+            AvroValue::String(s) => Some(s.as_str()),
+            _ => None,
+        })?)),
+        Schema::Enum(_) => Arc::new(StringArray::from(scalars(&values, schema, |v| match v {
+            AvroValue::Enum(_, symbol) | AvroValue::String(symbol) => Some(symbol.as_str()),
+            _ => None,
+        })?)),
+        Schema::Uuid => Arc::new(StringArray::from(scalars(&values, schema, |v| match v {
+            AvroValue::Uuid(uuid) => Some(uuid.to_string()),
+            AvroValue::String(s) => Some(s.clone()),
+            _ => None,
+        })?)),
+        Schema::BigDecimal => Arc::new(StringArray::from(scalars(&values, schema, |v| match v {
+            AvroValue::BigDecimal(decimal) => Some(decimal.to_string()),
+            _ => None,
+        })?)),
+        Schema::Date => Arc::new(Date32Array::from(scalars(&values, schema, |v| match v {
+            AvroValue::Date(days) | AvroValue::Int(days) => Some(*days),
+            _ => None,
+        })?)),
+        Schema::TimeMillis => Arc::new(Time64NanosecondArray::from(scalars(
+            &values,
+            schema,
+            |v| match v {
+                AvroValue::TimeMillis(millis) | AvroValue::Int(millis) => {
+                    Some(i64::from(*millis) * 1_000_000)
+                }
+                _ => None,
+            },
+        )?)),
+        Schema::TimeMicros => Arc::new(Time64NanosecondArray::from(scalars(
+            &values,
+            schema,
+            |v| match v {
+                AvroValue::TimeMicros(micros) | AvroValue::Long(micros) => {
+                    micros.checked_mul(1_000)
+                }
+                _ => None,
+            },
+        )?)),
+        Schema::TimestampMillis | Schema::LocalTimestampMillis => {
+            let array = TimestampMillisecondArray::from(scalars(&values, schema, |v| match v {
+                AvroValue::TimestampMillis(t)
+                | AvroValue::LocalTimestampMillis(t)
+                | AvroValue::Long(t) => Some(*t),
+                _ => None,
+            })?);
+            match schema {
+                Schema::TimestampMillis => Arc::new(array.with_timezone("UTC")),
+                _ => Arc::new(array),
+            }
+        }
+        Schema::TimestampMicros | Schema::LocalTimestampMicros => {
+            let array = TimestampMicrosecondArray::from(scalars(&values, schema, |v| match v {
+                AvroValue::TimestampMicros(t)
+                | AvroValue::LocalTimestampMicros(t)
+                | AvroValue::Long(t) => Some(*t),
+                _ => None,
+            })?);
+            match schema {
+                Schema::TimestampMicros => Arc::new(array.with_timezone("UTC")),
+                _ => Arc::new(array),
+            }
+        }
+        Schema::TimestampNanos | Schema::LocalTimestampNanos => {
+            let array = TimestampNanosecondArray::from(scalars(&values, schema, |v| match v {
+                AvroValue::TimestampNanos(t)
+                | AvroValue::LocalTimestampNanos(t)
+                | AvroValue::Long(t) => Some(*t),
+                _ => None,
+            })?);
+            match schema {
+                Schema::TimestampNanos => Arc::new(array.with_timezone("UTC")),
+                _ => Arc::new(array),
+            }
+        }
+        Schema::Decimal(DecimalSchema {
+            precision, scale, ..
+        }) => {
+            if *precision > 38 {
+                return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                    "Avro decimals of precision {precision} are wider than 38 digits"
+                ))));
+            }
+            let array = Decimal128Array::from(scalars(&values, schema, |v| match v {
+                AvroValue::Decimal(decimal) => decimal_value(decimal),
+                AvroValue::Bytes(b) | AvroValue::Fixed(_, b) => decimal_value(&Decimal::from(b)),
+                _ => None,
+            })?);
+            Arc::new(array.with_precision_and_scale(*precision as u8, *scale as i8)?)
+        }
+        Schema::Duration => {
+            let parts = scalars(&values, schema, |v| match v {
+                AvroValue::Duration(duration) => Some([
+                    u32::from(duration.months()),
+                    u32::from(duration.days()),
+                    u32::from(duration.millis()),
+                ]),
+                _ => None,
+            })?;
+            let part = |i: usize| -> ArrayRef {
+                Arc::new(UInt32Array::from(
+                    parts.iter().map(|p| p.map(|p| p[i])).collect::<Vec<_>>(),
+                ))
+            };
+            let fields: Fields = ["months", "days", "milliseconds"]
+                .into_iter()
+                .map(|name| Field::new(name, DataType::UInt32, true))
+                .collect();
+            Arc::new(StructArray::try_new(
+                fields,
+                vec![part(0), part(1), part(2)],
+                validity(&values),
+            )?)
+        }
+        Schema::Array(ArraySchema { items, .. }) => {
+            let lists = scalars(&values, schema, |v| match v {
+                AvroValue::Array(items) => Some(items),
+                _ => None,
+            })?;
+            let flat: Vec<_> = lists
+                .iter()
+                .flatten()
+                .flat_map(|l| l.iter().map(Some))
+                .collect();
+            let items = column(items, &flat, names, resolving)?;
+            list(
+                items,
+                lists.iter().map(|l| l.map_or(0, |l| l.len())),
+                &values,
+            )?
+        }
+        Schema::Map(MapSchema { types, .. }) => {
+            let maps = scalars(&values, schema, |v| match v {
+                AvroValue::Map(entries) => {
+                    let mut entries: Vec<_> = entries.iter().collect();
+                    entries.sort_by(|a, b| a.0.cmp(b.0));
+                    Some(entries)
+                }
+                _ => None,
+            })?;
+            let entries: Vec<_> = maps.iter().flatten().flatten().collect();
+            let keys: ArrayRef = Arc::new(StringArray::from(
+                entries.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+            ));
+            let values_of_entries: Vec<_> = entries.iter().map(|(_, v)| Some(*v)).collect();
+            let map_values = column(types, &values_of_entries, names, resolving)?;
+            let fields = Fields::from(vec![
+                Field::new("key", DataType::Utf8, false),
+                Field::new("value", map_values.data_type().clone(), true),
+            ]);
+            let entries = StructArray::try_new(fields, vec![keys, map_values], None)?;
+            list(
+                Arc::new(entries),
+                maps.iter().map(|m| m.as_ref().map_or(0, Vec::len)),
+                &values,
+            )?
+        }
+        Schema::Record(RecordSchema { name, fields, .. }) => {
+            scalars(&values, schema, |v| match v {
+                AvroValue::Record(_) => Some(()),
+                _ => None,
+            })?;
+            resolving.push(name.clone());
+            let arrays = fields
+                .iter()
+                .enumerate()
+                .map(|(position, field)| {
+                    let field_values: Vec<_> = values
+                        .iter()
+                        .map(|v| v.and_then(|v| record_field(v, position, &field.name)))
+                        .collect();
+                    column(&field.schema, &field_values, names, resolving)
+                })
+                .collect::<Result<Vec<_>>>();
+            resolving.pop();
+            let arrays = arrays?;
+            let struct_fields: Vec<_> = fields
+                .iter()
+                .zip(&arrays)
+                .map(|(field, array)| Field::new(&field.name, array.data_type().clone(), true))
+                .collect();
+            Arc::new(StructArray::try_new_with_length(
+                struct_fields.into(),
+                arrays,
+                validity(&values),
+                values.len(),
+            )?)
+        }
+        Schema::Union(_) | Schema::Ref { .. } => unreachable!("resolved above"),
+    };
+    Ok(array)
+}
+
+/// Build the array of a union: the one type of a nullable union, or a
+/// struct with a field per branch
+fn union_column(
+    union: &UnionSchema,
+    values: &[Option<&AvroValue>],
+    names: &NamesRef<'_>,
+    resolving: &mut Vec<Name>,
+) -> Result<ArrayRef> {
+    let branches: Vec<_> = union
+        .variants()
+        .iter()
+        .enumerate()
+        .filter(|(_, variant)| !matches!(variant, Schema::Null))
+        .collect();
+    match branches.as_slice() {
+        [] => return Ok(Arc::new(NullArray::new(values.len()))),
+        [(_, variant)] => return column(variant, values, names, resolving),
+        _ => {}
+    }
+
+    let mut fields = Vec::with_capacity(branches.len());
+    let mut arrays = Vec::with_capacity(branches.len());
+    for (index, variant) in branches {
+        let branch_values: Vec<_> = values
+            .iter()
+            .map(|v| match v {
+                Some(AvroValue::Union(i, value)) if *i as usize == index => Some(value.as_ref()),
+                _ => None,
+            })
+            .collect();
+        let array = column(variant, &branch_values, names, resolving)?;
+        fields.push(Field::new(
+            branch_name(variant),
+            array.data_type().clone(),
+            true,
+        ));
+        arrays.push(array);
+    }
+    let present: Vec<_> = values.iter().map(|v| v.and_then(present)).collect();
+    Ok(Arc::new(StructArray::try_new_with_length(
+        fields.into(),
+        arrays,
+        validity(&present),
+        values.len(),
+    )?))
+}
+
+/// Name of the struct field holding a branch of a union
+fn branch_name(schema: &Schema) -> String {
+    match schema {
+        Schema::Record(RecordSchema { name, .. })
+        | Schema::Enum(EnumSchema { name, .. })
+        | Schema::Fixed(FixedSchema { name, .. })
+        | Schema::Ref { name } => name.name.clone(),
+        schema => format!("{:?}", SchemaKind::from(schema)).to_lowercase(),
+    }
+}
+
+/// Wrap items in a list array of the given lengths
+fn list(
+    items: ArrayRef,
+    lengths: impl Iterator<Item = usize>,
+    values: &[Option<&AvroValue>],
+) -> Result<ArrayRef> {
+    let field = Arc::new(Field::new("item", items.data_type().clone(), true));
+    Ok(Arc::new(LargeListArray::try_new(
+        field,
+        OffsetBuffer::from_lengths(lengths),
+        items,
+        validity(values),
+    )?))
+}
+
+/// Convert present values with `convert`, failing on values of another type
+fn scalars<'v, T>(
+    values: &[Option<&'v AvroValue>],
+    schema: &Schema,
+    convert: impl Fn(&'v AvroValue) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    values
+        .iter()
+        .map(|value| match value {
+            Some(value) => convert(value)
+                .map(Some)
+                .ok_or_else(|| unexpected(value, schema)),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+fn unexpected(value: &AvroValue, schema: &Schema) -> Error {
+    Error::Format(FormatError::SchemaMismatch {
+        expected: format!("Avro {:?}", SchemaKind::from(schema)),
+        actual: format!("Avro {:?}", SchemaKind::from(value)),
+    })
+}
+
+/// The unscaled value of a decimal, if it fits 128 bits
+fn decimal_value(decimal: &Decimal) -> Option<i128> {
+    let bytes = Vec::<u8>::try_from(decimal).ok()?;
+    if bytes.len() > 16 {
+        return None;
+    }
+    let sign = match bytes.first() {
+        Some(byte) if byte & 0x80 != 0 => 0xff,
+        _ => 0,
+    };
+    let mut extended = [sign; 16];
+    extended[16 - bytes.len()..].copy_from_slice(&bytes);
+    Some(i128::from_be_bytes(extended))
+}
+
+/// Serialize data to an Avro object container file
+///
+/// The schema and compression come from `FormatWriteOptions::Avro`; without
+/// a schema, one is derived from the columns.
+pub fn serialize_avro<W: Write>(
+    mut writer: W,
+    value: &Value,
+    _options: &WriteOptions,
+    format_options: &FormatWriteOptions,
+) -> Result<()> {
+    let (compression, schema) = match format_options {
+        FormatWriteOptions::Avro {
+            compression,
+            schema,
+        } => (compression.clone(), schema.as_deref()),
+        _ => (AvroCompression::Null, None),
+    };
+    let codec = match compression {
+        AvroCompression::Null => Codec::Null,
+        AvroCompression::Deflate => Codec::Deflate(Default::default()),
+        AvroCompression::Snappy => Codec::Snappy,
+        other => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "{other:?} compression of Avro files"
+            ))))
+        }
+    };
+
+    let mut df: DataFrame = match value {
+        Value::Series(series) => DataFrame::new(vec![series.clone().into()])?,
+        Value::Object(_) => Value::Array(vec![value.clone()]).to_dataframe()?,
+        Value::DataFrame(_) | Value::LazyFrame(_) | Value::Array(_) => value.to_dataframe()?,
+        other => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "cannot write {} to Avro",
+                other.type_name()
+            ))))
+        }
+    };
+    let (arrow_schema, batches) = dataframe_to_batches(&mut df)?;
+    let schema = match schema {
+        Some(text) => parse_schema(text)?,
+        None => Schema::parse(&record_schema("record", arrow_schema.fields())?)?,
+    };
+
+    let resolved = ResolvedSchema::try_from(&schema)?;
+    let names = resolved.get_names();
+    if !matches!(resolve(&schema, names)?, Schema::Record(_)) {
+        return Err(Error::Format(FormatError::UnsupportedFeature(
+            "Avro schema must be a record".to_string(),
+        )));
+    }
+    let mut avro_writer = Writer::with_codec(&schema, Vec::new(), codec);
+    for batch in batches {
+        let rows = StructArray::from(batch);
+        for row in 0..rows.len() {
+            avro_writer.append(to_avro(&rows, row, &schema, names)?)?;
+        }
+    }
+    writer.write_all(&avro_writer.into_inner()?)?;
+    Ok(())
+}
+
+/// Avro schema, as JSON, of a record of nullable fields
+///
+/// Nested records are named after the path to them, as in `record_address`.
+fn record_schema(name: &str, fields: &Fields) -> Result<serde_json::Value> {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let nested = format!("{name}_{}", sanitize(field.name()));
+            Ok(json!({
+                "name": field.name(),
+                "type": nullable(avro_type(&nested, field.data_type())?),
+                "default": null,
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(json!({ "type": "record", "name": name, "fields": fields }))
+}
+
+/// Avro type, as JSON, of an Arrow data type
+fn avro_type(name: &str, data_type: &DataType) -> Result<serde_json::Value> {
+    let logical = |base: &str, logical: &str| json!({ "type": base, "logicalType": logical });
+    Ok(match data_type {
+        DataType::Null => json!("null"),
+        DataType::Boolean => json!("boolean"),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::UInt8 | DataType::UInt16 => {
+            json!("int")
+        }
+        DataType::Int64 | DataType::UInt32 | DataType::UInt64 | DataType::Duration(_) => {
+            json!("long")
+        }
+        DataType::Float32 => json!("float"),
+        DataType::Float64 => json!("double"),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View | DataType::Dictionary(..) => {
+            json!("string")
+        }
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => json!("bytes"),
+        DataType::Date32 | DataType::Date64 => logical("int", "date"),
+        DataType::Time32(_) | DataType::Time64(_) => logical("long", "time-micros"),
+        DataType::Timestamp(unit, tz) => {
+            let unit = match unit {
+                TimeUnit::Second | TimeUnit::Millisecond => "millis",
+                TimeUnit::Microsecond => "micros",
+                TimeUnit::Nanosecond => "nanos",
+            };
+            let local = if tz.is_some() { "" } else { "local-" };
+            logical("long", &format!("{local}timestamp-{unit}"))
+        }
+        DataType::Decimal128(precision, scale) => json!({
+            "type": "bytes",
+            "logicalType": "decimal",
+            "precision": precision,
+            "scale": scale,
+        }),
+        DataType::List(item) | DataType::LargeList(item) | DataType::FixedSizeList(item, _) => {
+            json!({
+                "type": "array",
+                "items": nullable(avro_type(&format!("{name}_item"), item.data_type())?),
+            })
+        }
+        DataType::Struct(fields) => record_schema(name, fields)?,
+        other => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "cannot write {other} columns to Avro"
+            ))))
+        }
+    })
+}
+
+/// A union of null and `avro_type`
+fn nullable(avro_type: serde_json::Value) -> serde_json::Value {
+    if avro_type == "null" {
+        avro_type
+    } else {
+        json!(["null", avro_type])
+    }
+}
+
+/// Replace the characters Avro names cannot hold with underscores
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Convert the value at `row` of `array` to an Avro value of `schema`
+fn to_avro(
+    array: &dyn Array,
+    row: usize,
+    schema: &Schema,
+    names: &NamesRef<'_>,
+) -> Result<AvroValue> {
+    let schema = resolve(schema, names)?;
+    if let Schema::Union(union) = schema {
+        return union_to_avro(array, row, union, names);
+    }
+    if is_null(array, row) {
+        return match schema {
+            Schema::Null => Ok(AvroValue::Null),
+            _ => Err(Error::Format(FormatError::SerializationError(format!(
+                "cannot write a null as Avro {:?}",
+                SchemaKind::from(schema)
+            )))),
+        };
+    }
+    let mismatch = || {
+        Error::Format(FormatError::SerializationError(format!(
+            "cannot write {} value as Avro {:?}",
+            array.data_type(),
+            SchemaKind::from(schema)
+        )))
+    };
+
+    let value =
+        match schema {
+            Schema::Null => return Err(mismatch()),
+            Schema::Boolean => {
+                AvroValue::Boolean(array.as_boolean_opt().ok_or_else(mismatch)?.value(row))
+            }
+            Schema::Int => AvroValue::Int(
+                number(array, row)
+                    .and_then(|n| i32::try_from(n).ok())
+                    .ok_or_else(mismatch)?,
+            ),
+            Schema::Long => AvroValue::Long(
+                number(array, row)
+                    .and_then(|n| i64::try_from(n).ok())
+                    .ok_or_else(mismatch)?,
+            ),
+            Schema::Float => AvroValue::Float(float(array, row).ok_or_else(mismatch)? as f32),
+            Schema::Double => AvroValue::Double(float(array, row).ok_or_else(mismatch)?),
+            Schema::Bytes => AvroValue::Bytes(bytes(array, row).ok_or_else(mismatch)?),
+            Schema::Fixed(FixedSchema { size, .. }) => {
+                let bytes = bytes(array, row).ok_or_else(mismatch)?;
+                if bytes.len() != *size {
+                    return Err(Error::Format(FormatError::SerializationError(format!(
+                        "cannot write {} bytes as Avro fixed of size {size}",
+                        bytes.len()
+                    ))));
+                }
+                AvroValue::Fixed(*size, bytes)
+            }
+            Schema::String => AvroValue::String(string(array, row).ok_or_else(mismatch)?),
+            Schema::Enum(EnumSchema { symbols, name, .. }) => {
+                let symbol = string(array, row).ok_or_else(mismatch)?;
+                let index = symbols.iter().position(|s| *s == symbol).ok_or_else(|| {
+                    Error::Format(FormatError::SerializationError(format!(
+                        "{symbol} is not a symbol of the Avro enum {}",
+                        name.name
+                    )))
+                })?;
+                AvroValue::Enum(index as u32, symbol)
+            }
+            Schema::Uuid => AvroValue::String(string(array, row).ok_or_else(mismatch)?)
+                .resolve(&Schema::Uuid)?,
+            Schema::BigDecimal => {
+                return Err(Error::Format(FormatError::UnsupportedFeature(
+                    "writing Avro big-decimal values".to_string(),
+                )))
+            }
+            Schema::Date => {
+                let (value, per_second) = ticks(array, row).ok_or_else(mismatch)?;
+                let days = in_unit(value, per_second, 1).div_euclid(86_400);
+                AvroValue::Date(i32::try_from(days).map_err(|_| mismatch())?)
+            }
+            Schema::TimeMillis => {
+                let (value, per_second) = ticks(array, row).ok_or_else(mismatch)?;
+                let millis = in_unit(value, per_second, 1_000);
+                AvroValue::TimeMillis(i32::try_from(millis).map_err(|_| mismatch())?)
+            }
+            Schema::TimeMicros
+            | Schema::TimestampMillis
+            | Schema::TimestampMicros
+            | Schema::TimestampNanos
+            | Schema::LocalTimestampMillis
+            | Schema::LocalTimestampMicros
+            | Schema::LocalTimestampNanos => {
+                let (value, per_second) = ticks(array, row).ok_or_else(mismatch)?;
+                let target = match schema {
+                    Schema::TimestampMillis | Schema::LocalTimestampMillis => 1_000,
+                    Schema::TimeMicros | Schema::TimestampMicros | Schema::LocalTimestampMicros => {
+                        1_000_000
+                    }
+                    _ => 1_000_000_000,
+                };
+                let value =
+                    i64::try_from(in_unit(value, per_second, target)).map_err(|_| mismatch())?;
+                match schema {
+                    Schema::TimeMicros => AvroValue::TimeMicros(value),
+                    Schema::TimestampMillis => AvroValue::TimestampMillis(value),
+                    Schema::TimestampMicros => AvroValue::TimestampMicros(value),
+                    Schema::TimestampNanos => AvroValue::TimestampNanos(value),
+                    Schema::LocalTimestampMillis => AvroValue::LocalTimestampMillis(value),
+                    Schema::LocalTimestampMicros => AvroValue::LocalTimestampMicros(value),
+                    _ => AvroValue::LocalTimestampNanos(value),
+                }
+            }
+            Schema::Decimal(DecimalSchema {
+                precision,
+                scale,
+                inner,
+            }) => {
+                let (unscaled, from_scale) = decimal(array, row).ok_or_else(mismatch)?;
+                let unscaled = rescale(unscaled, from_scale, *scale as i32).ok_or_else(|| {
+                    Error::Format(FormatError::SerializationError(format!(
+                        "{} value does not fit an Avro decimal of scale {scale}",
+                        array.data_type()
+                    )))
+                })?;
+                let bytes = signed_bytes(unscaled);
+                let fits = match resolve(inner, names)? {
+                    Schema::Fixed(FixedSchema { size, .. }) => bytes.len() <= *size,
+                    _ => true,
+                };
+                if !fits || digits(unscaled) > *precision {
+                    return Err(Error::Format(FormatError::SerializationError(format!(
+                        "{} value does not fit an Avro decimal of precision {precision}",
+                        array.data_type()
+                    ))));
+                }
+                AvroValue::Decimal(Decimal::from(bytes))
+            }
+            Schema::Duration => {
+                let [months, days, millis] = duration(array, row).ok_or_else(mismatch)?;
+                AvroValue::Duration(Duration::new(
+                    Months::new(months),
+                    Days::new(days),
+                    Millis::new(millis),
+                ))
+            }
+            Schema::Array(ArraySchema { items, .. }) => {
+                let list = list_value(array, row).ok_or_else(mismatch)?;
+                AvroValue::Array(
+                    (0..list.len())
+                        .map(|i| to_avro(list.as_ref(), i, items, names))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            Schema::Map(MapSchema { types, .. }) => {
+                let mut entries = HashMap::new();
+                if let Some(record) = array.as_struct_opt() {
+                    for (field, column) in record.fields().iter().zip(record.columns()) {
+                        entries.insert(
+                            field.name().clone(),
+                            to_avro(column.as_ref(), row, types, names)?,
+                        );
+                    }
+                } else {
+                    let list = list_value(array, row).ok_or_else(mismatch)?;
+                    let pairs = list.as_struct_opt().ok_or_else(mismatch)?;
+                    let keys = pairs
+                        .column_by_name("key")
+                        .or_else(|| pairs.columns().first())
+                        .ok_or_else(mismatch)?;
+                    let values = pairs
+                        .column_by_name("value")
+                        .or_else(|| pairs.columns().get(1))
+                        .ok_or_else(mismatch)?;
+                    for i in 0..pairs.len() {
+                        let key = string(keys.as_ref(), i).ok_or_else(mismatch)?;
+                        entries.insert(key, to_avro(values.as_ref(), i, types, names)?);
+                    }
+                }
+                AvroValue::Map(entries)
+            }
+            Schema::Record(RecordSchema { fields, name, .. }) => {
+                let record = array.as_struct_opt().ok_or_else(mismatch)?;
+                let mut values = Vec::with_capacity(fields.len());
+                for field in fields {
+                    let value = match (record.column_by_name(&field.name), &field.default) {
+                        (Some(column), _) => to_avro(column.as_ref(), row, &field.schema, names)
+                            .map_err(|e| in_field(&field.name, e))?,
+                        (None, Some(default)) => AvroValue::from(default.clone())
+                            .resolve(resolve(&field.schema, names)?)?,
+                        (None, None) => {
+                            return Err(Error::Format(FormatError::InvalidOption(format!(
+                                "no column {} for the field of Avro record {} without a default",
+                                field.name, name.name
+                            ))))
+                        }
+                    };
+                    values.push((field.name.clone(), value));
+                }
+                AvroValue::Record(values)
+            }
+            Schema::Union(_) | Schema::Ref { .. } => unreachable!("resolved above"),
+        };
+    Ok(value)
+}
+
+/// Convert a value to the branch of a union it belongs to
+///
+/// Structs with a field per branch, as unions are read, write the branch
+/// whose field is set; other values take the first branch they convert to.
+fn union_to_avro(
+    array: &dyn Array,
+    row: usize,
+    union: &UnionSchema,
+    names: &NamesRef<'_>,
+) -> Result<AvroValue> {
+    let variants = union.variants();
+    if is_null(array, row) {
+        let index = variants
+            .iter()
+            .position(|variant| matches!(variant, Schema::Null))
+            .ok_or_else(|| {
+                Error::Format(FormatError::SerializationError(
+                    "cannot write a null as an Avro union without null".to_string(),
+                ))
+            })?;
+        return Ok(AvroValue::Union(index as u32, Box::new(AvroValue::Null)));
+    }
+
+    let branch_names: Vec<_> = variants.iter().map(branch_name).collect();
+    if let Some(branches) = array.as_struct_opt() {
+        let non_null = variants
+            .iter()
+            .filter(|variant| !matches!(variant, Schema::Null))
+            .count();
+        let is_branches = non_null > 1
+            && branches
+                .fields()
+                .iter()
+                .all(|field| branch_names.contains(field.name()));
+        if is_branches {
+            for (index, variant) in variants.iter().enumerate() {
+                if let Some(column) = branches.column_by_name(&branch_names[index]) {
+                    if !is_null(column.as_ref(), row) {
+                        let value = to_avro(column.as_ref(), row, variant, names)?;
+                        return Ok(AvroValue::Union(index as u32, Box::new(value)));
+                    }
+                }
+            }
+        }
+    }
+
+    for (index, variant) in variants.iter().enumerate() {
+        if matches!(variant, Schema::Null) {
+            continue;
+        }
+        if let Ok(value) = to_avro(array, row, variant, names) {
+            return Ok(AvroValue::Union(index as u32, Box::new(value)));
+        }
+    }
+    Err(Error::Format(FormatError::SerializationError(format!(
+        "cannot write {} value as any of the Avro union {}",
+        array.data_type(),
+        branch_names.join(", ")
+    ))))
+}
+
+/// Prefix a serialization error with the field it happened in
+fn in_field(name: &str, error: Error) -> Error {
+    match error {
+        Error::Format(FormatError::SerializationError(message)) => Error::Format(
+            FormatError::SerializationError(format!("{name}: {message}")),
+        ),
+        error => error,
+    }
+}
+
+fn is_null(array: &dyn Array, row: usize) -> bool {
+    array.data_type() == &DataType::Null || array.is_null(row)
+}
+
+/// The integer at `row` of an integer, date, time, timestamp or duration
+/// array, in the unit of the array
+fn number(array: &dyn Array, row: usize) -> Option<i128> {
+    macro_rules! value {
+        ($type:ty) => {
+            i128::from(array.as_primitive::<$type>().value(row))
+        };
+    }
+    Some(match array.data_type() {
+        DataType::Int8 => value!(Int8Type),
+        DataType::Int16 => value!(Int16Type),
+        DataType::Int32 => value!(Int32Type),
+        DataType::Int64 => value!(Int64Type),
+        DataType::UInt8 => value!(UInt8Type),
+        DataType::UInt16 => value!(UInt16Type),
+        DataType::UInt32 => value!(UInt32Type),
+        DataType::UInt64 => value!(UInt64Type),
+        DataType::Date32 => value!(Date32Type),
+        DataType::Date64 => value!(Date64Type),
+        DataType::Time32(TimeUnit::Second) => value!(Time32SecondType),
+        DataType::Time32(_) => value!(Time32MillisecondType),
+        DataType::Time64(TimeUnit::Microsecond) => value!(Time64MicrosecondType),
+        DataType::Time64(_) => value!(Time64NanosecondType),
+        DataType::Timestamp(TimeUnit::Second, _) => value!(TimestampSecondType),
+        DataType::Timestamp(TimeUnit::Millisecond, _) => value!(TimestampMillisecondType),
+        DataType::Timestamp(TimeUnit::Microsecond, _) => value!(TimestampMicrosecondType),
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => value!(TimestampNanosecondType),
+        DataType::Duration(TimeUnit::Second) => value!(DurationSecondType),
+        DataType::Duration(TimeUnit::Millisecond) => value!(DurationMillisecondType),
+        DataType::Duration(TimeUnit::Microsecond) => value!(DurationMicrosecondType),
+        DataType::Duration(TimeUnit::Nanosecond) => value!(DurationNanosecondType),
+        _ => return None,
+    })
+}
+
+fn float(array: &dyn Array, row: usize) -> Option<f64> {
+    if let Some(array) = array.as_primitive_opt::<Float64Type>() {
+        return Some(array.value(row));
+    }
+    if let Some(array) = array.as_primitive_opt::<Float32Type>() {
+        return Some(f64::from(array.value(row)));
+    }
+    match array.data_type() {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => number(array, row).map(|n| n as f64),
+        _ => None,
+    }
+}
+
+fn string(array: &dyn Array, row: usize) -> Option<String> {
+    if let Some(array) = array.as_string_opt::<i32>() {
+        return Some(array.value(row).to_string());
+    }
+    if let Some(array) = array.as_string_opt::<i64>() {
+        return Some(array.value(row).to_string());
+    }
+    if let Some(array) = array.as_string_view_opt() {
+        return Some(array.value(row).to_string());
+    }
+    let dictionary = array.as_any_dictionary_opt()?;
+    let key = usize::try_from(number(dictionary.keys(), row)?).ok()?;
+    string(dictionary.values().as_ref(), key)
+}
+
+fn bytes(array: &dyn Array, row: usize) -> Option<Vec<u8>> {
+    if let Some(array) = array.as_binary_opt::<i32>() {
+        return Some(array.value(row).to_vec());
+    }
+    if let Some(array) = array.as_binary_opt::<i64>() {
+        return Some(array.value(row).to_vec());
+    }
+    if let Some(array) = array.as_binary_view_opt() {
+        return Some(array.value(row).to_vec());
+    }
+    array
+        .as_fixed_size_binary_opt()
+        .map(|array| array.value(row).to_vec())
+}
+
+/// The time at `row` of a date, time, timestamp or duration array, with the
+/// ticks of its unit in a second
+fn ticks(array: &dyn Array, row: usize) -> Option<(i128, i128)> {
+    let per_second = |unit: &TimeUnit| match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    };
+    let value = number(array, row)?;
+    match array.data_type() {
+        DataType::Date32 => Some((value * 86_400, 1)),
+        DataType::Date64 => Some((value, 1_000)),
+        DataType::Time32(unit)
+        | DataType::Time64(unit)
+        | DataType::Timestamp(unit, _)
+        | DataType::Duration(unit) => Some((value, per_second(unit))),
+        _ => None,
+    }
+}
+
+/// Convert `value` ticks of `from` per second to ticks of `to` per second,
+/// rounding towards the past
+fn in_unit(value: i128, from: i128, to: i128) -> i128 {
+    (value * to).div_euclid(from)
+}
+
+/// The unscaled value and scale at `row` of a decimal or integer array
+fn decimal(array: &dyn Array, row: usize) -> Option<(i128, i32)> {
+    match array.data_type() {
+        DataType::Decimal128(_, scale) => Some((
+            array.as_primitive::<Decimal128Type>().value(row),
+            i32::from(*scale),
+        )),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => Some((number(array, row)?, 0)),
+        _ => None,
+    }
+}
+
+/// Change the scale of an unscaled decimal, unless that loses digits
+fn rescale(value: i128, from: i32, to: i32) -> Option<i128> {
+    if to >= from {
+        value.checked_mul(10i128.checked_pow((to - from) as u32)?)
+    } else {
+        let factor = 10i128.checked_pow((from - to) as u32)?;
+        (value % factor == 0).then_some(value / factor)
+    }
+}
+
+/// Number of decimal digits of an unscaled decimal
+fn digits(value: i128) -> usize {
+    value
+        .unsigned_abs()
+        .checked_ilog10()
+        .map_or(1, |d| d as usize + 1)
+}
+
+/// Shortest big-endian two's complement bytes of `value`
+fn signed_bytes(value: i128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let sign = if value < 0 { 0xff } else { 0 };
+    let start = bytes
+        .windows(2)
+        .position(|pair| pair[0] != sign || (pair[1] & 0x80 != 0) != (value < 0))
+        .unwrap_or(bytes.len() - 1);
+    bytes[start..].to_vec()
+}
+
+/// Months, days and milliseconds at `row` of a struct with those fields or
+/// of a duration array
+fn duration(array: &dyn Array, row: usize) -> Option<[u32; 3]> {
+    if let Some(parts) = array.as_struct_opt() {
+        let part = |name: &str| {
+            let column = parts.column_by_name(name)?;
+            u32::try_from(number(column.as_ref(), row)?).ok()
+        };
+        return Some([part("months")?, part("days")?, part("milliseconds")?]);
+    }
+    let (value, per_second) = match array.data_type() {
+        DataType::Duration(_) => ticks(array, row)?,
+        _ => return None,
+    };
+    Some([0, 0, u32::try_from(in_unit(value, per_second, 1_000)).ok()?])
+}
+
+/// The items of the list at `row` of a list array
+fn list_value(array: &dyn Array, row: usize) -> Option<ArrayRef> {
+    if let Some(list) = array.as_list_opt::<i32>() {
+        return Some(list.value(row));
+    }
+    if let Some(list) = array.as_list_opt::<i64>() {
+        return Some(list.value(row));
+    }
+    array.as_fixed_size_list_opt().map(|list| list.value(row))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::{DataType as PolarsType, NamedFrom, Series, TimeUnit as PolarsUnit};
+    use std::io::Cursor;
+
+    const ORDER_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Order",
+        "namespace": "shop",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "customer", "type": ["null", "string"], "default": null},
+            {"name": "status", "type": {"type": "enum", "name": "Status", "symbols": ["open", "shipped"]}},
+            {"name": "tags", "type": {"type": "array", "items": "string"}},
+            {"name": "quantities", "type": {"type": "map", "values": "int"}},
+            {"name": "address", "type": {"type": "record", "name": "Address", "fields": [
+                {"name": "city", "type": "string"},
+                {"name": "zip", "type": ["null", "int"]}
+            ]}},
+            {"name": "billing", "type": ["null", "Address"]},
+            {"name": "total", "type": {"type": "bytes", "logicalType": "decimal", "precision": 9, "scale": 2}},
+            {"name": "checksum", "type": {"type": "fixed", "name": "Checksum", "size": 4}},
+            {"name": "placed", "type": {"type": "int", "logicalType": "date"}},
+            {"name": "updated", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+            {"name": "note", "type": ["null", "int", "string"]}
+        ]
+    }"#;
+
+    fn order(id: i64, status: u32, note: AvroValue) -> AvroValue {
+        let address = |city: &str| {
+            AvroValue::Record(vec![
+                ("city".to_string(), AvroValue::String(city.to_string())),
+                (
+                    "zip".to_string(),
+                    AvroValue::Union(1, Box::new(AvroValue::Int(10115))),
+                ),
+            ])
+        };
+        let symbol = ["open", "shipped"][status as usize].to_string();
+        AvroValue::Record(vec![
+            ("id".to_string(), AvroValue::Long(id)),
+            (
+                "customer".to_string(),
+                AvroValue::Union(1, Box::new(AvroValue::String(format!("c{id}")))),
+            ),
+            ("status".to_string(), AvroValue::Enum(status, symbol)),
+            (
+                "tags".to_string(),
+                AvroValue::Array(vec![AvroValue::String("gift".to_string())]),
+            ),
+            (
+                "quantities".to_string(),
+                AvroValue::Map(HashMap::from([
+                    ("apple".to_string(), AvroValue::Int(3)),
+                    ("pear".to_string(), AvroValue::Int(1)),
+                ])),
+            ),
+            ("address".to_string(), address("Berlin")),
+            (
+                "billing".to_string(),
+                if id == 1 {
+                    AvroValue::Union(1, Box::new(address("Hamburg")))
+                } else {
+                    AvroValue::Union(0, Box::new(AvroValue::Null))
+                },
+            ),
+            (
+                "total".to_string(),
+                AvroValue::Decimal(Decimal::from(signed_bytes(-1999))),
+            ),
+            (
+                "checksum".to_string(),
+                AvroValue::Fixed(4, vec![0xde, 0xad, 0xbe, 0xef]),
+            ),
+            ("placed".to_string(), AvroValue::Date(19_000)),
+            (
+                "updated".to_string(),
+                AvroValue::TimestampMillis(1_641_600_000_000),
+            ),
+            ("note".to_string(), note),
+        ])
+    }
+
+    fn orders() -> Vec<u8> {
+        let schema = parse_schema(ORDER_SCHEMA).unwrap();
+        let mut writer = Writer::new(&schema, Vec::new());
+        writer
+            .append(order(
+                1,
+                0,
+                AvroValue::Union(1, Box::new(AvroValue::Int(7))),
+            ))
+            .unwrap();
+        writer
+            .append(order(
+                2,
+                1,
+                AvroValue::Union(2, Box::new(AvroValue::String("fragile".to_string()))),
+            ))
+            .unwrap();
+        writer
+            .append(order(3, 1, AvroValue::Union(0, Box::new(AvroValue::Null))))
+            .unwrap();
+        writer.into_inner().unwrap()
+    }
+
+    fn read(bytes: &[u8], options: &ReadOptions, format_options: &FormatReadOptions) -> DataFrame {
+        match deserialize_avro(Cursor::new(bytes), options, format_options).unwrap() {
+            Value::DataFrame(df) => df,
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
+    fn avro_options(reader_schema: Option<&str>) -> FormatReadOptions {
+        FormatReadOptions::Avro {
+            columns: None,
+            reader_schema: reader_schema.map(str::to_string),
+        }
+    }
+
+    fn write(df: DataFrame, schema: Option<&str>) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        serialize_avro(
+            &mut buffer,
+            &Value::DataFrame(df),
+            &WriteOptions::default(),
+            &FormatWriteOptions::Avro {
+                compression: AvroCompression::Deflate,
+                schema: schema.map(str::to_string),
+            },
+        )?;
+        Ok(buffer)
+    }
+
+    #[test]
+    fn test_avro_reads_nested_and_logical_types() {
+        let df = read(&orders(), &ReadOptions::default(), &avro_options(None));
+        let dtype = |name: &str| df.column(name).unwrap().dtype().clone();
+
+        assert_eq!(df.height(), 3);
+        assert_eq!(dtype("id"), PolarsType::Int64);
+        assert_eq!(dtype("status"), PolarsType::String);
+        assert!(matches!(dtype("tags"), PolarsType::List(_)));
+        assert!(matches!(dtype("quantities"), PolarsType::List(_)));
+        assert!(matches!(dtype("address"), PolarsType::Struct(_)));
+        assert!(matches!(dtype("billing"), PolarsType::Struct(_)));
+        assert!(matches!(dtype("total"), PolarsType::Decimal(..)));
+        assert_eq!(dtype("checksum"), PolarsType::Binary);
+        assert_eq!(dtype("placed"), PolarsType::Date);
+        assert!(matches!(
+            dtype("updated"),
+            PolarsType::Datetime(PolarsUnit::Milliseconds, Some(_))
+        ));
+        match dtype("note") {
+            PolarsType::Struct(fields) => {
+                let names: Vec<_> = fields.iter().map(|f| f.name().to_string()).collect();
+                assert_eq!(names, ["int", "string"]);
+            }
+            other => panic!("expected a struct of union branches, got {other}"),
+        }
+
+        let status: Vec<_> = df
+            .column("status")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(status, [Some("open"), Some("shipped"), Some("shipped")]);
+        assert_eq!(df.column("billing").unwrap().null_count(), 2);
+        assert_eq!(df.column("note").unwrap().null_count(), 1);
+    }
+
+    #[test]
+    fn test_avro_round_trip_with_a_user_schema() {
+        let df = read(&orders(), &ReadOptions::default(), &avro_options(None));
+        let bytes = write(df.clone(), Some(ORDER_SCHEMA)).unwrap();
+
+        let reader = Reader::new(Cursor::new(&bytes)).unwrap();
+        assert_eq!(
+            reader.writer_schema().canonical_form(),
+            parse_schema(ORDER_SCHEMA).unwrap().canonical_form()
+        );
+        let records: Vec<_> = reader.map(|record| record.unwrap()).collect();
+        let note = AvroValue::Union(2, Box::new(AvroValue::String("fragile".to_string())));
+        assert_eq!(records[1], order(2, 1, note));
+
+        let read_back = read(&bytes, &ReadOptions::default(), &avro_options(None));
+        assert!(read_back.equals_missing(&df));
+    }
+
+    #[test]
+    fn test_avro_reader_schema_evolves_fields() {
+        let reader_schema = r#"{
+            "type": "record",
+            "name": "Order",
+            "namespace": "shop",
+            "fields": [
+                {"name": "id", "type": "double"},
+                {"name": "status", "type": {"type": "enum", "name": "Status", "symbols": ["open", "shipped", "lost"]}},
+                {"name": "priority", "type": "int", "default": 3},
+                {"name": "customer", "type": ["null", "string"], "default": null}
+            ]
+        }"#;
+        let df = read(
+            &orders(),
+            &ReadOptions::default(),
+            &avro_options(Some(reader_schema)),
+        );
+
+        assert_eq!(
+            df.get_column_names(),
+            ["id", "status", "priority", "customer"]
+        );
+        assert_eq!(df.column("id").unwrap().dtype(), &PolarsType::Float64);
+        let priority: Vec<_> = df
+            .column("priority")
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(priority, [Some(3), Some(3), Some(3)]);
+
+        let incompatible = r#"{"type": "record", "name": "Order", "namespace": "shop",
+            "fields": [{"name": "rating", "type": "int"}]}"#;
+        assert!(deserialize_avro(
+            Cursor::new(orders()),
+            &ReadOptions::default(),
+            &avro_options(Some(incompatible)),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_avro_columns_and_row_limits() {
+        let options = ReadOptions {
+            columns: Some(vec!["note".to_string(), "id".to_string()]),
+            skip_rows: 1,
+            max_rows: Some(1),
+            ..Default::default()
+        };
+        let df = read(&orders(), &options, &avro_options(None));
+        assert_eq!(df.get_column_names(), ["note", "id"]);
+        let ids: Vec<_> = df
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(ids, [Some(2)]);
+
+        let options = ReadOptions {
+            columns: Some(vec!["missing".to_string()]),
+            ..Default::default()
+        };
+        assert!(deserialize_avro(Cursor::new(orders()), &options, &avro_options(None)).is_err());
+    }
+
+    #[test]
+    fn test_avro_derives_a_schema_from_the_columns() {
+        let df = DataFrame::new(vec![
+            Series::new("name".into(), vec![Some("Ada"), None, Some("Alan")]).into(),
+            Series::new("age".into(), vec![36i64, 41, 41]).into(),
+            Series::new("score".into(), vec![Some(1.5f64), Some(2.0), None]).into(),
+        ])
+        .unwrap();
+        let bytes = write(df.clone(), None).unwrap();
+
+        let reader = Reader::new(Cursor::new(&bytes)).unwrap();
+        match reader.writer_schema() {
+            Schema::Record(RecordSchema { name, fields, .. }) => {
+                assert_eq!(name.name, "record");
+                assert!(fields.iter().all(|field| field.is_nullable()));
+            }
+            other => panic!("expected a record schema, got {other:?}"),
+        }
+        let read_back = read(&bytes, &ReadOptions::default(), &avro_options(None));
+        assert!(read_back.equals_missing(&df));
+    }
+
+    #[test]
+    fn test_avro_recursive_record_round_trip() {
+        const NODE_SCHEMA: &str = r#"{"type": "record", "name": "Node", "fields": [
+            {"name": "value", "type": "long"},
+            {"name": "next", "type": ["null", "Node"], "default": null}
+        ]}"#;
+        fn node(values: &[i64]) -> AvroValue {
+            let next = match &values[1..] {
+                [] => AvroValue::Union(0, Box::new(AvroValue::Null)),
+                rest => AvroValue::Union(1, Box::new(node(rest))),
+            };
+            AvroValue::Record(vec![
+                ("value".to_string(), AvroValue::Long(values[0])),
+                ("next".to_string(), next),
+            ])
+        }
+        let lists = [node(&[1, 2, 3]), node(&[4])];
+        let mut writer = Writer::new(&parse_schema(NODE_SCHEMA).unwrap(), Vec::new());
+        for list in &lists {
+            writer.append(list.clone()).unwrap();
+        }
+        let bytes = writer.into_inner().unwrap();
+
+        let df = read(&bytes, &ReadOptions::default(), &avro_options(None));
+        let row = |df: &DataFrame, i| {
+            dsq_shared::value::df_row_to_value(df, i)
+                .unwrap()
+                .to_json()
+                .unwrap()
+        };
+        assert_eq!(
+            row(&df, 0),
+            json!({"value": 1, "next": {"value": 2, "next": {"value": 3, "next": null}}})
+        );
+        assert_eq!(row(&df, 1), json!({"value": 4, "next": null}));
+
+        let written = write(df.clone(), Some(NODE_SCHEMA)).unwrap();
+        let records: Vec<_> = Reader::new(Cursor::new(&written))
+            .unwrap()
+            .map(|record| record.unwrap())
+            .collect();
+        assert_eq!(records, lists);
+        let read_back = read(&written, &ReadOptions::default(), &avro_options(None));
+        assert!(read_back.equals_missing(&df));
+    }
+
+    #[test]
+    fn test_avro_rejects_values_outside_the_schema() {
+        let schema = r#"{"type": "record", "name": "Row", "fields": [
+            {"name": "status", "type": {"type": "enum", "name": "Status", "symbols": ["open"]}}
+        ]}"#;
+        let df = DataFrame::new(vec![
+            Series::new("status".into(), vec!["open", "lost"]).into()
+        ])
+        .unwrap();
+        assert!(write(df, Some(schema)).is_err());
+
+        let schema = r#"{"type": "record", "name": "Row", "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "source", "type": "string", "default": "import"}
+        ]}"#;
+        let ids = |ids: Vec<Option<i64>>| {
+            DataFrame::new(vec![Series::new("id".into(), ids).into()]).unwrap()
+        };
+        assert!(write(ids(vec![None, Some(2)]), Some(schema)).is_err());
+
+        let bytes = write(ids(vec![Some(2)]), Some(schema)).unwrap();
+        let read_back = read(&bytes, &ReadOptions::default(), &avro_options(None));
+        let source: Vec<_> = read_back
+            .column("source")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(source, [Some("import")]);
+
+        assert!(parse_schema("{\"type\": \"record\"}").is_err());
+    }
+}
@@ -0,0 +1,335 @@
+//! Compressed files
+//!
+//! Files of any format can be compressed with gzip, zstd, bzip2 or xz. The
+//! compression of a file is recognized by a second extension, as in
+//! `events.jsonl.gz`, or by the magic bytes at its start, and the file is
+//! decompressed as it is read. Output is compressed as it is written.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{Error, FormatError, Result};
+
+/// Buffer size for reading files
+const READ_BUFFER_SIZE: usize = 128 * 1024;
+
+/// Compression codec of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// gzip (`.gz`)
+    Gzip,
+    /// Zstandard (`.zst`)
+    Zstd,
+    /// bzip2 (`.bz2`)
+    Bzip2,
+    /// xz (`.xz`)
+    Xz,
+}
+
+impl Compression {
+    /// Detect compression from a file extension
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "gz" | "gzip" => Some(Self::Gzip),
+            "zst" | "zstd" => Some(Self::Zstd),
+            "bz2" => Some(Self::Bzip2),
+            "xz" => Some(Self::Xz),
+            _ => None,
+        }
+    }
+
+    /// Detect compression from the last extension of a path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Detect compression from the magic bytes at the start of a file
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else if bytes.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Self::Xz)
+        } else {
+            None
+        }
+    }
+
+    /// Parse compression from string (for CLI arguments)
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "gzip" | "gz" => Ok(Self::Gzip),
+            "zstd" | "zst" => Ok(Self::Zstd),
+            "bzip2" | "bz2" => Ok(Self::Bzip2),
+            "xz" => Ok(Self::Xz),
+            _ => Err(Error::Format(FormatError::Unknown(format!(
+                "{s} compression; use gzip, zstd, bzip2 or xz"
+            )))),
+        }
+    }
+
+    /// The file extension of the compression
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+            Self::Bzip2 => "bz2",
+            Self::Xz => "xz",
+        }
+    }
+
+    /// Decompress the data read from `reader`
+    ///
+    /// Files made of several concatenated compressed streams, as written by
+    /// `cat a.gz b.gz`, are read to the end.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn decoder<'a, R: BufRead + Send + 'a>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn Read + Send + 'a>> {
+        Ok(match self {
+            Self::Gzip => Box::new(libflate::gzip::MultiDecoder::new(reader)?),
+            Self::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+            Self::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+            Self::Xz => Box::new(liblzma::bufread::XzDecoder::new_multi_decoder(reader)),
+        })
+    }
+
+    /// Decompress the data read from `reader`
+    #[cfg(target_arch = "wasm32")]
+    pub fn decoder<'a, R: BufRead + Send + 'a>(
+        &self,
+        _reader: R,
+    ) -> Result<Box<dyn Read + Send + 'a>> {
+        Err(self.unsupported())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn unsupported(&self) -> Error {
+        Error::Format(FormatError::UnsupportedFeature(format!(
+            "{self} compression is not supported on WASM"
+        )))
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s).map_err(|e| e.to_string())
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gzip => write!(f, "gzip"),
+            Self::Zstd => write!(f, "zstd"),
+            Self::Bzip2 => write!(f, "bzip2"),
+            Self::Xz => write!(f, "xz"),
+        }
+    }
+}
+
+/// Open the file at `path` for reading, decompressing it if its magic
+/// bytes show it is compressed
+pub fn open(path: &Path) -> Result<Box<dyn BufRead + Send>> {
+    let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, File::open(path)?);
+    match Compression::detect(reader.fill_buf()?) {
+        Some(compression) => Ok(Box::new(BufReader::with_capacity(
+            READ_BUFFER_SIZE,
+            compression.decoder(reader)?,
+        ))),
+        None => Ok(Box::new(reader)),
+    }
+}
+
+/// Whether the file at `path` starts with the magic bytes of a compression
+pub fn is_compressed(path: &Path) -> Result<bool> {
+    let mut magic = Vec::with_capacity(6);
+    File::open(path)?.take(6).read_to_end(&mut magic)?;
+    Ok(Compression::detect(&magic).is_some())
+}
+
+/// Decompress `bytes` if their magic bytes show they are compressed
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    match Compression::detect(&bytes) {
+        Some(compression) => {
+            let mut decompressed = Vec::new();
+            compression
+                .decoder(io::Cursor::new(bytes))?
+                .read_to_end(&mut decompressed)?;
+            Ok(decompressed)
+        }
+        None => Ok(bytes),
+    }
+}
+
+/// Writer that compresses what is written to it
+///
+/// The compressed stream is only complete once [`CompressedWriter::finish`]
+/// is called.
+pub struct CompressedWriter<W: Write> {
+    encoder: Encoder<W>,
+}
+
+enum Encoder<W: Write> {
+    Plain(W),
+    #[cfg(not(target_arch = "wasm32"))]
+    Gzip(libflate::gzip::Encoder<W>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Bzip2(bzip2::write::BzEncoder<W>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Xz(liblzma::write::XzEncoder<W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    /// Compress what is written to `writer` with `compression`, or write it
+    /// as it is when `compression` is `None`
+    pub fn new(writer: W, compression: Option<Compression>) -> Result<Self> {
+        let encoder = match compression {
+            None => Encoder::Plain(writer),
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Compression::Gzip) => Encoder::Gzip(libflate::gzip::Encoder::new(writer)?),
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Compression::Zstd) => Encoder::Zstd(zstd::stream::write::Encoder::new(
+                writer,
+                zstd::DEFAULT_COMPRESSION_LEVEL,
+            )?),
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Compression::Bzip2) => Encoder::Bzip2(bzip2::write::BzEncoder::new(
+                writer,
+                bzip2::Compression::default(),
+            )),
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Compression::Xz) => Encoder::Xz(liblzma::write::XzEncoder::new(writer, 6)),
+            #[cfg(target_arch = "wasm32")]
+            Some(compression) => return Err(compression.unsupported()),
+        };
+        Ok(Self { encoder })
+    }
+
+    /// Complete the compressed stream and return the underlying writer
+    pub fn finish(self) -> Result<W> {
+        let mut writer = match self.encoder {
+            Encoder::Plain(writer) => writer,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Gzip(encoder) => encoder.finish().into_result()?,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Zstd(encoder) => encoder.finish()?,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Bzip2(encoder) => encoder.finish()?,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Xz(encoder) => encoder.finish()?,
+        };
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    fn inner(&mut self) -> &mut dyn Write {
+        match &mut self.encoder {
+            Encoder::Plain(writer) => writer,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Gzip(encoder) => encoder,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Zstd(encoder) => encoder,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Bzip2(encoder) => encoder,
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Xz(encoder) => encoder,
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner().write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner().flush()
+    }
+}
+
+/// Compress `bytes` with `compression`, or return them as they are when
+/// `compression` is `None`
+pub fn compress(bytes: &[u8], compression: Option<Compression>) -> Result<Vec<u8>> {
+    let mut writer = CompressedWriter::new(Vec::new(), compression)?;
+    writer.write_all(bytes)?;
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODECS: [Compression; 4] = [
+        Compression::Gzip,
+        Compression::Zstd,
+        Compression::Bzip2,
+        Compression::Xz,
+    ];
+
+    #[test]
+    fn test_round_trip_and_detection() {
+        let data = b"name,age\nAda,36\nGrace,45\n".repeat(100);
+        for compression in CODECS {
+            let compressed = compress(&data, Some(compression)).unwrap();
+            assert_ne!(compressed, data);
+            assert_eq!(Compression::detect(&compressed), Some(compression));
+            assert_eq!(decompress(compressed).unwrap(), data, "{compression}");
+        }
+        assert_eq!(decompress(data.clone()).unwrap(), data);
+        assert_eq!(compress(&data, None).unwrap(), data);
+    }
+
+    #[test]
+    fn test_concatenated_streams() {
+        for compression in CODECS {
+            let mut compressed = compress(b"{\"a\":1}\n", Some(compression)).unwrap();
+            compressed.extend(compress(b"{\"a\":2}\n", Some(compression)).unwrap());
+            assert_eq!(
+                decompress(compressed).unwrap(),
+                b"{\"a\":1}\n{\"a\":2}\n",
+                "{compression}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_open_detects_compression_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events");
+        std::fs::write(&path, compress(b"line\n", Some(Compression::Zstd)).unwrap()).unwrap();
+        assert!(is_compressed(&path).unwrap());
+        let mut text = String::new();
+        open(&path).unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "line\n");
+    }
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(
+            Compression::from_path(Path::new("events.jsonl.gz")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(Compression::from_path(Path::new("events.jsonl")), None);
+        assert_eq!(Compression::parse("zst").unwrap(), Compression::Zstd);
+        assert!(Compression::parse("lz4").is_err());
+    }
+}
@@ -725,6 +725,40 @@ pub fn read_csv_file_with_options<P: AsRef<Path>>(
     reader.read_dataframe()
 }
 
+/// Scan a CSV file lazily with options
+///
+/// Nothing is read until the LazyFrame is collected, so the columns and
+/// predicates of a query, and its row limit, are pushed down into the scan.
+pub fn scan_csv_file_with_options<P: AsRef<Path>>(
+    path: P,
+    mut options: DsqCsvReadOptions,
+) -> Result<LazyFrame> {
+    use polars::prelude::{LazyCsvReader, LazyFileListReader, PlPath};
+
+    // Auto-detect TSV files
+    if path.as_ref().extension() == Some(std::ffi::OsStr::new("tsv")) {
+        options.separator = b'\t';
+    }
+    let encoding = match options.encoding {
+        CsvEncoding::Utf8 => polars::prelude::CsvEncoding::Utf8,
+        CsvEncoding::Utf8Lossy => polars::prelude::CsvEncoding::LossyUtf8,
+    };
+    LazyCsvReader::new(PlPath::Local(path.as_ref().into()))
+        .with_separator(options.separator)
+        .with_has_header(options.has_header && !options.ignore_header)
+        .with_quote_char(options.quote_char)
+        .with_comment_prefix(options.comment_char.map(|c| (c as char).to_string().into()))
+        .with_null_values(options.null_values.map(|values| {
+            NullValues::AllColumns(values.iter().map(|s| s.as_str().into()).collect())
+        }))
+        .with_encoding(encoding)
+        .with_infer_schema_length(options.infer_schema_length)
+        .with_skip_rows(options.skip_rows)
+        .with_skip_rows_after_header(options.skip_rows_after_header)
+        .finish()
+        .map_err(Error::from)
+}
+
 /// Convenience function to write DataFrame to CSV file
 pub fn write_csv_file<P: AsRef<Path>>(df: &DataFrame, path: P) -> Result<()> {
     let file = File::create(path)?;
@@ -958,7 +992,6 @@ fn array_to_dataframe(arr: &[Value]) -> Result<DataFrame> {
                 // On first row, establish column order
                 if row_idx == 0 {
                     column_order = obj.keys().cloned().collect();
-                    column_order.sort(); // Use sorted order for consistency
                     for col in &column_order {
                         columns.insert(col.clone(), Vec::new());
                     }
@@ -1032,6 +1065,19 @@ fn value_to_string(value: &Value) -> Result<String> {
         Value::Int(i) => Ok(i.to_string()),
         Value::Float(f) => Ok(f.to_string()),
         Value::String(s) => Ok(s.clone()),
+        Value::Date(_)
+        | Value::Datetime { .. }
+        | Value::Duration(_)
+        | Value::Decimal { .. }
+        | Value::Binary(_) => {
+            match value
+                .to_json()
+                .map_err(|e| Error::operation(format!("Failed to convert value to JSON: {}", e)))?
+            {
+                serde_json::Value::String(s) => Ok(s),
+                json => Ok(json.to_string()),
+            }
+        }
         _ => {
             // For complex types, convert to JSON string
             let json_val = value
@@ -1051,6 +1097,14 @@ fn value_to_any_value(value: &Value) -> Result<AnyValue<'static>> {
         Value::Int(i) => Ok(AnyValue::Int64(*i)),
         Value::Float(f) => Ok(AnyValue::Float64(*f)),
         Value::String(s) => Ok(AnyValue::StringOwned(s.clone().into())),
+        Value::Date(_) | Value::Datetime { .. } | Value::Decimal { .. } => value
+            .to_any_value()
+            .map(AnyValue::into_static)
+            .map_err(|e| Error::operation(format!("Failed to convert value: {}", e))),
+        // CSV has no duration or binary columns, so write their JSON text
+        Value::Duration(_) | Value::Binary(_) => {
+            Ok(AnyValue::StringOwned(value_to_string(value)?.into()))
+        }
         _ => {
             // For complex types, convert to JSON string
             let json_val = value
@@ -1111,6 +1165,14 @@ pub fn serialize_csv<W: Write>(
             let col = Column::new("value".into(), &[bi.to_string()]);
             DataFrame::new(vec![col]).map_err(Error::from)?
         }
+        Value::Date(_)
+        | Value::Datetime { .. }
+        | Value::Duration(_)
+        | Value::Decimal { .. }
+        | Value::Binary(_) => {
+            let col = Column::new("value".into(), vec![value_to_any_value(value)?]);
+            DataFrame::new(vec![col]).map_err(Error::from)?
+        }
     };
 
     let csv_opts = match format_options {
@@ -1146,15 +1208,15 @@ pub fn serialize_csv<W: Write>(
 mod tests {
     use super::{
         array_to_dataframe, detect_csv_dialect, detect_csv_format, read_csv_file,
-        read_csv_file_with_options, serialize_csv, value_to_any_value, write_csv_file,
-        write_csv_file_with_options, CsvEncoding, CsvReader, CsvWriteOptions, DsqCsvReadOptions,
-        Error, FormatError, QuoteStyle, Value,
+        read_csv_file_with_options, scan_csv_file_with_options, serialize_csv, value_to_any_value,
+        write_csv_file, write_csv_file_with_options, CsvEncoding, CsvReader, CsvWriteOptions,
+        DsqCsvReadOptions, Error, FormatError, QuoteStyle, Value,
     };
     use crate::csv::CsvWriter;
     use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
     use polars::{
         df,
-        prelude::{Column, DataFrame, NamedFrom, Series},
+        prelude::{col, lit, Column, DataFrame, NamedFrom, Series},
     };
     use std::fs;
     use std::io::Cursor;
@@ -1855,6 +1917,42 @@ mod tests {
         assert_eq!(df.get_column_names(), vec!["name", "age"]);
     }
 
+    #[test]
+    fn test_scan_csv_file_with_options() {
+        let csv_content = "# people\nname;age;city\nAlice;30;NA\nBob;25;Paris\nCarol;41;Rome\n";
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(&temp_file, csv_content).unwrap();
+
+        let options = DsqCsvReadOptions {
+            separator: b';',
+            comment_char: Some(b'#'),
+            null_values: Some(vec!["NA".to_string()]),
+            ..Default::default()
+        };
+        let lf = scan_csv_file_with_options(temp_file.path(), options.clone())
+            .unwrap()
+            .filter(col("age").gt(lit(26)))
+            .select([col("name")]);
+
+        // The projection and the predicate run inside the scan
+        let plan = lf.clone().describe_optimized_plan().unwrap();
+        assert!(plan.contains("PROJECT 2/3 COLUMNS"), "{plan}");
+        assert!(plan.contains("SELECTION"), "{plan}");
+
+        let df = lf.collect().unwrap();
+        assert_eq!(
+            df.column("name").unwrap().str().unwrap().get(1),
+            Some("Carol")
+        );
+
+        let df = scan_csv_file_with_options(temp_file.path(), options)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(df.height(), 3);
+        assert_eq!(df.column("city").unwrap().str().unwrap().get(0), None);
+    }
+
     #[test]
     fn test_write_csv_file() {
         let df = df! {
@@ -2061,8 +2159,8 @@ mod tests {
     mod serialize_arbitrary_data {
         use super::*;
         use crate::writer::{CsvEncoding, FormatWriteOptions, WriteOptions};
+        use indexmap::IndexMap;
         use polars::prelude::IntoLazy;
-        use std::collections::HashMap;
 
         fn default_write_options() -> WriteOptions {
             WriteOptions::default()
@@ -2087,11 +2185,11 @@ mod tests {
         #[test]
         fn test_serialize_array_of_objects() {
             let value = Value::Array(vec![
-                Value::Object(HashMap::from([
+                Value::Object(IndexMap::from([
                     ("name".to_string(), Value::String("Alice".to_string())),
                     ("age".to_string(), Value::Int(30)),
                 ])),
-                Value::Object(HashMap::from([
+                Value::Object(IndexMap::from([
                     ("name".to_string(), Value::String("Bob".to_string())),
                     ("age".to_string(), Value::Int(25)),
                 ])),
@@ -2226,7 +2324,7 @@ mod tests {
 
         #[test]
         fn test_serialize_single_object() {
-            let value = Value::Object(HashMap::from([
+            let value = Value::Object(IndexMap::from([
                 ("name".to_string(), Value::String("Alice".to_string())),
                 ("age".to_string(), Value::Int(30)),
                 ("active".to_string(), Value::Bool(true)),
@@ -2412,11 +2510,11 @@ mod tests {
         #[test]
         fn test_serialize_nested_objects_in_array() {
             // Nested objects should have their complex values serialized as JSON strings
-            let value = Value::Array(vec![Value::Object(HashMap::from([
+            let value = Value::Array(vec![Value::Object(IndexMap::from([
                 ("name".to_string(), Value::String("Alice".to_string())),
                 (
                     "details".to_string(),
-                    Value::Object(HashMap::from([(
+                    Value::Object(IndexMap::from([(
                         "city".to_string(),
                         Value::String("NYC".to_string()),
                     )])),
@@ -2498,11 +2596,11 @@ mod tests {
         #[test]
         fn test_array_to_dataframe_with_objects() {
             let arr = vec![
-                Value::Object(HashMap::from([
+                Value::Object(IndexMap::from([
                     ("a".to_string(), Value::Int(1)),
                     ("b".to_string(), Value::String("x".to_string())),
                 ])),
-                Value::Object(HashMap::from([
+                Value::Object(IndexMap::from([
                     ("a".to_string(), Value::Int(2)),
                     ("b".to_string(), Value::String("y".to_string())),
                 ])),
@@ -2554,7 +2652,7 @@ mod tests {
         #[test]
         fn test_value_to_any_value_complex_types_to_json_string() {
             // Complex types should be converted to JSON strings
-            let obj = Value::Object(HashMap::from([("key".to_string(), Value::Int(1))]));
+            let obj = Value::Object(IndexMap::from([("key".to_string(), Value::Int(1))]));
             let result = value_to_any_value(&obj).unwrap();
             // Should be a string containing JSON
             match result {
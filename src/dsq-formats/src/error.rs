@@ -141,6 +141,34 @@ impl From<apache_avro::Error> for Error {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl From<calamine::XlsxError> for Error {
+    fn from(e: calamine::XlsxError) -> Self {
+        Error::Format(FormatError::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<rust_xlsxwriter::XlsxError> for Error {
+    fn from(e: rust_xlsxwriter::XlsxError) -> Self {
+        Error::Format(FormatError::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<orc_rust::error::OrcError> for Error {
+    fn from(e: orc_rust::error::OrcError) -> Self {
+        Error::Format(FormatError::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<arrow::error::ArrowError> for Error {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        Error::Format(FormatError::SerializationError(e.to_string()))
+    }
+}
+
 impl From<FormatError> for Error {
     fn from(e: FormatError) -> Self {
         Error::Format(e)
@@ -0,0 +1,597 @@
+//! Excel (.xlsx) format support
+//!
+//! Worksheets are read with calamine and written with rust_xlsxwriter.
+//! Cells keep their types both ways: numbers, booleans, dates, datetimes and
+//! durations are read into Polars columns of the matching dtype, and are
+//! written as typed cells rather than as text.
+
+use crate::error::{Error, FormatError, Result};
+use crate::reader::{ExcelSheet, FormatReadOptions, ReadOptions};
+use crate::writer::{FormatWriteOptions, WriteOptions};
+use calamine::{Data, Range, Reader, Xlsx};
+use chrono::{NaiveDate, NaiveDateTime};
+use dsq_shared::value::{local_datetime, schema::values_to_series, value_from_any_value, Value};
+use polars::prelude::*;
+use rust_xlsxwriter::{Format, FormatBorder, Workbook, Worksheet};
+use std::collections::HashSet;
+use std::io::{Read, Seek, Write};
+
+/// Largest number of rows in a worksheet
+const MAX_ROWS: usize = 1_048_576;
+
+/// Number format of date cells
+const DATE_FORMAT: &str = "yyyy-mm-dd";
+/// Number format of datetime cells
+const DATETIME_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+/// Number format of duration cells
+const DURATION_FORMAT: &str = "[h]:mm:ss";
+
+/// Deserialize a worksheet of an Excel workbook from a reader
+///
+/// The worksheet, the header row and the cell range come from
+/// `FormatReadOptions::Excel`; other format options read the first
+/// worksheet with a header row.
+pub fn deserialize_excel<R: Read + Seek>(
+    reader: R,
+    options: &ReadOptions,
+    format_options: &FormatReadOptions,
+) -> Result<Value> {
+    let (sheet, has_header, range) = match format_options {
+        FormatReadOptions::Excel {
+            sheet,
+            has_header,
+            range,
+        } => (sheet.clone(), *has_header, range.as_deref()),
+        _ => (None, true, None),
+    };
+
+    let mut workbook: Xlsx<R> = Xlsx::new(reader).map_err(Error::from)?;
+    let mut cells = read_sheet(&mut workbook, sheet.as_ref())?;
+    if let Some(range) = range {
+        let (start, end) = parse_cell_range(range)?;
+        cells = match end.or(cells.end()) {
+            Some(end) if end.0 >= start.0 && end.1 >= start.1 => cells.range(start, end),
+            _ => Range::empty(),
+        };
+    }
+
+    let mut rows = cells.rows();
+    let width = cells.width();
+    let names = if has_header {
+        header_names(rows.next().unwrap_or_default(), width)
+    } else {
+        (1..=width).map(|i| format!("column_{i}")).collect()
+    };
+
+    let rows: Vec<&[Data]> = rows
+        .skip(options.skip_rows)
+        .take(options.max_rows.unwrap_or(usize::MAX))
+        .collect();
+
+    let mut columns = Vec::with_capacity(width);
+    for (idx, name) in names.iter().enumerate() {
+        if let Some(selected) = &options.columns {
+            if !selected.contains(name) {
+                continue;
+            }
+        }
+        let mut values: Vec<Value> = rows
+            .iter()
+            .map(|row| row.get(idx).map_or(Value::Null, cell_to_value))
+            .collect();
+        promote_dates(&mut values);
+        let values: Vec<&Value> = values.iter().collect();
+        columns.push(values_to_series(name, &values)?.into());
+    }
+
+    let df = DataFrame::new(columns).map_err(Error::from)?;
+    Ok(Value::DataFrame(df))
+}
+
+/// The cells of the selected worksheet, or of the first one
+fn read_sheet<R: Read + Seek>(
+    workbook: &mut Xlsx<R>,
+    sheet: Option<&ExcelSheet>,
+) -> Result<Range<Data>> {
+    let names = workbook.sheet_names();
+    let not_found = |sheet: &ExcelSheet| {
+        Error::Format(FormatError::InvalidOption(format!(
+            "worksheet {sheet} not found; the workbook has {}",
+            names.join(", ")
+        )))
+    };
+    match sheet {
+        Some(ExcelSheet::Name(name)) => {
+            if !names.contains(name) {
+                return Err(not_found(&ExcelSheet::Name(name.clone())));
+            }
+            workbook.worksheet_range(name).map_err(Error::from)
+        }
+        Some(ExcelSheet::Index(index)) => workbook
+            .worksheet_range_at(*index)
+            .ok_or_else(|| not_found(&ExcelSheet::Index(*index)))?
+            .map_err(Error::from),
+        None => workbook
+            .worksheet_range_at(0)
+            .ok_or_else(|| Error::operation("Excel workbook has no worksheets"))?
+            .map_err(Error::from),
+    }
+}
+
+/// Column names from a header row; blank and repeated names are made unique
+fn header_names(header: &[Data], width: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    (0..width)
+        .map(|idx| {
+            let name = match header.get(idx) {
+                Some(Data::Empty) | None => format!("column_{}", idx + 1),
+                Some(cell) => cell.to_string(),
+            };
+            let mut unique = name.clone();
+            let mut n = 1;
+            while !seen.insert(unique.clone()) {
+                n += 1;
+                unique = format!("{name}_{n}");
+            }
+            unique
+        })
+        .collect()
+}
+
+/// Convert a worksheet cell to a value
+///
+/// Numbers without a fractional part become integers, since a workbook
+/// stores every number as a float. Error cells such as `#N/A` become null.
+fn cell_to_value(cell: &Data) -> Value {
+    match cell {
+        Data::Empty | Data::Error(_) => Value::Null,
+        Data::Bool(b) => Value::Bool(*b),
+        Data::Int(i) => Value::Int(*i),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 9.0e15 => Value::Int(*f as i64),
+        Data::Float(f) => Value::Float(*f),
+        Data::String(s) => Value::String(s.clone()),
+        Data::DateTime(dt) if dt.is_duration() => {
+            dt.as_duration().map_or(Value::Null, Value::Duration)
+        }
+        Data::DateTime(dt) => dt.as_datetime().map_or(Value::Null, datetime_value),
+        Data::DateTimeIso(s) => NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+            .map(datetime_value)
+            .or_else(|_| NaiveDate::parse_from_str(s, "%Y-%m-%d").map(Value::Date))
+            .unwrap_or_else(|_| Value::String(s.clone())),
+        Data::DurationIso(s) => Value::String(s.clone()),
+    }
+}
+
+/// A date when the time of `datetime` is midnight, a datetime otherwise
+fn datetime_value(datetime: NaiveDateTime) -> Value {
+    if datetime.time() == chrono::NaiveTime::MIN {
+        Value::Date(datetime.date())
+    } else {
+        Value::Datetime { datetime, tz: None }
+    }
+}
+
+/// Turn the dates of a column that also holds datetimes into datetimes at
+/// midnight, so the column keeps one temporal dtype
+fn promote_dates(values: &mut [Value]) {
+    if !values
+        .iter()
+        .any(|value| matches!(value, Value::Datetime { .. }))
+    {
+        return;
+    }
+    for value in values {
+        if let Value::Date(date) = value {
+            *value = Value::Datetime {
+                datetime: date.and_time(chrono::NaiveTime::MIN),
+                tz: None,
+            };
+        }
+    }
+}
+
+/// Parse a cell range such as `B2:D10` into zero-based (row, column)
+/// corners; a single cell such as `B2` reaches to the end of the worksheet
+pub fn parse_cell_range(range: &str) -> Result<((u32, u32), Option<(u32, u32)>)> {
+    let invalid = || {
+        Error::Format(FormatError::InvalidOption(format!(
+            "invalid cell range {range}; use a range such as A1:D100"
+        )))
+    };
+    let (start, end) = match range.split_once(':') {
+        Some((start, end)) => (start, Some(end)),
+        None => (range, None),
+    };
+    let start = parse_cell(start).ok_or_else(invalid)?;
+    let end = end
+        .map(|end| parse_cell(end).ok_or_else(invalid))
+        .transpose()?;
+    match end {
+        Some(end) if end.0 < start.0 || end.1 < start.1 => Err(invalid()),
+        _ => Ok((start, end)),
+    }
+}
+
+/// Parse a cell reference such as `B2` into a zero-based (row, column)
+fn parse_cell(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.trim().replace('$', "");
+    let digits = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(digits);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let col = letters.chars().try_fold(0u32, |col, c| {
+        let letter = c.to_ascii_uppercase() as u32 - 'A' as u32 + 1;
+        col.checked_mul(26)?.checked_add(letter)
+    })?;
+    let row: u32 = digits.parse().ok()?;
+    (row > 0).then(|| (row - 1, col - 1))
+}
+
+/// Serialize data to an Excel workbook with a single worksheet
+///
+/// The header row is bold with a bottom border and stays in view when
+/// scrolling. Numbers, booleans, dates, datetimes and durations are written
+/// as typed cells; nested values are written as JSON text.
+pub fn serialize_excel<W: Write>(
+    mut writer: W,
+    value: &Value,
+    options: &WriteOptions,
+    format_options: &FormatWriteOptions,
+) -> Result<()> {
+    let (sheet_name, include_header, autofit, float_precision) = match format_options {
+        FormatWriteOptions::Excel {
+            worksheet_name,
+            include_header,
+            autofit,
+            float_precision,
+        } => (
+            worksheet_name.as_str(),
+            *include_header && options.include_header,
+            *autofit,
+            *float_precision,
+        ),
+        _ => ("Sheet1", options.include_header, true, None),
+    };
+
+    let df = match value {
+        Value::Series(series) => DataFrame::new(vec![series.clone().into()])?,
+        Value::Object(_) => Value::Array(vec![value.clone()]).to_dataframe()?,
+        Value::DataFrame(_) | Value::LazyFrame(_) | Value::Array(_) => value.to_dataframe()?,
+        other => {
+            return Err(Error::operation(format!(
+                "Expected tabular data for Excel serialization, got {}",
+                other.type_name()
+            )));
+        }
+    };
+
+    let header_rows = usize::from(include_header);
+    if df.height() + header_rows > MAX_ROWS {
+        return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+            "Excel worksheets hold at most {MAX_ROWS} rows, got {}",
+            df.height() + header_rows
+        ))));
+    }
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet_name)?;
+
+    let formats = CellFormats::new(float_precision);
+    if include_header {
+        let header = Format::new()
+            .set_bold()
+            .set_border_bottom(FormatBorder::Thin);
+        for (col, name) in df.get_column_names().iter().enumerate() {
+            worksheet.write_string_with_format(0, column_number(col)?, name.as_str(), &header)?;
+        }
+        worksheet.set_freeze_panes(1, 0)?;
+    }
+
+    for (col, column) in df.get_columns().iter().enumerate() {
+        let col = column_number(col)?;
+        for idx in 0..df.height() {
+            let cell = value_from_any_value(column.get(idx)?).unwrap_or(Value::Null);
+            let row = (idx + header_rows) as u32;
+            write_cell(worksheet, row, col, &cell, &formats)?;
+        }
+    }
+
+    if autofit {
+        worksheet.autofit();
+    }
+
+    writer.write_all(&workbook.save_to_buffer()?)?;
+    Ok(())
+}
+
+/// Number formats of typed cells
+struct CellFormats {
+    float: Option<Format>,
+    date: Format,
+    datetime: Format,
+    duration: Format,
+}
+
+impl CellFormats {
+    fn new(float_precision: Option<usize>) -> Self {
+        Self {
+            float: float_precision.map(|precision| {
+                let format = match precision {
+                    0 => "0".to_string(),
+                    n => format!("0.{}", "0".repeat(n)),
+                };
+                Format::new().set_num_format(format)
+            }),
+            date: Format::new().set_num_format(DATE_FORMAT),
+            datetime: Format::new().set_num_format(DATETIME_FORMAT),
+            duration: Format::new().set_num_format(DURATION_FORMAT),
+        }
+    }
+}
+
+/// Write a value to a cell with the type of the value
+fn write_cell(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    value: &Value,
+    formats: &CellFormats,
+) -> Result<()> {
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => {
+            worksheet.write_boolean(row, col, *b)?;
+        }
+        Value::Int(i) => {
+            worksheet.write_number(row, col, *i as f64)?;
+        }
+        Value::Float(f) => match &formats.float {
+            Some(format) => {
+                worksheet.write_number_with_format(row, col, *f, format)?;
+            }
+            None => {
+                worksheet.write_number(row, col, *f)?;
+            }
+        },
+        Value::Decimal { value, scale } => {
+            let number = *value as f64 / 10f64.powi(*scale as i32);
+            worksheet.write_number(row, col, number)?;
+        }
+        Value::String(s) => {
+            worksheet.write_string(row, col, s)?;
+        }
+        Value::Date(date) => {
+            worksheet.write_datetime_with_format(row, col, date, &formats.date)?;
+        }
+        Value::Datetime { datetime, tz } => {
+            let local = local_datetime(*datetime, tz.as_deref());
+            worksheet.write_datetime_with_format(row, col, &local, &formats.datetime)?;
+        }
+        Value::Duration(duration) => {
+            // Excel durations are fractions of a day
+            let days = duration.num_milliseconds() as f64 / 86_400_000.0;
+            worksheet.write_number_with_format(row, col, days, &formats.duration)?;
+        }
+        other => {
+            let text = match other.to_json()? {
+                serde_json::Value::String(s) => s,
+                json => json.to_string(),
+            };
+            worksheet.write_string(row, col, text)?;
+        }
+    }
+    Ok(())
+}
+
+/// The worksheet column of the `idx`th DataFrame column
+fn column_number(idx: usize) -> Result<u16> {
+    u16::try_from(idx)
+        .ok()
+        .filter(|&col| col < 16_384)
+        .ok_or_else(|| {
+            Error::Format(FormatError::UnsupportedFeature(
+                "Excel worksheets hold at most 16384 columns".to_string(),
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn people() -> DataFrame {
+        df! {
+            "name" => ["Ada", "Grace", "Edsger"],
+            "age" => [36i64, 45, 72],
+            "score" => [9.5f64, 8.25, 7.0],
+            "active" => [true, false, true],
+            "born" => [
+                NaiveDate::from_ymd_opt(1915, 12, 10).unwrap(),
+                NaiveDate::from_ymd_opt(1906, 12, 9).unwrap(),
+                NaiveDate::from_ymd_opt(1930, 5, 11).unwrap(),
+            ],
+        }
+        .unwrap()
+    }
+
+    fn write(value: &Value, format_options: &FormatWriteOptions) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        serialize_excel(&mut buffer, value, &WriteOptions::default(), format_options).unwrap();
+        buffer
+    }
+
+    fn read(
+        bytes: Vec<u8>,
+        options: &ReadOptions,
+        format_options: &FormatReadOptions,
+    ) -> DataFrame {
+        match deserialize_excel(Cursor::new(bytes), options, format_options).unwrap() {
+            Value::DataFrame(df) => df,
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
+    fn excel_options(
+        sheet: Option<ExcelSheet>,
+        has_header: bool,
+        range: Option<&str>,
+    ) -> FormatReadOptions {
+        FormatReadOptions::Excel {
+            sheet,
+            has_header,
+            range: range.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_excel_round_trip_keeps_types() {
+        let bytes = write(
+            &Value::DataFrame(people()),
+            &FormatWriteOptions::Excel {
+                worksheet_name: "People".to_string(),
+                include_header: true,
+                autofit: true,
+                float_precision: Some(2),
+            },
+        );
+        assert!(bytes.starts_with(b"PK"));
+
+        let df = read(
+            bytes,
+            &ReadOptions::default(),
+            &FormatReadOptions::default(),
+        );
+        assert_eq!(df.get_column_names(), people().get_column_names());
+        assert_eq!(df.column("name").unwrap().dtype(), &DataType::String);
+        assert_eq!(df.column("age").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(df.column("score").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(df.column("active").unwrap().dtype(), &DataType::Boolean);
+        assert_eq!(df.column("born").unwrap().dtype(), &DataType::Date);
+        assert!(df.equals(&people()));
+    }
+
+    #[test]
+    fn test_excel_datetime_column_at_midnight() {
+        let at = |hour, minute| {
+            NaiveDate::from_ymd_opt(2024, 3, 1)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap()
+        };
+        let df = df! { "at" => [at(0, 0), at(13, 45)] }.unwrap();
+        let df = read(
+            write(&Value::DataFrame(df), &FormatWriteOptions::default()),
+            &ReadOptions::default(),
+            &FormatReadOptions::default(),
+        );
+
+        let column = df.column("at").unwrap();
+        assert!(matches!(column.dtype(), DataType::Datetime(_, None)));
+        assert_eq!(
+            value_from_any_value(column.get(0).unwrap()).unwrap(),
+            Value::Datetime {
+                datetime: at(0, 0),
+                tz: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_excel_sheet_selection() {
+        let bytes = write(
+            &Value::DataFrame(people()),
+            &FormatWriteOptions::Excel {
+                worksheet_name: "People".to_string(),
+                include_header: true,
+                autofit: false,
+                float_precision: None,
+            },
+        );
+
+        let by_name = excel_options(Some(ExcelSheet::Name("People".to_string())), true, None);
+        assert_eq!(
+            read(bytes.clone(), &ReadOptions::default(), &by_name).height(),
+            3
+        );
+        let by_index = excel_options(Some(ExcelSheet::Index(0)), true, None);
+        assert_eq!(
+            read(bytes.clone(), &ReadOptions::default(), &by_index).height(),
+            3
+        );
+
+        let missing = excel_options(Some(ExcelSheet::Name("Sales".to_string())), true, None);
+        let error = deserialize_excel(Cursor::new(bytes), &ReadOptions::default(), &missing)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("Sales") && error.contains("People"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_excel_range_and_header() {
+        let bytes = write(&Value::DataFrame(people()), &FormatWriteOptions::default());
+
+        // B2:C3 holds the ages and scores of the first two people
+        let df = read(
+            bytes.clone(),
+            &ReadOptions::default(),
+            &excel_options(None, false, Some("B2:C3")),
+        );
+        assert_eq!(df.get_column_names(), ["column_1", "column_2"]);
+        assert_eq!(
+            df.column("column_1").unwrap().i64().unwrap().get(1),
+            Some(45)
+        );
+
+        let options = ReadOptions {
+            skip_rows: 1,
+            max_rows: Some(1),
+            columns: Some(vec!["name".to_string()]),
+            ..Default::default()
+        };
+        let df = read(bytes, &options, &excel_options(None, true, Some("A1")));
+        assert_eq!(df.shape(), (1, 1));
+        assert_eq!(
+            df.column("name").unwrap().str().unwrap().get(0),
+            Some("Grace")
+        );
+    }
+
+    #[test]
+    fn test_excel_array_of_objects() {
+        let value = Value::Array(vec![
+            Value::Object([("id".to_string(), Value::Int(1))].into_iter().collect()),
+            Value::Object([("id".to_string(), Value::Int(2))].into_iter().collect()),
+        ]);
+        let df = read(
+            write(&value, &FormatWriteOptions::default()),
+            &ReadOptions::default(),
+            &FormatReadOptions::default(),
+        );
+        assert_eq!(df.column("id").unwrap().i64().unwrap().sum(), Some(3));
+        assert!(serialize_excel(
+            Vec::new(),
+            &Value::Int(1),
+            &WriteOptions::default(),
+            &FormatWriteOptions::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_cell_range() {
+        assert_eq!(
+            parse_cell_range("A1:D100").unwrap(),
+            ((0, 0), Some((99, 3)))
+        );
+        assert_eq!(parse_cell_range("$AA$10").unwrap(), ((9, 26), None));
+        assert!(parse_cell_range("D4:A1").is_err());
+        assert!(parse_cell_range("A0").is_err());
+        assert!(parse_cell_range("12").is_err());
+    }
+}
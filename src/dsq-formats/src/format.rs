@@ -23,22 +23,28 @@ pub enum DataFormat {
         value(name = "json-lines", alias = "jsonl", alias = "ndjson")
     )]
     JsonLines,
-    /// Apache Arrow format
+    /// Apache Arrow IPC format, as files (Feather v2) or streams
+    #[cfg_attr(feature = "cli", value(alias = "feather", alias = "ipc"))]
     Arrow,
     /// Standard JSON (array of objects)
     Json,
     /// Compact JSON (no pretty printing)
     #[cfg_attr(feature = "cli", value(name = "jsonc", alias = "json-compact"))]
     JsonCompact,
-    /// Microsoft Excel format (output only)
+    /// Microsoft Excel workbook format
     Excel,
-    /// Apache ORC columnar format (output only)
+    /// Apache ORC columnar format
     Orc,
 }
 
 impl DataFormat {
-    /// Detect format from file extension
+    /// Detect format from file extension, ignoring the extension of a
+    /// compression as in `events.jsonl.gz`
     pub fn from_path(path: &Path) -> Result<Self> {
+        let path = match crate::compression::Compression::from_path(path) {
+            Some(_) => Path::new(path.file_stem().unwrap_or_default()),
+            None => path,
+        };
         let ext = path.extension().and_then(|e| e.to_str()).ok_or_else(|| {
             Error::Format(FormatError::DetectionFailed(path.display().to_string()))
         })?;
@@ -55,7 +61,7 @@ impl DataFormat {
             "parquet" => Ok(Self::Parquet),
             "avro" => Ok(Self::Avro),
             "jsonl" | "ndjson" => Ok(Self::JsonLines),
-            "arrow" => Ok(Self::Arrow),
+            "arrow" | "arrows" | "feather" | "ipc" => Ok(Self::Arrow),
             "json" => Ok(Self::Json),
             "jsonc" => Ok(Self::JsonCompact),
             "xlsx" => Ok(Self::Excel),
@@ -73,7 +79,7 @@ impl DataFormat {
             "parquet" => Ok(Self::Parquet),
             "avro" => Ok(Self::Avro),
             "jsonl" | "json-lines" | "ndjson" => Ok(Self::JsonLines),
-            "arrow" => Ok(Self::Arrow),
+            "arrow" | "feather" | "ipc" => Ok(Self::Arrow),
             "json" => Ok(Self::Json),
             "jsonc" | "json-compact" => Ok(Self::JsonCompact),
             "excel" | "xlsx" => Ok(Self::Excel),
@@ -110,8 +116,9 @@ impl DataFormat {
             | Self::JsonLines
             | Self::Arrow
             | Self::Json
-            | Self::JsonCompact => true,
-            Self::Excel | Self::Orc => false,
+            | Self::JsonCompact
+            | Self::Excel
+            | Self::Orc => true,
         }
     }
 
@@ -180,6 +187,46 @@ impl FromStr for DataFormat {
     }
 }
 
+/// Framing of Apache Arrow IPC data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcFraming {
+    /// IPC file format (Feather v2): `ARROW1` magic bytes, the record batches
+    /// and a footer indexing them
+    File,
+    /// IPC stream format: a sequence of messages without a footer, which
+    /// can be written to and read from a pipe
+    Stream,
+}
+
+impl IpcFraming {
+    /// Framing a path is written with: the stream format for `.arrows`
+    /// files, the file format otherwise
+    pub fn from_path(path: &Path) -> Self {
+        let path = match crate::compression::Compression::from_path(path) {
+            Some(_) => Path::new(path.file_stem().unwrap_or_default()),
+            None => path,
+        };
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("arrows") => Self::Stream,
+            _ => Self::File,
+        }
+    }
+
+    /// Detect the framing of Arrow IPC data from its first bytes
+    ///
+    /// Streams start with the `0xFFFFFFFF` continuation marker and the
+    /// length of the schema message.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() >= 8 && &bytes[0..6] == b"ARROW1" {
+            Some(Self::File)
+        } else if bytes.len() >= 8 && bytes[0..4] == [0xff; 4] && bytes[4..8] != [0; 4] {
+            Some(Self::Stream)
+        } else {
+            None
+        }
+    }
+}
+
 /// Options for format detection
 #[derive(Debug, Clone)]
 pub struct FormatOptions {
@@ -215,8 +262,8 @@ pub fn detect_format_from_content(bytes: &[u8]) -> Option<DataFormat> {
         return Some(DataFormat::Avro);
     }
 
-    // Arrow magic bytes: "ARROW1\x00\x00"
-    if bytes.len() >= 8 && &bytes[0..6] == b"ARROW1" {
+    // Arrow magic bytes: "ARROW1\x00\x00", or a stream's continuation marker
+    if IpcFraming::detect(bytes).is_some() {
         return Some(DataFormat::Arrow);
     }
 
@@ -306,6 +353,14 @@ mod tests {
             DataFormat::from_extension("arrow").unwrap(),
             DataFormat::Arrow
         );
+        assert_eq!(
+            DataFormat::from_extension("arrows").unwrap(),
+            DataFormat::Arrow
+        );
+        assert_eq!(
+            DataFormat::from_extension("feather").unwrap(),
+            DataFormat::Arrow
+        );
         assert_eq!(
             DataFormat::from_extension("json").unwrap(),
             DataFormat::Json
@@ -376,8 +431,8 @@ mod tests {
         assert!(DataFormat::Arrow.supports_reading());
         assert!(DataFormat::Json.supports_reading());
         assert!(DataFormat::JsonCompact.supports_reading());
-        assert!(!DataFormat::Excel.supports_reading());
-        assert!(!DataFormat::Orc.supports_reading());
+        assert!(DataFormat::Excel.supports_reading());
+        assert!(DataFormat::Orc.supports_reading());
 
         // Test writing support (all should support)
         assert!(DataFormat::Csv.supports_writing());
@@ -433,6 +488,10 @@ mod tests {
             detect_format_from_content(b"ARROW1\x00\x00"),
             Some(DataFormat::Arrow)
         );
+        assert_eq!(
+            detect_format_from_content(b"\xff\xff\xff\xff\x78\x00\x00\x00"),
+            Some(DataFormat::Arrow)
+        );
         assert_eq!(detect_format_from_content(b"ORC"), Some(DataFormat::Orc));
 
         assert_eq!(
@@ -493,6 +552,15 @@ mod tests {
         );
         assert!(DataFormat::from_path(Path::new("file")).is_err());
         assert!(DataFormat::from_path(Path::new("file.unknown")).is_err());
+        assert_eq!(
+            DataFormat::from_path(Path::new("events.jsonl.gz")).unwrap(),
+            DataFormat::JsonLines
+        );
+        assert_eq!(
+            DataFormat::from_path(Path::new("logs/2024.tsv.zst")).unwrap(),
+            DataFormat::Tsv
+        );
+        assert!(DataFormat::from_path(Path::new("file.gz")).is_err());
     }
 
     #[test]
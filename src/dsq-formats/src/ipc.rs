@@ -0,0 +1,272 @@
+//! Apache Arrow IPC (.arrow, .arrows, .feather) format support
+//!
+//! Arrow data is framed in one of two ways: the IPC file format, also known
+//! as Feather v2, which starts with `ARROW1` and ends with a footer indexing
+//! its record batches, and the IPC stream format, a plain sequence of
+//! messages that can be written to a pipe as it is produced. Readers tell the
+//! two apart from their first bytes, so either can be read from a file or
+//! from stdin.
+//!
+//! IPC files on disk are memory-mapped: the record batch buffers are used in
+//! place rather than copied, which makes opening even large files cheap.
+//! Files whose buffers are LZ4 or Zstandard compressed are decompressed as
+//! they are read instead.
+
+use crate::compression::Compression;
+use crate::error::{Error, FormatError, Result};
+use crate::format::IpcFraming;
+use crate::reader::ReadOptions;
+use crate::writer::{ArrowCompression, FormatWriteOptions, WriteOptions};
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::reader::FileReader as ArrowFileReader;
+use arrow::ipc::writer::FileWriter as ArrowFileWriter;
+use arrow::record_batch::RecordBatch;
+use dsq_shared::value::Value;
+use polars::io::mmap::MmapBytesReader;
+use polars::prelude::*;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Deserialize Arrow IPC data, in either framing, from a reader
+pub fn deserialize_arrow<R: Read>(mut reader: R, options: &ReadOptions) -> Result<Value> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let df = match IpcFraming::detect(&bytes) {
+        Some(IpcFraming::File) => read_file(IpcReader::new(Cursor::new(bytes)), options)?,
+        Some(IpcFraming::Stream) => read_stream(Cursor::new(bytes), options)?,
+        None => return Err(not_arrow()),
+    };
+    Ok(Value::DataFrame(df))
+}
+
+/// Read the Arrow IPC file or stream at `path`
+///
+/// IPC files are memory-mapped; streams and compressed files are read
+/// through a buffer.
+pub fn read_arrow_file(path: &Path, options: &ReadOptions) -> Result<Value> {
+    let mut file = File::open(path)?;
+    let mut head = Vec::with_capacity(8);
+    (&mut file).take(8).read_to_end(&mut head)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    if Compression::detect(&head).is_some() {
+        return deserialize_arrow(crate::compression::open(path)?, options);
+    }
+    let df = match IpcFraming::detect(&head) {
+        Some(IpcFraming::File) => read_file(
+            IpcReader::new(file).memory_mapped(Some(path.to_path_buf())),
+            options,
+        )?,
+        Some(IpcFraming::Stream) => read_stream(BufReader::new(file), options)?,
+        None => return Err(not_arrow()),
+    };
+    Ok(Value::DataFrame(df))
+}
+
+fn read_file<R: MmapBytesReader>(reader: IpcReader<R>, options: &ReadOptions) -> Result<DataFrame> {
+    let df = reader
+        .with_columns(options.columns.clone())
+        .with_n_rows(row_limit(options))
+        .finish()?;
+    select_rows(df, options)
+}
+
+fn read_stream<R: Read + Seek>(reader: R, options: &ReadOptions) -> Result<DataFrame> {
+    let df = IpcStreamReader::new(reader)
+        .with_columns(options.columns.clone())
+        .with_n_rows(row_limit(options))
+        .finish()?;
+    select_rows(df, options)
+}
+
+/// Rows to read so that `max_rows` remain after skipping `skip_rows`
+fn row_limit(options: &ReadOptions) -> Option<usize> {
+    options
+        .max_rows
+        .map(|max_rows| max_rows.saturating_add(options.skip_rows))
+}
+
+/// Skip the first `skip_rows` rows and put the columns in the requested order
+fn select_rows(df: DataFrame, options: &ReadOptions) -> Result<DataFrame> {
+    let df = if options.skip_rows > 0 {
+        df.slice(options.skip_rows as i64, usize::MAX)
+    } else {
+        df
+    };
+    match &options.columns {
+        Some(columns) => Ok(df.select(columns.iter().map(String::as_str))?),
+        None => Ok(df),
+    }
+}
+
+fn not_arrow() -> Error {
+    Error::Format(FormatError::SerializationError(
+        "not an Arrow IPC file or stream".to_string(),
+    ))
+}
+
+/// Serialize data to an Arrow IPC file or stream
+///
+/// The compression and framing come from `FormatWriteOptions::Arrow`; other
+/// format options write an uncompressed IPC file.
+pub fn serialize_arrow<W: Write>(
+    writer: W,
+    value: &Value,
+    _options: &WriteOptions,
+    format_options: &FormatWriteOptions,
+) -> Result<()> {
+    let (compression, framing) = match format_options {
+        FormatWriteOptions::Arrow {
+            compression,
+            framing,
+        } => (*compression, *framing),
+        _ => (ArrowCompression::Uncompressed, IpcFraming::File),
+    };
+    let compression = match compression {
+        ArrowCompression::Uncompressed => None,
+        ArrowCompression::Lz4 => Some(IpcCompression::LZ4),
+        ArrowCompression::Zstd => Some(IpcCompression::ZSTD(Default::default())),
+    };
+
+    let mut df = match value {
+        Value::Series(series) => DataFrame::new(vec![series.clone().into()])?,
+        Value::Object(_) => Value::Array(vec![value.clone()]).to_dataframe()?,
+        Value::DataFrame(_) | Value::LazyFrame(_) | Value::Array(_) => value.to_dataframe()?,
+        other => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "cannot write {} to Arrow",
+                other.type_name()
+            ))))
+        }
+    };
+    match framing {
+        IpcFraming::File => IpcWriter::new(writer)
+            .with_compression(compression)
+            .finish(&mut df)?,
+        IpcFraming::Stream => IpcStreamWriter::new(writer)
+            .with_compression(compression)
+            .finish(&mut df)?,
+    }
+    Ok(())
+}
+
+/// Hand Arrow record batches to Polars through the Arrow IPC file format
+pub(crate) fn batches_to_dataframe(
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+) -> Result<DataFrame> {
+    let mut buffer = Vec::new();
+    let mut writer = ArrowFileWriter::try_new(&mut buffer, &schema)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    drop(writer);
+    Ok(IpcReader::new(Cursor::new(buffer)).finish()?)
+}
+
+/// Hand a DataFrame to Arrow as record batches, the reverse of
+/// `batches_to_dataframe`
+pub(crate) fn dataframe_to_batches(df: &mut DataFrame) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let mut buffer = Vec::new();
+    IpcWriter::new(&mut buffer).finish(df)?;
+    let reader = ArrowFileReader::try_new(Cursor::new(buffer), None)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok((schema, batches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn people() -> DataFrame {
+        df! {
+            "name" => &[Some("Ada"), Some("Grace"), None, Some("Alan")],
+            "age" => &[36i64, 85, 41, 41],
+            "active" => &[true, false, true, true],
+        }
+        .unwrap()
+    }
+
+    fn write(compression: ArrowCompression, framing: IpcFraming) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        serialize_arrow(
+            &mut buffer,
+            &Value::DataFrame(people()),
+            &WriteOptions::default(),
+            &FormatWriteOptions::Arrow {
+                compression,
+                framing,
+            },
+        )
+        .unwrap();
+        buffer
+    }
+
+    fn read(bytes: Vec<u8>, options: &ReadOptions) -> DataFrame {
+        match deserialize_arrow(Cursor::new(bytes), options).unwrap() {
+            Value::DataFrame(df) => df,
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_arrow_round_trip_in_each_framing_and_compression() {
+        for framing in [IpcFraming::File, IpcFraming::Stream] {
+            for compression in [
+                ArrowCompression::Uncompressed,
+                ArrowCompression::Lz4,
+                ArrowCompression::Zstd,
+            ] {
+                let bytes = write(compression, framing);
+                assert_eq!(IpcFraming::detect(&bytes), Some(framing));
+                let df = read(bytes, &ReadOptions::default());
+                assert!(df.equals_missing(&people()), "{framing:?} {compression:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_arrow_columns_and_row_limits() {
+        let options = ReadOptions {
+            columns: Some(vec!["age".to_string(), "name".to_string()]),
+            skip_rows: 1,
+            max_rows: Some(2),
+            ..Default::default()
+        };
+        for framing in [IpcFraming::File, IpcFraming::Stream] {
+            let df = read(write(ArrowCompression::Lz4, framing), &options);
+            assert_eq!(df.get_column_names(), ["age", "name"]);
+            let ages: Vec<_> = df
+                .column("age")
+                .unwrap()
+                .i64()
+                .unwrap()
+                .into_iter()
+                .collect();
+            assert_eq!(ages, [Some(85), Some(41)]);
+        }
+    }
+
+    #[test]
+    fn test_read_arrow_file_memory_maps_and_detects_framing() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, framing) in [
+            ("people.arrow", IpcFraming::File),
+            ("people.arrows", IpcFraming::Stream),
+        ] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, write(ArrowCompression::Uncompressed, framing)).unwrap();
+            match read_arrow_file(&path, &ReadOptions::default()).unwrap() {
+                Value::DataFrame(df) => assert!(df.equals_missing(&people())),
+                other => panic!("expected DataFrame, got {other:?}"),
+            }
+        }
+
+        let path = dir.path().join("people.csv");
+        std::fs::write(&path, "name,age\nAda,36\n").unwrap();
+        assert!(read_arrow_file(&path, &ReadOptions::default()).is_err());
+    }
+}
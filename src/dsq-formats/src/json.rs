@@ -1,6 +1,7 @@
 use crate::error::{Error, FormatError, Result};
 
-use dsq_shared::value::Value;
+use dsq_shared::value::schema::values_to_series;
+use dsq_shared::value::{value_from_any_value, Value};
 use polars::datatypes::AnyValue;
 use polars::prelude::*;
 use serde_json::{Map as JsonMap, Value as JsonValue};
@@ -92,7 +93,7 @@ fn process_json_value_with_options(
             if options.flatten {
                 flatten_object_with_options(obj, "", depth, options)
             } else {
-                let mut map = std::collections::HashMap::new();
+                let mut map = indexmap::IndexMap::new();
                 for (k, v) in obj {
                     let value = process_json_value_with_options(v, depth + 1, options)?;
                     map.insert(k, value);
@@ -111,7 +112,7 @@ fn flatten_object_with_options(
     depth: usize,
     options: &JsonReadOptions,
 ) -> Result<Value> {
-    let mut flattened = std::collections::HashMap::new();
+    let mut flattened = indexmap::IndexMap::new();
 
     for (key, value) in obj {
         let new_key = if prefix.is_empty() {
@@ -424,7 +425,7 @@ impl<R: std::io::BufRead> JsonReader<R> {
         prefix: &str,
         depth: usize,
     ) -> Result<Value> {
-        let mut flattened = std::collections::HashMap::new();
+        let mut flattened = indexmap::IndexMap::new();
 
         for (key, value) in obj {
             let new_key = if prefix.is_empty() {
@@ -501,8 +502,9 @@ impl<R: std::io::BufRead> JsonReader<R> {
             return Ok(DataFrame::empty());
         }
 
-        // Extract all unique column names from all objects
-        let mut all_columns = std::collections::BTreeSet::new();
+        // Extract all unique column names from all objects, in the order
+        // they first appear
+        let mut all_columns = indexmap::IndexSet::new();
         for value in &values {
             if let Value::Object(obj) = value {
                 for key in obj.keys() {
@@ -516,46 +518,19 @@ impl<R: std::io::BufRead> JsonReader<R> {
             return self.create_simple_dataframe(values);
         }
 
+        // Non-object values go in the first column
         let columns: Vec<String> = all_columns.into_iter().collect();
-        let mut series_data: std::collections::HashMap<String, Vec<AnyValue>> =
-            std::collections::HashMap::new();
-
-        // Initialize series vectors
-        for col in &columns {
-            series_data.insert(col.clone(), Vec::new());
-        }
-
-        // Process each row
-        for value in values {
-            match value {
-                Value::Object(obj) => {
-                    for col in &columns {
-                        let val = obj.get(col).unwrap_or(&Value::Null);
-                        let any_val = self.value_to_any_value(val)?;
-                        series_data.get_mut(col).unwrap().push(any_val);
-                    }
-                }
-                _ => {
-                    // If we have a non-object value, put it in the first column
-                    if let Some(first_col) = columns.first() {
-                        let any_val = self.value_to_any_value(&value)?;
-                        series_data.get_mut(first_col).unwrap().push(any_val);
-
-                        // Fill other columns with nulls
-                        for col in columns.iter().skip(1) {
-                            series_data.get_mut(col).unwrap().push(AnyValue::Null);
-                        }
-                    }
-                }
-            }
-        }
-
-        // Create Series from vectors
-        let mut series_vec = Vec::new();
-        for col in columns {
-            let values = series_data.remove(&col).unwrap();
-            let series = Series::new(col.as_str().into(), values);
-            series_vec.push(series.into());
+        let mut series_vec = Vec::with_capacity(columns.len());
+        for (col_idx, col) in columns.iter().enumerate() {
+            let column_values: Vec<&Value> = values
+                .iter()
+                .map(|value| match value {
+                    Value::Object(obj) => obj.get(col).unwrap_or(&Value::Null),
+                    value if col_idx == 0 => value,
+                    _ => &Value::Null,
+                })
+                .collect();
+            series_vec.push(values_to_series(col, &column_values)?.into());
         }
 
         DataFrame::new(series_vec).map_err(Error::from)
@@ -563,34 +538,11 @@ impl<R: std::io::BufRead> JsonReader<R> {
 
     /// Create a simple DataFrame for non-object values
     fn create_simple_dataframe(&self, values: Vec<Value>) -> Result<DataFrame> {
-        let any_values: Result<Vec<AnyValue>> =
-            values.iter().map(|v| self.value_to_any_value(v)).collect();
-
-        let series = Series::new("value".into(), any_values?);
+        let values: Vec<&Value> = values.iter().collect();
+        let series = values_to_series("value", &values)?;
         DataFrame::new(vec![series.into()]).map_err(Error::from)
     }
 
-    /// Convert Value to AnyValue for Polars
-    fn value_to_any_value(&self, value: &Value) -> Result<AnyValue<'static>> {
-        match value {
-            Value::Null => Ok(AnyValue::Null),
-            Value::Bool(b) => Ok(AnyValue::Boolean(*b)),
-            Value::Int(i) => Ok(AnyValue::Int64(*i)),
-            Value::Float(f) => Ok(AnyValue::Float64(*f)),
-            Value::String(s) => Ok(AnyValue::StringOwned(s.clone().into())),
-            Value::Array(_) => Err(Error::Format(FormatError::UnsupportedFeature(
-                "Cannot convert array to AnyValue".to_string(),
-            ))),
-            Value::Object(_) => Err(Error::Format(FormatError::UnsupportedFeature(
-                "Cannot convert object to AnyValue".to_string(),
-            ))),
-            _ => Err(Error::Format(FormatError::UnsupportedFeature(format!(
-                "Cannot convert {} to AnyValue",
-                value.type_name()
-            )))),
-        }
-    }
-
     /// Peek at the first few records without consuming the reader
     pub fn peek(&mut self, records: usize) -> Result<DataFrame> {
         let mut temp_options = self.options.clone();
@@ -849,9 +801,11 @@ impl<W: Write> JsonWriter<W> {
                 }
             }
             DataType::Struct(_) => {
-                // Handle struct types
                 let any_val = series.get(index).map_err(Error::from)?;
-                Ok(JsonValue::String(format!("{:?}", any_val)))
+                match value_from_any_value(any_val) {
+                    Some(value) => Ok(value.to_json()?),
+                    None => Ok(JsonValue::Null),
+                }
             }
             _ => {
                 // For unsupported types, convert to string representation
@@ -998,7 +952,9 @@ pub(crate) fn row_to_json_value(row: &[AnyValue], column_names: &[String]) -> se
                 serde_json::Number::from_f64(*f).unwrap_or(serde_json::Number::from(0)),
             ),
             AnyValue::String(s) => serde_json::Value::String(s.to_string()),
-            _ => serde_json::Value::Null,
+            other => value_from_any_value(other.clone())
+                .and_then(|value| value.to_json().ok())
+                .unwrap_or(serde_json::Value::Null),
         };
         map.insert(key.to_string(), value);
     }
@@ -1036,6 +992,7 @@ pub fn deserialize_json<R: Read>(
 
         let cursor = Cursor::new(jsonl_str.as_bytes());
         let mut df = polars::io::json::JsonReader::new(cursor)
+            .with_json_format(polars::io::json::JsonFormat::JsonLines)
             .finish()
             .map_err(|e| {
                 if ignore_errors {
@@ -1218,8 +1175,8 @@ mod tests {
         assert_eq!(df.height(), 2);
         assert_eq!(df.width(), 3);
         let columns = df.get_column_names();
-        // Columns are collected in BTreeSet order, so they should be sorted
-        assert_eq!(columns, vec!["active", "age", "name"]);
+        // Columns keep the order of the keys in the input
+        assert_eq!(columns, vec!["name", "age", "active"]);
     }
 
     #[test]
@@ -18,13 +18,17 @@
 //! - **Parquet** (`.parquet`) - Columnar storage with compression
 //! - **JSON** (`.json`) - Standard JSON arrays and objects
 //! - **JSON Lines** (`.jsonl`, `.ndjson`) - Newline-delimited JSON
-//! - **Arrow** (`.arrow`) - Apache Arrow IPC format
+//! - **Arrow** (`.arrow`, `.feather`, `.arrows`) - Apache Arrow IPC files and
+//!   streams
 //! - **Avro** (`.avro`) - Apache Avro serialization
+//! - **Excel** (`.xlsx`) - Microsoft Excel workbooks
+//! - **ORC** (`.orc`) - Optimized Row Columnar format
+//!
+//! Files of any format can be compressed with gzip (`.gz`), zstd (`.zst`),
+//! bzip2 (`.bz2`) or xz (`.xz`), as in `events.jsonl.gz`.
 //!
 //! ## Output Formats
-//! All input formats plus:
-//! - **Excel** (`.xlsx`) - Microsoft Excel format
-//! - **ORC** (`.orc`) - Optimized Row Columnar format
+//! All input formats.
 //!
 //! # Architecture
 //!
@@ -38,6 +42,8 @@
 pub use dsq_shared::{BuildInfo, VERSION};
 
 // Core modules
+/// Compressed file reading and writing
+pub mod compression;
 /// Error types and result handling
 pub mod error;
 /// File format detection and metadata
@@ -46,10 +52,22 @@ pub mod format;
 // Format implementations
 /// ADT (ASCII Delimited Text) format reading and writing
 pub mod adt;
+/// Avro format reading and writing
+#[cfg(not(target_arch = "wasm32"))]
+pub mod avro;
 /// CSV format reading and writing
 pub mod csv;
+/// Excel format reading and writing
+#[cfg(not(target_arch = "wasm32"))]
+pub mod excel;
+/// Arrow IPC format reading and writing
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ipc;
 /// JSON format reading and writing
 pub mod json;
+/// ORC format reading and writing
+#[cfg(not(target_arch = "wasm32"))]
+pub mod orc;
 /// Parquet format reading and writing
 pub mod parquet;
 
@@ -60,8 +78,9 @@ pub mod reader;
 pub mod writer;
 /// Old writer implementation (for testing)
 // Re-export main types for convenience
+pub use compression::{CompressedWriter, Compression};
 pub use error::{Error, FormatError, Result};
-pub use format::{detect_format_from_content, DataFormat, FormatOptions};
+pub use format::{detect_format_from_content, DataFormat, FormatOptions, IpcFraming};
 #[cfg(any(
     feature = "csv",
     feature = "json",
@@ -69,9 +88,10 @@ pub use format::{detect_format_from_content, DataFormat, FormatOptions};
     feature = "avro"
 ))]
 pub use reader::{
-    from_memory, from_path, from_path_with_format, DataReader, FileReader, MemoryReader,
+    from_memory, from_path, from_path_with_format, BatchReader, DataReader, FileReader,
+    MemoryReader,
 };
-pub use reader::{FormatReadOptions, ReadOptions};
+pub use reader::{ExcelSheet, FormatReadOptions, ReadOptions};
 #[cfg(any(
     feature = "csv",
     feature = "json",
@@ -80,8 +100,8 @@ pub use reader::{FormatReadOptions, ReadOptions};
 ))]
 pub use writer::{to_memory, to_path, to_path_with_format, DataWriter, FileWriter, MemoryWriter};
 pub use writer::{
-    AvroCompression, CompressionLevel, CsvEncoding, FormatWriteOptions, OrcCompression,
-    WriteOptions,
+    ArrowCompression, AvroCompression, CompressionLevel, CsvEncoding, FormatWriteOptions,
+    OrcCompression, WriteOptions,
 };
 
 #[cfg(feature = "parquet")]
@@ -103,6 +123,17 @@ pub use reader::{deserialize, deserialize_adt, from_csv, from_json};
 #[cfg(feature = "parquet")]
 pub use reader::deserialize_parquet;
 
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(
+        feature = "csv",
+        feature = "json",
+        feature = "parquet",
+        feature = "avro"
+    )
+))]
+pub use reader::{deserialize_arrow, deserialize_avro, deserialize_excel, deserialize_orc};
+
 #[cfg(feature = "csv")]
 pub use writer::serialize_csv;
 #[cfg(feature = "json")]
@@ -118,14 +149,25 @@ pub use writer::{serialize, serialize_adt};
 #[cfg(feature = "parquet")]
 pub use writer::serialize_parquet;
 
-#[cfg(feature = "avro")]
+#[cfg(all(feature = "avro", not(target_arch = "wasm32")))]
 pub use writer::serialize_avro;
 
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(
+        feature = "csv",
+        feature = "json",
+        feature = "parquet",
+        feature = "avro"
+    )
+))]
+pub use writer::{serialize_arrow, serialize_excel, serialize_orc};
+
 // Format-specific re-exports
 #[cfg(feature = "csv")]
 pub use csv::{
-    detect_csv_format, read_csv_file, read_csv_file_with_options, write_csv_file,
-    write_csv_file_with_options, CsvReader, CsvWriteOptions, CsvWriter,
+    detect_csv_format, read_csv_file, read_csv_file_with_options, scan_csv_file_with_options,
+    write_csv_file, write_csv_file_with_options, CsvReader, CsvWriteOptions, CsvWriter,
     DsqCsvReadOptions as CsvReadOptions,
 };
 
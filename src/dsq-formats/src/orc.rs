@@ -0,0 +1,1077 @@
+//! Apache ORC (.orc) format support
+//!
+//! Files are read with orc-rust, which decodes stripes into Arrow record
+//! batches that reach Polars through the Arrow IPC format. Files are written
+//! by the encoder in this module: every column is stored with the direct
+//! encodings of ORC's first RLE version, in stripes of about the configured
+//! size, and compressed with any ORC codec but LZO.
+//!
+//! orc-rust's own writer is not used because it writes neither compressed
+//! streams nor struct, list, date or timestamp columns. The encodings here
+//! are checked against the examples of the ORC specification, and files
+//! written here against files orc-rust writes for the same data.
+//!
+//! Structs and lists are written as ORC structs and lists. ORC maps are read
+//! as lists of `key`/`value` structs, the way Polars represents maps.
+
+use crate::compression::Compression;
+use crate::error::{Error, FormatError, Result};
+use crate::ipc::batches_to_dataframe;
+use crate::reader::ReadOptions;
+use crate::writer::{FormatWriteOptions, OrcCompression, WriteOptions};
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use dsq_shared::value::Value;
+use orc_rust::projection::ProjectionMask;
+use orc_rust::reader::ChunkReader;
+use orc_rust::row_selection::RowSelector;
+use orc_rust::ArrowReaderBuilder;
+use polars::prelude::*;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Default size of a stripe, in bytes of uncompressed column data
+pub const DEFAULT_STRIPE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Size of the blocks each stream is compressed in
+const COMPRESSION_BLOCK_SIZE: usize = 256 * 1024;
+
+/// Seconds from the Unix epoch to 2015-01-01, the base of ORC timestamps
+const ORC_EPOCH_SECONDS: i64 = 1_420_070_400;
+
+/// Read the ORC file at `path`
+///
+/// orc-rust reads the file tail and then only the stripes and columns it
+/// decodes. Compressed files have to be decompressed into memory first.
+pub fn read_orc_file(path: &Path, options: &ReadOptions) -> Result<Value> {
+    let mut file = File::open(path)?;
+    let mut head = Vec::with_capacity(6);
+    (&mut file).take(6).read_to_end(&mut head)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    if Compression::detect(&head).is_some() {
+        return deserialize_orc(crate::compression::open(path)?, options);
+    }
+    read_orc(file, options)
+}
+
+/// Deserialize an ORC file from a reader
+///
+/// ORC files are read from their end, so a reader that cannot seek, such as
+/// stdin, is read into memory first; use [`read_orc_file`] for files.
+pub fn deserialize_orc<R: Read>(mut reader: R, options: &ReadOptions) -> Result<Value> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    read_orc(Bytes::from(bytes), options)
+}
+
+/// Read ORC data from `reader`
+///
+/// Only the `columns` of the read options are decoded, and stripes are
+/// decoded one at a time until `max_rows` rows after `skip_rows` have been
+/// read, so the stripes after them are never read or decompressed.
+fn read_orc<R: ChunkReader>(reader: R, options: &ReadOptions) -> Result<Value> {
+    let mut builder = ArrowReaderBuilder::try_new(reader).map_err(Error::from)?;
+
+    let metadata = builder.file_metadata();
+    let total_rows = metadata.number_of_rows() as usize;
+    if let Some(columns) = &options.columns {
+        let names: Vec<&str> = metadata
+            .root_data_type()
+            .children()
+            .iter()
+            .map(|column| column.name())
+            .collect();
+        if let Some(missing) = columns.iter().find(|c| !names.contains(&c.as_str())) {
+            return Err(Error::Format(FormatError::InvalidOption(format!(
+                "column {missing} not found; the file has {}",
+                names.join(", ")
+            ))));
+        }
+        let projection = ProjectionMask::named_roots(metadata.root_data_type(), columns);
+        builder = builder.with_projection(projection);
+    }
+
+    let skip = options.skip_rows.min(total_rows);
+    let take = options
+        .max_rows
+        .unwrap_or(usize::MAX)
+        .min(total_rows - skip);
+    if take < total_rows {
+        builder = builder.with_row_selection(
+            vec![
+                RowSelector::skip(skip),
+                RowSelector::select(take),
+                RowSelector::skip(total_rows - skip - take),
+            ]
+            .into(),
+        );
+    }
+
+    let schema = builder.schema();
+    let mut reader = builder.build();
+    let mut batches = Vec::new();
+    let mut rows = 0;
+    while rows < take {
+        let Some(batch) = reader.next() else {
+            break;
+        };
+        let batch = batch.map_err(Error::from)?;
+        rows += batch.num_rows();
+        batches.push(batch);
+    }
+
+    let mut df = batches_to_dataframe(schema, &batches)?;
+    if let Some(columns) = &options.columns {
+        df = df.select(columns.iter().map(String::as_str))?;
+    }
+    Ok(Value::DataFrame(df))
+}
+
+/// Serialize data to an ORC file
+///
+/// The compression and stripe size come from `FormatWriteOptions::Orc`;
+/// other format options write zlib-compressed stripes of the default size.
+/// Unsigned integers are widened to the next signed type, and durations and
+/// times are written as integers of their unit.
+pub fn serialize_orc<W: Write>(
+    mut writer: W,
+    value: &Value,
+    _options: &WriteOptions,
+    format_options: &FormatWriteOptions,
+) -> Result<()> {
+    let (compression, stripe_size) = match format_options {
+        FormatWriteOptions::Orc {
+            compression,
+            stripe_size,
+        } => (compression.clone(), *stripe_size),
+        _ => (OrcCompression::Zlib, DEFAULT_STRIPE_SIZE),
+    };
+    let codec = Codec::new(&compression)?;
+
+    let df = match value {
+        Value::Series(series) => DataFrame::new(vec![series.clone().into()])?,
+        Value::Object(_) => Value::Array(vec![value.clone()]).to_dataframe()?,
+        Value::DataFrame(_) | Value::LazyFrame(_) | Value::Array(_) => value.to_dataframe()?,
+        _ => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "cannot write {} to ORC",
+                value.type_name()
+            ))))
+        }
+    };
+    let columns = df
+        .get_columns()
+        .iter()
+        .map(|column| {
+            let series = column.as_materialized_series();
+            series
+                .strict_cast(&orc_dtype(series.dtype())?)
+                .map(Column::from)
+                .map_err(Error::from)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let height = df.height();
+    let rows_per_stripe = rows_per_stripe(&df, stripe_size);
+    let root = DataFrame::new(columns)?
+        .into_struct(PlSmallStr::EMPTY)
+        .into_series();
+
+    let mut types = Vec::new();
+    push_type(&mut types, root.dtype())?;
+    let mut statistics = vec![ColumnStatistics::default(); types.len()];
+
+    writer.write_all(b"ORC")?;
+    let mut offset = 3;
+    let mut stripes = Vec::new();
+    for start in (0..height).step_by(rows_per_stripe) {
+        let stripe = root.slice(start as i64, rows_per_stripe);
+        let mut streams = Vec::new();
+        encode_column(&stripe, &mut 0, &mut streams, &mut statistics)?;
+
+        let mut footer = Message::default();
+        let mut data_length = 0;
+        for stream in &streams {
+            let data = codec.compress(&stream.data)?;
+            writer.write_all(&data)?;
+            data_length += data.len() as u64;
+            footer = footer.message(
+                1,
+                Message::default()
+                    .uint(1, stream.kind as u64)
+                    .uint(2, u64::from(stream.column))
+                    .uint(3, data.len() as u64),
+            );
+        }
+        for _ in 0..types.len() {
+            // DIRECT encoding, with version 1 run lengths
+            footer = footer.message(2, Message::default().uint(1, 0));
+        }
+        let footer = codec.compress(&footer.0)?;
+        writer.write_all(&footer)?;
+
+        stripes.push(
+            Message::default()
+                .uint(1, offset)
+                .uint(2, 0)
+                .uint(3, data_length)
+                .uint(4, footer.len() as u64)
+                .uint(5, stripe.len() as u64),
+        );
+        offset += data_length + footer.len() as u64;
+    }
+
+    let mut footer = Message::default().uint(1, 3).uint(2, offset);
+    for stripe in stripes {
+        footer = footer.message(3, stripe);
+    }
+    for column_type in types {
+        footer = footer.message(4, column_type);
+    }
+    footer = footer.uint(6, height as u64);
+    for column in statistics {
+        footer = footer.message(
+            7,
+            Message::default()
+                .uint(1, column.values)
+                .uint(10, u64::from(column.has_null)),
+        );
+    }
+    let footer = codec.compress(&footer.uint(8, 0).0)?;
+    writer.write_all(&footer)?;
+
+    let mut postscript = Message::default()
+        .uint(1, footer.len() as u64)
+        .uint(2, codec.kind());
+    if !matches!(codec, Codec::None) {
+        postscript = postscript.uint(3, COMPRESSION_BLOCK_SIZE as u64);
+    }
+    let postscript = postscript
+        .packed(4, &[0, 12])
+        .uint(5, 0)
+        .uint(6, 6)
+        .bytes(8000, b"ORC");
+    writer.write_all(&postscript.0)?;
+    writer.write_all(&[postscript.0.len() as u8])?;
+    Ok(())
+}
+
+/// Rows in each stripe, so a stripe holds about `stripe_size` bytes
+fn rows_per_stripe(df: &DataFrame, stripe_size: usize) -> usize {
+    let height = df.height().max(1);
+    let size = df.estimated_size().max(1);
+    let rows = height as u128 * stripe_size as u128 / size as u128;
+    (rows as usize).clamp(1, height)
+}
+
+/// The type a column of type `dtype` is written as
+fn orc_dtype(dtype: &DataType) -> Result<DataType> {
+    Ok(match dtype {
+        DataType::UInt8 => DataType::Int16,
+        DataType::UInt16 => DataType::Int32,
+        DataType::UInt32 | DataType::UInt64 | DataType::Time | DataType::Duration(_) => {
+            DataType::Int64
+        }
+        DataType::Null => DataType::String,
+        DataType::List(inner) => DataType::List(Box::new(orc_dtype(inner)?)),
+        DataType::Struct(fields) => DataType::Struct(
+            fields
+                .iter()
+                .map(|field| Ok(Field::new(field.name().clone(), orc_dtype(field.dtype())?)))
+                .collect::<Result<_>>()?,
+        ),
+        DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::String
+        | DataType::Binary
+        | DataType::Date
+        | DataType::Datetime(_, _)
+        | DataType::Decimal(_, _) => dtype.clone(),
+        other => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "cannot write {other} columns to ORC"
+            ))))
+        }
+    })
+}
+
+/// ORC type kinds
+mod kind {
+    pub const BOOLEAN: u64 = 0;
+    pub const BYTE: u64 = 1;
+    pub const SHORT: u64 = 2;
+    pub const INT: u64 = 3;
+    pub const LONG: u64 = 4;
+    pub const FLOAT: u64 = 5;
+    pub const DOUBLE: u64 = 6;
+    pub const STRING: u64 = 7;
+    pub const BINARY: u64 = 8;
+    pub const TIMESTAMP: u64 = 9;
+    pub const LIST: u64 = 10;
+    pub const STRUCT: u64 = 12;
+    pub const DECIMAL: u64 = 14;
+    pub const DATE: u64 = 15;
+    pub const TIMESTAMP_INSTANT: u64 = 18;
+}
+
+/// Add the ORC type of `dtype`, then those of its children, to `types`;
+/// returns the column id of the type
+fn push_type(types: &mut Vec<Message>, dtype: &DataType) -> Result<u64> {
+    let id = types.len();
+    types.push(Message::default());
+    let message = match dtype {
+        DataType::Struct(fields) => {
+            let subtypes = fields
+                .iter()
+                .map(|field| push_type(types, field.dtype()))
+                .collect::<Result<Vec<_>>>()?;
+            let mut message = Message::default()
+                .uint(1, kind::STRUCT)
+                .packed(2, &subtypes);
+            for field in fields {
+                message = message.bytes(3, field.name().as_bytes());
+            }
+            message
+        }
+        DataType::List(inner) => {
+            let subtype = push_type(types, inner)?;
+            Message::default().uint(1, kind::LIST).packed(2, &[subtype])
+        }
+        DataType::Decimal(precision, scale) => Message::default()
+            .uint(1, kind::DECIMAL)
+            .uint(5, *precision as u64)
+            .uint(6, *scale as u64),
+        DataType::Boolean => Message::default().uint(1, kind::BOOLEAN),
+        DataType::Int8 => Message::default().uint(1, kind::BYTE),
+        DataType::Int16 => Message::default().uint(1, kind::SHORT),
+        DataType::Int32 => Message::default().uint(1, kind::INT),
+        DataType::Int64 => Message::default().uint(1, kind::LONG),
+        DataType::Float32 => Message::default().uint(1, kind::FLOAT),
+        DataType::Float64 => Message::default().uint(1, kind::DOUBLE),
+        DataType::String => Message::default().uint(1, kind::STRING),
+        DataType::Binary => Message::default().uint(1, kind::BINARY),
+        DataType::Date => Message::default().uint(1, kind::DATE),
+        DataType::Datetime(_, None) => Message::default().uint(1, kind::TIMESTAMP),
+        DataType::Datetime(_, Some(_)) => Message::default().uint(1, kind::TIMESTAMP_INSTANT),
+        other => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "cannot write {other} columns to ORC"
+            ))))
+        }
+    };
+    types[id] = message;
+    Ok(id as u64)
+}
+
+/// ORC stream kinds
+#[derive(Debug, Clone, Copy)]
+enum StreamKind {
+    Present = 0,
+    Data = 1,
+    Length = 2,
+    Secondary = 5,
+}
+
+/// An uncompressed stream of a stripe
+struct Stream {
+    kind: StreamKind,
+    column: u32,
+    data: Vec<u8>,
+}
+
+/// File statistics of a column
+#[derive(Debug, Clone, Default)]
+struct ColumnStatistics {
+    values: u64,
+    has_null: bool,
+}
+
+/// Encode the streams of `series` and of its children, which take the
+/// column ids from `next_column` on
+fn encode_column(
+    series: &Series,
+    next_column: &mut u32,
+    streams: &mut Vec<Stream>,
+    statistics: &mut [ColumnStatistics],
+) -> Result<()> {
+    let column = *next_column;
+    *next_column += 1;
+    let mut push = |kind, data| {
+        streams.push(Stream { kind, column, data });
+    };
+
+    let nulls = series.null_count();
+    let statistic = &mut statistics[column as usize];
+    statistic.values += (series.len() - nulls) as u64;
+    statistic.has_null |= nulls > 0;
+    let values = if nulls > 0 {
+        push(
+            StreamKind::Present,
+            encode_booleans(series.is_not_null().into_no_null_iter()),
+        );
+        series.drop_nulls()
+    } else {
+        series.clone()
+    };
+
+    match values.dtype() {
+        DataType::Boolean => push(
+            StreamKind::Data,
+            encode_booleans(values.bool()?.into_no_null_iter()),
+        ),
+        DataType::Int8 => push(
+            StreamKind::Data,
+            encode_bytes(values.i8()?.into_no_null_iter().map(|v| v as u8).collect()),
+        ),
+        DataType::Int16 | DataType::Int32 | DataType::Int64 | DataType::Date => {
+            let ints = values.to_physical_repr().cast(&DataType::Int64)?;
+            let ints: Vec<i64> = ints.i64()?.into_no_null_iter().collect();
+            push(StreamKind::Data, encode_ints(&ints, true));
+        }
+        DataType::Float32 => push(
+            StreamKind::Data,
+            values
+                .f32()?
+                .into_no_null_iter()
+                .flat_map(f32::to_le_bytes)
+                .collect(),
+        ),
+        DataType::Float64 => push(
+            StreamKind::Data,
+            values
+                .f64()?
+                .into_no_null_iter()
+                .flat_map(f64::to_le_bytes)
+                .collect(),
+        ),
+        DataType::String | DataType::Binary => {
+            let values = values.cast(&DataType::Binary)?;
+            let mut data = Vec::new();
+            let mut lengths = Vec::with_capacity(values.len());
+            for value in values.binary()?.into_no_null_iter() {
+                data.extend_from_slice(value);
+                lengths.push(value.len() as i64);
+            }
+            push(StreamKind::Data, data);
+            push(StreamKind::Length, encode_ints(&lengths, false));
+        }
+        DataType::Datetime(unit, _) => {
+            let per_second = match unit {
+                TimeUnit::Nanoseconds => 1_000_000_000,
+                TimeUnit::Microseconds => 1_000_000,
+                TimeUnit::Milliseconds => 1_000,
+            };
+            let mut seconds = Vec::with_capacity(values.len());
+            let mut nanos = Vec::with_capacity(values.len());
+            for value in values.to_physical_repr().i64()?.into_no_null_iter() {
+                let second = value.div_euclid(per_second);
+                let nano = value.rem_euclid(per_second) * (1_000_000_000 / per_second);
+                // Readers take a second off timestamps before the epoch with
+                // more than 999,999 nanoseconds (ORC-763)
+                let second = if second < 0 && nano > 999_999 {
+                    second + 1
+                } else {
+                    second
+                };
+                seconds.push(second - ORC_EPOCH_SECONDS);
+                nanos.push(encode_nanos(nano));
+            }
+            push(StreamKind::Data, encode_ints(&seconds, true));
+            push(StreamKind::Secondary, encode_ints(&nanos, false));
+        }
+        DataType::Decimal(_, scale) => {
+            let mut data = Vec::new();
+            for value in values.decimal()?.physical().into_no_null_iter() {
+                write_varint(&mut data, ((value << 1) ^ (value >> 127)) as u128);
+            }
+            push(StreamKind::Data, data);
+            let scales = vec![*scale as i64; values.len()];
+            push(StreamKind::Secondary, encode_ints(&scales, true));
+        }
+        DataType::List(inner) => {
+            let mut lengths = Vec::with_capacity(values.len());
+            let mut elements = Series::new_empty(PlSmallStr::EMPTY, inner);
+            for list in values.list()?.into_iter().flatten() {
+                lengths.push(list.len() as i64);
+                elements.append(&list)?;
+            }
+            push(StreamKind::Length, encode_ints(&lengths, false));
+            encode_column(&elements, next_column, streams, statistics)?;
+        }
+        DataType::Struct(_) => {
+            for field in values.struct_()?.fields_as_series() {
+                encode_column(&field, next_column, streams, statistics)?;
+            }
+        }
+        other => {
+            return Err(Error::Format(FormatError::UnsupportedFeature(format!(
+                "cannot write {other} columns to ORC"
+            ))))
+        }
+    }
+    Ok(())
+}
+
+/// Nanoseconds of a timestamp, with their trailing zeros replaced by a
+/// count in the low three bits
+fn encode_nanos(nanos: i64) -> i64 {
+    if nanos == 0 || nanos % 100 != 0 {
+        return nanos << 3;
+    }
+    let mut nanos = nanos / 100;
+    let mut zeros = 1;
+    while nanos % 10 == 0 && zeros < 7 {
+        nanos /= 10;
+        zeros += 1;
+    }
+    (nanos << 3) | zeros
+}
+
+/// Byte run-length encoding: runs of 3 to 130 equal bytes, and groups of up
+/// to 128 literal bytes
+fn encode_bytes(values: Vec<u8>) -> Vec<u8> {
+    fn literals(out: &mut Vec<u8>, values: &[u8]) {
+        for group in values.chunks(128) {
+            out.push((group.len() as i16).wrapping_neg() as u8);
+            out.extend_from_slice(group);
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+    while i < values.len() {
+        let run = values[i..]
+            .iter()
+            .take(130)
+            .take_while(|&&value| value == values[i])
+            .count();
+        if run >= 3 {
+            literals(&mut out, &values[literal_start..i]);
+            out.push((run - 3) as u8);
+            out.push(values[i]);
+            i += run;
+            literal_start = i;
+        } else {
+            i += 1;
+        }
+    }
+    literals(&mut out, &values[literal_start..]);
+    out
+}
+
+/// Boolean encoding: bits packed from the most significant, then byte
+/// run-length encoded
+fn encode_booleans(values: impl Iterator<Item = bool>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (i, value) in values.enumerate() {
+        if i % 8 == 0 {
+            bytes.push(0);
+        }
+        if value {
+            let last = bytes.len() - 1;
+            bytes[last] |= 0x80 >> (i % 8);
+        }
+    }
+    encode_bytes(bytes)
+}
+
+/// Integer run-length encoding, version 1: runs of 3 to 130 integers with a
+/// constant step of -128 to 127, and groups of up to 128 literal varints,
+/// zigzag encoded when `signed`
+fn encode_ints(values: &[i64], signed: bool) -> Vec<u8> {
+    let varint = |out: &mut Vec<u8>, value: i64| {
+        let value = if signed {
+            ((value << 1) ^ (value >> 63)) as u64
+        } else {
+            value as u64
+        };
+        write_varint(out, u128::from(value));
+    };
+    let literals = |out: &mut Vec<u8>, values: &[i64]| {
+        for group in values.chunks(128) {
+            out.push((group.len() as i16).wrapping_neg() as u8);
+            for &value in group {
+                varint(out, value);
+            }
+        }
+    };
+
+    let mut out = Vec::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+    while i < values.len() {
+        let (run, delta) = run_of(&values[i..]);
+        if run >= 3 {
+            literals(&mut out, &values[literal_start..i]);
+            out.push((run - 3) as u8);
+            out.push(delta as i8 as u8);
+            varint(&mut out, values[i]);
+            i += run;
+            literal_start = i;
+        } else {
+            i += 1;
+        }
+    }
+    literals(&mut out, &values[literal_start..]);
+    out
+}
+
+/// Length and step of the run of integers that `values` starts with
+fn run_of(values: &[i64]) -> (usize, i64) {
+    let delta = match values {
+        [first, second, ..] => second.checked_sub(*first),
+        _ => None,
+    };
+    let Some(delta) = delta.filter(|delta| (-128..=127).contains(delta)) else {
+        return (1, 0);
+    };
+    let mut length = 2;
+    while length < values.len()
+        && length < 130
+        && values[length].checked_sub(values[length - 1]) == Some(delta)
+    {
+        length += 1;
+    }
+    (length, delta)
+}
+
+/// Append `value` as a base 128 varint
+fn write_varint(out: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Protobuf encoding of the messages in the file tail and stripe footers
+#[derive(Debug, Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn key(&mut self, field: u32, wire_type: u32) {
+        write_varint(&mut self.0, u128::from((field << 3) | wire_type));
+    }
+
+    fn uint(mut self, field: u32, value: u64) -> Self {
+        self.key(field, 0);
+        write_varint(&mut self.0, u128::from(value));
+        self
+    }
+
+    fn bytes(mut self, field: u32, value: &[u8]) -> Self {
+        self.key(field, 2);
+        write_varint(&mut self.0, value.len() as u128);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    fn packed(self, field: u32, values: &[u64]) -> Self {
+        let mut data = Vec::new();
+        for &value in values {
+            write_varint(&mut data, u128::from(value));
+        }
+        self.bytes(field, &data)
+    }
+
+    fn message(self, field: u32, message: Message) -> Self {
+        self.bytes(field, &message.0)
+    }
+}
+
+/// Compression codec of the streams and footers
+enum Codec {
+    None,
+    Zlib,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
+impl Codec {
+    fn new(compression: &OrcCompression) -> Result<Self> {
+        Ok(match compression {
+            OrcCompression::Uncompressed => Self::None,
+            OrcCompression::Zlib => Self::Zlib,
+            OrcCompression::Snappy => Self::Snappy,
+            OrcCompression::Lz4 => Self::Lz4,
+            OrcCompression::Zstd => Self::Zstd,
+            OrcCompression::Lzo => {
+                return Err(Error::Format(FormatError::UnsupportedFeature(
+                    "LZO compression is not supported for writing ORC".to_string(),
+                )))
+            }
+        })
+    }
+
+    /// The ORC `CompressionKind` of the codec
+    fn kind(&self) -> u64 {
+        match self {
+            Self::None => 0,
+            Self::Zlib => 1,
+            Self::Snappy => 2,
+            Self::Lz4 => 4,
+            Self::Zstd => 5,
+        }
+    }
+
+    /// Compress `data` in blocks, each behind a three byte header giving its
+    /// length and whether it was stored as is because it did not shrink
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if let Self::None = self {
+            return Ok(data.to_vec());
+        }
+        let mut out = Vec::with_capacity(data.len() / 2);
+        for block in data.chunks(COMPRESSION_BLOCK_SIZE) {
+            let compressed = self.compress_block(block)?;
+            let (body, original) = if compressed.len() < block.len() {
+                (compressed.as_slice(), 0)
+            } else {
+                (block, 1)
+            };
+            let header = (body.len() << 1) | original;
+            out.extend_from_slice(&header.to_le_bytes()[..3]);
+            out.extend_from_slice(body);
+        }
+        Ok(out)
+    }
+
+    fn compress_block(&self, block: &[u8]) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::None => block.to_vec(),
+            Self::Zlib => {
+                let mut encoder = libflate::deflate::Encoder::new(Vec::new());
+                encoder.write_all(block)?;
+                encoder.finish().into_result()?
+            }
+            Self::Snappy => snap::raw::Encoder::new()
+                .compress_vec(block)
+                .map_err(|e| Error::Format(FormatError::SerializationError(e.to_string())))?,
+            Self::Lz4 => lz4_flex::block::compress(block),
+            Self::Zstd => zstd::bulk::compress(block, zstd::DEFAULT_COMPRESSION_LEVEL)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::io::Cursor;
+
+    fn write(df: &DataFrame, compression: OrcCompression, stripe_size: usize) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        serialize_orc(
+            &mut buffer,
+            &Value::DataFrame(df.clone()),
+            &WriteOptions::default(),
+            &FormatWriteOptions::Orc {
+                compression,
+                stripe_size,
+            },
+        )
+        .unwrap();
+        buffer
+    }
+
+    fn read(bytes: Vec<u8>, options: &ReadOptions) -> DataFrame {
+        match deserialize_orc(Cursor::new(bytes), options).unwrap() {
+            Value::DataFrame(df) => df,
+            other => panic!("expected DataFrame, got {other:?}"),
+        }
+    }
+
+    fn people() -> DataFrame {
+        df! {
+            "name" => &[Some("Ada"), Some("Grace"), None, Some("Alan")],
+            "age" => &[Some(36i64), None, Some(85), Some(41)],
+            "score" => &[1.5f64, -2.25, 0.0, 1e10],
+            "active" => &[true, false, true, true],
+            "small" => &[1i32, 1, 1, -7],
+        }
+        .unwrap()
+    }
+
+    #[test]
+    fn test_orc_round_trip_with_each_codec() {
+        let df = people();
+        for compression in [
+            OrcCompression::Uncompressed,
+            OrcCompression::Zlib,
+            OrcCompression::Snappy,
+            OrcCompression::Lz4,
+            OrcCompression::Zstd,
+        ] {
+            let label = format!("{compression:?}");
+            let bytes = write(&df, compression, DEFAULT_STRIPE_SIZE);
+            assert_eq!(
+                crate::detect_format_from_content(&bytes),
+                Some(crate::DataFormat::Orc),
+                "{label}"
+            );
+            assert!(
+                read(bytes, &ReadOptions::default()).equals_missing(&df),
+                "{label}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_orc_lzo_is_not_written() {
+        let mut buffer = Vec::new();
+        let result = serialize_orc(
+            &mut buffer,
+            &Value::DataFrame(people()),
+            &WriteOptions::default(),
+            &FormatWriteOptions::Orc {
+                compression: OrcCompression::Lzo,
+                stripe_size: DEFAULT_STRIPE_SIZE,
+            },
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_orc_nested_and_temporal_types() {
+        let born = Series::new(
+            "born".into(),
+            [
+                NaiveDate::from_ymd_opt(1815, 12, 10),
+                None,
+                NaiveDate::from_ymd_opt(1912, 6, 23),
+            ],
+        );
+        let seen = Series::new(
+            "seen".into(),
+            [
+                NaiveDate::from_ymd_opt(1969, 12, 30)
+                    .and_then(|d| d.and_hms_milli_opt(23, 59, 59, 500)),
+                NaiveDate::from_ymd_opt(2024, 2, 29).and_then(|d| d.and_hms_opt(12, 0, 0)),
+                None,
+            ],
+        );
+        let tags = Series::new(
+            "tags".into(),
+            [
+                Some(Series::new("".into(), ["math", "engines"])),
+                None,
+                Some(Series::new("".into(), Vec::<&str>::new())),
+            ],
+        );
+        let address = StructChunked::from_series(
+            "address".into(),
+            3,
+            [
+                Series::new("city".into(), [Some("London"), Some("Arlington"), None]),
+                Series::new("zip".into(), [Some(1i32), None, Some(3)]),
+            ]
+            .iter(),
+        )
+        .unwrap()
+        .into_series();
+        let df =
+            DataFrame::new(vec![born.into(), seen.into(), tags.into(), address.into()]).unwrap();
+
+        let read = read(
+            write(&df, OrcCompression::Zstd, DEFAULT_STRIPE_SIZE),
+            &ReadOptions::default(),
+        );
+        for name in ["born", "seen", "tags", "address"] {
+            // Timestamps are read back in nanoseconds
+            let dtype = df.column(name).unwrap().dtype();
+            let column = read.column(name).unwrap().cast(dtype).unwrap();
+            assert!(
+                column
+                    .as_materialized_series()
+                    .equals_missing(df.column(name).unwrap().as_materialized_series()),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_orc_projection_and_row_limits_across_stripes() {
+        let n = 10_000i64;
+        let df = df! {
+            "id" => (0..n).collect::<Vec<_>>(),
+            "label" => (0..n).map(|i| format!("row {i}")).collect::<Vec<_>>(),
+        }
+        .unwrap();
+        // Stripes of a few hundred rows each
+        let bytes = write(&df, OrcCompression::Snappy, 4096);
+
+        let options = ReadOptions {
+            columns: Some(vec!["label".to_string()]),
+            skip_rows: 2_500,
+            max_rows: Some(1_000),
+            ..Default::default()
+        };
+        let projected = read(bytes.clone(), &options);
+        assert_eq!(projected.get_column_names(), ["label"]);
+        assert_eq!(projected.height(), 1_000);
+        let labels = projected.column("label").unwrap().str().unwrap().clone();
+        assert_eq!(labels.get(0), Some("row 2500"));
+        assert_eq!(labels.get(999), Some("row 3499"));
+
+        assert_eq!(read(bytes, &ReadOptions::default()).height(), 10_000);
+
+        let options = ReadOptions {
+            columns: Some(vec!["missing".to_string()]),
+            ..Default::default()
+        };
+        assert!(deserialize_orc(
+            Cursor::new(write(&df, OrcCompression::Zlib, 4096)),
+            &options
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_orc_file_is_read_in_place_or_decompressed() {
+        let df = df! {
+            "id" => (0..1_000i64).collect::<Vec<_>>(),
+        }
+        .unwrap();
+        let bytes = write(&df, OrcCompression::Zlib, 4096);
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("ids.orc");
+        std::fs::write(&plain, &bytes).unwrap();
+        let gzipped = dir.path().join("ids.orc.gz");
+        std::fs::write(
+            &gzipped,
+            crate::compression::compress(&bytes, Some(Compression::Gzip)).unwrap(),
+        )
+        .unwrap();
+
+        let options = ReadOptions {
+            skip_rows: 900,
+            max_rows: Some(50),
+            ..Default::default()
+        };
+        for path in [plain, gzipped] {
+            let Value::DataFrame(read) = read_orc_file(&path, &options).unwrap() else {
+                panic!("expected DataFrame");
+            };
+            let ids = read.column("id").unwrap().i64().unwrap().clone();
+            assert_eq!(ids.len(), 50);
+            assert_eq!(ids.get(0), Some(900));
+        }
+    }
+
+    #[test]
+    fn test_orc_file_matches_orc_rust_writer() {
+        use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray};
+        use std::sync::Arc;
+
+        let columns: Vec<(&str, ArrayRef)> = vec![
+            (
+                "name",
+                Arc::new(StringArray::from(vec![Some("Ada"), Some("Grace"), None])),
+            ),
+            (
+                "age",
+                Arc::new(Int64Array::from(vec![Some(36), None, Some(85)])),
+            ),
+            (
+                "score",
+                Arc::new(Float64Array::from(vec![1.5, -2.25, 1e10])),
+            ),
+            (
+                "active",
+                Arc::new(BooleanArray::from(vec![true, false, true])),
+            ),
+        ];
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let mut reference = Vec::new();
+        let mut writer = orc_rust::ArrowWriterBuilder::new(&mut reference, batch.schema())
+            .try_build()
+            .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let df = df! {
+            "name" => &[Some("Ada"), Some("Grace"), None],
+            "age" => &[Some(36i64), None, Some(85)],
+            "score" => &[1.5f64, -2.25, 1e10],
+            "active" => &[true, false, true],
+        }
+        .unwrap();
+        let ours = write(&df, OrcCompression::Uncompressed, DEFAULT_STRIPE_SIZE);
+
+        let metadata = |bytes: &[u8]| {
+            let builder = ArrowReaderBuilder::try_new(Bytes::copy_from_slice(bytes)).unwrap();
+            let metadata = builder.file_metadata();
+            (
+                metadata.number_of_rows(),
+                format!("{:?}", metadata.root_data_type()),
+            )
+        };
+        assert_eq!(metadata(&ours), metadata(&reference));
+        assert!(read(ours, &ReadOptions::default())
+            .equals_missing(&read(reference, &ReadOptions::default())));
+    }
+
+    #[test]
+    fn test_encodings_match_the_orc_specification() {
+        // Base 128 varints, zigzag encoded when signed
+        for (value, bytes) in [
+            (0, vec![0x00]),
+            (1, vec![0x01]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (129, vec![0x81, 0x01]),
+            (16_383, vec![0xff, 0x7f]),
+            (16_384, vec![0x80, 0x80, 0x01]),
+        ] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+            assert_eq!(out, bytes, "{value}");
+        }
+        for (value, zigzag) in [(0, 0x00), (-1, 0x01), (1, 0x02), (-2, 0x03), (2, 0x04)] {
+            assert_eq!(encode_ints(&[value], true), vec![0xff, zigzag], "{value}");
+        }
+
+        // Integer run-length encoding, version 1
+        assert_eq!(encode_ints(&[7; 100], false), vec![0x61, 0x00, 0x07]);
+        assert_eq!(
+            encode_ints(&[2, 3, 6, 7, 11], false),
+            vec![0xfb, 0x02, 0x03, 0x06, 0x07, 0x0b]
+        );
+
+        // Byte run-length encoding, and booleans on top of it
+        assert_eq!(encode_bytes(vec![0; 100]), vec![0x61, 0x00]);
+        assert_eq!(encode_bytes(vec![0x44, 0x45]), vec![0xfe, 0x44, 0x45]);
+        let booleans = std::iter::once(true).chain(std::iter::repeat(false).take(7));
+        assert_eq!(encode_booleans(booleans), vec![0xff, 0x80]);
+
+        // A block that does not shrink is stored as is behind its header
+        assert_eq!(
+            Codec::Zlib.compress(&[1, 2, 3, 4, 5]).unwrap(),
+            vec![0x0b, 0x00, 0x00, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_encode_ints_runs_and_literals() {
+        // A run of five with a step of one, then two literals
+        assert_eq!(
+            encode_ints(&[1, 2, 3, 4, 5, 9, -1], true),
+            vec![0x02, 0x01, 0x02, 0xfe, 0x12, 0x01]
+        );
+        assert_eq!(encode_bytes(vec![7; 4]), vec![0x01, 0x07]);
+        assert_eq!(
+            encode_booleans([true, false, true].into_iter()),
+            vec![0xff, 0xa0]
+        );
+        assert_eq!(encode_nanos(0), 0);
+        assert_eq!(encode_nanos(1_000), (1 << 3) | 2);
+        assert_eq!(encode_nanos(500_000_000), (5 << 3) | 7);
+    }
+}
@@ -0,0 +1,343 @@
+//! Reading record-oriented files in bounded batches
+//!
+//! JSON Lines, CSV, TSV and ADT files are sequences of independent
+//! records, so they can be read a batch of records at a time instead of
+//! all at once. A batch of CSV, TSV or ADT records is parsed into a
+//! DataFrame together with the header record of the file; a batch of JSON
+//! Lines objects is a DataFrame of their keys, and any other batch of JSON
+//! Lines records is an array of values.
+
+use std::io::{BufRead, Cursor};
+use std::path::Path;
+
+use dsq_shared::constants::RECORD_SEPARATOR;
+use dsq_shared::value::Value;
+
+use crate::error::{Error, Result};
+use crate::format::DataFormat;
+use crate::reader::json_utils::json_to_dataframe;
+use crate::reader::options::{FormatReadOptions, ReadOptions};
+
+/// Iterator over the batches of records of a file
+///
+/// `ReadOptions::skip_rows` and `ReadOptions::max_rows` apply to the
+/// records of the whole file, not to each batch.
+pub struct BatchReader<R> {
+    reader: R,
+    format: DataFormat,
+    batch_size: usize,
+    options: ReadOptions,
+    format_options: FormatReadOptions,
+    /// The header record of a CSV, TSV or ADT file, with its terminator
+    header: Option<Vec<u8>>,
+    /// Records read so far, including skipped ones
+    records: usize,
+    done: bool,
+}
+
+impl BatchReader<Box<dyn BufRead + Send>> {
+    /// Read the file at `path` in batches of `batch_size` records, in the
+    /// format given by its extension; compressed files are decompressed as
+    /// they are read
+    pub fn from_path<P: AsRef<Path>>(path: P, batch_size: usize) -> Result<Self> {
+        let path = path.as_ref();
+        let format = DataFormat::from_path(path)?;
+        Self::new(crate::compression::open(path)?, format, batch_size)
+    }
+}
+
+impl<R: BufRead> BatchReader<R> {
+    /// Read `reader` in batches of `batch_size` records of `format`
+    pub fn new(reader: R, format: DataFormat, batch_size: usize) -> Result<Self> {
+        if !format.supports_streaming() {
+            return Err(Error::operation(format!(
+                "{} cannot be read in batches; streaming supports JSON Lines, CSV, TSV and ADT",
+                format.display_name()
+            )));
+        }
+        let format_options = match format {
+            DataFormat::Tsv => FormatReadOptions::Csv {
+                separator: b'\t',
+                has_header: true,
+                quote_char: Some(b'"'),
+                comment_char: None,
+                null_values: None,
+                encoding: crate::writer::CsvEncoding::Utf8,
+            },
+            _ => FormatReadOptions::default(),
+        };
+        Ok(Self {
+            reader,
+            format,
+            batch_size: batch_size.max(1),
+            options: ReadOptions::default(),
+            format_options,
+            header: None,
+            records: 0,
+            done: false,
+        })
+    }
+
+    /// Set the read options
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the format-specific read options
+    pub fn with_format_options(mut self, format_options: FormatReadOptions) -> Self {
+        self.format_options = format_options;
+        self
+    }
+
+    /// The format of the records
+    pub fn format(&self) -> DataFormat {
+        self.format
+    }
+
+    /// Read the next record with its terminator, skipping blank lines;
+    /// `None` at the end of the input
+    fn next_record(&mut self) -> Result<Option<Vec<u8>>> {
+        let terminator = match self.format {
+            DataFormat::Adt => RECORD_SEPARATOR,
+            _ => b'\n',
+        };
+        let quote = match (&self.format, &self.format_options) {
+            (DataFormat::Csv | DataFormat::Tsv, FormatReadOptions::Csv { quote_char, .. }) => {
+                *quote_char
+            }
+            _ => None,
+        };
+        let mut record = Vec::new();
+        loop {
+            if self.reader.read_until(terminator, &mut record)? == 0 {
+                return Ok((!is_blank(&record)).then_some(record));
+            }
+            // A CSV record continues past a newline inside a quoted field,
+            // which leaves an odd number of quotes in the record so far
+            if quote.is_some_and(|q| record.iter().filter(|&&b| b == q).count() % 2 == 1) {
+                continue;
+            }
+            if is_blank(&record) {
+                record.clear();
+                continue;
+            }
+            return Ok(Some(record));
+        }
+    }
+
+    /// Read the next batch of records as raw bytes
+    fn next_records(&mut self) -> Result<Vec<Vec<u8>>> {
+        let limit = self
+            .options
+            .max_rows
+            .map_or(usize::MAX, |max| max + self.options.skip_rows);
+        let mut batch = Vec::with_capacity(self.batch_size.min(1024));
+        while batch.len() < self.batch_size && self.records < limit {
+            let Some(record) = self.next_record()? else {
+                self.done = true;
+                break;
+            };
+            self.records += 1;
+            if self.records > self.options.skip_rows {
+                batch.push(record);
+            }
+        }
+        if self.records >= limit {
+            self.done = true;
+        }
+        Ok(batch)
+    }
+
+    /// Parse a batch of raw records
+    fn parse(&self, records: Vec<Vec<u8>>) -> Result<Value> {
+        let options = ReadOptions {
+            max_rows: None,
+            skip_rows: 0,
+            ..self.options.clone()
+        };
+        match self.format {
+            DataFormat::JsonLines => {
+                let first_record = self.records - records.len() + 1;
+                let values = records
+                    .iter()
+                    .enumerate()
+                    .map(|(i, record)| {
+                        serde_json::from_slice(record).map_err(|e| {
+                            Error::operation(format!(
+                                "Invalid JSON Lines record {}: {e}",
+                                first_record + i
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<serde_json::Value>>>()?;
+                if values.iter().all(serde_json::Value::is_object) {
+                    let values = serde_json::Value::Array(values);
+                    json_to_dataframe(&values, &options).map(Value::DataFrame)
+                } else {
+                    Ok(Value::Array(
+                        values.into_iter().map(Value::from_json).collect(),
+                    ))
+                }
+            }
+            DataFormat::Adt => {
+                let mut bytes = self.header.clone().unwrap_or_default();
+                records.iter().for_each(|record| bytes.extend(record));
+                crate::adt::deserialize_adt(Cursor::new(bytes), &options, &self.format_options)
+            }
+            _ => {
+                let mut bytes = self.header.clone().unwrap_or_default();
+                if !bytes.is_empty() && !bytes.ends_with(b"\n") {
+                    bytes.push(b'\n');
+                }
+                records.iter().for_each(|record| bytes.extend(record));
+                crate::csv::deserialize_csv(Cursor::new(bytes), &options, &self.format_options)
+            }
+        }
+    }
+
+    /// Read and parse the next batch
+    fn next_batch(&mut self) -> Result<Option<Value>> {
+        let has_header = match (&self.format, &self.format_options) {
+            (DataFormat::JsonLines, _) => false,
+            (_, FormatReadOptions::Csv { has_header, .. }) => *has_header,
+            _ => true,
+        };
+        if has_header && self.header.is_none() {
+            match self.next_record()? {
+                Some(header) => self.header = Some(header),
+                None => {
+                    self.done = true;
+                    return Ok(None);
+                }
+            }
+        }
+        let records = self.next_records()?;
+        if records.is_empty() {
+            return Ok(None);
+        }
+        self.parse(records).map(Some)
+    }
+}
+
+/// Whether a record holds nothing but whitespace
+fn is_blank(record: &[u8]) -> bool {
+    record
+        .iter()
+        .all(|&b| b.is_ascii_whitespace() || b == RECORD_SEPARATOR)
+}
+
+impl<R: BufRead> Iterator for BatchReader<R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_batch() {
+            Ok(batch) => batch.map(Ok),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batches(data: &str, format: DataFormat, batch_size: usize) -> Vec<Value> {
+        BatchReader::new(Cursor::new(data.as_bytes().to_vec()), format, batch_size)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    fn heights(batches: &[Value]) -> Vec<usize> {
+        batches
+            .iter()
+            .map(|batch| match batch {
+                Value::DataFrame(df) => df.height(),
+                Value::Array(items) => items.len(),
+                other => panic!("unexpected batch {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_batches_share_the_header() {
+        let data = "name,age\nAda,36\nGrace,45\nAlan,41\n";
+        let batches = batches(data, DataFormat::Csv, 2);
+        assert_eq!(heights(&batches), vec![2, 1]);
+        let Value::DataFrame(df) = &batches[1] else {
+            panic!("expected a DataFrame");
+        };
+        assert_eq!(df.get_column_names(), ["name", "age"]);
+        assert_eq!(df.column("age").unwrap().i64().unwrap().get(0), Some(41));
+    }
+
+    #[test]
+    fn test_csv_quoted_newlines_stay_in_one_record() {
+        let data = "id,note\n1,\"two\nlines\"\n2,plain\n";
+        let batches = batches(data, DataFormat::Csv, 1);
+        assert_eq!(heights(&batches), vec![1, 1]);
+        let Value::DataFrame(df) = &batches[0] else {
+            panic!("expected a DataFrame");
+        };
+        assert_eq!(
+            df.column("note").unwrap().str().unwrap().get(0),
+            Some("two\nlines")
+        );
+    }
+
+    #[test]
+    fn test_json_lines_batches() {
+        let data = "{\"a\":1}\n\n{\"a\":2}\n{\"a\":3,\"b\":true}";
+        let objects = batches(data, DataFormat::JsonLines, 2);
+        assert_eq!(heights(&objects), vec![2, 1]);
+        let Value::DataFrame(df) = &objects[1] else {
+            panic!("expected a DataFrame");
+        };
+        assert_eq!(df.get_column_names(), ["a", "b"]);
+        assert_eq!(df.column("a").unwrap().i64().unwrap().get(0), Some(3));
+
+        let scalars = batches("1\n\"two\"\n", DataFormat::JsonLines, 2);
+        assert_eq!(
+            scalars[0],
+            Value::Array(vec![Value::Int(1), Value::String("two".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_skip_and_max_rows_span_batches() {
+        let data = "x\n1\n2\n3\n4\n5\n";
+        let reader = BatchReader::new(Cursor::new(data.as_bytes().to_vec()), DataFormat::Csv, 2)
+            .unwrap()
+            .with_options(ReadOptions {
+                skip_rows: 1,
+                max_rows: Some(3),
+                ..Default::default()
+            });
+        let batches = reader.collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(heights(&batches), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_invalid_json_line_is_reported() {
+        let reader = BatchReader::new(
+            Cursor::new(b"{\"a\":1}\n{oops}\n".to_vec()),
+            DataFormat::JsonLines,
+            10,
+        )
+        .unwrap();
+        let error = reader.collect::<Result<Vec<_>>>().unwrap_err();
+        assert!(error.to_string().contains("record 2"), "{error}");
+    }
+
+    #[test]
+    fn test_parquet_cannot_be_streamed() {
+        assert!(BatchReader::new(Cursor::new(Vec::new()), DataFormat::Parquet, 10).is_err());
+    }
+}
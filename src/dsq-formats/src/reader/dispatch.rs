@@ -55,6 +55,46 @@ pub fn deserialize_parquet<R: Read + polars::io::mmap::MmapBytesReader + std::io
     Ok(Value::DataFrame(df))
 }
 
+/// Deserialize a worksheet of an Excel workbook from a reader
+#[cfg(not(target_arch = "wasm32"))]
+pub fn deserialize_excel<R: Read + std::io::Seek>(
+    reader: R,
+    options: &ReadOptions,
+    format_options: &FormatReadOptions,
+) -> Result<Value> {
+    crate::excel::deserialize_excel(reader, options, format_options)
+}
+
+/// Deserialize an Arrow IPC file or stream from a reader
+#[cfg(not(target_arch = "wasm32"))]
+pub fn deserialize_arrow<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+    _format_options: &FormatReadOptions,
+) -> Result<Value> {
+    crate::ipc::deserialize_arrow(reader, options)
+}
+
+/// Deserialize an Avro object container file from a reader
+#[cfg(not(target_arch = "wasm32"))]
+pub fn deserialize_avro<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+    format_options: &FormatReadOptions,
+) -> Result<Value> {
+    crate::avro::deserialize_avro(reader, options, format_options)
+}
+
+/// Deserialize an ORC file from a reader
+#[cfg(not(target_arch = "wasm32"))]
+pub fn deserialize_orc<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+    _format_options: &FormatReadOptions,
+) -> Result<Value> {
+    crate::orc::deserialize_orc(reader, options)
+}
+
 /// Deserialize ADT (ASCII Delimited Text) data from a reader
 pub fn deserialize_adt<R: Read>(
     reader: R,
@@ -74,6 +114,14 @@ pub fn deserialize<R: Read + polars::io::mmap::MmapBytesReader>(
     match format {
         DataFormat::Csv => deserialize_csv(reader, options, format_options),
         DataFormat::Json => deserialize_json(reader, options, format_options),
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Excel => deserialize_excel(reader, options, format_options),
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Arrow => deserialize_arrow(reader, options, format_options),
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Avro => deserialize_avro(reader, options, format_options),
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Orc => deserialize_orc(reader, options, format_options),
         #[cfg(feature = "parquet")]
         DataFormat::Parquet => deserialize_parquet(reader, options, format_options),
         #[cfg(not(feature = "parquet"))]
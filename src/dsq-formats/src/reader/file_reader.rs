@@ -5,7 +5,7 @@ use dsq_shared::value::Value;
 #[cfg(feature = "parquet")]
 use polars::prelude::ScanArgsParquet;
 use polars::prelude::*;
-use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -72,8 +72,35 @@ impl FileReader {
             ),
         };
 
-        let file = std::fs::File::open(&self.path).map_err(Error::from)?;
-        let buf_reader = std::io::BufReader::with_capacity(128 * 1024, file); // 128KB buffer
+        // Scan lazily so projections, predicates and row limits are pushed
+        // down into the scan; an explicit schema is only honored when reading
+        // eagerly, and compressed files cannot be scanned
+        let compressed = crate::compression::is_compressed(Path::new(&self.path))?;
+        if options.lazy && options.schema.is_none() && self.format != DataFormat::Adt && !compressed
+        {
+            let csv_options = crate::csv::DsqCsvReadOptions {
+                separator: csv_opts.0,
+                has_header: csv_opts.1,
+                quote_char: csv_opts.2,
+                comment_char: csv_opts.3,
+                null_values: csv_opts.4,
+                encoding: match csv_opts.5 {
+                    crate::writer::CsvEncoding::Utf8 => crate::csv::CsvEncoding::Utf8,
+                    crate::writer::CsvEncoding::Utf8Lossy => crate::csv::CsvEncoding::Utf8Lossy,
+                },
+                infer_schema_length: options.infer_schema_length.or(Some(100)),
+                skip_rows: options.skip_rows,
+                ..Default::default()
+            };
+            let mut lf = crate::csv::scan_csv_file_with_options(&self.path, csv_options)?;
+            if let Some(cols) = &options.columns {
+                lf = lf.select(cols.iter().map(|c| col(c.as_str())).collect::<Vec<_>>());
+            }
+            if let Some(max_rows) = options.max_rows {
+                lf = lf.limit(max_rows as IdxSize);
+            }
+            return Ok(Value::LazyFrame(Box::new(lf)));
+        }
 
         let mut parse_options = CsvParseOptions::default().with_separator(csv_opts.0);
 
@@ -116,9 +143,20 @@ impl FileReader {
         // We'll handle column selection after reading
         let selected_columns = options.columns.clone();
 
-        let reader = CsvReader::new(buf_reader).with_options(read_options);
-
-        let mut df = reader.finish().map_err(Error::from)?;
+        let mut df = if compressed {
+            let mut bytes = Vec::new();
+            crate::compression::open(Path::new(&self.path))?.read_to_end(&mut bytes)?;
+            CsvReader::new(std::io::Cursor::new(bytes))
+                .with_options(read_options)
+                .finish()
+        } else {
+            let file = std::fs::File::open(&self.path).map_err(Error::from)?;
+            let buf_reader = std::io::BufReader::with_capacity(128 * 1024, file); // 128KB buffer
+            CsvReader::new(buf_reader)
+                .with_options(read_options)
+                .finish()
+        }
+        .map_err(Error::from)?;
 
         // Apply column selection if specified
         if let Some(cols) = selected_columns {
@@ -180,8 +218,7 @@ impl FileReader {
             _ => (false, false),
         };
 
-        let file = std::fs::File::open(&self.path)?;
-        let mut reader = std::io::BufReader::with_capacity(128 * 1024, file); // 128KB buffer
+        let mut reader = crate::compression::open(Path::new(&self.path))?;
 
         if json_opts.0 {
             // JSON Lines format
@@ -290,284 +327,48 @@ impl FileReader {
         }
     }
 
-    /// Read Avro file
+    /// Read an Avro object container file
     #[cfg(not(target_arch = "wasm32"))]
     fn read_avro(&self, options: &ReadOptions) -> Result<Value> {
-        use apache_avro::Reader;
-
-        let file = std::fs::File::open(&self.path)?;
-        let reader = Reader::new(file)?;
-
-        let schema = reader.writer_schema().clone();
-        let mut records = Vec::new();
-
-        for result in reader {
-            let record = result?;
-            records.push(record);
-        }
-
-        if records.is_empty() {
-            // Return empty DataFrame with schema inferred from Avro schema
-            let empty_df = self.avro_schema_to_empty_dataframe(&schema)?;
-            return if options.lazy {
-                Ok(Value::LazyFrame(Box::new(empty_df.lazy())))
-            } else {
-                Ok(Value::DataFrame(empty_df))
-            };
-        }
-
-        // Convert records to DataFrame
-        let df = self.avro_records_to_dataframe(&records, &schema)?;
-
-        // Apply options
-        let mut df = df;
-        if options.skip_rows > 0 {
-            df = df.slice(options.skip_rows as i64, usize::MAX);
-        }
-
-        if let Some(max_rows) = options.max_rows {
-            df = df.head(Some(max_rows));
-        }
-
-        if let Some(cols) = &options.columns {
-            df = df.select(cols).map_err(Error::from)?;
-        }
-
-        if options.lazy {
-            Ok(Value::LazyFrame(Box::new(df.lazy())))
-        } else {
-            Ok(Value::DataFrame(df))
+        let reader = crate::compression::open(Path::new(&self.path))?;
+        match crate::avro::deserialize_avro(reader, options, &self.format_options)? {
+            Value::DataFrame(df) if options.lazy => Ok(Value::LazyFrame(Box::new(df.lazy()))),
+            value => Ok(value),
         }
     }
 
-    /// Convert Avro schema to empty Polars DataFrame
-    fn avro_schema_to_empty_dataframe(&self, schema: &apache_avro::Schema) -> Result<DataFrame> {
-        if let apache_avro::Schema::Record(record_schema) = schema {
-            let mut columns = Vec::new();
-
-            for field in &record_schema.fields {
-                let series = match &field.schema {
-                    apache_avro::Schema::String => {
-                        Series::new(field.name.as_str().into(), Vec::<String>::new())
-                    }
-                    apache_avro::Schema::Int => {
-                        Series::new(field.name.as_str().into(), Vec::<i32>::new())
-                    }
-                    apache_avro::Schema::Long => {
-                        Series::new(field.name.as_str().into(), Vec::<i64>::new())
-                    }
-                    apache_avro::Schema::Float => {
-                        Series::new(field.name.as_str().into(), Vec::<f32>::new())
-                    }
-                    apache_avro::Schema::Double => {
-                        Series::new(field.name.as_str().into(), Vec::<f64>::new())
-                    }
-                    apache_avro::Schema::Boolean => {
-                        Series::new(field.name.as_str().into(), Vec::<bool>::new())
-                    }
-                    _ => Series::new(field.name.as_str().into(), Vec::<String>::new()), // Default to string for complex types
-                };
-                columns.push(series.into());
-            }
-
-            DataFrame::new(columns).map_err(Error::from)
-        } else {
-            Err(Error::Format(
-                crate::error::FormatError::UnsupportedFeature(
-                    "Avro schema must be a record".to_string(),
-                ),
-            ))
-        }
-    }
-
-    /// Convert Avro records to Polars DataFrame
-    fn avro_records_to_dataframe(
-        &self,
-        records: &[apache_avro::types::Value],
-        schema: &apache_avro::Schema,
-    ) -> Result<DataFrame> {
-        use apache_avro::types::Value as AvroValue;
-
-        if let apache_avro::Schema::Record(record_schema) = schema {
-            let mut column_data: HashMap<String, Vec<AvroValue>> = HashMap::new();
-
-            // Initialize columns
-            for field in &record_schema.fields {
-                column_data.insert(field.name.clone(), Vec::new());
-            }
-
-            // Collect data from records
-            for record in records {
-                if let AvroValue::Record(fields) = record {
-                    for (field_name, value) in fields {
-                        if let Some(column) = column_data.get_mut(field_name) {
-                            column.push(value.clone());
-                        }
-                    }
-                }
-            }
-
-            // Convert to Polars Series
-            let mut series_vec = Vec::new();
-            for field in &record_schema.fields {
-                if let Some(values) = column_data.get(&field.name) {
-                    let series = self.avro_values_to_series(&field.name, values, &field.schema)?;
-                    series_vec.push(series.into());
-                }
-            }
-
-            DataFrame::new(series_vec).map_err(Error::from)
-        } else {
-            Err(Error::Format(
-                crate::error::FormatError::UnsupportedFeature(
-                    "Avro schema must be a record".to_string(),
-                ),
-            ))
+    /// Read a worksheet of an Excel workbook
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_excel(&self, options: &ReadOptions) -> Result<Value> {
+        let mut bytes = Vec::new();
+        crate::compression::open(Path::new(&self.path))?.read_to_end(&mut bytes)?;
+        let value = crate::excel::deserialize_excel(
+            std::io::Cursor::new(bytes),
+            options,
+            &self.format_options,
+        )?;
+
+        match value {
+            Value::DataFrame(df) if options.lazy => Ok(Value::LazyFrame(Box::new(df.lazy()))),
+            value => Ok(value),
         }
     }
 
-    /// Convert Avro values to Polars Series
-    fn avro_values_to_series(
-        &self,
-        name: &str,
-        values: &[apache_avro::types::Value],
-        field_schema: &apache_avro::Schema,
-    ) -> Result<Series> {
-        use apache_avro::types::Value as AvroValue;
-
-        match field_schema {
-            apache_avro::Schema::String => {
-                let strings: Vec<String> = values
-                    .iter()
-                    .map(|v| match v {
-                        AvroValue::String(s) => s.clone(),
-                        AvroValue::Union(_, boxed_val) => {
-                            if let AvroValue::String(s) = &**boxed_val {
-                                s.clone()
-                            } else {
-                                "".to_string()
-                            }
-                        }
-                        _ => "".to_string(),
-                    })
-                    .collect();
-                Ok(Series::new(name.into(), strings))
-            }
-            apache_avro::Schema::Int => {
-                let ints: Vec<Option<i32>> = values
-                    .iter()
-                    .map(|v| match v {
-                        AvroValue::Int(i) => Some(*i),
-                        AvroValue::Union(_, boxed_val) => {
-                            if let AvroValue::Int(i) = &**boxed_val {
-                                Some(*i)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    })
-                    .collect();
-                Ok(Series::new(name.into(), ints))
-            }
-            apache_avro::Schema::Long => {
-                let longs: Vec<Option<i64>> = values
-                    .iter()
-                    .map(|v| match v {
-                        AvroValue::Long(l) => Some(*l),
-                        AvroValue::Union(_, boxed_val) => {
-                            if let AvroValue::Long(l) = &**boxed_val {
-                                Some(*l)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    })
-                    .collect();
-                Ok(Series::new(name.into(), longs))
-            }
-            apache_avro::Schema::Float => {
-                let floats: Vec<Option<f32>> = values
-                    .iter()
-                    .map(|v| match v {
-                        AvroValue::Float(f) => Some(*f),
-                        AvroValue::Union(_, boxed_val) => {
-                            if let AvroValue::Float(f) = &**boxed_val {
-                                Some(*f)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    })
-                    .collect();
-                Ok(Series::new(name.into(), floats))
-            }
-            apache_avro::Schema::Double => {
-                let doubles: Vec<Option<f64>> = values
-                    .iter()
-                    .map(|v| match v {
-                        AvroValue::Double(d) => Some(*d),
-                        AvroValue::Union(_, boxed_val) => {
-                            if let AvroValue::Double(d) = &**boxed_val {
-                                Some(*d)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    })
-                    .collect();
-                Ok(Series::new(name.into(), doubles))
-            }
-            apache_avro::Schema::Boolean => {
-                let bools: Vec<Option<bool>> = values
-                    .iter()
-                    .map(|v| match v {
-                        AvroValue::Boolean(b) => Some(*b),
-                        AvroValue::Union(_, boxed_val) => {
-                            if let AvroValue::Boolean(b) = &**boxed_val {
-                                Some(*b)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    })
-                    .collect();
-                Ok(Series::new(name.into(), bools))
-            }
-            _ => {
-                // For complex types, convert to string representation
-                let strings: Vec<String> = values.iter().map(|v| format!("{:?}", v)).collect();
-                Ok(Series::new(name.into(), strings))
-            }
+    /// Read an ORC file
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_orc(&self, options: &ReadOptions) -> Result<Value> {
+        match crate::orc::read_orc_file(Path::new(&self.path), options)? {
+            Value::DataFrame(df) if options.lazy => Ok(Value::LazyFrame(Box::new(df.lazy()))),
+            value => Ok(value),
         }
     }
 
-    /// Read Arrow file
+    /// Read an Arrow IPC file or stream, memory-mapping IPC files
     #[cfg(not(target_arch = "wasm32"))]
     fn read_arrow(&self, options: &ReadOptions) -> Result<Value> {
-        use polars::io::ipc::IpcReader;
-
-        let file = std::fs::File::open(&self.path)?;
-        let mut reader = IpcReader::new(file);
-
-        if let Some(cols) = &options.columns {
-            reader = reader.with_columns(Some(cols.clone()));
-        }
-
-        if let Some(max_rows) = options.max_rows {
-            reader = reader.with_n_rows(Some(max_rows));
-        }
-
-        let df = reader.finish().map_err(Error::from)?;
-
-        if options.lazy {
-            Ok(Value::LazyFrame(Box::new(df.lazy())))
-        } else {
-            Ok(Value::DataFrame(df))
+        match crate::ipc::read_arrow_file(Path::new(&self.path), options)? {
+            Value::DataFrame(df) if options.lazy => Ok(Value::LazyFrame(Box::new(df.lazy()))),
+            value => Ok(value),
         }
     }
 }
@@ -604,8 +405,14 @@ impl crate::reader::data_reader::DataReader for FileReader {
             DataFormat::Json | DataFormat::JsonLines | DataFormat::JsonCompact => {
                 // Adjust for JSON Lines
                 if self.format == DataFormat::JsonLines {
-                    if let FormatReadOptions::Json { lines, .. } = &mut self.format_options {
-                        *lines = true;
+                    match &mut self.format_options {
+                        FormatReadOptions::Json { lines, .. } => *lines = true,
+                        options => {
+                            *options = FormatReadOptions::Json {
+                                lines: true,
+                                ignore_errors: false,
+                            }
+                        }
                     }
                 }
                 self.read_json(options)
@@ -614,19 +421,17 @@ impl crate::reader::data_reader::DataReader for FileReader {
             DataFormat::Avro => self.read_avro(options),
             #[cfg(not(target_arch = "wasm32"))]
             DataFormat::Arrow => self.read_arrow(options),
+            #[cfg(not(target_arch = "wasm32"))]
+            DataFormat::Excel => self.read_excel(options),
+            #[cfg(not(target_arch = "wasm32"))]
+            DataFormat::Orc => self.read_orc(options),
             #[cfg(target_arch = "wasm32")]
-            DataFormat::Avro | DataFormat::Arrow => Err(Error::Format(
-                crate::error::FormatError::UnsupportedFeature(format!(
+            DataFormat::Avro | DataFormat::Arrow | DataFormat::Excel | DataFormat::Orc => Err(
+                Error::Format(crate::error::FormatError::UnsupportedFeature(format!(
                     "{} format is not supported on WASM",
                     self.format
-                )),
-            )),
-            DataFormat::Excel | DataFormat::Orc => Err(Error::Format(
-                crate::error::FormatError::UnsupportedFeature(format!(
-                    "{} format does not support reading",
-                    self.format
-                )),
-            )),
+                ))),
+            ),
         }
     }
 
@@ -649,3 +454,90 @@ impl crate::reader::data_reader::DataReader for FileReader {
         self.format
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::DataReader;
+
+    #[test]
+    fn test_lazy_csv_is_scanned() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("people.csv");
+        std::fs::write(
+            &path,
+            "name|age|city\nAda|36|London\nTim|9|NULL\nGrace|45|Arlington\n",
+        )
+        .unwrap();
+
+        let mut reader =
+            FileReader::new(&path)
+                .unwrap()
+                .with_format_options(FormatReadOptions::Csv {
+                    separator: b'|',
+                    has_header: true,
+                    quote_char: Some(b'"'),
+                    comment_char: None,
+                    null_values: Some(vec!["NULL".to_string()]),
+                    encoding: crate::writer::CsvEncoding::Utf8,
+                });
+        let value = reader
+            .read(&ReadOptions {
+                lazy: true,
+                columns: Some(vec!["name".to_string(), "city".to_string()]),
+                max_rows: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+        let Value::LazyFrame(lf) = value else {
+            panic!("expected a LazyFrame");
+        };
+
+        let plan = lf.describe_optimized_plan().unwrap();
+        assert!(plan.contains("Csv SCAN"), "{plan}");
+        assert!(plan.contains("PROJECT 2/3 COLUMNS"), "{plan}");
+
+        let df = lf.collect().unwrap();
+        assert_eq!(df.get_column_names(), vec!["name", "city"]);
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.column("city").unwrap().str().unwrap().get(1), None);
+    }
+
+    #[test]
+    fn test_compressed_files_are_decompressed() {
+        use crate::compression::{compress, Compression};
+
+        let dir = tempfile::tempdir().unwrap();
+        let csv = dir.path().join("people.csv.gz");
+        std::fs::write(
+            &csv,
+            compress(b"name,age\nAda,36\nGrace,45\n", Some(Compression::Gzip)).unwrap(),
+        )
+        .unwrap();
+        let value = FileReader::new(&csv)
+            .unwrap()
+            .read(&ReadOptions {
+                lazy: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let Value::LazyFrame(lf) = value else {
+            panic!("expected a LazyFrame");
+        };
+        assert_eq!(lf.collect().unwrap().height(), 2);
+
+        // Compression is detected from the content when the name has no
+        // compression extension
+        let jsonl = dir.path().join("events.jsonl");
+        std::fs::write(
+            &jsonl,
+            compress(b"{\"a\":1}\n{\"a\":2}\n", Some(Compression::Xz)).unwrap(),
+        )
+        .unwrap();
+        let value = FileReader::new(&jsonl)
+            .unwrap()
+            .read(&ReadOptions::default())
+            .unwrap();
+        assert_eq!(value.len(), Some(2));
+    }
+}
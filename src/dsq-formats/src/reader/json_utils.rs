@@ -1,5 +1,7 @@
 use crate::error::{Error, Result};
 use crate::reader::options::ReadOptions;
+use dsq_shared::value::schema::values_to_series;
+use dsq_shared::value::Value;
 use polars::prelude::*;
 
 /// Helper function to convert JSON value to DataFrame
@@ -17,42 +19,26 @@ pub fn json_to_dataframe(value: &serde_json::Value, options: &ReadOptions) -> Re
                 return Ok(DataFrame::empty());
             }
 
-            // Pre-allocate series vector with capacity
-            let row_count = arr_slice.len();
-            let mut series_vec = Vec::new();
-
-            if let Some(columns) = &options.columns {
-                // Pre-allocate with known column count
-                series_vec.reserve(columns.len());
-                for col in columns {
-                    // Pre-allocate values vector with exact capacity
-                    let mut values = Vec::with_capacity(row_count);
-                    for item in arr_slice {
-                        if let Some(val) = item.get(col) {
-                            values.push(json_value_to_anyvalue(val));
-                        } else {
-                            values.push(AnyValue::Null);
-                        }
+            let columns: Vec<&str> = if let Some(columns) = &options.columns {
+                columns.iter().map(String::as_str).collect()
+            } else {
+                // Union of the keys of all rows, in the order they first appear
+                let mut keys = indexmap::IndexSet::new();
+                for item in arr_slice {
+                    if let serde_json::Value::Object(obj) = item {
+                        keys.extend(obj.keys().map(String::as_str));
                     }
-                    series_vec.push(Series::new(col.as_str().into(), values).into());
                 }
-            } else if let Some(serde_json::Value::Object(obj)) = arr_slice.first() {
-                // Pre-allocate with known column count
-                let column_count = obj.len();
-                series_vec.reserve(column_count);
+                keys.into_iter().collect()
+            };
 
-                for key in obj.keys() {
-                    // Pre-allocate values vector with exact capacity
-                    let mut values = Vec::with_capacity(row_count);
-                    for item in arr_slice {
-                        if let Some(val) = item.get(key) {
-                            values.push(json_value_to_anyvalue(val));
-                        } else {
-                            values.push(AnyValue::Null);
-                        }
-                    }
-                    series_vec.push(Series::new(key.as_str().into(), values).into());
-                }
+            let mut series_vec = Vec::with_capacity(columns.len());
+            for col in columns {
+                let values: Vec<Value> = arr_slice
+                    .iter()
+                    .map(|item| item.get(col).cloned().map_or(Value::Null, Value::from_json))
+                    .collect();
+                series_vec.push(json_column(col, &values)?);
             }
             DataFrame::new(series_vec).map_err(Error::from)
         }
@@ -71,9 +57,7 @@ pub fn json_to_dataframe(value: &serde_json::Value, options: &ReadOptions) -> Re
                 let val = obj
                     .get(key)
                     .ok_or_else(|| Error::operation("Key not found in object"))?;
-                series_vec.push(
-                    Series::new(key.as_str().into(), vec![json_value_to_anyvalue(val)]).into(),
-                );
+                series_vec.push(json_column(key, &[Value::from_json(val.clone())])?);
             }
             DataFrame::new(series_vec).map_err(Error::from)
         }
@@ -85,22 +69,53 @@ pub fn json_to_dataframe(value: &serde_json::Value, options: &ReadOptions) -> Re
     }
 }
 
-/// Convert JSON value to Polars AnyValue
-fn json_value_to_anyvalue(value: &serde_json::Value) -> AnyValue<'_> {
-    match value {
-        serde_json::Value::Null => AnyValue::Null,
-        serde_json::Value::Bool(b) => AnyValue::Boolean(*b),
-        serde_json::Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                AnyValue::Int64(i)
-            } else if let Some(f) = n.as_f64() {
-                AnyValue::Float64(f)
-            } else {
-                AnyValue::Null
-            }
-        }
-        serde_json::Value::String(s) => AnyValue::String(s),
-        serde_json::Value::Array(_) => AnyValue::Null, // TODO: Handle nested arrays
-        serde_json::Value::Object(_) => AnyValue::Null, // TODO: Handle nested objects
+/// Build a column from the values of one key, nested arrays and objects
+/// becoming `List` and `Struct` columns
+fn json_column(name: &str, values: &[Value]) -> Result<Column> {
+    let values: Vec<&Value> = values.iter().collect();
+    Ok(values_to_series(name, &values)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_json_to_dataframe() {
+        let json = serde_json::json!([
+            {"name": "Ada", "address": {"city": "London"}, "tags": ["a", "b"]},
+            {"name": "Grace", "address": {"zip": "10001", "city": "New York"}, "tags": []}
+        ]);
+        let df = json_to_dataframe(&json, &ReadOptions::default()).unwrap();
+
+        let address = df.column("address").unwrap();
+        assert_eq!(
+            address.dtype(),
+            &DataType::Struct(vec![
+                Field::new("city".into(), DataType::String),
+                Field::new("zip".into(), DataType::String),
+            ])
+        );
+        let tags = df.column("tags").unwrap();
+        assert_eq!(tags.dtype(), &DataType::List(Box::new(DataType::String)));
+
+        let row = dsq_shared::value::df_row_to_value(&df, 1).unwrap();
+        assert_eq!(
+            row.to_json().unwrap(),
+            serde_json::json!({
+                "name": "Grace",
+                "address": {"city": "New York", "zip": "10001"},
+                "tags": []
+            })
+        );
+    }
+
+    #[test]
+    fn test_columns_merged_across_rows() {
+        let json = serde_json::json!([{"a": 1}, {"a": 2.5, "b": true}]);
+        let df = json_to_dataframe(&json, &ReadOptions::default()).unwrap();
+        assert_eq!(df.get_column_names(), vec!["a", "b"]);
+        assert_eq!(df.column("a").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(df.column("b").unwrap().null_count(), 1);
     }
 }
@@ -79,6 +79,44 @@ impl crate::reader::data_reader::DataReader for MemoryReader {
                     Ok(Value::DataFrame(df))
                 }
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            DataFormat::Excel => {
+                let cursor = Cursor::new(&self.data);
+                match crate::excel::deserialize_excel(cursor, options, &self.format_options)? {
+                    Value::DataFrame(df) if options.lazy => {
+                        Ok(Value::LazyFrame(Box::new(df.lazy())))
+                    }
+                    value => Ok(value),
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            DataFormat::Arrow => {
+                match crate::ipc::deserialize_arrow(Cursor::new(&self.data), options)? {
+                    Value::DataFrame(df) if options.lazy => {
+                        Ok(Value::LazyFrame(Box::new(df.lazy())))
+                    }
+                    value => Ok(value),
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            DataFormat::Avro => {
+                let cursor = Cursor::new(&self.data);
+                match crate::avro::deserialize_avro(cursor, options, &self.format_options)? {
+                    Value::DataFrame(df) if options.lazy => {
+                        Ok(Value::LazyFrame(Box::new(df.lazy())))
+                    }
+                    value => Ok(value),
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            DataFormat::Orc => {
+                match crate::orc::deserialize_orc(Cursor::new(&self.data), options)? {
+                    Value::DataFrame(df) if options.lazy => {
+                        Ok(Value::LazyFrame(Box::new(df.lazy())))
+                    }
+                    value => Ok(value),
+                }
+            }
             _ => Err(Error::Format(
                 crate::error::FormatError::UnsupportedFeature(format!(
                     "{} format not supported for memory reading",
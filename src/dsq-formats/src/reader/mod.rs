@@ -1,4 +1,12 @@
 // Include all submodules
+/// Reading record-oriented files in bounded batches
+#[cfg(any(
+    feature = "csv",
+    feature = "json",
+    feature = "parquet",
+    feature = "avro"
+))]
+pub mod batches;
 #[cfg(any(
     feature = "csv",
     feature = "json",
@@ -45,6 +53,13 @@ pub mod options;
     feature = "parquet",
     feature = "avro"
 ))]
+pub use batches::BatchReader;
+#[cfg(any(
+    feature = "csv",
+    feature = "json",
+    feature = "parquet",
+    feature = "avro"
+))]
 pub use data_reader::DataReader;
 #[cfg(feature = "csv")]
 pub use dispatch::deserialize_csv;
@@ -59,6 +74,16 @@ pub use dispatch::deserialize_parquet;
     feature = "avro"
 ))]
 pub use dispatch::{deserialize, deserialize_adt, from_csv, from_json};
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(
+        feature = "csv",
+        feature = "json",
+        feature = "parquet",
+        feature = "avro"
+    )
+))]
+pub use dispatch::{deserialize_arrow, deserialize_avro, deserialize_excel, deserialize_orc};
 #[cfg(any(
     feature = "csv",
     feature = "json",
@@ -73,7 +98,7 @@ pub use file_reader::FileReader;
     feature = "avro"
 ))]
 pub use memory_reader::MemoryReader;
-pub use options::{FormatReadOptions, ReadOptions};
+pub use options::{ExcelSheet, FormatReadOptions, ReadOptions};
 
 // Re-export CsvEncoding for convenience
 pub use crate::writer::CsvEncoding;
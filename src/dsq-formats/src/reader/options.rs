@@ -1,3 +1,6 @@
+use std::fmt;
+use std::str::FromStr;
+
 use crate::writer::CsvEncoding;
 
 /// Options for reading data
@@ -86,12 +89,57 @@ pub enum FormatReadOptions {
     Avro {
         /// Columns to read
         columns: Option<Vec<String>>,
+        /// Reader schema, as JSON, to resolve the records against (the
+        /// schema of the file if None)
+        reader_schema: Option<String>,
     },
     /// Arrow format options
     Arrow {
         /// Columns to read
         columns: Option<Vec<String>>,
     },
+    /// Excel format options
+    Excel {
+        /// Worksheet to read (the first one if None)
+        sheet: Option<ExcelSheet>,
+        /// Whether the first row of the range holds column names
+        has_header: bool,
+        /// Cell range to read, such as `B2:F100` (the used cells if None)
+        range: Option<String>,
+    },
+}
+
+/// Worksheet of an Excel workbook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExcelSheet {
+    /// Worksheet with this name
+    Name(String),
+    /// Worksheet at this zero-based position
+    Index(usize),
+}
+
+impl FromStr for ExcelSheet {
+    type Err = String;
+
+    /// A number selects a worksheet by position, anything else by name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("worksheet name cannot be empty".to_string());
+        }
+        Ok(match s.parse::<usize>() {
+            Ok(index) => Self::Index(index),
+            Err(_) => Self::Name(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ExcelSheet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "'{name}'"),
+            Self::Index(index) => write!(f, "#{index}"),
+        }
+    }
 }
 
 impl Default for FormatReadOptions {
@@ -10,6 +10,7 @@ use polars::prelude::*;
 use polars::prelude::ParquetWriter;
 
 use crate::error::{Error, FormatError, Result};
+use crate::format::IpcFraming;
 use dsq_shared::value::Value;
 use std::io::Write;
 
@@ -110,9 +111,17 @@ pub enum FormatWriteOptions {
     Avro {
         /// Compression type
         compression: AvroCompression,
+        /// Avro schema of the records, as JSON (derived from the columns if
+        /// None)
+        schema: Option<String>,
+    },
+    /// Arrow IPC format options
+    Arrow {
+        /// Compression of the record batch buffers
+        compression: ArrowCompression,
+        /// Whether to write an IPC file or an IPC stream
+        framing: IpcFraming,
     },
-    /// Arrow format
-    Arrow,
     /// Excel format options
     Excel {
         /// Worksheet name
@@ -128,6 +137,8 @@ pub enum FormatWriteOptions {
     Orc {
         /// Compression type
         compression: OrcCompression,
+        /// Target size of a stripe, in bytes of uncompressed column data
+        stripe_size: usize,
     },
 }
 
@@ -148,6 +159,32 @@ pub enum OrcCompression {
     Zstd,
 }
 
+/// Arrow IPC buffer compression options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowCompression {
+    /// No compression
+    Uncompressed,
+    /// LZ4 frame compression
+    Lz4,
+    /// Zstandard compression
+    Zstd,
+}
+
+impl std::str::FromStr for ArrowCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "uncompressed" | "none" => Ok(Self::Uncompressed),
+            "lz4" => Ok(Self::Lz4),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(format!(
+                "unknown Arrow compression {s}; use lz4, zstd or uncompressed"
+            )),
+        }
+    }
+}
+
 /// CSV encoding options
 #[derive(Debug, Clone)]
 pub enum CsvEncoding {
@@ -194,6 +231,36 @@ pub enum AvroCompression {
     Zstandard,
 }
 
+impl FormatWriteOptions {
+    /// Arrow options writing uncompressed record batches with `framing`
+    pub fn arrow(framing: IpcFraming) -> Self {
+        FormatWriteOptions::Arrow {
+            compression: ArrowCompression::Uncompressed,
+            framing,
+        }
+    }
+
+    /// Excel options writing a `Sheet1` worksheet with a header row and
+    /// columns fitted to their contents
+    pub fn excel() -> Self {
+        FormatWriteOptions::Excel {
+            worksheet_name: "Sheet1".to_string(),
+            include_header: true,
+            autofit: true,
+            float_precision: None,
+        }
+    }
+
+    /// ORC options writing zlib-compressed stripes of 64 MiB
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn orc() -> Self {
+        FormatWriteOptions::Orc {
+            compression: OrcCompression::Zlib,
+            stripe_size: crate::orc::DEFAULT_STRIPE_SIZE,
+        }
+    }
+}
+
 impl Default for FormatWriteOptions {
     fn default() -> Self {
         FormatWriteOptions::Csv {
@@ -275,140 +342,48 @@ pub fn serialize_parquet<W: Write>(
     Ok(())
 }
 
-/// Serialize Avro data to a writer
-#[cfg(feature = "avro")]
+/// Serialize data to an Avro object container file
+#[cfg(all(feature = "avro", not(target_arch = "wasm32")))]
 pub fn serialize_avro<W: Write>(
-    mut writer: W,
+    writer: W,
     value: &Value,
-    _options: &WriteOptions,
+    options: &WriteOptions,
     format_options: &FormatWriteOptions,
 ) -> Result<()> {
-    use apache_avro::{types::Value as AvroValue, Codec, Writer as AvroWriter};
-
-    let df = match value {
-        Value::DataFrame(df) => df.clone(),
-        Value::LazyFrame(lf) => (*lf).clone().collect().map_err(Error::from)?,
-        _ => {
-            return Err(Error::operation(
-                "Expected DataFrame for Avro serialization",
-            ));
-        }
-    };
-
-    // Get compression codec
-    let codec = match format_options {
-        FormatWriteOptions::Avro { compression } => match compression {
-            AvroCompression::Null => Codec::Null,
-            AvroCompression::Deflate => Codec::Deflate(Default::default()),
-            AvroCompression::Snappy => Codec::Snappy,
-            #[allow(unreachable_patterns)]
-            _ => Codec::Null,
-        },
-        _ => Codec::Null,
-    };
-
-    // Build Avro schema from DataFrame schema
-    let schema = dataframe_to_avro_schema(&df)?;
-
-    // Create temporary buffer since AvroWriter needs seekable writer
-    let mut buffer = Vec::new();
-    let mut avro_writer = AvroWriter::with_codec(&schema, &mut buffer, codec);
-
-    // Write rows
-    for row_idx in 0..df.height() {
-        let mut record_fields = Vec::new();
-
-        for (col_idx, column) in df.get_columns().iter().enumerate() {
-            let field_name = df.get_column_names()[col_idx];
-            let avro_value = polars_value_to_avro(column.get(row_idx).map_err(Error::from)?)?;
-            record_fields.push((field_name.to_string(), avro_value));
-        }
-
-        let record = AvroValue::Record(record_fields);
-        avro_writer.append(record).map_err(Error::from)?;
-    }
-
-    avro_writer.flush().map_err(Error::from)?;
-    drop(avro_writer); // Drop the writer to release the mutable borrow on buffer
-    writer.write_all(&buffer)?;
-    Ok(())
+    crate::avro::serialize_avro(writer, value, options, format_options)
 }
 
-/// Convert DataFrame schema to Avro schema
-#[cfg(feature = "avro")]
-fn dataframe_to_avro_schema(df: &DataFrame) -> Result<apache_avro::Schema> {
-    use apache_avro::Schema;
-
-    let mut fields = Vec::new();
-
-    for (col_idx, column) in df.get_columns().iter().enumerate() {
-        let field_name = df.get_column_names()[col_idx];
-        let field_schema = polars_dtype_to_avro_schema(column.dtype())?;
-
-        fields.push(apache_avro::schema::RecordField {
-            name: field_name.to_string(),
-            doc: None,
-            aliases: None,
-            default: None,
-            schema: field_schema,
-            order: apache_avro::schema::RecordFieldOrder::Ascending,
-            position: col_idx,
-            custom_attributes: Default::default(),
-        });
-    }
-
-    Ok(Schema::Record(apache_avro::schema::RecordSchema {
-        name: apache_avro::schema::Name::new("record").map_err(Error::from)?,
-        aliases: None,
-        doc: None,
-        fields,
-        lookup: Default::default(),
-        attributes: Default::default(),
-    }))
+/// Serialize data to an Excel workbook
+#[cfg(not(target_arch = "wasm32"))]
+pub fn serialize_excel<W: Write>(
+    writer: W,
+    value: &Value,
+    options: &WriteOptions,
+    format_options: &FormatWriteOptions,
+) -> Result<()> {
+    crate::excel::serialize_excel(writer, value, options, format_options)
 }
 
-/// Convert Polars DataType to Avro Schema
-#[cfg(feature = "avro")]
-fn polars_dtype_to_avro_schema(dtype: &DataType) -> Result<apache_avro::Schema> {
-    use apache_avro::Schema;
-
-    match dtype {
-        DataType::Boolean => Ok(Schema::Boolean),
-        DataType::Int8 | DataType::Int16 | DataType::Int32 => Ok(Schema::Int),
-        DataType::Int64 => Ok(Schema::Long),
-        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 => Ok(Schema::Int),
-        DataType::UInt64 => Ok(Schema::Long),
-        DataType::Float32 => Ok(Schema::Float),
-        DataType::Float64 => Ok(Schema::Double),
-        DataType::String => Ok(Schema::String),
-        DataType::Binary => Ok(Schema::Bytes),
-        DataType::Null => Ok(Schema::Null),
-        _ => Ok(Schema::String), // Default to string for unsupported types
-    }
+/// Serialize data to an Arrow IPC file or stream
+#[cfg(not(target_arch = "wasm32"))]
+pub fn serialize_arrow<W: Write>(
+    writer: W,
+    value: &Value,
+    options: &WriteOptions,
+    format_options: &FormatWriteOptions,
+) -> Result<()> {
+    crate::ipc::serialize_arrow(writer, value, options, format_options)
 }
 
-/// Convert Polars AnyValue to Avro Value
-#[cfg(feature = "avro")]
-fn polars_value_to_avro(value: AnyValue) -> Result<apache_avro::types::Value> {
-    use apache_avro::types::Value as AvroValue;
-
-    match value {
-        AnyValue::Null => Ok(AvroValue::Null),
-        AnyValue::Boolean(b) => Ok(AvroValue::Boolean(b)),
-        AnyValue::Int8(i) => Ok(AvroValue::Int(i as i32)),
-        AnyValue::Int16(i) => Ok(AvroValue::Int(i as i32)),
-        AnyValue::Int32(i) => Ok(AvroValue::Int(i)),
-        AnyValue::Int64(i) => Ok(AvroValue::Long(i)),
-        AnyValue::UInt8(u) => Ok(AvroValue::Int(u as i32)),
-        AnyValue::UInt16(u) => Ok(AvroValue::Int(u as i32)),
-        AnyValue::UInt32(u) => Ok(AvroValue::Int(u as i32)),
-        AnyValue::UInt64(u) => Ok(AvroValue::Long(u as i64)),
-        AnyValue::Float32(f) => Ok(AvroValue::Float(f)),
-        AnyValue::Float64(f) => Ok(AvroValue::Double(f)),
-        AnyValue::String(s) => Ok(AvroValue::String(s.to_string())),
-        AnyValue::Binary(b) => Ok(AvroValue::Bytes(b.to_vec())),
-        _ => Ok(AvroValue::String(format!("{}", value))),
-    }
+/// Serialize data to an ORC file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn serialize_orc<W: Write>(
+    writer: W,
+    value: &Value,
+    options: &WriteOptions,
+    format_options: &FormatWriteOptions,
+) -> Result<()> {
+    crate::orc::serialize_orc(writer, value, options, format_options)
 }
 
 /// Serialize ADT (ASCII Delimited Text) data to a writer
@@ -511,20 +486,29 @@ pub fn serialize<W: Write>(
         DataFormat::Parquet => Err(Error::Format(FormatError::UnsupportedFeature(
             "Parquet not supported in this build".to_string(),
         ))),
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Arrow => serialize_arrow(writer, value, options, format_options),
+        #[cfg(target_arch = "wasm32")]
         DataFormat::Arrow => Err(Error::Format(FormatError::UnsupportedFeature(
-            "Arrow serialization not yet implemented".to_string(),
+            "Arrow is not supported on WASM".to_string(),
         ))),
-        #[cfg(feature = "avro")]
+        #[cfg(all(feature = "avro", not(target_arch = "wasm32")))]
         DataFormat::Avro => serialize_avro(writer, value, options, format_options),
-        #[cfg(not(feature = "avro"))]
+        #[cfg(not(all(feature = "avro", not(target_arch = "wasm32"))))]
         DataFormat::Avro => Err(Error::Format(FormatError::UnsupportedFeature(
             "Avro not supported in this build".to_string(),
         ))),
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Excel => serialize_excel(writer, value, options, format_options),
+        #[cfg(target_arch = "wasm32")]
         DataFormat::Excel => Err(Error::Format(FormatError::UnsupportedFeature(
-            "Excel serialization not yet implemented".to_string(),
+            "Excel is not supported on WASM".to_string(),
         ))),
+        #[cfg(not(target_arch = "wasm32"))]
+        DataFormat::Orc => serialize_orc(writer, value, options, format_options),
+        #[cfg(target_arch = "wasm32")]
         DataFormat::Orc => Err(Error::Format(FormatError::UnsupportedFeature(
-            "ORC serialization not yet implemented".to_string(),
+            "ORC is not supported on WASM".to_string(),
         ))),
         _ => Err(Error::Format(FormatError::Unknown(format.to_string()))),
     }
@@ -571,11 +555,15 @@ impl FileWriter {
     pub fn new<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
         let path_ref = path.as_ref();
         let format = crate::format::DataFormat::from_path(path_ref)?;
+        let format_options = match format {
+            DataFormat::Arrow => FormatWriteOptions::arrow(IpcFraming::from_path(path_ref)),
+            _ => Self::default_format_options_for_format(format),
+        };
 
         Ok(Self {
             path: path_ref.to_string_lossy().to_string(),
             format,
-            format_options: Self::default_format_options_for_format(format),
+            format_options,
         })
     }
 
@@ -634,10 +622,14 @@ impl FileWriter {
                 lines: false,
                 pretty: false,
             },
-            DataFormat::Arrow => FormatWriteOptions::Arrow,
+            DataFormat::Arrow => FormatWriteOptions::arrow(IpcFraming::File),
             DataFormat::Avro => FormatWriteOptions::Avro {
                 compression: AvroCompression::Null,
+                schema: None,
             },
+            DataFormat::Excel => FormatWriteOptions::excel(),
+            #[cfg(not(target_arch = "wasm32"))]
+            DataFormat::Orc => FormatWriteOptions::orc(),
             _ => FormatWriteOptions::default(),
         }
     }
@@ -675,8 +667,18 @@ impl DataWriter for FileWriter {
 
         self.check_overwrite(options)?;
         let file = File::create(&self.path)?;
-        let writer = BufWriter::new(file);
-        serialize(writer, value, self.format, options, &self.format_options)
+        let compression = crate::compression::Compression::from_path(self.path.as_ref());
+        let mut writer =
+            crate::compression::CompressedWriter::new(BufWriter::new(file), compression)?;
+        serialize(
+            &mut writer,
+            value,
+            self.format,
+            options,
+            &self.format_options,
+        )?;
+        writer.finish()?;
+        Ok(())
     }
 
     fn format(&self) -> DataFormat {
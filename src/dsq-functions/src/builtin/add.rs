@@ -99,6 +99,17 @@ fn builtin_add_single(args: &[Value]) -> Result<Value> {
                     }
                 }
                 Ok(Value::String(result))
+            }
+            // Durations, decimals and binary add up with their own arithmetic
+            else if arr.iter().all(|v| {
+                matches!(
+                    v,
+                    Value::Duration(_) | Value::Decimal { .. } | Value::Binary(_)
+                )
+            }) {
+                let mut iter = arr.iter();
+                let first = iter.next().cloned().unwrap_or(Value::Null);
+                iter.try_fold(first, |sum, v| dsq_shared::ops::add_values(&sum, v))
             } else {
                 Err(dsq_shared::error::operation_error(
                     "add() requires homogeneous array",
@@ -221,7 +232,15 @@ fn builtin_add_multiple(args: &[Value]) -> Result<Value> {
                     "add() with multiple arguments does not support arrays",
                 ));
             }
-            Value::DataFrame(_) | Value::LazyFrame(_) | Value::Series(_) | Value::Object(_) => {
+            Value::DataFrame(_)
+            | Value::LazyFrame(_)
+            | Value::Series(_)
+            | Value::Object(_)
+            | Value::Date(_)
+            | Value::Datetime { .. }
+            | Value::Duration(_)
+            | Value::Decimal { .. }
+            | Value::Binary(_) => {
                 return Err(dsq_shared::error::operation_error(
                     "add() with multiple arguments only supports numeric and string values",
                 ));
@@ -379,6 +398,21 @@ mod tests {
         assert_eq!(result, Value::Int(15));
     }
 
+    #[test]
+    fn test_builtin_add_durations_and_decimals() {
+        let durations = Value::Array(vec![
+            Value::duration(chrono::Duration::minutes(30)),
+            Value::duration(chrono::Duration::minutes(45)),
+        ]);
+        assert_eq!(
+            builtin_add(&[durations]).unwrap(),
+            Value::duration(chrono::Duration::minutes(75))
+        );
+
+        let decimals = Value::Array(vec![Value::decimal(10, 2), Value::decimal(2, 1)]);
+        assert_eq!(builtin_add(&[decimals]).unwrap(), Value::decimal(30, 2));
+    }
+
     #[test]
     fn test_add_registered_via_inventory() {
         use crate::BuiltinRegistry;
@@ -2,8 +2,8 @@ use crate::inventory;
 use crate::FunctionRegistration;
 use dsq_shared::value::{is_truthy, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 inventory::submit! {
     FunctionRegistration {
@@ -68,7 +68,7 @@ pub fn builtin_avg_if(args: &[Value]) -> Result<Value> {
                 ));
             }
             // For DataFrame, average all numeric columns where mask is true
-            let mut result = HashMap::new();
+            let mut result = IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(series) = df.column(col_name) {
                     if series.dtype().is_numeric() {
@@ -1,8 +1,8 @@
 use dsq_shared::value::{is_truthy, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 pub fn builtin_avg_ifs(args: &[Value]) -> Result<Value> {
     if args.len() < 3 {
@@ -88,7 +88,7 @@ pub fn builtin_avg_ifs(args: &[Value]) -> Result<Value> {
                 }
             }
 
-            let mut result = HashMap::new();
+            let mut result = IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(series) = df.column(col_name) {
                     if series.dtype().is_numeric() {
@@ -61,7 +61,7 @@ pub fn builtin_bbands(args: &[Value]) -> Result<Value> {
                 .iter()
                 .map(|&price| {
                     let output = bb_indicator.next(price);
-                    let mut obj = std::collections::HashMap::new();
+                    let mut obj = indexmap::IndexMap::new();
                     obj.insert("middle".to_string(), Value::Float(output.average));
                     obj.insert("upper".to_string(), Value::Float(output.upper));
                     obj.insert("lower".to_string(), Value::Float(output.lower));
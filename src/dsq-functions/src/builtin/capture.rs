@@ -0,0 +1,125 @@
+use crate::builtin::regex_match::{
+    capture_object, compile_regex, find_matches, map_strings, not_a_string, regex_args,
+    string_values, RegexFlags,
+};
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+use polars::prelude::*;
+use regex::Regex;
+
+fn capture_string(re: &Regex, text: &str, flags: &RegexFlags) -> Value {
+    let mut captures: Vec<Value> = find_matches(re, text, flags)
+        .iter()
+        .map(|caps| capture_object(re, caps))
+        .collect();
+    if flags.global {
+        Value::Array(captures)
+    } else {
+        captures.pop().unwrap_or(Value::Null)
+    }
+}
+
+/// Extract every named group of `pattern` from a string Series into a
+/// DataFrame with one column per group
+fn capture_columns(series: &Series, re: &Regex, pattern: &str) -> Result<Value> {
+    let values = string_values(series)?;
+    let pattern = StringChunked::new(PlSmallStr::EMPTY, [pattern]);
+    let columns = re
+        .capture_names()
+        .enumerate()
+        .filter_map(|(index, name)| name.map(|name| (index, name)))
+        .map(|(index, name)| {
+            let extracted = values.extract(&pattern, index).map_err(|e| {
+                dsq_shared::error::operation_error(format!("capture() failed: {}", e))
+            })?;
+            Ok(extracted.with_name(name.into()).into_column())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    DataFrame::new(columns)
+        .map(Value::DataFrame)
+        .map_err(|e| dsq_shared::error::operation_error(format!("capture() failed: {}", e)))
+}
+
+pub fn builtin_capture(args: &[Value]) -> Result<Value> {
+    let Some((input, rest)) = args.split_first() else {
+        return Err(dsq_shared::error::operation_error(
+            "capture() expects 1 or 2 arguments",
+        ));
+    };
+    let (pattern, flags) = regex_args("capture", rest)?;
+    let re = compile_regex(&pattern)?;
+
+    match input {
+        Value::String(s) => Ok(capture_string(&re, s, &flags)),
+        Value::Series(series)
+            if !flags.global && !flags.skip_empty && re.capture_names().flatten().count() > 0 =>
+        {
+            capture_columns(series, &re, &pattern)
+        }
+        Value::Series(series) => map_strings(series, |s| Ok(capture_string(&re, s, &flags))),
+        other => Err(not_a_string(other)),
+    }
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "capture",
+        func: builtin_capture,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_capture_string() {
+        let result =
+            builtin_capture(&[s("xyzzy-14"), s("(?<a>[a-z]+)-(?<n>[0-9]+)(?<rest>!)?")]).unwrap();
+        let object = |pairs: &[(&str, Value)]| {
+            Value::Object(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.clone()))
+                    .collect(),
+            )
+        };
+        assert_eq!(
+            result,
+            object(&[("a", s("xyzzy")), ("n", s("14")), ("rest", Value::Null)])
+        );
+
+        let result = builtin_capture(&[s("a1 b2"), s("(?<l>[a-z])(?<d>\\d)"), s("g")]).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                object(&[("l", s("a")), ("d", s("1"))]),
+                object(&[("l", s("b")), ("d", s("2"))]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_capture_series_columns() {
+        let series = Series::new("id".into(), [Some("ab-12"), Some("zz"), None]);
+        let result =
+            builtin_capture(&[Value::Series(series), s("(?<prefix>[a-z]+)-(?<num>\\d+)")]).unwrap();
+        let Value::DataFrame(df) = result else {
+            panic!("expected a DataFrame, got {:?}", result);
+        };
+        assert_eq!(df.get_column_names(), vec!["prefix", "num"]);
+        let nums: Vec<_> = df
+            .column("num")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(nums, vec![Some("12"), None, None]);
+    }
+}
@@ -95,7 +95,7 @@ mod tests {
 
     #[test]
     fn test_coalesce_with_object() {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
         let result = builtin_coalesce(&[Value::Null, Value::Object(obj.clone())]).unwrap();
         assert_eq!(result, Value::Object(obj));
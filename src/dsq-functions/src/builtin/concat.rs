@@ -64,7 +64,7 @@ mod tests {
 
     #[test]
     fn test_concat_objects() {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
         let result = builtin_concat(&[Value::Object(obj)]).unwrap();
         // Object to_string might vary, but should contain the key-value
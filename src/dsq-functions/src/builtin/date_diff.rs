@@ -84,4 +84,19 @@ mod tests {
         let result = builtin_date_diff(&[arr1, arr2]).unwrap();
         assert_eq!(result, Value::Array(vec![Value::Int(4), Value::Int(4)]));
     }
+
+    #[test]
+    fn test_date_diff_temporal_values() {
+        let start = chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let end = chrono::NaiveDate::from_ymd_opt(2023, 1, 11).unwrap();
+        let result = builtin_date_diff(&[Value::date(start), Value::date(end)]).unwrap();
+        assert_eq!(result, Value::Int(10));
+
+        let result = builtin_date_diff(&[
+            Value::datetime(end.and_hms_opt(6, 0, 0).unwrap(), Some("UTC")),
+            Value::date(start),
+        ])
+        .unwrap();
+        assert_eq!(result, Value::Int(10));
+    }
 }
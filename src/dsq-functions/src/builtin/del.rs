@@ -16,7 +16,7 @@ pub fn builtin_del(args: &[Value]) -> Result<Value> {
             let mut new_obj = obj.clone();
             for key_arg in &args[1..] {
                 if let Value::String(key) = key_arg {
-                    new_obj.remove(key);
+                    new_obj.shift_remove(key);
                 }
             }
             Ok(Value::Object(new_obj))
@@ -61,11 +61,11 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_builtin_del_object() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         obj.insert("c".to_string(), Value::Int(3));
@@ -83,7 +83,7 @@ mod tests {
 
     #[test]
     fn test_builtin_del_object_multiple_keys() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         obj.insert("c".to_string(), Value::Int(3));
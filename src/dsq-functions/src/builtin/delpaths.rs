@@ -0,0 +1,184 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+use polars::prelude::*;
+
+use super::getpath::{describe_key, get_key, path_components, slice_range};
+use super::setpath::set_key;
+
+/// Remove the child of `value` at the path component `key`
+///
+/// On a DataFrame a string key drops the column and an integer key the row.
+pub fn delete_key(value: &Value, key: &Value) -> Result<Value> {
+    match (value, key) {
+        (Value::Null, _) => Ok(Value::Null),
+        (Value::Object(obj), Value::String(k)) => {
+            let mut obj = obj.clone();
+            obj.shift_remove(k);
+            Ok(Value::Object(obj))
+        }
+        (Value::Array(arr), Value::Int(i)) => {
+            let len = arr.len() as i64;
+            let index = if *i < 0 { len + i } else { *i };
+            let mut arr = arr.clone();
+            if (0..len).contains(&index) {
+                arr.remove(index as usize);
+            }
+            Ok(Value::Array(arr))
+        }
+        (Value::Array(arr), Value::Object(_)) => {
+            let (start, end) = slice_range(key, arr.len()).ok_or_else(|| {
+                dsq_shared::error::operation_error("Array slice bounds must be numbers")
+            })?;
+            let mut arr = arr.clone();
+            arr.drain(start..end);
+            Ok(Value::Array(arr))
+        }
+        (Value::DataFrame(df), Value::String(column)) => {
+            if df.column(column).is_err() {
+                return Ok(value.clone());
+            }
+            df.drop(column).map(Value::DataFrame).map_err(|e| {
+                dsq_shared::error::operation_error(format!(
+                    "Failed to drop column \"{}\": {}",
+                    column, e
+                ))
+            })
+        }
+        (Value::DataFrame(df), Value::Int(i)) => {
+            let len = df.height() as i64;
+            let index = if *i < 0 { len + i } else { *i };
+            let mask: BooleanChunked = (0..len).map(|row| row != index).collect();
+            df.filter(&mask).map(Value::DataFrame).map_err(|e| {
+                dsq_shared::error::operation_error(format!("Failed to delete row {}: {}", i, e))
+            })
+        }
+        (Value::LazyFrame(lf), _) => {
+            let df = lf.clone().collect().map_err(|e| {
+                dsq_shared::error::operation_error(format!("Failed to collect LazyFrame: {}", e))
+            })?;
+            delete_key(&Value::DataFrame(df), key)
+        }
+        _ => Err(dsq_shared::error::operation_error(format!(
+            "Cannot delete {} of {}",
+            describe_key(key),
+            value.type_name()
+        ))),
+    }
+}
+
+/// `value` without whatever is at `path`
+pub fn delete_path(value: &Value, path: &[Value]) -> Result<Value> {
+    match path {
+        [] => Ok(Value::Null),
+        [key] => delete_key(value, key),
+        [key, rest @ ..] => {
+            let child = get_key(value, key)?;
+            if child == Value::Null {
+                return Ok(value.clone());
+            }
+            set_key(value, key, delete_path(&child, rest)?)
+        }
+    }
+}
+
+/// `value` without everything at `paths`
+///
+/// Paths are deleted from last to first so that removing an array element
+/// does not shift the indices of the elements still to be removed.
+pub fn delete_paths(value: &Value, paths: &[Vec<Value>]) -> Result<Value> {
+    let mut paths: Vec<&Vec<Value>> = paths.iter().collect();
+    paths.sort_by(|a, b| {
+        a.iter()
+            .zip(b.iter())
+            .map(|(x, y)| crate::compare_values_for_sorting(x, y))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len()))
+    });
+    paths.dedup();
+
+    let mut result = value.clone();
+    for path in paths.into_iter().rev() {
+        result = delete_path(&result, path)?;
+    }
+    Ok(result)
+}
+
+pub fn builtin_delpaths(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(dsq_shared::error::operation_error(
+            "delpaths() expects 2 arguments",
+        ));
+    }
+
+    let paths = path_components(&args[1], "delpaths")?
+        .iter()
+        .map(|path| path_components(path, "delpaths").map(<[Value]>::to_vec))
+        .collect::<Result<Vec<_>>>()?;
+    delete_paths(&args[0], &paths)
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "delpaths",
+        func: builtin_delpaths,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_delpaths_array_indices() {
+        let value = Value::Array(vec![Value::Int(0), Value::Int(1), Value::Int(2)]);
+        let paths = Value::Array(vec![
+            Value::Array(vec![Value::Int(0)]),
+            Value::Array(vec![Value::Int(2)]),
+        ]);
+        let result = builtin_delpaths(&[value, paths]).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(1)]));
+    }
+
+    #[test]
+    fn test_delpaths_nested() {
+        let value = Value::Object(IndexMap::from([(
+            "a".to_string(),
+            Value::Object(IndexMap::from([
+                ("b".to_string(), Value::Int(1)),
+                ("c".to_string(), Value::Int(2)),
+            ])),
+        )]));
+        let paths = Value::Array(vec![
+            Value::Array(vec![
+                Value::String("a".to_string()),
+                Value::String("b".to_string()),
+            ]),
+            Value::Array(vec![Value::String("missing".to_string())]),
+        ]);
+        let result = builtin_delpaths(&[value, paths]).unwrap();
+        assert_eq!(
+            result,
+            Value::Object(IndexMap::from([(
+                "a".to_string(),
+                Value::Object(IndexMap::from([("c".to_string(), Value::Int(2))])),
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_delpaths_dataframe() {
+        let df = df! { "a" => [1i64, 2, 3], "b" => ["x", "y", "z"] }.unwrap();
+        let paths = Value::Array(vec![
+            Value::Array(vec![Value::String("b".to_string())]),
+            Value::Array(vec![Value::Int(1)]),
+        ]);
+        let result = builtin_delpaths(&[Value::DataFrame(df), paths]).unwrap();
+        let Value::DataFrame(df) = result else {
+            panic!("Expected DataFrame");
+        };
+        assert_eq!(df.get_column_names(), vec!["a"]);
+        assert_eq!(df.height(), 2);
+    }
+}
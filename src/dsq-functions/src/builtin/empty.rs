@@ -29,7 +29,7 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_empty_array() {
@@ -59,7 +59,7 @@ mod tests {
 
     #[test]
     fn test_empty_object() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         let result = builtin_empty(&[Value::Object(obj)]).unwrap();
@@ -68,7 +68,7 @@ mod tests {
 
     #[test]
     fn test_empty_empty_object() {
-        let obj = HashMap::new();
+        let obj = IndexMap::new();
         let result = builtin_empty(&[Value::Object(obj)]).unwrap();
         assert_eq!(result, Value::Bool(true));
     }
@@ -10,7 +10,7 @@ pub fn builtin_end_of_month(args: &[Value]) -> Result<Value> {
         ));
     }
 
-    let dt = crate::extract_timestamp(&args[0])?;
+    let dt = crate::extract_local_timestamp(&args[0])?;
     let year = dt.year();
     let month = dt.month();
     let last_day = match month {
@@ -31,6 +31,7 @@ pub fn builtin_end_of_month(args: &[Value]) -> Result<Value> {
             .and_hms_opt(23, 59, 59)
             .unwrap(),
     );
+    let end_of_month = crate::local_to_instant(&args[0], end_of_month)?;
     Ok(Value::Int(end_of_month.timestamp()))
 }
 
@@ -11,8 +11,12 @@ pub fn builtin_end_of_week(args: &[Value]) -> Result<Value> {
     }
 
     match &args[0] {
-        Value::Int(_) | Value::Float(_) | Value::String(_) => {
-            let dt = crate::extract_timestamp(&args[0])?;
+        Value::Int(_)
+        | Value::Float(_)
+        | Value::String(_)
+        | Value::Date(_)
+        | Value::Datetime { .. } => {
+            let dt = crate::extract_local_timestamp(&args[0])?;
             // Sunday is the end of the week (ISO 8601 standard where weeks start on Monday)
             let is_end_of_week = dt.weekday() == Weekday::Sun;
             Ok(Value::Bool(is_end_of_week))
@@ -21,8 +25,15 @@ pub fn builtin_end_of_week(args: &[Value]) -> Result<Value> {
             let results: Result<Vec<Value>> = arr
                 .iter()
                 .map(|v| {
-                    if matches!(v, Value::Int(_) | Value::Float(_) | Value::String(_)) {
-                        let dt = crate::extract_timestamp(v)?;
+                    if matches!(
+                        v,
+                        Value::Int(_)
+                            | Value::Float(_)
+                            | Value::String(_)
+                            | Value::Date(_)
+                            | Value::Datetime { .. }
+                    ) {
+                        let dt = crate::extract_local_timestamp(v)?;
                         let is_end_of_week = dt.weekday() == Weekday::Sun;
                         Ok(Value::Bool(is_end_of_week))
                     } else {
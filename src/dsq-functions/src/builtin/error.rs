@@ -11,13 +11,12 @@ inventory::submit! {
 }
 
 pub fn builtin_error(args: &[Value]) -> Result<Value> {
-    let message = if args.is_empty() {
-        "error".to_string()
-    } else {
-        args[0].to_string()
-    };
+    let value = args
+        .first()
+        .cloned()
+        .unwrap_or_else(|| Value::String("error".to_string()));
 
-    Err(dsq_shared::error::operation_error(message))
+    Err(dsq_shared::error::value_error(value))
 }
 
 #[cfg(test)]
@@ -58,6 +57,14 @@ mod tests {
         assert!(err.to_string().contains("[1, 2]"));
     }
 
+    #[test]
+    fn test_error_carries_value() {
+        let payload = Value::Object([("code".to_string(), Value::Int(7))].into_iter().collect());
+        let err = builtin_error(std::slice::from_ref(&payload)).unwrap_err();
+        assert_eq!(dsq_shared::error::error_value(&err), payload);
+        assert!(err.to_string().contains("(not a string)"));
+    }
+
     #[test]
     fn test_error_registered_via_inventory() {
         use crate::BuiltinRegistry;
@@ -96,7 +96,7 @@ mod tests {
     use super::*;
     use dsq_shared::value::Value;
 
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_filter_array() {
@@ -194,7 +194,7 @@ mod tests {
         let result = builtin_filter(&[Value::Int(42), Value::Int(1)]).unwrap();
         assert_eq!(result, Value::Int(42));
 
-        let obj = HashMap::new();
+        let obj = IndexMap::new();
         let result = builtin_filter(&[Value::Object(obj), Value::Int(1)]).unwrap();
         match result {
             Value::Object(_) => {}
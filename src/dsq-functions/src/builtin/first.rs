@@ -1,8 +1,8 @@
 use crate::FunctionRegistration;
 use dsq_shared::value::{value_from_any_value, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
-use std::collections::HashMap;
 
 inventory::submit! {
     FunctionRegistration {
@@ -38,7 +38,7 @@ pub fn builtin_first(args: &[Value]) -> Result<Value> {
                 Ok(Value::Null)
             } else {
                 // Return first row as object
-                let mut row_obj = HashMap::new();
+                let mut row_obj = IndexMap::new();
                 for col_name in df.get_column_names() {
                     if let Ok(series) = df.column(col_name) {
                         if let Ok(val) = series.get(0) {
@@ -0,0 +1,311 @@
+use base64::{engine::general_purpose, Engine};
+use dsq_shared::value::{value_from_any_value, Value};
+use dsq_shared::Result;
+use inventory;
+use polars::prelude::*;
+
+/// Names accepted by `@name` and `format(name)`
+pub const FORMATS: &[&str] = &[
+    "text", "json", "csv", "tsv", "html", "uri", "sh", "base64", "base64d", "base32", "base32d",
+];
+
+/// Render `value` as JSON text
+fn json_text(value: &Value) -> Result<String> {
+    serde_json::to_string(value)
+        .map_err(|e| dsq_shared::error::operation_error(format!("Cannot format as JSON: {}", e)))
+}
+
+/// Render `value` as `tostring` does: strings as is, anything else as JSON
+fn plain_text(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        other => json_text(other),
+    }
+}
+
+/// The elements of a `@csv`/`@tsv`/`@sh` input
+fn row_items<'a>(name: &str, value: &'a Value) -> Result<&'a [Value]> {
+    match value {
+        Value::Array(items) => Ok(items),
+        other => Err(dsq_shared::error::operation_error(format!(
+            "{} cannot be {}-formatted, only an array can be",
+            other.type_name(),
+            name
+        ))),
+    }
+}
+
+fn csv_field(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(format!("\"{}\"", s.replace('"', "\"\""))),
+        Value::Null => Ok(String::new()),
+        Value::Int(_) | Value::BigInt(_) | Value::Float(_) | Value::Bool(_) => json_text(value),
+        other => Err(dsq_shared::error::operation_error(format!(
+            "{} is not valid in a csv row",
+            other.type_name()
+        ))),
+    }
+}
+
+fn tsv_field(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")),
+        Value::Null => Ok(String::new()),
+        Value::Int(_) | Value::BigInt(_) | Value::Float(_) | Value::Bool(_) => json_text(value),
+        other => Err(dsq_shared::error::operation_error(format!(
+            "{} is not valid in a tsv row",
+            other.type_name()
+        ))),
+    }
+}
+
+fn sh_word(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(format!("'{}'", s.replace('\'', "'\\''"))),
+        Value::Int(_) | Value::BigInt(_) | Value::Float(_) | Value::Bool(_) | Value::Null => {
+            json_text(value)
+        }
+        other => Err(dsq_shared::error::operation_error(format!(
+            "{} can not be escaped for shell",
+            other.type_name()
+        ))),
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '\'' => out.push_str("&#39;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn uri_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+fn decoded_text(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Apply the format `name` (`csv` for `@csv`) to a single value
+pub fn format_value(name: &str, value: &Value) -> Result<String> {
+    match name {
+        "text" => plain_text(value),
+        "json" => json_text(value),
+        "csv" => Ok(row_items(name, value)?
+            .iter()
+            .map(csv_field)
+            .collect::<Result<Vec<_>>>()?
+            .join(",")),
+        "tsv" => Ok(row_items(name, value)?
+            .iter()
+            .map(tsv_field)
+            .collect::<Result<Vec<_>>>()?
+            .join("\t")),
+        "html" => Ok(html_escape(&plain_text(value)?)),
+        "uri" => Ok(uri_escape(&plain_text(value)?)),
+        "sh" => match value {
+            Value::Array(items) => Ok(items
+                .iter()
+                .map(sh_word)
+                .collect::<Result<Vec<_>>>()?
+                .join(" ")),
+            other => sh_word(other),
+        },
+        "base64" => Ok(general_purpose::STANDARD.encode(plain_text(value)?)),
+        "base64d" => {
+            let text = plain_text(value)?;
+            general_purpose::STANDARD_NO_PAD
+                .decode(text.trim_end_matches('='))
+                .map(decoded_text)
+                .map_err(|_| {
+                    dsq_shared::error::operation_error(format!(
+                        "{} is not valid base64 data",
+                        json_text(value).unwrap_or(text)
+                    ))
+                })
+        }
+        "base32" => Ok(base32::encode(
+            base32::Alphabet::Rfc4648 { padding: true },
+            plain_text(value)?.as_bytes(),
+        )),
+        "base32d" => {
+            let text = plain_text(value)?;
+            base32::decode(base32::Alphabet::Rfc4648 { padding: true }, &text)
+                .map(decoded_text)
+                .ok_or_else(|| {
+                    dsq_shared::error::operation_error(format!(
+                        "{} is not valid base32 data",
+                        json_text(value).unwrap_or(text)
+                    ))
+                })
+        }
+        _ => Err(dsq_shared::error::operation_error(format!(
+            "{} is not a valid format",
+            name
+        ))),
+    }
+}
+
+/// Apply the format `name` to `value`, formatting Series element-wise and
+/// DataFrames row by row (each row as the array of its column values)
+pub fn format_any(name: &str, value: &Value) -> Result<Value> {
+    let element = |av: AnyValue<'_>| -> Result<Option<String>> {
+        match value_from_any_value(av) {
+            Some(Value::Null) | None => Ok(None),
+            Some(v) => format_value(name, &v).map(Some),
+        }
+    };
+    match value {
+        Value::Series(series) => {
+            let formatted = (0..series.len())
+                .map(|i| element(series.get(i).map_err(polars_error)?))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Value::Series(Series::new(series.name().clone(), formatted)))
+        }
+        Value::DataFrame(df) => {
+            let mut formatted = Vec::with_capacity(df.height());
+            for row in 0..df.height() {
+                let items = df
+                    .get_columns()
+                    .iter()
+                    .map(|column| {
+                        let av = column.get(row).map_err(polars_error)?;
+                        Ok(value_from_any_value(av).unwrap_or(Value::Null))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                formatted.push(format_value(name, &Value::Array(items))?);
+            }
+            Ok(Value::Series(Series::new(name.into(), formatted)))
+        }
+        Value::LazyFrame(lf) => {
+            let df = lf.clone().collect().map_err(polars_error)?;
+            format_any(name, &Value::DataFrame(df))
+        }
+        other => Ok(Value::String(format_value(name, other)?)),
+    }
+}
+
+fn polars_error(e: PolarsError) -> anyhow::Error {
+    dsq_shared::error::operation_error(format!("format() failed: {}", e))
+}
+
+pub fn builtin_format(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(dsq_shared::error::operation_error(
+            "format() expects 1 argument",
+        ));
+    }
+
+    match &args[1] {
+        Value::String(name) => format_any(name, &args[0]),
+        other => Err(dsq_shared::error::operation_error(format!(
+            "{} is not a valid format",
+            other.type_name()
+        ))),
+    }
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "format",
+        func: builtin_format,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_format_csv_and_tsv() {
+        let row = Value::Array(vec![
+            Value::Int(1),
+            s("a \"b\""),
+            Value::Null,
+            Value::Bool(true),
+        ]);
+        assert_eq!(
+            format_value("csv", &row).unwrap(),
+            "1,\"a \"\"b\"\"\",,true"
+        );
+        let row = Value::Array(vec![s("a\tb"), s("c\\d"), Value::Float(1.5)]);
+        assert_eq!(format_value("tsv", &row).unwrap(), "a\\tb\tc\\\\d\t1.5");
+        assert!(format_value("csv", &s("x")).is_err());
+    }
+
+    #[test]
+    fn test_format_escapes() {
+        assert_eq!(
+            format_value("html", &s("<a href='x'>&</a>")).unwrap(),
+            "&lt;a href=&#39;x&#39;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(
+            format_value("uri", &s("a b/ü~")).unwrap(),
+            "a%20b%2F%C3%BC~"
+        );
+        assert_eq!(format_value("sh", &s("it's")).unwrap(), "'it'\\''s'");
+        assert_eq!(
+            format_value("sh", &Value::Array(vec![s("a b"), Value::Int(2)])).unwrap(),
+            "'a b' 2"
+        );
+        assert_eq!(format_value("text", &Value::Int(3)).unwrap(), "3");
+        assert_eq!(format_value("json", &s("x")).unwrap(), "\"x\"");
+    }
+
+    #[test]
+    fn test_format_base64_and_base32() {
+        assert_eq!(format_value("base64", &s("hello")).unwrap(), "aGVsbG8=");
+        assert_eq!(format_value("base64d", &s("aGVsbG8=")).unwrap(), "hello");
+        assert_eq!(format_value("base64d", &s("aGVsbG8")).unwrap(), "hello");
+        assert_eq!(format_value("base32", &s("hi")).unwrap(), "NBUQ====");
+        assert_eq!(format_value("base32d", &s("NBUQ====")).unwrap(), "hi");
+    }
+
+    #[test]
+    fn test_format_unknown() {
+        let err = format_value("nope", &s("x")).unwrap_err();
+        assert!(err.to_string().contains("nope is not a valid format"));
+    }
+
+    #[test]
+    fn test_format_dataframe_rows() {
+        let df = df! {
+            "name" => ["Alice", "Bob"],
+            "age" => [30i64, 25],
+        }
+        .unwrap();
+        let result = builtin_format(&[Value::DataFrame(df), s("csv")]).unwrap();
+        match result {
+            Value::Series(series) => {
+                let values: Vec<_> = series.str().unwrap().into_iter().collect();
+                assert_eq!(values, vec![Some("\"Alice\",30"), Some("\"Bob\",25")]);
+            }
+            other => panic!("expected a series, got {:?}", other),
+        }
+    }
+}
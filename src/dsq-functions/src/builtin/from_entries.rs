@@ -0,0 +1,96 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use indexmap::IndexMap;
+use inventory;
+
+/// Field names accepted for an entry's key and value, in order of preference
+const KEY_FIELDS: &[&str] = &["key", "k", "name", "Name", "Key", "K"];
+const VALUE_FIELDS: &[&str] = &["value", "v", "Value", "V"];
+
+/// The first of `fields` present in `entry` (null when none is)
+fn entry_field<'a>(entry: &'a IndexMap<String, Value>, fields: &[&str]) -> Option<&'a Value> {
+    fields.iter().find_map(|field| entry.get(*field))
+}
+
+pub fn builtin_from_entries(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(dsq_shared::error::operation_error(
+            "from_entries() expects 1 argument",
+        ));
+    }
+
+    let Value::Array(entries) = &args[0] else {
+        return Err(dsq_shared::error::operation_error(format!(
+            "from_entries() requires an array, got {}",
+            args[0].type_name()
+        )));
+    };
+
+    let mut obj = IndexMap::new();
+    for item in entries {
+        let Value::Object(entry) = item else {
+            return Err(dsq_shared::error::operation_error(format!(
+                "from_entries() entries must be objects, got {}",
+                item.type_name()
+            )));
+        };
+        let key = match entry_field(entry, KEY_FIELDS) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Int(i)) => i.to_string(),
+            Some(Value::Float(f)) => f.to_string(),
+            Some(Value::Bool(b)) => b.to_string(),
+            Some(Value::Null) | None => "null".to_string(),
+            Some(other) => {
+                return Err(dsq_shared::error::operation_error(format!(
+                    "Cannot use {} as object key",
+                    other.type_name()
+                )))
+            }
+        };
+        let value = entry_field(entry, VALUE_FIELDS)
+            .cloned()
+            .unwrap_or(Value::Null);
+        obj.insert(key, value);
+    }
+    Ok(Value::Object(obj))
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "from_entries",
+        func: builtin_from_entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_entries() {
+        let entries = Value::Array(vec![
+            Value::Object(IndexMap::from([
+                ("key".to_string(), Value::String("a".to_string())),
+                ("value".to_string(), Value::Int(1)),
+            ])),
+            Value::Object(IndexMap::from([
+                ("name".to_string(), Value::Int(2)),
+                ("v".to_string(), Value::Bool(true)),
+            ])),
+        ]);
+        let result = builtin_from_entries(&[entries]).unwrap();
+        assert_eq!(
+            result,
+            Value::Object(IndexMap::from([
+                ("a".to_string(), Value::Int(1)),
+                ("2".to_string(), Value::Bool(true)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_from_entries_invalid() {
+        assert!(builtin_from_entries(&[Value::Int(1)]).is_err());
+        assert!(builtin_from_entries(&[Value::Array(vec![Value::Int(1)])]).is_err());
+    }
+}
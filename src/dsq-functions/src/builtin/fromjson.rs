@@ -1,9 +1,9 @@
 use crate::FunctionRegistration;
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use serde_json;
-use std::collections::HashMap;
 
 inventory::submit! {
     FunctionRegistration {
@@ -61,7 +61,7 @@ pub fn builtin_fromjson(args: &[Value]) -> Result<Value> {
                             Ok(Value::Array(values?))
                         }
                         serde_json::Value::Object(obj) => {
-                            let mut map = HashMap::new();
+                            let mut map = IndexMap::new();
                             for (k, v) in obj {
                                 let val = match v {
                                     serde_json::Value::Null => Value::Null,
@@ -149,7 +149,7 @@ mod tests {
     fn test_builtin_fromjson_object() {
         let result =
             builtin_fromjson(&[Value::String("{\"key\": \"value\"}".to_string())]).unwrap();
-        let mut expected = HashMap::new();
+        let mut expected = IndexMap::new();
         expected.insert("key".to_string(), Value::String("value".to_string()));
         assert_eq!(result, Value::Object(expected));
     }
@@ -160,7 +160,7 @@ mod tests {
             "{\"name\": \"John\", \"age\": 30, \"active\": true}".to_string(),
         )])
         .unwrap();
-        let mut expected = HashMap::new();
+        let mut expected = IndexMap::new();
         expected.insert("name".to_string(), Value::String("John".to_string()));
         expected.insert("age".to_string(), Value::Int(30));
         expected.insert("active".to_string(), Value::Bool(true));
@@ -0,0 +1,150 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+
+/// The `[start, end)` range of a slice key (`{"start": s, "end": e}`) on a
+/// sequence of length `len`, with negative and missing bounds resolved
+pub fn slice_range(key: &Value, len: usize) -> Option<(usize, usize)> {
+    let Value::Object(obj) = key else {
+        return None;
+    };
+    let bound = |name: &str, default: i64| match obj.get(name) {
+        None | Some(Value::Null) => Some(default),
+        Some(Value::Int(i)) => Some(*i),
+        Some(Value::Float(f)) => Some(f.floor() as i64),
+        _ => None,
+    };
+    let len = len as i64;
+    let resolve = |i: i64| (if i < 0 { len + i } else { i }).clamp(0, len) as usize;
+    let start = resolve(bound("start", 0)?);
+    let end = resolve(bound("end", len)?);
+    Some((start, end.max(start)))
+}
+
+/// Describe a path component for error messages
+pub fn describe_key(key: &Value) -> String {
+    match key {
+        Value::String(s) => format!("\"{}\"", s),
+        Value::Int(i) => i.to_string(),
+        other => other.type_name().to_string(),
+    }
+}
+
+/// The child of `value` at the path component `key`
+///
+/// Missing keys and indices, and any key of null, give null.
+pub fn get_key(value: &Value, key: &Value) -> Result<Value> {
+    match (value, key) {
+        (Value::Null, _) => Ok(Value::Null),
+        (Value::Object(obj), Value::String(k)) => Ok(obj.get(k).cloned().unwrap_or(Value::Null)),
+        (Value::DataFrame(_) | Value::LazyFrame(_), Value::String(k)) => value.field(k),
+        (Value::Array(_) | Value::DataFrame(_) | Value::LazyFrame(_), Value::Int(i)) => {
+            value.index(*i)
+        }
+        (Value::Series(series), Value::Int(i)) => {
+            let len = series.len() as i64;
+            let index = if *i < 0 { len + i } else { *i };
+            if index < 0 || index >= len {
+                return Ok(Value::Null);
+            }
+            let av = series.get(index as usize).map_err(|e| {
+                dsq_shared::error::operation_error(format!("Failed to get value: {}", e))
+            })?;
+            Ok(dsq_shared::value::value_from_any_value(av.clone())
+                .unwrap_or_else(|| Value::String(av.to_string())))
+        }
+        (Value::Array(arr), Value::Object(_)) => match slice_range(key, arr.len()) {
+            Some((start, end)) => Ok(Value::Array(arr[start..end].to_vec())),
+            None => Err(dsq_shared::error::operation_error(
+                "Array slice bounds must be numbers",
+            )),
+        },
+        _ => Err(dsq_shared::error::operation_error(format!(
+            "Cannot index {} with {}",
+            value.type_name(),
+            describe_key(key)
+        ))),
+    }
+}
+
+/// The value at `path` inside `value`, or null where the path is missing
+pub fn get_path(value: &Value, path: &[Value]) -> Result<Value> {
+    let mut current = value.clone();
+    for key in path {
+        if current == Value::Null {
+            break;
+        }
+        current = get_key(&current, key)?;
+    }
+    Ok(current)
+}
+
+/// The components of a path argument
+pub fn path_components<'a>(path: &'a Value, function: &str) -> Result<&'a [Value]> {
+    match path {
+        Value::Array(components) => Ok(components),
+        _ => Err(dsq_shared::error::operation_error(format!(
+            "{}() path must be an array",
+            function
+        ))),
+    }
+}
+
+pub fn builtin_getpath(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(dsq_shared::error::operation_error(
+            "getpath() expects 2 arguments",
+        ));
+    }
+
+    get_path(&args[0], path_components(&args[1], "getpath")?)
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "getpath",
+        func: builtin_getpath,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    fn sample() -> Value {
+        Value::Object(IndexMap::from([(
+            "a".to_string(),
+            Value::Array(vec![Value::Object(IndexMap::from([(
+                "b".to_string(),
+                Value::Int(1),
+            )]))]),
+        )]))
+    }
+
+    #[test]
+    fn test_getpath() {
+        let path = Value::Array(vec![
+            Value::String("a".to_string()),
+            Value::Int(0),
+            Value::String("b".to_string()),
+        ]);
+        assert_eq!(builtin_getpath(&[sample(), path]).unwrap(), Value::Int(1));
+    }
+
+    #[test]
+    fn test_getpath_missing() {
+        let path = Value::Array(vec![
+            Value::String("x".to_string()),
+            Value::String("y".to_string()),
+        ]);
+        assert_eq!(builtin_getpath(&[sample(), path]).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_getpath_invalid() {
+        let path = Value::Array(vec![Value::String("a".to_string()), Value::Int(0)]);
+        assert!(builtin_getpath(&[Value::Int(1), path]).is_err());
+        assert!(builtin_getpath(&[sample(), Value::Int(0)]).is_err());
+    }
+}
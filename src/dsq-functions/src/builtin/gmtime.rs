@@ -1,9 +1,9 @@
 use chrono::{Datelike, Timelike};
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 pub fn builtin_gmtime(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
@@ -13,9 +13,13 @@ pub fn builtin_gmtime(args: &[Value]) -> Result<Value> {
     }
 
     match &args[0] {
-        Value::Int(_) | Value::Float(_) | Value::String(_) => {
+        Value::Int(_)
+        | Value::Float(_)
+        | Value::String(_)
+        | Value::Date(_)
+        | Value::Datetime { .. } => {
             let dt = crate::extract_timestamp(&args[0])?;
-            let mut result = HashMap::new();
+            let mut result = IndexMap::new();
             result.insert("year".to_string(), Value::Int(dt.year() as i64));
             result.insert("month".to_string(), Value::Int(dt.month() as i64));
             result.insert("day".to_string(), Value::Int(dt.day() as i64));
@@ -33,7 +37,14 @@ pub fn builtin_gmtime(args: &[Value]) -> Result<Value> {
             let gmtimes: Result<Vec<Value>> = arr
                 .iter()
                 .map(|v| {
-                    if matches!(v, Value::Int(_) | Value::Float(_) | Value::String(_)) {
+                    if matches!(
+                        v,
+                        Value::Int(_)
+                            | Value::Float(_)
+                            | Value::String(_)
+                            | Value::Date(_)
+                            | Value::Datetime { .. }
+                    ) {
                         builtin_gmtime(std::slice::from_ref(v))
                     } else {
                         Ok(Value::Null)
@@ -55,7 +66,11 @@ pub fn builtin_gmtime(args: &[Value]) -> Result<Value> {
                                         crate::value_from_any_value(val).unwrap_or(Value::Null);
                                     if matches!(
                                         value,
-                                        Value::Int(_) | Value::Float(_) | Value::String(_)
+                                        Value::Int(_)
+                                            | Value::Float(_)
+                                            | Value::String(_)
+                                            | Value::Date(_)
+                                            | Value::Datetime { .. }
                                     ) {
                                         match builtin_gmtime(&[value]) {
                                             Ok(gmtime_obj) => {
@@ -100,7 +115,14 @@ pub fn builtin_gmtime(args: &[Value]) -> Result<Value> {
                 match series.get(i) {
                     Ok(val) => {
                         let value = crate::value_from_any_value(val).unwrap_or(Value::Null);
-                        if matches!(value, Value::Int(_) | Value::Float(_) | Value::String(_)) {
+                        if matches!(
+                            value,
+                            Value::Int(_)
+                                | Value::Float(_)
+                                | Value::String(_)
+                                | Value::Date(_)
+                                | Value::Datetime { .. }
+                        ) {
                             match builtin_gmtime(&[value]) {
                                 Ok(gmtime_obj) => {
                                     gmtime_values.push(format!("{:?}", gmtime_obj));
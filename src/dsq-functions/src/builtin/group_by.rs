@@ -1,16 +1,16 @@
 use dsq_shared::error::operation_error;
 use dsq_shared::value::{value_from_any_value, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 use crate::compare_values_for_sorting;
 
-fn dataframe_to_objects(df: &DataFrame) -> Vec<HashMap<String, Value>> {
+fn dataframe_to_objects(df: &DataFrame) -> Vec<IndexMap<String, Value>> {
     let mut objects = Vec::new();
     for i in 0..df.height() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         for col_name in df.get_column_names() {
             if let Ok(series) = df.column(col_name) {
                 if let Ok(val) = series.get(i) {
@@ -40,7 +40,7 @@ pub fn builtin_group_by(args: &[Value]) -> Result<Value> {
         }
         (Value::Array(arr), Value::Array(keys)) if arr.len() == keys.len() => {
             // Group array by keys
-            let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+            let mut groups: IndexMap<String, Vec<Value>> = IndexMap::new();
             for (item, key) in arr.iter().zip(keys.iter()) {
                 let key_str = match key {
                     Value::String(s) => s.clone(),
@@ -96,7 +96,7 @@ pub fn builtin_group_by(args: &[Value]) -> Result<Value> {
         }
         (Value::Array(arr), Value::String(field)) => {
             // Group array of objects by field
-            let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+            let mut groups: IndexMap<String, Vec<Value>> = IndexMap::new();
             for item in arr {
                 if let Value::Object(obj) = item {
                     let key = if let Some(Value::String(s)) = obj.get(field) {
@@ -333,8 +333,8 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
+    use indexmap::IndexMap;
     use polars::prelude::{DataFrame, NamedFrom, Series};
-    use std::collections::HashMap;
 
     #[test]
     fn test_builtin_group_by_with_dataframe() {
@@ -390,13 +390,13 @@ mod tests {
         }
 
         // Test array of objects by field
-        let mut obj1 = HashMap::new();
+        let mut obj1 = IndexMap::new();
         obj1.insert("name".to_string(), Value::String("Alice".to_string()));
         obj1.insert("group".to_string(), Value::String("A".to_string()));
-        let mut obj2 = HashMap::new();
+        let mut obj2 = IndexMap::new();
         obj2.insert("name".to_string(), Value::String("Bob".to_string()));
         obj2.insert("group".to_string(), Value::String("B".to_string()));
-        let mut obj3 = HashMap::new();
+        let mut obj3 = IndexMap::new();
         obj3.insert("name".to_string(), Value::String("Charlie".to_string()));
         obj3.insert("group".to_string(), Value::String("A".to_string()));
         let arr = Value::Array(vec![
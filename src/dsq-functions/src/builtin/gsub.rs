@@ -0,0 +1,49 @@
+use crate::builtin::sub::substitute_args;
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+
+pub fn builtin_gsub(args: &[Value]) -> Result<Value> {
+    substitute_args("gsub", args, true)
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "gsub",
+        func: builtin_gsub,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_gsub_string() {
+        assert_eq!(
+            builtin_gsub(&[s("a.b.c"), s("\\."), s("/")]).unwrap(),
+            s("a/b/c")
+        );
+        assert_eq!(builtin_gsub(&[s("ab"), s(""), s("-")]).unwrap(), s("-a-b-"));
+        assert_eq!(
+            builtin_gsub(&[s("ab"), s("x*"), s("-"), s("n")]).unwrap(),
+            s("ab")
+        );
+    }
+
+    #[test]
+    fn test_gsub_series() {
+        let series = Series::new("s".into(), [Some("1,2,3"), None]);
+        let result = builtin_gsub(&[Value::Series(series), s(","), s(" ")]).unwrap();
+        let Value::Series(result) = result else {
+            panic!("expected a series");
+        };
+        let values: Vec<_> = result.str().unwrap().into_iter().collect();
+        assert_eq!(values, vec![Some("1 2 3"), None]);
+    }
+}
@@ -35,11 +35,11 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_has_object_true() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key1".to_string(), Value::String("value1".to_string()));
         obj.insert("key2".to_string(), Value::Int(42));
         let result = builtin_has(&[Value::Object(obj), Value::String("key1".to_string())]).unwrap();
@@ -48,7 +48,7 @@ mod tests {
 
     #[test]
     fn test_has_object_false() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key1".to_string(), Value::String("value1".to_string()));
         let result = builtin_has(&[Value::Object(obj), Value::String("key2".to_string())]).unwrap();
         assert_eq!(result, Value::Bool(false));
@@ -101,14 +101,14 @@ mod tests {
 
     #[test]
     fn test_has_empty_object() {
-        let obj = HashMap::new();
+        let obj = IndexMap::new();
         let result = builtin_has(&[Value::Object(obj), Value::String("key".to_string())]).unwrap();
         assert_eq!(result, Value::Bool(false));
     }
 
     #[test]
     fn test_has_key_not_string() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key1".to_string(), Value::String("value1".to_string()));
         let result = builtin_has(&[Value::Object(obj), Value::Int(123)]);
         assert!(result.is_err());
@@ -1,8 +1,8 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 pub fn builtin_histogram(args: &[Value]) -> Result<Value> {
     if args.is_empty() || args.len() > 2 {
@@ -119,7 +119,7 @@ pub fn builtin_histogram(args: &[Value]) -> Result<Value> {
     };
 
     if values.is_empty() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("counts".to_string(), Value::Array(vec![]));
         obj.insert("bins".to_string(), Value::Array(vec![]));
         return Ok(Value::Object(obj));
@@ -144,7 +144,7 @@ pub fn builtin_histogram(args: &[Value]) -> Result<Value> {
         .collect();
     let counts_values: Vec<Value> = counts.into_iter().map(|c| Value::Int(c as i64)).collect();
 
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
     obj.insert("counts".to_string(), Value::Array(counts_values));
     obj.insert("bins".to_string(), Value::Array(bin_edges));
     Ok(Value::Object(obj))
@@ -39,9 +39,51 @@ pub fn builtin_keys(args: &[Value]) -> Result<Value> {
     }
 }
 
+/// `keys_unsorted`: the keys of an object in insertion order
+pub fn builtin_keys_unsorted(args: &[Value]) -> Result<Value> {
+    match args {
+        [Value::Object(obj)] => Ok(Value::Array(
+            obj.keys().cloned().map(Value::String).collect(),
+        )),
+        [other] => builtin_keys(std::slice::from_ref(other)),
+        _ => Err(dsq_shared::error::operation_error(
+            "keys_unsorted() expects 1 argument",
+        )),
+    }
+}
+
 inventory::submit! {
     crate::FunctionRegistration {
         name: "keys",
         func: builtin_keys,
     }
 }
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "keys_unsorted",
+        func: builtin_keys_unsorted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_keys_sorted_and_unsorted() {
+        let obj = Value::Object(IndexMap::from([
+            ("b".to_string(), Value::Int(1)),
+            ("a".to_string(), Value::Int(2)),
+        ]));
+        let strings = |keys: &[&str]| {
+            Value::Array(keys.iter().map(|k| Value::String(k.to_string())).collect())
+        };
+        assert_eq!(
+            builtin_keys(std::slice::from_ref(&obj)).unwrap(),
+            strings(&["a", "b"])
+        );
+        assert_eq!(builtin_keys_unsorted(&[obj]).unwrap(), strings(&["b", "a"]));
+    }
+}
@@ -1,7 +1,7 @@
 use dsq_shared::value::{value_from_any_value, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
-use std::collections::HashMap;
 
 pub fn builtin_last(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
@@ -31,7 +31,7 @@ pub fn builtin_last(args: &[Value]) -> Result<Value> {
             } else {
                 // Return last row as object
                 let last_idx = df.height() - 1;
-                let mut row_obj = HashMap::new();
+                let mut row_obj = IndexMap::new();
                 for col_name in df.get_column_names() {
                     if let Ok(series) = df.column(col_name) {
                         if let Ok(val) = series.get(last_idx) {
@@ -0,0 +1,67 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+use std::ops::ControlFlow;
+
+use super::paths::for_each_path;
+
+/// Whether `value` has no children for `paths` to descend into
+pub fn is_leaf(value: &Value) -> bool {
+    !matches!(
+        value,
+        Value::Array(_)
+            | Value::Object(_)
+            | Value::DataFrame(_)
+            | Value::LazyFrame(_)
+            | Value::Series(_)
+    )
+}
+
+pub fn builtin_leaf_paths(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(dsq_shared::error::operation_error(
+            "leaf_paths() expects 1 argument",
+        ));
+    }
+
+    let mut paths = Vec::new();
+    for_each_path(&args[0], &mut |path, value| {
+        if is_leaf(value) {
+            paths.push(Value::Array(path.to_vec()));
+        }
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(Value::Array(paths))
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "leaf_paths",
+        func: builtin_leaf_paths,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_leaf_paths() {
+        let value = Value::Object(IndexMap::from([
+            ("a".to_string(), Value::Int(1)),
+            (
+                "b".to_string(),
+                Value::Array(vec![Value::String("x".to_string())]),
+            ),
+        ]));
+        let result = builtin_leaf_paths(&[value]).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Array(vec![Value::String("a".to_string())]),
+                Value::Array(vec![Value::String("b".to_string()), Value::Int(0)]),
+            ])
+        );
+    }
+}
@@ -75,7 +75,7 @@ mod tests {
 
     #[test]
     fn test_length_object() {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         let result = builtin_length(&[Value::Object(obj)]).unwrap();
@@ -84,7 +84,7 @@ mod tests {
 
     #[test]
     fn test_length_empty_object() {
-        let obj = std::collections::HashMap::new();
+        let obj = indexmap::IndexMap::new();
         let result = builtin_length(&[Value::Object(obj)]).unwrap();
         assert_eq!(result, Value::Int(0));
     }
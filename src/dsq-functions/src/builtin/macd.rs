@@ -72,7 +72,7 @@ pub fn builtin_macd(args: &[Value]) -> Result<Value> {
                 .iter()
                 .map(|&price| {
                     let output = macd_indicator.next(price);
-                    let mut obj = std::collections::HashMap::new();
+                    let mut obj = indexmap::IndexMap::new();
                     obj.insert("macd".to_string(), Value::Float(output.macd));
                     obj.insert("signal".to_string(), Value::Float(output.signal));
                     obj.insert("histogram".to_string(), Value::Float(output.histogram));
@@ -2,9 +2,9 @@ use crate::FunctionRegistration;
 use dsq_shared::error::operation_error;
 use dsq_shared::value::{value_from_any_value, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 pub fn builtin_map(args: &[Value]) -> Result<Value> {
     match args.len() {
@@ -30,7 +30,7 @@ pub fn builtin_map(args: &[Value]) -> Result<Value> {
                     let mut result = Vec::new();
                     for item in arr {
                         if let Value::Object(obj) = item {
-                            let mut new_obj = HashMap::new();
+                            let mut new_obj = IndexMap::new();
                             for key in template.keys() {
                                 if let Some(val) = obj.get(key) {
                                     new_obj.insert(key.clone(), val.clone());
@@ -103,7 +103,7 @@ inventory::submit!(FunctionRegistration {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_map_single_arg() {
@@ -114,11 +114,11 @@ mod tests {
 
     #[test]
     fn test_map_array_with_string_field() {
-        let mut obj1 = HashMap::new();
+        let mut obj1 = IndexMap::new();
         obj1.insert("name".to_string(), Value::String("Alice".to_string()));
         obj1.insert("age".to_string(), Value::Int(25));
 
-        let mut obj2 = HashMap::new();
+        let mut obj2 = IndexMap::new();
         obj2.insert("name".to_string(), Value::String("Bob".to_string()));
         obj2.insert("age".to_string(), Value::Int(30));
 
@@ -135,10 +135,10 @@ mod tests {
 
     #[test]
     fn test_map_array_with_string_field_missing() {
-        let mut obj1 = HashMap::new();
+        let mut obj1 = IndexMap::new();
         obj1.insert("name".to_string(), Value::String("Alice".to_string()));
 
-        let mut obj2 = HashMap::new();
+        let mut obj2 = IndexMap::new();
         obj2.insert("other".to_string(), Value::String("Bob".to_string()));
 
         let arr = vec![Value::Object(obj1), Value::Object(obj2)];
@@ -151,19 +151,19 @@ mod tests {
 
     #[test]
     fn test_map_array_with_object_template() {
-        let mut obj1 = HashMap::new();
+        let mut obj1 = IndexMap::new();
         obj1.insert("name".to_string(), Value::String("Alice".to_string()));
         obj1.insert("age".to_string(), Value::Int(25));
         obj1.insert("city".to_string(), Value::String("NYC".to_string()));
 
-        let mut obj2 = HashMap::new();
+        let mut obj2 = IndexMap::new();
         obj2.insert("name".to_string(), Value::String("Bob".to_string()));
         obj2.insert("age".to_string(), Value::Int(30));
         obj2.insert("city".to_string(), Value::String("LA".to_string()));
 
         let arr = vec![Value::Object(obj1), Value::Object(obj2)];
 
-        let mut template = HashMap::new();
+        let mut template = IndexMap::new();
         template.insert("name".to_string(), Value::Null);
         template.insert("age".to_string(), Value::Null);
 
@@ -223,7 +223,7 @@ mod tests {
         let cities = Series::new(PlSmallStr::from("city"), &["NYC", "LA"]).into();
         let df = DataFrame::new(vec![names, ages, cities]).unwrap();
 
-        let mut template = HashMap::new();
+        let mut template = IndexMap::new();
         template.insert("name".to_string(), Value::Null);
         template.insert("age".to_string(), Value::Null);
 
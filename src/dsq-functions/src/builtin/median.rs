@@ -1,7 +1,7 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
-use std::collections::HashMap;
 
 pub fn builtin_median(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
@@ -32,7 +32,7 @@ pub fn builtin_median(args: &[Value]) -> Result<Value> {
             }
         }
         Value::DataFrame(df) => {
-            let mut medians = HashMap::new();
+            let mut medians = IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(column) = df.column(col_name) {
                     let series = column.as_materialized_series();
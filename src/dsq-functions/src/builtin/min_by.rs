@@ -1,8 +1,8 @@
 use dsq_shared::value::{value_from_any_value, Value};
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 inventory::submit! {
     crate::FunctionRegistration {
@@ -99,7 +99,7 @@ pub fn builtin_min_by(args: &[Value]) -> Result<Value> {
                         }
                     }
                     // Return the row as object
-                    let mut row_obj = HashMap::new();
+                    let mut row_obj = IndexMap::new();
                     for col_name in df.get_column_names() {
                         if let Ok(s) = df.column(col_name) {
                             if let Ok(val) = s.get(min_idx) {
@@ -12,16 +12,27 @@ pub fn builtin_minute(args: &[Value]) -> Result<Value> {
     }
 
     match &args[0] {
-        Value::Int(_) | Value::Float(_) | Value::String(_) => {
-            let dt = crate::extract_timestamp(&args[0])?;
+        Value::Int(_)
+        | Value::Float(_)
+        | Value::String(_)
+        | Value::Date(_)
+        | Value::Datetime { .. } => {
+            let dt = crate::extract_local_timestamp(&args[0])?;
             Ok(Value::Int(dt.minute() as i64))
         }
         Value::Array(arr) => {
             let minutes: Result<Vec<Value>> = arr
                 .iter()
                 .map(|v| {
-                    if matches!(v, Value::Int(_) | Value::Float(_) | Value::String(_)) {
-                        let dt = crate::extract_timestamp(v)?;
+                    if matches!(
+                        v,
+                        Value::Int(_)
+                            | Value::Float(_)
+                            | Value::String(_)
+                            | Value::Date(_)
+                            | Value::Datetime { .. }
+                    ) {
+                        let dt = crate::extract_local_timestamp(v)?;
                         Ok(Value::Int(dt.minute() as i64))
                     } else {
                         Ok(Value::Null)
@@ -43,9 +54,13 @@ pub fn builtin_minute(args: &[Value]) -> Result<Value> {
                                         crate::value_from_any_value(val).unwrap_or(Value::Null);
                                     if matches!(
                                         value,
-                                        Value::Int(_) | Value::Float(_) | Value::String(_)
+                                        Value::Int(_)
+                                            | Value::Float(_)
+                                            | Value::String(_)
+                                            | Value::Date(_)
+                                            | Value::Datetime { .. }
                                     ) {
-                                        match crate::extract_timestamp(&value) {
+                                        match crate::extract_local_timestamp(&value) {
                                             Ok(dt) => {
                                                 minute_values.push(dt.minute() as i64);
                                             }
@@ -86,8 +101,15 @@ pub fn builtin_minute(args: &[Value]) -> Result<Value> {
                     match series.get(i) {
                         Ok(val) => {
                             let value = crate::value_from_any_value(val).unwrap_or(Value::Null);
-                            if matches!(value, Value::Int(_) | Value::Float(_) | Value::String(_)) {
-                                match crate::extract_timestamp(&value) {
+                            if matches!(
+                                value,
+                                Value::Int(_)
+                                    | Value::Float(_)
+                                    | Value::String(_)
+                                    | Value::Date(_)
+                                    | Value::Datetime { .. }
+                            ) {
+                                match crate::extract_local_timestamp(&value) {
                                     Ok(dt) => {
                                         minute_values.push(dt.minute() as i64);
                                     }
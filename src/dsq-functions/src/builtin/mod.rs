@@ -23,6 +23,7 @@ pub mod bbands;
 pub mod beta;
 pub mod buffer;
 pub mod camel_case;
+pub mod capture;
 pub mod cci;
 pub mod ceil;
 pub mod coalesce;
@@ -37,6 +38,7 @@ pub mod cut;
 pub mod date_diff;
 pub mod day;
 pub mod del;
+pub mod delpaths;
 pub mod dos2unix;
 pub mod empty;
 pub mod end_of_month;
@@ -48,13 +50,17 @@ pub mod filter;
 pub mod first;
 pub mod flatten;
 pub mod floor;
+pub mod format;
+pub mod from_entries;
 pub mod fromjson;
 pub mod generate_sequence;
 pub mod generate_uuidv4;
 pub mod generate_uuidv7;
+pub mod getpath;
 pub mod gmtime;
 pub mod group_by;
 pub mod group_concat;
+pub mod gsub;
 pub mod has;
 pub mod head;
 pub mod histogram;
@@ -66,6 +72,7 @@ pub mod is_valid_utf8;
 pub mod join;
 pub mod keys;
 pub mod last;
+pub mod leaf_paths;
 pub mod least_frequent;
 pub mod length;
 pub mod localtime;
@@ -90,6 +97,7 @@ pub mod mround;
 pub mod now;
 pub mod obv;
 pub mod parabolic_sar;
+pub mod paths;
 pub mod percentile;
 pub mod pi;
 pub mod topk;
@@ -102,6 +110,8 @@ pub mod rand;
 pub mod randarray;
 pub mod randbetween;
 pub mod range;
+pub mod recurse;
+pub mod regex_match;
 pub mod repeat;
 pub mod replace;
 pub mod reverse;
@@ -111,8 +121,10 @@ pub mod rounddown;
 pub mod roundup;
 pub mod rsi;
 pub mod rstrip;
+pub mod scan;
 pub mod second;
 pub mod select;
+pub mod setpath;
 pub mod sha1;
 pub mod sha512;
 pub mod shape;
@@ -125,6 +137,7 @@ pub mod sort_by;
 pub mod sortino_ratio;
 pub mod spaces_to_tabs;
 pub mod split;
+pub mod splits;
 pub mod sqrt;
 pub mod start_of_month;
 pub mod start_of_week;
@@ -137,15 +150,18 @@ pub mod stoch_k;
 pub mod strflocaltime;
 pub mod strftime;
 pub mod strptime;
+pub mod sub;
 pub mod sum;
 pub mod systime;
 pub mod systime_int;
 pub mod tabs_to_spaces;
 pub mod tail;
 pub mod tan;
+pub mod test;
 pub mod time_series_range;
 pub mod titlecase;
 pub mod to_ascii;
+pub mod to_entries;
 pub mod to_valid_utf8;
 pub mod today;
 pub mod tojson;
@@ -2,10 +2,10 @@ use dsq_shared::{
     value::{value_from_any_value, Value},
     Result,
 };
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
 use serde_json;
-use std::collections::HashMap;
 
 use crate::FunctionRegistration;
 
@@ -40,7 +40,7 @@ pub fn builtin_most_frequent(args: &[Value]) -> Result<Value> {
             if arr.is_empty() {
                 return Ok(Value::Null);
             }
-            let mut counts: HashMap<String, (Value, usize)> = HashMap::new();
+            let mut counts: IndexMap<String, (Value, usize)> = IndexMap::new();
             for val in arr {
                 let key = serde_json::to_string(val).unwrap_or_default();
                 let entry = counts.entry(key).or_insert_with(|| (val.clone(), 0));
@@ -70,9 +70,9 @@ pub fn builtin_most_frequent(args: &[Value]) -> Result<Value> {
             })?;
 
             // Track counts and maintain insertion order
-            let mut counts: HashMap<String, usize> = HashMap::new();
+            let mut counts: IndexMap<String, usize> = IndexMap::new();
             let mut value_order: Vec<Value> = Vec::new();
-            let mut seen: HashMap<String, bool> = HashMap::new();
+            let mut seen: IndexMap<String, bool> = IndexMap::new();
 
             for i in 0..series.len() {
                 if let Ok(val) = series.get(i) {
@@ -106,7 +106,7 @@ pub fn builtin_most_frequent(args: &[Value]) -> Result<Value> {
             if series.is_empty() {
                 return Ok(Value::Null);
             }
-            let mut counts: HashMap<String, (Value, usize)> = HashMap::new();
+            let mut counts: IndexMap<String, (Value, usize)> = IndexMap::new();
             for i in 0..series.len() {
                 if let Ok(val) = series.get(i) {
                     let value = value_from_any_value(val).unwrap_or(Value::Null);
@@ -0,0 +1,96 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+use std::ops::ControlFlow;
+
+use super::recurse::for_each_child;
+
+/// Call `f` with every path below `value` (excluding the empty path) and the
+/// value found there, parents before their children, until it returns
+/// `ControlFlow::Break`
+pub fn for_each_path(
+    value: &Value,
+    f: &mut dyn FnMut(&[Value], &Value) -> Result<ControlFlow<()>>,
+) -> Result<ControlFlow<()>> {
+    fn walk(
+        value: &Value,
+        prefix: &mut Vec<Value>,
+        f: &mut dyn FnMut(&[Value], &Value) -> Result<ControlFlow<()>>,
+    ) -> Result<ControlFlow<()>> {
+        for_each_child(value, &mut |key, child| {
+            prefix.push(key);
+            let mut flow = f(prefix, child)?;
+            if flow.is_continue() {
+                flow = walk(child, prefix, f)?;
+            }
+            prefix.pop();
+            Ok(flow)
+        })
+    }
+
+    walk(value, &mut Vec::new(), f)
+}
+
+/// Every path below `value` (excluding the empty path) paired with the value
+/// found there, parents before their children
+pub fn value_paths(value: &Value) -> Result<Vec<(Vec<Value>, Value)>> {
+    let mut out = Vec::new();
+    for_each_path(value, &mut |path, item| {
+        out.push((path.to_vec(), item.clone()));
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(out)
+}
+
+pub fn builtin_paths(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(dsq_shared::error::operation_error(
+            "paths() expects 1 argument",
+        ));
+    }
+
+    let mut paths = Vec::new();
+    for_each_path(&args[0], &mut |path, _| {
+        paths.push(Value::Array(path.to_vec()));
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(Value::Array(paths))
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "paths",
+        func: builtin_paths,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_paths_nested() {
+        let value = Value::Object(IndexMap::from([
+            ("a".to_string(), Value::Int(1)),
+            ("b".to_string(), Value::Array(vec![Value::Null])),
+        ]));
+        let result = builtin_paths(&[value]).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Array(vec![Value::String("a".to_string())]),
+                Value::Array(vec![Value::String("b".to_string())]),
+                Value::Array(vec![Value::String("b".to_string()), Value::Int(0)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_paths_scalar() {
+        assert_eq!(
+            builtin_paths(&[Value::Int(1)]).unwrap(),
+            Value::Array(vec![])
+        );
+    }
+}
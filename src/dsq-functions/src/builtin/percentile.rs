@@ -1,8 +1,8 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 pub fn builtin_percentile(args: &[Value]) -> Result<Value> {
     if args.len() != 2 {
@@ -49,7 +49,7 @@ pub fn builtin_percentile(args: &[Value]) -> Result<Value> {
             Ok(Value::Float(values[idx]))
         }
         Value::DataFrame(df) => {
-            let mut percentiles = HashMap::new();
+            let mut percentiles = IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(column) = df.column(col_name) {
                     let series = column.as_materialized_series();
@@ -2,7 +2,7 @@ use dsq_shared::Result;
 use dsq_shared::value::{Value, value_from_any_value};
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 pub fn builtin_pivot(args: &[Value]) -> Result<Value> {
     if args.len() < 4 || args.len() > 5 {
@@ -134,7 +134,7 @@ pub fn builtin_pivot(args: &[Value]) -> Result<Value> {
         let mut aggregated_values = Vec::new();
 
         // Group by index columns and aggregate for this pivot value
-        let mut groups: HashMap<Vec<Value>, Vec<Value>> = HashMap::new();
+        let mut groups: IndexMap<Vec<Value>, Vec<Value>> = IndexMap::new();
 
         for i in 0..df.height() {
             // Get index values
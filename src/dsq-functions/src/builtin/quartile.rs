@@ -1,8 +1,8 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 pub fn builtin_quartile(args: &[Value]) -> Result<Value> {
     if args.is_empty() || args.len() > 2 {
@@ -64,7 +64,7 @@ pub fn builtin_quartile(args: &[Value]) -> Result<Value> {
             }
         }
         Value::DataFrame(df) => {
-            let mut quartiles = HashMap::new();
+            let mut quartiles = IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(column) = df.column(col_name) {
                     let series = column.as_materialized_series();
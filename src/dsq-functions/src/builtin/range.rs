@@ -2,64 +2,84 @@ use dsq_shared::value::Value;
 use dsq_shared::Result;
 use inventory;
 
-pub fn builtin_range(args: &[Value]) -> Result<Value> {
-    if args.is_empty() || args.len() > 3 {
-        return Err(dsq_shared::error::operation_error(
-            "range() expects 1-3 arguments",
-        ));
+/// A numeric bound or step of `range`
+fn number(value: &Value, what: &str) -> Result<f64> {
+    match value {
+        Value::Int(i) => Ok(*i as f64),
+        Value::Float(f) => Ok(*f),
+        _ => Err(dsq_shared::error::operation_error(format!(
+            "range() {} must be a number",
+            what
+        ))),
     }
+}
 
-    let start = if args.len() >= 2 {
-        match &args[0] {
-            Value::Int(i) => *i,
-            _ => {
-                return Err(dsq_shared::error::operation_error(
-                    "range() start must be integer",
-                ));
-            }
-        }
-    } else {
-        0
-    };
-
-    let end = match &args[if args.len() >= 2 { 1 } else { 0 }] {
-        Value::Int(i) => *i,
+/// The start, end and step of `range(end)`, `range(start; end)` or
+/// `range(start; end; step)`
+///
+/// Without a step, the range counts down when `start` is above `end`.
+pub fn range_bounds(args: &[Value]) -> Result<(Value, Value, Value)> {
+    let (start, end, step) = match args {
+        [end] => (Value::Int(0), end, None),
+        [start, end] => (start.clone(), end, None),
+        [start, end, step] => (start.clone(), end, Some(step)),
         _ => {
             return Err(dsq_shared::error::operation_error(
-                "range() end must be integer",
-            ));
+                "range() expects 1-3 arguments",
+            ))
         }
     };
-
-    let step = if args.len() == 3 {
-        match &args[2] {
-            Value::Int(i) if *i != 0 => *i,
-            _ => {
-                return Err(dsq_shared::error::operation_error(
-                    "range() step must be non-zero integer",
-                ));
-            }
-        }
-    } else if start <= end {
-        1
-    } else {
-        -1
+    let step = match step {
+        Some(step) => step.clone(),
+        None if number(&start, "start")? <= number(end, "end")? => Value::Int(1),
+        None => Value::Int(-1),
     };
+    Ok((start, end.clone(), step))
+}
 
-    let mut result = Vec::new();
-    let mut current = start;
-    if step > 0 {
-        while current < end {
-            result.push(Value::Int(current));
-            current += step;
-        }
-    } else {
-        while current > end {
-            result.push(Value::Int(current));
-            current += step;
+/// The numbers from `start` towards `end` (exclusive) in steps of `step`,
+/// produced lazily
+///
+/// Integer bounds and step produce integers; any float produces floats.
+pub fn range_values(
+    start: &Value,
+    end: &Value,
+    step: &Value,
+) -> Result<Box<dyn Iterator<Item = Value> + Send>> {
+    if let (Value::Int(start), Value::Int(end), Value::Int(step)) = (start, end, step) {
+        let (end, step) = (*end, *step);
+        if step == 0 {
+            return Err(dsq_shared::error::operation_error(
+                "range() step must be non-zero",
+            ));
         }
+        return Ok(Box::new(
+            std::iter::successors(Some(*start), move |i| i.checked_add(step))
+                .take_while(move |&i| if step > 0 { i < end } else { i > end })
+                .map(Value::Int),
+        ));
+    }
+    let (start, end, step) = (
+        number(start, "start")?,
+        number(end, "end")?,
+        number(step, "step")?,
+    );
+    if step == 0.0 || step.is_nan() {
+        return Err(dsq_shared::error::operation_error(
+            "range() step must be non-zero",
+        ));
     }
-    Ok(Value::Array(result))
+    Ok(Box::new(
+        (0u64..)
+            .map(move |n| start + n as f64 * step)
+            .take_while(move |&x| if step > 0.0 { x < end } else { x > end })
+            .map(Value::Float),
+    ))
+}
+
+pub fn builtin_range(args: &[Value]) -> Result<Value> {
+    let (start, end, step) = range_bounds(args)?;
+    Ok(Value::Array(range_values(&start, &end, &step)?.collect()))
 }
 
 inventory::submit! {
@@ -165,4 +185,25 @@ mod tests {
         let result = builtin_range(&[Value::String("1".to_string())]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_range_floats() {
+        let result = builtin_range(&[Value::Int(0), Value::Int(1), Value::Float(0.25)]).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Float(0.0),
+                Value::Float(0.25),
+                Value::Float(0.5),
+                Value::Float(0.75)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_range_values_is_lazy() {
+        let values = range_values(&Value::Int(0), &Value::Int(i64::MAX), &Value::Int(1)).unwrap();
+        let first: Vec<Value> = values.take(2).collect();
+        assert_eq!(first, vec![Value::Int(0), Value::Int(1)]);
+    }
 }
@@ -0,0 +1,180 @@
+use dsq_shared::value::{df_row_to_value, value_from_any_value, Value};
+use dsq_shared::Result;
+use inventory;
+use std::ops::ControlFlow;
+
+/// The direct children of `value` with the key or index each is found at
+///
+/// Objects yield their values in insertion order, arrays and Series their
+/// elements, and DataFrames their rows as objects (with struct and list
+/// columns converted to nested objects and arrays). Scalars have no children.
+pub fn child_entries(value: &Value) -> Result<Vec<(Value, Value)>> {
+    match value {
+        Value::Array(arr) => Ok(arr
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (Value::Int(i as i64), item.clone()))
+            .collect()),
+        Value::Object(obj) => Ok(obj
+            .iter()
+            .map(|(key, item)| (Value::String(key.clone()), item.clone()))
+            .collect()),
+        Value::DataFrame(df) => (0..df.height())
+            .map(|i| Ok((Value::Int(i as i64), df_row_to_value(df, i)?)))
+            .collect(),
+        Value::LazyFrame(lf) => {
+            let df = lf.clone().collect().map_err(|e| {
+                dsq_shared::error::operation_error(format!("Failed to collect LazyFrame: {}", e))
+            })?;
+            child_entries(&Value::DataFrame(df))
+        }
+        Value::Series(series) => (0..series.len())
+            .map(|i| {
+                let av = series.get(i).map_err(|e| {
+                    dsq_shared::error::operation_error(format!("Failed to get value: {}", e))
+                })?;
+                let item = value_from_any_value(av.clone())
+                    .unwrap_or_else(|| Value::String(av.to_string()));
+                Ok((Value::Int(i as i64), item))
+            })
+            .collect(),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Call `f` with the key and value of each direct child of `value`, in the
+/// order of `child_entries`, until it returns `ControlFlow::Break`
+///
+/// The children of arrays and objects are borrowed rather than copied, and
+/// DataFrame rows are built one at a time.
+pub fn for_each_child(
+    value: &Value,
+    f: &mut dyn FnMut(Value, &Value) -> Result<ControlFlow<()>>,
+) -> Result<ControlFlow<()>> {
+    match value {
+        Value::Array(arr) => {
+            for (i, item) in arr.iter().enumerate() {
+                if f(Value::Int(i as i64), item)?.is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+        Value::Object(obj) => {
+            for (key, item) in obj {
+                if f(Value::String(key.clone()), item)?.is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+        Value::DataFrame(df) => {
+            for i in 0..df.height() {
+                if f(Value::Int(i as i64), &df_row_to_value(df, i)?)?.is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+        _ => {
+            for (key, item) in child_entries(value)? {
+                if f(key, &item)?.is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Call `f` with `value` and everything below it, parents first, until it
+/// returns `ControlFlow::Break`
+pub fn for_each_descendant(
+    value: &Value,
+    f: &mut dyn FnMut(&Value) -> Result<ControlFlow<()>>,
+) -> Result<ControlFlow<()>> {
+    if f(value)?.is_break() {
+        return Ok(ControlFlow::Break(()));
+    }
+    for_each_child(value, &mut |_, child| for_each_descendant(child, f))
+}
+
+pub fn builtin_recurse(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(dsq_shared::error::operation_error(
+            "recurse() expects 1 argument",
+        ));
+    }
+
+    let mut values = Vec::new();
+    for_each_descendant(&args[0], &mut |value| {
+        values.push(value.clone());
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(Value::Array(values))
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "recurse",
+        func: builtin_recurse,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use polars::prelude::*;
+
+    #[test]
+    fn test_recurse_nested() {
+        let inner = Value::Object(IndexMap::from([("id".to_string(), Value::Int(2))]));
+        let value = Value::Object(IndexMap::from([
+            ("id".to_string(), Value::Int(1)),
+            ("items".to_string(), Value::Array(vec![inner.clone()])),
+        ]));
+        let result = builtin_recurse(std::slice::from_ref(&value)).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                value,
+                Value::Int(1),
+                Value::Array(vec![inner.clone()]),
+                inner,
+                Value::Int(2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_recurse_scalar() {
+        let result = builtin_recurse(&[Value::Int(1)]).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(1)]));
+    }
+
+    #[test]
+    fn test_recurse_dataframe_list_column() {
+        let tags = Series::new(
+            "tags".into(),
+            [
+                Series::new("".into(), ["x"]),
+                Series::new("".into(), ["y", "z"]),
+            ],
+        );
+        let df = DataFrame::new(vec![tags.into()]).unwrap();
+        let result = builtin_recurse(&[Value::DataFrame(df)]).unwrap();
+        let Value::Array(values) = result else {
+            panic!("Expected array");
+        };
+        let strings: Vec<&Value> = values
+            .iter()
+            .filter(|v| matches!(v, Value::String(_)))
+            .collect();
+        assert_eq!(
+            strings,
+            vec![
+                &Value::String("x".to_string()),
+                &Value::String("y".to_string()),
+                &Value::String("z".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use indexmap::IndexMap;
+use inventory;
+use lru::LruCache;
+use polars::prelude::*;
+use regex::{Captures, Regex};
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, OnceLock};
+
+/// Number of compiled patterns kept by [`compile_regex`]
+const REGEX_CACHE_SIZE: usize = 256;
+
+/// Modifiers parsed from a jq flags string (`"gi"`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegexFlags {
+    /// `g`: every match rather than only the first
+    pub global: bool,
+    /// `n`: ignore empty matches
+    pub skip_empty: bool,
+    /// Inline flags passed to the regex engine (`i`, `x`)
+    inline: String,
+}
+
+impl RegexFlags {
+    /// Parse a flags argument; `null` means no flags
+    pub fn parse(flags: &Value) -> Result<Self> {
+        let text = match flags {
+            Value::Null => return Ok(Self::default()),
+            Value::String(s) => s,
+            other => {
+                return Err(dsq_shared::error::operation_error(format!(
+                    "{} is not a string",
+                    describe(other)
+                )))
+            }
+        };
+        let mut parsed = Self::default();
+        for ch in text.chars() {
+            match ch {
+                'g' => parsed.global = true,
+                'n' => parsed.skip_empty = true,
+                'i' | 'x' => {
+                    if !parsed.inline.contains(ch) {
+                        parsed.inline.push(ch);
+                    }
+                }
+                _ => {
+                    return Err(dsq_shared::error::operation_error(format!(
+                        "{} is not a valid modifier string",
+                        text
+                    )))
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// `re` with the inline flags applied, as handed to the regex engine
+    pub fn pattern(&self, re: &str) -> String {
+        if self.inline.is_empty() {
+            re.to_string()
+        } else {
+            format!("(?{}){}", self.inline, re)
+        }
+    }
+}
+
+/// Compile `pattern`, reusing a previous compilation of the same pattern
+pub fn compile_regex(pattern: &str) -> Result<Regex> {
+    static CACHE: OnceLock<Mutex<LruCache<String, Regex>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| {
+        Mutex::new(LruCache::new(
+            NonZeroUsize::new(REGEX_CACHE_SIZE).expect("cache size is non-zero"),
+        ))
+    });
+    if let Some(re) = cache.lock().ok().and_then(|mut c| c.get(pattern).cloned()) {
+        return Ok(re);
+    }
+    let re = Regex::new(pattern).map_err(|e| {
+        dsq_shared::error::operation_error(format!("{} is not a valid regex: {}", pattern, e))
+    })?;
+    if let Ok(mut cache) = cache.lock() {
+        cache.put(pattern.to_string(), re.clone());
+    }
+    Ok(re)
+}
+
+/// The pattern and flags of a regex builtin: `(re)`, `(re; flags)` or the
+/// array form `([re, flags])`
+///
+/// Returns the pattern with its inline flags applied.
+pub fn regex_args(name: &str, args: &[Value]) -> Result<(String, RegexFlags)> {
+    let (re, flags) = match args {
+        [Value::Array(pair)] => match pair.as_slice() {
+            [re] => (re, &Value::Null),
+            [re, flags] => (re, flags),
+            _ => {
+                return Err(dsq_shared::error::operation_error(format!(
+                    "{}() expects an array of a regex and flags",
+                    name
+                )))
+            }
+        },
+        [re] => (re, &Value::Null),
+        [re, flags] => (re, flags),
+        _ => {
+            return Err(dsq_shared::error::operation_error(format!(
+                "{}() expects 1 or 2 arguments",
+                name
+            )))
+        }
+    };
+    let Value::String(re) = re else {
+        return Err(dsq_shared::error::operation_error(format!(
+            "{} cannot be matched, as it is not a string",
+            describe(re)
+        )));
+    };
+    let flags = RegexFlags::parse(flags)?;
+    Ok((flags.pattern(re), flags))
+}
+
+/// `type (value)`, as used in error messages
+pub fn describe(value: &Value) -> String {
+    let text = serde_json::to_string(value).unwrap_or_else(|_| value.to_string());
+    format!("{} ({})", value.type_name(), text)
+}
+
+/// Error for matching against a value that is not a string
+pub fn not_a_string(value: &Value) -> anyhow::Error {
+    dsq_shared::error::operation_error(format!(
+        "{} cannot be matched, as it is not a string",
+        describe(value)
+    ))
+}
+
+/// The matches of `re` in `text`: the first only, or all with `g`, skipping
+/// empty matches with `n`
+pub fn find_matches<'t>(re: &Regex, text: &'t str, flags: &RegexFlags) -> Vec<Captures<'t>> {
+    let matches = re
+        .captures_iter(text)
+        .filter(|caps| !flags.skip_empty || caps.get(0).is_some_and(|m| !m.as_str().is_empty()));
+    if flags.global {
+        matches.collect()
+    } else {
+        matches.take(1).collect()
+    }
+}
+
+/// Offset of byte `index` of `text` in codepoints
+fn char_offset(text: &str, index: usize) -> Value {
+    Value::Int(text[..index].chars().count() as i64)
+}
+
+/// The jq match object for `caps`: offset, length and string of the match,
+/// plus the same for each capture group
+pub fn match_object(re: &Regex, text: &str, caps: &Captures) -> Value {
+    let whole = caps.get(0).expect("a match has group 0");
+    let captures = re
+        .capture_names()
+        .enumerate()
+        .skip(1)
+        .map(|(i, name)| {
+            let name = name.map_or(Value::Null, |n| Value::String(n.to_string()));
+            let (offset, length, string) = match caps.get(i) {
+                Some(m) => (
+                    char_offset(text, m.start()),
+                    Value::Int(m.as_str().chars().count() as i64),
+                    Value::String(m.as_str().to_string()),
+                ),
+                None => (Value::Int(-1), Value::Int(0), Value::Null),
+            };
+            Value::Object(IndexMap::from([
+                ("offset".to_string(), offset),
+                ("length".to_string(), length),
+                ("string".to_string(), string),
+                ("name".to_string(), name),
+            ]))
+        })
+        .collect();
+    Value::Object(IndexMap::from([
+        ("offset".to_string(), char_offset(text, whole.start())),
+        (
+            "length".to_string(),
+            Value::Int(whole.as_str().chars().count() as i64),
+        ),
+        (
+            "string".to_string(),
+            Value::String(whole.as_str().to_string()),
+        ),
+        ("captures".to_string(), Value::Array(captures)),
+    ]))
+}
+
+/// The named groups of `caps` as an object (unmatched groups are `null`)
+pub fn capture_object(re: &Regex, caps: &Captures) -> Value {
+    Value::Object(
+        re.capture_names()
+            .flatten()
+            .map(|name| {
+                let value = caps
+                    .name(name)
+                    .map_or(Value::Null, |m| Value::String(m.as_str().to_string()));
+                (name.to_string(), value)
+            })
+            .collect(),
+    )
+}
+
+/// The string values of a Series, which must have a string dtype
+pub fn string_values(series: &Series) -> Result<&StringChunked> {
+    series.str().map_err(|_| {
+        dsq_shared::error::operation_error(format!(
+            "{} cannot be matched, as it is not a string",
+            series.dtype()
+        ))
+    })
+}
+
+/// Apply `f` to each string of a Series, collecting the results as an array
+/// (`null` for missing values)
+pub fn map_strings(series: &Series, mut f: impl FnMut(&str) -> Result<Value>) -> Result<Value> {
+    string_values(series)?
+        .into_iter()
+        .map(|s| s.map_or(Ok(Value::Null), &mut f))
+        .collect::<Result<Vec<_>>>()
+        .map(Value::Array)
+}
+
+/// Build a list-of-strings Series from per-element string lists
+pub fn string_list_series(name: PlSmallStr, lists: Vec<Option<Vec<String>>>) -> Value {
+    let lists: Vec<Option<Series>> = lists
+        .into_iter()
+        .map(|list| list.map(|items| Series::new(PlSmallStr::EMPTY, items)))
+        .collect();
+    let mut series: Series = lists.into_iter().collect::<ListChunked>().into_series();
+    series.rename(name);
+    Value::Series(series)
+}
+
+fn match_string(re: &Regex, text: &str, flags: &RegexFlags) -> Value {
+    let mut matches: Vec<Value> = find_matches(re, text, flags)
+        .iter()
+        .map(|caps| match_object(re, text, caps))
+        .collect();
+    if flags.global {
+        Value::Array(matches)
+    } else {
+        matches.pop().unwrap_or(Value::Null)
+    }
+}
+
+pub fn builtin_match(args: &[Value]) -> Result<Value> {
+    let Some((input, rest)) = args.split_first() else {
+        return Err(dsq_shared::error::operation_error(
+            "match() expects 1 or 2 arguments",
+        ));
+    };
+    let (pattern, flags) = regex_args("match", rest)?;
+    let re = compile_regex(&pattern)?;
+
+    match input {
+        Value::String(s) => Ok(match_string(&re, s, &flags)),
+        Value::Series(series) => map_strings(series, |s| Ok(match_string(&re, s, &flags))),
+        other => Err(not_a_string(other)),
+    }
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "match",
+        func: builtin_match,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_match_object() {
+        let result = builtin_match(&[s("föo bar"), s("(?<word>b)(x)?")]).unwrap();
+        let expected = serde_json::json!({
+            "offset": 4, "length": 1, "string": "b",
+            "captures": [
+                {"offset": 4, "length": 1, "string": "b", "name": "word"},
+                {"offset": -1, "length": 0, "string": null, "name": null}
+            ]
+        });
+        assert_eq!(result, Value::from_json(expected));
+    }
+
+    #[test]
+    fn test_match_global_and_flags() {
+        let result = builtin_match(&[s("aAa"), s("a"), s("gi")]).unwrap();
+        assert_eq!(result.len(), Some(3));
+        let result = builtin_match(&[s("abc"), Value::Array(vec![s("B"), s("i")])]).unwrap();
+        assert_eq!(result.field("offset").unwrap(), Value::Int(1));
+        assert_eq!(builtin_match(&[s("abc"), s("z")]).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_match_empty_matches() {
+        let all = builtin_match(&[s("ab"), s("x*"), s("g")]).unwrap();
+        assert_eq!(all.len(), Some(3));
+        let non_empty = builtin_match(&[s("ab"), s("x*"), s("gn")]).unwrap();
+        assert_eq!(non_empty, Value::Array(vec![]));
+    }
+
+    #[test]
+    fn test_match_errors() {
+        assert!(builtin_match(&[Value::Int(1), s("a")]).is_err());
+        let err = builtin_match(&[s("a"), s("a"), s("q")]).unwrap_err();
+        assert!(err.to_string().contains("q is not a valid modifier string"));
+        assert!(builtin_match(&[s("a"), s("(")]).is_err());
+    }
+
+    #[test]
+    fn test_regex_cache_reuses_patterns() {
+        let first = compile_regex("ca+che").unwrap();
+        let second = compile_regex("ca+che").unwrap();
+        assert_eq!(first.as_str(), second.as_str());
+    }
+}
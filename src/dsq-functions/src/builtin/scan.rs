@@ -0,0 +1,98 @@
+use crate::builtin::regex_match::{
+    compile_regex, find_matches, map_strings, not_a_string, regex_args, string_values, RegexFlags,
+};
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+use polars::prelude::*;
+use regex::Regex;
+
+/// Every match of `re` in `text`: the matched string, or the array of
+/// captured strings when the pattern has groups
+fn scan_string(re: &Regex, text: &str, flags: &RegexFlags) -> Value {
+    Value::Array(
+        find_matches(re, text, flags)
+            .iter()
+            .map(|caps| {
+                if caps.len() == 1 {
+                    Value::String(caps[0].to_string())
+                } else {
+                    Value::Array(
+                        caps.iter()
+                            .skip(1)
+                            .map(|m| {
+                                m.map_or(Value::Null, |m| Value::String(m.as_str().to_string()))
+                            })
+                            .collect(),
+                    )
+                }
+            })
+            .collect(),
+    )
+}
+
+pub fn builtin_scan(args: &[Value]) -> Result<Value> {
+    let Some((input, rest)) = args.split_first() else {
+        return Err(dsq_shared::error::operation_error(
+            "scan() expects 1 or 2 arguments",
+        ));
+    };
+    let (pattern, mut flags) = regex_args("scan", rest)?;
+    flags.global = true;
+    let re = compile_regex(&pattern)?;
+
+    match input {
+        Value::String(s) => Ok(scan_string(&re, s, &flags)),
+        Value::Series(series) if re.captures_len() == 1 && !flags.skip_empty => {
+            let matches = string_values(series)?
+                .extract_all(&pattern)
+                .map_err(|e| dsq_shared::error::operation_error(format!("scan() failed: {}", e)))?;
+            Ok(Value::Series(matches.into_series()))
+        }
+        Value::Series(series) => map_strings(series, |s| Ok(scan_string(&re, s, &flags))),
+        other => Err(not_a_string(other)),
+    }
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "scan",
+        func: builtin_scan,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_scan_string() {
+        let result = builtin_scan(&[s("abAB"), s("b"), s("i")]).unwrap();
+        assert_eq!(result, Value::Array(vec![s("b"), s("B")]));
+        let result = builtin_scan(&[s("a1b2"), s("([a-z])(\\d)")]).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Array(vec![s("a"), s("1")]),
+                Value::Array(vec![s("b"), s("2")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_scan_series() {
+        let series = Series::new("text".into(), [Some("a1b22"), None]);
+        let result = builtin_scan(&[Value::Series(series), s("\\d+")]).unwrap();
+        let Value::Series(result) = result else {
+            panic!("expected a series");
+        };
+        let first = result.list().unwrap().get_as_series(0).unwrap();
+        let first: Vec<_> = first.str().unwrap().into_iter().collect();
+        assert_eq!(first, vec![Some("1"), Some("22")]);
+        assert_eq!(result.null_count(), 1);
+    }
+}
@@ -12,16 +12,27 @@ pub fn builtin_second(args: &[Value]) -> Result<Value> {
     }
 
     match &args[0] {
-        Value::Int(_) | Value::Float(_) | Value::String(_) => {
-            let dt = crate::extract_timestamp(&args[0])?;
+        Value::Int(_)
+        | Value::Float(_)
+        | Value::String(_)
+        | Value::Date(_)
+        | Value::Datetime { .. } => {
+            let dt = crate::extract_local_timestamp(&args[0])?;
             Ok(Value::Int(dt.second() as i64))
         }
         Value::Array(arr) => {
             let seconds: Result<Vec<Value>> = arr
                 .iter()
                 .map(|v| {
-                    if matches!(v, Value::Int(_) | Value::Float(_) | Value::String(_)) {
-                        let dt = crate::extract_timestamp(v)?;
+                    if matches!(
+                        v,
+                        Value::Int(_)
+                            | Value::Float(_)
+                            | Value::String(_)
+                            | Value::Date(_)
+                            | Value::Datetime { .. }
+                    ) {
+                        let dt = crate::extract_local_timestamp(v)?;
                         Ok(Value::Int(dt.second() as i64))
                     } else {
                         Ok(Value::Null)
@@ -42,9 +53,13 @@ pub fn builtin_second(args: &[Value]) -> Result<Value> {
                                     let value = value_from_any_value(val).unwrap_or(Value::Null);
                                     if matches!(
                                         value,
-                                        Value::Int(_) | Value::Float(_) | Value::String(_)
+                                        Value::Int(_)
+                                            | Value::Float(_)
+                                            | Value::String(_)
+                                            | Value::Date(_)
+                                            | Value::Datetime { .. }
                                     ) {
-                                        match crate::extract_timestamp(&value) {
+                                        match crate::extract_local_timestamp(&value) {
                                             Ok(dt) => {
                                                 second_values.push(dt.second() as i64);
                                             }
@@ -85,8 +100,15 @@ pub fn builtin_second(args: &[Value]) -> Result<Value> {
                     match series.get(i) {
                         Ok(val) => {
                             let value = value_from_any_value(val).unwrap_or(Value::Null);
-                            if matches!(value, Value::Int(_) | Value::Float(_) | Value::String(_)) {
-                                match crate::extract_timestamp(&value) {
+                            if matches!(
+                                value,
+                                Value::Int(_)
+                                    | Value::Float(_)
+                                    | Value::String(_)
+                                    | Value::Date(_)
+                                    | Value::Datetime { .. }
+                            ) {
+                                match crate::extract_local_timestamp(&value) {
                                     Ok(dt) => {
                                         second_values.push(dt.second() as i64);
                                     }
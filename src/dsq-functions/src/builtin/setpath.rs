@@ -0,0 +1,182 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use indexmap::IndexMap;
+use inventory;
+use polars::prelude::*;
+
+use super::getpath::{describe_key, get_key, path_components, slice_range};
+
+/// Replace the child of `value` at the path component `key` with `new`
+///
+/// Null grows into an object or array as needed, and arrays are padded with
+/// nulls when `key` is past their end. On a DataFrame, a string key replaces
+/// or adds the column with a Series or a scalar broadcast to every row.
+pub fn set_key(value: &Value, key: &Value, new: Value) -> Result<Value> {
+    match (value, key) {
+        (Value::Null, Value::String(k)) => Ok(Value::Object(IndexMap::from([(k.clone(), new)]))),
+        (Value::Object(obj), Value::String(k)) => {
+            let mut obj = obj.clone();
+            obj.insert(k.clone(), new);
+            Ok(Value::Object(obj))
+        }
+        (Value::Null, Value::Int(_)) => set_key(&Value::Array(Vec::new()), key, new),
+        (Value::Array(arr), Value::Int(i)) => {
+            let len = arr.len() as i64;
+            let index = if *i < 0 { len + i } else { *i };
+            if index < 0 {
+                return Err(dsq_shared::error::operation_error(
+                    "Out of bounds negative array index",
+                ));
+            }
+            let index = index as usize;
+            let mut arr = arr.clone();
+            if index >= arr.len() {
+                arr.resize(index + 1, Value::Null);
+            }
+            arr[index] = new;
+            Ok(Value::Array(arr))
+        }
+        (Value::Null | Value::Array(_), Value::Object(_)) => {
+            let arr = match value {
+                Value::Array(arr) => arr.clone(),
+                _ => Vec::new(),
+            };
+            let Value::Array(items) = new else {
+                return Err(dsq_shared::error::operation_error(
+                    "A slice of an array can only be assigned another array",
+                ));
+            };
+            let (start, end) = slice_range(key, arr.len()).ok_or_else(|| {
+                dsq_shared::error::operation_error("Array slice bounds must be numbers")
+            })?;
+            let mut arr = arr;
+            arr.splice(start..end, items);
+            Ok(Value::Array(arr))
+        }
+        (Value::DataFrame(df), Value::String(column)) => set_column(df, column, new),
+        (Value::LazyFrame(lf), Value::String(column)) => {
+            let df = lf.clone().collect().map_err(|e| {
+                dsq_shared::error::operation_error(format!("Failed to collect LazyFrame: {}", e))
+            })?;
+            set_column(&df, column, new)
+        }
+        _ => Err(dsq_shared::error::operation_error(format!(
+            "Cannot index {} with {}",
+            value.type_name(),
+            describe_key(key)
+        ))),
+    }
+}
+
+/// Replace or add `column` of `df`
+fn set_column(df: &DataFrame, column: &str, new: Value) -> Result<Value> {
+    let fill = match new {
+        Value::Series(series) => lit(series.with_name(column.into())),
+        Value::Null => lit(NULL),
+        Value::Bool(b) => lit(b),
+        Value::Int(i) => lit(i),
+        Value::Float(f) => lit(f),
+        Value::String(s) => lit(s),
+        other => {
+            return Err(dsq_shared::error::operation_error(format!(
+                "Cannot set column \"{}\" to {}",
+                column,
+                other.type_name()
+            )))
+        }
+    };
+    df.clone()
+        .lazy()
+        .with_column(fill.alias(column))
+        .collect()
+        .map(Value::DataFrame)
+        .map_err(|e| {
+            dsq_shared::error::operation_error(format!(
+                "Failed to set column \"{}\": {}",
+                column, e
+            ))
+        })
+}
+
+/// `value` with the value at `path` replaced by `new`
+pub fn set_path(value: &Value, path: &[Value], new: Value) -> Result<Value> {
+    let Some((key, rest)) = path.split_first() else {
+        return Ok(new);
+    };
+    let child = if rest.is_empty() {
+        Value::Null
+    } else {
+        get_key(value, key)?
+    };
+    set_key(value, key, set_path(&child, rest, new)?)
+}
+
+pub fn builtin_setpath(args: &[Value]) -> Result<Value> {
+    if args.len() != 3 {
+        return Err(dsq_shared::error::operation_error(
+            "setpath() expects 3 arguments",
+        ));
+    }
+
+    set_path(
+        &args[0],
+        path_components(&args[1], "setpath")?,
+        args[2].clone(),
+    )
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "setpath",
+        func: builtin_setpath,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setpath_creates_structure() {
+        let path = Value::Array(vec![Value::String("a".to_string()), Value::Int(1)]);
+        let result = builtin_setpath(&[Value::Null, path, Value::Int(5)]).unwrap();
+        assert_eq!(
+            result,
+            Value::Object(IndexMap::from([(
+                "a".to_string(),
+                Value::Array(vec![Value::Null, Value::Int(5)])
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_setpath_replaces() {
+        let value = Value::Array(vec![Value::Int(1), Value::Int(2)]);
+        let path = Value::Array(vec![Value::Int(-1)]);
+        let result = builtin_setpath(&[value, path, Value::Int(9)]).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(1), Value::Int(9)]));
+    }
+
+    #[test]
+    fn test_setpath_slice() {
+        let value = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        let slice = Value::Object(IndexMap::from([
+            ("start".to_string(), Value::Int(0)),
+            ("end".to_string(), Value::Int(2)),
+        ]));
+        let path = Value::Array(vec![slice]);
+        let result = builtin_setpath(&[value, path, Value::Array(vec![Value::Int(0)])]).unwrap();
+        assert_eq!(result, Value::Array(vec![Value::Int(0), Value::Int(3)]));
+    }
+
+    #[test]
+    fn test_setpath_dataframe_column() {
+        let df = df! { "a" => [1i64, 2] }.unwrap();
+        let path = Value::Array(vec![Value::String("b".to_string())]);
+        let result = builtin_setpath(&[Value::DataFrame(df), path, Value::Int(0)]).unwrap();
+        let Value::DataFrame(df) = result else {
+            panic!("Expected DataFrame");
+        };
+        assert_eq!(df.get_column_names(), vec!["a", "b"]);
+    }
+}
@@ -159,7 +159,7 @@ mod tests {
     use super::*;
     use dsq_shared::value::Value;
 
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     fn create_test_dataframe() -> DataFrame {
         let names = Series::new(PlSmallStr::from("name"), &["Alice", "Bob", "Charlie"]);
@@ -188,15 +188,15 @@ mod tests {
 
     #[test]
     fn test_sort_by_array_of_objects_by_field() {
-        let mut obj1 = HashMap::new();
+        let mut obj1 = IndexMap::new();
         obj1.insert("name".to_string(), Value::String("Alice".to_string()));
         obj1.insert("age".to_string(), Value::Int(25));
 
-        let mut obj2 = HashMap::new();
+        let mut obj2 = IndexMap::new();
         obj2.insert("name".to_string(), Value::String("Bob".to_string()));
         obj2.insert("age".to_string(), Value::Int(30));
 
-        let mut obj3 = HashMap::new();
+        let mut obj3 = IndexMap::new();
         obj3.insert("name".to_string(), Value::String("Charlie".to_string()));
         obj3.insert("age".to_string(), Value::Int(20));
 
@@ -0,0 +1,86 @@
+use crate::builtin::regex_match::{
+    compile_regex, find_matches, not_a_string, regex_args, string_list_series, string_values,
+    RegexFlags,
+};
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+use regex::Regex;
+
+/// The pieces of `text` between the matches of `re`
+fn split_string(re: &Regex, text: &str, flags: &RegexFlags) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut last = 0;
+    for caps in find_matches(re, text, flags) {
+        let whole = caps.get(0).expect("a match has group 0");
+        pieces.push(text[last..whole.start()].to_string());
+        last = whole.end();
+    }
+    pieces.push(text[last..].to_string());
+    pieces
+}
+
+pub fn builtin_splits(args: &[Value]) -> Result<Value> {
+    let Some((input, rest)) = args.split_first() else {
+        return Err(dsq_shared::error::operation_error(
+            "splits() expects 1 or 2 arguments",
+        ));
+    };
+    let (pattern, mut flags) = regex_args("splits", rest)?;
+    flags.global = true;
+    let re = compile_regex(&pattern)?;
+
+    match input {
+        Value::String(s) => Ok(Value::Array(
+            split_string(&re, s, &flags)
+                .into_iter()
+                .map(Value::String)
+                .collect(),
+        )),
+        Value::Series(series) => {
+            let pieces = string_values(series)?
+                .into_iter()
+                .map(|s| s.map(|s| split_string(&re, s, &flags)))
+                .collect();
+            Ok(string_list_series(series.name().clone(), pieces))
+        }
+        other => Err(not_a_string(other)),
+    }
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "splits",
+        func: builtin_splits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_splits_string() {
+        let result = builtin_splits(&[s("ab,cd, ef"), s(", *")]).unwrap();
+        assert_eq!(result, Value::Array(vec![s("ab"), s("cd"), s("ef")]));
+        let result = builtin_splits(&[s("aXbxc"), s("x"), s("i")]).unwrap();
+        assert_eq!(result, Value::Array(vec![s("a"), s("b"), s("c")]));
+    }
+
+    #[test]
+    fn test_splits_series() {
+        let series = Series::new("csv".into(), [Some("a;b"), None]);
+        let result = builtin_splits(&[Value::Series(series), s(";")]).unwrap();
+        let Value::Series(result) = result else {
+            panic!("expected a series");
+        };
+        assert_eq!(result.name().as_str(), "csv");
+        assert_eq!(result.list().unwrap().get_as_series(0).unwrap().len(), 2);
+        assert_eq!(result.null_count(), 1);
+    }
+}
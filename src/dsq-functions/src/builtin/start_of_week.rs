@@ -25,8 +25,12 @@ pub fn builtin_start_of_week(args: &[Value]) -> Result<Value> {
     };
 
     match &args[0] {
-        Value::Int(_) | Value::Float(_) | Value::String(_) => {
-            let dt = crate::extract_timestamp(&args[0])?;
+        Value::Int(_)
+        | Value::Float(_)
+        | Value::String(_)
+        | Value::Date(_)
+        | Value::Datetime { .. } => {
+            let dt = crate::extract_local_timestamp(&args[0])?;
             let weekday = dt.weekday();
             let days_to_subtract = match start_day.as_str() {
                 "monday" => weekday.num_days_from_monday() as i64,
@@ -46,8 +50,15 @@ pub fn builtin_start_of_week(args: &[Value]) -> Result<Value> {
             let results: Result<Vec<Value>> = arr
                 .iter()
                 .map(|v| {
-                    if matches!(v, Value::Int(_) | Value::Float(_) | Value::String(_)) {
-                        let dt = crate::extract_timestamp(v)?;
+                    if matches!(
+                        v,
+                        Value::Int(_)
+                            | Value::Float(_)
+                            | Value::String(_)
+                            | Value::Date(_)
+                            | Value::Datetime { .. }
+                    ) {
+                        let dt = crate::extract_local_timestamp(v)?;
                         let weekday = dt.weekday();
                         let days_to_subtract = match start_day.as_str() {
                             "monday" => weekday.num_days_from_monday() as i64,
@@ -82,9 +93,13 @@ pub fn builtin_start_of_week(args: &[Value]) -> Result<Value> {
                                     let value = value_from_any_value(val).unwrap_or(Value::Null);
                                     if matches!(
                                         value,
-                                        Value::Int(_) | Value::Float(_) | Value::String(_)
+                                        Value::Int(_)
+                                            | Value::Float(_)
+                                            | Value::String(_)
+                                            | Value::Date(_)
+                                            | Value::Datetime { .. }
                                     ) {
-                                        match crate::extract_timestamp(&value) {
+                                        match crate::extract_local_timestamp(&value) {
                                             Ok(dt) => {
                                                 let weekday = dt.weekday();
                                                 let days_to_subtract = match start_day.as_str() {
@@ -146,8 +161,15 @@ pub fn builtin_start_of_week(args: &[Value]) -> Result<Value> {
                     match series.get(i) {
                         Ok(val) => {
                             let value = value_from_any_value(val).unwrap_or(Value::Null);
-                            if matches!(value, Value::Int(_) | Value::Float(_) | Value::String(_)) {
-                                match crate::extract_timestamp(&value) {
+                            if matches!(
+                                value,
+                                Value::Int(_)
+                                    | Value::Float(_)
+                                    | Value::String(_)
+                                    | Value::Date(_)
+                                    | Value::Datetime { .. }
+                            ) {
+                                match crate::extract_local_timestamp(&value) {
                                     Ok(dt) => {
                                         let weekday = dt.weekday();
                                         let days_to_subtract = match start_day.as_str() {
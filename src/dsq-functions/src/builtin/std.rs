@@ -1,6 +1,6 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 pub fn builtin_std(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
@@ -28,7 +28,7 @@ pub fn builtin_std(args: &[Value]) -> Result<Value> {
             Ok(Value::Float(variance.sqrt()))
         }
         Value::DataFrame(df) => {
-            let mut stds = HashMap::new();
+            let mut stds = IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(series) = df.column(col_name) {
                     if series.dtype().is_numeric() {
@@ -1,7 +1,7 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use polars::prelude::*;
-use std::collections::HashMap;
 
 pub fn builtin_stdev_s(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
@@ -29,7 +29,7 @@ pub fn builtin_stdev_s(args: &[Value]) -> Result<Value> {
             Ok(Value::Float(variance.sqrt()))
         }
         Value::DataFrame(df) => {
-            let mut stds = HashMap::new();
+            let mut stds = IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(series) = df.column(col_name) {
                     if series.dtype().is_numeric() {
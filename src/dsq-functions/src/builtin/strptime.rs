@@ -32,6 +32,10 @@ pub fn builtin_strptime(args: &[Value]) -> Result<Value> {
     };
 
     match date_val {
+        // Already parsed, e.g. a value from a Date or Datetime column
+        Value::Date(_) | Value::Datetime { .. } => {
+            Ok(Value::Int(crate::extract_timestamp(date_val)?.timestamp()))
+        }
         Value::String(date_str) => match format_val {
             Value::String(format_str) => parse_single(date_str, format_str),
             Value::Array(_) => Err(dsq_shared::error::operation_error(
@@ -47,6 +51,9 @@ pub fn builtin_strptime(args: &[Value]) -> Result<Value> {
                     .iter()
                     .map(|v| match v {
                         Value::String(s) => parse_single(s, format_str),
+                        Value::Date(_) | Value::Datetime { .. } => {
+                            Ok(Value::Int(crate::extract_timestamp(v)?.timestamp()))
+                        }
                         _ => Ok(Value::Null),
                     })
                     .collect();
@@ -274,6 +281,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_strptime_parsed_values() {
+        let datetime = NaiveDate::from_ymd_opt(2021, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let expected = Value::Int(datetime.and_utc().timestamp());
+
+        let result = builtin_strptime(&[
+            Value::datetime(datetime, Some("UTC")),
+            Value::String("%Y-%m-%d".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(result, expected);
+
+        let result = builtin_strptime(&[
+            Value::Array(vec![Value::date(datetime.date())]),
+            Value::String("%Y-%m-%d".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::Int(
+                datetime
+                    .date()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    .and_utc()
+                    .timestamp()
+            )])
+        );
+    }
+
     #[test]
     fn test_strptime_invalid_args() {
         // Too few args
@@ -0,0 +1,201 @@
+use crate::builtin::regex_match::{
+    capture_object, compile_regex, describe, find_matches, not_a_string, regex_args, string_values,
+    RegexFlags,
+};
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use indexmap::IndexMap;
+use inventory;
+use polars::prelude::*;
+use regex::Regex;
+
+/// The replacement for one match, given its capture object
+pub type Replacement<'a> = dyn FnMut(&Value) -> Result<Value> + 'a;
+
+fn replacement_text(value: Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s),
+        other => Err(dsq_shared::error::operation_error(format!(
+            "{} cannot be added to a string",
+            describe(&other)
+        ))),
+    }
+}
+
+fn substitute_string(
+    re: &Regex,
+    text: &str,
+    flags: &RegexFlags,
+    replacement: &mut Replacement,
+) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for caps in find_matches(re, text, flags) {
+        let whole = caps.get(0).expect("a match has group 0");
+        out.push_str(&text[last..whole.start()]);
+        out.push_str(&replacement_text(replacement(&capture_object(re, &caps))?)?);
+        last = whole.end();
+    }
+    out.push_str(&text[last..]);
+    Ok(out)
+}
+
+fn substitute_series(
+    series: &Series,
+    re: &Regex,
+    pattern: &str,
+    flags: &RegexFlags,
+    replacement: &mut Replacement,
+) -> Result<Series> {
+    let values = string_values(series)?;
+    let replaced = if re.capture_names().flatten().next().is_none() && !flags.skip_empty {
+        // Every match sees the same (empty) capture object, so the
+        // replacement is a constant and Polars can do the whole column
+        let text = replacement_text(replacement(&Value::Object(IndexMap::new()))?)?;
+        let text = text.replace('$', "$$");
+        if flags.global {
+            values.replace_all(pattern, &text)
+        } else {
+            values.replace(pattern, &text)
+        }
+        .map_err(|e| dsq_shared::error::operation_error(format!("sub() failed: {}", e)))?
+    } else {
+        values
+            .into_iter()
+            .map(|s| {
+                s.map(|s| substitute_string(re, s, flags, replacement))
+                    .transpose()
+            })
+            .collect::<Result<StringChunked>>()?
+    };
+    Ok(replaced.with_name(series.name().clone()).into_series())
+}
+
+/// Replace the matches of `pattern` in `input` (a string, a string Series or
+/// the string columns of a DataFrame) with the output of `replacement`
+pub fn substitute(
+    input: &Value,
+    pattern: &str,
+    flags: &RegexFlags,
+    replacement: &mut Replacement,
+) -> Result<Value> {
+    let re = compile_regex(pattern)?;
+    match input {
+        Value::String(s) => Ok(Value::String(substitute_string(
+            &re,
+            s,
+            flags,
+            replacement,
+        )?)),
+        Value::Series(series) => Ok(Value::Series(substitute_series(
+            series,
+            &re,
+            pattern,
+            flags,
+            replacement,
+        )?)),
+        Value::DataFrame(df) => {
+            let columns = df
+                .get_columns()
+                .iter()
+                .map(|column| {
+                    if column.dtype() == &DataType::String {
+                        let series = column.as_materialized_series();
+                        substitute_series(series, &re, pattern, flags, replacement)
+                            .map(Column::from)
+                    } else {
+                        Ok(column.clone())
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            DataFrame::new(columns)
+                .map(Value::DataFrame)
+                .map_err(|e| dsq_shared::error::operation_error(format!("sub() failed: {}", e)))
+        }
+        other => Err(not_a_string(other)),
+    }
+}
+
+/// `sub` and `gsub` with a fixed replacement string
+pub(crate) fn substitute_args(name: &str, args: &[Value], global: bool) -> Result<Value> {
+    if !(3..=4).contains(&args.len()) {
+        return Err(dsq_shared::error::operation_error(format!(
+            "{}() expects 2 or 3 arguments",
+            name
+        )));
+    }
+    let mut regex = vec![args[1].clone()];
+    regex.extend(args.get(3).cloned());
+    let (pattern, mut flags) = regex_args(name, &regex)?;
+    flags.global |= global;
+    let replacement = args[2].clone();
+    substitute(&args[0], &pattern, &flags, &mut |_| Ok(replacement.clone()))
+}
+
+pub fn builtin_sub(args: &[Value]) -> Result<Value> {
+    substitute_args("sub", args, false)
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "sub",
+        func: builtin_sub,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_sub_string() {
+        assert_eq!(builtin_sub(&[s("aaa"), s("a"), s("b")]).unwrap(), s("baa"));
+        assert_eq!(
+            builtin_sub(&[s("aAa"), s("A"), s("$"), s("gi")]).unwrap(),
+            s("$$$")
+        );
+        assert!(builtin_sub(&[s("a"), s("a"), Value::Int(1)]).is_err());
+    }
+
+    #[test]
+    fn test_substitute_with_captures() {
+        let flags = RegexFlags::parse(&s("g")).unwrap();
+        let result = substitute(&s("a1 b2"), "(?<l>[a-z])(?<d>\\d)", &flags, &mut |caps| {
+            let Value::Object(caps) = caps else {
+                unreachable!()
+            };
+            match (&caps["l"], &caps["d"]) {
+                (Value::String(l), Value::String(d)) => Ok(Value::String(format!("{}{}", d, l))),
+                _ => unreachable!(),
+            }
+        })
+        .unwrap();
+        assert_eq!(result, s("1a 2b"));
+    }
+
+    #[test]
+    fn test_sub_series_and_dataframe() {
+        let df = df! {
+            "name" => [Some("a-b-c"), None],
+            "n" => [1i64, 2],
+        }
+        .unwrap();
+        let result = builtin_sub(&[Value::DataFrame(df), s("-"), s("$1")]).unwrap();
+        let Value::DataFrame(df) = result else {
+            panic!("expected a DataFrame");
+        };
+        let names: Vec<_> = df
+            .column("name")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(names, vec![Some("a$1b-c"), None]);
+        assert_eq!(df.column("n").unwrap().i64().unwrap().get(1), Some(2));
+    }
+}
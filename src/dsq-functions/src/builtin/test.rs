@@ -0,0 +1,95 @@
+use crate::builtin::regex_match::{compile_regex, not_a_string, regex_args, string_values};
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use inventory;
+use polars::prelude::*;
+
+pub fn builtin_test(args: &[Value]) -> Result<Value> {
+    let Some((input, rest)) = args.split_first() else {
+        return Err(dsq_shared::error::operation_error(
+            "test() expects 1 or 2 arguments",
+        ));
+    };
+    let (pattern, flags) = regex_args("test", rest)?;
+
+    match input {
+        Value::String(s) => {
+            let re = compile_regex(&pattern)?;
+            let matched = re
+                .find_iter(s)
+                .any(|m| !flags.skip_empty || !m.as_str().is_empty());
+            Ok(Value::Bool(matched))
+        }
+        Value::Series(series) if !flags.skip_empty => {
+            // Report invalid patterns the same way as for strings
+            compile_regex(&pattern)?;
+            let matched = string_values(series)?
+                .contains(&pattern, true)
+                .map_err(|e| dsq_shared::error::operation_error(format!("test() failed: {}", e)))?;
+            Ok(Value::Series(matched.into_series()))
+        }
+        Value::Series(series) => {
+            let re = compile_regex(&pattern)?;
+            let matched: BooleanChunked = string_values(series)?
+                .into_iter()
+                .map(|s| s.map(|s| re.find_iter(s).any(|m| !m.as_str().is_empty())))
+                .collect();
+            Ok(Value::Series(
+                matched.with_name(series.name().clone()).into_series(),
+            ))
+        }
+        other => Err(not_a_string(other)),
+    }
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "test",
+        func: builtin_test,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(text: &str) -> Value {
+        Value::String(text.to_string())
+    }
+
+    #[test]
+    fn test_test_strings() {
+        assert_eq!(
+            builtin_test(&[s("foo bar"), s("ba.")]).unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            builtin_test(&[s("FOO"), s("foo")]).unwrap(),
+            Value::Bool(false)
+        );
+        assert_eq!(
+            builtin_test(&[s("FOO"), s("foo"), s("i")]).unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            builtin_test(&[s("abc"), s("a b # spaced"), s("x")]).unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            builtin_test(&[s("abc"), s("x*"), s("n")]).unwrap(),
+            Value::Bool(false)
+        );
+        assert!(builtin_test(&[Value::Int(1), s("1")]).is_err());
+    }
+
+    #[test]
+    fn test_test_series() {
+        let series = Series::new("name".into(), [Some("Alice"), None, Some("bob")]);
+        let result = builtin_test(&[Value::Series(series), s("^[ab]"), s("i")]).unwrap();
+        let Value::Series(result) = result else {
+            panic!("expected a series");
+        };
+        let values: Vec<_> = result.bool().unwrap().into_iter().collect();
+        assert_eq!(values, vec![Some(true), None, Some(true)]);
+    }
+}
@@ -31,6 +31,7 @@ pub fn builtin_time_series_range(args: &[Value]) -> Result<Value> {
 
 fn extract_timestamp(value: &Value) -> Result<DateTime<Utc>> {
     match value {
+        Value::Date(_) | Value::Datetime { .. } => crate::extract_timestamp(value),
         Value::Int(i) => Utc
             .timestamp_opt(*i, 0)
             .single()
@@ -0,0 +1,71 @@
+use dsq_shared::value::Value;
+use dsq_shared::Result;
+use indexmap::IndexMap;
+use inventory;
+
+/// A `{"key": k, "value": v}` entry object
+fn entry(key: Value, value: Value) -> Value {
+    Value::Object(IndexMap::from([
+        ("key".to_string(), key),
+        ("value".to_string(), value),
+    ]))
+}
+
+pub fn builtin_to_entries(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(dsq_shared::error::operation_error(
+            "to_entries() expects 1 argument",
+        ));
+    }
+
+    match &args[0] {
+        Value::Object(obj) => Ok(Value::Array(
+            obj.iter()
+                .map(|(key, value)| entry(Value::String(key.clone()), value.clone()))
+                .collect(),
+        )),
+        Value::Array(arr) => Ok(Value::Array(
+            arr.iter()
+                .enumerate()
+                .map(|(i, item)| entry(Value::Int(i as i64), item.clone()))
+                .collect(),
+        )),
+        other => Err(dsq_shared::error::operation_error(format!(
+            "to_entries() requires an object, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
+inventory::submit! {
+    crate::FunctionRegistration {
+        name: "to_entries",
+        func: builtin_to_entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_entries_object() {
+        let value = Value::Object(IndexMap::from([
+            ("b".to_string(), Value::Int(2)),
+            ("a".to_string(), Value::Int(1)),
+        ]));
+        let result = builtin_to_entries(&[value]).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                entry(Value::String("b".to_string()), Value::Int(2)),
+                entry(Value::String("a".to_string()), Value::Int(1)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_to_entries_invalid() {
+        assert!(builtin_to_entries(&[Value::Int(1)]).is_err());
+    }
+}
@@ -74,7 +74,7 @@ mod tests {
 
     #[test]
     fn test_tojson_object() {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
         let input = Value::Object(obj);
         let result = builtin_tojson(&[input]).unwrap();
@@ -2,10 +2,10 @@ use dsq_shared::{
     value::{value_from_any_value, Value},
     Result,
 };
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
 use serde_json;
-use std::collections::HashMap;
 
 use crate::FunctionRegistration;
 
@@ -60,7 +60,7 @@ pub fn builtin_topk(args: &[Value]) -> Result<Value> {
             }
 
             // Count frequency of each value
-            let mut frequency_map: HashMap<String, (usize, Value)> = HashMap::new();
+            let mut frequency_map: IndexMap<String, (usize, Value)> = IndexMap::new();
             for value in arr {
                 let key = serde_json::to_string(value).unwrap_or_default();
                 frequency_map
@@ -259,7 +259,7 @@ pub fn builtin_topk_with_counts(args: &[Value]) -> Result<Value> {
             }
 
             // Count frequency of each value
-            let mut frequency_map: HashMap<String, (usize, Value)> = HashMap::new();
+            let mut frequency_map: IndexMap<String, (usize, Value)> = IndexMap::new();
             for value in arr {
                 let key = serde_json::to_string(value).unwrap_or_default();
                 frequency_map
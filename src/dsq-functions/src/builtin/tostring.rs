@@ -94,7 +94,7 @@ mod tests {
 
     #[test]
     fn test_tostring_object() {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
         let result = builtin_tostring(&[Value::Object(obj)]).unwrap();
         assert_eq!(result, Value::String("{key: \"value\"}".to_string()));
@@ -1,7 +1,7 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
-use std::collections::HashMap;
 
 pub fn builtin_transform_keys(args: &[Value]) -> Result<Value> {
     if args.len() != 2 {
@@ -12,7 +12,7 @@ pub fn builtin_transform_keys(args: &[Value]) -> Result<Value> {
 
     match (&args[0], &args[1]) {
         (Value::Object(obj), Value::Object(mapping)) => {
-            let mut new_obj = HashMap::new();
+            let mut new_obj = IndexMap::new();
             for (key, value) in obj {
                 if let Some(new_key) = mapping.get(key) {
                     if let Value::String(s) = new_key {
@@ -45,15 +45,15 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_transform_keys_basic() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("old_key".to_string(), Value::String("value".to_string()));
         obj.insert("another_key".to_string(), Value::Int(42));
 
-        let mut mapping = HashMap::new();
+        let mut mapping = IndexMap::new();
         mapping.insert("old_key".to_string(), Value::String("new_key".to_string()));
 
         let args = vec![Value::Object(obj), Value::Object(mapping)];
@@ -75,11 +75,11 @@ mod tests {
 
     #[test]
     fn test_transform_keys_no_mapping() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key1".to_string(), Value::String("value1".to_string()));
         obj.insert("key2".to_string(), Value::Int(42));
 
-        let mapping = HashMap::new();
+        let mapping = IndexMap::new();
 
         let args = vec![Value::Object(obj.clone()), Value::Object(mapping)];
         let result = builtin_transform_keys(&args).unwrap();
@@ -93,10 +93,10 @@ mod tests {
 
     #[test]
     fn test_transform_keys_invalid_mapping_value() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
 
-        let mut mapping = HashMap::new();
+        let mut mapping = IndexMap::new();
         mapping.insert("key".to_string(), Value::Int(123)); // Should be string
 
         let args = vec![Value::Object(obj), Value::Object(mapping)];
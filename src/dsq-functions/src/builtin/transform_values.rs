@@ -1,7 +1,7 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
-use std::collections::HashMap;
 
 pub fn builtin_transform_values(args: &[Value]) -> Result<Value> {
     if args.len() != 2 {
@@ -22,10 +22,10 @@ pub fn builtin_transform_values(args: &[Value]) -> Result<Value> {
     }
 }
 
-fn transform_value_recursive(value: &Value, mapping: &HashMap<String, Value>) -> Result<Value> {
+fn transform_value_recursive(value: &Value, mapping: &IndexMap<String, Value>) -> Result<Value> {
     match value {
         Value::Object(obj) => {
-            let mut new_obj = HashMap::new();
+            let mut new_obj = IndexMap::new();
             for (key, val) in obj {
                 let transformed_val = transform_value_recursive(val, mapping)?;
                 new_obj.insert(key.clone(), transformed_val);
@@ -65,15 +65,15 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_transform_values_basic() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
 
-        let mut mapping = HashMap::new();
+        let mut mapping = IndexMap::new();
         mapping.insert("1".to_string(), Value::String("one".to_string()));
         mapping.insert("2".to_string(), Value::String("two".to_string()));
 
@@ -92,7 +92,7 @@ mod tests {
     fn test_transform_values_array() {
         let arr = vec![Value::Int(1), Value::Int(2), Value::Int(3)];
 
-        let mut mapping = HashMap::new();
+        let mut mapping = IndexMap::new();
         mapping.insert("2".to_string(), Value::String("two".to_string()));
 
         let args = vec![Value::Array(arr), Value::Object(mapping)];
@@ -109,14 +109,14 @@ mod tests {
 
     #[test]
     fn test_transform_values_nested() {
-        let mut inner_obj = HashMap::new();
+        let mut inner_obj = IndexMap::new();
         inner_obj.insert("x".to_string(), Value::Int(1));
 
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("nested".to_string(), Value::Object(inner_obj));
         obj.insert("value".to_string(), Value::Int(2));
 
-        let mut mapping = HashMap::new();
+        let mut mapping = IndexMap::new();
         mapping.insert("1".to_string(), Value::String("one".to_string()));
 
         let args = vec![Value::Object(obj), Value::Object(mapping)];
@@ -136,10 +136,10 @@ mod tests {
 
     #[test]
     fn test_transform_values_no_mapping() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
 
-        let mapping = HashMap::new();
+        let mapping = IndexMap::new();
 
         let args = vec![Value::Object(obj.clone()), Value::Object(mapping)];
         let result = builtin_transform_values(&args).unwrap();
@@ -168,7 +168,7 @@ mod tests {
 
     #[test]
     fn test_transform_values_invalid_mapping() {
-        let obj = HashMap::new();
+        let obj = IndexMap::new();
         let args = vec![
             Value::Object(obj),
             Value::String("not an object".to_string()),
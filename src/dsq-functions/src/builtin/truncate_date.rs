@@ -1,5 +1,5 @@
-use chrono::{Datelike, NaiveDate, TimeZone, Timelike, Utc};
-use dsq_shared::value::Value;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use dsq_shared::value::{local_datetime, utc_datetime, Value};
 use dsq_shared::Result;
 use inventory;
 
@@ -10,7 +10,6 @@ pub fn builtin_truncate_date(args: &[Value]) -> Result<Value> {
         ));
     }
 
-    let dt = crate::extract_timestamp(&args[0])?;
     let unit = match &args[1] {
         Value::String(s) => s.as_str(),
         _ => {
@@ -20,54 +19,48 @@ pub fn builtin_truncate_date(args: &[Value]) -> Result<Value> {
         }
     };
 
-    let truncated = match unit {
-        "year" => {
-            let date = NaiveDate::from_ymd_opt(dt.year(), 1, 1)
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid date"))?;
-            let datetime = date
-                .and_hms_opt(0, 0, 0)
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid time"))?;
-            Utc.from_utc_datetime(&datetime)
+    match &args[0] {
+        Value::Date(date) => {
+            let truncated = truncate(date.and_time(NaiveTime::MIN), unit)?;
+            Ok(Value::Date(truncated.date()))
         }
-        "month" => {
-            let date = NaiveDate::from_ymd_opt(dt.year(), dt.month(), 1)
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid date"))?;
-            let datetime = date
-                .and_hms_opt(0, 0, 0)
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid time"))?;
-            Utc.from_utc_datetime(&datetime)
+        // Truncate the wall-clock time in the datetime's own zone
+        Value::Datetime { datetime, tz } => {
+            let local = local_datetime(*datetime, tz.as_deref());
+            let truncated = truncate(local, unit)?;
+            let datetime = utc_datetime(truncated, tz.as_deref()).ok_or_else(|| {
+                dsq_shared::error::operation_error("truncate_date() result is not a valid time")
+            })?;
+            Ok(Value::Datetime {
+                datetime,
+                tz: tz.clone(),
+            })
         }
-        "day" => {
-            let date = NaiveDate::from_ymd_opt(dt.year(), dt.month(), dt.day())
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid date"))?;
-            let datetime = date
-                .and_hms_opt(0, 0, 0)
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid time"))?;
-            Utc.from_utc_datetime(&datetime)
-        }
-        "hour" => {
-            let date = NaiveDate::from_ymd_opt(dt.year(), dt.month(), dt.day())
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid date"))?;
-            let datetime = date
-                .and_hms_opt(dt.hour(), 0, 0)
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid time"))?;
-            Utc.from_utc_datetime(&datetime)
-        }
-        "minute" => {
-            let date = NaiveDate::from_ymd_opt(dt.year(), dt.month(), dt.day())
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid date"))?;
-            let datetime = date
-                .and_hms_opt(dt.hour(), dt.minute(), 0)
-                .ok_or_else(|| dsq_shared::error::operation_error("Invalid time"))?;
-            Utc.from_utc_datetime(&datetime)
+        value => {
+            let dt = crate::extract_timestamp(value)?;
+            let truncated = truncate(dt.naive_utc(), unit)?;
+            Ok(Value::Int(truncated.and_utc().timestamp()))
         }
+    }
+}
+
+fn truncate(dt: NaiveDateTime, unit: &str) -> Result<NaiveDateTime> {
+    let (month, day, hour, minute) = match unit {
+        "year" => (1, 1, 0, 0),
+        "month" => (dt.month(), 1, 0, 0),
+        "day" => (dt.month(), dt.day(), 0, 0),
+        "hour" => (dt.month(), dt.day(), dt.hour(), 0),
+        "minute" => (dt.month(), dt.day(), dt.hour(), dt.minute()),
         _ => {
             return Err(dsq_shared::error::operation_error(
                 "truncate_date() invalid unit",
             ));
         }
     };
-    Ok(Value::Int(truncated.timestamp()))
+    let date = NaiveDate::from_ymd_opt(dt.year(), month, day)
+        .ok_or_else(|| dsq_shared::error::operation_error("Invalid date"))?;
+    date.and_hms_opt(hour, minute, 0)
+        .ok_or_else(|| dsq_shared::error::operation_error("Invalid time"))
 }
 
 inventory::submit! {
@@ -76,3 +69,53 @@ inventory::submit! {
         func: builtin_truncate_date,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_truncate_date_timestamp() {
+        let result = builtin_truncate_date(&[
+            Value::String("2024-03-15T10:45:30Z".to_string()),
+            Value::String("month".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Int(datetime("2024-03-01 00:00:00").and_utc().timestamp())
+        );
+    }
+
+    #[test]
+    fn test_truncate_date_keeps_temporal_types() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let result =
+            builtin_truncate_date(&[Value::date(date), Value::String("year".to_string())]).unwrap();
+        assert_eq!(
+            result,
+            Value::date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+        );
+
+        // 2024-03-15 23:30 UTC is already the 16th in Tokyo
+        let result = builtin_truncate_date(&[
+            Value::datetime(datetime("2024-03-15 23:30:00"), Some("Asia/Tokyo")),
+            Value::String("day".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::datetime(datetime("2024-03-15 15:00:00"), Some("Asia/Tokyo"))
+        );
+    }
+
+    #[test]
+    fn test_truncate_date_invalid_unit() {
+        let result = builtin_truncate_date(&[Value::Int(0), Value::String("week".to_string())]);
+        assert!(result.is_err());
+    }
+}
@@ -10,7 +10,7 @@ pub fn builtin_truncate_time(args: &[Value]) -> Result<Value> {
         ));
     }
 
-    let dt = crate::extract_timestamp(&args[0])?;
+    let dt = crate::extract_local_timestamp(&args[0])?;
     let unit = match &args[1] {
         Value::String(s) => s.as_str(),
         _ => {
@@ -77,6 +77,7 @@ pub fn builtin_truncate_time(args: &[Value]) -> Result<Value> {
             ));
         }
     };
+    let truncated = crate::local_to_instant(&args[0], truncated)?;
     Ok(Value::String(truncated.to_rfc3339()))
 }
 
@@ -64,7 +64,7 @@ mod tests {
 
     #[test]
     fn test_builtin_type_object() {
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = indexmap::IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
         let result = builtin_type(&[Value::Object(obj)]).unwrap();
         assert_eq!(result, Value::String("object".to_string()));
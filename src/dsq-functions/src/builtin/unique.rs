@@ -56,7 +56,7 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_builtin_unique_array() {
@@ -126,11 +126,11 @@ mod tests {
 
     #[test]
     fn test_builtin_unique_array_objects() {
-        let mut obj1 = HashMap::new();
+        let mut obj1 = IndexMap::new();
         obj1.insert("x".to_string(), Value::Int(1));
-        let mut obj2 = HashMap::new();
+        let mut obj2 = IndexMap::new();
         obj2.insert("x".to_string(), Value::Int(2));
-        let mut obj3 = HashMap::new();
+        let mut obj3 = IndexMap::new();
         obj3.insert("x".to_string(), Value::Int(1)); // duplicate
 
         let arr = vec![
@@ -1,12 +1,12 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 use crate::inventory;
 use crate::FunctionRegistration;
 
-fn flatten_object(obj: &HashMap<String, Value>, separator: &str) -> HashMap<String, Value> {
-    let mut result = HashMap::new();
+fn flatten_object(obj: &IndexMap<String, Value>, separator: &str) -> IndexMap<String, Value> {
+    let mut result = IndexMap::new();
 
     for (key, value) in obj {
         match value {
@@ -66,17 +66,17 @@ inventory::submit! {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_builtin_unnest_nested_object() {
-        let mut inner = HashMap::new();
+        let mut inner = IndexMap::new();
         inner.insert("c".to_string(), Value::String("value".to_string()));
 
-        let mut middle = HashMap::new();
+        let mut middle = IndexMap::new();
         middle.insert("b".to_string(), Value::Object(inner));
 
-        let mut outer = HashMap::new();
+        let mut outer = IndexMap::new();
         outer.insert("a".to_string(), Value::Object(middle));
 
         let result = builtin_unnest(&[Value::Object(outer)]).unwrap();
@@ -94,10 +94,10 @@ mod tests {
 
     #[test]
     fn test_builtin_unnest_custom_separator() {
-        let mut inner = HashMap::new();
+        let mut inner = IndexMap::new();
         inner.insert("y".to_string(), Value::Int(42));
 
-        let mut outer = HashMap::new();
+        let mut outer = IndexMap::new();
         outer.insert("x".to_string(), Value::Object(inner));
         outer.insert("z".to_string(), Value::String("test".to_string()));
 
@@ -132,7 +132,7 @@ mod tests {
     #[test]
     fn test_builtin_unnest_error_too_many_args() {
         let result = builtin_unnest(&[
-            Value::Object(HashMap::new()),
+            Value::Object(IndexMap::new()),
             Value::String(".".to_string()),
             Value::Int(1),
         ]);
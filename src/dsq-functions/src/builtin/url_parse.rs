@@ -1,10 +1,10 @@
 use dsq_shared::value::Value;
 use dsq_shared::Result;
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
 use serde_json;
 use std::borrow::Cow;
-use std::collections::HashMap;
 use url::Url;
 
 pub fn builtin_url_parse(args: &[Value]) -> Result<Value> {
@@ -17,7 +17,7 @@ pub fn builtin_url_parse(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::String(s) => match Url::parse(s) {
             Ok(url) => {
-                let mut result = HashMap::new();
+                let mut result = IndexMap::new();
                 result.insert(
                     "scheme".to_string(),
                     Value::String(url.scheme().to_string()),
@@ -90,7 +90,7 @@ pub fn builtin_url_parse(args: &[Value]) -> Result<Value> {
                             .apply(|s| {
                                 s.and_then(|s| match Url::parse(s) {
                                     Ok(url) => {
-                                        let mut result = HashMap::new();
+                                        let mut result = IndexMap::new();
                                         result.insert(
                                             "scheme".to_string(),
                                             Value::String(url.scheme().to_string()),
@@ -172,7 +172,7 @@ pub fn builtin_url_parse(args: &[Value]) -> Result<Value> {
                     .apply(|s| {
                         s.and_then(|s| match Url::parse(s) {
                             Ok(url) => {
-                                let mut result = HashMap::new();
+                                let mut result = IndexMap::new();
                                 result.insert(
                                     "scheme".to_string(),
                                     Value::String(url.scheme().to_string()),
@@ -2,10 +2,10 @@ use dsq_shared::{
     value::{value_from_any_value, Value},
     Result,
 };
+use indexmap::IndexMap;
 use inventory;
 use polars::prelude::*;
 use serde_json;
-use std::collections::HashMap;
 
 use crate::FunctionRegistration;
 
@@ -57,9 +57,9 @@ pub fn builtin_value_counts(args: &[Value]) -> Result<Value> {
                 return Ok(Value::DataFrame(DataFrame::empty()));
             }
 
-            let mut counts: HashMap<String, (Value, usize)> = HashMap::new();
+            let mut counts: IndexMap<String, (Value, usize)> = IndexMap::new();
             let mut value_order: Vec<Value> = Vec::new();
-            let mut seen: HashMap<String, bool> = HashMap::new();
+            let mut seen: IndexMap<String, bool> = IndexMap::new();
 
             for val in arr {
                 let key = serde_json::to_string(val).unwrap_or_default();
@@ -118,9 +118,9 @@ pub fn builtin_value_counts(args: &[Value]) -> Result<Value> {
                 dsq_shared::error::operation_error(format!("Failed to get first column: {}", e))
             })?;
 
-            let mut counts: HashMap<String, usize> = HashMap::new();
+            let mut counts: IndexMap<String, usize> = IndexMap::new();
             let mut value_order: Vec<Value> = Vec::new();
-            let mut seen: HashMap<String, bool> = HashMap::new();
+            let mut seen: IndexMap<String, bool> = IndexMap::new();
 
             for i in 0..series.len() {
                 if let Ok(val) = series.get(i) {
@@ -168,9 +168,9 @@ pub fn builtin_value_counts(args: &[Value]) -> Result<Value> {
                 return Ok(Value::DataFrame(DataFrame::empty()));
             }
 
-            let mut counts: HashMap<String, usize> = HashMap::new();
+            let mut counts: IndexMap<String, usize> = IndexMap::new();
             let mut value_order: Vec<Value> = Vec::new();
-            let mut seen: HashMap<String, bool> = HashMap::new();
+            let mut seen: IndexMap<String, bool> = IndexMap::new();
 
             for i in 0..series.len() {
                 if let Ok(val) = series.get(i) {
@@ -29,11 +29,11 @@ pub fn builtin_values(args: &[Value]) -> Result<Value> {
 mod tests {
     use super::*;
     use dsq_shared::value::Value;
-    use std::collections::HashMap;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_builtin_values_object() {
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::String("hello".to_string()));
         obj.insert("c".to_string(), Value::Bool(true));
@@ -51,7 +51,7 @@ mod tests {
 
     #[test]
     fn test_builtin_values_empty_object() {
-        let obj = HashMap::new();
+        let obj = IndexMap::new();
         let obj_val = Value::Object(obj);
         let result = builtin_values(&[obj_val]).unwrap();
         if let Value::Array(values) = result {
@@ -132,7 +132,7 @@ mod tests {
     #[test]
     fn test_builtin_values_nested_structures() {
         // Object with nested array
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         let nested_arr = vec![Value::Int(1), Value::Int(2)];
         obj.insert("arr".to_string(), Value::Array(nested_arr));
         obj.insert("num".to_string(), Value::Int(3));
@@ -37,7 +37,7 @@ pub fn builtin_var(args: &[Value]) -> Result<Value> {
             Ok(Value::Float(variance))
         }
         Value::DataFrame(df) => {
-            let mut vars = std::collections::HashMap::new();
+            let mut vars = indexmap::IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(series) = df.column(col_name) {
                     if series.dtype().is_numeric() {
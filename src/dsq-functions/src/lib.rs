@@ -8,7 +8,7 @@ pub mod builtin;
 // Re-export inventory for use by builtin modules
 pub use inventory;
 
-use dsq_shared::value::{value_from_any_value, Value};
+use dsq_shared::value::{local_datetime, utc_datetime, value_from_any_value, Value};
 use dsq_shared::Result;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -108,9 +108,36 @@ impl Default for BuiltinRegistry {
     }
 }
 
+/// Wall-clock time of `value` in its own time zone, for reading calendar
+/// fields such as the hour or the month
+///
+/// Zoned datetimes are converted from the UTC instant they hold; other values
+/// are read as by `extract_timestamp`.
+pub(crate) fn extract_local_timestamp(value: &Value) -> Result<DateTime<Utc>> {
+    match value {
+        Value::Datetime { datetime, tz } => Ok(local_datetime(*datetime, tz.as_deref()).and_utc()),
+        _ => extract_timestamp(value),
+    }
+}
+
+/// UTC instant of a wall-clock time computed from
+/// `extract_local_timestamp(value)`, in the time zone of `value`
+pub(crate) fn local_to_instant(value: &Value, local: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    match value {
+        Value::Datetime { tz, .. } => utc_datetime(local.naive_utc(), tz.as_deref())
+            .map(|datetime| datetime.and_utc())
+            .ok_or_else(|| dsq_shared::error::operation_error("Invalid time in time zone")),
+        _ => Ok(local),
+    }
+}
+
 // Helper function to extract DateTime from various input types
 pub(crate) fn extract_timestamp(value: &Value) -> Result<DateTime<Utc>> {
     match value {
+        Value::Date(date) => Ok(date.and_time(chrono::NaiveTime::MIN).and_utc()),
+        // Zoned datetimes already hold the UTC instant; naive ones are
+        // taken as UTC like naive strings below
+        Value::Datetime { datetime, .. } => Ok(datetime.and_utc()),
         Value::Int(i) => Utc
             .timestamp_opt(*i, 0)
             .single()
@@ -218,7 +245,8 @@ pub fn compare_values_for_sorting(a: &Value, b: &Value) -> std::cmp::Ordering {
         (Value::Float(a_val), Value::Int(b_val)) => a_val
             .partial_cmp(&(*b_val as f64))
             .unwrap_or(std::cmp::Ordering::Equal),
-        _ => std::cmp::Ordering::Equal,
+        // Dates, durations, decimals and the like
+        _ => dsq_shared::ops::compare_values(a, b).unwrap_or(std::cmp::Ordering::Equal),
     }
 }
 
@@ -238,7 +266,7 @@ fn builtin_dtypes(args: &[Value]) -> Result<Value> {
 
     match &args[0] {
         Value::DataFrame(df) => {
-            let mut dtypes_obj = HashMap::new();
+            let mut dtypes_obj = indexmap::IndexMap::new();
             for col_name in df.get_column_names() {
                 if let Ok(series) = df.column(col_name) {
                     dtypes_obj.insert(
@@ -253,7 +281,7 @@ fn builtin_dtypes(args: &[Value]) -> Result<Value> {
             let schema = lf.clone().collect_schema().map_err(|e| {
                 dsq_shared::error::operation_error(format!("Failed to get schema: {}", e))
             })?;
-            let mut dtypes_obj = HashMap::new();
+            let mut dtypes_obj = indexmap::IndexMap::new();
             for (name, dtype) in schema.iter() {
                 dtypes_obj.insert(name.to_string(), Value::String(dtype.to_string()));
             }
@@ -347,9 +375,9 @@ mod tests {
     use crate::builtin::transliterate::builtin_transliterate;
     use chrono::Datelike;
     use dsq_shared::value::Value;
+    use indexmap::IndexMap;
     use polars::datatypes::PlSmallStr;
     use polars::prelude::*;
-    use std::collections::HashMap;
 
     fn create_test_dataframe() -> DataFrame {
         let names = Series::new(PlSmallStr::from("name"), &["Alice", "Bob", "Charlie"]);
@@ -380,6 +408,49 @@ mod tests {
         assert_eq!(dt.day(), 1);
     }
 
+    #[test]
+    fn test_zoned_datetime_fields() {
+        let registry = BuiltinRegistry::new();
+        let utc = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        // 2024-04-01 08:30 in Tokyo
+        let value = Value::datetime(utc("2024-03-31 23:30:00"), Some("Asia/Tokyo"));
+        let call = |name: &str| registry.call_function(name, &[value.clone()]).unwrap();
+
+        assert_eq!(call("hour"), Value::Int(8));
+        assert_eq!(call("day"), Value::Int(1));
+        assert_eq!(call("month"), Value::Int(4));
+        assert_eq!(call("year"), Value::Int(2024));
+        assert_eq!(call("start_of_month"), Value::string("2024-04-01"));
+        assert_eq!(
+            call("end_of_month"),
+            Value::Int(utc("2024-04-30 14:59:59").and_utc().timestamp())
+        );
+
+        // The same fields truncate_date works on
+        let day = registry
+            .call_function("truncate_date", &[value.clone(), Value::string("day")])
+            .unwrap();
+        assert_eq!(
+            day,
+            Value::datetime(utc("2024-03-31 15:00:00"), Some("Asia/Tokyo"))
+        );
+        let day = registry
+            .call_function("truncate_time", &[value.clone(), Value::string("day")])
+            .unwrap();
+        assert_eq!(day, Value::string("2024-03-31T15:00:00+00:00"));
+
+        // Instants are unaffected by the zone
+        let naive = Value::datetime(utc("2024-03-31 23:30:00"), None::<String>);
+        assert_eq!(
+            extract_timestamp(&value).unwrap(),
+            extract_timestamp(&naive).unwrap()
+        );
+        assert_eq!(
+            registry.call_function("hour", &[naive]).unwrap(),
+            Value::Int(23)
+        );
+    }
+
     #[test]
     fn test_start_of_month() {
         let registry = BuiltinRegistry::new();
@@ -1116,7 +1187,7 @@ mod tests {
         assert_eq!(result, Value::Int(0));
 
         // Test Object
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         let obj_val = Value::Object(obj);
@@ -1260,7 +1331,7 @@ mod tests {
     fn test_del_function() {
         let registry = BuiltinRegistry::new();
 
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         obj.insert("c".to_string(), Value::Int(3));
@@ -1605,7 +1676,7 @@ mod tests {
     #[test]
     fn test_builtin_select_single_arg_extended() {
         // Test with object
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
         let result = builtin_select(&[Value::Object(obj.clone())]).unwrap();
         assert_eq!(result, Value::Object(obj));
@@ -1628,7 +1699,7 @@ mod tests {
     fn test_builtin_select_two_args_extended() {
         use polars::prelude::*;
         // Test with object input
-        let mut obj = std::collections::HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
         let result = builtin_select(&[Value::Object(obj.clone()), Value::Bool(true)]).unwrap();
         assert_eq!(result, Value::Object(obj.clone()));
@@ -2576,7 +2647,7 @@ mod tests {
         assert_eq!(result, Value::Int(11)); // Character count, not byte count
 
         // Test with objects
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         let obj_val = Value::Object(obj);
@@ -2614,7 +2685,7 @@ mod tests {
         let registry = BuiltinRegistry::new();
 
         // Test object keys
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("b".to_string(), Value::Int(2));
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("c".to_string(), Value::Int(3));
@@ -2649,7 +2720,7 @@ mod tests {
         let registry = BuiltinRegistry::new();
 
         // Test object values
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("a".to_string(), Value::Int(1));
         obj.insert("b".to_string(), Value::Int(2));
         let obj_val = Value::Object(obj);
@@ -2693,7 +2764,7 @@ mod tests {
         let result = registry.call_function("type", &[arr]).unwrap();
         assert_eq!(result, Value::String("array".to_string()));
 
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key".to_string(), Value::Int(1));
         let obj_val = Value::Object(obj);
         let result = registry.call_function("type", &[obj_val]).unwrap();
@@ -2715,12 +2786,12 @@ mod tests {
         assert_eq!(result, Value::Bool(false));
 
         // Test empty object
-        let empty_obj = Value::Object(HashMap::new());
+        let empty_obj = Value::Object(IndexMap::new());
         let result = registry.call_function("empty", &[empty_obj]).unwrap();
         assert_eq!(result, Value::Bool(true));
 
         // Test non-empty object
-        let mut obj = HashMap::new();
+        let mut obj = IndexMap::new();
         obj.insert("key".to_string(), Value::Int(1));
         let obj_val = Value::Object(obj);
         let result = registry.call_function("empty", &[obj_val]).unwrap();
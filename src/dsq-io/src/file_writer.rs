@@ -1,6 +1,7 @@
 use crate::{Error, Result};
 use dsq_formats::{
-    format::DataFormat, serialize, FormatWriteOptions, ParquetCompression, WriteOptions,
+    format::DataFormat, serialize, CompressedWriter, Compression, FormatWriteOptions,
+    ParquetCompression, WriteOptions,
 };
 use dsq_shared::value::Value;
 use polars::prelude::*;
@@ -121,8 +122,16 @@ impl DataWriter for FileWriter {
 
         self.check_overwrite(options)?;
         let file = File::create(&self.path)?;
-        let writer = BufWriter::new(file);
-        serialize(writer, value, self.format, options, &self.format_options)?;
+        let compression = Compression::from_path(Path::new(&self.path));
+        let mut writer = CompressedWriter::new(BufWriter::new(file), compression)?;
+        serialize(
+            &mut writer,
+            value,
+            self.format,
+            options,
+            &self.format_options,
+        )?;
+        writer.finish()?;
         Ok(())
     }
 
@@ -1,4 +1,3 @@
-pub mod arrow;
 pub mod avro;
 pub mod csv;
 pub mod json;
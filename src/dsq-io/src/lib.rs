@@ -317,4 +317,30 @@ mod tests {
 
         assert_eq!(read_data, test_data);
     }
+
+    #[test]
+    fn test_file_writer_compresses_by_extension() {
+        use polars::prelude::*;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("people.csv.zst");
+        let value = dsq_shared::value::Value::DataFrame(
+            df! { "name" => ["Ada", "Grace"], "age" => [36i64, 45] }.unwrap(),
+        );
+        to_path(&path)
+            .unwrap()
+            .write(&value, &WriteOptions::default())
+            .unwrap();
+
+        let bytes = read_file_sync(&path).unwrap();
+        assert_eq!(
+            dsq_formats::Compression::detect(&bytes),
+            Some(dsq_formats::Compression::Zstd)
+        );
+        let csv = dsq_formats::compression::decompress(bytes).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "name,age\nAda,36\nGrace,45\n"
+        );
+    }
 }
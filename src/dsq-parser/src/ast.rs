@@ -8,10 +8,84 @@ use std::fmt;
 /// Represents a complete DSQ filter expression (possibly a pipeline)
 #[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub struct Filter {
+    /// Module directives (`import`/`include`) preceding the expression
+    pub directives: Vec<ModuleDirective>,
     /// The root expression of the filter
     pub expr: Expr,
 }
 
+/// A library module: directives followed by function definitions
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Module {
+    /// Metadata object from a leading `module {...};` line
+    pub metadata: Option<Expr>,
+    /// Module directives (`import`/`include`)
+    pub directives: Vec<ModuleDirective>,
+    /// Functions defined by the module, in definition order
+    pub functions: Vec<FunctionDefinition>,
+}
+
+/// Top-level function definition of a module
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FunctionDefinition {
+    /// Function name
+    pub name: String,
+    /// Parameters
+    pub params: Vec<FunctionParam>,
+    /// Function body
+    pub body: Expr,
+}
+
+/// Directive loading definitions or data from another module
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum ModuleDirective {
+    /// `import "path" as name;` makes the module's functions available as `name::fn`
+    Import {
+        /// Relative module path
+        path: String,
+        /// Namespace for the imported functions
+        alias: String,
+        /// Optional metadata object (e.g. `{search: "./lib"}`)
+        metadata: Option<Expr>,
+    },
+    /// `import "path" as $name;` binds a JSON/CSV data file to `$name` and `$name::name`
+    ImportData {
+        /// Relative data file path
+        path: String,
+        /// Variable name for the data
+        alias: String,
+        /// Optional metadata object
+        metadata: Option<Expr>,
+    },
+    /// `include "path";` makes the module's functions available unqualified
+    Include {
+        /// Relative module path
+        path: String,
+        /// Optional metadata object
+        metadata: Option<Expr>,
+    },
+}
+
+impl ModuleDirective {
+    /// The relative path of the module being loaded
+    pub fn path(&self) -> &str {
+        match self {
+            ModuleDirective::Import { path, .. }
+            | ModuleDirective::ImportData { path, .. }
+            | ModuleDirective::Include { path, .. } => path,
+        }
+    }
+
+    /// The metadata object attached to the directive, if any
+    pub fn metadata(&self) -> Option<&Expr> {
+        match self {
+            ModuleDirective::Import { metadata, .. }
+            | ModuleDirective::ImportData { metadata, .. }
+            | ModuleDirective::Include { metadata, .. } => metadata.as_ref(),
+        }
+    }
+}
+
 /// Core expression types in DSQ
 #[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub enum Expr {
@@ -95,6 +169,18 @@ pub enum Expr {
     /// Literal value
     Literal(Literal),
 
+    /// String with interpolations (`"text \(expr)"`), optionally rendered
+    /// through a format (`@csv "text \(expr)"`)
+    FormatString {
+        /// Format applied to each interpolated value (`csv` for `@csv`)
+        format: Option<String>,
+        /// Literal text and interpolated expressions, in order
+        parts: Vec<StringPart>,
+    },
+
+    /// Format applied to the input (`@csv`, `@base64`, ...)
+    Format(String),
+
     /// Identifier (bare word, could be function name or variable)
     Identifier(String),
 
@@ -119,6 +205,122 @@ pub enum Expr {
 
     /// Sequence (expr1, expr2, ...)
     Sequence(Vec<Expr>),
+
+    /// Error handling (`expr?`, or `try expr catch handler`)
+    Try {
+        /// Expression whose errors are caught
+        body: Box<Expr>,
+        /// Handler run on error; errors are suppressed when absent
+        catch: Option<Box<Expr>>,
+    },
+
+    /// Reduce expression (reduce source as $var (init; update))
+    Reduce {
+        /// Expression producing the values to fold over
+        source: Box<Expr>,
+        /// Variable bound to each value
+        variable: String,
+        /// Initial accumulator expression
+        init: Box<Expr>,
+        /// Update expression, evaluated with the accumulator as input
+        update: Box<Expr>,
+    },
+
+    /// Foreach expression (foreach source as $var (init; update; extract))
+    Foreach {
+        /// Expression producing the values to iterate over
+        source: Box<Expr>,
+        /// Variable bound to each value
+        variable: String,
+        /// Initial state expression
+        init: Box<Expr>,
+        /// Update expression, evaluated with the state as input
+        update: Box<Expr>,
+        /// Extract expression applied to each intermediate state (defaults to `.`)
+        extract: Option<Box<Expr>>,
+    },
+
+    /// Variable binding (source as $name | body)
+    Bind {
+        /// Expression producing the value(s) to bind
+        source: Box<Expr>,
+        /// Destructuring patterns, tried in order (`?//` alternatives)
+        patterns: Vec<Pattern>,
+        /// Expression evaluated with the bindings in scope
+        body: Box<Expr>,
+    },
+
+    /// Label for early exits (label $name | body)
+    Label {
+        /// Label name (without the `$`)
+        name: String,
+        /// Expression that `break $name` exits from
+        body: Box<Expr>,
+    },
+
+    /// Exit to the enclosing label (break $name)
+    Break(String),
+
+    /// Function definition (def name(params): body; rest)
+    Def {
+        /// Function name
+        name: String,
+        /// Parameters, either filters (`f`) or values (`$x`)
+        params: Vec<FunctionParam>,
+        /// Function body
+        body: Box<Expr>,
+        /// Expression in which the function is visible
+        rest: Box<Expr>,
+    },
+}
+
+/// Parameter of a user-defined function
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum FunctionParam {
+    /// Filter parameter (`f`), evaluated lazily against the input at each use
+    Filter(String),
+    /// Value parameter (`$x`), evaluated once and bound as `$x` (and `x`)
+    Value(String),
+}
+
+impl FunctionParam {
+    /// The parameter name without any `$` sigil
+    pub fn name(&self) -> &str {
+        match self {
+            FunctionParam::Filter(name) | FunctionParam::Value(name) => name,
+        }
+    }
+}
+
+/// Destructuring pattern used by `as` bindings
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum Pattern {
+    /// Bind the whole value ($name)
+    Variable(String),
+    /// Destructure an array by position ([$a, $b])
+    Array(Vec<Pattern>),
+    /// Destructure an object by key ({name: $n, $id})
+    Object(Vec<ObjectPatternEntry>),
+}
+
+/// Entry of an object destructuring pattern
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ObjectPatternEntry {
+    /// Key to look up
+    pub key: PatternKey,
+    /// Pattern for the value (required unless the key is a variable)
+    pub pattern: Option<Pattern>,
+}
+
+/// Key of an object destructuring entry
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum PatternKey {
+    /// `$name` - looks up `name` and binds it to `$name`
+    Variable(String),
+    /// Identifier or string key
+    Literal(String),
+    /// Computed key (`(expr)`)
+    Expr(Box<Expr>),
 }
 
 /// Binary operators
@@ -148,6 +350,8 @@ pub enum BinaryOperator {
     And,
     /// Logical OR (or)
     Or,
+    /// Alternative (//): the left value unless it is null, false or an error
+    Alternative,
 }
 
 /// Assignment operators
@@ -182,6 +386,15 @@ pub enum ObjectEntry {
     Shorthand(String),
 }
 
+/// A piece of an interpolated string
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum StringPart {
+    /// Literal text
+    Literal(String),
+    /// Interpolated expression (`\(expr)`)
+    Interpolation(Expr),
+}
+
 /// Order by specification
 #[derive(Debug, Clone, PartialEq, serde::Serialize)]
 pub struct OrderBy {
@@ -219,10 +432,31 @@ pub enum Literal {
 
 impl fmt::Display for Filter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for directive in &self.directives {
+            write!(f, "{} ", directive)?;
+        }
         write!(f, "{}", self.expr)
     }
 }
 
+impl fmt::Display for ModuleDirective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleDirective::Import { path, alias, .. } => {
+                write!(f, "import {:?} as {}", path, alias)?
+            }
+            ModuleDirective::ImportData { path, alias, .. } => {
+                write!(f, "import {:?} as ${}", path, alias)?
+            }
+            ModuleDirective::Include { path, .. } => write!(f, "include {:?}", path)?,
+        }
+        if let Some(metadata) = self.metadata() {
+            write!(f, " {}", metadata)?;
+        }
+        write!(f, ";")
+    }
+}
+
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -292,6 +526,20 @@ impl fmt::Display for Expr {
                 write!(f, "]")
             }
             Expr::Literal(lit) => write!(f, "{}", lit),
+            Expr::FormatString { format, parts } => {
+                if let Some(format) = format {
+                    write!(f, "@{} ", format)?;
+                }
+                write!(f, "\"")?;
+                for part in parts {
+                    match part {
+                        StringPart::Literal(text) => write!(f, "{}", text.escape_default())?,
+                        StringPart::Interpolation(expr) => write!(f, "\\({})", expr)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Expr::Format(format) => write!(f, "@{}", format),
             Expr::Identifier(name) => write!(f, "{}", name),
             Expr::Variable(name) => write!(f, "${}", name),
             Expr::Paren(expr) => write!(f, "({})", expr),
@@ -324,6 +572,116 @@ impl fmt::Display for Expr {
                 }
                 Ok(())
             }
+            Expr::Try { body, catch } => match catch {
+                Some(catch) => write!(f, "try {} catch {}", body, catch),
+                None => write!(f, "{}?", body),
+            },
+            Expr::Reduce {
+                source,
+                variable,
+                init,
+                update,
+            } => write!(
+                f,
+                "reduce {} as ${} ({}; {})",
+                source, variable, init, update
+            ),
+            Expr::Foreach {
+                source,
+                variable,
+                init,
+                update,
+                extract,
+            } => {
+                write!(
+                    f,
+                    "foreach {} as ${} ({}; {}",
+                    source, variable, init, update
+                )?;
+                if let Some(extract) = extract {
+                    write!(f, "; {}", extract)?;
+                }
+                write!(f, ")")
+            }
+            Expr::Bind {
+                source,
+                patterns,
+                body,
+            } => {
+                write!(f, "{} as ", source)?;
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ?// ")?;
+                    }
+                    write!(f, "{}", pattern)?;
+                }
+                write!(f, " | {}", body)
+            }
+            Expr::Label { name, body } => write!(f, "label ${} | {}", name, body),
+            Expr::Break(name) => write!(f, "break ${}", name),
+            Expr::Def {
+                name,
+                params,
+                body,
+                rest,
+            } => {
+                write!(f, "def {}", name)?;
+                if !params.is_empty() {
+                    write!(f, "(")?;
+                    for (i, param) in params.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, "; ")?;
+                        }
+                        write!(f, "{}", param)?;
+                    }
+                    write!(f, ")")?;
+                }
+                write!(f, ": {}; {}", body, rest)
+            }
+        }
+    }
+}
+
+impl fmt::Display for FunctionParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FunctionParam::Filter(name) => write!(f, "{}", name),
+            FunctionParam::Value(name) => write!(f, "${}", name),
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Variable(name) => write!(f, "${}", name),
+            Pattern::Array(elements) => {
+                write!(f, "[")?;
+                for (i, elem) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", elem)?;
+                }
+                write!(f, "]")
+            }
+            Pattern::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, entry) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match &entry.key {
+                        PatternKey::Variable(name) => write!(f, "${}", name)?,
+                        PatternKey::Literal(key) => write!(f, "{}", key)?,
+                        PatternKey::Expr(expr) => write!(f, "({})", expr)?,
+                    }
+                    if let Some(pattern) = &entry.pattern {
+                        write!(f, ": {}", pattern)?;
+                    }
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
@@ -343,6 +701,7 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::Le => write!(f, "<="),
             BinaryOperator::And => write!(f, "and"),
             BinaryOperator::Or => write!(f, "or"),
+            BinaryOperator::Alternative => write!(f, "//"),
         }
     }
 }
@@ -429,6 +788,7 @@ mod tests {
         assert_eq!(format!("{}", BinaryOperator::Le), "<=");
         assert_eq!(format!("{}", BinaryOperator::And), "and");
         assert_eq!(format!("{}", BinaryOperator::Or), "or");
+        assert_eq!(format!("{}", BinaryOperator::Alternative), "//");
     }
 
     #[test]
@@ -479,6 +839,18 @@ mod tests {
         assert_eq!(format!("{}", expr), "obj.field");
     }
 
+    #[test]
+    fn test_expr_display_try() {
+        let expr = Expr::Try {
+            body: Box::new(Expr::ArrayIteration(Box::new(Expr::FieldAccess {
+                base: Box::new(Expr::Identity),
+                fields: vec!["tags".to_string()],
+            }))),
+            catch: None,
+        };
+        assert_eq!(format!("{}", expr), ".tags[]?");
+    }
+
     #[test]
     fn test_expr_display_array_access() {
         let expr = Expr::ArrayAccess {
@@ -701,14 +1073,130 @@ mod tests {
         assert_eq!(format!("{}", expr), ".name, .age");
     }
 
+    #[test]
+    fn test_expr_display_reduce() {
+        let expr = Expr::Reduce {
+            source: Box::new(Expr::ArrayIteration(Box::new(Expr::Identity))),
+            variable: "x".to_string(),
+            init: Box::new(Expr::Literal(Literal::Int(0))),
+            update: Box::new(Expr::BinaryOp {
+                left: Box::new(Expr::Identity),
+                op: BinaryOperator::Add,
+                right: Box::new(Expr::Variable("x".to_string())),
+            }),
+        };
+        assert_eq!(format!("{}", expr), "reduce .[] as $x (0; . + $x)");
+    }
+
+    #[test]
+    fn test_expr_display_foreach() {
+        let expr = Expr::Foreach {
+            source: Box::new(Expr::ArrayIteration(Box::new(Expr::Identity))),
+            variable: "x".to_string(),
+            init: Box::new(Expr::Literal(Literal::Int(0))),
+            update: Box::new(Expr::BinaryOp {
+                left: Box::new(Expr::Identity),
+                op: BinaryOperator::Add,
+                right: Box::new(Expr::Variable("x".to_string())),
+            }),
+            extract: None,
+        };
+        assert_eq!(format!("{}", expr), "foreach .[] as $x (0; . + $x)");
+
+        let expr = Expr::Foreach {
+            source: Box::new(Expr::ArrayIteration(Box::new(Expr::Identity))),
+            variable: "x".to_string(),
+            init: Box::new(Expr::Literal(Literal::Int(0))),
+            update: Box::new(Expr::Variable("x".to_string())),
+            extract: Some(Box::new(Expr::Identity)),
+        };
+        assert_eq!(format!("{}", expr), "foreach .[] as $x (0; $x; .)");
+    }
+
+    #[test]
+    fn test_expr_display_bind() {
+        let expr = Expr::Bind {
+            source: Box::new(Expr::Identity),
+            patterns: vec![
+                Pattern::Object(vec![
+                    ObjectPatternEntry {
+                        key: PatternKey::Literal("name".to_string()),
+                        pattern: Some(Pattern::Variable("n".to_string())),
+                    },
+                    ObjectPatternEntry {
+                        key: PatternKey::Variable("id".to_string()),
+                        pattern: None,
+                    },
+                ]),
+                Pattern::Array(vec![Pattern::Variable("n".to_string())]),
+            ],
+            body: Box::new(Expr::Variable("n".to_string())),
+        };
+        assert_eq!(format!("{}", expr), ". as {name: $n, $id} ?// [$n] | $n");
+    }
+
+    #[test]
+    fn test_expr_display_def() {
+        let expr = Expr::Def {
+            name: "inc".to_string(),
+            params: vec![],
+            body: Box::new(Expr::BinaryOp {
+                left: Box::new(Expr::Identity),
+                op: BinaryOperator::Add,
+                right: Box::new(Expr::Literal(Literal::Int(1))),
+            }),
+            rest: Box::new(Expr::Identifier("inc".to_string())),
+        };
+        assert_eq!(format!("{}", expr), "def inc: . + 1; inc");
+
+        let expr = Expr::Def {
+            name: "f".to_string(),
+            params: vec![
+                FunctionParam::Filter("g".to_string()),
+                FunctionParam::Value("x".to_string()),
+            ],
+            body: Box::new(Expr::Identifier("g".to_string())),
+            rest: Box::new(Expr::Identity),
+        };
+        assert_eq!(format!("{}", expr), "def f(g; $x): g; .");
+    }
+
     #[test]
     fn test_filter_display() {
         let filter = Filter {
+            directives: vec![],
             expr: Expr::Identity,
         };
         assert_eq!(format!("{}", filter), ".");
     }
 
+    #[test]
+    fn test_filter_display_directives() {
+        let filter = Filter {
+            directives: vec![
+                ModuleDirective::Import {
+                    path: "lib/util".to_string(),
+                    alias: "util".to_string(),
+                    metadata: None,
+                },
+                ModuleDirective::ImportData {
+                    path: "codes".to_string(),
+                    alias: "codes".to_string(),
+                    metadata: None,
+                },
+                ModuleDirective::Include {
+                    path: "common".to_string(),
+                    metadata: None,
+                },
+            ],
+            expr: Expr::Identifier("util::f".to_string()),
+        };
+        assert_eq!(
+            format!("{}", filter),
+            r#"import "lib/util" as util; import "codes" as $codes; include "common"; util::f"#
+        );
+    }
+
     #[test]
     fn test_partial_eq() {
         // Test equality
@@ -8,16 +8,16 @@ use nom::{
     bytes::complete::tag,
     character::complete::{char, multispace1},
     combinator::{map, not, opt, peek, verify},
-    multi::{many0, many1, separated_list0},
-    sequence::{delimited, preceded},
+    multi::{many0, many1, separated_list0, separated_list1},
+    sequence::{delimited, preceded, terminated},
     IResult, Parser,
 };
 
-use crate::ast::{Expr, ObjectEntry};
+use crate::ast::{Expr, FunctionParam, ObjectEntry, ObjectPatternEntry, Pattern, PatternKey};
 
-use super::identifiers::{parse_identifier, parse_variable};
-use super::literals::{parse_literal, parse_string_literal};
-use super::operators::parse_assignment;
+use super::identifiers::{parse_identifier, parse_qualified_identifier, parse_variable};
+use super::literals::{parse_literal, parse_plain_string, parse_string_parts, string_expr};
+use super::operators::parse_alternative_expr;
 use super::utils::{keyword, ws, BUILTIN_FUNCTIONS, KEYWORDS};
 
 /// Parse an expression (main expression parser with precedence)
@@ -60,27 +60,148 @@ pub(crate) fn parse_if(input: &str) -> IResult<&str, Expr> {
     .parse(input)
 }
 
-/// Parse try-catch expression
+/// Parse try expression (try body, or try body catch handler)
 pub(crate) fn parse_try(input: &str) -> IResult<&str, Expr> {
     map(
         (
             keyword("try"),
             ws,
+            parse_postfix_expr,
+            opt(preceded((ws, keyword("catch"), ws), parse_postfix_expr)),
+        ),
+        |(_, _, body, catch)| Expr::Try {
+            body: Box::new(body),
+            catch: catch.map(Box::new),
+        },
+    )
+    .parse(input)
+}
+
+/// Parse label expression (label $name | body)
+fn parse_label(input: &str) -> IResult<&str, Expr> {
+    map(
+        (
+            keyword("label"),
+            ws,
+            preceded(char('$'), parse_identifier),
+            delimited(ws, char('|'), ws),
+            parse_pipeline,
+        ),
+        |(_, _, name, _, body)| Expr::Label {
+            name,
+            body: Box::new(body),
+        },
+    )
+    .parse(input)
+}
+
+/// Parse break expression (break $name)
+fn parse_break(input: &str) -> IResult<&str, Expr> {
+    map(
+        preceded((keyword("break"), ws, char('$')), parse_identifier),
+        Expr::Break,
+    )
+    .parse(input)
+}
+
+/// Parse reduce expression (reduce source as $var (init; update))
+pub(crate) fn parse_reduce(input: &str) -> IResult<&str, Expr> {
+    map(
+        (
+            keyword("reduce"),
+            ws,
+            parse_postfix_expr,
+            (ws, keyword("as"), ws),
+            preceded(char('$'), parse_identifier),
+            (ws, char('('), ws),
+            parse_pipeline,
+            delimited(ws, char(';'), ws),
+            parse_pipeline,
+            (ws, char(')')),
+        ),
+        |(_, _, source, _, variable, _, init, _, update, _)| Expr::Reduce {
+            source: Box::new(source),
+            variable,
+            init: Box::new(init),
+            update: Box::new(update),
+        },
+    )
+    .parse(input)
+}
+
+/// Parse foreach expression (foreach source as $var (init; update; extract))
+pub(crate) fn parse_foreach(input: &str) -> IResult<&str, Expr> {
+    map(
+        (
+            keyword("foreach"),
+            ws,
+            parse_postfix_expr,
+            (ws, keyword("as"), ws),
+            preceded(char('$'), parse_identifier),
+            (ws, char('('), ws),
             parse_pipeline,
-            (ws, keyword("catch"), ws),
+            delimited(ws, char(';'), ws),
             parse_pipeline,
+            opt(preceded(delimited(ws, char(';'), ws), parse_pipeline)),
+            (ws, char(')')),
         ),
-        |(_, _, try_expr, _, catch_expr)| Expr::FunctionCall {
-            name: "iferror".to_string(),
-            args: vec![try_expr, catch_expr],
+        |(_, _, source, _, variable, _, init, _, update, extract, _)| Expr::Foreach {
+            source: Box::new(source),
+            variable,
+            init: Box::new(init),
+            update: Box::new(update),
+            extract: extract.map(Box::new),
         },
     )
     .parse(input)
 }
 
+/// Parse function definition header and body (def name(params): body;)
+pub(crate) fn parse_def_header(input: &str) -> IResult<&str, (String, Vec<FunctionParam>, Expr)> {
+    map(
+        (
+            keyword("def"),
+            ws,
+            parse_identifier,
+            ws,
+            opt(delimited(
+                (char('('), ws),
+                separated_list1(
+                    delimited(ws, char(';'), ws),
+                    alt((
+                        map(preceded(char('$'), parse_identifier), FunctionParam::Value),
+                        map(parse_identifier, FunctionParam::Filter),
+                    )),
+                ),
+                (ws, char(')'), ws),
+            )),
+            (char(':'), ws),
+            parse_comma_sequence,
+            (ws, char(';'), ws),
+        ),
+        |(_, _, name, _, params, _, body, _)| (name, params.unwrap_or_default(), body),
+    )
+    .parse(input)
+}
+
+/// Parse a function definition followed by the expression it scopes
+fn parse_def<'a>(
+    rest: fn(&'a str) -> IResult<&'a str, Expr>,
+) -> impl Parser<&'a str, Output = Expr, Error = nom::error::Error<&'a str>> {
+    map((parse_def_header, rest), |((name, params, body), rest)| {
+        Expr::Def {
+            name,
+            params,
+            body: Box::new(body),
+            rest: Box::new(rest),
+        }
+    })
+}
+
 /// Parse comma-separated expressions
 pub(crate) fn parse_comma_sequence(input: &str) -> IResult<&str, Expr> {
     alt((
+        parse_def(parse_comma_sequence),
         map(
             (
                 parse_pipeline,
@@ -103,8 +224,11 @@ pub(crate) fn parse_pipeline(input: &str) -> IResult<&str, Expr> {
         // Pipeline starting with expression: expr | expr | ...
         map(
             (
-                parse_assignment,
-                many0(preceded(delimited(ws, char('|'), ws), parse_assignment)),
+                parse_pipeline_element,
+                many0(preceded(
+                    delimited(ws, char('|'), ws),
+                    parse_pipeline_element,
+                )),
             ),
             |(first, rest)| {
                 let mut exprs = vec![first];
@@ -124,8 +248,11 @@ pub(crate) fn parse_pipeline(input: &str) -> IResult<&str, Expr> {
         map(
             (
                 char('|'),
-                parse_assignment,
-                many0(preceded(delimited(ws, char('|'), ws), parse_assignment)),
+                parse_pipeline_element,
+                many0(preceded(
+                    delimited(ws, char('|'), ws),
+                    parse_pipeline_element,
+                )),
             ),
             |(_, first, rest)| {
                 let mut exprs = vec![first];
@@ -145,12 +272,112 @@ pub(crate) fn parse_pipeline(input: &str) -> IResult<&str, Expr> {
     .parse(input)
 }
 
+/// Parse a single pipeline stage, which may be a binding that scopes the rest
+/// of the pipeline (source as $name | body)
+fn parse_pipeline_element(input: &str) -> IResult<&str, Expr> {
+    if let Ok(result) = parse_def(parse_pipeline).parse(input) {
+        return Ok(result);
+    }
+    if let Ok(result) = parse_label(input) {
+        return Ok(result);
+    }
+    let (rest, expr) = parse_alternative_expr(input)?;
+    // Only plain terms can be bound; `1 + . as $x | ...` is not a binding
+    if matches!(
+        expr,
+        Expr::BinaryOp { .. } | Expr::UnaryOp { .. } | Expr::Assignment { .. }
+    ) {
+        return Ok((rest, expr));
+    }
+    match parse_binding_tail(rest) {
+        Ok((rest, (patterns, body))) => Ok((
+            rest,
+            Expr::Bind {
+                source: Box::new(expr),
+                patterns,
+                body: Box::new(body),
+            },
+        )),
+        Err(_) => Ok((rest, expr)),
+    }
+}
+
+/// Parse the `as PATTERN ?// PATTERN | body` part of a binding
+fn parse_binding_tail(input: &str) -> IResult<&str, (Vec<Pattern>, Expr)> {
+    preceded(
+        (ws, keyword("as"), ws),
+        (
+            separated_list1(delimited(ws, tag("?//"), ws), parse_pattern),
+            preceded(delimited(ws, char('|'), ws), parse_pipeline),
+        ),
+    )
+    .parse(input)
+}
+
+/// Parse destructuring pattern ($name, [$a, $b], {key: $v, $name})
+pub(crate) fn parse_pattern(input: &str) -> IResult<&str, Pattern> {
+    alt((
+        map(preceded(char('$'), parse_identifier), Pattern::Variable),
+        map(
+            delimited(
+                (char('['), ws),
+                separated_list1(delimited(ws, char(','), ws), parse_pattern),
+                (ws, char(']')),
+            ),
+            Pattern::Array,
+        ),
+        map(
+            delimited(
+                (char('{'), ws),
+                separated_list1(delimited(ws, char(','), ws), parse_object_pattern_entry),
+                (ws, char('}')),
+            ),
+            Pattern::Object,
+        ),
+    ))
+    .parse(input)
+}
+
+/// Parse object pattern entry ($name, $name: pattern, key: pattern, (expr): pattern)
+fn parse_object_pattern_entry(input: &str) -> IResult<&str, ObjectPatternEntry> {
+    alt((
+        map(
+            (
+                preceded(char('$'), parse_identifier),
+                opt(preceded(delimited(ws, char(':'), ws), parse_pattern)),
+            ),
+            |(name, pattern)| ObjectPatternEntry {
+                key: PatternKey::Variable(name),
+                pattern,
+            },
+        ),
+        map(
+            (
+                alt((
+                    map(parse_object_key, PatternKey::Literal),
+                    map(
+                        delimited((char('('), ws), parse_pipeline, (ws, char(')'))),
+                        |expr| PatternKey::Expr(Box::new(expr)),
+                    ),
+                )),
+                delimited(ws, char(':'), ws),
+                parse_pattern,
+            ),
+            |(key, _, pattern)| ObjectPatternEntry {
+                key,
+                pattern: Some(pattern),
+            },
+        ),
+    ))
+    .parse(input)
+}
+
 /// Parse function call with parentheses (func(arg, ...))
 pub(crate) fn parse_function_call_with_paren(input: &str) -> IResult<&str, Expr> {
     verify(
         map(
             (
-                parse_identifier,
+                parse_qualified_identifier,
                 delimited(ws, char('('), ws),
                 separated_list0(
                     delimited(ws, alt((char(','), char(';'))), ws),
@@ -181,11 +408,15 @@ pub(crate) fn parse_primary_expr(input: &str) -> IResult<&str, Expr> {
             parse_array_construction,
             parse_paren_expr,
             parse_try,
+            parse_break,
             parse_if,
+            parse_reduce,
+            parse_foreach,
+            parse_format,
             parse_literal,
             // Bare identifiers - check if they're builtin functions
             verify(
-                map(parse_identifier, |name: String| {
+                map(parse_qualified_identifier, |name: String| {
                     if BUILTIN_FUNCTIONS.contains(&name.as_str()) {
                         Expr::FunctionCall { name, args: vec![] }
                     } else {
@@ -200,6 +431,7 @@ pub(crate) fn parse_primary_expr(input: &str) -> IResult<&str, Expr> {
                     }
                 },
             ),
+            parse_recursive_descent,
             // Try field access before identity since ".name" should be field access, "." should be identity
             parse_field_access,
             parse_variable,
@@ -239,6 +471,8 @@ pub(crate) fn parse_postfix_expr(input: &str) -> IResult<&str, Expr> {
             ),
             |(_, args, _)| PostfixOp::FunctionCall(args),
         ),
+        // `?` but not the `?//` of destructuring alternatives
+        map(terminated(char('?'), not(tag("//"))), |_| PostfixOp::Try),
     )))
     .parse(input)?;
 
@@ -284,6 +518,10 @@ pub(crate) fn parse_postfix_expr(input: &str) -> IResult<&str, Expr> {
                 }
                 Expr::FunctionCall { name, args }
             }
+            PostfixOp::Try => Expr::Try {
+                body: Box::new(expr),
+                catch: None,
+            },
         };
     }
 
@@ -295,6 +533,7 @@ enum PostfixOp {
     FieldAccess(Vec<String>),
     ArrayIndex(ArrayIndex),
     FunctionCall(Vec<Expr>),
+    Try,
 }
 
 /// Array index types
@@ -338,16 +577,43 @@ pub(crate) fn parse_field_access(input: &str) -> IResult<&str, Expr> {
     .parse(input)
 }
 
+/// Parse recursive descent (`..`), shorthand for `recurse`
+fn parse_recursive_descent(input: &str) -> IResult<&str, Expr> {
+    map(terminated(tag(".."), not(peek(char('.')))), |_| {
+        Expr::FunctionCall {
+            name: "recurse".to_string(),
+            args: vec![],
+        }
+    })
+    .parse(input)
+}
+
 /// Parse parenthesized expressions
 fn parse_paren_expr(input: &str) -> IResult<&str, Expr> {
-    map(delimited(char('('), parse_expr, char(')')), |expr| {
-        Expr::Paren(Box::new(expr))
-    })
+    map(
+        delimited(char('('), parse_comma_sequence, char(')')),
+        |expr| Expr::Paren(Box::new(expr)),
+    )
+    .parse(input)
+}
+
+/// Parse a format (`@csv`) or a format string (`@csv "\(.a), \(.b)"`)
+fn parse_format(input: &str) -> IResult<&str, Expr> {
+    map(
+        (
+            preceded(char('@'), parse_identifier),
+            opt(preceded(ws, parse_string_parts)),
+        ),
+        |(format, parts)| match parts {
+            Some(parts) => string_expr(Some(format), parts),
+            None => Expr::Format(format),
+        },
+    )
     .parse(input)
 }
 
 /// Parse object construction {key: value, key, ...}
-fn parse_object_construction(input: &str) -> IResult<&str, Expr> {
+pub(crate) fn parse_object_construction(input: &str) -> IResult<&str, Expr> {
     map(
         delimited(
             char('{'),
@@ -365,14 +631,7 @@ fn parse_object_construction(input: &str) -> IResult<&str, Expr> {
 
 /// Parse object key (identifier or string literal)
 fn parse_object_key(input: &str) -> IResult<&str, String> {
-    alt((
-        map(parse_string_literal, |e| match e {
-            Expr::Literal(crate::ast::Literal::String(s)) => s,
-            _ => unreachable!(),
-        }),
-        parse_identifier,
-    ))
-    .parse(input)
+    alt((parse_plain_string, parse_identifier)).parse(input)
 }
 
 /// Parse object entry (key: value or key)
@@ -3,9 +3,10 @@
 //! This module contains parsers for identifiers, variables, and related constructs.
 
 use nom::{
-    bytes::complete::{take_while, take_while1},
+    bytes::complete::{tag, take_while, take_while1},
     character::complete::char,
     combinator::{map, recognize},
+    multi::many0,
     sequence::preceded,
     IResult, Parser,
 };
@@ -24,10 +25,20 @@ pub(crate) fn parse_identifier(input: &str) -> IResult<&str, String> {
     .parse(input)
 }
 
-/// Parse variable references ($name)
+/// Parse identifiers that may be qualified by a module name (lib::name)
+pub(crate) fn parse_qualified_identifier(input: &str) -> IResult<&str, String> {
+    map(
+        recognize((parse_identifier, many0((tag("::"), parse_identifier)))),
+        |s: &str| s.to_string(),
+    )
+    .parse(input)
+}
+
+/// Parse variable references ($name or $lib::name)
 pub(crate) fn parse_variable(input: &str) -> IResult<&str, Expr> {
-    map(preceded(char('$'), parse_identifier), |name: String| {
-        Expr::Variable(name)
-    })
+    map(
+        preceded(char('$'), parse_qualified_identifier),
+        |name: String| Expr::Variable(name),
+    )
     .parse(input)
 }
@@ -7,16 +7,17 @@ use nom::{
     branch::alt,
     bytes::complete::take_while,
     character::complete::{char, digit1},
-    combinator::{map, map_res, opt, recognize, verify},
+    combinator::{map, map_opt, map_res, opt, recognize, verify},
     error::ErrorKind,
     sequence::delimited,
     IResult, Parser,
 };
 
-use crate::ast::{Expr, Literal};
+use crate::ast::{Expr, Literal, StringPart};
 use num_bigint::BigInt;
 
-use super::utils::keyword;
+use super::expressions::parse_expr;
+use super::utils::{keyword, ws};
 
 /// Parse literals
 pub(crate) fn parse_literal(input: &str) -> IResult<&str, Expr> {
@@ -29,48 +30,84 @@ pub(crate) fn parse_literal(input: &str) -> IResult<&str, Expr> {
     .parse(input)
 }
 
-/// Parse string content with escapes
-fn parse_string_content(input: &str) -> IResult<&str, String> {
+/// Parse string content with escapes and `\(expr)` interpolations
+fn parse_string_content(input: &str) -> IResult<&str, Vec<StringPart>> {
+    let mut parts = Vec::new();
     let mut result = String::new();
-    let mut chars = input.char_indices();
-    while let Some((i, ch)) = chars.next() {
+    let mut rest = input;
+    loop {
+        let mut chars = rest.chars();
+        let Some(ch) = chars.next() else {
+            return Err(nom::Err::Error(nom::error::Error::new("", ErrorKind::Eof)));
+        };
         if ch == '"' {
-            return Ok((&input[i..], result));
-        } else if ch == '\\' {
-            if let Some((_, esc_ch)) = chars.next() {
-                let ch = match esc_ch {
-                    'n' => '\n',
-                    't' => '\t',
-                    'r' => '\r',
-                    'b' => '\x08',
-                    'f' => '\x0c',
-                    '"' => '"',
-                    '\\' => '\\',
-                    '/' => '/',
-                    _ => esc_ch, // unknown escapes are kept as is
-                };
-                result.push(ch);
-            } else {
-                return Err(nom::Err::Error(nom::error::Error::new(
-                    &input[i..],
-                    ErrorKind::Eof,
-                )));
+            if !result.is_empty() || parts.is_empty() {
+                parts.push(StringPart::Literal(result));
             }
-        } else {
+            return Ok((rest, parts));
+        }
+        if ch != '\\' {
             result.push(ch);
+            rest = chars.as_str();
+            continue;
+        }
+        let Some(esc_ch) = chars.next() else {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                rest,
+                ErrorKind::Eof,
+            )));
+        };
+        if esc_ch == '(' {
+            let (after, expr) = delimited(ws, parse_expr, (ws, char(')'))).parse(chars.as_str())?;
+            if !result.is_empty() {
+                parts.push(StringPart::Literal(std::mem::take(&mut result)));
+            }
+            parts.push(StringPart::Interpolation(expr));
+            rest = after;
+            continue;
         }
+        result.push(match esc_ch {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\x08',
+            'f' => '\x0c',
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            _ => esc_ch, // unknown escapes are kept as is
+        });
+        rest = chars.as_str();
+    }
+}
+
+/// Build the expression for a parsed string: a plain literal when there is
+/// nothing to interpolate, otherwise a format string
+pub(crate) fn string_expr(format: Option<String>, mut parts: Vec<StringPart>) -> Expr {
+    let plain = parts
+        .iter()
+        .all(|part| matches!(part, StringPart::Literal(_)));
+    if plain && format.is_none() {
+        let text = match parts.pop() {
+            Some(StringPart::Literal(s)) if parts.is_empty() => s,
+            _ => String::new(),
+        };
+        Expr::Literal(Literal::String(text))
+    } else {
+        Expr::FormatString { format, parts }
     }
-    Err(nom::Err::Error(nom::error::Error::new("", ErrorKind::Eof)))
+}
+
+/// Parse the parts of a double-quoted string
+pub(crate) fn parse_string_parts(input: &str) -> IResult<&str, Vec<StringPart>> {
+    delimited(char('"'), parse_string_content, char('"')).parse(input)
 }
 
 /// Parse string literals
 pub(crate) fn parse_string_literal(input: &str) -> IResult<&str, Expr> {
     alt((
-        // Double-quoted strings with escapes
-        map(
-            delimited(char('"'), parse_string_content, char('"')),
-            |s: String| Expr::Literal(Literal::String(s)),
-        ),
+        // Double-quoted strings with escapes and interpolation
+        map(parse_string_parts, |parts| string_expr(None, parts)),
         // Single-quoted strings (simple, no escapes)
         map(
             delimited(char('\''), take_while(|c| c != '\''), char('\'')),
@@ -80,6 +117,15 @@ pub(crate) fn parse_string_literal(input: &str) -> IResult<&str, Expr> {
     .parse(input)
 }
 
+/// Parse a string literal without interpolation and return its contents
+pub(crate) fn parse_plain_string(input: &str) -> IResult<&str, String> {
+    map_opt(parse_string_literal, |e| match e {
+        Expr::Literal(Literal::String(s)) => Some(s),
+        _ => None,
+    })
+    .parse(input)
+}
+
 /// Parse number literals
 pub(crate) fn parse_number_literal(input: &str) -> IResult<&str, Expr> {
     map_res(
@@ -17,11 +17,13 @@ use crate::error::{ParseError, Result};
 mod expressions;
 mod identifiers;
 mod literals;
+mod modules;
 mod operators;
 mod utils;
 
 use expressions::{parse_comma_sequence, parse_if};
 use identifiers::parse_identifier;
+use modules::{parse_directives, parse_module};
 use utils::keyword;
 
 /// Main parser for DSQ filter expressions
@@ -47,13 +49,23 @@ impl FilterParser {
             Err(e) => Err(ParseError::from(e)),
         }
     }
+
+    /// Parse a library module: an optional `module {...};` line, `import`
+    /// and `include` directives, then function definitions
+    pub fn parse_module(&self, input: &str) -> Result<Module> {
+        match all_consuming(parse_module).parse(input) {
+            Ok((_, module)) => Ok(module),
+            Err(e) => Err(ParseError::from(e)),
+        }
+    }
 }
 
 /// Parse a complete filter
 fn parse_filter(input: &str) -> IResult<&str, Filter> {
-    all_consuming(map(alt((parse_if, parse_comma_sequence)), |expr| Filter {
-        expr,
-    }))
+    all_consuming(map(
+        (parse_directives, alt((parse_if, parse_comma_sequence))),
+        |(directives, expr)| Filter { directives, expr },
+    ))
     .parse(input)
 }
 
@@ -0,0 +1,103 @@
+//! Module directive parsing
+//!
+//! This module contains parsers for `import`/`include` directives and for
+//! library module files made of directives and function definitions.
+
+use nom::{
+    branch::alt,
+    character::complete::char,
+    combinator::{map, opt},
+    multi::many0,
+    sequence::{delimited, preceded, terminated},
+    IResult, Parser,
+};
+
+use crate::ast::{Expr, FunctionDefinition, Module, ModuleDirective};
+
+use super::expressions::{parse_def_header, parse_object_construction};
+use super::identifiers::parse_identifier;
+use super::literals::parse_plain_string;
+use super::utils::{keyword, ws};
+
+/// Parse an optional metadata object following a module path
+fn parse_metadata(input: &str) -> IResult<&str, Option<Expr>> {
+    opt(preceded(ws, parse_object_construction)).parse(input)
+}
+
+/// Parse `import "path" as name;` and `import "path" as $name;`
+fn parse_import(input: &str) -> IResult<&str, ModuleDirective> {
+    map(
+        (
+            keyword("import"),
+            ws,
+            parse_plain_string,
+            (ws, keyword("as"), ws),
+            alt((
+                map(preceded(char('$'), parse_identifier), |name| (name, true)),
+                map(parse_identifier, |name| (name, false)),
+            )),
+            parse_metadata,
+            (ws, char(';'), ws),
+        ),
+        |(_, _, path, _, (alias, data), metadata, _)| {
+            if data {
+                ModuleDirective::ImportData {
+                    path,
+                    alias,
+                    metadata,
+                }
+            } else {
+                ModuleDirective::Import {
+                    path,
+                    alias,
+                    metadata,
+                }
+            }
+        },
+    )
+    .parse(input)
+}
+
+/// Parse `include "path";`
+fn parse_include(input: &str) -> IResult<&str, ModuleDirective> {
+    map(
+        (
+            keyword("include"),
+            ws,
+            parse_plain_string,
+            parse_metadata,
+            (ws, char(';'), ws),
+        ),
+        |(_, _, path, metadata, _)| ModuleDirective::Include { path, metadata },
+    )
+    .parse(input)
+}
+
+/// Parse the module directives at the start of a filter or module
+pub(crate) fn parse_directives(input: &str) -> IResult<&str, Vec<ModuleDirective>> {
+    preceded(ws, many0(alt((parse_import, parse_include)))).parse(input)
+}
+
+/// Parse a library module (`module {...};`, directives, then definitions)
+pub(crate) fn parse_module(input: &str) -> IResult<&str, Module> {
+    map(
+        (
+            opt(delimited(
+                (ws, keyword("module"), ws),
+                parse_object_construction,
+                (ws, char(';')),
+            )),
+            parse_directives,
+            terminated(many0(parse_def_header), ws),
+        ),
+        |(metadata, directives, defs)| Module {
+            metadata,
+            directives,
+            functions: defs
+                .into_iter()
+                .map(|(name, params, body)| FunctionDefinition { name, params, body })
+                .collect(),
+        },
+    )
+    .parse(input)
+}
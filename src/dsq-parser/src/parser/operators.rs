@@ -17,6 +17,28 @@ use crate::ast::{AssignmentOperator, BinaryOperator, Expr, UnaryOperator};
 
 use super::utils::{keyword, ws};
 
+/// Parse alternative expressions (a // b, right-associative)
+pub(crate) fn parse_alternative_expr(input: &str) -> IResult<&str, Expr> {
+    map(
+        (
+            parse_assignment,
+            opt(preceded(
+                delimited(ws, tag("//"), ws),
+                parse_alternative_expr,
+            )),
+        ),
+        |(left, right)| match right {
+            Some(right) => Expr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::Alternative,
+                right: Box::new(right),
+            },
+            None => left,
+        },
+    )
+    .parse(input)
+}
+
 /// Parse assignment expressions
 pub(crate) fn parse_assignment(input: &str) -> IResult<&str, Expr> {
     alt((
@@ -4,9 +4,9 @@
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::alphanumeric1,
-    combinator::{map, not},
+    bytes::complete::{tag, take_while},
+    character::complete::{alphanumeric1, char},
+    combinator::{map, not, recognize},
     multi::many0,
     sequence::terminated,
     IResult, Parser,
@@ -17,10 +17,16 @@ pub(crate) fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<
     move |input| terminated(tag(word), not(alphanumeric1)).parse(input)
 }
 
-/// Parse optional whitespace including newlines
+/// Parse optional whitespace including newlines and `#` comments
 pub(crate) fn ws(input: &str) -> IResult<&str, ()> {
     map(
-        many0(alt((tag(" "), tag("\t"), tag("\n"), tag("\r")))),
+        many0(alt((
+            tag(" "),
+            tag("\t"),
+            tag("\n"),
+            tag("\r"),
+            recognize((char('#'), take_while(|c| c != '\n'))),
+        ))),
         |_| (),
     )
     .parse(input)
@@ -32,6 +38,7 @@ pub(crate) const BUILTIN_FUNCTIONS: &[&str] = &[
     "map",
     "sort",
     "keys",
+    "keys_unsorted",
     "values",
     "add",
     "sum",
@@ -53,6 +60,17 @@ pub(crate) const BUILTIN_FUNCTIONS: &[&str] = &[
     "reverse",
     "unique",
     "flatten",
+    "recurse",
+    "walk",
+    "paths",
+    "leaf_paths",
+    "path",
+    "getpath",
+    "setpath",
+    "delpaths",
+    "to_entries",
+    "from_entries",
+    "with_entries",
     "transpose",
     "pivot",
     "unpivot",
@@ -131,6 +149,8 @@ pub(crate) const BUILTIN_FUNCTIONS: &[&str] = &[
     "has",
     "empty",
     "error",
+    "input",
+    "inputs",
     "sort_by",
     "repeat",
     "zip",
@@ -141,5 +161,6 @@ pub(crate) const BUILTIN_FUNCTIONS: &[&str] = &[
 
 /// Keywords that cannot be used as identifiers
 pub(crate) const KEYWORDS: &[&str] = &[
-    "if", "then", "else", "end", "try", "catch", "and", "or", "not", "del", "true", "false", "null",
+    "if", "then", "else", "end", "try", "catch", "and", "or", "not", "del", "true", "false",
+    "null", "reduce", "foreach", "as", "def", "import", "include", "module", "label", "break",
 ];
@@ -809,20 +809,501 @@ fn test_parse_errors() {
 
 #[test]
 fn test_try_catch_expression() {
-    // Test parsing try-catch expression (maps to iferror function call)
     let filter = parse_success("try .field catch null");
+    assert!(matches!(
+        filter.expr,
+        Expr::Try { body, catch: Some(catch) }
+            if matches!(*body, Expr::FieldAccess { .. })
+                && matches!(*catch, Expr::Literal(Literal::Null))
+    ));
+
+    // `try` without `catch`
+    let filter = parse_success("try error(\"x\")");
+    assert!(matches!(filter.expr, Expr::Try { catch: None, .. }));
+
+    // The body and handler are postfix terms, so `|` ends them
+    let filter = parse_success("try .a catch . | length");
+    assert!(matches!(filter.expr, Expr::Pipeline(exprs) if exprs.len() == 2));
+
+    // Test try-catch in pipeline
+    let filter = parse_success("map(try .field catch empty) | flatten");
+    assert!(matches!(filter.expr, Expr::Pipeline(exprs) if exprs.len() == 2));
+}
+
+#[test]
+fn test_label_break() {
+    let filter = parse_success("label $out | .[] | if . > 2 then break $out else . end");
+    match filter.expr {
+        Expr::Label { name, body } => {
+            assert_eq!(name, "out");
+            assert!(matches!(*body, Expr::Pipeline(ref exprs) if exprs.len() == 2));
+        }
+        other => panic!("expected a label, got {:?}", other),
+    }
+
+    let filter = parse_success("break $out");
+    assert_eq!(filter.expr, Expr::Break("out".to_string()));
+    assert_eq!(filter.expr.to_string(), "break $out");
+
+    parse_failure("break out");
+}
+
+#[test]
+fn test_reduce_expression() {
+    let filter = parse_success("reduce .[] as $x (0; . + $x)");
+    if let Expr::Reduce {
+        source,
+        variable,
+        init,
+        update,
+    } = filter.expr
+    {
+        assert!(matches!(*source, Expr::ArrayIteration(_)));
+        assert_eq!(variable, "x");
+        assert!(matches!(*init, Expr::Literal(Literal::Int(0))));
+        assert!(matches!(
+            *update,
+            Expr::BinaryOp {
+                op: BinaryOperator::Add,
+                ..
+            }
+        ));
+    } else {
+        panic!("Expected reduce expression");
+    }
+
+    // Reduce over a nested field with an object accumulator
+    let filter = parse_success("reduce .items[] as $item ({}; . + {name: $item.value})");
+    assert!(matches!(filter.expr, Expr::Reduce { .. }));
+
+    // Reduce inside a pipeline
+    let filter = parse_success(".orders | reduce .[] as $o (0; . + $o.total) | . * 2");
+    assert!(matches!(filter.expr, Expr::Pipeline(ref exprs) if exprs.len() == 3));
+    if let Expr::Pipeline(exprs) = filter.expr {
+        assert!(matches!(exprs[1], Expr::Reduce { .. }));
+    }
+
+    // Function-call form is still a plain function call
+    let filter = parse_success("reduce(.a; .b; . + .)");
+    assert!(matches!(filter.expr, Expr::FunctionCall { ref name, .. } if name == "reduce"));
+
+    parse_failure("reduce .[] as x (0; . + x)");
+    parse_failure("reduce .[] as $x (0)");
+    parse_failure("reduce .[] $x (0; .)");
+}
+
+#[test]
+fn test_foreach_expression() {
+    let filter = parse_success("foreach .[] as $x (0; . + $x)");
+    if let Expr::Foreach {
+        variable, extract, ..
+    } = filter.expr
+    {
+        assert_eq!(variable, "x");
+        assert!(extract.is_none());
+    } else {
+        panic!("Expected foreach expression");
+    }
+
+    let filter = parse_success("foreach .[] as $x (0; . + $x; [$x, .])");
+    if let Expr::Foreach { extract, .. } = filter.expr {
+        assert!(matches!(extract.as_deref(), Some(Expr::Array(_))));
+    } else {
+        panic!("Expected foreach expression");
+    }
+
+    let filter = parse_success("[foreach .[] as $x (0; . + $x)]");
     assert!(
-        matches!(filter.expr, Expr::FunctionCall { name, args } if name == "iferror" && args.len() == 2)
+        matches!(filter.expr, Expr::Array(ref items) if matches!(items[0], Expr::Foreach { .. }))
     );
 
-    let filter = parse_success("try .value catch \"default\"");
+    parse_failure("foreach .[] as $x (0)");
+    parse_failure("foreach .[] as $x (0; .; .; .)");
+}
+
+#[test]
+fn test_variable_binding() {
+    let filter = parse_success(". as $x | $x");
+    if let Expr::Bind {
+        source,
+        patterns,
+        body,
+    } = filter.expr
+    {
+        assert!(matches!(*source, Expr::Identity));
+        assert_eq!(patterns, vec![Pattern::Variable("x".to_string())]);
+        assert!(matches!(*body, Expr::Variable(ref name) if name == "x"));
+    } else {
+        panic!("Expected binding");
+    }
+
+    // The binding scopes the rest of the pipeline
+    let filter = parse_success(".users | .[] as $u | $u.name | length");
+    if let Expr::Pipeline(exprs) = filter.expr {
+        assert_eq!(exprs.len(), 2);
+        if let Expr::Bind { source, body, .. } = &exprs[1] {
+            assert!(matches!(**source, Expr::ArrayIteration(_)));
+            assert!(matches!(**body, Expr::Pipeline(ref rest) if rest.len() == 2));
+        } else {
+            panic!("Expected binding as second stage");
+        }
+    } else {
+        panic!("Expected pipeline");
+    }
+
+    // Nested bindings
+    let filter = parse_success(".a as $a | .b as $b | $a + $b");
+    if let Expr::Bind { body, .. } = filter.expr {
+        assert!(matches!(*body, Expr::Bind { .. }));
+    } else {
+        panic!("Expected binding");
+    }
+
+    parse_failure(". as x | x");
+    parse_failure(". as $x");
+    parse_failure("1 + . as $x | $x");
+}
+
+#[test]
+fn test_destructuring_patterns() {
+    let filter = parse_success(". as {name: $n, tags: [$first]} | $n");
+    if let Expr::Bind { patterns, .. } = filter.expr {
+        assert_eq!(
+            patterns,
+            vec![Pattern::Object(vec![
+                ObjectPatternEntry {
+                    key: PatternKey::Literal("name".to_string()),
+                    pattern: Some(Pattern::Variable("n".to_string())),
+                },
+                ObjectPatternEntry {
+                    key: PatternKey::Literal("tags".to_string()),
+                    pattern: Some(Pattern::Array(vec![Pattern::Variable("first".to_string())])),
+                },
+            ])]
+        );
+    } else {
+        panic!("Expected binding");
+    }
+
+    let filter = parse_success(r#". as {$id, "full name": $n, $meta: {v: $v}, (.key): $k} | $id"#);
+    if let Expr::Bind { patterns, .. } = filter.expr {
+        if let Pattern::Object(entries) = &patterns[0] {
+            assert_eq!(entries.len(), 4);
+            assert_eq!(entries[0].key, PatternKey::Variable("id".to_string()));
+            assert!(entries[0].pattern.is_none());
+            assert_eq!(entries[1].key, PatternKey::Literal("full name".to_string()));
+            assert_eq!(entries[2].key, PatternKey::Variable("meta".to_string()));
+            assert!(matches!(entries[2].pattern, Some(Pattern::Object(_))));
+            assert!(matches!(entries[3].key, PatternKey::Expr(_)));
+        } else {
+            panic!("Expected object pattern");
+        }
+    } else {
+        panic!("Expected binding");
+    }
+
+    let filter = parse_success(". as [$a, [$b, $c]] | $a");
+    if let Expr::Bind { patterns, .. } = filter.expr {
+        assert!(matches!(&patterns[0], Pattern::Array(items) if items.len() == 2));
+    } else {
+        panic!("Expected binding");
+    }
+
+    parse_failure(". as {name} | .");
+    parse_failure(". as [] | .");
+}
+
+#[test]
+fn test_alternative_patterns() {
+    let filter = parse_success(". as [$a] ?// {a: $a} ?// $a | $a");
+    if let Expr::Bind { patterns, .. } = filter.expr {
+        assert_eq!(patterns.len(), 3);
+        assert!(matches!(patterns[0], Pattern::Array(_)));
+        assert!(matches!(patterns[1], Pattern::Object(_)));
+        assert!(matches!(patterns[2], Pattern::Variable(_)));
+    } else {
+        panic!("Expected binding");
+    }
+}
+
+#[test]
+fn test_function_definition() {
+    let filter = parse_success("def inc: . + 1; inc");
+    if let Expr::Def {
+        name,
+        params,
+        body,
+        rest,
+    } = filter.expr
+    {
+        assert_eq!(name, "inc");
+        assert!(params.is_empty());
+        assert!(matches!(*body, Expr::BinaryOp { .. }));
+        assert!(matches!(*rest, Expr::Identifier(ref n) if n == "inc"));
+    } else {
+        panic!("Expected function definition");
+    }
+
+    let filter = parse_success("def f(g; $x): [g, $x]; f(.a; 1)");
+    if let Expr::Def { params, rest, .. } = filter.expr {
+        assert_eq!(
+            params,
+            vec![
+                FunctionParam::Filter("g".to_string()),
+                FunctionParam::Value("x".to_string()),
+            ]
+        );
+        assert!(
+            matches!(*rest, Expr::FunctionCall { ref name, ref args } if name == "f" && args.len() == 2)
+        );
+    } else {
+        panic!("Expected function definition");
+    }
+
+    // Definitions scope the whole sequence that follows
+    let filter = parse_success("def one: 1; one, one");
     assert!(
-        matches!(filter.expr, Expr::FunctionCall { name, args } if name == "iferror" && args.len() == 2)
+        matches!(filter.expr, Expr::Def { ref rest, .. } if matches!(**rest, Expr::Sequence(_)))
     );
 
-    // Test try-catch in pipeline
-    let filter = parse_success("map(try .field catch empty) | flatten");
-    assert!(matches!(filter.expr, Expr::Pipeline(exprs) if exprs.len() == 2));
+    // Multiple and nested definitions
+    let filter = parse_success("def a: 1; def b: def c: 2; c; a + b");
+    if let Expr::Def { rest, .. } = filter.expr {
+        if let Expr::Def { body, .. } = *rest {
+            assert!(matches!(*body, Expr::Def { .. }));
+        } else {
+            panic!("Expected second definition");
+        }
+    } else {
+        panic!("Expected function definition");
+    }
+
+    // Definitions inside a pipeline and in function arguments
+    let filter = parse_success(".items | def double: . * 2; map(double)");
+    assert!(
+        matches!(filter.expr, Expr::Pipeline(ref exprs) if matches!(exprs[1], Expr::Def { .. }))
+    );
+    parse_success("map(def sq: . * .; sq)");
+
+    parse_failure("def : 1; .");
+    parse_failure("def f: 1");
+    parse_failure("def f(): 1; f");
+}
+
+#[test]
+fn test_alternative_operator() {
+    let filter = parse_success(".price // 0");
+    assert!(matches!(
+        filter.expr,
+        Expr::BinaryOp {
+            op: BinaryOperator::Alternative,
+            ..
+        }
+    ));
+
+    // Right-associative and looser than `or` and arithmetic
+    let filter = parse_success(".a // .b // .c + 1");
+    if let Expr::BinaryOp { left, op, right } = filter.expr {
+        assert_eq!(op, BinaryOperator::Alternative);
+        assert!(matches!(*left, Expr::FieldAccess { .. }));
+        assert!(matches!(
+            *right,
+            Expr::BinaryOp {
+                op: BinaryOperator::Alternative,
+                ref right,
+                ..
+            } if matches!(**right, Expr::BinaryOp { op: BinaryOperator::Add, .. })
+        ));
+    } else {
+        panic!("Expected alternative");
+    }
+    let filter = parse_success(".a or .b // false");
+    assert!(
+        matches!(filter.expr, Expr::BinaryOp { op: BinaryOperator::Alternative, ref left, .. } if matches!(**left, Expr::BinaryOp { op: BinaryOperator::Or, .. }))
+    );
+
+    // Division is unaffected
+    let filter = parse_success(".a / 2");
+    assert!(matches!(
+        filter.expr,
+        Expr::BinaryOp {
+            op: BinaryOperator::Div,
+            ..
+        }
+    ));
+
+    parse_success("map(.price // 0) | .[0] // null");
+    parse_failure(".a //");
+}
+
+#[test]
+fn test_optional_postfix() {
+    let filter = parse_success(".a?");
+    assert!(
+        matches!(filter.expr, Expr::Try { ref body, catch: None } if matches!(**body, Expr::FieldAccess { .. }))
+    );
+
+    let filter = parse_success(".tags[]?");
+    assert!(
+        matches!(filter.expr, Expr::Try { ref body, catch: None } if matches!(**body, Expr::ArrayIteration(_)))
+    );
+
+    // Postfix operators continue after `?`
+    let filter = parse_success(".a?.b");
+    assert!(
+        matches!(filter.expr, Expr::FieldAccess { ref base, .. } if matches!(**base, Expr::Try { .. }))
+    );
+
+    let filter = parse_success(".a? // 1");
+    assert!(
+        matches!(filter.expr, Expr::BinaryOp { op: BinaryOperator::Alternative, ref left, .. } if matches!(**left, Expr::Try { .. }))
+    );
+
+    // `?//` still separates destructuring alternatives
+    let filter = parse_success(". as [$a] ?// $a | $a");
+    assert!(matches!(filter.expr, Expr::Bind { ref patterns, .. } if patterns.len() == 2));
+}
+
+#[test]
+fn test_recursive_descent() {
+    let filter = parse_success("..");
+    assert!(
+        matches!(filter.expr, Expr::FunctionCall { ref name, ref args } if name == "recurse" && args.is_empty())
+    );
+
+    let filter = parse_success(".. | .id?");
+    assert!(matches!(filter.expr, Expr::Pipeline(ref exprs) if exprs.len() == 2));
+
+    let filter = parse_success("walk(if type == \"object\" then del(.id) else . end)");
+    assert!(
+        matches!(filter.expr, Expr::FunctionCall { ref name, ref args } if name == "walk" && args.len() == 1)
+    );
+
+    parse_failure("...");
+    parse_failure("..a");
+}
+
+#[test]
+fn test_string_interpolation() {
+    let filter = parse_success(r#""\(.first) \(.last)""#);
+    assert_eq!(
+        filter.expr,
+        Expr::FormatString {
+            format: None,
+            parts: vec![
+                StringPart::Interpolation(Expr::FieldAccess {
+                    base: Box::new(Expr::Identity),
+                    fields: vec!["first".to_string()],
+                }),
+                StringPart::Literal(" ".to_string()),
+                StringPart::Interpolation(Expr::FieldAccess {
+                    base: Box::new(Expr::Identity),
+                    fields: vec!["last".to_string()],
+                }),
+            ],
+        }
+    );
+
+    // Strings without interpolation stay plain literals
+    let filter = parse_success(r#""a\tb""#);
+    assert_eq!(
+        filter.expr,
+        Expr::Literal(Literal::String("a\tb".to_string()))
+    );
+
+    let filter = parse_success(r#""x\("y\(.z)")""#);
+    assert!(matches!(filter.expr, Expr::FormatString { ref parts, .. } if parts.len() == 2));
+
+    let filter = parse_success(r#"@csv "row: \([.a, .b])""#);
+    assert!(
+        matches!(filter.expr, Expr::FormatString { format: Some(ref f), ref parts } if f == "csv" && parts.len() == 2)
+    );
+    assert_eq!(filter.expr.to_string(), r#"@csv "row: \([.a, .b])""#);
+
+    let filter = parse_success(".[] | @base64d");
+    assert!(
+        matches!(filter.expr, Expr::Pipeline(ref exprs) if exprs[1] == Expr::Format("base64d".to_string()))
+    );
+
+    parse_failure(r#""\(.a""#);
+    parse_failure(r#"{"\(.a)": 1}"#);
+}
+
+#[test]
+fn test_module_directives() {
+    let filter = parse_success(
+        r#"import "lib/util" as util; include "common" {search: "./"}; import "codes" as $codes; util::f"#,
+    );
+    assert_eq!(
+        filter.directives,
+        vec![
+            ModuleDirective::Import {
+                path: "lib/util".to_string(),
+                alias: "util".to_string(),
+                metadata: None,
+            },
+            ModuleDirective::Include {
+                path: "common".to_string(),
+                metadata: Some(Expr::Object {
+                    pairs: vec![ObjectEntry::KeyValue {
+                        key: "search".to_string(),
+                        value: Expr::Literal(Literal::String("./".to_string())),
+                    }],
+                }),
+            },
+            ModuleDirective::ImportData {
+                path: "codes".to_string(),
+                alias: "codes".to_string(),
+                metadata: None,
+            },
+        ]
+    );
+    assert!(matches!(filter.expr, Expr::Identifier(ref n) if n == "util::f"));
+
+    // Qualified calls and data variables
+    let filter = parse_success(r#"import "m" as m; m::g(.a; 1) | $codes::codes"#);
+    if let Expr::Pipeline(exprs) = filter.expr {
+        assert!(
+            matches!(exprs[0], Expr::FunctionCall { ref name, ref args } if name == "m::g" && args.len() == 2)
+        );
+        assert!(matches!(exprs[1], Expr::Variable(ref n) if n == "codes::codes"));
+    } else {
+        panic!("Expected pipeline");
+    }
+
+    // Filters without directives are unaffected
+    assert!(parse_success(".import").directives.is_empty());
+
+    parse_failure(r#"import "lib" as lib"#);
+    parse_failure(r#"import lib as lib; ."#);
+    parse_failure(r#"include "lib" ."#);
+}
+
+#[test]
+fn test_parse_module() {
+    let parser = FilterParser::new();
+    let module = parser
+        .parse_module(
+            r#"
+            module {name: "util"};
+            # Helpers shared across filters
+            import "other" as other;
+            def inc: . + 1;
+            def twice(f): f | f; # apply f two times
+            "#,
+        )
+        .unwrap();
+    assert!(module.metadata.is_some());
+    assert_eq!(module.directives.len(), 1);
+    let names: Vec<_> = module.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["inc", "twice"]);
+    assert_eq!(
+        module.functions[1].params,
+        vec![FunctionParam::Filter("f".to_string())]
+    );
+
+    assert!(parser.parse_module("").unwrap().functions.is_empty());
+    assert!(parser.parse_module("def f: 1; .").is_err());
 }
 
 #[test]
@@ -965,6 +1446,7 @@ fn test_builtin_functions() {
         "map",
         "sort",
         "keys",
+        "keys_unsorted",
         "values",
         "add",
         "sum",
@@ -1064,6 +1546,8 @@ fn test_builtin_functions() {
         "has",
         "empty",
         "error",
+        "input",
+        "inputs",
         "sort_by",
         "repeat",
         "zip",
@@ -57,6 +57,9 @@ impl std::fmt::Display for BuildInfo {
 
 /// Common error handling utilities
 pub mod error {
+    use crate::value::Value;
+    use std::fmt;
+
     /// Create a generic operation error
     pub fn operation_error(msg: impl Into<String>) -> anyhow::Error {
         anyhow::anyhow!("Operation error: {}", msg.into())
@@ -66,6 +69,103 @@ pub mod error {
     pub fn config_error(msg: impl Into<String>) -> anyhow::Error {
         anyhow::anyhow!("Configuration error: {}", msg.into())
     }
+
+    /// An error raised by `error(value)`, carrying the value it was raised with
+    #[derive(Debug, Clone)]
+    pub struct ValueError(pub Value);
+
+    impl fmt::Display for ValueError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.0 {
+                Value::String(s) => write!(f, "{s}"),
+                other => write!(f, "{other} (not a string)"),
+            }
+        }
+    }
+
+    impl std::error::Error for ValueError {}
+
+    /// Unwinding from `break $label` to the matching `label`
+    ///
+    /// `label` identifies the label expression. The outputs produced before
+    /// the break have already been emitted downstream.
+    #[derive(Debug, Clone)]
+    pub struct Break {
+        /// Identifier of the label being exited
+        pub label: usize,
+    }
+
+    impl fmt::Display for Break {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "break without a matching label")
+        }
+    }
+
+    impl std::error::Error for Break {}
+
+    /// Create the error raised by `error(value)`
+    pub fn value_error(value: Value) -> anyhow::Error {
+        ValueError(value).into()
+    }
+
+    /// The value a `catch` handler receives for `err`: the payload of
+    /// `error(value)`, or the message of any other error
+    pub fn error_value(err: &anyhow::Error) -> Value {
+        if let Some(ValueError(value)) = err.downcast_ref::<ValueError>() {
+            return value.clone();
+        }
+        let message = err.to_string();
+        let mut message = message.as_str();
+        while let Some(rest) = message.strip_prefix("Operation error: ") {
+            message = rest;
+        }
+        Value::String(message.to_string())
+    }
+
+    /// Whether `err` is a `break` unwinding to its label, which `try` and
+    /// `?` must not swallow
+    pub fn is_break(err: &anyhow::Error) -> bool {
+        err.is::<Break>()
+    }
+
+    /// An execution ran past one of its resource limits
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum LimitExceeded {
+        /// The execution ran longer than its timeout
+        Timeout {
+            /// Configured timeout in milliseconds
+            timeout_ms: u64,
+        },
+        /// The execution allocated more than its memory budget
+        Memory {
+            /// Configured budget in bytes
+            limit: usize,
+            /// Bytes allocated by the execution when the budget was checked
+            used: usize,
+        },
+    }
+
+    impl fmt::Display for LimitExceeded {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Timeout { timeout_ms } => {
+                    write!(f, "execution timed out after {timeout_ms} ms")
+                }
+                Self::Memory { limit, used } => write!(
+                    f,
+                    "memory limit of {limit} bytes exceeded ({used} bytes in use)"
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for LimitExceeded {}
+
+    /// Whether `err` must pass through `try`, `?` and `//` untouched: a
+    /// `break` unwinding to its label or an exceeded resource limit
+    pub fn escapes_try(err: &anyhow::Error) -> bool {
+        is_break(err) || err.is::<LimitExceeded>()
+    }
 }
 
 /// Core value types for data processing
@@ -74,6 +174,9 @@ pub mod value;
 /// Core operations for data processing
 pub mod ops;
 
+/// Execution timeouts and memory budgets
+pub mod limits;
+
 // Re-export commonly used functions
 pub use value::is_truthy;
 
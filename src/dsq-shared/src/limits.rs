@@ -0,0 +1,246 @@
+//! Execution timeouts and memory budgets
+//!
+//! An executor calls [`enforce`] with its configured limits and keeps the
+//! returned guard alive for the duration of the execution. Long-running
+//! operations call [`check`] while they iterate or recurse, so a runaway
+//! filter stops with [`LimitExceeded`] instead of running until the machine
+//! gives out. [`check`] is cheap enough to call per value: it only looks at
+//! the limits every [`CHECK_INTERVAL`] calls.
+//!
+//! Memory is measured by [`TrackingAllocator`], which binaries install as
+//! their global allocator and which keeps the high-water mark of the bytes
+//! allocated, so spikes between checks still count towards the budget and
+//! the peak. Without it allocations are not counted: the memory budget is
+//! never exceeded and the peak is reported as 0.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+
+use crate::error::LimitExceeded;
+use crate::Result;
+
+/// Bytes currently allocated through [`TrackingAllocator`]
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Most bytes allocated through [`TrackingAllocator`] at once since the
+/// innermost [`enforce`]
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of calls to [`check`] between two looks at the limits
+pub const CHECK_INTERVAL: u32 = 1024;
+
+/// Count `size` more bytes allocated, raising the high-water mark
+fn allocated(size: usize) {
+    let total = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(total, Ordering::Relaxed);
+}
+
+/// Global allocator that counts the bytes allocated by the process
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: dsq_shared::limits::TrackingAllocator = dsq_shared::limits::TrackingAllocator;
+/// ```
+pub struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size >= layout.size() {
+                allocated(new_size - layout.size());
+            } else {
+                ALLOCATED.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Bytes currently allocated by the process, or 0 when
+/// [`TrackingAllocator`] is not installed
+pub fn allocated_bytes() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/// Limits of the execution running on the current thread
+#[derive(Debug, Clone)]
+struct Limits {
+    #[cfg(not(target_arch = "wasm32"))]
+    deadline: Option<Instant>,
+    timeout_ms: Option<u64>,
+    memory_limit: Option<usize>,
+    /// Bytes allocated when the execution started
+    baseline: usize,
+}
+
+impl Limits {
+    /// Most bytes the execution has had allocated at once
+    fn peak(&self) -> usize {
+        PEAK.load(Ordering::Relaxed).saturating_sub(self.baseline)
+    }
+
+    fn check(&self) -> Result<()> {
+        if let Some(limit) = self.memory_limit {
+            let used = self.peak();
+            if used > limit {
+                return Err(LimitExceeded::Memory { limit, used }.into());
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let (Some(deadline), Some(timeout_ms)) = (self.deadline, self.timeout_ms) {
+            if Instant::now() >= deadline {
+                return Err(LimitExceeded::Timeout { timeout_ms }.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static LIMITS: RefCell<Option<Limits>> = const { RefCell::new(None) };
+    /// Calls to [`check`] left before it looks at the limits again
+    static COUNTDOWN: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Enforce a timeout and a memory budget on the current thread until the
+/// returned guard is dropped
+///
+/// The budget counts the bytes allocated since this call. Nested calls
+/// replace the outer limits until their guard is dropped.
+pub fn enforce(timeout_ms: Option<u64>, memory_limit: Option<usize>) -> LimitGuard {
+    let baseline = allocated_bytes();
+    let limits = Limits {
+        #[cfg(not(target_arch = "wasm32"))]
+        deadline: timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms)),
+        timeout_ms,
+        memory_limit,
+        baseline,
+    };
+    let outer_peak = PEAK.swap(baseline, Ordering::Relaxed);
+    let previous = LIMITS.with(|cell| cell.replace(Some(limits)));
+    // The first check of the new limits looks at them
+    COUNTDOWN.with(|countdown| countdown.set(0));
+    LimitGuard {
+        previous,
+        outer_peak,
+    }
+}
+
+/// Fail with [`LimitExceeded`] if the execution on the current thread has
+/// run past its timeout or memory budget
+///
+/// Only one call in [`CHECK_INTERVAL`] looks at the limits; the others
+/// return at once. Does nothing outside of [`enforce`].
+pub fn check() -> Result<()> {
+    let due = COUNTDOWN.with(|countdown| match countdown.get() {
+        0 => {
+            countdown.set(CHECK_INTERVAL - 1);
+            true
+        }
+        left => {
+            countdown.set(left - 1);
+            false
+        }
+    });
+    if !due {
+        return Ok(());
+    }
+    LIMITS.with(|cell| match cell.borrow().as_ref() {
+        Some(limits) => limits.check(),
+        None => Ok(()),
+    })
+}
+
+/// Restores the previous limits of the thread when dropped
+#[derive(Debug)]
+pub struct LimitGuard {
+    previous: Option<Limits>,
+    /// High-water mark of the allocations before [`enforce`] reset it
+    outer_peak: usize,
+}
+
+impl LimitGuard {
+    /// Most bytes the execution has had allocated at once
+    pub fn peak_memory_bytes(&self) -> usize {
+        LIMITS.with(|cell| cell.borrow().as_ref().map_or(0, Limits::peak))
+    }
+}
+
+impl Drop for LimitGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        LIMITS.with(|cell| *cell.borrow_mut() = previous);
+        PEAK.fetch_max(self.outer_peak, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_outside_enforce() {
+        assert!(check().is_ok());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_timeout() {
+        let _guard = enforce(Some(0), None);
+        let err = check().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<LimitExceeded>(),
+            Some(&LimitExceeded::Timeout { timeout_ms: 0 })
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_checks_are_spaced_out() {
+        let _guard = enforce(Some(60_000), None);
+        assert!(check().is_ok());
+        let _inner = enforce(Some(0), None);
+        assert!(check().is_err());
+        // The next calls within the interval do not look at the limits
+        for _ in 1..CHECK_INTERVAL {
+            assert!(check().is_ok());
+        }
+        assert!(check().is_err());
+    }
+
+    #[test]
+    fn test_guard_restores_previous_limits() {
+        let outer = enforce(Some(60_000), None);
+        {
+            let _inner = enforce(Some(0), None);
+        }
+        assert!(check().is_ok());
+        drop(outer);
+        assert!(check().is_ok());
+    }
+}
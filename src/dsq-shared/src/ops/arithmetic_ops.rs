@@ -6,7 +6,10 @@ use crate::value::Value;
 use crate::Result;
 use std::any::Any;
 
-use super::traits::{Context, Operation};
+use std::ops::ControlFlow;
+
+use super::stream::{collapse, collect_outputs, generate_pairs};
+use super::traits::{Branch, Context, Emit, Operation};
 use super::utils::{add_values, div_values, mul_values, sub_values};
 
 /// Addition operation
@@ -43,23 +46,34 @@ impl Operation for AddOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        add_values(&left_val, &right_val)
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| add_values(left_val, right_val);
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "add".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -99,23 +113,34 @@ impl Operation for SubOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        sub_values(&left_val, &right_val)
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| sub_values(left_val, right_val);
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "subtract".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -155,23 +180,34 @@ impl Operation for MulOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        mul_values(&left_val, &right_val)
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| mul_values(left_val, right_val);
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "multiply".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -211,23 +247,34 @@ impl Operation for DivOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        div_values(&left_val, &right_val)
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| div_values(left_val, right_val);
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "divide".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -2,11 +2,13 @@
 //!
 //! This module contains fundamental operations for accessing and manipulating basic data structures.
 
-use crate::value::Value;
+use crate::value::{df_row_to_value, value_from_any_value, Value};
 use crate::Result;
 use std::any::Any;
+use std::ops::ControlFlow;
 
-use super::traits::Operation;
+use super::stream::{CONTINUE, STOP};
+use super::traits::{Branch, Context, Emit, Operation};
 
 /// Identity operation - returns input unchanged
 pub struct IdentityOperation;
@@ -72,10 +74,48 @@ impl FieldAccessOperation {
     pub fn with_fields(fields: Vec<String>) -> Self {
         Self { fields }
     }
+
+    /// Polars expression reading the field path from a `DataFrame`
+    ///
+    /// The first field names a column and every further field reads a field
+    /// of the struct before it, so `.address.city` becomes
+    /// `col("address").struct_().field_by_name("city")`.
+    pub fn to_polars_expr(&self) -> Option<polars::prelude::Expr> {
+        let (first, rest) = self.fields.split_first()?;
+        Some(
+            rest.iter()
+                .fold(polars::prelude::col(first.as_str()), |expr, field| {
+                    expr.struct_().field_by_name(field)
+                }),
+        )
+    }
 }
 
 impl Operation for FieldAccessOperation {
     fn apply(&self, value: &Value) -> Result<Value> {
+        // Nested paths on frames read struct fields inside Polars
+        if self.fields.len() > 1 {
+            if let Some(expr) = self.to_polars_expr() {
+                match value {
+                    Value::LazyFrame(lf) => {
+                        return Ok(Value::LazyFrame(Box::new(
+                            lf.as_ref().clone().select([expr]),
+                        )));
+                    }
+                    Value::DataFrame(df) => {
+                        use polars::prelude::IntoLazy;
+                        return Ok(match df.clone().lazy().select([expr]).collect() {
+                            Ok(selected) => Value::Series(
+                                selected.get_columns()[0].as_materialized_series().clone(),
+                            ),
+                            Err(_) => Value::Null,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         let mut current = value.clone();
         for field in &self.fields {
             current = current.field(field)?;
@@ -124,6 +164,10 @@ impl Operation for IndexOperation {
         "array index".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("index", &self.index_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -144,7 +188,7 @@ impl Operation for IterateOperation {
                 // Convert DataFrame to array of objects
                 let mut rows = Vec::new();
                 for i in 0..df.height() {
-                    let mut row_obj = std::collections::HashMap::new();
+                    let mut row_obj = indexmap::IndexMap::new();
                     for col_name in df.get_column_names() {
                         if let Ok(series) = df.column(col_name) {
                             if let Ok(val) = series.get(i) {
@@ -169,6 +213,64 @@ impl Operation for IterateOperation {
         }
     }
 
+    /// Produces each element of an array, the values of an object in key
+    /// order, the rows of a DataFrame as objects and the elements of a Series
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    if emit(item.clone(), context)?.is_break() {
+                        return Ok(STOP);
+                    }
+                }
+            }
+            Value::Object(obj) => {
+                for item in obj.values() {
+                    if emit(item.clone(), context)?.is_break() {
+                        return Ok(STOP);
+                    }
+                }
+            }
+            Value::DataFrame(df) => {
+                for row in 0..df.height() {
+                    if emit(df_row_to_value(df, row)?, context)?.is_break() {
+                        return Ok(STOP);
+                    }
+                }
+            }
+            Value::LazyFrame(lf) => {
+                let df = lf.clone().collect().map_err(|e| {
+                    crate::error::operation_error(format!("Failed to collect LazyFrame: {e}"))
+                })?;
+                return self.generate(&Value::DataFrame(df), context, emit);
+            }
+            Value::Series(series) => {
+                for i in 0..series.len() {
+                    let item = series
+                        .get(i)
+                        .ok()
+                        .and_then(value_from_any_value)
+                        .unwrap_or(Value::Null);
+                    if emit(item, context)?.is_break() {
+                        return Ok(STOP);
+                    }
+                }
+            }
+            other => {
+                return Err(crate::error::operation_error(format!(
+                    "Cannot iterate over {}",
+                    other.type_name()
+                )))
+            }
+        }
+        Ok(CONTINUE)
+    }
+
     fn description(&self) -> String {
         "iterate array/object".to_string()
     }
@@ -6,7 +6,10 @@ use crate::value::Value;
 use crate::Result;
 use std::any::Any;
 
-use super::traits::{Context, Operation};
+use std::ops::ControlFlow;
+
+use super::stream::{collapse, collect_outputs, generate_pairs};
+use super::traits::{Branch, Context, Emit, Operation};
 use super::utils::compare_values;
 
 /// Equality comparison operation
@@ -43,23 +46,34 @@ impl Operation for EqOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        Ok(Value::Bool(left_val == right_val))
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| Ok(Value::Bool(left_val == right_val));
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "equals".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -99,23 +113,34 @@ impl Operation for NeOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        Ok(Value::Bool(left_val != right_val))
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| Ok(Value::Bool(left_val != right_val));
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "not equals".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -155,24 +180,37 @@ impl Operation for LtOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        let ordering = compare_values(&left_val, &right_val)?;
-        Ok(Value::Bool(ordering == std::cmp::Ordering::Less))
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| {
+            let ordering = compare_values(left_val, right_val)?;
+            Ok(Value::Bool(ordering == std::cmp::Ordering::Less))
+        };
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "less than".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -212,24 +250,37 @@ impl Operation for LeOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        let ordering = compare_values(&left_val, &right_val)?;
-        Ok(Value::Bool(ordering != std::cmp::Ordering::Greater))
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| {
+            let ordering = compare_values(left_val, right_val)?;
+            Ok(Value::Bool(ordering != std::cmp::Ordering::Greater))
+        };
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "less than or equal".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -268,24 +319,37 @@ impl Operation for GtOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        let ordering = compare_values(&left_val, &right_val)?;
-        Ok(Value::Bool(ordering == std::cmp::Ordering::Greater))
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| {
+            let ordering = compare_values(left_val, right_val)?;
+            Ok(Value::Bool(ordering == std::cmp::Ordering::Greater))
+        };
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "greater than".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -325,24 +389,37 @@ impl Operation for GeOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        let ordering = compare_values(&left_val, &right_val)?;
-        Ok(Value::Bool(ordering != std::cmp::Ordering::Less))
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let combine = |left_val: &Value, right_val: &Value| {
+            let ordering = compare_values(left_val, right_val)?;
+            Ok(Value::Bool(ordering != std::cmp::Ordering::Less))
+        };
+        generate_pairs(
+            &self.left_ops,
+            &self.right_ops,
+            value,
+            context,
+            emit,
+            combine,
+        )
     }
 
     fn description(&self) -> String {
         "greater than or equal".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
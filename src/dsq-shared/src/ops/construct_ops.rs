@@ -4,9 +4,12 @@
 
 use crate::value::Value;
 use crate::Result;
+use indexmap::IndexMap;
 use std::any::Any;
+use std::ops::ControlFlow;
 
-use super::traits::{Context, Operation};
+use super::stream::{collapse, collect_outputs, generate_ops, CONTINUE, STOP};
+use super::traits::{Branch, Context, Emit, Operation};
 
 /// Type alias for field operation pairs in object construction
 type FieldOpPair = (
@@ -17,6 +20,8 @@ type FieldOpPair = (
 /// Object construction operation
 ///
 /// Creates a new object by evaluating key-value pairs from operations.
+/// A `null` input builds an object too, whose fields read from the input
+/// are `null`, as in jq: `{name}` of `null` is `{"name": null}`.
 pub struct ObjectConstructOperation {
     /// Vector of (key_operation, optional_value_operations) pairs
     ///
@@ -33,6 +38,38 @@ impl ObjectConstructOperation {
     }
 }
 
+impl ObjectConstructOperation {
+    /// Generate the objects completing `obj` with the fields from `index`
+    /// on, one per combination of their key and value outputs
+    fn generate_fields(
+        &self,
+        index: usize,
+        obj: &IndexMap<String, Value>,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let Some((key_op, value_ops)) = self.field_ops.get(index) else {
+            return emit(Value::Object(obj.clone()), context);
+        };
+        key_op.generate(value, context, &mut |key, context| {
+            let Value::String(key) = key else {
+                return Err(crate::error::operation_error("Object key must be a string"));
+            };
+            let mut field = |field_value: Value, context: &mut Option<&mut dyn Context>| {
+                let mut obj = obj.clone();
+                obj.insert(key.clone(), field_value);
+                self.generate_fields(index + 1, &obj, value, context, emit)
+            };
+            match value_ops.as_deref() {
+                Some(ops) => generate_ops(ops, value, context, &mut field),
+                // Shorthand: use the key as a field access
+                None => field(value.field(&key)?, context),
+            }
+        })
+    }
+}
+
 impl Operation for ObjectConstructOperation {
     fn apply(&self, value: &Value) -> Result<Value> {
         let mut context = None;
@@ -44,42 +81,35 @@ impl Operation for ObjectConstructOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        // If the input value is null (e.g., from a failed select), return null
-        if matches!(value, Value::Null) {
-            return Ok(Value::Null);
-        }
-
-        // Apply object construction to the value (do not iterate over arrays)
-        let mut obj = std::collections::HashMap::new();
-
-        for (key_op, value_op) in &self.field_ops {
-            let key_value = key_op.apply_with_context(value, context)?;
-            let key = match key_value {
-                Value::String(s) => s,
-                _ => return Err(crate::error::operation_error("Object key must be a string")),
-            };
-
-            let field_value = if let Some(ops) = value_op {
-                let mut current = value.clone();
-                for op in ops {
-                    current = op.apply_with_context(&current, context)?;
-                }
-                current
-            } else {
-                // Shorthand: use the key as a field access
-                value.field(&key)?
-            };
-
-            obj.insert(key, field_value);
-        }
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        Ok(Value::Object(obj))
+    /// Produces one object per combination of the outputs of the keys and
+    /// values, the first field varying slowest
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        self.generate_fields(0, &IndexMap::new(), value, context, emit)
     }
 
     fn description(&self) -> String {
         "object construction".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        let mut branches: Vec<Branch<'_>> = Vec::new();
+        for (key_op, value_ops) in &self.field_ops {
+            branches.push(("key", std::slice::from_ref(key_op)));
+            if let Some(value_ops) = value_ops {
+                branches.push(("value", value_ops));
+            }
+        }
+        branches
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -113,7 +143,7 @@ impl Operation for ArrayConstructOperation {
     ) -> Result<Value> {
         let mut arr = Vec::new();
         for op in &self.element_ops {
-            arr.push(op.apply_with_context(value, context)?);
+            arr.extend(collect_outputs(op.as_ref(), value, context)?);
         }
         Ok(Value::Array(arr))
     }
@@ -122,6 +152,13 @@ impl Operation for ArrayConstructOperation {
         "array construction".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        self.element_ops
+            .iter()
+            .map(|op| ("element", std::slice::from_ref(op)))
+            .collect()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -153,21 +190,35 @@ impl Operation for SequenceOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut results = Vec::new();
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    /// Produces the outputs of each expression in turn
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
         for ops in &self.expr_ops {
-            let mut val = value.clone();
-            for op in ops {
-                val = op.apply_with_context(&val, context)?;
+            if generate_ops(ops, value, context, emit)?.is_break() {
+                return Ok(STOP);
             }
-            results.push(val);
         }
-        Ok(Value::Array(results))
+        Ok(CONTINUE)
     }
 
     fn description(&self) -> String {
         "sequence".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        self.expr_ops
+            .iter()
+            .map(|ops| ("expression", ops.as_slice()))
+            .collect()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -2,11 +2,29 @@
 //!
 //! This module contains boolean logic and conditional execution operations.
 
+use crate::error::{error_value, escapes_try};
 use crate::value::Value;
 use crate::Result;
+use polars::prelude::*;
 use std::any::Any;
-
-use super::traits::{Context, Operation};
+use std::ops::ControlFlow;
+
+use super::stream::{collapse, collect_ops, collect_outputs, emit_all, generate_ops};
+use super::stream::{guard_downstream, CONTINUE};
+use super::traits::{Branch, Context, Emit, Operation};
+
+/// Truthiness of a condition in `and`, `or`, `not` and `if`
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Int(i) => *i != 0,
+        Value::Float(f) => *f != 0.0,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(arr) => !arr.is_empty(),
+        Value::Object(obj) => !obj.is_empty(),
+        _ => false,
+    }
+}
 
 /// Logical AND operation
 ///
@@ -42,47 +60,35 @@ impl Operation for AndOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let left_truthy = match left_val {
-            Value::Bool(b) => b,
-            Value::Int(i) if i != 0 => true,
-            Value::Float(f) if f != 0.0 => true,
-            Value::String(s) if !s.is_empty() => true,
-            Value::Array(arr) if !arr.is_empty() => true,
-            Value::Object(obj) if !obj.is_empty() => true,
-            _ => false,
-        };
-
-        if !left_truthy {
-            return Ok(Value::Bool(false));
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
-
-        let right_truthy = match right_val {
-            Value::Bool(b) => b,
-            Value::Int(i) if i != 0 => true,
-            Value::Float(f) if f != 0.0 => true,
-            Value::String(s) if !s.is_empty() => true,
-            Value::Array(arr) if !arr.is_empty() => true,
-            Value::Object(obj) if !obj.is_empty() => true,
-            _ => false,
-        };
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        Ok(Value::Bool(right_truthy))
+    /// Produces `false` for each falsy left output, and the truthiness of
+    /// each right output for the truthy ones
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_ops(&self.left_ops, value, context, &mut |left, context| {
+            if !truthy(&left) {
+                return emit(Value::Bool(false), context);
+            }
+            generate_ops(&self.right_ops, value, context, &mut |right, context| {
+                emit(Value::Bool(truthy(&right)), context)
+            })
+        })
     }
 
     fn description(&self) -> String {
         "logical and".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -122,47 +128,35 @@ impl Operation for OrOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut left_val = value.clone();
-        for op in &self.left_ops {
-            left_val = op.apply_with_context(&left_val, context)?;
-        }
-
-        let left_truthy = match left_val {
-            Value::Bool(b) => b,
-            Value::Int(i) if i != 0 => true,
-            Value::Float(f) if f != 0.0 => true,
-            Value::String(s) if !s.is_empty() => true,
-            Value::Array(arr) if !arr.is_empty() => true,
-            Value::Object(obj) if !obj.is_empty() => true,
-            _ => false,
-        };
-
-        if left_truthy {
-            return Ok(Value::Bool(true));
-        }
-
-        let mut right_val = value.clone();
-        for op in &self.right_ops {
-            right_val = op.apply_with_context(&right_val, context)?;
-        }
-
-        let right_truthy = match right_val {
-            Value::Bool(b) => b,
-            Value::Int(i) if i != 0 => true,
-            Value::Float(f) if f != 0.0 => true,
-            Value::String(s) if !s.is_empty() => true,
-            Value::Array(arr) if !arr.is_empty() => true,
-            Value::Object(obj) if !obj.is_empty() => true,
-            _ => false,
-        };
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        Ok(Value::Bool(right_truthy))
+    /// Produces `true` for each truthy left output, and the truthiness of
+    /// each right output for the falsy ones
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_ops(&self.left_ops, value, context, &mut |left, context| {
+            if truthy(&left) {
+                return emit(Value::Bool(true), context);
+            }
+            generate_ops(&self.right_ops, value, context, &mut |right, context| {
+                emit(Value::Bool(truthy(&right)), context)
+            })
+        })
     }
 
     fn description(&self) -> String {
         "logical or".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -205,40 +199,43 @@ impl Operation for IfOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut condition_val = value.clone();
-        for op in &self.condition_ops {
-            condition_val = op.apply_with_context(&condition_val, context)?;
-        }
-
-        let condition_truthy = match condition_val {
-            Value::Bool(b) => b,
-            Value::Int(i) if i != 0 => true,
-            Value::Float(f) if f != 0.0 => true,
-            Value::String(s) if !s.is_empty() => true,
-            Value::Array(arr) if !arr.is_empty() => true,
-            Value::Object(obj) if !obj.is_empty() => true,
-            _ => false,
-        };
+        collect_outputs(self, value, context).map(collapse)
+    }
 
-        if condition_truthy {
-            let mut then_val = value.clone();
-            for op in &self.then_ops {
-                then_val = op.apply_with_context(&then_val, context)?;
-            }
-            Ok(then_val)
-        } else {
-            let mut else_val = value.clone();
-            for op in &self.else_ops {
-                else_val = op.apply_with_context(&else_val, context)?;
-            }
-            Ok(else_val)
-        }
+    /// Runs a branch for each output of the condition
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_ops(
+            &self.condition_ops,
+            value,
+            context,
+            &mut |condition, context| {
+                let branch = if truthy(&condition) {
+                    &self.then_ops
+                } else {
+                    &self.else_ops
+                };
+                generate_ops(branch, value, context, emit)
+            },
+        )
     }
 
     fn description(&self) -> String {
         "conditional".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![
+            ("if", &self.condition_ops),
+            ("then", &self.then_ops),
+            ("else", &self.else_ops),
+        ]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -270,26 +267,266 @@ impl Operation for NegationOperation {
         value: &Value,
         context: &mut Option<&mut dyn Context>,
     ) -> Result<Value> {
-        let mut expr_val = value.clone();
-        for op in &self.expr_ops {
-            expr_val = op.apply_with_context(&expr_val, context)?;
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        generate_ops(&self.expr_ops, value, context, &mut |output, context| {
+            emit(Value::Bool(!truthy(&output)), context)
+        })
+    }
+
+    fn description(&self) -> String {
+        "logical not".to_string()
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("operand", &self.expr_ops)]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Alternative operation (`a // b`)
+///
+/// Returns the left value unless it is null or false, or its evaluation
+/// fails, in which case the right value is returned. For Series, DataFrame
+/// and LazyFrame values the null cells are filled from the right value with
+/// a Polars `coalesce` instead.
+pub struct AlternativeOperation {
+    /// Operations that produce the left operand
+    pub left_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    /// Operations that produce the right operand
+    pub right_ops: Vec<Box<dyn Operation + Send + Sync>>,
+}
+
+impl AlternativeOperation {
+    /// Creates a new alternative operation with the given operand operations
+    pub fn new(
+        left_ops: Vec<Box<dyn Operation + Send + Sync>>,
+        right_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    ) -> Self {
+        Self {
+            left_ops,
+            right_ops,
+        }
+    }
+}
+
+impl Operation for AlternativeOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    /// Produces the truthy outputs of the left operand, or the outputs of
+    /// the right one when there are none
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let left = match collect_ops(&self.left_ops, value, context) {
+            Ok(outputs) => outputs,
+            Err(e) if escapes_try(&e) => return Err(e),
+            Err(_) => Vec::new(),
+        };
+        if let [left @ (Value::Series(_) | Value::DataFrame(_) | Value::LazyFrame(_))] =
+            left.as_slice()
+        {
+            let right = collapse(collect_ops(&self.right_ops, value, context)?);
+            return emit(coalesce_values(left.clone(), &right)?, context);
+        }
+        let mut truthy_left = left
+            .into_iter()
+            .filter(|v| !matches!(v, Value::Null | Value::Bool(false)))
+            .peekable();
+        if truthy_left.peek().is_none() {
+            return generate_ops(&self.right_ops, value, context, emit);
         }
+        emit_all(truthy_left, context, emit)
+    }
+
+    fn description(&self) -> String {
+        "alternative".to_string()
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("left", &self.left_ops), ("right", &self.right_ops)]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
 
-        let truthy = match expr_val {
-            Value::Bool(b) => b,
-            Value::Int(i) if i != 0 => true,
-            Value::Float(f) if f != 0.0 => true,
-            Value::String(s) if !s.is_empty() => true,
-            Value::Array(arr) if !arr.is_empty() => true,
-            Value::Object(obj) if !obj.is_empty() => true,
+/// Fill the null (and, for boolean columns, false) cells of a columnar value
+/// from `fill`, a scalar or a Series of the same length
+fn coalesce_values(left: Value, fill: &Value) -> Result<Value> {
+    let fill_expr = match fill {
+        Value::Bool(b) => lit(*b),
+        Value::Int(i) => lit(*i),
+        Value::Float(f) => lit(*f),
+        Value::String(s) => lit(s.as_str()),
+        Value::Series(_) if matches!(left, Value::Series(_)) => col("__fill"),
+        // Nothing to fill with: the columnar value is never null itself
+        _ => return Ok(left),
+    };
+
+    match left {
+        Value::Series(series) => {
+            let name = series.name().clone();
+            let dtype = series.dtype().clone();
+            let mut columns = vec![series.with_name("__left".into()).into_column()];
+            if let Value::Series(other) = fill {
+                if other.len() != columns[0].len() {
+                    return Err(crate::error::operation_error(format!(
+                        "Cannot fill a series of length {} from one of length {}",
+                        columns[0].len(),
+                        other.len()
+                    )));
+                }
+                columns.push(other.clone().with_name("__fill".into()).into_column());
+            }
+            let df = DataFrame::new(columns).map_err(polars_error)?;
+            let filled = df
+                .lazy()
+                .select([fill_column(col("__left"), &dtype, fill_expr).alias(name.clone())])
+                .collect()
+                .map_err(polars_error)?;
+            let column = filled.column(name.as_str()).map_err(polars_error)?;
+            Ok(Value::Series(column.as_materialized_series().clone()))
+        }
+        Value::DataFrame(df) => {
+            let exprs = fill_exprs(df.schema(), fill, &fill_expr);
+            let filled = df
+                .lazy()
+                .with_columns(exprs)
+                .collect()
+                .map_err(polars_error)?;
+            Ok(Value::DataFrame(filled))
+        }
+        Value::LazyFrame(mut lf) => {
+            let schema = lf.collect_schema().map_err(polars_error)?;
+            let exprs = fill_exprs(&schema, fill, &fill_expr);
+            Ok(Value::LazyFrame(Box::new(lf.with_columns(exprs))))
+        }
+        other => Ok(other),
+    }
+}
+
+/// Fill expressions for the columns of `schema` that can hold `fill`
+fn fill_exprs(schema: &Schema, fill: &Value, fill_expr: &Expr) -> Vec<Expr> {
+    schema
+        .iter()
+        .filter(|(_, dtype)| match fill {
+            Value::Bool(_) => dtype.is_bool(),
+            Value::Int(_) | Value::Float(_) => dtype.is_primitive_numeric(),
+            Value::String(_) => dtype.is_string(),
             _ => false,
-        };
+        })
+        .map(|(name, dtype)| {
+            fill_column(col(name.clone()), dtype, fill_expr.clone()).alias(name.clone())
+        })
+        .collect()
+}
+
+/// `column // fill` for a single column of type `dtype`
+fn fill_column(column: Expr, dtype: &DataType, fill: Expr) -> Expr {
+    if dtype.is_bool() {
+        // A null condition selects the `otherwise` branch too
+        when(column.clone()).then(column).otherwise(fill)
+    } else {
+        coalesce(&[column, fill])
+    }
+}
+
+fn polars_error(e: PolarsError) -> anyhow::Error {
+    crate::error::operation_error(format!("Polars error: {e}"))
+}
 
-        Ok(Value::Bool(!truthy))
+/// Error handling operation (`try body catch handler`, and `body?`)
+///
+/// Runs the body and, if it fails, runs the handler against the error value
+/// instead; without a handler the error is suppressed and the body's outputs
+/// stop there.
+pub struct TryOperation {
+    /// Operations whose errors are caught
+    pub body_ops: Vec<Box<dyn Operation + Send + Sync>>,
+    /// Operations run when the body fails
+    pub catch_ops: Option<Vec<Box<dyn Operation + Send + Sync>>>,
+}
+
+impl TryOperation {
+    /// Creates a new error handling operation
+    pub fn new(
+        body_ops: Vec<Box<dyn Operation + Send + Sync>>,
+        catch_ops: Option<Vec<Box<dyn Operation + Send + Sync>>>,
+    ) -> Self {
+        Self {
+            body_ops,
+            catch_ops,
+        }
+    }
+}
+
+impl Operation for TryOperation {
+    fn apply(&self, value: &Value) -> Result<Value> {
+        self.apply_with_context(value, &mut None)
+    }
+
+    fn apply_with_context(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+    ) -> Result<Value> {
+        collect_outputs(self, value, context).map(collapse)
+    }
+
+    /// Produces the outputs of the body up to its first error, then the
+    /// outputs of the handler for that error
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let body = guard_downstream(context, emit, |context, emit| {
+            generate_ops(&self.body_ops, value, context, emit)
+        })?;
+        match (body, &self.catch_ops) {
+            (Ok(flow), _) => Ok(flow),
+            (Err(e), _) if escapes_try(&e) => Err(e),
+            (Err(_), None) => Ok(CONTINUE),
+            (Err(e), Some(catch_ops)) => generate_ops(catch_ops, &error_value(&e), context, emit),
+        }
     }
 
     fn description(&self) -> String {
-        "logical not".to_string()
+        "try".to_string()
+    }
+
+    fn branches(&self) -> Vec<Branch<'_>> {
+        let mut branches: Vec<Branch<'_>> = vec![("try", &self.body_ops)];
+        if let Some(catch_ops) = &self.catch_ops {
+            branches.push(("catch", catch_ops));
+        }
+        branches
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -9,6 +9,7 @@ pub mod comparison_ops;
 pub mod construct_ops;
 pub mod logical_ops;
 pub mod special_ops;
+pub mod stream;
 pub mod traits;
 pub mod utils;
 
@@ -24,9 +25,12 @@ pub use comparison_ops::{
     EqOperation, GeOperation, GtOperation, LeOperation, LtOperation, NeOperation,
 };
 pub use construct_ops::{ArrayConstructOperation, ObjectConstructOperation, SequenceOperation};
-pub use logical_ops::{AndOperation, IfOperation, NegationOperation, OrOperation};
+pub use logical_ops::{
+    AlternativeOperation, AndOperation, IfOperation, NegationOperation, OrOperation, TryOperation,
+};
 pub use special_ops::{
     AssignmentOperation, DelOperation, FunctionCallOperation, JoinFromFileOperation,
 };
-pub use traits::{AssignmentOperator, Context, Operation, SimpleContext};
+pub use stream::{collapse, collect_ops, collect_outputs, emit_all, first_output, generate_ops};
+pub use traits::{AssignmentOperator, Branch, Context, Emit, Operation, SimpleContext};
 pub use utils::{add_values, compare_values, div_values, mul_values, sub_values};
@@ -6,7 +6,7 @@ use crate::value::Value;
 use crate::Result;
 use std::any::Any;
 
-use super::traits::{AssignmentOperator, Context, Operation};
+use super::traits::{AssignmentOperator, Branch, Context, Operation};
 
 /// Type alias for built-in function implementations
 type BuiltinFunc =
@@ -75,6 +75,13 @@ impl Operation for FunctionCallOperation {
         format!("call function {}", self.function_name)
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        self.arg_ops
+            .iter()
+            .map(|op| ("argument", std::slice::from_ref(op)))
+            .collect()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -115,6 +122,10 @@ impl Operation for DelOperation {
         "delete".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("path", &self.path_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -178,7 +189,7 @@ impl Operation for AssignmentOperation {
 
                         // Helper function to update nested field
                         fn update_nested(
-                            obj: &std::collections::HashMap<String, Value>,
+                            obj: &indexmap::IndexMap<String, Value>,
                             fields: &[String],
                             new_value: Value,
                             operator: &AssignmentOperator,
@@ -218,7 +229,7 @@ impl Operation for AssignmentOperation {
                                     new_obj.insert(field_name.clone(), updated);
                                 } else {
                                     // Path doesn't exist or not an object, create nested structure
-                                    let empty = std::collections::HashMap::new();
+                                    let empty = indexmap::IndexMap::new();
                                     let updated =
                                         update_nested(&empty, &fields[1..], new_value, operator);
                                     new_obj.insert(field_name.clone(), updated);
@@ -251,6 +262,10 @@ impl Operation for AssignmentOperation {
         "assignment".to_string()
     }
 
+    fn branches(&self) -> Vec<Branch<'_>> {
+        vec![("target", &self.target_ops), ("value", &self.value_ops)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -0,0 +1,154 @@
+//! Output streams
+//!
+//! A jq expression produces zero, one or many outputs. Operations expose this
+//! through [`Operation::generate`], which hands each output to an [`Emit`]
+//! callback as soon as it is produced. The callback can stop the generator
+//! early by returning `ControlFlow::Break`, which is how `first(f)` and
+//! `label`/`break` avoid computing outputs nobody reads.
+
+use std::ops::ControlFlow;
+
+use crate::limits;
+use crate::value::Value;
+use crate::Result;
+
+use super::traits::{Context, Emit, Operation};
+
+/// Keep producing outputs
+pub const CONTINUE: ControlFlow<()> = ControlFlow::Continue(());
+
+/// Stop producing outputs
+pub const STOP: ControlFlow<()> = ControlFlow::Break(());
+
+/// Generate the outputs of the pipeline `ops` for `value`
+///
+/// Every output of an operation becomes an input of the next one, so the
+/// pipeline yields the outputs of its last operation. An empty pipeline is
+/// the identity.
+///
+/// Each step checks the execution's [`limits`], so a pipeline that runs
+/// away stops even while it keeps producing outputs.
+pub fn generate_ops(
+    ops: &[Box<dyn Operation + Send + Sync>],
+    value: &Value,
+    context: &mut Option<&mut dyn Context>,
+    emit: &mut Emit<'_>,
+) -> Result<ControlFlow<()>> {
+    limits::check()?;
+    match ops.split_first() {
+        None => emit(value.clone(), context),
+        Some((first, [])) => first.generate(value, context, emit),
+        Some((first, rest)) => first.generate(value, context, &mut |output, context| {
+            generate_ops(rest, &output, context, emit)
+        }),
+    }
+}
+
+/// Collect every output of the pipeline `ops` for `value`
+pub fn collect_ops(
+    ops: &[Box<dyn Operation + Send + Sync>],
+    value: &Value,
+    context: &mut Option<&mut dyn Context>,
+) -> Result<Vec<Value>> {
+    let mut outputs = Vec::new();
+    let _ = generate_ops(ops, value, context, &mut |output, _| {
+        outputs.push(output);
+        Ok(CONTINUE)
+    })?;
+    Ok(outputs)
+}
+
+/// Collect every output of `op` for `value`
+pub fn collect_outputs(
+    op: &dyn Operation,
+    value: &Value,
+    context: &mut Option<&mut dyn Context>,
+) -> Result<Vec<Value>> {
+    let mut outputs = Vec::new();
+    let _ = op.generate(value, context, &mut |output, _| {
+        outputs.push(output);
+        Ok(CONTINUE)
+    })?;
+    Ok(outputs)
+}
+
+/// The first output of the pipeline `ops` for `value`, without generating
+/// the rest
+pub fn first_output(
+    ops: &[Box<dyn Operation + Send + Sync>],
+    value: &Value,
+    context: &mut Option<&mut dyn Context>,
+) -> Result<Option<Value>> {
+    let mut first = None;
+    let _ = generate_ops(ops, value, context, &mut |output, _| {
+        first = Some(output);
+        Ok(STOP)
+    })?;
+    Ok(first)
+}
+
+/// Fold a stream into the single value returned by [`Operation::apply`]: the
+/// only output, `null` when there is none, or an array of all of them
+pub fn collapse(mut outputs: Vec<Value>) -> Value {
+    match outputs.len() {
+        0 => Value::Null,
+        1 => outputs.remove(0),
+        _ => Value::Array(outputs),
+    }
+}
+
+/// Emit each of `values` in order
+pub fn emit_all(
+    values: impl IntoIterator<Item = Value>,
+    context: &mut Option<&mut dyn Context>,
+    emit: &mut Emit<'_>,
+) -> Result<ControlFlow<()>> {
+    for value in values {
+        limits::check()?;
+        if emit(value, context)?.is_break() {
+            return Ok(STOP);
+        }
+    }
+    Ok(CONTINUE)
+}
+
+/// Generate `combine(left, right)` for every pair of outputs of `left_ops`
+/// and `right_ops`, with the right operand varying slowest as in jq
+pub fn generate_pairs(
+    left_ops: &[Box<dyn Operation + Send + Sync>],
+    right_ops: &[Box<dyn Operation + Send + Sync>],
+    value: &Value,
+    context: &mut Option<&mut dyn Context>,
+    emit: &mut Emit<'_>,
+    combine: impl Fn(&Value, &Value) -> Result<Value>,
+) -> Result<ControlFlow<()>> {
+    generate_ops(right_ops, value, context, &mut |right, context| {
+        generate_ops(left_ops, value, context, &mut |left, context| {
+            emit(combine(&left, &right)?, context)
+        })
+    })
+}
+
+/// Run `generator` with `emit` as its output, keeping the errors raised by
+/// `emit` apart from those raised by the generator itself
+///
+/// `try`, `?` and `//` only handle errors of their own body; an error raised
+/// further down the pipeline has to pass through them untouched. The outer
+/// result carries downstream errors, the inner one the generator's.
+pub fn guard_downstream(
+    context: &mut Option<&mut dyn Context>,
+    emit: &mut Emit<'_>,
+    generator: impl FnOnce(&mut Option<&mut dyn Context>, &mut Emit<'_>) -> Result<ControlFlow<()>>,
+) -> Result<Result<ControlFlow<()>>> {
+    let mut downstream = None;
+    let result = generator(context, &mut |output, context| {
+        emit(output, context).or_else(|e| {
+            downstream = Some(e);
+            Ok(STOP)
+        })
+    });
+    match downstream {
+        Some(e) => Err(e),
+        None => Ok(result),
+    }
+}
@@ -3,7 +3,7 @@
 use super::*;
 use crate::value::Value;
 use crate::Result;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use std::sync::Arc;
 
 #[test]
@@ -27,12 +27,12 @@ fn test_literal_operation() {
 
 #[test]
 fn test_field_access_operation() {
-    let obj = Value::object(HashMap::from([
+    let obj = Value::object(IndexMap::from([
         ("name".to_string(), Value::string("Alice")),
         ("age".to_string(), Value::int(30)),
         (
             "nested".to_string(),
-            Value::object(HashMap::from([("inner".to_string(), Value::bool(true))])),
+            Value::object(IndexMap::from([("inner".to_string(), Value::bool(true))])),
         ),
     ]));
 
@@ -71,6 +71,53 @@ fn test_field_access_operation() {
     assert_eq!(result, Value::Null);
 }
 
+#[test]
+fn test_field_access_on_struct_columns() {
+    use polars::prelude::IntoLazy;
+
+    let rows = Value::array(vec![
+        Value::object(IndexMap::from([(
+            "address".to_string(),
+            Value::object(IndexMap::from([(
+                "city".to_string(),
+                Value::string("Oslo"),
+            )])),
+        )])),
+        Value::object(IndexMap::from([(
+            "address".to_string(),
+            Value::object(IndexMap::from([
+                ("zip".to_string(), Value::int(5003)),
+                ("city".to_string(), Value::string("Bergen")),
+            ])),
+        )])),
+    ]);
+    let df = rows.to_dataframe().unwrap();
+    let op = FieldAccessOperation::with_fields(vec!["address".to_string(), "city".to_string()]);
+
+    let Value::Series(cities) = op.apply(&Value::dataframe(df.clone())).unwrap() else {
+        panic!("expected a series");
+    };
+    let cities: Vec<_> = cities.str().unwrap().into_iter().collect();
+    assert_eq!(cities, vec![Some("Oslo"), Some("Bergen")]);
+
+    let Value::LazyFrame(lf) = op.apply(&Value::lazy_frame(df.clone().lazy())).unwrap() else {
+        panic!("expected a lazy frame");
+    };
+    let collected = lf.collect().unwrap();
+    assert_eq!(collected.get_column_names(), vec!["city"]);
+
+    // Field access on the struct column itself
+    let Value::Series(zips) = Value::dataframe(df)
+        .field("address")
+        .and_then(|address| address.field("zip"))
+        .unwrap()
+    else {
+        panic!("expected a series");
+    };
+    assert_eq!(zips.i64().unwrap().get(0), None);
+    assert_eq!(zips.i64().unwrap().get(1), Some(5003));
+}
+
 #[test]
 fn test_index_operation() {
     let arr = Value::array(vec![Value::int(1), Value::int(2), Value::int(3)]);
@@ -100,7 +147,7 @@ fn test_index_operation() {
     assert!(invalid_op.apply(&arr).is_err());
 
     // Index on non-indexable types should error
-    let obj = Value::object(HashMap::from([("key".to_string(), Value::int(1))]));
+    let obj = Value::object(IndexMap::from([("key".to_string(), Value::int(1))]));
     let index_on_obj = IndexOperation::new(vec![Box::new(LiteralOperation::new(Value::int(0)))]);
     assert!(index_on_obj.apply(&obj).is_err());
 
@@ -116,7 +163,7 @@ fn test_index_operation() {
 #[test]
 fn test_iterate_operation() {
     let arr = Value::array(vec![Value::int(1), Value::int(2)]);
-    let obj = Value::object(HashMap::from([
+    let obj = Value::object(IndexMap::from([
         ("a".to_string(), Value::int(1)),
         ("b".to_string(), Value::int(2)),
     ]));
@@ -128,8 +175,7 @@ fn test_iterate_operation() {
     let obj_result = op.apply(&obj).unwrap();
     match obj_result {
         Value::Array(values) => {
-            assert_eq!(values.len(), 2);
-            // Order may vary due to HashMap
+            assert_eq!(values, vec![Value::int(1), Value::int(2)]);
         }
         _ => panic!("Expected array"),
     }
@@ -141,7 +187,7 @@ fn test_iterate_operation() {
 
 #[test]
 fn test_object_construct_operation() {
-    let obj = Value::object(HashMap::from([
+    let obj = Value::object(IndexMap::from([
         ("name".to_string(), Value::string("Alice")),
         ("age".to_string(), Value::int(30)),
     ]));
@@ -172,9 +218,10 @@ fn test_object_construct_operation() {
         _ => panic!("Expected object"),
     }
 
-    // Null input
+    // Null input: every field is null
     let result = op.apply(&Value::Null).unwrap();
-    assert_eq!(result, Value::Null);
+    assert_eq!(result.field("name").unwrap(), Value::Null);
+    assert_eq!(result.field("age").unwrap(), Value::Null);
 
     // Object construction with invalid key type
     let invalid_key_ops = vec![(
@@ -190,7 +237,7 @@ fn test_object_construct_operation() {
 
 #[test]
 fn test_array_construct_operation() {
-    let obj = Value::object(HashMap::from([
+    let obj = Value::object(IndexMap::from([
         ("a".to_string(), Value::int(1)),
         ("b".to_string(), Value::int(2)),
     ]));
@@ -223,7 +270,7 @@ fn test_array_construct_operation() {
 
 #[test]
 fn test_sequence_operation() {
-    let obj = Value::object(HashMap::from([("x".to_string(), Value::int(1))]));
+    let obj = Value::object(IndexMap::from([("x".to_string(), Value::int(1))]));
 
     let expr_ops = vec![
         vec![Box::new(FieldAccessOperation::new("x".to_string()))
@@ -243,6 +290,56 @@ fn test_sequence_operation() {
     }
 }
 
+/// `.[] | . - 1`
+fn iterate_minus_one() -> Vec<Box<dyn Operation + Send + Sync>> {
+    vec![
+        Box::new(IterateOperation),
+        Box::new(SubOperation::new(
+            vec![Box::new(IdentityOperation)],
+            vec![Box::new(LiteralOperation::new(Value::int(1)))],
+        )),
+    ]
+}
+
+#[test]
+fn test_generate_streams() {
+    let arr = Value::array(vec![Value::int(1), Value::string("x"), Value::int(3)]);
+    let ops = iterate_minus_one();
+
+    // The first output is produced without evaluating the failing element
+    assert_eq!(
+        first_output(&ops, &arr, &mut None).unwrap(),
+        Some(Value::int(0))
+    );
+    assert!(collect_ops(&ops, &arr, &mut None).is_err());
+
+    // `try` keeps the outputs produced before the error
+    let op = TryOperation::new(iterate_minus_one(), None);
+    assert_eq!(
+        collect_outputs(&op, &arr, &mut None).unwrap(),
+        vec![Value::int(0)]
+    );
+
+    // `//` produces every truthy output of its left side
+    let values = Value::array(vec![Value::null(), Value::int(1), Value::bool(false)]);
+    let op = AlternativeOperation::new(
+        vec![Box::new(IterateOperation)],
+        vec![Box::new(LiteralOperation::new(Value::int(9)))],
+    );
+    assert_eq!(
+        collect_outputs(&op, &values, &mut None).unwrap(),
+        vec![Value::int(1)]
+    );
+    let empty = Value::array(vec![]);
+    assert_eq!(
+        collect_outputs(&op, &empty, &mut None).unwrap(),
+        vec![Value::int(9)]
+    );
+
+    assert_eq!(collapse(vec![]), Value::Null);
+    assert_eq!(collapse(vec![Value::int(1)]), Value::int(1));
+}
+
 #[test]
 fn test_arithmetic_operations() {
     let a = Value::int(10);
@@ -386,7 +483,7 @@ fn test_logical_operations() {
         Value::float(1.0),
         Value::string("hello"),
         Value::array(vec![Value::int(1)]),
-        Value::object(HashMap::from([("key".to_string(), Value::int(1))])),
+        Value::object(IndexMap::from([("key".to_string(), Value::int(1))])),
     ];
 
     let falsy_values = vec![
@@ -394,7 +491,7 @@ fn test_logical_operations() {
         Value::float(0.0),
         Value::string(""),
         Value::array(vec![]),
-        Value::object(HashMap::new()),
+        Value::object(IndexMap::new()),
         Value::Null,
     ];
 
@@ -417,6 +514,133 @@ fn test_logical_operations() {
     }
 }
 
+#[test]
+fn test_alternative_operation() {
+    let alt = |left: Value, right: Value| {
+        AlternativeOperation::new(
+            vec![Box::new(LiteralOperation::new(left))],
+            vec![Box::new(LiteralOperation::new(right))],
+        )
+        .apply(&Value::null())
+        .unwrap()
+    };
+
+    // Only null and false are replaced
+    assert_eq!(alt(Value::Null, Value::int(1)), Value::int(1));
+    assert_eq!(alt(Value::bool(false), Value::int(1)), Value::int(1));
+    assert_eq!(alt(Value::int(0), Value::int(1)), Value::int(0));
+    assert_eq!(alt(Value::string(""), Value::int(1)), Value::string(""));
+
+    // Errors on the left are suppressed
+    let op = AlternativeOperation::new(
+        vec![Box::new(FieldAccessOperation::new("a".to_string()))],
+        vec![Box::new(LiteralOperation::new(Value::string("default")))],
+    );
+    assert_eq!(op.apply(&Value::int(1)).unwrap(), Value::string("default"));
+    assert_eq!(op.description(), "alternative");
+}
+
+#[test]
+fn test_alternative_operation_columnar() {
+    use polars::prelude::*;
+
+    let df = df! {
+        "price" => [Some(1.5), None, Some(3.0)],
+        "name" => [Some("a"), None, Some("c")],
+        "flag" => [Some(true), Some(false), None],
+    }
+    .unwrap();
+    let fill = |left: Value, right: Value| {
+        AlternativeOperation::new(
+            vec![Box::new(LiteralOperation::new(left))],
+            vec![Box::new(LiteralOperation::new(right))],
+        )
+        .apply(&Value::null())
+        .unwrap()
+    };
+
+    // Series are filled element-wise, keeping their name
+    let price = df.column("price").unwrap().as_materialized_series().clone();
+    let Value::Series(filled) = fill(Value::Series(price.clone()), Value::int(0)) else {
+        panic!("expected a series");
+    };
+    assert_eq!(filled.name().as_str(), "price");
+    assert_eq!(
+        filled.f64().unwrap().into_iter().collect::<Vec<_>>(),
+        vec![Some(1.5), Some(0.0), Some(3.0)]
+    );
+
+    // Series // Series coalesces
+    let other = Series::new("other".into(), [9.0, 8.0, 7.0]);
+    let Value::Series(filled) = fill(Value::Series(price), Value::Series(other)) else {
+        panic!("expected a series");
+    };
+    assert_eq!(
+        filled.f64().unwrap().into_iter().collect::<Vec<_>>(),
+        vec![Some(1.5), Some(8.0), Some(3.0)]
+    );
+
+    // false counts as missing in boolean columns
+    let flag = df.column("flag").unwrap().as_materialized_series().clone();
+    let Value::Series(filled) = fill(Value::Series(flag), Value::bool(true)) else {
+        panic!("expected a series");
+    };
+    assert_eq!(
+        filled.bool().unwrap().into_iter().collect::<Vec<_>>(),
+        vec![Some(true), Some(true), Some(true)]
+    );
+
+    // DataFrames fill every column that can hold the value
+    let Value::DataFrame(filled) = fill(Value::DataFrame(df.clone()), Value::string("?")) else {
+        panic!("expected a dataframe");
+    };
+    assert_eq!(
+        filled
+            .column("name")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![Some("a"), Some("?"), Some("c")]
+    );
+    assert_eq!(filled.column("price").unwrap().null_count(), 1);
+
+    let Value::LazyFrame(lf) = fill(Value::LazyFrame(Box::new(df.lazy())), Value::int(0)) else {
+        panic!("expected a lazyframe");
+    };
+    let filled = lf.collect().unwrap();
+    assert_eq!(filled.column("price").unwrap().null_count(), 0);
+    assert_eq!(filled.column("name").unwrap().null_count(), 1);
+}
+
+#[test]
+fn test_try_operation() {
+    let failing = || -> Vec<Box<dyn Operation + Send + Sync>> {
+        vec![Box::new(FieldAccessOperation::new("a".to_string()))]
+    };
+
+    let op = TryOperation::new(failing(), None);
+    assert_eq!(op.apply(&Value::int(1)).unwrap(), Value::Null);
+    assert_eq!(op.apply(&Value::Null).unwrap(), Value::Null);
+
+    let op = TryOperation::new(
+        failing(),
+        Some(vec![Box::new(LiteralOperation::new(Value::string(
+            "caught",
+        )))]),
+    );
+    assert_eq!(op.apply(&Value::int(1)).unwrap(), Value::string("caught"));
+    assert_eq!(op.description(), "try");
+
+    // The handler receives the error message as its input
+    let op = TryOperation::new(failing(), Some(vec![Box::new(IdentityOperation)]));
+    match op.apply(&Value::int(1)).unwrap() {
+        Value::String(message) => assert!(!message.starts_with("Operation error")),
+        other => panic!("expected the error message, got {other:?}"),
+    }
+}
+
 #[test]
 fn test_if_operation() {
     let condition = Value::bool(true);
@@ -507,7 +731,7 @@ fn test_del_operation() {
 #[test]
 fn test_assignment_operation() {
     // Test the basic assignment logic (currently simplified)
-    let obj = Value::object(HashMap::from([("salary".to_string(), Value::int(50000))]));
+    let obj = Value::object(IndexMap::from([("salary".to_string(), Value::int(50000))]));
 
     let target_ops = vec![
         Box::new(IdentityOperation) as Box<dyn Operation + Send + Sync>,
@@ -706,6 +930,126 @@ fn test_div_values() {
     assert!(div_values(&Value::string("a"), &Value::int(1)).is_err());
 }
 
+fn datetime(s: &str, tz: Option<&str>) -> Value {
+    let datetime = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+    Value::datetime(datetime, tz)
+}
+
+fn date(s: &str) -> Value {
+    Value::date(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap())
+}
+
+#[test]
+fn test_temporal_arithmetic() {
+    use chrono::Duration;
+
+    let start = datetime("2024-03-01 08:00:00", Some("UTC"));
+    let end = datetime("2024-03-01 09:30:00", Some("UTC"));
+    assert_eq!(
+        sub_values(&end, &start).unwrap(),
+        Value::duration(Duration::minutes(90))
+    );
+    assert_eq!(
+        add_values(&start, &Value::duration(Duration::minutes(90))).unwrap(),
+        end
+    );
+    assert_eq!(
+        sub_values(&end, &Value::duration(Duration::minutes(90))).unwrap(),
+        start
+    );
+
+    // Naive and zoned datetimes do not mix
+    let naive = datetime("2024-03-01 08:00:00", None);
+    assert!(sub_values(&end, &naive).is_err());
+    assert!(compare_values(&end, &naive).is_err());
+
+    // Dates stay dates when shifted by whole days
+    assert_eq!(
+        sub_values(&date("2024-03-01"), &date("2024-02-01")).unwrap(),
+        Value::duration(Duration::days(29))
+    );
+    assert_eq!(
+        add_values(&date("2024-02-28"), &Value::duration(Duration::days(2))).unwrap(),
+        date("2024-03-01")
+    );
+    assert_eq!(
+        add_values(&date("2024-03-01"), &Value::duration(Duration::hours(8))).unwrap(),
+        naive
+    );
+
+    // Durations scale by numbers and divide into ratios
+    let hour = Value::duration(Duration::hours(1));
+    assert_eq!(
+        mul_values(&hour, &Value::int(3)).unwrap(),
+        Value::duration(Duration::hours(3))
+    );
+    assert_eq!(
+        div_values(&hour, &Value::int(4)).unwrap(),
+        Value::duration(Duration::minutes(15))
+    );
+    assert_eq!(
+        div_values(&Value::duration(Duration::minutes(90)), &hour).unwrap(),
+        Value::Float(1.5)
+    );
+
+    assert_eq!(
+        compare_values(&start, &end).unwrap(),
+        std::cmp::Ordering::Less
+    );
+    assert_eq!(
+        compare_values(&date("2024-03-02"), &date("2024-03-01")).unwrap(),
+        std::cmp::Ordering::Greater
+    );
+}
+
+#[test]
+fn test_decimal_arithmetic() {
+    // 0.10 + 0.2 is exact
+    assert_eq!(
+        add_values(&Value::decimal(10, 2), &Value::decimal(2, 1)).unwrap(),
+        Value::decimal(30, 2)
+    );
+    assert_eq!(
+        sub_values(&Value::decimal(150, 2), &Value::int(1)).unwrap(),
+        Value::decimal(50, 2)
+    );
+    assert_eq!(
+        mul_values(&Value::decimal(15, 1), &Value::decimal(15, 1)).unwrap(),
+        Value::decimal(225, 2)
+    );
+    assert_eq!(
+        div_values(&Value::decimal(15, 1), &Value::int(2)).unwrap(),
+        Value::Float(0.75)
+    );
+    assert_eq!(
+        add_values(&Value::decimal(15, 1), &Value::float(0.25)).unwrap(),
+        Value::Float(1.75)
+    );
+    assert!(add_values(&Value::decimal(i128::MAX, 0), &Value::int(1)).is_err());
+    assert!(div_values(&Value::int(1), &Value::decimal(0, 2)).is_err());
+
+    assert_eq!(
+        compare_values(&Value::decimal(100, 2), &Value::int(1)).unwrap(),
+        std::cmp::Ordering::Equal
+    );
+    assert_eq!(
+        compare_values(&Value::decimal(105, 2), &Value::float(1.1)).unwrap(),
+        std::cmp::Ordering::Less
+    );
+}
+
+#[test]
+fn test_binary_values() {
+    assert_eq!(
+        add_values(&Value::binary(vec![1, 2]), &Value::binary(vec![3])).unwrap(),
+        Value::binary(vec![1, 2, 3])
+    );
+    assert_eq!(
+        compare_values(&Value::binary(vec![1, 2]), &Value::binary(vec![1, 3])).unwrap(),
+        std::cmp::Ordering::Less
+    );
+}
+
 #[test]
 fn test_simple_context() {
     let value = Value::int(42);
@@ -2,6 +2,8 @@
 //!
 //! This module defines the fundamental traits and basic types that other operations depend on.
 
+use std::ops::ControlFlow;
+
 use crate::value::Value;
 use crate::Result;
 
@@ -22,6 +24,9 @@ pub trait Context {
     /// Set a variable value
     fn set_variable(&mut self, name: &str, value: Value);
 
+    /// Remove a variable binding, restoring the enclosing scope
+    fn remove_variable(&mut self, _name: &str) {}
+
     /// Downcast to Any for concrete type checking
     fn as_any(&self) -> &dyn std::any::Any;
 
@@ -57,6 +62,17 @@ impl Context for SimpleContext {
     }
 }
 
+/// Receiver of the outputs of [`Operation::generate`]
+///
+/// It is called with each output in turn, together with the context the
+/// output was produced in, and returns `ControlFlow::Break` to ask the
+/// generator to stop.
+pub type Emit<'a> = dyn FnMut(Value, &mut Option<&mut dyn Context>) -> Result<ControlFlow<()>> + 'a;
+
+/// A labelled pipeline of operations nested inside another operation, as
+/// returned by [`Operation::branches`]
+pub type Branch<'a> = (&'a str, &'a [Box<dyn Operation + Send + Sync>]);
+
 /// Trait for operations that can be applied to values
 ///
 /// This trait provides a common interface for all data operations,
@@ -75,9 +91,38 @@ pub trait Operation {
         self.apply(value)
     }
 
+    /// Produce every output of the operation for `value`, passing each to
+    /// `emit`
+    ///
+    /// An operation can produce any number of outputs; `.[]` produces one
+    /// per element and `empty` none at all. The default produces the single
+    /// result of [`apply_with_context`](Operation::apply_with_context).
+    /// Returns `ControlFlow::Break` when `emit` stopped the generator.
+    fn generate(
+        &self,
+        value: &Value,
+        context: &mut Option<&mut dyn Context>,
+        emit: &mut Emit<'_>,
+    ) -> Result<ControlFlow<()>> {
+        let output = self.apply_with_context(value, context)?;
+        emit(output, context)
+    }
+
     /// Get a description of what this operation does
     fn description(&self) -> String;
 
+    /// The pipelines nested inside this operation, such as the operands of
+    /// a binary operator, used to print the operation tree of a filter
+    fn branches(&self) -> Vec<Branch<'_>> {
+        Vec::new()
+    }
+
+    /// Whether this operation runs on DataFrames as a Polars expression
+    /// instead of row by row
+    fn is_vectorized(&self) -> bool {
+        false
+    }
+
     /// Check if this operation can be applied to the given value type
     fn is_applicable(&self, value: &Value) -> bool {
         // Default implementation: try to apply and see if it works
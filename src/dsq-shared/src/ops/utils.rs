@@ -4,7 +4,9 @@
 
 #![allow(clippy::cast_precision_loss)]
 
-use crate::value::Value;
+use chrono::{Duration, NaiveDate, NaiveTime};
+
+use crate::value::{align_decimals, decimal_to_f64, Value};
 use crate::Result;
 
 /// Compare two values for ordering
@@ -22,6 +24,36 @@ pub fn compare_values(a: &Value, b: &Value) -> Result<std::cmp::Ordering> {
             .partial_cmp(b)
             .ok_or_else(|| crate::error::operation_error("Cannot compare NaN values")),
         (Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+        (Value::Date(a), Value::Date(b)) => Ok(a.cmp(b)),
+        (
+            Value::Datetime {
+                datetime: a,
+                tz: tz_a,
+            },
+            Value::Datetime {
+                datetime: b,
+                tz: tz_b,
+            },
+        ) => {
+            check_same_awareness(tz_a, tz_b, "compare")?;
+            Ok(a.cmp(b))
+        }
+        (Value::Duration(a), Value::Duration(b)) => Ok(a.cmp(b)),
+        (Value::Binary(a), Value::Binary(b)) => Ok(a.cmp(b)),
+        (Value::Decimal { .. } | Value::Int(_), Value::Decimal { .. })
+        | (Value::Decimal { .. }, Value::Int(_)) => {
+            let (x, y) = (as_decimal(a).unwrap(), as_decimal(b).unwrap());
+            match align_decimals(x, y) {
+                Some((x, y, _)) => Ok(x.cmp(&y)),
+                None => compare_floats(as_f64(a).unwrap(), as_f64(b).unwrap()),
+            }
+        }
+        (Value::Decimal { value, scale }, Value::Float(f)) => {
+            compare_floats(decimal_to_f64(*value, *scale), *f)
+        }
+        (Value::Float(f), Value::Decimal { value, scale }) => {
+            compare_floats(*f, decimal_to_f64(*value, *scale))
+        }
 
         // Cross-type numeric comparisons
         (Value::Int(a), Value::Float(b)) => (*a as f64)
@@ -40,9 +72,113 @@ pub fn compare_values(a: &Value, b: &Value) -> Result<std::cmp::Ordering> {
     }
 }
 
+fn compare_floats(a: f64, b: f64) -> Result<std::cmp::Ordering> {
+    a.partial_cmp(&b)
+        .ok_or_else(|| crate::error::operation_error("Cannot compare NaN values"))
+}
+
+/// Naive and time zone aware datetimes cannot be mixed
+fn check_same_awareness(a: &Option<String>, b: &Option<String>, op: &str) -> Result<()> {
+    if a.is_some() == b.is_some() {
+        Ok(())
+    } else {
+        Err(crate::error::operation_error(format!(
+            "Cannot {op} naive and time zone aware datetimes"
+        )))
+    }
+}
+
+/// Unscaled value and scale of a decimal or integer
+fn as_decimal(v: &Value) -> Option<(i128, usize)> {
+    match v {
+        Value::Decimal { value, scale } => Some((*value, *scale)),
+        Value::Int(i) => Some((i128::from(*i), 0)),
+        _ => None,
+    }
+}
+
+/// Float value of a decimal, integer or float
+fn as_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Decimal { value, scale } => Some(decimal_to_f64(*value, *scale)),
+        Value::Int(i) => Some(*i as f64),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// Add or subtract decimals (or a decimal and an integer) without losing
+/// precision
+fn decimal_add(a: (i128, usize), b: (i128, usize), negate_b: bool) -> Result<Value> {
+    let overflow = || crate::error::operation_error("Decimal overflow");
+    let (x, y, scale) = align_decimals(a, b).ok_or_else(overflow)?;
+    let value = if negate_b {
+        x.checked_sub(y)
+    } else {
+        x.checked_add(y)
+    };
+    value
+        .map(|value| Value::Decimal { value, scale })
+        .ok_or_else(overflow)
+}
+
+/// Shift a date by a duration, keeping a date when the result falls on
+/// midnight and giving a naive datetime otherwise
+fn shift_date(date: NaiveDate, delta: Duration) -> Result<Value> {
+    let shifted = date
+        .and_time(NaiveTime::MIN)
+        .checked_add_signed(delta)
+        .ok_or_else(|| crate::error::operation_error("Date out of range"))?;
+    if shifted.time() == NaiveTime::MIN {
+        Ok(Value::Date(shifted.date()))
+    } else {
+        Ok(Value::Datetime {
+            datetime: shifted,
+            tz: None,
+        })
+    }
+}
+
+/// Scale the nanoseconds of a duration
+fn scale_duration(d: Duration, scale: impl FnOnce(f64) -> f64) -> Result<Value> {
+    let overflow = || crate::error::operation_error("Duration out of range");
+    let ns = scale(d.num_nanoseconds().ok_or_else(overflow)? as f64);
+    if !ns.is_finite() || ns.abs() >= i64::MAX as f64 {
+        return Err(overflow());
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(Value::Duration(Duration::nanoseconds(ns.round() as i64)))
+}
+
 /// Add two values
+///
+/// Besides numbers and strings this adds durations to datetimes, dates and
+/// other durations, keeps decimals exact and concatenates binary values.
 pub fn add_values(a: &Value, b: &Value) -> Result<Value> {
     match (a, b) {
+        (Value::Datetime { datetime, tz }, Value::Duration(d))
+        | (Value::Duration(d), Value::Datetime { datetime, tz }) => datetime
+            .checked_add_signed(*d)
+            .map(|datetime| Value::Datetime {
+                datetime,
+                tz: tz.clone(),
+            })
+            .ok_or_else(|| crate::error::operation_error("Datetime out of range")),
+        (Value::Date(date), Value::Duration(d)) | (Value::Duration(d), Value::Date(date)) => {
+            shift_date(*date, *d)
+        }
+        (Value::Duration(x), Value::Duration(y)) => x
+            .checked_add(y)
+            .map(Value::Duration)
+            .ok_or_else(|| crate::error::operation_error("Duration out of range")),
+        (Value::Decimal { .. }, Value::Decimal { .. } | Value::Int(_))
+        | (Value::Int(_), Value::Decimal { .. }) => {
+            decimal_add(as_decimal(a).unwrap(), as_decimal(b).unwrap(), false)
+        }
+        (Value::Decimal { .. }, Value::Float(_)) | (Value::Float(_), Value::Decimal { .. }) => {
+            Ok(Value::Float(as_f64(a).unwrap() + as_f64(b).unwrap()))
+        }
+        (Value::Binary(x), Value::Binary(y)) => Ok(Value::Binary([x.as_slice(), y].concat())),
         (Value::Int(x), Value::Int(y)) => Ok(Value::Int(x + y)),
         (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x + y)),
         (Value::Int(x), Value::Float(y)) => Ok(Value::Float(*x as f64 + *y)),
@@ -77,8 +213,43 @@ pub fn add_values(a: &Value, b: &Value) -> Result<Value> {
 }
 
 /// Subtract two values
+///
+/// Subtracting two datetimes or two dates gives a duration.
 pub fn sub_values(a: &Value, b: &Value) -> Result<Value> {
     match (a, b) {
+        (
+            Value::Datetime {
+                datetime: x,
+                tz: tz_x,
+            },
+            Value::Datetime {
+                datetime: y,
+                tz: tz_y,
+            },
+        ) => {
+            check_same_awareness(tz_x, tz_y, "subtract")?;
+            Ok(Value::Duration(x.signed_duration_since(*y)))
+        }
+        (Value::Date(x), Value::Date(y)) => Ok(Value::Duration(x.signed_duration_since(*y))),
+        (Value::Datetime { datetime, tz }, Value::Duration(d)) => datetime
+            .checked_sub_signed(*d)
+            .map(|datetime| Value::Datetime {
+                datetime,
+                tz: tz.clone(),
+            })
+            .ok_or_else(|| crate::error::operation_error("Datetime out of range")),
+        (Value::Date(date), Value::Duration(d)) => shift_date(*date, -*d),
+        (Value::Duration(x), Value::Duration(y)) => x
+            .checked_sub(y)
+            .map(Value::Duration)
+            .ok_or_else(|| crate::error::operation_error("Duration out of range")),
+        (Value::Decimal { .. }, Value::Decimal { .. } | Value::Int(_))
+        | (Value::Int(_), Value::Decimal { .. }) => {
+            decimal_add(as_decimal(a).unwrap(), as_decimal(b).unwrap(), true)
+        }
+        (Value::Decimal { .. }, Value::Float(_)) | (Value::Float(_), Value::Decimal { .. }) => {
+            Ok(Value::Float(as_f64(a).unwrap() - as_f64(b).unwrap()))
+        }
         (Value::Int(x), Value::Int(y)) => Ok(Value::Int(x - y)),
         (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x - y)),
         (Value::Int(x), Value::Float(y)) => Ok(Value::Float(*x as f64 - *y)),
@@ -94,6 +265,26 @@ pub fn sub_values(a: &Value, b: &Value) -> Result<Value> {
 /// Multiply two values
 pub fn mul_values(a: &Value, b: &Value) -> Result<Value> {
     match (a, b) {
+        (Value::Duration(d), Value::Int(_) | Value::Float(_))
+        | (Value::Int(_) | Value::Float(_), Value::Duration(d)) => {
+            let factor = if let Value::Duration(_) = a { b } else { a };
+            let factor = as_f64(factor).unwrap();
+            scale_duration(*d, |ns| ns * factor)
+        }
+        (Value::Decimal { .. }, Value::Decimal { .. } | Value::Int(_))
+        | (Value::Int(_), Value::Decimal { .. }) => {
+            let (x, scale_x) = as_decimal(a).unwrap();
+            let (y, scale_y) = as_decimal(b).unwrap();
+            x.checked_mul(y)
+                .map(|value| Value::Decimal {
+                    value,
+                    scale: scale_x + scale_y,
+                })
+                .ok_or_else(|| crate::error::operation_error("Decimal overflow"))
+        }
+        (Value::Decimal { .. }, Value::Float(_)) | (Value::Float(_), Value::Decimal { .. }) => {
+            Ok(Value::Float(as_f64(a).unwrap() * as_f64(b).unwrap()))
+        }
         (Value::Int(x), Value::Int(y)) => Ok(Value::Int(x * y)),
         (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x * y)),
         (Value::Int(x), Value::Float(y)) => Ok(Value::Float(*x as f64 * *y)),
@@ -107,14 +298,29 @@ pub fn mul_values(a: &Value, b: &Value) -> Result<Value> {
 }
 
 /// Divide two values
+///
+/// Dividing a duration by a number gives a duration, and dividing two
+/// durations gives their ratio.
 pub fn div_values(a: &Value, b: &Value) -> Result<Value> {
+    if let (Value::Duration(x), Value::Duration(y)) = (a, b) {
+        let overflow = || crate::error::operation_error("Duration out of range");
+        let y = y.num_nanoseconds().ok_or_else(overflow)?;
+        if y == 0 {
+            return Err(crate::error::operation_error("Division by zero"));
+        }
+        return Ok(Value::Float(
+            x.num_nanoseconds().ok_or_else(overflow)? as f64 / y as f64,
+        ));
+    }
     let b_float = match b {
         Value::Int(y) if *y == 0 => return Err(crate::error::operation_error("Division by zero")),
         Value::Float(y) if *y == 0.0 => {
             return Err(crate::error::operation_error("Division by zero"));
         }
-        Value::Int(y) => *y as f64,
-        Value::Float(y) => *y,
+        Value::Decimal { value: 0, .. } => {
+            return Err(crate::error::operation_error("Division by zero"));
+        }
+        Value::Int(_) | Value::Float(_) | Value::Decimal { .. } => as_f64(b).unwrap(),
         _ => {
             return Err(crate::error::operation_error(format!(
                 "Cannot divide by {}",
@@ -125,6 +331,10 @@ pub fn div_values(a: &Value, b: &Value) -> Result<Value> {
     match a {
         Value::Int(x) => Ok(Value::Float(*x as f64 / b_float)),
         Value::Float(x) => Ok(Value::Float(*x / b_float)),
+        Value::Decimal { value, scale } => {
+            Ok(Value::Float(decimal_to_f64(*value, *scale) / b_float))
+        }
+        Value::Duration(d) => scale_duration(*d, |ns| ns / b_float),
         _ => Err(crate::error::operation_error(format!(
             "Cannot divide {} by number",
             a.type_name()
@@ -3,7 +3,8 @@
 //! This module provides the core Value enum that represents all data types
 //! that can be processed by DSQ, including JSON-like values and Polars DataFrames.
 
-use chrono::{DateTime, Duration, NaiveDate};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone as _};
+use indexmap::IndexMap;
 use num_traits::identities::Zero;
 use polars::prelude::*;
 #[cfg(not(target_arch = "wasm32"))]
@@ -12,7 +13,8 @@ use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
 use serde::ser::{SerializeMap, SerializeSeq};
 use serde_json::{Number as JsonNumber, Value as JsonValue};
-use std::collections::HashMap;
+
+pub mod schema;
 
 #[derive(Clone)]
 pub enum Value {
@@ -28,10 +30,33 @@ pub enum Value {
     Float(f64),
     /// String value
     String(String),
+    /// Calendar date
+    Date(NaiveDate),
+    /// Date and time with an optional IANA time zone
+    ///
+    /// With a time zone, `datetime` is the UTC instant and the zone only
+    /// affects how it is displayed. Without one it is a wall-clock time.
+    Datetime {
+        /// UTC instant, or wall-clock time when `tz` is `None`
+        datetime: NaiveDateTime,
+        /// IANA time zone name, e.g. `Europe/Berlin`
+        tz: Option<String>,
+    },
+    /// Signed length of time
+    Duration(Duration),
+    /// Fixed-point decimal equal to `value / 10^scale`
+    Decimal {
+        /// Unscaled digits
+        value: i128,
+        /// Number of digits after the decimal point
+        scale: usize,
+    },
+    /// Raw bytes
+    Binary(Vec<u8>),
     /// Array of values
     Array(Vec<Value>),
     /// Object (key-value pairs)
-    Object(HashMap<String, Value>),
+    Object(IndexMap<String, Value>),
     /// Polars `DataFrame`
     DataFrame(polars::prelude::DataFrame),
     /// Polars `LazyFrame` (for lazy evaluation)
@@ -76,6 +101,38 @@ impl Value {
         Value::String(s.into())
     }
 
+    /// Create a new date value
+    #[must_use]
+    pub fn date(date: NaiveDate) -> Self {
+        Value::Date(date)
+    }
+
+    /// Create a new datetime value, optionally in a time zone
+    pub fn datetime(datetime: NaiveDateTime, tz: Option<impl Into<String>>) -> Self {
+        Value::Datetime {
+            datetime,
+            tz: tz.map(Into::into),
+        }
+    }
+
+    /// Create a new duration value
+    #[must_use]
+    pub fn duration(duration: Duration) -> Self {
+        Value::Duration(duration)
+    }
+
+    /// Create a new decimal value equal to `value / 10^scale`
+    #[must_use]
+    pub fn decimal(value: i128, scale: usize) -> Self {
+        Value::Decimal { value, scale }
+    }
+
+    /// Create a new binary value
+    #[must_use]
+    pub fn binary(bytes: Vec<u8>) -> Self {
+        Value::Binary(bytes)
+    }
+
     /// Create a new array value
     #[must_use]
     pub fn array(arr: Vec<Value>) -> Self {
@@ -84,7 +141,7 @@ impl Value {
 
     /// Create a new object value
     #[must_use]
-    pub fn object(obj: HashMap<String, Value>) -> Self {
+    pub fn object(obj: IndexMap<String, Value>) -> Self {
         Value::Object(obj)
     }
 
@@ -116,6 +173,19 @@ impl std::fmt::Debug for Value {
             Value::BigInt(bi) => f.debug_tuple("BigInt").field(bi).finish(),
             Value::Float(fl) => f.debug_tuple("Float").field(fl).finish(),
             Value::String(s) => f.debug_tuple("String").field(s).finish(),
+            Value::Date(d) => f.debug_tuple("Date").field(d).finish(),
+            Value::Datetime { datetime, tz } => f
+                .debug_struct("Datetime")
+                .field("datetime", datetime)
+                .field("tz", tz)
+                .finish(),
+            Value::Duration(d) => f.debug_tuple("Duration").field(d).finish(),
+            Value::Decimal { value, scale } => f
+                .debug_struct("Decimal")
+                .field("value", value)
+                .field("scale", scale)
+                .finish(),
+            Value::Binary(b) => f.debug_tuple("Binary").field(b).finish(),
             Value::Array(arr) => f.debug_tuple("Array").field(arr).finish(),
             Value::Object(obj) => f.debug_tuple("Object").field(obj).finish(),
             Value::DataFrame(df) => f
@@ -166,6 +236,11 @@ impl Value {
             Value::BigInt(_) => "biginteger",
             Value::Float(_) => "float",
             Value::String(_) => "string",
+            Value::Date(_) => "date",
+            Value::Datetime { .. } => "datetime",
+            Value::Duration(_) => "duration",
+            Value::Decimal { .. } => "decimal",
+            Value::Binary(_) => "binary",
             Value::Array(_) => "array",
             Value::Object(_) => "object",
             Value::DataFrame(_) => "dataframe",
@@ -175,6 +250,10 @@ impl Value {
     }
 
     /// Convert to JSON value (for jaq compatibility)
+    ///
+    /// Dates and datetimes become ISO 8601 strings, durations a number of
+    /// seconds, decimals a string with every digit kept, and binary a base64
+    /// string.
     pub fn to_json(&self) -> crate::Result<JsonValue> {
         match self {
             Value::Null => Ok(JsonValue::Null),
@@ -185,6 +264,14 @@ impl Value {
                 .map(JsonValue::Number)
                 .ok_or_else(|| crate::error::operation_error(format!("Invalid float: {f}"))),
             Value::String(s) => Ok(JsonValue::String(s.clone())),
+            Value::Date(_) | Value::Datetime { .. } | Value::Decimal { .. } => {
+                Ok(JsonValue::String(self.scalar_to_string()))
+            }
+            Value::Duration(d) => Ok(JsonValue::Number(duration_to_json_number(*d))),
+            Value::Binary(bytes) => {
+                use base64::{engine::general_purpose, Engine as _};
+                Ok(JsonValue::String(general_purpose::STANDARD.encode(bytes)))
+            }
             Value::Array(arr) => {
                 let json_arr: crate::Result<Vec<JsonValue>> =
                     arr.iter().map(Value::to_json).collect();
@@ -215,6 +302,16 @@ impl Value {
         }
     }
 
+    /// Text form of a date, datetime or decimal
+    fn scalar_to_string(&self) -> String {
+        match self {
+            Value::Date(d) => d.format("%Y-%m-%d").to_string(),
+            Value::Datetime { datetime, tz } => format_datetime(*datetime, tz.as_deref()),
+            Value::Decimal { value, scale } => format_decimal(*value, *scale),
+            _ => self.to_string(),
+        }
+    }
+
     /// Helper to convert `DataFrame` to JSON array
     #[cfg(not(target_arch = "wasm32"))]
     fn dataframe_to_json_array(df: &polars::prelude::DataFrame) -> crate::Result<JsonValue> {
@@ -398,58 +495,17 @@ impl Value {
                     .get(index);
                 Ok(JsonValue::String(val.unwrap_or("").to_string()))
             }
-            DataType::Binary => {
-                let val = series
-                    .binary()
-                    .map_err(|e| {
-                        crate::error::operation_error(format!("Binary access error: {e}"))
-                    })?
-                    .get(index);
-                // Convert binary to base64 string for JSON representation
-                if let Some(bytes) = val {
-                    use base64::{engine::general_purpose, Engine as _};
-                    Ok(JsonValue::String(general_purpose::STANDARD.encode(bytes)))
-                } else {
-                    Ok(JsonValue::Null)
-                }
-            }
-            DataType::Date => {
-                let val = series
-                    .date()
-                    .map_err(|e| crate::error::operation_error(format!("Date access error: {e}")))?
-                    .phys
-                    .get(index);
-                if let Some(days) = val {
-                    let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
-                    let actual_date = epoch + chrono::Duration::days(i64::from(days));
-                    Ok(JsonValue::String(
-                        actual_date.format("%Y-%m-%d").to_string(),
-                    ))
-                } else {
-                    Ok(JsonValue::Null)
-                }
-            }
-            DataType::Datetime(_, _) => {
-                let val = series
-                    .datetime()
-                    .map_err(|e| {
-                        crate::error::operation_error(format!("Datetime access error: {e}"))
-                    })?
-                    .phys
-                    .get(index);
-                if let Some(ns) = val {
-                    let secs = ns / 1_000_000_000;
-                    #[allow(clippy::cast_sign_loss)]
-                    let nsecs = (ns % 1_000_000_000) as u32;
-                    let dt = chrono::DateTime::from_timestamp(secs, nsecs)
-                        .unwrap()
-                        .naive_utc();
-                    Ok(JsonValue::String(
-                        dt.format("%Y-%m-%d %H:%M:%S%.f").to_string(),
-                    ))
-                } else {
-                    Ok(JsonValue::Null)
-                }
+            DataType::List(_)
+            | DataType::Struct(_)
+            | DataType::Binary
+            | DataType::Date
+            | DataType::Datetime(_, _)
+            | DataType::Duration(_)
+            | DataType::Decimal(_, _) => {
+                let av = series.get(index).map_err(|e| {
+                    crate::error::operation_error(format!("Series access error: {e}"))
+                })?;
+                value_from_any_value(av).map_or(Ok(JsonValue::Null), |v| v.to_json())
             }
             _ => Err(crate::error::operation_error(format!(
                 "Unsupported series type: {:?}",
@@ -506,49 +562,30 @@ impl Value {
                     return Ok(DataFrame::empty());
                 }
 
-                // Check if all elements are objects with the same keys
-                let Value::Object(first_obj) = &arr[0] else {
-                    return Err(crate::error::operation_error(
-                        "Cannot convert array to DataFrame: not all elements are objects",
-                    ));
-                };
-
-                let columns: Vec<String> = first_obj.keys().cloned().collect();
-                let num_rows = arr.len();
-                let num_cols = columns.len();
-
-                // Directly allocate column vectors without HashMap intermediate
-                // This reduces memory allocations and improves cache locality
-                let mut column_data: Vec<Vec<AnyValue>> = Vec::with_capacity(num_cols);
-                for _ in 0..num_cols {
-                    column_data.push(Vec::with_capacity(num_rows));
+                let mut rows = Vec::with_capacity(arr.len());
+                for value in arr {
+                    let Value::Object(obj) = value else {
+                        return Err(crate::error::operation_error(
+                            "Cannot convert array to DataFrame: not all elements are objects",
+                        ));
+                    };
+                    rows.push(obj);
                 }
 
-                // Single-pass row processing - columnar collection
-                for value in arr {
-                    match value {
-                        Value::Object(obj) => {
-                            for (col_idx, col) in columns.iter().enumerate() {
-                                let val = obj.get(col).unwrap_or(&Value::Null);
-                                let any_val = Self::value_to_any_value(val)?;
-                                // Direct indexing - we know col_idx is valid
-                                column_data[col_idx].push(any_val);
-                            }
-                        }
-                        _ => {
-                            return Err(crate::error::operation_error(
-                                "Cannot convert array to DataFrame: not all elements are objects",
-                            ));
-                        }
-                    }
+                // Columns are the union of the keys of all rows, in the order
+                // they first appear
+                let mut columns = indexmap::IndexSet::new();
+                for obj in &rows {
+                    columns.extend(obj.keys().map(String::as_str));
                 }
 
-                // Create Series directly from column vectors
-                let mut series_vec = Vec::with_capacity(num_cols);
-                for (col_idx, col_name) in columns.into_iter().enumerate() {
-                    let values = std::mem::take(&mut column_data[col_idx]);
-                    let series = Series::new(col_name.into(), values);
-                    series_vec.push(series.into());
+                let mut series_vec = Vec::with_capacity(columns.len());
+                for col_name in columns {
+                    let values: Vec<&Value> = rows
+                        .iter()
+                        .map(|obj| obj.get(col_name).unwrap_or(&Value::Null))
+                        .collect();
+                    series_vec.push(schema::values_to_series(col_name, &values)?.into());
                 }
 
                 DataFrame::new(series_vec).map_err(|e| {
@@ -562,8 +599,9 @@ impl Value {
         }
     }
 
-    /// Helper to convert Value to `AnyValue` for Polars
-    fn value_to_any_value(value: &Value) -> crate::Result<polars::prelude::AnyValue<'_>> {
+    /// Convert a scalar to the Polars `AnyValue` of the matching dtype
+    pub fn to_any_value(&self) -> crate::Result<polars::prelude::AnyValue<'_>> {
+        let value = self;
         match value {
             Value::Null => Ok(AnyValue::Null),
             Value::Bool(b) => Ok(AnyValue::Boolean(*b)),
@@ -573,6 +611,35 @@ impl Value {
             )),
             Value::Float(f) => Ok(AnyValue::Float64(*f)),
             Value::String(s) => Ok(AnyValue::String(s)),
+            Value::Date(d) => {
+                let days = d.signed_duration_since(NaiveDate::default()).num_days();
+                i32::try_from(days)
+                    .map(AnyValue::Date)
+                    .map_err(|_| crate::error::operation_error(format!("Date out of range: {d}")))
+            }
+            Value::Datetime { datetime, tz } => {
+                let ns = datetime.and_utc().timestamp_nanos_opt().ok_or_else(|| {
+                    crate::error::operation_error(format!("Datetime out of range: {datetime}"))
+                })?;
+                let tz = polars::prelude::TimeZone::opt_try_new(tz.as_deref()).map_err(|e| {
+                    crate::error::operation_error(format!("Invalid time zone: {e}"))
+                })?;
+                Ok(AnyValue::DatetimeOwned(
+                    ns,
+                    TimeUnit::Nanoseconds,
+                    tz.map(std::sync::Arc::new),
+                ))
+            }
+            Value::Duration(d) => d
+                .num_nanoseconds()
+                .map(|ns| AnyValue::Duration(ns, TimeUnit::Nanoseconds))
+                .ok_or_else(|| {
+                    crate::error::operation_error(format!("Duration out of range: {d}"))
+                }),
+            Value::Decimal { value, scale } => {
+                Ok(AnyValue::Decimal(*value, DECIMAL_PRECISION, *scale))
+            }
+            Value::Binary(bytes) => Ok(AnyValue::Binary(bytes)),
             _ => Err(crate::error::operation_error(format!(
                 "Cannot convert {} to AnyValue",
                 value.type_name()
@@ -586,6 +653,7 @@ impl Value {
         match self {
             Value::Array(arr) => Some(arr.len()),
             Value::String(s) => Some(s.len()),
+            Value::Binary(b) => Some(b.len()),
             Value::DataFrame(df) => Some(df.height()),
             Value::LazyFrame(_) => None, // LazyFrames are not collected, so len is unknown
             Value::Series(s) => Some(s.len()),
@@ -664,7 +732,7 @@ impl Value {
 
                 if index >= 0 && index < len {
                     // Return a row as an object
-                    let mut row_obj = HashMap::new();
+                    let mut row_obj = IndexMap::new();
                     for col_name in df.get_column_names() {
                         let series = df.column(col_name).map_err(|e| {
                             crate::error::operation_error(format!("Column access error: {e}"))
@@ -715,6 +783,13 @@ impl Value {
                     Err(_) => Ok(Value::Null),
                 }
             }
+            Value::Series(s) if matches!(s.dtype(), DataType::Struct(_)) => {
+                // Return the field of a struct column as a Series
+                match s.struct_().and_then(|ca| ca.field_by_name(key)) {
+                    Ok(field) => Ok(Value::Series(field)),
+                    Err(_) => Ok(Value::Null),
+                }
+            }
             Value::LazyFrame(lf) => {
                 // Use select to extract the column while staying lazy
                 let selected = lf.clone().select(&[col(key)]);
@@ -759,6 +834,40 @@ impl PartialEq for Value {
             (Value::BigInt(a), Value::BigInt(b)) => a == b,
             (Value::Float(a), Value::Float(b)) => a == b,
             (Value::String(a), Value::String(b)) => a == b,
+            (Value::Date(a), Value::Date(b)) => a == b,
+            // Zoned datetimes are UTC instants, so equal instants in
+            // different zones compare equal
+            (
+                Value::Datetime {
+                    datetime: a,
+                    tz: tz_a,
+                },
+                Value::Datetime {
+                    datetime: b,
+                    tz: tz_b,
+                },
+            ) => tz_a.is_some() == tz_b.is_some() && a == b,
+            (Value::Duration(a), Value::Duration(b)) => a == b,
+            (Value::Binary(a), Value::Binary(b)) => a == b,
+            (
+                Value::Decimal {
+                    value: a,
+                    scale: scale_a,
+                },
+                Value::Decimal {
+                    value: b,
+                    scale: scale_b,
+                },
+            ) => align_decimals((*a, *scale_a), (*b, *scale_b)).is_some_and(|(a, b, _)| a == b),
+            (Value::Decimal { value, scale }, Value::Int(i))
+            | (Value::Int(i), Value::Decimal { value, scale }) => {
+                align_decimals((*value, *scale), (i128::from(*i), 0))
+                    .is_some_and(|(a, b, _)| a == b)
+            }
+            (Value::Decimal { value, scale }, Value::Float(f))
+            | (Value::Float(f), Value::Decimal { value, scale }) => {
+                decimal_to_f64(*value, *scale) == *f
+            }
             (Value::Array(a), Value::Array(b)) => a == b,
             (Value::Object(a), Value::Object(b)) => a == b,
             // For DataFrames, no comparison implemented
@@ -789,6 +898,11 @@ impl std::fmt::Display for Value {
             Value::BigInt(bi) => write!(f, "{bi}"),
             Value::Float(fl) => write!(f, "{fl}"),
             Value::String(s) => write!(f, "\"{s}\""),
+            Value::Date(_) | Value::Datetime { .. } | Value::Decimal { .. } => {
+                write!(f, "{}", self.scalar_to_string())
+            }
+            Value::Duration(d) => write!(f, "{d}"),
+            Value::Binary(b) => write!(f, "Binary({} bytes)", b.len()),
             Value::Array(arr) => {
                 write!(f, "[")?;
                 for (i, item) in arr.iter().enumerate() {
@@ -835,6 +949,14 @@ impl serde::Serialize for Value {
             Value::BigInt(bi) => bi.serialize(serializer),
             Value::Float(f) => serializer.serialize_f64(*f),
             Value::String(s) => serializer.serialize_str(s),
+            Value::Date(_)
+            | Value::Datetime { .. }
+            | Value::Duration(_)
+            | Value::Decimal { .. }
+            | Value::Binary(_) => self
+                .to_json()
+                .map_err(serde::ser::Error::custom)?
+                .serialize(serializer),
             Value::Array(arr) => {
                 let mut seq = serializer.serialize_seq(Some(arr.len()))?;
                 for item in arr {
@@ -893,13 +1015,61 @@ pub fn value_from_any_value(av: polars::prelude::AnyValue<'_>) -> Option<Value>
         AnyValue::UInt64(i) => Some(Value::Int(i as i64)),
         AnyValue::Float32(f) => Some(Value::Float(f64::from(f))),
         AnyValue::Float64(f) => Some(Value::Float(f)),
+        AnyValue::StringOwned(s) => Some(Value::String(s.to_string())),
+        AnyValue::Date(days) => NaiveDate::default()
+            .checked_add_signed(Duration::days(i64::from(days)))
+            .map(Value::Date),
+        AnyValue::Datetime(v, unit, tz) => {
+            datetime_from_physical(v, unit).map(|datetime| Value::Datetime {
+                datetime,
+                tz: tz.map(ToString::to_string),
+            })
+        }
+        AnyValue::DatetimeOwned(v, unit, tz) => {
+            datetime_from_physical(v, unit).map(|datetime| Value::Datetime {
+                datetime,
+                tz: tz.map(|tz| tz.to_string()),
+            })
+        }
+        AnyValue::Duration(v, unit) => Some(Value::Duration(match unit {
+            TimeUnit::Nanoseconds => Duration::nanoseconds(v),
+            TimeUnit::Microseconds => Duration::microseconds(v),
+            TimeUnit::Milliseconds => Duration::milliseconds(v),
+        })),
+        AnyValue::Decimal(value, _precision, scale) => Some(Value::Decimal { value, scale }),
+        AnyValue::Binary(bytes) => Some(Value::Binary(bytes.to_vec())),
+        AnyValue::BinaryOwned(bytes) => Some(Value::Binary(bytes)),
+        AnyValue::List(series) => series
+            .iter()
+            .map(value_from_any_value)
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        AnyValue::Struct(_, _, fields) => av
+            ._iter_struct_av()
+            .zip(fields)
+            .map(|(field_av, field)| {
+                value_from_any_value(field_av).map(|v| (field.name().to_string(), v))
+            })
+            .collect::<Option<IndexMap<_, _>>>()
+            .map(Value::Object),
+        AnyValue::StructOwned(payload) => {
+            let (values, fields) = *payload;
+            values
+                .into_iter()
+                .zip(fields)
+                .map(|(field_av, field)| {
+                    value_from_any_value(field_av).map(|v| (field.name().to_string(), v))
+                })
+                .collect::<Option<IndexMap<_, _>>>()
+                .map(Value::Object)
+        }
         _ => None,
     }
 }
 
 /// Convert a `DataFrame` row to a `Value::Object`
 pub fn df_row_to_value(df: &polars::prelude::DataFrame, row_idx: usize) -> crate::Result<Value> {
-    let mut obj = HashMap::new();
+    let mut obj = IndexMap::new();
 
     for col_name in df.get_column_names() {
         let series = df
@@ -959,40 +1129,120 @@ fn series_value_at(series: &polars::prelude::Series, idx: usize) -> crate::Resul
                 .get(idx);
             Ok(Value::String(val.unwrap_or("").to_string()))
         }
-        DataType::Date => {
-            let val = series
-                .date()
-                .map_err(|e| crate::error::operation_error(format!("Date access error: {e}")))?
-                .phys
-                .get(idx);
-            if let Some(days) = val {
-                let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
-                let actual_date = epoch + Duration::days(i64::from(days));
-                Ok(Value::String(actual_date.format("%Y-%m-%d").to_string()))
-            } else {
-                Ok(Value::Null)
-            }
-        }
-        DataType::Datetime(_, _) => {
-            let val = series
-                .datetime()
-                .map_err(|e| crate::error::operation_error(format!("Datetime access error: {e}")))?
-                .phys
-                .get(idx);
-            if let Some(ns) = val {
-                let secs = ns / 1_000_000_000;
-                #[allow(clippy::cast_sign_loss)]
-                let nsecs = (ns % 1_000_000_000) as u32;
-                let dt = DateTime::from_timestamp(secs, nsecs).unwrap().naive_utc();
-                Ok(Value::String(dt.format("%Y-%m-%d %H:%M:%S%.f").to_string()))
-            } else {
-                Ok(Value::Null)
-            }
+        DataType::List(_)
+        | DataType::Struct(_)
+        | DataType::Binary
+        | DataType::Date
+        | DataType::Datetime(_, _)
+        | DataType::Duration(_)
+        | DataType::Decimal(_, _) => {
+            let av = series
+                .get(idx)
+                .map_err(|e| crate::error::operation_error(format!("Series access error: {e}")))?;
+            Ok(value_from_any_value(av).unwrap_or(Value::Null))
         }
         _ => Ok(Value::Null), // For unsupported types, return null
     }
 }
 
+/// Precision given to decimals handed to Polars, the largest it supports
+const DECIMAL_PRECISION: usize = 38;
+
+/// Convert a Polars physical datetime to a `NaiveDateTime`
+fn datetime_from_physical(v: i64, unit: TimeUnit) -> Option<NaiveDateTime> {
+    let datetime = match unit {
+        TimeUnit::Nanoseconds => Some(DateTime::from_timestamp_nanos(v)),
+        TimeUnit::Microseconds => DateTime::from_timestamp_micros(v),
+        TimeUnit::Milliseconds => DateTime::from_timestamp_millis(v),
+    };
+    datetime.map(|dt| dt.naive_utc())
+}
+
+/// Wall-clock time of a [`Value::Datetime`] in its time zone
+///
+/// Naive datetimes and unknown zones are returned unchanged.
+#[must_use]
+pub fn local_datetime(datetime: NaiveDateTime, tz: Option<&str>) -> NaiveDateTime {
+    match tz.and_then(|tz| tz.parse::<chrono_tz::Tz>().ok()) {
+        Some(zone) => zone.from_utc_datetime(&datetime).naive_local(),
+        None => datetime,
+    }
+}
+
+/// UTC instant of a wall-clock time in a time zone, the inverse of
+/// [`local_datetime`]
+///
+/// Returns `None` for wall-clock times skipped by a daylight saving
+/// transition. Ambiguous times resolve to the earlier instant.
+#[must_use]
+pub fn utc_datetime(local: NaiveDateTime, tz: Option<&str>) -> Option<NaiveDateTime> {
+    match tz.and_then(|tz| tz.parse::<chrono_tz::Tz>().ok()) {
+        Some(zone) => zone
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|dt| dt.naive_utc()),
+        None => Some(local),
+    }
+}
+
+/// ISO 8601 text of a datetime, with its UTC offset when it has a time zone
+fn format_datetime(datetime: NaiveDateTime, tz: Option<&str>) -> String {
+    match tz {
+        None => datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+        Some(tz) => match tz.parse::<chrono_tz::Tz>() {
+            Ok(zone) => zone
+                .from_utc_datetime(&datetime)
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            Err(_) => datetime
+                .and_utc()
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        },
+    }
+}
+
+/// Exact text of a decimal, e.g. `-0.050` for value -50 and scale 3
+fn format_decimal(value: i128, scale: usize) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let sign = if value < 0 { "-" } else { "" };
+    if scale == 0 {
+        return format!("{sign}{digits}");
+    }
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+    format!("{sign}{int_part}.{frac_part}")
+}
+
+/// Nearest `f64` to a decimal
+#[must_use]
+pub fn decimal_to_f64(value: i128, scale: usize) -> f64 {
+    format_decimal(value, scale).parse().unwrap_or(f64::NAN)
+}
+
+/// Rescale two decimals to their larger scale
+///
+/// Returns both unscaled values and the common scale, or `None` if
+/// rescaling overflows.
+#[must_use]
+pub fn align_decimals(a: (i128, usize), b: (i128, usize)) -> Option<(i128, i128, usize)> {
+    let scale = a.1.max(b.1);
+    let rescale = |(value, from): (i128, usize)| {
+        let exp = u32::try_from(scale - from).ok()?;
+        10i128.checked_pow(exp)?.checked_mul(value)
+    };
+    Some((rescale(a)?, rescale(b)?, scale))
+}
+
+/// JSON number of seconds in a duration, integral when it is whole seconds
+fn duration_to_json_number(d: Duration) -> JsonNumber {
+    if d.subsec_nanos() == 0 {
+        JsonNumber::from(d.num_seconds())
+    } else {
+        #[allow(clippy::cast_precision_loss)]
+        let secs = d.num_seconds() as f64 + f64::from(d.subsec_nanos()) / 1e9;
+        JsonNumber::from_f64(secs).unwrap_or_else(|| JsonNumber::from(d.num_seconds()))
+    }
+}
+
 /// Check if a value is truthy (non-null, non-empty, non-zero)
 #[must_use]
 pub fn is_truthy(v: &Value) -> bool {
@@ -1003,6 +1253,10 @@ pub fn is_truthy(v: &Value) -> bool {
         Value::BigInt(bi) => !bi.is_zero(),
         Value::Float(f) => *f != 0.0 && !f.is_nan(),
         Value::String(s) => !s.is_empty(),
+        Value::Date(_) | Value::Datetime { .. } => true,
+        Value::Duration(d) => !d.is_zero(),
+        Value::Decimal { value, .. } => *value != 0,
+        Value::Binary(b) => !b.is_empty(),
         Value::Array(a) => !a.is_empty(),
         Value::Object(o) => !o.is_empty(),
         Value::DataFrame(df) => df.height() > 0,
@@ -1040,7 +1294,7 @@ mod tests {
         let arr = vec![Value::int(1), Value::int(2)];
         assert_eq!(Value::array(arr.clone()), Value::Array(arr));
 
-        let obj = HashMap::from([("key".to_string(), Value::string("value"))]);
+        let obj = IndexMap::from([("key".to_string(), Value::string("value"))]);
         assert_eq!(Value::object(obj.clone()), Value::Object(obj));
 
         let df = DataFrame::new(vec![Series::new("a".into(), vec![1, 2, 3]).into()]).unwrap();
@@ -1082,11 +1336,11 @@ mod tests {
         assert!(!is_truthy(&Value::string("")));
         assert!(is_truthy(&Value::array(vec![Value::int(1)])));
         assert!(!is_truthy(&Value::array(vec![])));
-        assert!(is_truthy(&Value::object(HashMap::from([(
+        assert!(is_truthy(&Value::object(IndexMap::from([(
             "k".to_string(),
             Value::int(1)
         )]))));
-        assert!(!is_truthy(&Value::object(HashMap::new())));
+        assert!(!is_truthy(&Value::object(IndexMap::new())));
         assert!(is_truthy(&Value::dataframe(df)));
         assert!(is_truthy(&Value::series(series)));
         assert!(is_truthy(&Value::lazy_frame(lf)));
@@ -1174,7 +1428,7 @@ mod tests {
         assert_eq!(Value::from_json(json), arr);
 
         // Object
-        let obj = Value::object(HashMap::from([
+        let obj = Value::object(IndexMap::from([
             ("name".to_string(), Value::string("Alice")),
             ("age".to_string(), Value::int(30)),
             ("active".to_string(), Value::bool(true)),
@@ -1189,14 +1443,14 @@ mod tests {
         assert_eq!(Value::from_json(json), obj);
 
         // Nested structures
-        let nested = Value::object(HashMap::from([
+        let nested = Value::object(IndexMap::from([
             (
                 "data".to_string(),
                 Value::array(vec![Value::int(1), Value::int(2)]),
             ),
             (
                 "meta".to_string(),
-                Value::object(HashMap::from([("count".to_string(), Value::int(2))])),
+                Value::object(IndexMap::from([("count".to_string(), Value::int(2))])),
             ),
         ]));
         let json = nested.to_json().unwrap();
@@ -1318,7 +1572,7 @@ mod tests {
 
     #[test]
     fn test_indexing_invalid() {
-        let obj = Value::object(HashMap::new());
+        let obj = Value::object(IndexMap::new());
         assert!(obj.index(0).is_err());
 
         let null_val = Value::Null;
@@ -1327,12 +1581,12 @@ mod tests {
 
     #[test]
     fn test_field_access() {
-        let obj = Value::object(HashMap::from([
+        let obj = Value::object(IndexMap::from([
             ("name".to_string(), Value::string("Bob")),
             ("age".to_string(), Value::int(25)),
             (
                 "nested".to_string(),
-                Value::object(HashMap::from([("inner".to_string(), Value::bool(true))])),
+                Value::object(IndexMap::from([("inner".to_string(), Value::bool(true))])),
             ),
         ]));
 
@@ -1352,11 +1606,11 @@ mod tests {
     #[test]
     fn test_field_access_array() {
         let arr = Value::array(vec![
-            Value::object(HashMap::from([(
+            Value::object(IndexMap::from([(
                 "name".to_string(),
                 Value::string("Alice"),
             )])),
-            Value::object(HashMap::from([("name".to_string(), Value::string("Bob"))])),
+            Value::object(IndexMap::from([("name".to_string(), Value::string("Bob"))])),
         ]);
 
         let result = arr.field("name").unwrap();
@@ -1406,7 +1660,7 @@ mod tests {
         assert_eq!(Value::Float(std::f64::consts::PI).type_name(), "float");
         assert_eq!(Value::String("test".to_string()).type_name(), "string");
         assert_eq!(Value::Array(vec![]).type_name(), "array");
-        assert_eq!(Value::Object(HashMap::new()).type_name(), "object");
+        assert_eq!(Value::Object(IndexMap::new()).type_name(), "object");
     }
 
     #[test]
@@ -1436,8 +1690,8 @@ mod tests {
         let arr2 = Value::array(vec![Value::int(1), Value::int(2)]);
         assert_eq!(arr1, arr2);
 
-        let obj1 = Value::object(HashMap::from([("a".to_string(), Value::int(1))]));
-        let obj2 = Value::object(HashMap::from([("a".to_string(), Value::int(1))]));
+        let obj1 = Value::object(IndexMap::from([("a".to_string(), Value::int(1))]));
+        let obj2 = Value::object(IndexMap::from([("a".to_string(), Value::int(1))]));
         assert_eq!(obj1, obj2);
 
         // Inequalities
@@ -1469,7 +1723,7 @@ mod tests {
         assert_eq!(json, "[1,\"two\"]");
 
         // Object
-        let obj = Value::object(HashMap::from([
+        let obj = Value::object(IndexMap::from([
             ("a".to_string(), Value::int(1)),
             ("b".to_string(), Value::string("x")),
         ]));
@@ -1493,7 +1747,7 @@ mod tests {
         assert!(format!("{:?}", Value::int(42)).contains("Int(42)"));
         assert!(format!("{:?}", Value::string("hello")).contains("String(\"hello\")"));
         assert!(format!("{:?}", Value::array(vec![Value::int(1)])).contains("Array([Int(1)]"));
-        assert!(format!("{:?}", Value::object(HashMap::new())).contains("Object({})"));
+        assert!(format!("{:?}", Value::object(IndexMap::new())).contains("Object({})"));
         assert!(format!("{:?}", Value::dataframe(DataFrame::empty())).contains("DataFrame"));
         assert!(
             format!("{:?}", Value::series(Series::new("test".into(), vec![1]))).contains("Series")
@@ -1513,7 +1767,7 @@ mod tests {
         let arr = Value::array(vec![Value::int(1), Value::string("two")]);
         assert_eq!(format!("{}", arr), "[1, \"two\"]");
 
-        let obj = Value::object(HashMap::from([
+        let obj = Value::object(IndexMap::from([
             ("a".to_string(), Value::int(1)),
             ("b".to_string(), Value::string("x")),
         ]));
@@ -1526,11 +1780,11 @@ mod tests {
     fn test_to_dataframe() {
         // Array of objects
         let data = Value::array(vec![
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("name".to_string(), Value::string("Alice")),
                 ("age".to_string(), Value::int(30)),
             ])),
-            Value::object(HashMap::from([
+            Value::object(IndexMap::from([
                 ("name".to_string(), Value::string("Bob")),
                 ("age".to_string(), Value::int(25)),
             ])),
@@ -1555,12 +1809,13 @@ mod tests {
         let invalid = Value::array(vec![Value::int(1), Value::string("not object")]);
         assert!(invalid.to_dataframe().is_err());
 
-        // Array with BigInt (unsupported in AnyValue)
-        let data = Value::array(vec![Value::object(HashMap::from([
+        // BigInt has no Polars integer type and is kept as text
+        let data = Value::array(vec![Value::object(IndexMap::from([
             ("name".to_string(), Value::string("Alice")),
             ("big".to_string(), Value::bigint(BigInt::from(123))),
         ]))]);
-        assert!(data.to_dataframe().is_err());
+        let df = data.to_dataframe().unwrap();
+        assert_eq!(df.column("big").unwrap().str().unwrap().get(0), Some("123"));
 
         // DataFrame input
         let df = DataFrame::new(vec![
@@ -1604,8 +1859,139 @@ mod tests {
             Some(Value::string("hello"))
         );
 
+        assert_eq!(
+            value_from_any_value(AnyValue::Date(0)),
+            Some(Value::date(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()))
+        );
+        assert_eq!(
+            value_from_any_value(AnyValue::Decimal(12345, 10, 2)),
+            Some(Value::decimal(12345, 2))
+        );
+        assert_eq!(
+            value_from_any_value(AnyValue::Duration(1_500, TimeUnit::Milliseconds)),
+            Some(Value::duration(Duration::milliseconds(1_500)))
+        );
+        assert_eq!(
+            value_from_any_value(AnyValue::Binary(b"abc")),
+            Some(Value::binary(b"abc".to_vec()))
+        );
+
         // Unsupported types return None
-        assert_eq!(value_from_any_value(AnyValue::Date(0)), None);
+        assert_eq!(value_from_any_value(AnyValue::Time(0)), None);
+    }
+
+    #[test]
+    fn test_temporal_json() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+
+        assert_eq!(
+            Value::date(datetime.date()).to_json().unwrap(),
+            json!("2024-01-15")
+        );
+        assert_eq!(
+            Value::datetime(datetime, None::<String>).to_json().unwrap(),
+            json!("2024-01-15T09:30:00")
+        );
+        assert_eq!(
+            Value::datetime(datetime, Some("UTC")).to_json().unwrap(),
+            json!("2024-01-15T09:30:00Z")
+        );
+        // Zoned datetimes hold UTC instants and display in their zone
+        assert_eq!(
+            Value::datetime(datetime, Some("Europe/Berlin"))
+                .to_json()
+                .unwrap(),
+            json!("2024-01-15T10:30:00+01:00")
+        );
+        assert_eq!(
+            Value::duration(Duration::minutes(90)).to_json().unwrap(),
+            json!(5400)
+        );
+        assert_eq!(
+            Value::duration(Duration::milliseconds(1_500))
+                .to_json()
+                .unwrap(),
+            json!(1.5)
+        );
+        assert_eq!(Value::decimal(-5, 3).to_json().unwrap(), json!("-0.005"));
+        assert_eq!(
+            Value::decimal(123_456_789_012_345_678_901_234, 4)
+                .to_json()
+                .unwrap(),
+            json!("12345678901234567890.1234")
+        );
+        assert_eq!(
+            Value::binary(b"hi".to_vec()).to_json().unwrap(),
+            json!("aGk=")
+        );
+        assert_eq!(
+            serde_json::to_string(&Value::decimal(250, 2)).unwrap(),
+            r#""2.50""#
+        );
+    }
+
+    #[test]
+    fn test_temporal_equality() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+
+        // The same instant in different zones is equal
+        assert_eq!(
+            Value::datetime(datetime, Some("UTC")),
+            Value::datetime(datetime, Some("Asia/Tokyo"))
+        );
+        assert_ne!(
+            Value::datetime(datetime, Some("UTC")),
+            Value::datetime(datetime, None::<String>)
+        );
+        assert_eq!(Value::decimal(150, 2), Value::decimal(15, 1));
+        assert_eq!(Value::decimal(200, 2), Value::int(2));
+        assert_eq!(Value::decimal(25, 1), Value::float(2.5));
+    }
+
+    #[test]
+    fn test_temporal_dataframe_round_trip() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        let mut row = IndexMap::new();
+        row.insert("day".to_string(), Value::date(datetime.date()));
+        row.insert(
+            "at".to_string(),
+            Value::datetime(datetime, Some("America/New_York")),
+        );
+        row.insert("took".to_string(), Value::duration(Duration::seconds(42)));
+        row.insert("price".to_string(), Value::decimal(1999, 2));
+        row.insert("blob".to_string(), Value::binary(vec![0, 255]));
+        let row = Value::Object(row);
+
+        let df = Value::Array(vec![row.clone()]).to_dataframe().unwrap();
+        assert_eq!(df.column("day").unwrap().dtype(), &DataType::Date);
+        assert_eq!(
+            df.column("at").unwrap().dtype(),
+            &DataType::Datetime(
+                TimeUnit::Nanoseconds,
+                Some(
+                    polars::prelude::TimeZone::opt_try_new(Some("America/New_York"))
+                        .unwrap()
+                        .unwrap()
+                )
+            )
+        );
+        assert_eq!(
+            df.column("took").unwrap().dtype(),
+            &DataType::Duration(TimeUnit::Nanoseconds)
+        );
+        assert!(df.column("price").unwrap().dtype().is_decimal());
+        assert_eq!(df.column("blob").unwrap().dtype(), &DataType::Binary);
+
+        assert_eq!(df_row_to_value(&df, 0).unwrap(), row);
     }
 
     #[test]
@@ -1647,4 +2033,38 @@ mod tests {
         // Out of bounds
         assert_eq!(series_value_at(&series, 10).unwrap(), Value::Null);
     }
+
+    #[test]
+    fn test_series_value_at_nested() {
+        let list = Series::new(
+            "tags".into(),
+            [
+                Series::new("".into(), ["a", "b"]),
+                Series::new("".into(), ["c"]),
+            ],
+        );
+        assert_eq!(
+            series_value_at(&list, 0).unwrap(),
+            Value::array(vec![Value::string("a"), Value::string("b")])
+        );
+
+        let structs = StructChunked::from_series(
+            "point".into(),
+            1,
+            [
+                Series::new("x".into(), [1i64]),
+                Series::new("y".into(), [2i64]),
+            ]
+            .iter(),
+        )
+        .unwrap()
+        .into_series();
+        assert_eq!(
+            series_value_at(&structs, 0).unwrap(),
+            Value::object(IndexMap::from([
+                ("x".to_string(), Value::int(1)),
+                ("y".to_string(), Value::int(2)),
+            ]))
+        );
+    }
 }
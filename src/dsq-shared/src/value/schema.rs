@@ -0,0 +1,242 @@
+//! Polars dtypes for columns of nested values
+//!
+//! Rows read from JSON rarely agree on a single shape: a field may be
+//! missing from one object, null in another and a float in a third. The
+//! dtype of a column is the merge of the dtypes of all its values, and
+//! every value is then converted to that dtype, so nested arrays and
+//! objects become `List` and `Struct` columns instead of being dropped.
+
+use polars::prelude::*;
+use serde_json::Value as JsonValue;
+
+use super::{align_decimals, Value, DECIMAL_PRECISION};
+
+/// Infer the Polars dtype of a single value
+#[must_use]
+pub fn infer_dtype(value: &Value) -> DataType {
+    match value {
+        Value::Null => DataType::Null,
+        Value::Bool(_) => DataType::Boolean,
+        Value::Int(_) => DataType::Int64,
+        Value::Float(_) => DataType::Float64,
+        Value::Date(_) => DataType::Date,
+        Value::Datetime { tz, .. } => match TimeZone::opt_try_new(tz.as_deref()) {
+            Ok(tz) => DataType::Datetime(TimeUnit::Nanoseconds, tz),
+            Err(_) => DataType::String,
+        },
+        Value::Duration(_) => DataType::Duration(TimeUnit::Nanoseconds),
+        Value::Decimal { scale, .. } => DataType::Decimal(DECIMAL_PRECISION, *scale),
+        Value::Binary(_) => DataType::Binary,
+        Value::Array(items) => DataType::List(Box::new(
+            items
+                .iter()
+                .map(infer_dtype)
+                .fold(DataType::Null, merge_dtypes),
+        )),
+        Value::Object(obj) => DataType::Struct(
+            obj.iter()
+                .map(|(key, value)| Field::new(key.as_str().into(), infer_dtype(value)))
+                .collect(),
+        ),
+        // Big integers do not fit any Polars integer, and frames nested in
+        // rows have no column representation, so both are kept as text
+        Value::BigInt(_)
+        | Value::String(_)
+        | Value::DataFrame(_)
+        | Value::LazyFrame(_)
+        | Value::Series(_) => DataType::String,
+    }
+}
+
+/// Merge two dtypes into one that can hold the values of both
+///
+/// Nulls take the other dtype, integers widen to floats, decimals widen to
+/// the larger scale, lists merge their inner dtypes and structs take the
+/// union of their fields in the order they were first seen. Anything else
+/// falls back to `String`, holding each value as JSON text.
+#[must_use]
+pub fn merge_dtypes(a: DataType, b: DataType) -> DataType {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (DataType::Null, other) | (other, DataType::Null) => other,
+        (DataType::Int64, DataType::Float64) | (DataType::Float64, DataType::Int64) => {
+            DataType::Float64
+        }
+        (DataType::Decimal(_, a), DataType::Decimal(_, b)) => {
+            DataType::Decimal(DECIMAL_PRECISION, a.max(b))
+        }
+        (DataType::List(a), DataType::List(b)) => DataType::List(Box::new(merge_dtypes(*a, *b))),
+        (DataType::Struct(mut fields), DataType::Struct(others)) => {
+            for other in others {
+                match fields.iter_mut().find(|f| f.name() == other.name()) {
+                    Some(field) => {
+                        let dtype = merge_dtypes(field.dtype().clone(), other.dtype().clone());
+                        field.coerce(dtype);
+                    }
+                    None => fields.push(other),
+                }
+            }
+            DataType::Struct(fields)
+        }
+        _ => DataType::String,
+    }
+}
+
+/// Build a Series from values, inferring a dtype that holds all of them
+pub fn values_to_series(name: &str, values: &[&Value]) -> crate::Result<Series> {
+    let dtype = values
+        .iter()
+        .map(|value| infer_dtype(value))
+        .fold(DataType::Null, merge_dtypes);
+    let any_values = values
+        .iter()
+        .map(|value| to_any_value_as(value, &dtype))
+        .collect::<crate::Result<Vec<_>>>()?;
+    Series::from_any_values_and_dtype(name.into(), &any_values, &dtype, false)
+        .map_err(|e| crate::error::operation_error(format!("Series creation error: {e}")))
+}
+
+/// Convert a value to an `AnyValue` of `dtype`, as inferred by [`infer_dtype`]
+/// and [`merge_dtypes`]
+fn to_any_value_as(value: &Value, dtype: &DataType) -> crate::Result<AnyValue<'static>> {
+    match (value, dtype) {
+        (Value::Null, _) => Ok(AnyValue::Null),
+        (Value::String(s), DataType::String) => Ok(AnyValue::StringOwned(s.as_str().into())),
+        (_, DataType::String) => {
+            let text = match value.to_json()? {
+                JsonValue::String(s) => s,
+                json => json.to_string(),
+            };
+            Ok(AnyValue::StringOwned(text.into()))
+        }
+        #[allow(clippy::cast_precision_loss)]
+        (Value::Int(i), DataType::Float64) => Ok(AnyValue::Float64(*i as f64)),
+        (Value::Decimal { value, scale }, DataType::Decimal(_, target)) => {
+            let (value, _, scale) = align_decimals((*value, *scale), (0, *target))
+                .ok_or_else(|| crate::error::operation_error("Decimal out of range"))?;
+            Ok(AnyValue::Decimal(value, DECIMAL_PRECISION, scale))
+        }
+        (Value::Array(items), DataType::List(inner)) => {
+            let any_values = items
+                .iter()
+                .map(|item| to_any_value_as(item, inner))
+                .collect::<crate::Result<Vec<_>>>()?;
+            Series::from_any_values_and_dtype(PlSmallStr::EMPTY, &any_values, inner, false)
+                .map(AnyValue::List)
+                .map_err(|e| crate::error::operation_error(format!("List creation error: {e}")))
+        }
+        (Value::Object(obj), DataType::Struct(fields)) => {
+            let values = fields
+                .iter()
+                .map(|field| match obj.get(field.name().as_str()) {
+                    Some(value) => to_any_value_as(value, field.dtype()),
+                    None => Ok(AnyValue::Null),
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+            Ok(AnyValue::StructOwned(Box::new((values, fields.clone()))))
+        }
+        _ => value.to_any_value().map(AnyValue::into_static),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    fn object(pairs: Vec<(&str, Value)>) -> Value {
+        Value::Object(
+            pairs
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect::<IndexMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn test_merge_dtypes() {
+        assert_eq!(
+            merge_dtypes(DataType::Null, DataType::Int64),
+            DataType::Int64
+        );
+        assert_eq!(
+            merge_dtypes(DataType::Int64, DataType::Float64),
+            DataType::Float64
+        );
+        assert_eq!(
+            merge_dtypes(DataType::Boolean, DataType::Int64),
+            DataType::String
+        );
+        assert_eq!(
+            merge_dtypes(
+                DataType::List(Box::new(DataType::Null)),
+                DataType::List(Box::new(DataType::Int64))
+            ),
+            DataType::List(Box::new(DataType::Int64))
+        );
+    }
+
+    #[test]
+    fn test_struct_fields_merge_across_rows() {
+        let first = object(vec![("city", Value::string("Oslo"))]);
+        let second = object(vec![
+            ("zip", Value::Int(1234)),
+            ("city", Value::string("Bergen")),
+        ]);
+        let dtype = merge_dtypes(infer_dtype(&first), infer_dtype(&second));
+        assert_eq!(
+            dtype,
+            DataType::Struct(vec![
+                Field::new("city".into(), DataType::String),
+                Field::new("zip".into(), DataType::Int64),
+            ])
+        );
+
+        let series = values_to_series("address", &[&first, &second]).unwrap();
+        assert_eq!(series.dtype(), &dtype);
+        let city = series.struct_().unwrap().field_by_name("city").unwrap();
+        assert_eq!(city.str().unwrap().get(1), Some("Bergen"));
+        let zip = series.struct_().unwrap().field_by_name("zip").unwrap();
+        assert_eq!(zip.i64().unwrap().get(0), None);
+    }
+
+    #[test]
+    fn test_values_to_series_round_trip() {
+        let values = [
+            object(vec![(
+                "tags",
+                Value::Array(vec![Value::Int(1), Value::Float(2.5)]),
+            )]),
+            Value::Null,
+            object(vec![("tags", Value::Array(vec![]))]),
+        ];
+        let refs: Vec<&Value> = values.iter().collect();
+        let series = values_to_series("row", &refs).unwrap();
+        let round_trip: Vec<Value> = series
+            .iter()
+            .map(|av| super::super::value_from_any_value(av).unwrap())
+            .collect();
+        assert_eq!(
+            round_trip[0],
+            object(vec![(
+                "tags",
+                Value::Array(vec![Value::Float(1.0), Value::Float(2.5)])
+            )])
+        );
+        assert_eq!(round_trip[1], Value::Null);
+        assert_eq!(round_trip[2], object(vec![("tags", Value::Array(vec![]))]));
+    }
+
+    #[test]
+    fn test_conflicting_values_become_json_text() {
+        let values = [
+            Value::Int(1),
+            Value::Array(vec![Value::Int(2)]),
+            Value::string("three"),
+        ];
+        let refs: Vec<&Value> = values.iter().collect();
+        let series = values_to_series("mixed", &refs).unwrap();
+        let strings: Vec<_> = series.str().unwrap().into_iter().collect();
+        assert_eq!(strings, vec![Some("1"), Some("[2]"), Some("three")]);
+    }
+}